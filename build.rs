@@ -22,11 +22,14 @@ fn track_resource_inputs() {
     println!("cargo:rerun-if-changed=data/victory/rank-b.svg");
     println!("cargo:rerun-if-changed=data/victory/rank-c.svg");
     println!("cargo:rerun-if-changed=data/victory/finish-flag.svg");
+    println!("cargo:rerun-if-changed=data/victory/ink");
+    println!("cargo:rerun-if-changed=data/victory/aurora");
     println!("cargo:rerun-if-changed=data/howto/01-flow.svg");
     println!("cargo:rerun-if-changed=data/howto/02-goal.svg");
     println!("cargo:rerun-if-changed=data/howto/03-modes.svg");
     println!("cargo:rerun-if-changed=data/howto/04-difficulty.svg");
     println!("cargo:rerun-if-changed=data/howto/05-restless.svg");
+    println!("cargo:rerun-if-changed=data/faces");
     println!("cargo:rerun-if-changed=data/icons/hicolor/index.theme");
     println!("cargo:rerun-if-changed=data/icons/hicolor/scalable/apps/io.github.basshift.Recall.svg");
     println!(
@@ -11,7 +11,9 @@ ROOT = Path(__file__).resolve().parents[1]
 SRC_DIR = ROOT / "src"
 POT_PATH = ROOT / "po" / "io.github.basshift.Recall.pot"
 
-TR_CALL_RE = re.compile(r"\btr\(\s*\"", re.MULTILINE)
+TR_CALL_RE = re.compile(r"\b(tr|tr_format|ntr|ntr_format|tr_noop)\(\s*\"", re.MULTILINE)
+PLURAL_CALLS = {"ntr", "ntr_format"}
+NEXT_LITERAL_RE = re.compile(r"\s*,\s*\"")
 
 
 def line_number_for_offset(text: str, offset: int) -> int:
@@ -22,32 +24,42 @@ def decode_rust_string_literal(source: str) -> str:
     return ast.literal_eval(source)
 
 
-def extract_messages(path: Path) -> list[tuple[int, str]]:
-    text = path.read_text(encoding="utf-8")
-    messages: list[tuple[int, str]] = []
+def read_literal(path: Path, text: str, start: int) -> tuple[str, int]:
+    escaped = False
+    index = start + 1
+    while index < len(text):
+        char = text[index]
+        if escaped:
+            escaped = False
+        elif char == "\\":
+            escaped = True
+        elif char == '"':
+            literal = text[start : index + 1]
+            try:
+                return decode_rust_string_literal(literal), index + 1
+            except (SyntaxError, ValueError) as exc:
+                rel_path = path.relative_to(ROOT)
+                raise SystemExit(f"failed to decode string literal in {rel_path}:{line_number_for_offset(text, start)}: {exc}")
+        index += 1
+    rel_path = path.relative_to(ROOT)
+    raise SystemExit(f"unterminated tr() string in {rel_path}:{line_number_for_offset(text, start)}")
+
+
+# Each message is (msgid, msgid_plural); the plural is None for plain tr() calls.
+def extract_messages(path: Path) -> list[tuple[int, tuple[str, str | None]]]:
+    # Test modules sit at the end of a file and only use messages as fixtures.
+    text = path.read_text(encoding="utf-8").split("#[cfg(test)]\nmod tests", 1)[0]
+    messages: list[tuple[int, tuple[str, str | None]]] = []
     for match in TR_CALL_RE.finditer(text):
-        start = match.end() - 1
-        escaped = False
-        index = start + 1
-        while index < len(text):
-            char = text[index]
-            if escaped:
-                escaped = False
-            elif char == "\\":
-                escaped = True
-            elif char == '"':
-                literal = text[start : index + 1]
-                try:
-                    message = decode_rust_string_literal(literal)
-                except (SyntaxError, ValueError) as exc:
-                    rel_path = path.relative_to(ROOT)
-                    raise SystemExit(f"failed to decode string literal in {rel_path}:{line_number_for_offset(text, start)}: {exc}")
-                messages.append((line_number_for_offset(text, match.start()), message))
-                break
-            index += 1
-        else:
-            rel_path = path.relative_to(ROOT)
-            raise SystemExit(f"unterminated tr() string in {rel_path}:{line_number_for_offset(text, match.start())}")
+        message, end = read_literal(path, text, match.end() - 1)
+        plural = None
+        if match.group(1) in PLURAL_CALLS:
+            next_literal = NEXT_LITERAL_RE.match(text, end)
+            if next_literal is None:
+                rel_path = path.relative_to(ROOT)
+                raise SystemExit(f"{match.group(1)}() without a plural literal in {rel_path}:{line_number_for_offset(text, match.start())}")
+            plural, _ = read_literal(path, text, next_literal.end() - 1)
+        messages.append((line_number_for_offset(text, match.start()), (message, plural)))
     return messages
 
 
@@ -59,7 +71,7 @@ def escape_po(value: str) -> str:
     )
 
 
-def write_pot(messages: OrderedDict[str, list[str]]) -> None:
+def write_pot(messages: OrderedDict[tuple[str, str | None], list[str]]) -> None:
     header = [
         'msgid ""',
         'msgstr ""',
@@ -77,10 +89,15 @@ def write_pot(messages: OrderedDict[str, list[str]]) -> None:
     ]
 
     lines = header
-    for message, refs in messages.items():
+    for (message, plural), refs in messages.items():
         lines.append(f"#: {' '.join(refs)}")
         lines.append(f'msgid "{escape_po(message)}"')
-        lines.append('msgstr ""')
+        if plural is None:
+            lines.append('msgstr ""')
+        else:
+            lines.append(f'msgid_plural "{escape_po(plural)}"')
+            lines.append('msgstr[0] ""')
+            lines.append('msgstr[1] ""')
         lines.append("")
 
     POT_PATH.parent.mkdir(parents=True, exist_ok=True)
@@ -88,7 +105,7 @@ def write_pot(messages: OrderedDict[str, list[str]]) -> None:
 
 
 def main() -> None:
-    messages: OrderedDict[str, list[str]] = OrderedDict()
+    messages: OrderedDict[tuple[str, str | None], list[str]] = OrderedDict()
     for path in sorted(SRC_DIR.rglob("*.rs")):
         rel_path = path.relative_to(ROOT)
         for line_no, message in extract_messages(path):
@@ -1,7 +1,10 @@
+use std::ffi::CStr;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use gettextrs::{
-    LocaleCategory, bind_textdomain_codeset, bindtextdomain, dgettext, setlocale, textdomain,
+    LocaleCategory, bind_textdomain_codeset, bindtextdomain, dgettext, dngettext, setlocale,
+    textdomain,
 };
 
 pub const GETTEXT_PACKAGE: &str = "io.github.basshift.Recall";
@@ -10,14 +13,15 @@ pub fn init() {
     let locale_dir = std::env::var("RECALL_LOCALEDIR").unwrap_or_else(|_| default_locale_dir());
 
     setlocale(LocaleCategory::LcAll, "");
+    NUMBER_FORMAT.get_or_init(NumberFormat::from_locale);
     if bindtextdomain(GETTEXT_PACKAGE, locale_dir).is_err() {
-        eprintln!("warning: failed to bind gettext domain to locale dir");
+        tracing::warn!("failed to bind gettext domain to locale dir");
     }
     if bind_textdomain_codeset(GETTEXT_PACKAGE, "UTF-8").is_err() {
-        eprintln!("warning: failed to bind gettext domain to UTF-8 codeset");
+        tracing::warn!("failed to bind gettext domain to UTF-8 codeset");
     }
     if textdomain(GETTEXT_PACKAGE).is_err() {
-        eprintln!("warning: failed to activate gettext domain");
+        tracing::warn!("failed to activate gettext domain");
     }
 }
 
@@ -33,9 +37,213 @@ fn default_locale_dir() -> String {
 
 pub fn tr(message: &str) -> String {
     if message.contains('\0') {
-        eprintln!("warning: gettext key contains interior null byte");
+        tracing::warn!("gettext key contains interior null byte");
         return message.to_string();
     }
 
     dgettext(GETTEXT_PACKAGE, message)
 }
+
+// Marks a message for extraction where it can only be translated later, e.g.
+// in a const table; pass the result to `tr` when it is shown.
+pub const fn tr_noop(message: &str) -> &str {
+    message
+}
+
+// Translates a whole sentence with named `{placeholders}`, so translators can
+// reorder them; values are filled in after translation.
+pub fn tr_format(message: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    fill_placeholders(tr(message), args)
+}
+
+// Picks the plural form for `count`; both forms go to translators together.
+pub fn ntr(singular: &str, plural: &str, count: u32) -> String {
+    if singular.contains('\0') || plural.contains('\0') {
+        tracing::warn!("gettext key contains interior null byte");
+        return if count == 1 { singular } else { plural }.to_string();
+    }
+
+    dngettext(GETTEXT_PACKAGE, singular, plural, count)
+}
+
+// `ntr` with `{count}` and any other named placeholders filled in.
+pub fn ntr_format(
+    singular: &str,
+    plural: &str,
+    count: u32,
+    args: &[(&str, &dyn std::fmt::Display)],
+) -> String {
+    let text = fill_placeholders(ntr(singular, plural, count), &[("count", &count)]);
+    fill_placeholders(text, args)
+}
+
+fn fill_placeholders(mut text: String, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
+
+// localeconv() marks "no further grouping" with CHAR_MAX.
+const NO_MORE_GROUPS: u8 = 127;
+
+static NUMBER_FORMAT: OnceLock<NumberFormat> = OnceLock::new();
+
+// Separators from LC_NUMERIC, read once right after the locale is set.
+struct NumberFormat {
+    decimal_point: String,
+    thousands_sep: String,
+    grouping: Vec<u8>,
+}
+
+impl NumberFormat {
+    fn from_locale() -> Self {
+        // SAFETY: read on the main thread right after `setlocale`, before anything
+        // else can change the locale; the strings are copied out straight away.
+        unsafe {
+            let conv = &*libc::localeconv();
+            let text = |ptr: *const libc::c_char| {
+                if ptr.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+                }
+            };
+            let grouping = if conv.grouping.is_null() {
+                Vec::new()
+            } else {
+                CStr::from_ptr(conv.grouping).to_bytes().to_vec()
+            };
+            Self {
+                decimal_point: Some(text(conv.decimal_point))
+                    .filter(|point| !point.is_empty())
+                    .unwrap_or_else(|| ".".to_string()),
+                thousands_sep: text(conv.thousands_sep),
+                grouping,
+            }
+        }
+    }
+
+    // Group sizes run from the right; the last one repeats until the locale says stop.
+    fn group_digits(&self, digits: &str) -> String {
+        if self.thousands_sep.is_empty() {
+            return digits.to_string();
+        }
+        let mut sizes = self.grouping.iter().copied();
+        let mut size = 0;
+        let mut groups = Vec::new();
+        let mut rest = digits;
+        loop {
+            match sizes.next() {
+                Some(0) | None => {}
+                Some(next) => size = next,
+            }
+            if size == 0 || size >= NO_MORE_GROUPS || rest.len() <= usize::from(size) {
+                break;
+            }
+            let (head, tail) = rest.split_at(rest.len() - usize::from(size));
+            groups.push(tail);
+            rest = head;
+        }
+        groups.push(rest);
+        groups.reverse();
+        groups.join(&self.thousands_sep)
+    }
+
+    fn decimal(&self, value: f64) -> String {
+        let tenths = (value * 10.0).round() as i64;
+        let sign = if tenths < 0 { "-" } else { "" };
+        let tenths = tenths.unsigned_abs();
+        let whole = self.group_digits(&(tenths / 10).to_string());
+        format!("{sign}{whole}{}{}", self.decimal_point, tenths % 10)
+    }
+}
+
+fn number_format() -> &'static NumberFormat {
+    NUMBER_FORMAT.get_or_init(NumberFormat::from_locale)
+}
+
+// Where the sign goes, and whether a space comes before it, is up to translators.
+pub fn format_percent(value: impl std::fmt::Display) -> String {
+    tr_format("{value}%", &[("value", &value)])
+}
+
+// One decimal place, with the locale's decimal point and digit grouping.
+pub fn format_decimal(value: f64) -> String {
+    number_format().decimal(value)
+}
+
+pub fn format_fraction(whole: impl std::fmt::Display, fraction: &str) -> String {
+    format!("{whole}{}{fraction}", number_format().decimal_point)
+}
+
+pub fn format_integer(value: u64) -> String {
+    number_format().group_digits(&value.to_string())
+}
+
+// Runs past an hour gain an hours field, which keeps counting past a day. The
+// separators are translatable, since not every locale writes times with colons.
+pub fn format_duration(total_secs: u32) -> String {
+    let (hours, mins, secs) = (total_secs / 3600, total_secs / 60 % 60, total_secs % 60);
+    let (minutes, seconds) = (format!("{mins:02}"), format!("{secs:02}"));
+    if hours > 0 {
+        tr_format(
+            "{hours}:{minutes}:{seconds}",
+            &[("hours", &hours), ("minutes", &minutes), ("seconds", &seconds)],
+        )
+    } else {
+        tr_format("{minutes}:{seconds}", &[("minutes", &minutes), ("seconds", &seconds)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_and_percentages_are_formatted() {
+        assert_eq!(format_duration(0), "00:00");
+        assert_eq!(format_duration(155), "02:35");
+        assert_eq!(format_duration(3725), "1:02:05");
+        assert_eq!(format_duration(90_061), "25:01:01");
+        assert_eq!(format_percent(92), "92%");
+    }
+
+    #[test]
+    fn numbers_follow_the_locale_separators() {
+        let german = NumberFormat {
+            decimal_point: ",".to_string(),
+            thousands_sep: ".".to_string(),
+            grouping: vec![3, 3],
+        };
+        assert_eq!(german.decimal(2.44), "2,4");
+        assert_eq!(german.decimal(-0.36), "-0,4");
+        assert_eq!(german.decimal(12_345.0), "12.345,0");
+        assert_eq!(german.group_digits("1234005"), "1.234.005");
+        assert_eq!(german.group_digits("999"), "999");
+
+        let indian = NumberFormat {
+            thousands_sep: ",".to_string(),
+            grouping: vec![3, 2],
+            ..german
+        };
+        assert_eq!(indian.group_digits("12345678"), "1,23,45,678");
+
+        let plain = NumberFormat {
+            decimal_point: ".".to_string(),
+            thousands_sep: String::new(),
+            grouping: Vec::new(),
+        };
+        assert_eq!(plain.group_digits("1234005"), "1234005");
+        assert_eq!(plain.decimal(2.44), "2.4");
+    }
+
+    #[test]
+    fn plural_messages_fill_the_count() {
+        assert_eq!(ntr_format("{count} pair", "{count} pairs", 1, &[]), "1 pair");
+        assert_eq!(
+            ntr_format("{count} pair on {board}", "{count} pairs on {board}", 3, &[("board", &"Hard")]),
+            "3 pairs on Hard"
+        );
+    }
+}
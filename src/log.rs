@@ -0,0 +1,53 @@
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+pub const LOG_ENV: &str = "RECALL_LOG";
+
+pub fn level_from_code(code: &str) -> Option<LevelFilter> {
+    match code.trim().to_ascii_lowercase().as_str() {
+        "off" | "none" => Some(LevelFilter::OFF),
+        "warn" | "warning" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" | "all" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+// `--verbose` turns everything on; otherwise RECALL_LOG picks the level.
+// Closing a game span logs how long the game lasted.
+pub fn init(verbose: bool) {
+    let level = if verbose {
+        LevelFilter::TRACE
+    } else {
+        match std::env::var(LOG_ENV) {
+            Ok(code) => level_from_code(&code).unwrap_or_else(|| {
+                eprintln!("warning: unknown {LOG_ENV} level {code:?}");
+                LevelFilter::WARN
+            }),
+            Err(_) => LevelFilter::WARN,
+        }
+    };
+    let installed = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_target(false)
+        .without_time()
+        .with_writer(std::io::stderr)
+        .try_init();
+    if let Err(err) = installed {
+        eprintln!("warning: logging was already set up: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_codes_are_case_insensitive() {
+        assert_eq!(level_from_code(" Debug "), Some(LevelFilter::DEBUG));
+        assert_eq!(level_from_code("warning"), Some(LevelFilter::WARN));
+        assert_eq!(level_from_code("loud"), None);
+    }
+}
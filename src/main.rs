@@ -1,3 +1,4 @@
+mod log;
 mod i18n;
 mod ui;
 
@@ -10,11 +10,17 @@ use libadwaita as adw;
 use adw::prelude::*;
 use gio::SimpleAction;
 
-use crate::i18n::tr;
-
-use super::board::{build_board_grid, CONTENT_MARGIN};
+use crate::i18n::{format_duration, ntr_format, tr, tr_format};
+
+use super::audio::{self, AudioCues};
+use super::breaks::{self, BreakReminder};
+use super::board_canvas::{BoardCanvas, CardFlag, FLIP_PHASE_MS, TURN_DOWN_MS};
+use super::board::{self, build_board_canvas, CONTENT_MARGIN};
+use super::bracket::Bracket;
+use super::challenge::Challenge;
 use super::dialogs::{create_keyboard_shortcuts_overlay, show_about_dialog, show_instructions_dialog};
 use super::hud::{
+    self,
     set_header_game,
     set_header_menu,
     start_preview_phase,
@@ -25,20 +31,171 @@ use super::hud::{
 };
 use super::infinite;
 use super::classic_penalties;
-use super::mode_dialogs::show_mode_dialog;
+use super::coordinates;
+use super::custom::CustomPreset;
+use super::mode_dialogs::{self, show_mode_dialog};
 use super::records::{
     load_records,
+    merge_records_from_file,
     register_infinite_run_result,
     register_non_infinite_result,
     reset_local_records,
+    restore_trashed_records,
     show_memory_dialog,
 };
-use super::scene::{rebuild_board, show_menu, show_victory};
+use super::scene::{
+    rebuild_board, show_defeat, show_gauntlet_splash, show_menu, show_training_rest, show_victory,
+    show_warmup_splash,
+};
+use super::sequence::{self, Sequence, SequenceHandle};
+use super::paths;
+use super::play_limit::{self, DailyLimit};
+use super::penalty_sim;
+use super::particles;
+use super::perf_hud;
+use super::rank_art::{self, ArtPack};
+use super::resources;
+use super::session_recap;
 use super::session_save;
-use super::state::{AppState, Difficulty, Rank, TileStatus};
+use super::settings;
+use super::shortcuts;
+use super::similarity;
+use super::theme::{self, ThemeChoice};
+use super::user_css;
+use super::stats_panel;
+use super::symbol_stats;
+use super::tags;
+use super::hint;
+use super::heatmap;
+use super::leaderboard;
+use super::overlay;
+use super::habits;
+use super::events::{self, GameEvent};
+use super::toasts;
+use super::spectator;
+use super::speech;
+use super::music;
+use super::speedrun;
+use super::state::{AppState, Difficulty, Rank, RunClock, Series, TileStatus};
+use super::game_title::{GameTitle, StatusSource};
+use super::gauntlet::GauntletRun;
+use super::transitions::{self, WavePattern};
+use super::layout::{self, ReshuffleStrategy};
+use super::tournament::TournamentRun;
+use super::hotseat::{Handicap, HotseatRun};
+use super::training::{self, TrainingSession};
 use super::trio_penalties;
 use super::debug_tools;
+use super::defeat::{self, FailCondition};
+use super::faces::{self, CardFaces};
+use super::fonts::{self, FontChoice, TextScale};
 use super::infinite_flow;
+use super::kids::{self, KidsBoard};
+use super::nback::NBackRun;
+use super::nback_flow;
+use super::pattern::PatternRun;
+use super::pattern_flow;
+use super::peek;
+use super::warmup;
+
+fn apply_font_preferences(st: &AppState) {
+    fonts::apply_dynamic_css(st);
+    if let Some(board) = &st.board_canvas {
+        board.queue_draw();
+    }
+}
+
+fn build_sound_group(state: &Rc<RefCell<AppState>>) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&tr("Sound"));
+    let cues = state.borrow().audio_cues;
+
+    let cue_row = |title: &str, subtitle: &str, key: &'static str, apply: fn(&mut AudioCues, bool)| {
+        let row = adw::SwitchRow::builder()
+            .title(title)
+            .subtitle(subtitle)
+            .sensitive(!cues.audio_only)
+            .build();
+        settings::bind_boolean(key, false, &row, "active");
+        let state = state.clone();
+        row.connect_active_notify(move |row| {
+            apply(&mut state.borrow_mut().audio_cues, row.is_active());
+        });
+        group.add(&row);
+        row
+    };
+    let countdown_row = cue_row(
+        &tr("Memorize countdown"),
+        &tr("Tick through the last three seconds of memorizing, then a final beep"),
+        hud::PREVIEW_TICK_KEY,
+        |cues, on| cues.countdown = on,
+    );
+    let match_row = cue_row(
+        &tr("Match sound"),
+        &tr("A rising chime when cards match"),
+        audio::CUE_MATCH_KEY,
+        |cues, on| cues.matches = on,
+    );
+    let mismatch_row = cue_row(
+        &tr("Mismatch sound"),
+        &tr("A low tone when cards do not match"),
+        audio::CUE_MISMATCH_KEY,
+        |cues, on| cues.mismatches = on,
+    );
+
+    let audio_only_row = adw::SwitchRow::builder()
+        .title(tr("Audio feedback only"))
+        .subtitle(tr("Every cue on, plus a click for each flipped card, for playing by ear"))
+        .build();
+    settings::bind_boolean(audio::AUDIO_ONLY_KEY, false, &audio_only_row, "active");
+    audio_only_row.connect_active_notify({
+        let state = state.clone();
+        move |row| {
+            state.borrow_mut().audio_cues.audio_only = row.is_active();
+            for cue_row in [&countdown_row, &match_row, &mismatch_row] {
+                cue_row.set_sensitive(!row.is_active());
+            }
+        }
+    });
+    group.add(&audio_only_row);
+
+    let speech_row = adw::SwitchRow::builder()
+        .title(tr("Spoken announcements"))
+        .subtitle(tr("Read matches, pairs left and level ups aloud through your screen reader"))
+        .build();
+    settings::bind_boolean(speech::SPOKEN_ANNOUNCEMENTS_KEY, false, &speech_row, "active");
+    speech_row.connect_active_notify({
+        let state = state.clone();
+        move |row| {
+            state.borrow_mut().spoken_announcements = row.is_active();
+        }
+    });
+    group.add(&speech_row);
+
+    let music_row = adw::SwitchRow::builder()
+        .title(tr("Dynamic music"))
+        .subtitle(tr("A background loop that speeds up and gains layers with your match streak"))
+        .build();
+    settings::bind_boolean(music::DYNAMIC_MUSIC_KEY, false, &music_row, "active");
+    music_row.connect_active_notify({
+        let state = state.clone();
+        move |row| music::set_enabled(&state, row.is_active())
+    });
+    group.add(&music_row);
+    group
+}
+
+fn limit_position(limit: DailyLimit) -> u32 {
+    DailyLimit::ALL
+        .iter()
+        .position(|candidate| *candidate == limit)
+        .unwrap_or(0) as u32
+}
+
+fn apply_kids_limit(state: &Rc<RefCell<AppState>>, limit: DailyLimit) {
+    settings::set_string(play_limit::KIDS_DAILY_LIMIT_KEY, &limit.code());
+    state.borrow_mut().kids_daily_limit = limit;
+}
 
 fn show_preferences_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) -> adw::PreferencesDialog {
     let dialog = adw::PreferencesDialog::new();
@@ -58,24 +215,23 @@ fn show_preferences_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application
         .title(tr("Theme"))
         .subtitle(tr("Select app color scheme"))
         .build();
-    let theme_values = [tr("System"), tr("Light"), tr("Dark")];
+    let theme_values: Vec<String> = ThemeChoice::ALL.iter().map(|choice| tr(choice.label())).collect();
     let theme_refs: Vec<&str> = theme_values.iter().map(|s| s.as_str()).collect();
     let theme_model = gtk::StringList::new(&theme_refs);
     theme_row.set_model(Some(&theme_model));
-    let style_manager = adw::StyleManager::default();
-    let initial_theme_index = match style_manager.color_scheme() {
-        adw::ColorScheme::ForceLight | adw::ColorScheme::PreferLight => 1,
-        adw::ColorScheme::ForceDark | adw::ColorScheme::PreferDark => 2,
-        _ => 0,
-    };
-    theme_row.set_selected(initial_theme_index);
+    let current_theme = ThemeChoice::from_code(&settings::string(theme::COLOR_SCHEME_KEY, "system"));
+    let initial_theme_index = ThemeChoice::ALL
+        .iter()
+        .position(|choice| *choice == current_theme)
+        .unwrap_or(0);
+    theme_row.set_selected(initial_theme_index as u32);
     theme_row.connect_selected_notify(move |row| {
-        let scheme = match row.selected() {
-            1 => adw::ColorScheme::ForceLight,
-            2 => adw::ColorScheme::ForceDark,
-            _ => adw::ColorScheme::Default,
-        };
-        adw::StyleManager::default().set_color_scheme(scheme);
+        let choice = ThemeChoice::ALL
+            .get(row.selected() as usize)
+            .copied()
+            .unwrap_or_default();
+        settings::set_string(theme::COLOR_SCHEME_KEY, choice.code());
+        choice.apply();
     });
     appearance_group.add(&theme_row);
 
@@ -93,8 +249,505 @@ fn show_preferences_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application
     });
     appearance_group.add(&motion_row);
 
+    let coordinates_row = adw::SwitchRow::builder()
+        .title(tr("Board coordinates"))
+        .subtitle(tr("Label rows and columns to flip cards by typing, e.g. B3"))
+        .build();
+    settings::bind_boolean(coordinates::SHOW_COORDINATES_KEY, false, &coordinates_row, "active");
+    {
+        let state = state.clone();
+        coordinates_row.connect_active_notify(move |row| {
+            let rebuild = {
+                let mut st = state.borrow_mut();
+                st.show_coordinates = row.is_active();
+                is_game_view_active(&st) && !st.lock_input
+            };
+            if rebuild {
+                rebuild_board(&state);
+            }
+        });
+    }
+    appearance_group.add(&coordinates_row);
+
+    let left_handed_row = adw::SwitchRow::builder()
+        .title(tr("Left-handed layout"))
+        .subtitle(tr("Mirror the header controls and result buttons"))
+        .build();
+    settings::bind_boolean(hud::LEFT_HANDED_KEY, false, &left_handed_row, "active");
+    {
+        let state = state.clone();
+        left_handed_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.left_handed = row.is_active();
+            hud::apply_handedness(&st);
+        });
+    }
+    appearance_group.add(&left_handed_row);
+
+    let font_row = adw::ComboRow::builder()
+        .title(tr("Font"))
+        .subtitle(tr("Used for cards and the game interface"))
+        .build();
+    let font_choices = fonts::available_choices();
+    let font_values: Vec<String> = font_choices
+        .iter()
+        .map(|choice| tr(choice.label()))
+        .collect();
+    let font_refs: Vec<&str> = font_values.iter().map(|s| s.as_str()).collect();
+    font_row.set_model(Some(&gtk::StringList::new(&font_refs)));
+    let current_font = state.borrow().font_choice;
+    font_row.set_selected(
+        font_choices
+            .iter()
+            .position(|choice| *choice == current_font)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        font_row.connect_selected_notify(move |row| {
+            let choice = font_choices
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(fonts::FONT_FAMILY_KEY, choice.code());
+            let mut st = state.borrow_mut();
+            st.font_choice = choice;
+            apply_font_preferences(&st);
+        });
+    }
+    appearance_group.add(&font_row);
+
+    let scale_row = adw::ComboRow::builder()
+        .title(tr("Text size"))
+        .subtitle(tr("Scale card labels and interface text"))
+        .build();
+    let scale_values: Vec<String> = TextScale::ALL.iter().map(|scale| scale.label()).collect();
+    let scale_refs: Vec<&str> = scale_values.iter().map(|s| s.as_str()).collect();
+    scale_row.set_model(Some(&gtk::StringList::new(&scale_refs)));
+    let current_scale = state.borrow().text_scale;
+    scale_row.set_selected(
+        TextScale::ALL
+            .iter()
+            .position(|scale| *scale == current_scale)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        scale_row.connect_selected_notify(move |row| {
+            let scale = TextScale::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(fonts::TEXT_SCALE_KEY, scale.code());
+            let mut st = state.borrow_mut();
+            st.text_scale = scale;
+            apply_font_preferences(&st);
+        });
+    }
+    appearance_group.add(&scale_row);
+
+    let faces_row = adw::ComboRow::builder()
+        .title(tr("Card faces"))
+        .subtitle(tr_format(
+            "Bundled artwork looks the same without emoji fonts on boards of up to {count} symbols, applies to the next board",
+            &[("count", &faces::FACE_ART.len())],
+        ))
+        .build();
+    let faces_values: Vec<String> = CardFaces::ALL
+        .iter()
+        .map(|faces| tr(faces.label()))
+        .collect();
+    let faces_refs: Vec<&str> = faces_values.iter().map(|s| s.as_str()).collect();
+    faces_row.set_model(Some(&gtk::StringList::new(&faces_refs)));
+    let current_faces = state.borrow().card_faces;
+    faces_row.set_selected(
+        CardFaces::ALL
+            .iter()
+            .position(|faces| *faces == current_faces)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        faces_row.connect_selected_notify(move |row| {
+            let card_faces = CardFaces::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(faces::CARD_FACES_KEY, card_faces.code());
+            state.borrow_mut().card_faces = card_faces;
+        });
+    }
+    appearance_group.add(&faces_row);
+
+    let transition_row = adw::ComboRow::builder()
+        .title(tr("Board transitions"))
+        .subtitle(tr("How cards are dealt in and cleared after a win"))
+        .build();
+    let transition_values: Vec<String> = WavePattern::ALL
+        .iter()
+        .map(|pattern| tr(pattern.label()))
+        .collect();
+    let transition_refs: Vec<&str> = transition_values.iter().map(|s| s.as_str()).collect();
+    transition_row.set_model(Some(&gtk::StringList::new(&transition_refs)));
+    let current_transition = state.borrow().board_transition;
+    transition_row.set_selected(
+        WavePattern::ALL
+            .iter()
+            .position(|pattern| *pattern == current_transition)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        transition_row.connect_selected_notify(move |row| {
+            let pattern = WavePattern::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(transitions::BOARD_TRANSITION_KEY, pattern.code());
+            state.borrow_mut().board_transition = pattern;
+        });
+    }
+    appearance_group.add(&transition_row);
+
+    // Only unlocked sets are offered; the subtitle points at the next one to earn.
+    let (unlocked_art, locked_hint) = {
+        let st = state.borrow();
+        let locked_hint = ArtPack::ALL
+            .into_iter()
+            .find(|pack| !pack.is_unlocked(&st.records))
+            .and_then(ArtPack::requirement)
+            .map(|requirement| format!("{}: {}", tr("Next set"), tr(requirement)));
+        (rank_art::unlocked_packs(&st.records), locked_hint)
+    };
+    let rank_art_row = adw::ComboRow::builder()
+        .title(tr("Victory art"))
+        .subtitle(locked_hint.unwrap_or_else(|| tr("Every set unlocked")))
+        .build();
+    let rank_art_values: Vec<String> = unlocked_art.iter().map(|pack| tr(pack.label())).collect();
+    let rank_art_refs: Vec<&str> = rank_art_values.iter().map(|s| s.as_str()).collect();
+    rank_art_row.set_model(Some(&gtk::StringList::new(&rank_art_refs)));
+    let current_art = state.borrow().rank_art;
+    rank_art_row.set_selected(
+        unlocked_art
+            .iter()
+            .position(|pack| *pack == current_art)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        rank_art_row.connect_selected_notify(move |row| {
+            let pack = unlocked_art
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(rank_art::RANK_ART_KEY, pack.code());
+            state.borrow_mut().rank_art = pack;
+        });
+    }
+    appearance_group.add(&rank_art_row);
+
     page.add(&appearance_group);
 
+    let gameplay_group = adw::PreferencesGroup::new();
+    gameplay_group.set_title(&tr("Gameplay"));
+    let fail_row = adw::ComboRow::builder()
+        .title(tr("Fail condition"))
+        .subtitle(tr("End single-board runs early with a defeat"))
+        .build();
+    let fail_values: Vec<String> = FailCondition::ALL
+        .iter()
+        .map(|condition| tr(condition.label()))
+        .collect();
+    let fail_refs: Vec<&str> = fail_values.iter().map(|s| s.as_str()).collect();
+    fail_row.set_model(Some(&gtk::StringList::new(&fail_refs)));
+    let current_fail_condition = state.borrow().fail_condition;
+    fail_row.set_selected(
+        FailCondition::ALL
+            .iter()
+            .position(|condition| *condition == current_fail_condition)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        fail_row.connect_selected_notify(move |row| {
+            let condition = FailCondition::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(defeat::FAIL_CONDITION_KEY, condition.code());
+            let mut st = state.borrow_mut();
+            st.fail_condition = condition;
+            update_subtitle(&st);
+        });
+    }
+    gameplay_group.add(&fail_row);
+
+    let pacing_row = adw::ComboRow::builder()
+        .title(tr("Infinite pacing"))
+        .subtitle(tr("How fast Infinite climbs to Expert, from the next run"))
+        .build();
+    let pacing_values: Vec<String> = infinite::Pacing::ALL
+        .iter()
+        .map(|pacing| tr(pacing.label()))
+        .collect();
+    let pacing_refs: Vec<&str> = pacing_values.iter().map(|s| s.as_str()).collect();
+    pacing_row.set_model(Some(&gtk::StringList::new(&pacing_refs)));
+    let current_pacing = state.borrow().preferred_pacing;
+    pacing_row.set_selected(
+        infinite::Pacing::ALL
+            .iter()
+            .position(|pacing| *pacing == current_pacing)
+            .unwrap_or(1) as u32,
+    );
+    {
+        let state = state.clone();
+        pacing_row.connect_selected_notify(move |row| {
+            let pacing = infinite::Pacing::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(infinite::PACING_KEY, pacing.code());
+            state.borrow_mut().preferred_pacing = pacing;
+        });
+    }
+    gameplay_group.add(&pacing_row);
+
+    let break_row = adw::ComboRow::builder()
+        .title(tr("Break reminders"))
+        .subtitle(tr("Offer a pause between Infinite rounds on long runs"))
+        .build();
+    let break_values: Vec<String> = BreakReminder::ALL
+        .iter()
+        .map(|reminder| reminder.label())
+        .collect();
+    let break_refs: Vec<&str> = break_values.iter().map(|s| s.as_str()).collect();
+    break_row.set_model(Some(&gtk::StringList::new(&break_refs)));
+    let current_reminder = state.borrow().break_reminder;
+    break_row.set_selected(
+        BreakReminder::ALL
+            .iter()
+            .position(|reminder| *reminder == current_reminder)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        break_row.connect_selected_notify(move |row| {
+            let reminder = BreakReminder::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(breaks::BREAK_REMINDER_KEY, &reminder.code());
+            state.borrow_mut().break_reminder = reminder;
+        });
+    }
+    gameplay_group.add(&break_row);
+
+    let kids_limit_row = adw::ComboRow::builder()
+        .title(tr("Kids daily play time"))
+        .subtitle(tr("Warn before the time runs out, then close Kids mode until tomorrow"))
+        .build();
+    let limit_values: Vec<String> = DailyLimit::ALL.iter().map(|limit| limit.label()).collect();
+    let limit_refs: Vec<&str> = limit_values.iter().map(|s| s.as_str()).collect();
+    kids_limit_row.set_model(Some(&gtk::StringList::new(&limit_refs)));
+    let current_limit = state.borrow().kids_daily_limit;
+    kids_limit_row.set_selected(limit_position(current_limit));
+    {
+        let state = state.clone();
+        kids_limit_row.connect_selected_notify(move |row| {
+            let limit = DailyLimit::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            let current = state.borrow().kids_daily_limit;
+            if limit == current {
+                return;
+            }
+            if !limit.is_looser_than(current) {
+                apply_kids_limit(&state, limit);
+                return;
+            }
+            // Snap back until a grown-up confirms; the row follows once they do.
+            row.set_selected(limit_position(current));
+            let state = state.clone();
+            let row_for_answer = row.clone();
+            play_limit::ask_grown_up(row, move || {
+                apply_kids_limit(&state, limit);
+                row_for_answer.set_selected(limit_position(limit));
+            });
+        });
+    }
+    gameplay_group.add(&kids_limit_row);
+
+    let cruel_row = adw::SwitchRow::builder()
+        .title(tr("Cruel symbols"))
+        .subtitle(tr("Deal lookalike symbols together instead of keeping them apart"))
+        .build();
+    settings::bind_boolean(similarity::CRUEL_SYMBOLS_KEY, false, &cruel_row, "active");
+    {
+        let state = state.clone();
+        cruel_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.cruel_symbols = row.is_active();
+            update_subtitle(&st);
+        });
+    }
+    gameplay_group.add(&cruel_row);
+
+    let scaled_row = adw::SwitchRow::builder()
+        .title(tr("Lookalikes on hard boards"))
+        .subtitle(tr("Expert and late Infinite rounds mix in similar symbols"))
+        .build();
+    settings::bind_boolean(similarity::SCALED_SIMILARITY_KEY, true, &scaled_row, "active");
+    {
+        let state = state.clone();
+        scaled_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.scaled_similarity = row.is_active();
+            update_subtitle(&st);
+        });
+    }
+    gameplay_group.add(&scaled_row);
+
+    let progress_row = adw::SwitchRow::builder()
+        .title(tr("Matches progress"))
+        .subtitle(tr("Show how many matches are found above the board"))
+        .build();
+    settings::bind_boolean(hud::MATCH_PROGRESS_KEY, true, &progress_row, "active");
+    {
+        let state = state.clone();
+        progress_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.show_match_progress = row.is_active();
+            hud::refresh_match_progress(&st);
+        });
+    }
+    gameplay_group.add(&progress_row);
+
+    let title_progress_row = adw::SwitchRow::builder()
+        .title(tr("Progress in window title"))
+        .subtitle(tr("Show the mode and matches found in the taskbar and window switcher"))
+        .build();
+    settings::bind_boolean(hud::PROGRESS_IN_TITLE_KEY, false, &title_progress_row, "active");
+    {
+        let state = state.clone();
+        title_progress_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.progress_in_title = row.is_active();
+            hud::refresh_window_title(&st);
+        });
+    }
+    gameplay_group.add(&title_progress_row);
+
+    let grace_row = adw::SwitchRow::builder()
+        .title(tr("First mismatch grace"))
+        .subtitle(tr("A game's first mismatch never counts towards a reshuffle"))
+        .build();
+    settings::bind_boolean(classic_penalties::FIRST_MISMATCH_GRACE_KEY, false, &grace_row, "active");
+    {
+        let state = state.clone();
+        grace_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.first_mismatch_grace = row.is_active();
+            if st.active_session_started {
+                st.note_run_modifiers();
+            }
+        });
+    }
+    gameplay_group.add(&grace_row);
+
+    let reshuffle_row = adw::ComboRow::builder()
+        .title(tr("Reshuffles"))
+        .subtitle(tr("How penalties rearrange the face-down cards. Only Random runs are ranked"))
+        .build();
+    let reshuffle_values: Vec<String> = ReshuffleStrategy::ALL
+        .iter()
+        .map(|strategy| tr(strategy.label()))
+        .collect();
+    let reshuffle_refs: Vec<&str> = reshuffle_values.iter().map(|s| s.as_str()).collect();
+    reshuffle_row.set_model(Some(&gtk::StringList::new(&reshuffle_refs)));
+    let current_reshuffle = state.borrow().reshuffle_strategy;
+    reshuffle_row.set_selected(
+        ReshuffleStrategy::ALL
+            .iter()
+            .position(|strategy| *strategy == current_reshuffle)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let state = state.clone();
+        reshuffle_row.connect_selected_notify(move |row| {
+            let strategy = ReshuffleStrategy::ALL
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or_default();
+            settings::set_string(layout::RESHUFFLE_STRATEGY_KEY, strategy.code());
+            let mut st = state.borrow_mut();
+            st.reshuffle_strategy = strategy;
+            if st.active_session_started {
+                st.note_run_modifiers();
+            }
+        });
+    }
+    gameplay_group.add(&reshuffle_row);
+
+    let peek_row = adw::SwitchRow::builder()
+        .title(tr("Hold to peek"))
+        .subtitle(tr("Hold Shift to see cards you have already turned over. Runs are not ranked"))
+        .build();
+    settings::bind_boolean(peek::PEEK_ASSIST_KEY, false, &peek_row, "active");
+    {
+        let state = state.clone();
+        peek_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.peek_assist = row.is_active();
+            if st.active_session_started {
+                st.note_run_modifiers();
+            }
+        });
+    }
+    gameplay_group.add(&peek_row);
+
+    let tags_row = adw::SwitchRow::builder()
+        .title(tr("Color tags"))
+        .subtitle(tr("Long-press a face-down card or use its shortcut to cycle color tags. Runs are not ranked"))
+        .build();
+    settings::bind_boolean(tags::TILE_TAGS_KEY, false, &tags_row, "active");
+    {
+        let state = state.clone();
+        tags_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.tile_tags = row.is_active();
+            if st.active_session_started {
+                st.note_run_modifiers();
+            }
+        });
+    }
+    gameplay_group.add(&tags_row);
+
+    let hint_row = adw::SwitchRow::builder()
+        .title(tr("Hints"))
+        .subtitle(ntr_format(
+            "Use the hint shortcut up to {count} time per board to point at a pair you have already seen. Runs are not ranked",
+            "Use the hint shortcut up to {count} times per board to point at a pair you have already seen. Runs are not ranked",
+            hint::HINT_CHARGES,
+            &[],
+        ))
+        .build();
+    settings::bind_boolean(hint::HINT_ASSIST_KEY, false, &hint_row, "active");
+    {
+        let state = state.clone();
+        hint_row.connect_active_notify(move |row| {
+            let mut st = state.borrow_mut();
+            st.hint_assist = row.is_active();
+            if st.active_session_started {
+                st.note_run_modifiers();
+            }
+            stats_panel::refresh_live_stats(&st);
+        });
+    }
+    gameplay_group.add(&hint_row);
+    page.add(&gameplay_group);
+    page.add(&build_sound_group(state));
+
     let data_group = adw::PreferencesGroup::new();
     data_group.set_title(&tr("Data"));
     let reset_row = adw::ActionRow::builder()
@@ -115,7 +768,7 @@ fn show_preferences_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application
         reset_button.connect_clicked(move |_| {
             let confirm = adw::AlertDialog::builder()
                 .heading(tr("Reset local records"))
-                .body(tr("This will permanently remove all saved scores on this device"))
+                .body(tr("Saved scores on this device move to the trash for 30 days"))
                 .build();
             confirm.add_response("cancel", &tr("Cancel"));
             confirm.add_response("reset", &tr("Reset"));
@@ -126,52 +779,327 @@ fn show_preferences_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application
             let state_after = state.clone();
             confirm.connect_response(None, move |_, response| {
                 if response == "reset" {
-                    reset_local_records(&state_after);
-                    let done = adw::AlertDialog::builder()
-                        .heading(tr("Records reset"))
-                        .body(tr("Local scores were cleared successfully"))
-                        .build();
-                    done.add_response("ok", &tr("OK"));
-                    done.present(Some(&dialog_after));
+                    let trashed_at = match reset_local_records(&state_after) {
+                        Ok(trashed_at) => trashed_at,
+                        Err(err) => {
+                            tracing::warn!("failed to move records to the trash: {err}");
+                            dialog_after.add_toast(adw::Toast::new(&tr(
+                                "Records could not be moved to the trash and were kept",
+                            )));
+                            return;
+                        }
+                    };
+                    let toast = adw::Toast::new(&if trashed_at.is_some() {
+                        tr("Records moved to the trash")
+                    } else {
+                        tr("Records reset")
+                    });
+                    if let Some(trashed_at) = trashed_at {
+                        toast.set_button_label(Some(&tr("Undo")));
+                        let state_undo = state_after.clone();
+                        toast.connect_button_clicked(move |_| {
+                            restore_trashed_records(&state_undo, trashed_at);
+                        });
+                    }
+                    dialog_after.add_toast(toast);
                 }
             });
             confirm.present(Some(&dialog));
         });
     }
     data_group.add(&reset_row);
+    let merge_row = adw::ActionRow::builder()
+        .title(tr("Merge records from file…"))
+        .subtitle(tr("Add scores saved on another device, keeping the ones here"))
+        .activatable(true)
+        .build();
+    merge_row.add_suffix(&gtk::Image::from_icon_name("document-open-symbolic"));
+    {
+        let dialog = dialog.clone();
+        let state = state.clone();
+        merge_row.connect_activated(move |_| {
+            let parent = dialog.root().and_downcast::<gtk::Window>();
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some(&tr("Recall records")));
+            filter.add_pattern("*.json");
+            filter.add_pattern("*.txt");
+            let filters = gio::ListStore::new::<gtk::FileFilter>();
+            filters.append(&filter);
+            let chooser = gtk::FileDialog::builder()
+                .title(tr("Merge Records"))
+                .accept_label(tr("Merge"))
+                .filters(&filters)
+                .modal(true)
+                .build();
+            let dialog = dialog.clone();
+            let state = state.clone();
+            chooser.open(parent.as_ref(), gio::Cancellable::NONE, move |result| {
+                let Some(path) = result.ok().and_then(|file| file.path()) else {
+                    return;
+                };
+                let message = match merge_records_from_file(&state, &path) {
+                    Ok(0) => tr("No new records in this file"),
+                    Ok(added) => ntr_format(
+                        "{count} new record added",
+                        "{count} new records added",
+                        u32::try_from(added).unwrap_or(u32::MAX),
+                        &[],
+                    ),
+                    Err(err) => {
+                        tracing::warn!("failed to merge records from {}: {err}", path.display());
+                        tr("This file could not be merged")
+                    }
+                };
+                dialog.add_toast(adw::Toast::new(&message));
+            });
+        });
+    }
+    data_group.add(&merge_row);
+    let habits_row = adw::SwitchRow::builder()
+        .title(tr("Track play habits"))
+        .subtitle(tr("Sessions, favourite modes and quit points, kept on this device"))
+        .build();
+    settings::bind_boolean(habits::COLLECT_HABITS_KEY, false, &habits_row, "active");
+    habits_row.connect_active_notify({
+        let state = state.clone();
+        move |row| {
+            state.borrow_mut().collect_habits = row.is_active();
+            if !row.is_active() {
+                habits::clear_habits();
+            }
+        }
+    });
+    data_group.add(&habits_row);
+    let overlay_row = adw::SwitchRow::builder()
+        .title(tr("Streaming overlay"))
+        .subtitle(format!(
+            "{} {}",
+            tr("Keep mode, time, matches and streak in files under"),
+            overlay::overlay_dir().display()
+        ))
+        .build();
+    settings::bind_boolean(overlay::STREAM_OVERLAY_KEY, false, &overlay_row, "active");
+    overlay_row.connect_active_notify({
+        let state = state.clone();
+        move |row| {
+            let mut st = state.borrow_mut();
+            st.stream_overlay = row.is_active();
+            overlay::refresh(&st);
+        }
+    });
+    data_group.add(&overlay_row);
+
+    let online_group = adw::PreferencesGroup::new();
+    online_group.set_title(&tr("Online"));
+    online_group.set_description(Some(&tr(
+        "Weekly Challenge results are sent to the server with an anonymous id",
+    )));
+    let leaderboard_row = adw::SwitchRow::builder()
+        .title(tr("Online leaderboard"))
+        .build();
+    settings::bind_boolean(leaderboard::LEADERBOARD_ENABLED_KEY, false, &leaderboard_row, "active");
+    let server_row = adw::EntryRow::builder()
+        .title(tr("Leaderboard server"))
+        .text(settings::string(leaderboard::LEADERBOARD_URL_KEY, ""))
+        .show_apply_button(true)
+        .input_purpose(gtk::InputPurpose::Url)
+        .build();
+    server_row.set_sensitive(leaderboard_row.is_active());
+    leaderboard_row.connect_active_notify({
+        let server_row = server_row.clone();
+        move |row| {
+            server_row.set_sensitive(row.is_active());
+        }
+    });
+    server_row.connect_apply(|row| {
+        settings::set_string(leaderboard::LEADERBOARD_URL_KEY, row.text().trim());
+    });
+    online_group.add(&leaderboard_row);
+    online_group.add(&server_row);
     page.add(&data_group);
+    page.add(&online_group);
 
     dialog.add(&page);
+    dialog.add(&build_shortcuts_page(state, app, &dialog));
     dialog.present(app.active_window().as_ref());
     dialog
 }
 
-pub(super) fn clear_flip_classes(button: &gtk::Button) {
-    button.remove_css_class("flip-hide");
-    button.remove_css_class("flip-show");
-    button.remove_css_class("flip-show-a");
-    button.remove_css_class("flip-show-b");
-    button.remove_css_class("reshuffle-flip");
-    button.remove_css_class("hard-reshuffle-fast");
-    button.remove_css_class("infinite-round-flip");
+fn apply_shortcut_changes(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
+    shortcuts::apply_app_accels(app);
+    if let Some(win) = app.active_window().and_downcast::<gtk::ApplicationWindow>() {
+        install_shortcuts_overlay(&win, state);
+    }
+}
+
+fn build_shortcuts_page(
+    state: &Rc<RefCell<AppState>>,
+    app: &adw::Application,
+    dialog: &adw::PreferencesDialog,
+) -> adw::PreferencesPage {
+    let page = adw::PreferencesPage::new();
+    page.set_title(&tr("Shortcuts"));
+    page.set_icon_name(Some("preferences-desktop-keyboard-shortcuts-symbolic"));
+
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&tr("Keyboard Shortcuts"));
+    group.set_description(Some(&tr("Select a shortcut to assign a new key")));
+
+    let labels: Rc<Vec<gtk::ShortcutLabel>> = Rc::new(
+        shortcuts::SHORTCUTS
+            .iter()
+            .map(|shortcut| gtk::ShortcutLabel::new(&shortcuts::accelerator(shortcut)))
+            .collect(),
+    );
+    for (shortcut, label) in shortcuts::SHORTCUTS.iter().zip(labels.iter()) {
+        let row = adw::ActionRow::builder()
+            .title(tr(shortcut.title))
+            .activatable(true)
+            .build();
+        label.set_valign(gtk::Align::Center);
+        row.add_suffix(label);
+        let state = state.clone();
+        let app = app.clone();
+        let dialog = dialog.clone();
+        let label = label.clone();
+        row.connect_activated(move |_| {
+            show_shortcut_capture_dialog(&state, &app, &dialog, shortcut, &label);
+        });
+        group.add(&row);
+    }
+
+    let reset_button = gtk::Button::with_label(&tr("Reset to Defaults"));
+    reset_button.add_css_class("flat");
+    reset_button.set_valign(gtk::Align::Center);
+    group.set_header_suffix(Some(&reset_button));
+    {
+        let state = state.clone();
+        let app = app.clone();
+        reset_button.connect_clicked(move |_| {
+            shortcuts::reset_all();
+            for (shortcut, label) in shortcuts::SHORTCUTS.iter().zip(labels.iter()) {
+                label.set_accelerator(&shortcuts::accelerator(shortcut));
+            }
+            apply_shortcut_changes(&state, &app);
+        });
+    }
+
+    page.add(&group);
+    page
+}
+
+fn show_shortcut_capture_dialog(
+    state: &Rc<RefCell<AppState>>,
+    app: &adw::Application,
+    parent: &adw::PreferencesDialog,
+    shortcut: &'static shortcuts::Shortcut,
+    label: &gtk::ShortcutLabel,
+) {
+    let prompt = format!(
+        "{}\n{}",
+        tr(shortcut.title),
+        tr("Press the new key combination, or Escape to cancel")
+    );
+    let capture = adw::AlertDialog::builder()
+        .heading(tr("Set Shortcut"))
+        .body(prompt.as_str())
+        .build();
+    capture.add_response("cancel", &tr("Cancel"));
+    capture.set_close_response("cancel");
+
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    key_controller.connect_key_pressed({
+        let state = state.clone();
+        let app = app.clone();
+        let capture = capture.clone();
+        let label = label.clone();
+        move |_, key, _, mods| {
+            if key == gdk::Key::Escape {
+                capture.close();
+                return glib::Propagation::Stop;
+            }
+            let Some(accel) = shortcuts::accelerator_from_event(key, mods) else {
+                return glib::Propagation::Stop;
+            };
+            if let Some(other) = shortcuts::conflict(shortcut, &accel) {
+                capture.set_body(&format!(
+                    "{} · {} {}",
+                    shortcuts::label(&accel),
+                    tr("Already used by"),
+                    tr(other)
+                ));
+                return glib::Propagation::Stop;
+            }
+            shortcuts::set_accelerator(shortcut, &accel);
+            label.set_accelerator(&accel);
+            apply_shortcut_changes(&state, &app);
+            capture.close();
+            glib::Propagation::Stop
+        }
+    });
+    capture.add_controller(key_controller);
+    capture.present(Some(parent));
+}
+
+// Flags only ever set for the length of an animation or a flash.
+const TRANSIENT_CARD_FLAGS: [CardFlag; 8] = [
+    CardFlag::Shake,
+    CardFlag::Bump,
+    CardFlag::HintMatch,
+    CardFlag::HintExplore,
+    CardFlag::PatternLit,
+    CardFlag::PatternHit,
+    CardFlag::PatternMiss,
+    CardFlag::NBackCue,
+];
+const TRANSIENT_BOARD_CLASSES: [&str; 3] = [
+    "victory-pending",
+    "infinite-level-swap-in",
+    "infinite-level-swap-out",
+];
+
+// Drops every pending timeout and animation of the current game and puts the
+// cards back to a plain state, so a rebuild or mode switch starts clean.
+pub(super) fn cancel_all_transients(st: &mut AppState) {
+    stop_preview(st);
+    stop_victory_sparks(st);
+    st.invalidate_callbacks();
+    st.lock_input = false;
+    st.peeking = false;
+    st.preview_paused = false;
+    st.flipped_indices.clear();
+    set_board_concealed(st, false);
+    if let Some(board) = &st.board_canvas {
+        board.clear_flips();
+        board.remove_flags_everywhere(&TRANSIENT_CARD_FLAGS);
+    }
+    if let Some(container) = &st.board_container {
+        for class in TRANSIENT_BOARD_CLASSES {
+            container.remove_css_class(class);
+        }
+    }
 }
 
-pub(super) fn redraw_button_child(button: &gtk::Button) {
-    if let Some(child) = button.child() {
-        child.queue_draw();
+// Hides the tile at once; the card shows its face until the turn takes it edge-on.
+pub(super) fn play_flip_hide(st: &mut AppState, index: usize) {
+    if let Some(tile) = st.tiles.get_mut(index) {
+        tile.status = TileStatus::Hidden;
     }
+    if let Some(board) = &st.board_canvas {
+        for flag in [CardFlag::ReshuffleFlip, CardFlag::FastReshuffleFlip, CardFlag::RoundFlip] {
+            board.remove_flag(index, flag);
+        }
+        board.turn_face_down(index);
+    }
+    events::emit(GameEvent::BoardChanged);
 }
 
-pub(super) fn play_flip_show(st: &mut AppState, index: usize) {
-    let button = st.grid_buttons[index].clone();
-    clear_flip_classes(&button);
-    st.flip_anim_phase = !st.flip_anim_phase;
-    if st.flip_anim_phase {
-        button.add_css_class("flip-show-a");
-    } else {
-        button.add_css_class("flip-show-b");
+pub(super) fn play_flip_show(st: &AppState, index: usize) {
+    if let Some(board) = &st.board_canvas {
+        board::reveal_card(board, index);
     }
-    redraw_button_child(&button);
+    events::emit(GameEvent::BoardChanged);
 }
 
 enum FlipOutcome {
@@ -204,7 +1132,6 @@ fn evaluate_flip_outcome(st: &AppState, indices: &[usize], latest_index: usize)
     }
 }
 
-const FLIP_PHASE_MS: u64 = 260;
 const CLASSIC_RESHUFFLE_FLIP_MS: u64 = 760;
 const HARD_ENDGAME_RESHUFFLE_FLIP_MS: u64 = 620;
 const INFINITE_PRE_TRANSITION_WAIT_MS: u64 = 500;
@@ -212,6 +1139,9 @@ const MATCH_BUMP_DELAY_MS: u64 = 120;
 const MATCH_BUMP_DURATION_MS: u64 = 700;
 const FINAL_MATCH_DIM_SETTLE_MS: u64 = 110;
 const PREVIEW_REVEAL_MIN_DELAY_MS: u64 = 500;
+const INTRO_DEAL_SPAN_MS: u64 = 600;
+const INTRO_STEP_MIN_MS: u64 = 12;
+const INTRO_STEP_MAX_MS: u64 = 90;
 const VICTORY_FLIP_SHOW_DURATION_MS: u64 = 380;
 const VICTORY_CASCADE_END_BUFFER_MS: u64 = 32;
 
@@ -224,15 +1154,25 @@ fn sync_window_maximized_class(win: &adw::ApplicationWindow) {
 }
 
 pub(super) fn refresh_board_shell_ratio(state: &Rc<RefCell<AppState>>) {
-    let (board_shell, grid_cols, grid_rows, compact_layout) = {
+    let (board_shell, board_clamp, board_bin, grid_cols, grid_rows, show_coordinates, compact_layout) = {
         let st = state.borrow();
         (
             st.board_shell.clone(),
+            st.board_clamp.clone(),
+            st.board_bin.clone(),
             st.grid_cols,
             st.grid_rows,
+            st.show_coordinates,
             st.compact_layout,
         )
     };
+    if let Some(board_clamp) = board_clamp {
+        board_clamp.set_maximum_size(board::board_max_size(grid_cols, grid_rows));
+    }
+    if let Some(board_bin) = board_bin {
+        let (min_width, min_height) = board::board_min_size(grid_cols, grid_rows, show_coordinates);
+        board_bin.set_size_request(min_width, min_height);
+    }
     let Some(board_shell) = board_shell else {
         return;
     };
@@ -245,8 +1185,8 @@ pub(super) fn refresh_board_shell_ratio(state: &Rc<RefCell<AppState>>) {
 }
 
 fn sync_window_layout_classes(win: &adw::ApplicationWindow, state: &Rc<RefCell<AppState>>) {
-    let width = win.allocated_width().max(1);
-    let height = win.allocated_height().max(1);
+    let width = win.width().max(1);
+    let height = win.height().max(1);
     let compact_layout = (width < 760 && height < 620) || width < 520;
     let ultra_compact_layout = (width < 620 && height < 520) || width < 440;
 
@@ -271,10 +1211,11 @@ fn sync_window_layout_classes(win: &adw::ApplicationWindow, state: &Rc<RefCell<A
     if layout_changed {
         let st = state.borrow();
         update_subtitle(&st);
+        speedrun::refresh_splits_panel(&st);
     }
 }
 
-fn is_game_view_active(st: &AppState) -> bool {
+pub(super) fn is_game_view_active(st: &AppState) -> bool {
     st.view_stack
         .as_ref()
         .and_then(|stack| stack.visible_child_name())
@@ -290,19 +1231,13 @@ fn can_show_keyboard_focus(st: &AppState) -> bool {
 }
 
 fn clear_keyboard_focus(state: &Rc<RefCell<AppState>>) {
-    let buttons = {
-        let st = state.borrow();
-        st.grid_buttons.clone()
-    };
-    for button in buttons {
-        button.remove_css_class("kbd-focus");
+    if let Some(board) = &state.borrow().board_canvas {
+        board.focus_card(None);
     }
 }
 
 fn focused_tile_index(st: &AppState) -> Option<usize> {
-    st.grid_buttons.iter().position(|button| {
-        button.has_focus() || button.has_visible_focus() || button.has_css_class("kbd-focus")
-    })
+    st.board_canvas.as_ref().and_then(BoardCanvas::focused_index)
 }
 
 fn normalize_target_col(row: i32, col: i32, cols: i32, len: usize) -> i32 {
@@ -318,37 +1253,31 @@ fn normalize_target_col(row: i32, col: i32, cols: i32, len: usize) -> i32 {
 }
 
 fn focus_tile_at_index(state: &Rc<RefCell<AppState>>, index: usize) -> bool {
-    let (buttons, button) = {
+    let board = {
         let st = state.borrow();
         if !can_show_keyboard_focus(&st) {
             return false;
         }
-        (st.grid_buttons.clone(), st.grid_buttons.get(index).cloned())
+        st.board_canvas.clone()
     };
-    let Some(button) = button else {
+    let Some(board) = board.filter(|board| index < board.card_count()) else {
         return false;
     };
-    for (button_index, candidate) in buttons.iter().enumerate() {
-        if button_index == index {
-            candidate.add_css_class("kbd-focus");
-        } else {
-            candidate.remove_css_class("kbd-focus");
-        }
-    }
-    button.grab_focus();
+    board.focus_card(Some(index));
+    board.grab_focus();
     true
 }
 
 fn move_board_focus(state: &Rc<RefCell<AppState>>, col_delta: i32, row_delta: i32) -> bool {
     let next_index = {
         let st = state.borrow();
-        if !is_game_view_active(&st) || st.grid_buttons.is_empty() || st.grid_cols <= 0 {
+        if !is_game_view_active(&st) || st.tiles.is_empty() || st.grid_cols <= 0 {
             return false;
         }
 
         let current_index = focused_tile_index(&st).unwrap_or(0);
         let cols = st.grid_cols;
-        let len = st.grid_buttons.len();
+        let len = st.tiles.len();
         let max_row = ((len as i32 - 1) / cols).max(0);
 
         let current_row = (current_index as i32 / cols).clamp(0, max_row);
@@ -375,7 +1304,7 @@ fn suppress_board_hover_for_keyboard(state: &Rc<RefCell<AppState>>) {
 fn activate_focused_tile(state: &Rc<RefCell<AppState>>) -> bool {
     let tile_index = {
         let st = state.borrow();
-        if !is_game_view_active(&st) || st.grid_buttons.is_empty() {
+        if !is_game_view_active(&st) || st.tiles.is_empty() {
             return false;
         }
         focused_tile_index(&st).unwrap_or(0)
@@ -384,6 +1313,47 @@ fn activate_focused_tile(state: &Rc<RefCell<AppState>>) -> bool {
     true
 }
 
+fn tag_focused_tile(state: &Rc<RefCell<AppState>>) -> bool {
+    let mut st = state.borrow_mut();
+    if !is_game_view_active(&st) {
+        return false;
+    }
+    let Some(tile_index) = focused_tile_index(&st) else {
+        return false;
+    };
+    tags::cycle(&mut st, tile_index)
+}
+
+// Column letter followed by row digit, e.g. "B3".
+fn handle_coordinate_key(state: &Rc<RefCell<AppState>>, key: gdk::Key) -> bool {
+    let tile_index = {
+        let mut st = state.borrow_mut();
+        if !can_show_keyboard_focus(&st) {
+            st.pending_coordinate_col = None;
+            return false;
+        }
+        if let Some(col) = coordinates::column_from_key(key) {
+            let valid = col < st.grid_cols;
+            st.pending_coordinate_col = valid.then_some(col);
+            return valid;
+        }
+        let Some(row) = coordinates::row_from_key(key) else {
+            return false;
+        };
+        let Some(col) = st.pending_coordinate_col.take() else {
+            return false;
+        };
+        coordinates::tile_index(col, row, st.grid_cols, st.tiles.len())
+    };
+    let Some(tile_index) = tile_index else {
+        return false;
+    };
+    suppress_board_hover_for_keyboard(state);
+    focus_tile_at_index(state, tile_index);
+    handle_tile_click(state, tile_index);
+    true
+}
+
 #[derive(Clone, Copy)]
 struct CascadeProfile {
     start_delay_ms: u64,
@@ -509,47 +1479,32 @@ fn balanced_cascade_timings(total_cards: usize, profile: CascadeProfile) -> (u64
     )
 }
 
-fn build_cascade_waves(total_cards: usize, dual_corner_wave: bool) -> Vec<Vec<usize>> {
-    if total_cards == 0 {
-        return Vec::new();
-    }
-    if !dual_corner_wave {
-        return (0..total_cards).map(|idx| vec![idx]).collect();
-    }
-
-    let mut waves = Vec::new();
-    let mut left = 0usize;
-    let mut right = total_cards - 1;
-    while left < right {
-        waves.push(vec![left, right]);
-        left += 1;
-        right = right.saturating_sub(1);
-    }
-    if left == right {
-        waves.push(vec![left]);
-    }
-    waves
-}
-
 #[derive(Clone, Copy, Default)]
 struct OverlayPauseState {
     paused: bool,
+    timer_running: bool,
     previous_lock_input: bool,
     paused_during_preview: bool,
+    preview_remaining_ms: u32,
+}
+
+// Covering the cards keeps a paused preview from turning into extra study time.
+fn set_board_concealed(st: &AppState, concealed: bool) {
+    if let Some(container) = &st.board_container {
+        container.set_opacity(if concealed { 0.0 } else { 1.0 });
+    }
 }
 
 fn pause_game_for_overlay(state: &Rc<RefCell<AppState>>) -> OverlayPauseState {
     let mut st = state.borrow_mut();
-    let in_game_view = st
-        .view_stack
-        .as_ref()
-        .and_then(|stack| stack.visible_child_name())
-        .as_deref()
-        == Some("game");
-    if !in_game_view {
+    if !is_game_view_active(&st) {
         return OverlayPauseState::default();
     }
+    pause_game(&mut st)
+}
 
+// Stops the clock too, so a paused game neither counts time nor runs out of it.
+fn pause_game(st: &mut AppState) -> OverlayPauseState {
     let has_active_game_flow = st.timer_handle.is_some() || st.preview_active || st.lock_input;
     if !has_active_game_flow {
         return OverlayPauseState::default();
@@ -557,25 +1512,44 @@ fn pause_game_for_overlay(state: &Rc<RefCell<AppState>>) -> OverlayPauseState {
 
     let pause_state = OverlayPauseState {
         paused: true,
+        timer_running: st.timer_handle.is_some(),
         previous_lock_input: st.lock_input,
         paused_during_preview: st.preview_active,
+        preview_remaining_ms: st.preview_remaining_ms,
     };
+    stop_timer(st);
     st.lock_input = true;
+    if let Some(title) = &st.game_title {
+        title.show_status(StatusSource::Paused, &tr("Paused"));
+    }
+    if st.preview_active {
+        st.preview_paused = true;
+        set_board_concealed(st, true);
+    }
     pause_state
 }
 
 fn resume_game_after_overlay(state: &Rc<RefCell<AppState>>, pause_state: OverlayPauseState) {
+    let in_game_view = is_game_view_active(&state.borrow());
+    resume_game(state, pause_state, in_game_view);
+}
+
+fn resume_game(state: &Rc<RefCell<AppState>>, pause_state: OverlayPauseState, in_game_view: bool) {
     if !pause_state.paused {
         return;
     }
 
     let mut st = state.borrow_mut();
-    let in_game_view = st
-        .view_stack
-        .as_ref()
-        .and_then(|stack| stack.visible_child_name())
-        .as_deref()
-        == Some("game");
+    if let Some(title) = &st.game_title {
+        title.clear_status(StatusSource::Paused);
+    }
+    if pause_state.paused_during_preview {
+        set_board_concealed(&st, false);
+        st.preview_paused = false;
+        if st.preview_active {
+            st.preview_remaining_ms = pause_state.preview_remaining_ms;
+        }
+    }
     if !in_game_view {
         return;
     }
@@ -587,6 +1561,10 @@ fn resume_game_after_overlay(state: &Rc<RefCell<AppState>>, pause_state: Overlay
         pause_state.previous_lock_input
     };
     update_subtitle(&st);
+    if pause_state.timer_running && st.timer_handle.is_none() {
+        drop(st);
+        start_timer(state, false);
+    }
 }
 
 fn saved_run_level_name(level: u8) -> &'static str {
@@ -599,14 +1577,39 @@ fn saved_run_level_name(level: u8) -> &'static str {
 }
 
 fn saved_run_subtitle(saved_run: &session_save::SavedRun) -> String {
-    let mode_label = match saved_run.difficulty {
-        Difficulty::Infinite => format!("{} {}", tr("Infinite Round"), saved_run.infinite_round.max(1)),
-        Difficulty::Trio => format!("{} {}", tr("Trio"), tr(saved_run_level_name(saved_run.trio_level))),
-        _ => format!("{} {}", tr("Classic"), tr(saved_run.difficulty.name())),
+    let mode_label = if let Some(challenge) = &saved_run.challenge {
+        challenge.mode_label()
+    } else if let Some(tournament) = &saved_run.tournament {
+        tournament.mode_label()
+    } else if let Some(bracket) = &saved_run.bracket {
+        bracket.mode_label()
+    } else if let Some(hotseat) = &saved_run.hotseat {
+        hotseat.mode_label()
+    } else if let Some(gauntlet) = &saved_run.gauntlet {
+        gauntlet.mode_label()
+    } else if saved_run.speedrun {
+        format!("{} {}", tr("Speedrun"), tr(saved_run.difficulty.name()))
+    } else if saved_run.sudden_death {
+        format!("{} {}", tr("Sudden Death"), tr(saved_run.difficulty.name()))
+    } else if let Some(board) = saved_run.kids {
+        board.mode_label()
+    } else if let Some(custom) = &saved_run.custom {
+        custom.mode_label()
+    } else {
+        match saved_run.difficulty {
+            Difficulty::Infinite => tr_format(
+                "Infinite Round {round}",
+                &[("round", &saved_run.infinite_round.max(1))],
+            ),
+            Difficulty::InfiniteTrio => tr_format(
+                "Infinite Trio Round {round}",
+                &[("round", &saved_run.infinite_round.max(1))],
+            ),
+            Difficulty::Trio => format!("{} {}", tr("Trio"), tr(saved_run_level_name(saved_run.trio_level))),
+            _ => format!("{} {}", tr("Classic"), tr(saved_run.difficulty.name())),
+        }
     };
-    let mins = saved_run.seconds_elapsed / 60;
-    let secs = saved_run.seconds_elapsed % 60;
-    format!("{mode_label} · {mins:02}:{secs:02}")
+    format!("{mode_label} · {}", format_duration(saved_run.seconds_elapsed))
 }
 
 fn set_continue_button_content(
@@ -653,19 +1656,35 @@ fn clear_saved_run_and_refresh(st: &mut AppState) {
 
 fn save_current_run_and_refresh(st: &AppState) {
     if let Err(err) = session_save::save_current_run(st) {
-        eprintln!("warning: failed to save current run: {err}");
+        tracing::warn!("failed to save current run: {err}");
     }
     refresh_continue_button_state(st);
 }
 
+pub(super) fn save_play_stats(st: &AppState) {
+    symbol_stats::save(st);
+    heatmap::save(st);
+}
+
 fn mark_run_dirty(st: &mut AppState) {
     if st.active_session_started {
         save_current_run_and_refresh(st);
     }
 }
 
+fn save_run_on_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| match event {
+        GameEvent::TileFlipped(_)
+        | GameEvent::MatchFound(_)
+        | GameEvent::MismatchMade(_)
+        | GameEvent::PenaltyApplied(_) => mark_run_dirty(&mut state.borrow_mut()),
+        GameEvent::RoundFinished { .. } | GameEvent::BoardChanged => {}
+    });
+}
+
 fn should_finalize_infinite_run(st: &AppState) -> bool {
-    st.difficulty == Difficulty::Infinite
+    infinite::is_infinite(st.difficulty)
         && st.active_session_started
         && (st.seconds_elapsed > 0 || st.run_matches > 0 || st.run_mismatches > 0)
 }
@@ -673,16 +1692,16 @@ fn should_finalize_infinite_run(st: &AppState) -> bool {
 fn finalize_infinite_run_if_needed(st: &mut AppState) {
     if should_finalize_infinite_run(st) {
         register_infinite_run_result(st);
+        session_recap::note_board(st, None);
+        save_play_stats(st);
         st.active_session_started = false;
         clear_saved_run_and_refresh(st);
     }
 }
 
 fn prepare_infinite_finish_victory(st: &mut AppState) {
-    let mins = st.seconds_elapsed / 60;
-    let secs = st.seconds_elapsed % 60;
-    let elapsed = format!("{mins:02}:{secs:02}");
-    st.victory_art_resource = Some("/io/github/basshift/Recall/victory/finish-flag.svg".to_string());
+    let elapsed = format_duration(st.seconds_elapsed);
+    st.victory_art_resource = Some(resources::FINISH_FLAG_ART.to_string());
     st.victory_title_text = tr("You chose the finish");
     st.victory_message_text = tr("Infinite on your terms");
     st.victory_stats_text = format!(
@@ -709,6 +1728,8 @@ fn finish_infinite_run(state: &Rc<RefCell<AppState>>) {
         st.lock_input = false;
         st.flipped_indices.clear();
         register_infinite_run_result(&mut st);
+        session_recap::note_board(&mut st, None);
+        save_play_stats(&st);
         prepare_infinite_finish_victory(&mut st);
         st.active_session_started = false;
         clear_saved_run_and_refresh(&mut st);
@@ -748,6 +1769,57 @@ fn maybe_finish_infinite_run(state: &Rc<RefCell<AppState>>, app: &adw::Applicati
     dialog.present(app.active_window().as_ref());
 }
 
+// Leaving an Infinite run mid-board asks whether to file it, drop it or keep it for Continue.
+fn leave_game(state: &Rc<RefCell<AppState>>) {
+    let (parent, summary) = {
+        let st = state.borrow();
+        if !is_game_view_active(&st) || !should_finalize_infinite_run(&st) {
+            drop(st);
+            show_menu(state);
+            return;
+        }
+        (
+            st.main_window.clone(),
+            infinite_flow::quit_summary(st.infinite_pacing, st.infinite_round, st.seconds_elapsed),
+        )
+    };
+
+    let pause_state = pause_game_for_overlay(state);
+    let dialog = adw::AlertDialog::builder()
+        .heading(tr("Leave run?"))
+        .body(summary)
+        .build();
+    dialog.add_response("discard", &tr("Discard"));
+    dialog.add_response("later", &tr("Continue Later"));
+    dialog.add_response("save", &tr("Save Record"));
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+    dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+
+    let state = state.clone();
+    dialog.connect_response(None, move |_, response| {
+        resume_game_after_overlay(&state, pause_state);
+        match response {
+            "save" => {
+                let mut st = state.borrow_mut();
+                habits::note_quit(&st);
+                finalize_infinite_run_if_needed(&mut st);
+            }
+            "discard" => {
+                let mut st = state.borrow_mut();
+                habits::note_quit(&st);
+                st.active_session_started = false;
+                clear_saved_run_and_refresh(&mut st);
+            }
+            "later" => {}
+            _ => return,
+        }
+        show_menu(&state);
+    });
+    dialog.present(parent.as_ref());
+}
+
 fn should_confirm_restart(st: &AppState) -> bool {
     st.active_session_started || st.seconds_elapsed > 0 || st.run_matches > 0 || st.run_mismatches > 0
 }
@@ -812,28 +1884,73 @@ fn continue_last_run(state: &Rc<RefCell<AppState>>) {
         refresh_continue_button_state(&st);
         return;
     };
+    // A Kids run stays saved for tomorrow once today's play time is used up.
+    if saved_run.kids.is_some() && play_limit::is_locked(&mut state.borrow_mut()) {
+        play_limit::show_lockout(state);
+        return;
+    }
+    if !restore_saved_run(state, saved_run) {
+        let mut st = state.borrow_mut();
+        clear_saved_run_and_refresh(&mut st);
+    }
+}
 
+// Returns false when the saved tiles do not fit the board of the saved mode.
+pub(super) fn restore_saved_run(
+    state: &Rc<RefCell<AppState>>,
+    saved_run: session_save::SavedRun,
+) -> bool {
     {
         let mut st = state.borrow_mut();
         stop_timer(&mut st);
         stop_preview(&mut st);
         st.trio_level = saved_run.trio_level.clamp(1, 4);
         st.infinite_level = saved_run.infinite_level.clamp(1, 4);
+        st.challenge = saved_run.challenge.clone();
+        st.series = saved_run
+            .tournament
+            .clone()
+            .map(Series::Tournament)
+            .or(saved_run.gauntlet.map(Series::Gauntlet));
+        st.hotseat = saved_run.hotseat.clone();
+        st.bracket = saved_run.bracket.clone();
+        st.speedrun = saved_run.speedrun;
+        st.sudden_death = saved_run.sudden_death;
+        st.run_modifier_codes = saved_run.modifiers.clone();
+        st.pattern = None;
+        st.nback = None;
+        st.warmup_target = None;
+        st.custom = saved_run.custom.clone();
+        st.kids = saved_run.kids;
         st.set_difficulty(saved_run.difficulty);
-        if saved_run.difficulty == Difficulty::Infinite {
+        if infinite::is_infinite(saved_run.difficulty) {
             st.infinite_round = saved_run.infinite_round.max(1);
+            st.infinite_pacing = saved_run.infinite_pacing;
+            infinite::note_best_round(&mut st);
         }
         if st.tiles.len() != saved_run.tiles.len() {
-            clear_saved_run_and_refresh(&mut st);
-            return;
+            return false;
         }
         st.tiles = saved_run.tiles;
+        st.refresh_board_faces();
+        match saved_run.seed {
+            Some(seed) => st.seed_board_rng(seed),
+            None => st.reseed_board_rng(),
+        }
         st.flipped_indices = saved_run
             .flipped_indices
             .into_iter()
             .filter(|idx| *idx < st.tiles.len() && st.tiles[*idx].status == TileStatus::Flipped)
             .collect();
+        for idx in 0..st.tiles.len() {
+            if st.tiles[idx].status == TileStatus::Flipped && !st.flipped_indices.contains(&idx) {
+                st.tiles[idx].status = TileStatus::Hidden;
+            }
+        }
         st.seconds_elapsed = saved_run.seconds_elapsed;
+        st.run_clock = RunClock::starting_at_ms(u64::from(saved_run.seconds_elapsed) * 1000);
+        breaks::mark_break(&mut st);
+        st.match_times_ms = saved_run.match_times_ms;
         st.run_mismatches = saved_run.run_mismatches;
         st.run_matches = saved_run.run_matches;
         st.impossible_mismatch_count = saved_run.impossible_mismatch_count;
@@ -845,32 +1962,37 @@ fn continue_last_run(state: &Rc<RefCell<AppState>>) {
         st.lock_input = false;
         st.active_session_started = true;
     }
+    // A board dumped mid-turn turns its last pick back down and plays it again
+    // below, so a full turn is resolved instead of left face up.
+    let replayed_pick = {
+        let mut st = state.borrow_mut();
+        let pick = st.flipped_indices.pop();
+        if let Some(idx) = pick {
+            st.tiles[idx].status = TileStatus::Hidden;
+            st.tiles[idx].views = st.tiles[idx].views.saturating_sub(1);
+        }
+        pick
+    };
 
     rebuild_board(state);
 
     {
         let st = state.borrow();
-        for idx in 0..st.grid_buttons.len() {
-            let button = st.grid_buttons[idx].clone();
-            clear_flip_classes(&button);
-            button.remove_css_class("matched");
-            button.remove_css_class("matched-dim");
-            button.remove_css_class("active");
-            button.remove_css_class("mismatch-shake");
-            button.remove_css_class("match-bump");
-            if idx < st.tiles.len() {
-                match st.tiles[idx].status {
-                    TileStatus::Matched => {
-                        button.add_css_class("matched");
-                        button.add_css_class("matched-dim");
-                    }
-                    TileStatus::Flipped => button.add_css_class("active"),
-                    TileStatus::Hidden => {}
+        if let Some(board) = &st.board_canvas {
+            for (idx, tile) in st.tiles.iter().enumerate() {
+                board.clear_flip(idx);
+                board.remove_flag(idx, CardFlag::Shake);
+                board.remove_flag(idx, CardFlag::Bump);
+                if tile.status == TileStatus::Matched {
+                    board.add_flag(idx, CardFlag::Dim);
+                } else {
+                    board.remove_flag(idx, CardFlag::Dim);
                 }
             }
-            redraw_button_child(&button);
         }
         update_subtitle(&st);
+        speedrun::refresh_splits_panel(&st);
+        stats_panel::refresh_stats_panel(&st);
     }
 
     set_header_game(state);
@@ -882,6 +2004,10 @@ fn continue_last_run(state: &Rc<RefCell<AppState>>) {
         }
     }
     start_timer(state, false);
+    if let Some(idx) = replayed_pick {
+        handle_tile_click(state, idx);
+    }
+    true
 }
 
 fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices: Vec<usize>) {
@@ -896,11 +2022,14 @@ fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices
     if will_finish
         && is_infinite_mode
         && let Some((next_milestone_difficulty, next_milestone_value)) =
-            infinite_flow::infinite_milestone_value(st.infinite_round.saturating_add(1))
-        && let Some(subtitle) = &st.title_game_subtitle
+            infinite_flow::infinite_milestone_value(
+                st.infinite_pacing,
+                st.infinite_round.saturating_add(1),
+            )
+        && let Some(title) = &st.game_title
     {
-        infinite_flow::set_infinite_milestone_subtitle(
-            subtitle,
+        infinite_flow::flash_milestone_status(
+            title,
             next_milestone_difficulty,
             next_milestone_value,
         );
@@ -915,12 +2044,12 @@ fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices
 
     for &idx in &indices {
         st.tiles[idx].status = TileStatus::Matched;
-        clear_flip_classes(&st.grid_buttons[idx]);
-        st.grid_buttons[idx].remove_css_class("active");
-        st.grid_buttons[idx].remove_css_class("matched-dim");
-        st.grid_buttons[idx].add_css_class("matched");
-        redraw_button_child(&st.grid_buttons[idx]);
+        if let Some(board) = &st.board_canvas {
+            board.clear_flip(idx);
+            board.remove_flag(idx, CardFlag::Dim);
+        }
     }
+    events::emit(GameEvent::MatchFound(indices.clone()));
     st.flipped_indices.clear();
     st.lock_input = false;
 
@@ -932,6 +2061,10 @@ fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices
             save_current_run_and_refresh(&st);
         } else {
             register_non_infinite_result(&mut st);
+            let rank = (st.kids.is_none() && !st.run_assisted()).then_some(st.victory_rank);
+            session_recap::note_board(&mut st, rank);
+            save_play_stats(&st);
+            training::note_game(&mut st, true);
             st.active_session_started = false;
             clear_saved_run_and_refresh(&mut st);
         }
@@ -942,22 +2075,18 @@ fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices
         let final_match_delay_ms =
             MATCH_BUMP_DELAY_MS + MATCH_BUMP_DURATION_MS + FINAL_MATCH_DIM_SETTLE_MS;
         if is_infinite_mode {
-            let state_next = state.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(final_match_delay_ms + INFINITE_PRE_TRANSITION_WAIT_MS),
-                move || {
-                    infinite_flow::schedule_infinite_round_transition(&state_next, game_id);
-                    glib::ControlFlow::Break
-                },
+            sequence::after(
+                state,
+                game_id,
+                final_match_delay_ms + INFINITE_PRE_TRANSITION_WAIT_MS,
+                move |state| infinite_flow::schedule_infinite_round_transition(state, game_id),
             );
         } else {
-            let state_victory = state.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(final_match_delay_ms + cascade_start_delay_ms),
-                move || {
-                    schedule_win_cascade_and_continue(&state_victory, game_id);
-                    glib::ControlFlow::Break
-                },
+            sequence::after(
+                state,
+                game_id,
+                final_match_delay_ms + cascade_start_delay_ms,
+                move |state| schedule_win_cascade_and_continue(state, game_id),
             );
         }
     } else {
@@ -965,239 +2094,159 @@ fn handle_tile_click_result(state: &Rc<RefCell<AppState>>, game_id: u64, indices
     }
 }
 
+// Continues the mismatch `shake` with the pause, the turn back down and any punishment.
 fn schedule_mismatch_reset(
-    state: &Rc<RefCell<AppState>>,
+    shake: Sequence,
     indices: Vec<usize>,
     game_id: u64,
     mismatch_pause_ms: u64,
     penalty_plan: Option<classic_penalties::PunishmentPlan>,
 ) {
-    let state_clone = state.clone();
-    glib::timeout_add_local(
-        std::time::Duration::from_millis(mismatch_pause_ms),
-        move || {
-            let st = state_clone.borrow();
-            if st.game_id != game_id {
-                return glib::ControlFlow::Break;
-            }
+    shake
+        .then_after(mismatch_pause_ms, move |state| {
+            let mut st = state.borrow_mut();
             for &idx in &indices {
-                if let Some(button) = st.grid_buttons.get(idx) {
-                    button.remove_css_class("mismatch-shake");
-                    clear_flip_classes(button);
-                    button.add_css_class("flip-hide");
-                    redraw_button_child(button);
+                if let Some(board) = &st.board_canvas {
+                    board.remove_flag(idx, CardFlag::Shake);
                 }
+                play_flip_hide(&mut st, idx);
             }
-            drop(st);
+            glib::ControlFlow::Continue
+        })
+        .then_after(TURN_DOWN_MS, move |state| {
+            let mut st = state.borrow_mut();
+            if let Some(punishment) = penalty_plan {
+                drop(st);
+                schedule_punishment(state, game_id, punishment);
+            } else {
+                st.flipped_indices.clear();
+                st.lock_input = false;
+                mark_run_dirty(&mut st);
+            }
+            glib::ControlFlow::Continue
+        })
+        .start();
+}
 
-            let state_swap = state_clone.clone();
-            let indices_swap = indices.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(FLIP_PHASE_MS),
-                move || {
-                    let mut st = state_swap.borrow_mut();
-                    if st.game_id != game_id {
-                        return glib::ControlFlow::Break;
-                    }
-                    for &idx in &indices_swap {
-                        st.tiles[idx].status = TileStatus::Hidden;
-                        st.grid_buttons[idx].remove_css_class("active");
-                        play_flip_show(&mut st, idx);
-                    }
-                    glib::ControlFlow::Break
-                },
-            );
+fn hard_endgame_reshuffle(
+    punishment: &classic_penalties::PunishmentPlan,
+    hidden_count: usize,
+    tile_count: usize,
+) -> bool {
+    punishment.source_difficulty == Difficulty::Hard
+        && punishment.reshuffle_hidden
+        && hidden_count.saturating_mul(3) <= tile_count
+}
 
-            let state_finish = state_clone.clone();
-            let indices_finish = indices.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(FLIP_PHASE_MS * 2),
-                move || {
-                    let mut st = state_finish.borrow_mut();
-                    if st.game_id != game_id {
-                        return glib::ControlFlow::Break;
-                    }
-                    for &idx in &indices_finish {
-                        clear_flip_classes(&st.grid_buttons[idx]);
-                        st.grid_buttons[idx].remove_css_class("active");
-                        st.grid_buttons[idx].remove_css_class("mismatch-shake");
-                        redraw_button_child(&st.grid_buttons[idx]);
-                    }
-                    if let Some(punishment) = penalty_plan {
-                        let mut rotate_indices = Vec::new();
-                        let hidden_count = st
-                            .tiles
-                            .iter()
-                            .filter(|tile| tile.status == TileStatus::Hidden)
-                            .count();
-                        let hard_endgame_reshuffle_fast =
-                            punishment.source_difficulty == Difficulty::Hard
-                                && punishment.reshuffle_hidden
-                                && hidden_count.saturating_mul(3) <= st.tiles.len();
-                        if punishment.reshuffle_hidden {
-                            for idx in 0..st.tiles.len() {
-                                if st.tiles[idx].status == TileStatus::Hidden {
-                                    let button = st.grid_buttons[idx].clone();
-                                    clear_flip_classes(&button);
-                                    button.remove_css_class("reshuffle-flip");
-                                    button.add_css_class("reshuffle-flip");
-                                    if hard_endgame_reshuffle_fast {
-                                        button.add_css_class("hard-reshuffle-fast");
-                                    }
-                                    redraw_button_child(&button);
-                                    rotate_indices.push(idx);
-                                }
-                            }
-                        }
-                        st.flipped_indices.clear();
-                        st.lock_input = true;
-                        drop(st);
-
-                        let state_mix_finish = state_finish.clone();
-                        let rotate_indices_finish = rotate_indices.clone();
-                        let punishment_reshuffle = punishment.reshuffle_hidden;
-                        glib::timeout_add_local(
-                            std::time::Duration::from_millis(if punishment_reshuffle {
-                                if hard_endgame_reshuffle_fast {
-                                    HARD_ENDGAME_RESHUFFLE_FLIP_MS
-                                } else {
-                                    CLASSIC_RESHUFFLE_FLIP_MS
-                                }
-                            } else {
-                                0
-                            }),
-                            move || {
-                                let mut st = state_mix_finish.borrow_mut();
-                                if st.game_id != game_id {
-                                    return glib::ControlFlow::Break;
-                                }
-                                for &idx in &rotate_indices_finish {
-                                    if idx < st.grid_buttons.len() {
-                                        let button = st.grid_buttons[idx].clone();
-                                        button.remove_css_class("hard-reshuffle-fast");
-                                        button.remove_css_class("reshuffle-flip");
-                                        clear_flip_classes(&button);
-                                        redraw_button_child(&button);
-                                    }
-                                }
-
-                                if punishment_reshuffle {
-                                    // Punishment: reshuffle hidden cards first.
-                                    st.reshuffle_hidden_tiles();
-                                }
-
-                                // Show only a random subset after reshuffle to force real memory.
-                                use rand::seq::SliceRandom;
-                                let mut hidden_indices: Vec<usize> = st
-                                    .tiles
-                                    .iter()
-                                    .enumerate()
-                                    .filter_map(|(idx, tile)| {
-                                        (tile.status == TileStatus::Hidden).then_some(idx)
-                                    })
-                                    .collect();
-                                let mut rng = rand::rng();
-                                hidden_indices.shuffle(&mut rng);
-                                let reveal_indices: Vec<usize> = if punishment.reveal_all_hidden {
-                                    hidden_indices
-                                } else {
-                                    let reveal_count =
-                                        punishment.reveal_count.min(hidden_indices.len());
-                                    hidden_indices.into_iter().take(reveal_count).collect()
-                                };
-
-                                for &idx in &reveal_indices {
-                                    st.tiles[idx].status = TileStatus::Flipped;
-                                    st.grid_buttons[idx].add_css_class("active");
-                                    play_flip_show(&mut st, idx);
-                                }
-                                st.flipped_indices.clear();
-                                st.lock_input = true;
-                                drop(st);
-
-                                let state_hide_start = state_mix_finish.clone();
-                                let reveal_indices_start = reveal_indices.clone();
-                                glib::timeout_add_local(
-                                    std::time::Duration::from_millis(punishment.reveal_ms),
-                                    move || {
-                                        let st = state_hide_start.borrow();
-                                        if st.game_id != game_id {
-                                            return glib::ControlFlow::Break;
-                                        }
-                                        for &idx in &reveal_indices_start {
-                                            if let Some(button) = st.grid_buttons.get(idx) {
-                                                clear_flip_classes(button);
-                                                button.add_css_class("flip-hide");
-                                                redraw_button_child(button);
-                                            }
-                                        }
-                                        drop(st);
-
-                                        let state_hide_mid = state_hide_start.clone();
-                                        let reveal_indices_mid = reveal_indices_start.clone();
-                                        glib::timeout_add_local(
-                                            std::time::Duration::from_millis(FLIP_PHASE_MS),
-                                            move || {
-                                                let mut st = state_hide_mid.borrow_mut();
-                                                if st.game_id != game_id {
-                                                    return glib::ControlFlow::Break;
-                                                }
-                                                for &idx in &reveal_indices_mid {
-                                                    if idx < st.tiles.len() {
-                                                        st.tiles[idx].status = TileStatus::Hidden;
-                                                    }
-                                                    if idx < st.grid_buttons.len() {
-                                                        st.grid_buttons[idx]
-                                                            .remove_css_class("active");
-                                                        play_flip_show(&mut st, idx);
-                                                    }
-                                                }
-                                                glib::ControlFlow::Break
-                                            },
-                                        );
-
-                                        let state_hide_finish = state_hide_start.clone();
-                                        let reveal_indices_finish = reveal_indices_start.clone();
-                                        glib::timeout_add_local(
-                                            std::time::Duration::from_millis(FLIP_PHASE_MS * 2),
-                                            move || {
-                                                let mut st = state_hide_finish.borrow_mut();
-                                                if st.game_id != game_id {
-                                                    return glib::ControlFlow::Break;
-                                                }
-                                                for &idx in &reveal_indices_finish {
-                                                    if let Some(button) = st.grid_buttons.get(idx)
-                                                    {
-                                                        clear_flip_classes(button);
-                                                        redraw_button_child(button);
-                                                    }
-                                                }
-                                                st.flipped_indices.clear();
-                                                st.lock_input = false;
-                                                mark_run_dirty(&mut st);
-                                                glib::ControlFlow::Break
-                                            },
-                                        );
-
-                                        glib::ControlFlow::Break
-                                    },
-                                );
-
-                                glib::ControlFlow::Break
-                            },
-                        );
-                        glib::ControlFlow::Break
-                    } else {
-                        st.flipped_indices.clear();
-                        st.lock_input = false;
-                        mark_run_dirty(&mut st);
-                        glib::ControlFlow::Break
+pub(super) fn reshuffle_flip_ms(
+    punishment: &classic_penalties::PunishmentPlan,
+    hidden_count: usize,
+    tile_count: usize,
+) -> u64 {
+    if !punishment.reshuffle_hidden {
+        0
+    } else if hard_endgame_reshuffle(punishment, hidden_count, tile_count) {
+        HARD_ENDGAME_RESHUFFLE_FLIP_MS
+    } else {
+        CLASSIC_RESHUFFLE_FLIP_MS
+    }
+}
+
+fn schedule_punishment(
+    state: &Rc<RefCell<AppState>>,
+    game_id: u64,
+    punishment: classic_penalties::PunishmentPlan,
+) {
+    let (rotate_indices, reshuffle_ms) = {
+        let mut st = state.borrow_mut();
+        let mut rotate_indices = Vec::new();
+        let hidden_count = st
+            .tiles
+            .iter()
+            .filter(|tile| tile.status == TileStatus::Hidden)
+            .count();
+        let hard_endgame_reshuffle_fast =
+            hard_endgame_reshuffle(&punishment, hidden_count, st.tiles.len());
+        if punishment.reshuffle_hidden {
+            let flip = if hard_endgame_reshuffle_fast {
+                CardFlag::FastReshuffleFlip
+            } else {
+                CardFlag::ReshuffleFlip
+            };
+            for idx in 0..st.tiles.len() {
+                if st.tiles[idx].status == TileStatus::Hidden {
+                    if let Some(board) = &st.board_canvas {
+                        board.clear_flip(idx);
+                        board.add_flag(idx, flip);
                     }
-                },
-            );
-            glib::ControlFlow::Break
-        },
-    );
+                    rotate_indices.push(idx);
+                }
+            }
+        }
+        st.flipped_indices.clear();
+        st.lock_input = true;
+        events::emit(GameEvent::PenaltyApplied(punishment.summary(hidden_count)));
+        let reshuffle_ms = reshuffle_flip_ms(&punishment, hidden_count, st.tiles.len());
+        (rotate_indices, reshuffle_ms)
+    };
+
+    // Filled once the board is reshuffled, then flipped back down by the later steps.
+    let revealed = Rc::new(RefCell::new(Vec::<usize>::new()));
+    let revealed_hide = revealed.clone();
+    Sequence::new(state, game_id)
+        .at(reshuffle_ms, move |state| {
+            let mut st = state.borrow_mut();
+            if let Some(board) = &st.board_canvas {
+                for &idx in &rotate_indices {
+                    board.clear_flip(idx);
+                }
+            }
+
+            if punishment.reshuffle_hidden {
+                // Punishment: reshuffle hidden cards first.
+                st.reshuffle_hidden_tiles();
+            }
+
+            // Show only a random subset after reshuffle to force real memory.
+            use rand::seq::SliceRandom;
+            let mut hidden_indices: Vec<usize> = st
+                .tiles
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, tile)| (tile.status == TileStatus::Hidden).then_some(idx))
+                .collect();
+            st.with_board_rng(|rng| hidden_indices.shuffle(rng));
+            let reveal_indices: Vec<usize> = if punishment.reveal_all_hidden {
+                hidden_indices
+            } else {
+                let reveal_count = punishment.reveal_count.min(hidden_indices.len());
+                hidden_indices.into_iter().take(reveal_count).collect()
+            };
+
+            for &idx in &reveal_indices {
+                st.tiles[idx].status = TileStatus::Flipped;
+                play_flip_show(&st, idx);
+            }
+            st.flipped_indices.clear();
+            st.lock_input = true;
+            *revealed.borrow_mut() = reveal_indices;
+            glib::ControlFlow::Continue
+        })
+        .then_after(punishment.reveal_ms, move |state| {
+            let mut st = state.borrow_mut();
+            for &idx in revealed_hide.borrow().iter() {
+                play_flip_hide(&mut st, idx);
+            }
+            glib::ControlFlow::Continue
+        })
+        .then_after(TURN_DOWN_MS, move |state| {
+            let mut st = state.borrow_mut();
+            st.flipped_indices.clear();
+            st.lock_input = false;
+            mark_run_dirty(&mut st);
+            glib::ControlFlow::Continue
+        })
+        .start();
 }
 
 fn schedule_match_bump(
@@ -1206,163 +2255,89 @@ fn schedule_match_bump(
     game_id: u64,
     allow_dim_on_complete: bool,
 ) {
-    let state_bump_start = state.clone();
-    let indices_start = indices.clone();
-    glib::timeout_add_local(
-        std::time::Duration::from_millis(MATCH_BUMP_DELAY_MS),
-        move || {
-            let st = state_bump_start.borrow();
-            if st.game_id != game_id {
-                return glib::ControlFlow::Break;
-            }
-            for &idx in &indices_start {
-                if let Some(button) = st.grid_buttons.get(idx) {
-                    button.remove_css_class("matched-dim");
-                    button.remove_css_class("match-bump");
-                    button.add_css_class("match-bump");
+    sequence::after(state, game_id, MATCH_BUMP_DELAY_MS, move |state| {
+        {
+            let st = state.borrow();
+            if let Some(board) = &st.board_canvas {
+                for &idx in &indices {
+                    board.remove_flag(idx, CardFlag::Dim);
+                    board.add_flag(idx, CardFlag::Bump);
                 }
             }
+        }
 
-            let state_bump_end = state_bump_start.clone();
-            let indices_end = indices_start.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(MATCH_BUMP_DURATION_MS),
-                move || {
-                    let st = state_bump_end.borrow();
-                    if st.game_id != game_id {
-                        return glib::ControlFlow::Break;
-                    }
-                    let victory_started = st.tiles.iter().all(|tile| tile.status == TileStatus::Matched);
-                    for &idx in &indices_end {
-                        if let Some(button) = st.grid_buttons.get(idx) {
-                            button.remove_css_class("match-bump");
-                            if !victory_started || allow_dim_on_complete {
-                                button.add_css_class("matched-dim");
-                            }
-                        }
+        sequence::after(state, game_id, MATCH_BUMP_DURATION_MS, move |state| {
+            let st = state.borrow();
+            let victory_started = st.tiles.iter().all(|tile| tile.status == TileStatus::Matched);
+            if let Some(board) = &st.board_canvas {
+                for &idx in &indices {
+                    board.remove_flag(idx, CardFlag::Bump);
+                    if !victory_started || allow_dim_on_complete {
+                        board.add_flag(idx, CardFlag::Dim);
                     }
-                    glib::ControlFlow::Break
-                },
-            );
-
-            glib::ControlFlow::Break
-        },
-    );
+                }
+            }
+        });
+    });
 }
 
 fn schedule_win_cascade_and_continue(state: &Rc<RefCell<AppState>>, game_id: u64) {
-    let (total_cards, profile) = {
+    let (total_cards, profile, waves) = {
         let mut st = state.borrow_mut();
         st.lock_input = true;
         if let Some(container) = &st.board_container {
             container.add_css_class("no-hover");
         }
-        for button in &st.grid_buttons {
-            button.remove_css_class("matched-dim");
-            button.remove_css_class("match-bump");
+        if let Some(board) = &st.board_canvas {
+            board.remove_flags_everywhere(&[CardFlag::Dim, CardFlag::Bump]);
         }
-        (st.grid_buttons.len(), cascade_profile_for(&st))
+        let total_cards = st.tiles.len();
+        let profile = cascade_profile_for(&st);
+        let waves = st.board_transition.waves(
+            st.grid_cols.max(1) as usize,
+            st.grid_rows.max(0) as usize,
+            total_cards,
+            profile.dual_corner_wave,
+        );
+        (total_cards, profile, waves)
     };
     clear_keyboard_focus(state);
     let color_restore_ms = 220;
     let pre_cascade_bump_ms = color_restore_ms + MATCH_BUMP_DURATION_MS;
 
-    let state_bump_start = state.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(color_restore_ms), move || {
-        let st = state_bump_start.borrow();
-        let is_in_game = st.view_stack.as_ref()
-            .and_then(|s| s.visible_child_name())
-            .as_deref() == Some("game");
-
-        if st.game_id != game_id || !is_in_game {
-            return glib::ControlFlow::Break;
-        }
-        for button in &st.grid_buttons {
-            button.remove_css_class("match-bump");
-            button.add_css_class("match-bump");
-        }
-        glib::ControlFlow::Break
-    });
-
-    let state_bump_end = state.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(pre_cascade_bump_ms), move || {
-        let st = state_bump_end.borrow();
-        let is_in_game = st.view_stack.as_ref()
-            .and_then(|s| s.visible_child_name())
-            .as_deref() == Some("game");
-
-        if st.game_id != game_id || !is_in_game {
-            return glib::ControlFlow::Break;
-        }
-        for button in &st.grid_buttons {
-            button.remove_css_class("match-bump");
-        }
-        glib::ControlFlow::Break
-    });
+    let mut cascade = Sequence::new(state, game_id)
+        .only_while(is_game_view_active)
+        .at(color_restore_ms, |state| {
+            let st = state.borrow();
+            if let Some(board) = &st.board_canvas {
+                for idx in 0..board.card_count() {
+                    board.add_flag(idx, CardFlag::Bump);
+                }
+            }
+            glib::ControlFlow::Continue
+        })
+        .at(pre_cascade_bump_ms, |state| {
+            let st = state.borrow();
+            if let Some(board) = &st.board_canvas {
+                board.remove_flags_everywhere(&[CardFlag::Bump]);
+            }
+            glib::ControlFlow::Continue
+        });
 
     let (cascade_step_ms, post_cascade_pause_ms) = balanced_cascade_timings(total_cards, profile);
-    let waves = build_cascade_waves(total_cards, profile.dual_corner_wave);
 
     for (wave_idx, wave_indices) in waves.iter().enumerate() {
-        let wave_indices_hide = wave_indices.clone();
-        let state_step = state.clone();
-        glib::timeout_add_local(
-            std::time::Duration::from_millis(pre_cascade_bump_ms + wave_idx as u64 * cascade_step_ms),
-            move || {
-                let st = state_step.borrow_mut();
-                let is_in_game = st.view_stack.as_ref()
-                    .and_then(|s| s.visible_child_name())
-                    .as_deref() == Some("game");
-                
-                if st.game_id != game_id || !is_in_game {
-                    return glib::ControlFlow::Break;
-                }
-                for &idx in &wave_indices_hide {
-                    if idx < st.grid_buttons.len() {
-                        st.grid_buttons[idx].remove_css_class("matched");
-                        st.grid_buttons[idx].remove_css_class("matched-dim");
-                        st.grid_buttons[idx].remove_css_class("active");
-                    }
-                    if let Some(button) = st.grid_buttons.get(idx) {
-                        button.add_css_class("victory-cascade");
-                        clear_flip_classes(button);
-                        button.add_css_class("flip-hide");
-                        redraw_button_child(button);
-                    }
-                }
-                glib::ControlFlow::Break
-            },
-        );
-
-        let wave_indices_show = wave_indices.clone();
-        let state_step_back = state.clone();
-        glib::timeout_add_local(
-            std::time::Duration::from_millis(
-                pre_cascade_bump_ms + wave_idx as u64 * cascade_step_ms + FLIP_PHASE_MS
-            ),
-            move || {
-                let mut st = state_step_back.borrow_mut();
-                let is_in_game = st.view_stack.as_ref()
-                    .and_then(|s| s.visible_child_name())
-                    .as_deref() == Some("game");
-
-                if st.game_id != game_id || !is_in_game {
-                    return glib::ControlFlow::Break;
-                }
-                for &idx in &wave_indices_show {
-                    if idx < st.tiles.len() {
-                        st.tiles[idx].status = TileStatus::Hidden;
-                    }
-                    if idx < st.grid_buttons.len() {
-                        st.grid_buttons[idx].remove_css_class("matched");
-                        st.grid_buttons[idx].remove_css_class("matched-dim");
-                        st.grid_buttons[idx].remove_css_class("active");
-                        play_flip_show(&mut st, idx);
-                    }
+        let wave_indices = wave_indices.clone();
+        cascade = cascade.at(pre_cascade_bump_ms + wave_idx as u64 * cascade_step_ms, move |state| {
+            let mut st = state.borrow_mut();
+            for &idx in &wave_indices {
+                if let Some(board) = &st.board_canvas {
+                    board.remove_flag(idx, CardFlag::Dim);
                 }
-                glib::ControlFlow::Break
-            },
-        );
+                play_flip_hide(&mut st, idx);
+            }
+            glib::ControlFlow::Continue
+        });
     }
 
     let wave_count = waves.len();
@@ -1373,416 +2348,691 @@ fn schedule_win_cascade_and_continue(state: &Rc<RefCell<AppState>>, game_id: u64
         + VICTORY_FLIP_SHOW_DURATION_MS
         + post_cascade_pause_ms
         + VICTORY_CASCADE_END_BUFFER_MS;
-    let state_end = state.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(total_delay), move || {
-        let mut st = state_end.borrow_mut();
-        let is_in_game = st.view_stack.as_ref()
-            .and_then(|s| s.visible_child_name())
-            .as_deref() == Some("game");
+    let handle = cascade
+        .at(total_delay, |state| {
+            let mut st = state.borrow_mut();
+            st.victory_cascade = None;
+            if let Some(container) = &st.board_container {
+                container.remove_css_class("victory-pending");
+            }
+            if let Some(board) = &st.board_canvas {
+                board.clear_flips();
+            }
+            st.lock_input = false;
+            let gauntlet_continues = st
+                .gauntlet()
+                .is_some_and(|gauntlet| !gauntlet.is_complete());
+            let warmed_up = warmup::is_warming_up(&st);
+            drop(st);
+            if warmed_up {
+                show_warmup_splash(state);
+            } else if gauntlet_continues {
+                show_gauntlet_splash(state);
+            } else {
+                show_victory(state);
+            }
+            glib::ControlFlow::Break
+        })
+        .start();
+    state.borrow_mut().victory_cascade = Some(handle);
+}
 
-        if st.game_id != game_id || !is_in_game {
-            return glib::ControlFlow::Break;
-        }
-        if let Some(container) = &st.board_container {
-            container.remove_css_class("victory-pending");
+fn install_shortcuts_overlay(win: &gtk::ApplicationWindow, state: &Rc<RefCell<AppState>>) {
+    let shortcuts_overlay = create_keyboard_shortcuts_overlay();
+    shortcuts_overlay.set_transient_for(Some(win));
+    let overlay_pause_state = Rc::new(RefCell::new(OverlayPauseState::default()));
+    shortcuts_overlay.connect_show({
+        let state = state.clone();
+        let overlay_pause_state = overlay_pause_state.clone();
+        move |_| {
+            *overlay_pause_state.borrow_mut() = pause_game_for_overlay(&state);
         }
-        for button in &st.grid_buttons {
-            clear_flip_classes(button);
-            button.remove_css_class("victory-cascade");
-            redraw_button_child(button);
+    });
+    shortcuts_overlay.connect_hide({
+        let state = state.clone();
+        let overlay_pause_state = overlay_pause_state.clone();
+        move |_| {
+            let pause_state = *overlay_pause_state.borrow();
+            resume_game_after_overlay(&state, pause_state);
+            *overlay_pause_state.borrow_mut() = OverlayPauseState::default();
         }
-        st.lock_input = false;
-        drop(st);
-        show_victory(&state_end);
-        glib::ControlFlow::Break
     });
+    win.set_help_overlay(Some(&shortcuts_overlay));
 }
 
 pub fn run() {
     glib::set_prgname(Some("io.github.basshift.Recall"));
     let app = adw::Application::builder()
         .application_id("io.github.basshift.Recall")
+        .flags(gio::ApplicationFlags::HANDLES_OPEN)
         .build();
-    app.set_accels_for_action("win.show-help-overlay", &["<Primary>slash"]);
-    app.set_accels_for_action("app.instructions", &["F1"]);
-    app.set_accels_for_action("app.back-menu", &["<Primary>m"]);
-    app.set_accels_for_action("app.game-action", &["<Primary>r"]);
-    app.set_accels_for_action("app.preferences", &["<Primary>comma"]);
-    app.set_accels_for_action("app.quit", &["<Primary>q"]);
+    app.add_main_option(
+        "data-dir",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        &tr("Keep saves, records and settings in this directory"),
+        Some("PATH"),
+    );
+    app.add_main_option(
+        "mode",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        &tr("Start a game in this mode, with an optional level, e.g. classic:3"),
+        Some("MODE"),
+    );
+    app.add_main_option(
+        "verbose",
+        glib::Char::from(b'v'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        &tr("Print debug messages about the running game"),
+        None,
+    );
+    app.add_main_option(
+        "simulate-penalties",
+        glib::Char::from(0),
+        glib::OptionFlags::HIDDEN,
+        glib::OptionArg::String,
+        &tr("Play simulated games against every penalty profile and print the results"),
+        Some("GAMES[:RECALL]"),
+    );
+    app.connect_handle_local_options(|app, options| {
+        crate::log::init(options.contains("verbose") || debug_tools::debug_mode_enabled());
+        if let Ok(Some(spec)) = options.lookup::<String>("simulate-penalties") {
+            let Some((games, models)) = penalty_sim::parse_spec(&spec) else {
+                tracing::warn!("invalid simulation spec {spec:?}, expected GAMES or GAMES:RECALL");
+                return std::ops::ControlFlow::Break(glib::ExitCode::FAILURE);
+            };
+            let seed = glib::real_time() as u64;
+            for line in penalty_sim::report(games, &models, seed) {
+                println!("{line}");
+            }
+            return std::ops::ControlFlow::Break(glib::ExitCode::SUCCESS);
+        }
+        let data_dir = options.lookup::<std::path::PathBuf>("data-dir").ok().flatten();
+        paths::set_dir_override(data_dir);
+        if paths::dir_override().is_some() {
+            // A separate profile must not hand its window to an instance using another one.
+            app.set_flags(app.flags() | gio::ApplicationFlags::NON_UNIQUE);
+        }
+        // Activating the action after registering hands the request to the running
+        // instance when there is one, so a second launch starts the game there.
+        if let Ok(Some(request)) = options.lookup::<String>("mode") {
+            match app.register(None::<&gio::Cancellable>) {
+                Ok(()) => app.activate_action("start-mode", Some(&request.to_variant())),
+                Err(err) => tracing::warn!("failed to register application: {err}"),
+            }
+        }
+        std::ops::ControlFlow::Continue(())
+    });
+    app.connect_startup(|app| {
+        session_save::migrate_saved_run();
+        shortcuts::apply_app_accels(app);
+    });
 
-    app.connect_activate(move |app| {
-        load_css();
+    let main_state: Rc<RefCell<Option<Rc<RefCell<AppState>>>>> = Rc::new(RefCell::new(None));
+    app.connect_activate({
+        let main_state = main_state.clone();
+        move |app| {
+            ensure_main_window(app, &main_state);
+        }
+    });
+    let start_mode_action = SimpleAction::new("start-mode", Some(glib::VariantTy::STRING));
+    start_mode_action.connect_activate({
+        let app = app.clone();
+        let main_state = main_state.clone();
+        move |_, parameter| {
+            let Some(request) = parameter.and_then(|parameter| parameter.get::<String>()) else {
+                return;
+            };
+            let state = ensure_main_window(&app, &main_state);
+            let Some((mode, level)) = mode_dialogs::parse_mode_request(&request) else {
+                tracing::warn!("ignoring unknown mode request: {request}");
+                return;
+            };
+            {
+                let st = state.borrow();
+                if st.active_session_started {
+                    save_current_run_and_refresh(&st);
+                }
+            }
+            mode_dialogs::start_choice(&state, mode, level);
+        }
+    });
+    app.add_action(&start_mode_action);
+    app.connect_open({
+        let main_state = main_state.clone();
+        move |app, files, _| {
+            let state = ensure_main_window(app, &main_state);
+            let challenge = files
+                .iter()
+                .find_map(|file| Challenge::from_uri(&file.uri()));
+            match challenge {
+                Some(challenge) => open_challenge(&state, challenge),
+                None => tracing::warn!("ignoring unsupported launch target"),
+            }
+        }
+    });
 
-        let state = Rc::new(RefCell::new(AppState::new()));
+    app.run();
+}
 
-        let instructions_action = SimpleAction::new("instructions", None);
-        instructions_action.connect_activate({
-            let app = app.clone();
-            let state = state.clone();
-            move |_, _| {
-                let pause_state = pause_game_for_overlay(&state);
-                let dialog = show_instructions_dialog(&app);
-                let state_resume = state.clone();
-                dialog.connect_closed(move |_| {
-                    resume_game_after_overlay(&state_resume, pause_state);
-                });
-            }
-        });
-        app.add_action(&instructions_action);
+fn ensure_main_window(
+    app: &adw::Application,
+    main_state: &Rc<RefCell<Option<Rc<RefCell<AppState>>>>>,
+) -> Rc<RefCell<AppState>> {
+    if let Some(state) = main_state.borrow().as_ref() {
+        // The spectator window may be the active one; the game window is what gets raised.
+        if let Some(win) = &state.borrow().main_window {
+            win.present();
+        }
+        return state.clone();
+    }
+    let state = build_main_window(app);
+    *main_state.borrow_mut() = Some(state.clone());
+    state
+}
 
-        let back_menu_action = SimpleAction::new("back-menu", None);
-        back_menu_action.connect_activate({
-            let state = state.clone();
-            move |_, _| {
-                show_menu(&state);
-            }
-        });
-        app.add_action(&back_menu_action);
+fn build_main_window(app: &adw::Application) -> Rc<RefCell<AppState>> {
+    load_css();
 
-        let game_action = SimpleAction::new("game-action", None);
-        game_action.connect_activate({
-            let app = app.clone();
-            let state = state.clone();
-            move |_, _| {
-                maybe_restart_game(&state, &app);
-            }
-        });
-        app.add_action(&game_action);
+    let state = Rc::new(RefCell::new(AppState::new()));
 
-        let about_action = SimpleAction::new("about", None);
-        about_action.connect_activate({
-            let app = app.clone();
-            let state = state.clone();
-            move |_, _| {
-                let pause_state = pause_game_for_overlay(&state);
-                let dialog = show_about_dialog(&app);
-                let state_resume = state.clone();
-                dialog.connect_closed(move |_| {
-                    resume_game_after_overlay(&state_resume, pause_state);
-                });
-            }
-        });
-        app.add_action(&about_action);
+    let instructions_action = SimpleAction::new("instructions", None);
+    instructions_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            let pause_state = pause_game_for_overlay(&state);
+            let dialog = show_instructions_dialog(&app);
+            let state_resume = state.clone();
+            dialog.connect_closed(move |_| {
+                resume_game_after_overlay(&state_resume, pause_state);
+            });
+        }
+    });
+    app.add_action(&instructions_action);
 
-        let score_action = SimpleAction::new("score", None);
-        score_action.connect_activate({
-            let app = app.clone();
-            let state = state.clone();
-            move |_, _| {
-                let pause_state = pause_game_for_overlay(&state);
-                let dialog = show_memory_dialog(&state, &app);
-                let state_resume = state.clone();
-                dialog.connect_closed(move |_| {
-                    resume_game_after_overlay(&state_resume, pause_state);
-                });
+    // Pressing the shortcut again resumes; so does opening and closing any overlay.
+    let pause_action = SimpleAction::new("pause", None);
+    pause_action.connect_activate({
+        let state = state.clone();
+        let pause_state = Rc::new(Cell::new(OverlayPauseState::default()));
+        move |_, _| {
+            let held = pause_state.take();
+            if held.paused {
+                resume_game_after_overlay(&state, held);
+            } else {
+                pause_state.set(pause_game_for_overlay(&state));
             }
-        });
-        app.add_action(&score_action);
+        }
+    });
+    app.add_action(&pause_action);
 
-        let preferences_action = SimpleAction::new("preferences", None);
-        preferences_action.connect_activate({
-            let app = app.clone();
-            let state = state.clone();
-            move |_, _| {
-                let pause_state = pause_game_for_overlay(&state);
-                let dialog = show_preferences_dialog(&state, &app);
-                let state_resume = state.clone();
-                dialog.connect_closed(move |_| {
-                    resume_game_after_overlay(&state_resume, pause_state);
-                });
-            }
-        });
-        app.add_action(&preferences_action);
+    let back_menu_action = SimpleAction::new("back-menu", None);
+    back_menu_action.connect_activate({
+        let state = state.clone();
+        move |_, _| {
+            leave_game(&state);
+        }
+    });
+    app.add_action(&back_menu_action);
 
-        let quit_action = SimpleAction::new("quit", None);
-        quit_action.connect_activate({
-            let app = app.clone();
-            move |_, _| app.quit()
-        });
-        app.add_action(&quit_action);
+    let game_action = SimpleAction::new("game-action", None);
+    game_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            maybe_restart_game(&state, &app);
+        }
+    });
+    app.add_action(&game_action);
 
-        let dynamic_css_provider = gtk::CssProvider::new();
-        if let Some(display) = gtk::gdk::Display::default() {
-            gtk::style_context_add_provider_for_display(
-                &display,
-                &dynamic_css_provider,
-                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-            );
+    let about_action = SimpleAction::new("about", None);
+    about_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            let pause_state = pause_game_for_overlay(&state);
+            let dialog = show_about_dialog(&app);
+            let state_resume = state.clone();
+            dialog.connect_closed(move |_| {
+                resume_game_after_overlay(&state_resume, pause_state);
+            });
         }
+    });
+    app.add_action(&about_action);
 
-        let title_menu = gtk::Label::new(None);
-        title_menu.set_markup("<b>Recall</b>");
-        title_menu.set_halign(gtk::Align::Center);
+    let score_action = SimpleAction::new("score", None);
+    score_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            let pause_state = pause_game_for_overlay(&state);
+            let dialog = show_memory_dialog(&state, &app);
+            let state_resume = state.clone();
+            dialog.connect_closed(move |_| {
+                resume_game_after_overlay(&state_resume, pause_state);
+            });
+        }
+    });
+    app.add_action(&score_action);
 
-        let title_game_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
-        title_game_box.set_valign(gtk::Align::Center);
-        title_game_box.set_halign(gtk::Align::Center);
-        title_game_box.set_hexpand(true);
+    let preferences_action = SimpleAction::new("preferences", None);
+    preferences_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            let pause_state = pause_game_for_overlay(&state);
+            let dialog = show_preferences_dialog(&state, &app);
+            let state_resume = state.clone();
+            dialog.connect_closed(move |_| {
+                resume_game_after_overlay(&state_resume, pause_state);
+            });
+        }
+    });
+    app.add_action(&preferences_action);
 
-        let title_game_main = gtk::Label::builder()
-            .label("Recall")
-            .halign(gtk::Align::Center)
-            .css_classes(vec!["game-title-main"])
-            .build();
+    let spectate_action = SimpleAction::new("spectate", None);
+    spectate_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| spectator::open_spectator(&state, &app)
+    });
+    app.add_action(&spectate_action);
 
-        let title_game_subtitle = gtk::Label::builder()
-            .label("")
-            .halign(gtk::Align::Center)
-            .css_classes(vec!["game-title-subtitle", "caption"])
-            .build();
+    let quit_action = SimpleAction::new("quit", None);
+    quit_action.connect_activate({
+        let app = app.clone();
+        let state = state.clone();
+        move |_, _| {
+            // Quitting skips the window's close request, so save the same way it would.
+            let st = state.borrow();
+            if st.active_session_started {
+                save_play_stats(&st);
+                save_current_run_and_refresh(&st);
+            }
+            drop(st);
+            app.quit()
+        }
+    });
+    app.add_action(&quit_action);
+
+    let dynamic_css_provider = gtk::CssProvider::new();
+    if let Some(display) = gtk::gdk::Display::default() {
+        gtk::style_context_add_provider_for_display(
+            &display,
+            &dynamic_css_provider,
+            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+    }
 
-        title_game_box.append(&title_game_main);
-        title_game_box.append(&title_game_subtitle);
-
-            let title_victory_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
-            title_victory_box.set_valign(gtk::Align::Center);
-            title_victory_box.set_halign(gtk::Align::Center);
-        
-            let title_victory_main = gtk::Label::new(Some("Recall"));
-            title_victory_main.add_css_class("game-title-main");
-        
-            let title_victory_sub = gtk::Label::new(Some(&tr("Victory")));
-            title_victory_sub.add_css_class("game-title-subtitle");
-            title_victory_sub.add_css_class("caption");
-        
-            title_victory_box.append(&title_victory_main);
-            title_victory_box.append(&title_victory_sub);
-        let header = adw::HeaderBar::builder()
-            .title_widget(&title_menu)
-            .build();
-        header.add_css_class("app-header");
-        header.add_css_class("flat");
+    let title_menu = gtk::Label::new(None);
+    title_menu.set_markup("<b>Recall</b>");
+    title_menu.set_halign(gtk::Align::Center);
+
+    let game_title = GameTitle::new();
+
+        let title_victory_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        title_victory_box.set_valign(gtk::Align::Center);
+        title_victory_box.set_halign(gtk::Align::Center);
+    
+        let title_victory_main = gtk::Label::new(Some("Recall"));
+        title_victory_main.add_css_class("game-title-main");
+    
+        let title_victory_sub = gtk::Label::new(Some(&tr("Victory")));
+        title_victory_sub.add_css_class("game-title-subtitle");
+        title_victory_sub.add_css_class("caption");
+    
+        title_victory_box.append(&title_victory_main);
+        title_victory_box.append(&title_victory_sub);
+    let header = adw::HeaderBar::builder()
+        .title_widget(&title_menu)
+        .build();
+    header.add_css_class("app-header");
+    header.add_css_class("flat");
 
-        let back_button = gtk::Button::builder()
-            .icon_name("go-home-symbolic")
-            .build();
-        back_button.set_tooltip_text(Some(&tr("Home")));
-        back_button.connect_clicked({
-            let state = state.clone();
-            move |_| {
-                show_menu(&state);
-            }
-        });
-        header.pack_start(&back_button);
+    let back_button = gtk::Button::builder()
+        .icon_name("go-home-symbolic")
+        .build();
+    back_button.set_tooltip_text(Some(&tr("Home")));
+    back_button.connect_clicked({
+        let state = state.clone();
+        move |_| {
+            leave_game(&state);
+        }
+    });
+    header.pack_start(&back_button);
+
+    let header_timer_label = gtk::Label::builder()
+        .label("00:00")
+        .halign(gtk::Align::Start)
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["game-header-timer", "dim-label"])
+        .build();
+    header_timer_label.set_visible(false);
+    header.pack_start(&header_timer_label);
+
+    let menu_button = gtk::MenuButton::builder()
+        .icon_name("open-menu-symbolic")
+        .build();
+    let restart_button = gtk::Button::builder().has_frame(false).build();
+    restart_button.add_css_class("flat");
+    restart_button.connect_clicked({
+        let app = app.clone();
+        let state = state.clone();
+        move |_| {
+            trigger_contextual_game_action(&state, &app);
+        }
+    });
+    header.pack_end(&menu_button);
+    header.pack_end(&restart_button);
+
+    let view_stack = gtk::Stack::new();
+    view_stack.set_hexpand(true);
+    view_stack.set_vexpand(true);
+    view_stack.set_hhomogeneous(false);
+    view_stack.set_vhomogeneous(false);
+    view_stack.set_interpolate_size(false);
+    view_stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
+    view_stack.set_transition_duration(300);
+
+    {
+        let mut st = state.borrow_mut();
+        st.dynamic_css_provider = Some(dynamic_css_provider.clone());
+        st.narrow_breakpoint = Some(board::narrow_breakpoint());
+    }
+
+    let game_view = build_game_view(&state);
+    view_stack.add_named(&game_view, Some("game"));
+
+    let victory_view = build_victory_view(&state);
+    view_stack.add_named(&victory_view, Some("victory"));
 
-        let header_timer_label = gtk::Label::builder()
-            .label("00:00")
-            .halign(gtk::Align::Start)
-            .valign(gtk::Align::Center)
-            .css_classes(vec!["game-header-timer", "dim-label"])
-            .build();
-        header_timer_label.set_visible(false);
-        header.pack_start(&header_timer_label);
+    let defeat_view = build_defeat_view(&state);
+    view_stack.add_named(&defeat_view, Some("defeat"));
 
-        let menu_button = gtk::MenuButton::builder()
-            .icon_name("open-menu-symbolic")
-            .build();
-        let restart_button = gtk::Button::builder().has_frame(false).build();
-        restart_button.add_css_class("flat");
-        restart_button.connect_clicked({
-            let app = app.clone();
-            let state = state.clone();
-            move |_| {
-                trigger_contextual_game_action(&state, &app);
-            }
-        });
-        header.pack_end(&menu_button);
-        header.pack_end(&restart_button);
-
-        let view_stack = gtk::Stack::new();
-        view_stack.set_hexpand(true);
-        view_stack.set_vexpand(true);
-        view_stack.set_hhomogeneous(false);
-        view_stack.set_vhomogeneous(false);
-        view_stack.set_interpolate_size(false);
-        view_stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
-        view_stack.set_transition_duration(300);
+    let splash_view = build_splash_view(&state);
+    view_stack.add_named(&splash_view, Some("splash"));
 
-        {
-            let mut st = state.borrow_mut();
-            st.dynamic_css_provider = Some(dynamic_css_provider.clone());
-        }
+    let menu_view = build_menu_view(&state, app);
+    view_stack.add_named(&menu_view, Some("menu"));
 
-        let game_view = build_game_view(&state);
-        view_stack.add_named(&game_view, Some("game"));
+    view_stack.set_visible_child_name("menu");
+    let toast_overlay = adw::ToastOverlay::new();
+    if debug_tools::debug_mode_enabled() {
+        let debug_overlay = gtk::Overlay::new();
+        debug_overlay.set_child(Some(&view_stack));
+        let perf_hud = perf_hud::build_perf_hud();
+        debug_overlay.add_overlay(&perf_hud);
+        toast_overlay.set_child(Some(&debug_overlay));
+        state.borrow_mut().perf_hud = Some(perf_hud);
+    } else {
+        toast_overlay.set_child(Some(&view_stack));
+    }
+    state.borrow_mut().toast_overlay = Some(toast_overlay.clone());
+    toasts::subscribe_to_game_events(&state);
+    hud::subscribe_to_game_events(&state);
+    audio::subscribe_to_game_events(&state);
+    speech::subscribe_to_game_events(&state);
+    music::subscribe_to_game_events(&state, &view_stack);
+    board::subscribe_to_game_events(&state);
+    symbol_stats::subscribe_to_game_events(&state);
+    heatmap::subscribe_to_game_events(&state);
+    save_run_on_game_events(&state);
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.set_hexpand(true);
+    toolbar.set_vexpand(true);
+    toolbar.add_top_bar(&header);
+    let preview_bar = gtk::ProgressBar::new();
+    preview_bar.add_css_class("preview-bar");
+    preview_bar.set_visible(false);
+    toolbar.add_top_bar(&preview_bar);
+    state.borrow_mut().preview_bar = Some(preview_bar);
+    toolbar.set_content(Some(&toast_overlay));
+
+    let win = adw::ApplicationWindow::builder()
+        .application(app)
+        .title("Recall")
+        .icon_name("io.github.basshift.Recall")
+        .default_width(860)
+        .default_height(680)
+        .content(&toolbar)
+        .build();
+    install_shortcuts_overlay(win.upcast_ref(), &state);
+    win.set_size_request(360, 560);
+    if let Some(narrow) = state.borrow().narrow_breakpoint.clone() {
+        win.add_breakpoint(narrow);
+    }
+    win.add_breakpoint(stats_panel::wide_breakpoint(&state));
+    win.add_css_class("app-window");
+    state.borrow_mut().main_window = Some(win.clone());
+    sync_window_maximized_class(&win);
+    win.connect_notify_local(Some("maximized"), {
+        let win = win.clone();
+        move |_, _| sync_window_maximized_class(&win)
+    });
 
-        let victory_view = build_victory_view(&state);
-        view_stack.add_named(&victory_view, Some("victory"));
+    ThemeChoice::from_code(&settings::string(theme::COLOR_SCHEME_KEY, "system")).apply();
+    let style_manager = adw::StyleManager::default();
+    if style_manager.is_dark() {
+        win.add_css_class("theme-dark");
+    } else {
+        win.add_css_class("theme-light");
+    }
+    style_manager.connect_notify_local(Some("dark"), {
+        let win = win.clone();
+        move |manager, _| {
+            if manager.is_dark() {
+                win.remove_css_class("theme-light");
+                win.add_css_class("theme-dark");
+            } else {
+                win.remove_css_class("theme-dark");
+                win.add_css_class("theme-light");
+            }
+        }
+    });
 
-        let menu_view = build_menu_view(&state, app);
-        view_stack.add_named(&menu_view, Some("menu"));
+    {
+        let mut st = state.borrow_mut();
+        st.view_stack = Some(view_stack.clone());
+        st.header = Some(header.clone());
+        st.back_button = Some(back_button);
+        st.menu_button = Some(menu_button);
+        st.restart_button = Some(restart_button);
+        st.title_menu = Some(title_menu);
+        st.game_title = Some(game_title);
+        st.header_timer_label = Some(header_timer_label);
+        st.title_victory = Some(title_victory_box.upcast::<gtk::Widget>());
+        st.dynamic_css_provider = Some(dynamic_css_provider);
+        st.records = load_records();
+        st.symbol_stats = symbol_stats::load_symbol_stats();
+        st.mistake_heatmap = heatmap::load_heatmap();
+        st.show_coordinates = settings::boolean(coordinates::SHOW_COORDINATES_KEY, false);
+        st.fail_condition =
+            FailCondition::from_code(&settings::string(defeat::FAIL_CONDITION_KEY, "off"));
+        st.preferred_pacing =
+            infinite::Pacing::from_code(&settings::string(infinite::PACING_KEY, "standard"));
+        st.break_reminder =
+            BreakReminder::from_code(&settings::string(breaks::BREAK_REMINDER_KEY, "off"));
+        st.font_choice = FontChoice::from_code(&settings::string(fonts::FONT_FAMILY_KEY, "default"));
+        st.text_scale = TextScale::from_code(&settings::string(fonts::TEXT_SCALE_KEY, "100"));
+        fonts::apply_dynamic_css(&st);
+        st.card_faces = CardFaces::from_code(&settings::string(faces::CARD_FACES_KEY, "emoji"));
+        st.rank_art = ArtPack::from_code(&settings::string(rank_art::RANK_ART_KEY, "classic"));
+        st.board_transition =
+            WavePattern::from_code(&settings::string(transitions::BOARD_TRANSITION_KEY, "corners"));
+        st.reshuffle_strategy = ReshuffleStrategy::from_code(&settings::string(
+            layout::RESHUFFLE_STRATEGY_KEY,
+            "random",
+        ));
+        st.cruel_symbols = settings::boolean(similarity::CRUEL_SYMBOLS_KEY, false);
+        st.scaled_similarity = settings::boolean(similarity::SCALED_SIMILARITY_KEY, true);
+        st.audio_cues = AudioCues {
+            countdown: settings::boolean(hud::PREVIEW_TICK_KEY, false),
+            matches: settings::boolean(audio::CUE_MATCH_KEY, false),
+            mismatches: settings::boolean(audio::CUE_MISMATCH_KEY, false),
+            audio_only: settings::boolean(audio::AUDIO_ONLY_KEY, false),
+        };
+        st.spoken_announcements = settings::boolean(speech::SPOKEN_ANNOUNCEMENTS_KEY, false);
+        st.dynamic_music = settings::boolean(music::DYNAMIC_MUSIC_KEY, false);
+        st.expert_warmup = settings::boolean(warmup::WARMUP_KEY, false);
+        st.show_match_progress = settings::boolean(hud::MATCH_PROGRESS_KEY, true);
+        st.progress_in_title = settings::boolean(hud::PROGRESS_IN_TITLE_KEY, false);
+        st.left_handed = settings::boolean(hud::LEFT_HANDED_KEY, false);
+        st.peek_assist = settings::boolean(peek::PEEK_ASSIST_KEY, false);
+        st.first_mismatch_grace =
+            settings::boolean(classic_penalties::FIRST_MISMATCH_GRACE_KEY, false);
+        st.tile_tags = settings::boolean(tags::TILE_TAGS_KEY, false);
+        st.hint_assist = settings::boolean(hint::HINT_ASSIST_KEY, false);
+        st.set_trio_level(settings::integer(mode_dialogs::TRIO_LEVEL_KEY, 1).clamp(1, 4) as u8);
+        hud::apply_handedness(&st);
+        st.collect_habits = settings::boolean(habits::COLLECT_HABITS_KEY, false);
+        st.kids_daily_limit =
+            DailyLimit::from_code(&settings::string(play_limit::KIDS_DAILY_LIMIT_KEY, "off"));
+        st.kids_play = play_limit::load_play_time();
+        st.stream_overlay = settings::boolean(overlay::STREAM_OVERLAY_KEY, false);
+        habits::note_session(&st);
+        refresh_continue_button_state(&st);
+    }
 
-        view_stack.set_visible_child_name("menu");
-        let toolbar = adw::ToolbarView::new();
-        toolbar.set_hexpand(true);
-        toolbar.set_vexpand(true);
-        toolbar.add_top_bar(&header);
-        toolbar.set_content(Some(&view_stack));
+    let last_window_size = Rc::new(Cell::new((0, 0)));
+    let state_layout = state.clone();
+    let last_window_size_tick = last_window_size.clone();
+    win.add_tick_callback(move |window, _| {
+        let size = (window.width(), window.height());
+        if size.0 > 0 && size.1 > 0 && size != last_window_size_tick.get() {
+            last_window_size_tick.set(size);
+            sync_window_layout_classes(window, &state_layout);
+        }
+        glib::ControlFlow::Continue
+    });
 
-        let win = adw::ApplicationWindow::builder()
-            .application(app)
-            .title("Recall")
-            .icon_name("io.github.basshift.Recall")
-            .default_width(860)
-            .default_height(680)
-            .content(&toolbar)
-            .build();
-        let shortcuts_overlay = create_keyboard_shortcuts_overlay();
-        shortcuts_overlay.set_transient_for(Some(&win));
-        let overlay_pause_state = Rc::new(RefCell::new(OverlayPauseState::default()));
-        shortcuts_overlay.connect_show({
-            let state = state.clone();
-            let overlay_pause_state = overlay_pause_state.clone();
-            move |_| {
-                *overlay_pause_state.borrow_mut() = pause_game_for_overlay(&state);
+    let global_key = gtk::EventControllerKey::new();
+    global_key.set_propagation_phase(gtk::PropagationPhase::Capture);
+    global_key.connect_key_pressed({
+        let state = state.clone();
+        move |_, key, _, mods| {
+            if debug_tools::handle_debug_shortcut(&state, key, mods) {
+                return gtk::glib::Propagation::Stop;
             }
-        });
-        shortcuts_overlay.connect_hide({
-            let state = state.clone();
-            let overlay_pause_state = overlay_pause_state.clone();
-            move |_| {
-                let pause_state = *overlay_pause_state.borrow();
-                resume_game_after_overlay(&state, pause_state);
-                *overlay_pause_state.borrow_mut() = OverlayPauseState::default();
+            if peek::is_peek_key(key) {
+                peek::set_peeking(&mut state.borrow_mut(), true);
             }
-        });
-        win.set_help_overlay(Some(&shortcuts_overlay));
-        win.set_size_request(360, 560);
-        win.add_css_class("app-window");
-        sync_window_maximized_class(&win);
-        win.connect_notify_local(Some("maximized"), {
-            let win = win.clone();
-            move |_, _| sync_window_maximized_class(&win)
-        });
-
-        let style_manager = adw::StyleManager::default();
-        if style_manager.is_dark() {
-            win.add_css_class("theme-dark");
-        } else {
-            win.add_css_class("theme-light");
-        }
-        style_manager.connect_notify_local(Some("dark"), {
-            let win = win.clone();
-            move |manager, _| {
-                if manager.is_dark() {
-                    win.remove_css_class("theme-light");
-                    win.add_css_class("theme-dark");
-                } else {
-                    win.remove_css_class("theme-dark");
-                    win.add_css_class("theme-light");
-                }
+            let flip_shortcut = shortcuts::find("flip").map(shortcuts::accelerator);
+            if flip_shortcut.is_some_and(|accel| shortcuts::key_matches(&accel, key, mods))
+                && activate_focused_tile(&state)
+            {
+                return gtk::glib::Propagation::Stop;
             }
-        });
-
-        {
-            let mut st = state.borrow_mut();
-            st.view_stack = Some(view_stack.clone());
-            st.header = Some(header.clone());
-            st.back_button = Some(back_button);
-            st.menu_button = Some(menu_button);
-            st.restart_button = Some(restart_button);
-            st.title_menu = Some(title_menu);
-            st.title_game = Some(title_game_box.upcast::<gtk::Widget>());
-            st.title_game_subtitle = Some(title_game_subtitle);
-            st.header_timer_label = Some(header_timer_label);
-            st.title_victory = Some(title_victory_box.upcast::<gtk::Widget>());
-            st.dynamic_css_provider = Some(dynamic_css_provider);
-            st.records = load_records();
-            refresh_continue_button_state(&st);
-        }
-
-        let last_window_size = Rc::new(Cell::new((0, 0)));
-        let state_layout = state.clone();
-        let last_window_size_tick = last_window_size.clone();
-        win.add_tick_callback(move |window, _| {
-            let size = (window.allocated_width(), window.allocated_height());
-            if size.0 > 0 && size.1 > 0 && size != last_window_size_tick.get() {
-                last_window_size_tick.set(size);
-                sync_window_layout_classes(window, &state_layout);
+            let hint_shortcut = shortcuts::find("hint").map(shortcuts::accelerator);
+            if hint_shortcut.is_some_and(|accel| shortcuts::key_matches(&accel, key, mods))
+                && is_game_view_active(&state.borrow())
+                && hint::show(&state)
+            {
+                return gtk::glib::Propagation::Stop;
             }
-            glib::ControlFlow::Continue
-        });
-
-        let global_key = gtk::EventControllerKey::new();
-        global_key.set_propagation_phase(gtk::PropagationPhase::Capture);
-        global_key.connect_key_pressed({
-            let state = state.clone();
-            move |_, key, _, mods| {
-                if debug_tools::handle_debug_shortcut(&state, key, mods) {
-                    return gtk::glib::Propagation::Stop;
-                }
-                let has_primary_modifier = mods.intersects(
-                    gdk::ModifierType::CONTROL_MASK
-                        | gdk::ModifierType::ALT_MASK
-                        | gdk::ModifierType::SUPER_MASK,
-                );
-                if !has_primary_modifier {
-                    let handled = match key {
-                        gdk::Key::Up | gdk::Key::KP_Up => {
-                            suppress_board_hover_for_keyboard(&state);
-                            move_board_focus(&state, 0, -1)
-                        }
-                        gdk::Key::Down | gdk::Key::KP_Down => {
-                            suppress_board_hover_for_keyboard(&state);
-                            move_board_focus(&state, 0, 1)
-                        }
-                        gdk::Key::Left | gdk::Key::KP_Left => {
-                            suppress_board_hover_for_keyboard(&state);
-                            move_board_focus(&state, -1, 0)
-                        }
-                        gdk::Key::Right | gdk::Key::KP_Right => {
-                            suppress_board_hover_for_keyboard(&state);
-                            move_board_focus(&state, 1, 0)
-                        }
-                        gdk::Key::space | gdk::Key::Return | gdk::Key::KP_Enter => {
-                            activate_focused_tile(&state)
-                        }
-                        _ => false,
-                    };
-                    if handled {
-                        return gtk::glib::Propagation::Stop;
+            let tag_shortcut = shortcuts::find("tag").map(shortcuts::accelerator);
+            if tag_shortcut.is_some_and(|accel| shortcuts::key_matches(&accel, key, mods))
+                && tag_focused_tile(&state)
+            {
+                return gtk::glib::Propagation::Stop;
+            }
+            let has_primary_modifier = mods.intersects(
+                gdk::ModifierType::CONTROL_MASK
+                    | gdk::ModifierType::ALT_MASK
+                    | gdk::ModifierType::SUPER_MASK,
+            );
+            if !has_primary_modifier
+                && !peek::is_peek_key(key)
+                && is_game_view_active(&state.borrow())
+                && skip_animation(&state)
+            {
+                return gtk::glib::Propagation::Stop;
+            }
+            if !has_primary_modifier {
+                let handled = match key {
+                    gdk::Key::Up | gdk::Key::KP_Up => {
+                        suppress_board_hover_for_keyboard(&state);
+                        move_board_focus(&state, 0, -1)
                     }
-                }
-                if key == gdk::Key::Escape {
-                    let st = state.borrow();
-                    let in_game = is_game_view_active(&st);
-                    // Allow escape if input is unlocked OR if we are just in the preview phase (so user can quit early)
-                    if in_game && (!st.lock_input || st.preview_active) {
-                        drop(st);
-                        show_menu(&state);
-                        return gtk::glib::Propagation::Stop;
+                    gdk::Key::Down | gdk::Key::KP_Down => {
+                        suppress_board_hover_for_keyboard(&state);
+                        move_board_focus(&state, 0, 1)
+                    }
+                    gdk::Key::Left | gdk::Key::KP_Left => {
+                        suppress_board_hover_for_keyboard(&state);
+                        move_board_focus(&state, -1, 0)
                     }
+                    gdk::Key::Right | gdk::Key::KP_Right => {
+                        suppress_board_hover_for_keyboard(&state);
+                        move_board_focus(&state, 1, 0)
+                    }
+                    gdk::Key::Return | gdk::Key::KP_Enter => activate_focused_tile(&state),
+                    _ => {
+                        nback_flow::handle_nback_key(&state, key)
+                            || handle_coordinate_key(&state, key)
+                    }
+                };
+                if handled {
+                    return gtk::glib::Propagation::Stop;
                 }
-                gtk::glib::Propagation::Proceed
             }
-        });
-        win.add_controller(global_key);
-
-        win.connect_close_request({
-            let state = state.clone();
-            move |_| {
+            if key == gdk::Key::Escape {
                 let st = state.borrow();
-                if st.active_session_started {
-                    save_current_run_and_refresh(&st);
+                let in_game = is_game_view_active(&st);
+                // Allow escape if input is unlocked OR if we are just in the preview phase (so user can quit early)
+                if in_game && (!st.lock_input || st.preview_active) {
+                    drop(st);
+                    leave_game(&state);
+                    return gtk::glib::Propagation::Stop;
                 }
-                gtk::glib::Propagation::Proceed
             }
-        });
+            gtk::glib::Propagation::Proceed
+        }
+    });
+    global_key.connect_key_released({
+        let state = state.clone();
+        move |_, key, _, _| {
+            if peek::is_peek_key(key) {
+                peek::set_peeking(&mut state.borrow_mut(), false);
+            }
+        }
+    });
+    win.add_controller(global_key);
+    // A release is never delivered once the window loses focus.
+    win.connect_is_active_notify({
+        let state = state.clone();
+        move |window| {
+            if !window.is_active() {
+                peek::set_peeking(&mut state.borrow_mut(), false);
+            }
+        }
+    });
 
-        set_header_menu(&state);
-        win.present();
+    win.connect_close_request({
+        let state = state.clone();
+        move |_| {
+            let st = state.borrow();
+            if st.active_session_started {
+                save_play_stats(&st);
+                save_current_run_and_refresh(&st);
+            }
+            gtk::glib::Propagation::Proceed
+        }
     });
 
-    app.run();
+    set_header_menu(&state);
+    win.present();
+    state
 }
 
 fn load_css() {
-    static RESOURCES_INIT: Once = Once::new();
     static CSS_PROVIDERS_INIT: Once = Once::new();
-    RESOURCES_INIT.call_once(|| {
-        gio::resources_register_include!("recall.gresource")
-            .expect("failed to register embedded resources");
-    });
+    resources::register();
 
     let Some(display) = gtk::gdk::Display::default() else {
         return;
@@ -1790,24 +3040,19 @@ fn load_css() {
 
     CSS_PROVIDERS_INIT.call_once(|| {
         let icon_theme = gtk::IconTheme::for_display(&display);
-        icon_theme.add_resource_path("/io/github/basshift/Recall/icons/hicolor");
-        icon_theme.add_resource_path("/io/github/basshift/Recall/icons");
-
-        for resource_path in [
-            "/io/github/basshift/Recall/style.vars.css",
-            "/io/github/basshift/Recall/style.css",
-            "/io/github/basshift/Recall/style.light.css",
-            "/io/github/basshift/Recall/style.dark.css",
-            "/io/github/basshift/Recall/style.mobile.css",
-        ] {
+        icon_theme.add_resource_path(resources::ICONS_HICOLOR);
+        icon_theme.add_resource_path(resources::ICONS);
+
+        for resource_path in resources::STYLESHEETS {
             let provider = gtk::CssProvider::new();
-            provider.load_from_resource(resource_path);
+            resources::load_stylesheet(&provider, resource_path);
             gtk::style_context_add_provider_for_display(
                 &display,
                 &provider,
                 gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
             );
         }
+        user_css::install(&display);
     });
 }
 
@@ -1874,11 +3119,17 @@ fn build_menu_view(state: &Rc<RefCell<AppState>>, app: &adw::Application) -> gtk
     buttons_box.append(&continue_button);
     buttons_box.append(&new_button);
     content.append(&buttons_box);
+    let recap_card = session_recap::build_recap_card(state);
+    content.append(&recap_card);
 
     center.set_center_widget(Some(&content));
     root.append(&center);
 
-    state.borrow_mut().continue_button = Some(continue_button);
+    {
+        let mut st = state.borrow_mut();
+        st.continue_button = Some(continue_button);
+        st.session_recap_card = Some(recap_card);
+    }
 
     root
 }
@@ -1899,7 +3150,16 @@ fn build_game_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     content.set_margin_start(CONTENT_MARGIN);
     content.set_margin_end(CONTENT_MARGIN);
 
-    let board_grid = build_board_grid(state);
+    let board_canvas = build_board_canvas(state);
+    let training_bar = training::build_progress_bar();
+    content.append(&training_bar);
+    let (progress_row, progress_label, progress_bar) = hud::build_match_progress();
+    let penalty_pips = hud::build_penalty_pips();
+    let hud_row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    hud_row.set_halign(gtk::Align::Center);
+    hud_row.append(&progress_row);
+    hud_row.append(&penalty_pips);
+    content.append(&hud_row);
 
     let board_frame = gtk::AspectFrame::new(0.5, 0.5, 1.0, false);
     board_frame.set_halign(gtk::Align::Fill);
@@ -1933,18 +3193,6 @@ fn build_game_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     });
     board_card.add_controller(board_motion);
 
-    board_card.connect_closure(
-        "notify::width",
-        false,
-        glib::closure_local!(move |card: gtk::Box, _: glib::ParamSpec| {
-            if card.width() < 500 {
-                card.add_css_class("compact");
-            } else {
-                card.remove_css_class("compact");
-            }
-        }),
-    );
-
     let (grid_cols, grid_rows) = {
         let st = state.borrow();
         (st.grid_cols as f32, st.grid_rows as f32)
@@ -1955,135 +3203,107 @@ fn build_game_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     grid_frame.set_valign(gtk::Align::Fill);
     grid_frame.set_hexpand(true);
     grid_frame.set_vexpand(true);
-    grid_frame.set_child(Some(&board_grid));
+    grid_frame.set_child(Some(&board_canvas));
     board_card.append(&grid_frame);
 
-    board_frame.set_child(Some(&board_card));
-    content.append(&board_frame);
+    let board_overlay = gtk::Overlay::new();
+    board_overlay.set_child(Some(&board_card));
+    board_overlay.add_overlay(&infinite_flow::build_round_banner(state));
+    let board_bin = board::build_board_bin(&board_card);
+    let (min_width, min_height) = {
+        let st = state.borrow();
+        board::board_min_size(st.grid_cols, st.grid_rows, st.show_coordinates)
+    };
+    board_bin.set_size_request(min_width, min_height);
+    board_bin.set_child(Some(&board_overlay));
+    board_frame.set_child(Some(&board_bin));
+    let board_clamp = adw::Clamp::builder()
+        .maximum_size(board::board_max_size(grid_cols as i32, grid_rows as i32))
+        .tightening_threshold(0)
+        .hexpand(true)
+        .vexpand(true)
+        .child(&board_frame)
+        .build();
+    let (splits_panel, splits_list) = speedrun::build_splits_panel();
+    let play_row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    play_row.set_hexpand(true);
+    play_row.set_vexpand(true);
+    play_row.append(&board_clamp);
+    play_row.append(&splits_panel);
+    let stats = stats_panel::build_stats_panel();
+    play_row.append(&stats.root);
+    content.append(&play_row);
+    let nback_controls = nback_flow::build_nback_controls(state);
+    content.append(&nback_controls);
     root.append(&content);
+    if let Some(narrow) = &state.borrow().narrow_breakpoint {
+        board::add_margin_setters(narrow, &content, board::NARROW_CONTENT_MARGIN);
+        narrow.add_setters(&[(&content, "spacing", 6), (&progress_row, "spacing", 4)]);
+        narrow.add_setter(&progress_bar, "width-request", Some(&80.to_value()));
+    }
 
     {
         let mut st = state.borrow_mut();
         st.board_container = Some(board_card.clone());
         st.board_shell = Some(board_frame.clone());
+        st.board_clamp = Some(board_clamp);
+        st.board_bin = Some(board_bin);
+        st.splits_panel = Some(splits_panel);
+        st.splits_list = Some(splits_list);
+        st.stats_panel = Some(stats);
+        st.nback_controls = Some(nback_controls);
+        st.match_progress = Some((progress_row, progress_label, progress_bar));
+        st.penalty_pips = Some(penalty_pips);
+        st.training_bar = Some(training_bar);
     }
 
     root
 }
 
-fn spawn_victory_confetti_piece(layer: &gtk::Fixed, x: f64, y: f64) {
-    let color_idx = glib::random_int_range(0, 6);
-    let shape_symbol = match glib::random_int_range(0, 3) {
-        0 => "■",
-        1 => "◆",
-        _ => "●",
-    };
-    let shape_class = match glib::random_int_range(0, 3) {
-        0 => "shape-square",
-        1 => "shape-diamond",
-        _ => "shape-circle",
-    };
-    let drift_class = if glib::random_int_range(0, 2) == 0 {
-        "drift-left"
-    } else {
-        "drift-right"
-    };
-    let speed_class = match glib::random_int_range(0, 3) {
-        0 => "speed-a",
-        1 => "speed-b",
-        _ => "speed-c",
-    };
-    let particle = gtk::Label::builder()
-        .label(shape_symbol)
-        .css_classes(vec![
-            "victory-confetti-particle",
-            &format!("color-{}", color_idx),
-            shape_class,
-            drift_class,
-            speed_class,
-        ])
-        .build();
-
-    particle.set_can_target(false);
-    layer.put(&particle, x, y);
-
-    glib::timeout_add_local_once(std::time::Duration::from_millis(1800), {
-        let layer_weak = layer.downgrade();
-        let particle_weak = particle.downgrade();
-        move || {
-            if let (Some(layer), Some(particle)) = (layer_weak.upgrade(), particle_weak.upgrade()) {
-                layer.remove(&particle);
-            }
-        }
-    });
-}
-
-fn random_confetti_spawn_x(layer: &gtk::Fixed) -> f64 {
-    let layer_width = layer.width().max(280) as f64;
-    let side_padding = 8.0;
-    let min_x = side_padding;
-    let max_x = (layer_width - side_padding - 12.0).max(min_x + 1.0);
-    glib::random_double_range(min_x, max_x)
-}
-
 pub(super) fn stop_victory_sparks(st: &mut AppState) {
-    if let Some(handle) = st.spark_timer_handle.take() {
-        handle.remove();
+    if let Some(tick) = st.spark_tick.take() {
+        tick.remove();
     }
     if let Some(layer) = &st.victory_spark_layer {
-        while let Some(child) = layer.first_child() {
-            layer.remove(&child);
-        }
+        particles::clear(layer);
     }
 }
 
-pub(super) fn start_victory_sparks(state: &Rc<RefCell<AppState>>) {
+pub(super) fn start_victory_sparks(state: &Rc<RefCell<AppState>>, new_record: bool) {
     let mut st = state.borrow_mut();
     stop_victory_sparks(&mut st);
 
-    let layer = st.victory_spark_layer.clone();
+    let Some(layer) = st.victory_spark_layer.clone() else {
+        return;
+    };
+    let emission = if new_record {
+        Some(particles::record_emission())
+    } else {
+        particles::emission_for(st.victory_rank, st.kids.is_some())
+    };
+    let Some(emission) = emission else {
+        return;
+    };
     let state_weak = Rc::downgrade(state);
-    let mut elapsed_ms = 0u32;
-    let handle = glib::timeout_add_local(std::time::Duration::from_millis(85), move || {
+    let tick = particles::start(&layer, emission, move || {
         let Some(state) = state_weak.upgrade() else {
-            return glib::ControlFlow::Break;
+            return false;
         };
-
-        let in_victory_view = {
-            let st = state.borrow();
-            st.view_stack
-                .as_ref()
-                .and_then(|stack| stack.visible_child_name())
-                .as_deref()
-                == Some("victory")
+        let Ok(mut st) = state.try_borrow_mut() else {
+            return true;
         };
+        let in_victory_view = st
+            .view_stack
+            .as_ref()
+            .and_then(|stack| stack.visible_child_name())
+            .as_deref()
+            == Some("victory");
         if !in_victory_view {
-            state.borrow_mut().spark_timer_handle = None;
-            return glib::ControlFlow::Break;
-        }
-
-        elapsed_ms = elapsed_ms.saturating_add(85);
-        if elapsed_ms >= 3500 {
-            state.borrow_mut().spark_timer_handle = None;
-            return glib::ControlFlow::Break;
-        }
-
-        if let Some(layer) = &layer {
-            let spawn_count = glib::random_int_range(1, 4);
-            for _ in 0..spawn_count {
-                let x = random_confetti_spawn_x(layer);
-                let y = glib::random_double_range(-24.0, -5.0);
-                spawn_victory_confetti_piece(layer, x, y);
-            }
-        } else {
-            state.borrow_mut().spark_timer_handle = None;
-            return glib::ControlFlow::Break;
+            st.spark_tick = None;
         }
-
-        glib::ControlFlow::Continue
+        in_victory_view
     });
-
-    st.spark_timer_handle = Some(handle);
+    st.spark_tick = Some(tick);
 }
 
 fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
@@ -2110,7 +3330,7 @@ fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     card_overlay.set_hexpand(true);
     card_overlay.set_vexpand(true);
 
-    let spark_layer = gtk::Fixed::new();
+    let spark_layer = gtk::DrawingArea::new();
     spark_layer.set_hexpand(true);
     spark_layer.set_vexpand(true);
     spark_layer.set_can_target(false);
@@ -2125,11 +3345,36 @@ fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     content.set_margin_start(28);
     content.set_margin_end(28);
 
-    let rank_art = gtk::Image::from_resource("/io/github/basshift/Recall/victory/rank-c.svg");
+    let rank_art = gtk::Image::from_resource(&ArtPack::Classic.resource_path(Rank::C));
     rank_art.add_css_class("victory-rank-art");
     rank_art.set_pixel_size(160);
     rank_art.set_halign(gtk::Align::Center);
 
+    let rank_info_label = gtk::Label::new(None);
+    rank_info_label.set_wrap(true);
+    rank_info_label.set_max_width_chars(28);
+    rank_info_label.set_justify(gtk::Justification::Center);
+    let rank_info_popover = gtk::Popover::new();
+    rank_info_popover.set_child(Some(&rank_info_label));
+    let rank_info = gtk::MenuButton::new();
+    rank_info.set_icon_name("dialog-information-symbolic");
+    rank_info.set_tooltip_text(Some(&tr("Rank requirements")));
+    rank_info.add_css_class("flat");
+    rank_info.add_css_class("circular");
+    rank_info.set_halign(gtk::Align::End);
+    rank_info.set_valign(gtk::Align::End);
+    rank_info.set_popover(Some(&rank_info_popover));
+    rank_info.set_visible(false);
+    let rank_overlay = gtk::Overlay::new();
+    rank_overlay.set_halign(gtk::Align::Center);
+    rank_overlay.set_child(Some(&rank_art));
+    rank_overlay.add_overlay(&rank_info);
+
+    let record_banner = gtk::Label::new(Some(&tr("NEW RECORD")));
+    record_banner.add_css_class("victory-record-banner");
+    record_banner.set_halign(gtk::Align::Center);
+    record_banner.set_visible(false);
+
     let title = gtk::Label::new(Some(&tr("Well done!")));
     title.add_css_class("victory-title");
     title.add_css_class("title-1");
@@ -2148,6 +3393,13 @@ fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     stats.set_justify(gtk::Justification::Center);
     stats.set_max_width_chars(36);
 
+    let goal = gtk::Label::new(None);
+    goal.add_css_class("victory-goal");
+    goal.set_wrap(true);
+    goal.set_justify(gtk::Justification::Center);
+    goal.set_max_width_chars(36);
+    goal.set_visible(false);
+
     let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 12);
     buttons.set_halign(gtk::Align::Center);
     buttons.set_margin_top(6);
@@ -2159,7 +3411,121 @@ fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
     again_btn.connect_clicked({
         let state = state.clone();
         move |_| {
-            restart_game(&state);
+            continue_after_victory(&state);
+        }
+    });
+    menu_btn.connect_clicked({
+        let state = state.clone();
+        move |_| {
+            show_menu(&state);
+        }
+    });
+
+    buttons.append(&again_btn);
+    buttons.append(&menu_btn);
+
+    content.append(&record_banner);
+    content.append(&rank_overlay);
+    content.append(&title);
+    content.append(&message);
+    content.append(&stats);
+    content.append(&goal);
+    content.append(&buttons);
+    card_overlay.set_child(Some(&spark_layer));
+    card_overlay.add_overlay(&content);
+    card_shell.append(&card_overlay);
+    center.set_center_widget(Some(&card_shell));
+    root.append(&center);
+    if let Some(narrow) = &state.borrow().narrow_breakpoint {
+        board::add_margin_setters(narrow, &content, 16);
+        narrow.add_setters(&[(&card_shell, "height-request", 360), (&content, "spacing", 8)]);
+        narrow.add_setter(&spark_layer, "height-request", Some(&360.to_value()));
+        narrow.add_setter(&rank_art, "pixel-size", Some(&112.to_value()));
+    }
+
+    {
+        let mut st = state.borrow_mut();
+        st.victory_title_label = Some(title.clone());
+        st.victory_message_label = Some(message.clone());
+        st.victory_stats_label = Some(stats.clone());
+        st.victory_goal_label = Some(goal.clone());
+        st.victory_record_banner = Some(record_banner.clone());
+        st.victory_again_button = Some(again_btn.clone());
+        st.victory_buttons = Some(buttons.clone());
+        st.victory_rank_art = Some(rank_art.clone());
+        st.victory_rank_info = Some(rank_info.clone());
+        st.victory_rank_info_label = Some(rank_info_label.clone());
+        st.victory_art_resource = None;
+        st.victory_spark_layer = Some(spark_layer.clone());
+    }
+
+    root
+}
+
+fn build_defeat_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
+    let root = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    root.set_hexpand(true);
+    root.set_vexpand(true);
+    root.add_css_class("victory-root");
+    root.add_css_class("defeat-root");
+    root.set_halign(gtk::Align::Fill);
+    root.set_valign(gtk::Align::Fill);
+
+    let center = gtk::CenterBox::new();
+    center.set_hexpand(true);
+    center.set_vexpand(true);
+
+    let card_shell = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    card_shell.set_halign(gtk::Align::Center);
+    card_shell.set_valign(gtk::Align::Center);
+    card_shell.add_css_class("victory-card");
+    card_shell.set_size_request(280, 360);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 14);
+    content.set_halign(gtk::Align::Center);
+    content.set_valign(gtk::Align::Center);
+    content.set_margin_top(28);
+    content.set_margin_bottom(28);
+    content.set_margin_start(28);
+    content.set_margin_end(28);
+
+    let icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+    icon.add_css_class("defeat-icon");
+    icon.set_pixel_size(96);
+    icon.set_halign(gtk::Align::Center);
+
+    let title = gtk::Label::new(Some(&tr("Out of Chances")));
+    title.add_css_class("victory-title");
+    title.add_css_class("title-1");
+
+    let message = gtk::Label::new(Some(""));
+    message.add_css_class("victory-message");
+    message.add_css_class("body");
+    message.set_wrap(true);
+    message.set_justify(gtk::Justification::Center);
+    message.set_max_width_chars(36);
+
+    let stats = gtk::Label::new(None);
+    stats.add_css_class("victory-message");
+    stats.add_css_class("body");
+    stats.set_wrap(true);
+    stats.set_justify(gtk::Justification::Center);
+    stats.set_max_width_chars(36);
+
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    buttons.set_halign(gtk::Align::Center);
+    buttons.set_margin_top(6);
+
+    let retry_btn = gtk::Button::with_label(&tr("Try Again"));
+    retry_btn.add_css_class("suggested-action");
+    let menu_btn = gtk::Button::with_label(&tr("Main Menu"));
+
+    retry_btn.connect_clicked({
+        let state = state.clone();
+        move |_| {
+            if !rest_before_next_training_game(&state) {
+                restart_game(&state);
+            }
         }
     });
     menu_btn.connect_clicked({
@@ -2169,74 +3535,144 @@ fn build_victory_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
         }
     });
 
-    buttons.append(&again_btn);
-    buttons.append(&menu_btn);
-
-    content.append(&rank_art);
-    content.append(&title);
-    content.append(&message);
-    content.append(&stats);
-    content.append(&buttons);
-    card_overlay.set_child(Some(&spark_layer));
-    card_overlay.add_overlay(&content);
-    card_shell.append(&card_overlay);
-    center.set_center_widget(Some(&card_shell));
-    root.append(&center);
+    buttons.append(&retry_btn);
+    buttons.append(&menu_btn);
+
+    content.append(&icon);
+    content.append(&title);
+    content.append(&message);
+    content.append(&stats);
+    content.append(&buttons);
+    card_shell.append(&content);
+    center.set_center_widget(Some(&card_shell));
+    root.append(&center);
+    if let Some(narrow) = &state.borrow().narrow_breakpoint {
+        board::add_margin_setters(narrow, &content, 16);
+        narrow.add_setters(&[
+            (&card_shell, "height-request", 300),
+            (&content, "spacing", 8),
+        ]);
+    }
+
+    {
+        let mut st = state.borrow_mut();
+        st.defeat_title_label = Some(title);
+        st.defeat_message_label = Some(message);
+        st.defeat_stats_label = Some(stats);
+        st.defeat_retry_button = Some(retry_btn.clone());
+        st.defeat_buttons = Some(buttons.clone());
+    }
+
+    root
+}
+
+fn build_splash_view(state: &Rc<RefCell<AppState>>) -> gtk::Box {
+    let root = gtk::Box::new(gtk::Orientation::Vertical, 10);
+    root.set_hexpand(true);
+    root.set_vexpand(true);
+    root.set_halign(gtk::Align::Center);
+    root.set_valign(gtk::Align::Center);
+    root.add_css_class("gauntlet-splash");
+
+    let title = gtk::Label::new(None);
+    title.add_css_class("title-1");
+    let subtitle = gtk::Label::new(None);
+    subtitle.add_css_class("title-3");
+    let stats = gtk::Label::new(None);
+    stats.add_css_class("dim-label");
+    stats.add_css_class("numeric");
+
+    let skip = gtk::Button::with_label(&tr("Skip Rest"));
+    skip.add_css_class("pill");
+    skip.set_halign(gtk::Align::Center);
+    skip.set_margin_top(12);
+    skip.set_visible(false);
+    skip.connect_clicked({
+        let state = state.clone();
+        move |_| skip_training_rest(&state)
+    });
+
+    root.append(&title);
+    root.append(&subtitle);
+    root.append(&stats);
+    root.append(&skip);
 
     {
         let mut st = state.borrow_mut();
-        st.victory_title_label = Some(title.clone());
-        st.victory_message_label = Some(message.clone());
-        st.victory_stats_label = Some(stats.clone());
-        st.victory_rank_art = Some(rank_art.clone());
-        st.victory_art_resource = None;
-        st.victory_spark_layer = Some(spark_layer.clone());
+        st.splash_title_label = Some(title);
+        st.splash_subtitle_label = Some(subtitle);
+        st.splash_stats_label = Some(stats);
+        st.splash_skip_button = Some(skip);
     }
 
     root
 }
 
+fn skippable_animation(st: &AppState) -> Option<SequenceHandle> {
+    st.victory_cascade
+        .clone()
+        .or_else(|| st.round_transition.clone())
+        .or_else(|| st.round_interstitial.clone())
+}
+
+// Runs what is left of the running animation at once; false when none is playing.
+fn skip_animation(state: &Rc<RefCell<AppState>>) -> bool {
+    let Some(animation) = skippable_animation(&state.borrow()) else {
+        return false;
+    };
+    animation.skip();
+    true
+}
+
 pub fn handle_tile_click(state: &Rc<RefCell<AppState>>, index: usize) {
+    if state.borrow().pattern.is_some() {
+        pattern_flow::handle_pattern_pick(state, index);
+        return;
+    }
+    let game_span = state.borrow().game_span.clone();
+    let _game = game_span.enter();
     let mut st = state.borrow_mut();
 
     if index >= st.tiles.len() {
         return;
     }
 
+    // A click during a cascade or round transition jumps straight past it.
+    if skippable_animation(&st).is_some() {
+        drop(st);
+        skip_animation(state);
+        return;
+    }
+
     if st.lock_input || st.tiles[index].status != TileStatus::Hidden {
         return;
     }
 
     // Flip the tile
     st.tiles[index].status = TileStatus::Flipped;
-    st.grid_buttons[index].add_css_class("active");
-    play_flip_show(&mut st, index);
+    st.tiles[index].seen = true;
+    st.tiles[index].views += 1;
+    events::emit(GameEvent::TileFlipped(index));
     st.flipped_indices.push(index);
-    if !st.active_session_started {
-        st.active_session_started = true;
-        save_current_run_and_refresh(&st);
-    } else {
-        mark_run_dirty(&mut st);
-    }
+    st.active_session_started = true;
 
     let indices = st.flipped_indices.clone();
     let game_id = st.game_id;
 
-        match evaluate_flip_outcome(&st, &indices, index) {
-            FlipOutcome::Mismatch => {
-                st.run_mismatches = st.run_mismatches.saturating_add(1);
-                let first_pick_index = indices.first().copied().unwrap_or(index);
-                let (mismatch_pause_ms, penalty_plan) = if st.difficulty == Difficulty::Trio {
+    match evaluate_flip_outcome(&st, &indices, index) {
+        FlipOutcome::Mismatch => {
+            st.run_mismatches = st.run_mismatches.saturating_add(1);
+            st.match_streak = 0;
+            events::emit(GameEvent::MismatchMade(indices.clone()));
+            let defeat_reason = defeat::mismatch_defeat_reason(&st);
+            let first_pick_index = indices.first().copied().unwrap_or(index);
+            let (mismatch_pause_ms, penalty_plan) = if trio_penalties::applies_to(&st) {
                 (
-                    trio_penalties::mismatch_pause_ms(st.trio_level),
+                    trio_penalties::mismatch_pause_ms(trio_penalties::penalty_level(&st)),
                     trio_penalties::register_mismatch_and_plan_reshuffle(&mut st, first_pick_index),
                 )
             } else {
-                let penalty_difficulty = if infinite::is_infinite(st.difficulty) {
-                    infinite::classic_difficulty_for_round(st.infinite_round)
-                } else {
-                    st.difficulty
-                };
+                let penalty_difficulty = classic_penalties::penalty_difficulty(&st);
                 (
                     classic_penalties::mismatch_pause_ms(penalty_difficulty),
                     classic_penalties::register_mismatch_and_plan_reshuffle_for(
@@ -2247,69 +3683,66 @@ pub fn handle_tile_click(state: &Rc<RefCell<AppState>>, index: usize) {
                 )
             };
             st.lock_input = true;
-            let state_after_flip = state.clone();
-            let indices_after_flip = indices.clone();
+            let indices_shake = indices.clone();
             drop(st);
             clear_keyboard_focus(state);
-            glib::timeout_add_local(std::time::Duration::from_millis(FLIP_PHASE_MS), move || {
-                let st = state_after_flip.borrow_mut();
-                if st.game_id != game_id {
-                    return glib::ControlFlow::Break;
-                }
-                for &idx in &indices_after_flip {
-                    if let Some(button) = st.grid_buttons.get(idx) {
-                        clear_flip_classes(button);
-                        button.remove_css_class("mismatch-shake");
-                        button.add_css_class("mismatch-shake");
+            let shake = Sequence::new(state, game_id).at(FLIP_PHASE_MS, move |state| {
+                let st = state.borrow();
+                if let Some(board) = &st.board_canvas {
+                    for &idx in &indices_shake {
+                        board.clear_flip(idx);
+                        board.add_flag(idx, CardFlag::Shake);
                     }
                 }
-                drop(st);
-                schedule_mismatch_reset(
-                    &state_after_flip,
-                    indices_after_flip.clone(),
-                    game_id,
-                    mismatch_pause_ms,
-                    penalty_plan,
-                );
-                glib::ControlFlow::Break
+                glib::ControlFlow::Continue
             });
-            let mut st = state.borrow_mut();
-            mark_run_dirty(&mut st);
+            if let Some(reason) = defeat_reason {
+                shake
+                    .then_after(mismatch_pause_ms, move |state| {
+                        show_defeat(state, reason);
+                        glib::ControlFlow::Break
+                    })
+                    .start();
+            } else {
+                schedule_mismatch_reset(shake, indices, game_id, mismatch_pause_ms, penalty_plan);
+            }
         }
         FlipOutcome::CompleteMatch => {
             st.run_matches = st.run_matches.saturating_add(1);
-            if st.difficulty == Difficulty::Trio {
+            st.match_streak = st.match_streak.saturating_add(1);
+            st.best_match_streak = st.best_match_streak.max(st.match_streak);
+            st.record_match_time(glib::monotonic_time());
+            if trio_penalties::applies_to(&st) {
                 trio_penalties::reset_penalty_after_match(&mut st);
             } else {
-                let penalty_difficulty = if infinite::is_infinite(st.difficulty) {
-                    infinite::classic_difficulty_for_round(st.infinite_round)
-                } else {
-                    st.difficulty
-                };
+                let penalty_difficulty = classic_penalties::penalty_difficulty(&st);
                 classic_penalties::reset_penalty_after_match_for(&mut st, penalty_difficulty);
             }
             st.lock_input = true;
-            mark_run_dirty(&mut st);
             drop(st);
             clear_keyboard_focus(state);
-            let state_after_flip = state.clone();
-            glib::timeout_add_local(std::time::Duration::from_millis(FLIP_PHASE_MS), move || {
-                let st = state_after_flip.borrow();
-                if st.game_id != game_id {
-                    return glib::ControlFlow::Break;
-                }
-                drop(st);
-                handle_tile_click_result(&state_after_flip, game_id, indices.clone());
-                glib::ControlFlow::Break
+            sequence::after(state, game_id, FLIP_PHASE_MS, move |state_after_flip| {
+                handle_tile_click_result(state_after_flip, game_id, indices);
             });
         }
-        FlipOutcome::Continue => {
-            mark_run_dirty(&mut st);
-        }
+        FlipOutcome::Continue => {}
     }
 }
 
 fn preview_seconds_for(st: &AppState) -> f64 {
+    let base_secs = base_preview_seconds(st);
+    st.hotseat
+        .as_ref()
+        .map_or(base_secs, |run| run.preview_seconds(base_secs))
+}
+
+fn base_preview_seconds(st: &AppState) -> f64 {
+    if st.kids.is_some() {
+        return kids::KIDS_PREVIEW_SECONDS;
+    }
+    if let Some(custom) = &st.custom {
+        return f64::from(custom.preview_secs);
+    }
     match st.difficulty {
         Difficulty::Easy => 4.0,
         Difficulty::Medium => 7.0,
@@ -2321,7 +3754,13 @@ fn preview_seconds_for(st: &AppState) -> f64 {
             3 => 14.0,
             _ => 15.0,
         },
-        Difficulty::Infinite => match infinite::classic_difficulty_for_round(st.infinite_round) {
+        Difficulty::InfiniteTrio => match st.infinite_level {
+            1 => 9.0,
+            2 => 11.0,
+            3 => 14.0,
+            _ => 15.0,
+        },
+        Difficulty::Infinite => match infinite::classic_difficulty_for_round(st.infinite_pacing, st.infinite_round) {
             Difficulty::Easy => 4.0,
             Difficulty::Medium => 7.0,
             Difficulty::Hard => 10.0,
@@ -2341,8 +3780,21 @@ pub(super) fn show_game_with_reveal_delay(state: &Rc<RefCell<AppState>>, reveal_
             .as_deref()
             == Some("game");
         let is_infinite_mode = infinite::is_infinite(st.difficulty);
-        let reset_timer_for_round = !is_infinite_mode || !was_in_game_view;
+        let carries_run_totals = st
+            .gauntlet()
+            .is_some_and(|gauntlet| gauntlet.carries_run_totals());
+        let reset_timer_for_round =
+            !carries_run_totals && (!is_infinite_mode || !was_in_game_view);
         st.reset_game();
+        if reset_timer_for_round {
+            st.run_modifier_codes.clear();
+        }
+        st.note_run_modifiers();
+        st.game_span = tracing::info_span!("game", id = st.game_id, mode = st.difficulty.name());
+        st.game_span.in_scope(|| tracing::info!("started"));
+        if reset_timer_for_round {
+            habits::note_game_start(&st);
+        }
         stop_timer(&mut st);
         stop_preview(&mut st);
         stop_victory_sparks(&mut st);
@@ -2363,7 +3815,7 @@ pub(super) fn show_game_with_reveal_delay(state: &Rc<RefCell<AppState>>, reveal_
             PREVIEW_REVEAL_MIN_DELAY_MS
         };
         (
-            st.grid_buttons.len() != st.tiles.len(),
+            st.board_canvas.as_ref().map_or(0, BoardCanvas::card_count) != st.tiles.len(),
             preview_seconds_for(&st),
             st.game_id,
             reveal_delay_override_ms.unwrap_or(reveal_delay_ms),
@@ -2388,102 +3840,57 @@ pub(super) fn show_game_with_reveal_delay(state: &Rc<RefCell<AppState>>, reveal_
                 container.remove_css_class("mode-infinite");
             }
         }
+        speedrun::refresh_splits_panel(&st);
+        stats_panel::refresh_stats_panel(&st);
         // Start face-down before the global reveal.
-        for i in 0..st.grid_buttons.len() {
-            if let Some(tile) = st.tiles.get_mut(i) {
-                tile.status = TileStatus::Hidden;
-            }
-            let button = &st.grid_buttons[i];
-            button.remove_css_class("matched");
-            button.remove_css_class("matched-dim");
-            button.remove_css_class("active");
-            button.remove_css_class("match-bump");
-            button.remove_css_class("mismatch-shake");
-            clear_flip_classes(button);
-            if let Some(child) = button.child() {
-                child.queue_draw();
-            }
+        for tile in &mut st.tiles {
+            tile.status = TileStatus::Hidden;
+        }
+        if let Some(board) = &st.board_canvas {
+            board.clear_flips();
+            board.remove_flags_everywhere(&[CardFlag::Dim, CardFlag::Bump, CardFlag::Shake]);
         }
+        hint::clear_glow(&st);
     }
+    events::emit(GameEvent::BoardChanged);
     clear_keyboard_focus(state);
 
-    // Reveal all cards together after a short beat.
-    let state_reveal = state.clone();
-    glib::timeout_add_local(std::time::Duration::from_millis(reveal_delay_ms), move || {
-        let mut st = state_reveal.borrow_mut();
-        if st.game_id != game_id {
-            return glib::ControlFlow::Break;
+    // Reveal all cards together after a short beat, or deal them in wave by wave.
+    let intro_waves = {
+        let st = state.borrow();
+        if st.board_transition.staggers_intro() {
+            st.board_transition.waves(
+                st.grid_cols.max(1) as usize,
+                st.grid_rows.max(0) as usize,
+                st.tiles.len(),
+                false,
+            )
+        } else {
+            Vec::new()
         }
-        for i in 0..st.grid_buttons.len() {
-            if let Some(tile) = st.tiles.get_mut(i) {
-                tile.status = TileStatus::Flipped;
+    };
+    sequence::after(state, game_id, reveal_delay_ms, move |state_reveal| {
+        if intro_waves.is_empty() {
+            {
+                let mut st = state_reveal.borrow_mut();
+                let all_cards: Vec<usize> = (0..st.tiles.len()).collect();
+                reveal_cards(&mut st, &all_cards);
             }
-            st.grid_buttons[i].add_css_class("active");
-            play_flip_show(&mut st, i);
+            begin_memorize(state_reveal, preview_seconds, game_id, reset_timer_for_round);
+            return;
         }
-        drop(st);
-        start_preview_phase(&state_reveal, preview_seconds, game_id);
-
-        // Hide all cards together when memorize countdown ends.
-        let state_hide_start = state_reveal.clone();
-        glib::timeout_add_local(
-            std::time::Duration::from_millis((preview_seconds * 1000.0) as u64),
-            move || {
-                let st = state_hide_start.borrow();
-                if st.game_id != game_id || !st.preview_active {
-                    return glib::ControlFlow::Break;
-                }
-                for button in &st.grid_buttons {
-                    clear_flip_classes(button);
-                    button.add_css_class("flip-hide");
-                    redraw_button_child(button);
-                }
-                drop(st);
-
-                let state_hide_mid = state_hide_start.clone();
-                glib::timeout_add_local(
-                    std::time::Duration::from_millis(FLIP_PHASE_MS),
-                    move || {
-                        let mut st = state_hide_mid.borrow_mut();
-                        if st.game_id != game_id || !st.preview_active {
-                            return glib::ControlFlow::Break;
-                        }
-                        for i in 0..st.grid_buttons.len() {
-                            if let Some(tile) = st.tiles.get_mut(i) {
-                                tile.status = TileStatus::Hidden;
-                            }
-                            st.grid_buttons[i].remove_css_class("active");
-                            play_flip_show(&mut st, i);
-                        }
-                        glib::ControlFlow::Break
-                    },
-                );
-
-                let state_finish = state_hide_start.clone();
-                glib::timeout_add_local(
-                    std::time::Duration::from_millis(FLIP_PHASE_MS * 2),
-                    move || {
-                        let mut st = state_finish.borrow_mut();
-                        if st.game_id != game_id || !st.preview_active {
-                            return glib::ControlFlow::Break;
-                        }
-                        for button in &st.grid_buttons {
-                            clear_flip_classes(button);
-                            redraw_button_child(button);
-                        }
-                        st.lock_input = false;
-                        stop_preview(&mut st);
-                        update_subtitle(&st);
-                        drop(st);
-                        start_timer(&state_finish, reset_timer_for_round);
-                        glib::ControlFlow::Break
-                    },
-                );
-
-                glib::ControlFlow::Break
-            },
-        );
-        glib::ControlFlow::Break
+        // The countdown waits for the last wave, so every card gets the full preview.
+        let step_ms = (INTRO_DEAL_SPAN_MS / intro_waves.len() as u64)
+            .clamp(INTRO_STEP_MIN_MS, INTRO_STEP_MAX_MS);
+        let deal_ms = intro_waves.len() as u64 * step_ms;
+        for (wave_idx, wave) in intro_waves.into_iter().enumerate() {
+            sequence::after(state_reveal, game_id, wave_idx as u64 * step_ms, move |state_wave| {
+                reveal_cards(&mut state_wave.borrow_mut(), &wave);
+            });
+        }
+        sequence::after(state_reveal, game_id, deal_ms, move |state_dealt| {
+            begin_memorize(state_dealt, preview_seconds, game_id, reset_timer_for_round);
+        });
     });
 
     set_header_game(state);
@@ -2494,19 +3901,75 @@ pub(super) fn show_game_with_reveal_delay(state: &Rc<RefCell<AppState>>, reveal_
     }
 }
 
+fn reveal_cards(st: &mut AppState, indices: &[usize]) {
+    for &i in indices {
+        if let Some(tile) = st.tiles.get_mut(i) {
+            tile.status = TileStatus::Flipped;
+            play_flip_show(st, i);
+        }
+    }
+}
+
+fn begin_memorize(
+    state: &Rc<RefCell<AppState>>,
+    preview_seconds: f64,
+    game_id: u64,
+    reset_timer_for_round: bool,
+) {
+    // Hide all cards together when memorize countdown ends.
+    start_preview_phase(
+        state,
+        preview_seconds,
+        game_id,
+        move |state_hide_start| {
+            {
+                let mut st = state_hide_start.borrow_mut();
+                if !st.preview_active {
+                    return;
+                }
+                for i in 0..st.tiles.len() {
+                    play_flip_hide(&mut st, i);
+                }
+            }
+
+            Sequence::new(state_hide_start, game_id)
+                .only_while(|st| st.preview_active)
+                .at(TURN_DOWN_MS, move |state_finish| {
+                    {
+                        let mut st = state_finish.borrow_mut();
+                        st.lock_input = false;
+                        stop_preview(&mut st);
+                        update_subtitle(&st);
+                    }
+                    start_timer(state_finish, reset_timer_for_round);
+                    glib::ControlFlow::Continue
+                })
+                .start();
+        },
+    );
+}
+
 pub(super) fn show_game(state: &Rc<RefCell<AppState>>) {
+    nback_flow::refresh_nback_controls(&state.borrow());
+    if state.borrow().nback.is_some() {
+        nback_flow::start_nback_game(state);
+        return;
+    }
+    if state.borrow().pattern.is_some() {
+        pattern_flow::start_pattern_game(state);
+        return;
+    }
     show_game_with_reveal_delay(state, None);
 }
 
 fn restart_game(state: &Rc<RefCell<AppState>>) {
-    {
+    let restart_gauntlet = {
         let mut st = state.borrow_mut();
+        if st.active_session_started {
+            habits::note_quit(&st);
+        }
         stop_timer(&mut st);
-        stop_preview(&mut st);
-        stop_victory_sparks(&mut st);
-        st.invalidate_callbacks();
-        st.lock_input = false;
-        st.flipped_indices.clear();
+        cancel_all_transients(&mut st);
         if infinite::is_infinite(st.difficulty) {
             infinite::prepare_start(&mut st);
         }
@@ -2515,15 +3978,230 @@ fn restart_game(state: &Rc<RefCell<AppState>>) {
         }
         st.active_session_started = false;
         clear_saved_run_and_refresh(&mut st);
+        st.gauntlet().is_some()
+    };
+    if restart_gauntlet {
+        apply_gauntlet_start(state);
+    } else {
+        show_game(state);
+    }
+}
+
+// Between training games the player rests first; the rest screen starts the next game.
+fn rest_before_next_training_game(state: &Rc<RefCell<AppState>>) -> bool {
+    let resting = state
+        .borrow()
+        .training()
+        .is_some_and(TrainingSession::is_resting_due);
+    if resting {
+        show_training_rest(state, restart_game);
+    }
+    resting
+}
+
+pub(super) fn skip_training_rest(state: &Rc<RefCell<AppState>>) {
+    let resting = {
+        let st = state.borrow();
+        st.training().is_some()
+            && st
+                .view_stack
+                .as_ref()
+                .and_then(|stack| stack.visible_child_name())
+                .as_deref()
+                == Some("splash")
+    };
+    if resting {
+        restart_game(state);
+    }
+}
+
+fn continue_after_victory(state: &Rc<RefCell<AppState>>) {
+    if rest_before_next_training_game(state) {
+        return;
+    }
+    let (series, hotseat, bracket) = {
+        let st = state.borrow();
+        (st.series.clone(), st.hotseat.clone(), st.bracket.clone())
+    };
+    if let Some(bracket) = bracket {
+        match hotseat {
+            Some(run) if !run.is_complete() => apply_bracket_match(state, bracket, run),
+            // A crowned champion starts the same field over.
+            _ if bracket.champion().is_some() => {
+                apply_bracket_start(state, Bracket::new(bracket.level, bracket.players));
+            }
+            _ => apply_bracket_start(state, bracket),
+        }
+        return;
+    }
+    if let Some(run) = hotseat {
+        // A finished game deals a fresh board for the same players.
+        let run = if run.is_complete() {
+            HotseatRun::new(
+                run.level,
+                run.players
+                    .into_iter()
+                    .map(|player| (player.name, player.handicap))
+                    .collect(),
+            )
+        } else {
+            run
+        };
+        apply_hotseat_start(state, run);
+        return;
+    }
+    match series {
+        Some(Series::Tournament(run)) if !run.is_complete() => {
+            let difficulty = run.current_difficulty();
+            apply_mode_change(state, difficulty, |st| {
+                st.series = Some(Series::Tournament(run));
+            });
+        }
+        Some(Series::Tournament(_)) => apply_tournament_start(state),
+        Some(Series::Gauntlet(_)) => apply_gauntlet_start(state),
+        Some(Series::Training(_)) | None => restart_game(state),
     }
-    show_game(state);
 }
 
 pub(super) fn apply_difficulty_change(state: &Rc<RefCell<AppState>>, difficulty: Difficulty) {
+    apply_mode_change(state, difficulty, |_| {});
+}
+
+pub(super) fn apply_challenge(state: &Rc<RefCell<AppState>>, challenge: Challenge) {
+    let difficulty = challenge.difficulty();
+    apply_mode_change(state, difficulty, |st| st.challenge = Some(challenge));
+}
+
+pub(super) fn apply_tournament_start(state: &Rc<RefCell<AppState>>) {
+    let run = TournamentRun::default();
+    let difficulty = run.current_difficulty();
+    apply_mode_change(state, difficulty, |st| st.series = Some(Series::Tournament(run)));
+}
+
+pub(super) fn apply_hotseat_start(state: &Rc<RefCell<AppState>>, run: HotseatRun) {
+    let difficulty = run.difficulty();
+    apply_mode_change(state, difficulty, |st| st.hotseat = Some(run));
+}
+
+// Deals the bracket's next head-to-head match on a shared seed.
+pub(super) fn apply_bracket_start(state: &Rc<RefCell<AppState>>, bracket: Bracket) {
+    let Some((first, second)) = bracket.current_pair() else {
+        return;
+    };
+    let run = HotseatRun::new(
+        bracket.level,
+        [first, second]
+            .into_iter()
+            .map(|player| (bracket.name(player).to_string(), Handicap::for_level(bracket.level)))
+            .collect(),
+    );
+    apply_bracket_match(state, bracket, run);
+}
+
+fn apply_bracket_match(state: &Rc<RefCell<AppState>>, bracket: Bracket, run: HotseatRun) {
+    let difficulty = run.difficulty();
+    apply_mode_change(state, difficulty, |st| {
+        st.hotseat = Some(run);
+        st.bracket = Some(bracket);
+    });
+}
+
+pub(super) fn apply_training_start(state: &Rc<RefCell<AppState>>, difficulty: Difficulty) {
+    apply_mode_change(state, difficulty, |st| {
+        st.series = Some(Series::Training(TrainingSession::default()));
+    });
+}
+
+pub(super) fn apply_speedrun_start(state: &Rc<RefCell<AppState>>, difficulty: Difficulty) {
+    apply_mode_change(state, difficulty, |st| st.speedrun = true);
+}
+
+pub(super) fn apply_custom_start(state: &Rc<RefCell<AppState>>, preset: CustomPreset) {
+    apply_mode_change(state, preset.penalty.difficulty(), |st| st.custom = Some(preset));
+}
+
+pub(super) fn apply_warmup_start(state: &Rc<RefCell<AppState>>, target: Difficulty) {
+    apply_mode_change(state, warmup::WARMUP_DIFFICULTY, |st| {
+        st.warmup_target = Some(target);
+    });
+}
+
+pub(super) fn finish_warmup(state: &Rc<RefCell<AppState>>) {
+    let Some(target) = state.borrow().warmup_target else {
+        return;
+    };
+    apply_difficulty_change(state, target);
+}
+
+pub(super) fn apply_pattern_start(state: &Rc<RefCell<AppState>>) {
+    apply_mode_change(state, Difficulty::Medium, |st| {
+        st.pattern = Some(PatternRun::default());
+    });
+}
+
+pub(super) fn apply_nback_start(state: &Rc<RefCell<AppState>>, n: u8) {
+    apply_mode_change(state, Difficulty::Easy, |st| {
+        st.nback = Some(NBackRun {
+            n,
+            ..NBackRun::default()
+        });
+    });
+}
+
+pub(super) fn apply_kids_start(state: &Rc<RefCell<AppState>>, board: KidsBoard) {
+    if play_limit::is_locked(&mut state.borrow_mut()) {
+        play_limit::show_lockout(state);
+        return;
+    }
+    apply_mode_change(state, Difficulty::Easy, |st| st.kids = Some(board));
+}
+
+pub(super) fn apply_sudden_death_start(state: &Rc<RefCell<AppState>>, difficulty: Difficulty) {
+    apply_mode_change(state, difficulty, |st| st.sudden_death = true);
+}
+
+pub(super) fn apply_gauntlet_start(state: &Rc<RefCell<AppState>>) {
+    let run = GauntletRun::default();
+    apply_mode_change(state, run.current_difficulty(), |st| {
+        st.series = Some(Series::Gauntlet(run));
+    });
+}
+
+pub(super) fn advance_gauntlet_board(state: &Rc<RefCell<AppState>>) {
+    let Some(run) = state.borrow().gauntlet() else {
+        return;
+    };
+    if run.is_complete() {
+        return;
+    }
+    apply_mode_change(state, run.current_difficulty(), |st| {
+        st.series = Some(Series::Gauntlet(run));
+    });
+}
+
+fn open_challenge(state: &Rc<RefCell<AppState>>, challenge: Challenge) {
+    {
+        let st = state.borrow();
+        if st.active_session_started {
+            save_current_run_and_refresh(&st);
+        }
+    }
+    apply_challenge(state, challenge);
+}
+
+fn apply_mode_change(
+    state: &Rc<RefCell<AppState>>,
+    difficulty: Difficulty,
+    configure_mode: impl FnOnce(&mut AppState),
+) {
     let should_rebuild = {
         let mut st = state.borrow_mut();
+        cancel_all_transients(&mut st);
         if st.pending_new_game_selection {
             st.pending_new_game_selection = false;
+            if st.active_session_started {
+                habits::note_quit(&st);
+            }
             finalize_infinite_run_if_needed(&mut st);
             st.active_session_started = false;
             clear_saved_run_and_refresh(&mut st);
@@ -2531,24 +4209,31 @@ pub(super) fn apply_difficulty_change(state: &Rc<RefCell<AppState>>, difficulty:
         if st.difficulty != difficulty {
             finalize_infinite_run_if_needed(&mut st);
         }
+        st.kids = None;
+        st.custom = None;
         st.active_session_started = false;
-        if st.difficulty == difficulty {
-            if infinite::is_infinite(difficulty) {
-                infinite::prepare_start(&mut st);
-            }
-            if classic_penalties::is_expert(difficulty) {
-                st.impossible_mismatch_count = 0;
-            }
-            false
-        } else {
-            if infinite::is_infinite(difficulty) {
-                infinite::prepare_start(&mut st);
-            }
-            if classic_penalties::is_expert(difficulty) {
-                st.impossible_mismatch_count = 0;
-            }
+        st.challenge = None;
+        st.series = None;
+        st.hotseat = None;
+        st.bracket = None;
+        st.speedrun = false;
+        st.sudden_death = false;
+        st.pattern = None;
+        st.nback = None;
+        st.warmup_target = None;
+        configure_mode(&mut st);
+        if infinite::is_infinite(difficulty) {
+            infinite::prepare_start(&mut st);
+        }
+        if classic_penalties::is_expert(difficulty) {
+            st.impossible_mismatch_count = 0;
+        }
+        // The next deal covers everything else; only a new board shape needs new widgets.
+        if st.needs_new_grid(difficulty) {
             st.set_difficulty(difficulty);
             true
+        } else {
+            false
         }
     };
 
@@ -2565,6 +4250,7 @@ pub(super) fn apply_trio_level_change(state: &Rc<RefCell<AppState>>, level: u8)
             false
         } else {
             st.set_trio_level(level);
+            settings::set_integer(mode_dialogs::TRIO_LEVEL_KEY, i32::from(st.trio_level));
             st.difficulty == Difficulty::Trio
         }
     };
@@ -2577,13 +4263,22 @@ pub(super) fn apply_trio_level_change(state: &Rc<RefCell<AppState>>, level: u8)
 
 #[cfg(test)]
 mod tests {
-    use super::{evaluate_flip_outcome, FlipOutcome};
-    use crate::ui::state::{AppState, Tile, TileStatus};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::{evaluate_flip_outcome, handle_tile_click, pause_game, resume_game, FlipOutcome};
+    use crate::ui::hud::start_timer;
+    use crate::ui::paths;
+    use crate::ui::state::{AppState, Difficulty, Tile, TileStatus};
+    use crate::ui::timers::fake::FakeClock;
 
     fn tile(value: &str) -> Tile {
         Tile {
             value: value.to_string(),
             status: TileStatus::Flipped,
+            seen: true,
+            views: 1,
+            tag: None,
         }
     }
 
@@ -2598,6 +4293,103 @@ mod tests {
         assert!(matches!(outcome, FlipOutcome::Mismatch));
     }
 
+    // Longer than any flip, mismatch, penalty or match-bump chain on a Medium board.
+    const SETTLE_MS: u64 = 5_000;
+
+    fn flip_pair(state: &Rc<RefCell<AppState>>, clock: &FakeClock, first: usize, second: usize) {
+        handle_tile_click(state, first);
+        handle_tile_click(state, second);
+        clock.advance(SETTLE_MS);
+    }
+
+    fn hidden_pair(st: &AppState) -> Option<(usize, usize)> {
+        let first = st.tiles.iter().position(|tile| tile.status == TileStatus::Hidden)?;
+        let second = (first + 1..st.tiles.len()).find(|&idx| {
+            st.tiles[idx].status == TileStatus::Hidden && st.tiles[idx].value == st.tiles[first].value
+        })?;
+        Some((first, second))
+    }
+
+    #[test]
+    fn golden_path_runs_from_mismatch_through_penalty_to_victory() {
+        // Every widget stays unset, so the whole flow runs without a display.
+        let dir = std::env::temp_dir().join(format!("recall-golden-path-{}", std::process::id()));
+        paths::set_dir_override(Some(dir.clone()));
+        assert_eq!(
+            paths::dir_override(),
+            Some(dir.as_path()),
+            "records would land in the real data dir"
+        );
+        let clock = FakeClock::install();
+        let values = ["A", "B", "C", "A", "B", "C"];
+        let state = Rc::new(RefCell::new(AppState {
+            difficulty: Difficulty::Medium,
+            game_id: 1,
+            tiles: values
+                .iter()
+                .map(|value| Tile {
+                    status: TileStatus::Hidden,
+                    seen: false,
+                    views: 0,
+                    ..tile(value)
+                })
+                .collect(),
+            ..Default::default()
+        }));
+
+        for round in 1..=4 {
+            flip_pair(&state, &clock, 0, 1);
+            let st = state.borrow();
+            assert_eq!(st.run_mismatches, round);
+            assert!(!st.lock_input && st.flipped_indices.is_empty());
+            assert!(st.tiles.iter().all(|tile| tile.status == TileStatus::Hidden));
+        }
+        assert!(!dir.join("symbol_stats.json").exists(), "stats are saved once the game ends");
+        assert!(!dir.join("mistake_heatmap.json").exists());
+
+        // The fifth mismatch on Medium reshuffles the hidden cards and flashes two.
+        flip_pair(&state, &clock, 0, 1);
+        {
+            let st = state.borrow();
+            assert_eq!(st.impossible_mismatch_count, 0);
+            assert!(!st.lock_input);
+            assert!(st.tiles.iter().all(|tile| tile.status == TileStatus::Hidden));
+        }
+
+        while let Some((first, second)) = hidden_pair(&state.borrow()) {
+            flip_pair(&state, &clock, first, second);
+        }
+        let st = state.borrow();
+        assert!(st.tiles.iter().all(|tile| tile.status == TileStatus::Matched));
+        assert_eq!((st.run_matches, st.run_mismatches), (3, 5));
+        let record = st.records.classic.last().expect("the win should be recorded");
+        assert_eq!((record.level, record.precision_pct), (2, 38));
+        assert!(!st.victory_title_text.is_empty());
+        assert!(dir.join("symbol_stats.json").exists());
+        assert!(dir.join("mistake_heatmap.json").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn time_stands_still_while_paused() {
+        let clock = FakeClock::install();
+        let state = Rc::new(RefCell::new(AppState::default()));
+        start_timer(&state, true);
+        clock.advance(3_000);
+        assert_eq!(state.borrow().seconds_elapsed, 3);
+
+        let held = pause_game(&mut state.borrow_mut());
+        assert!(held.paused && state.borrow().timer_handle.is_none());
+        clock.advance(10_000);
+        assert_eq!(state.borrow().seconds_elapsed, 3);
+
+        resume_game(&state, held, true);
+        assert!(!state.borrow().lock_input);
+        clock.advance(2_000);
+        assert_eq!(state.borrow().seconds_elapsed, 5);
+    }
+
     #[test]
     fn trio_match_accepts_three_equal_values() {
         let mut st = AppState::default();
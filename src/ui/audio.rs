@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::gdk;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use super::events::{self, GameEvent};
+use super::state::AppState;
+
+pub const CUE_MATCH_KEY: &str = "cue-match";
+pub const CUE_MISMATCH_KEY: &str = "cue-mismatch";
+pub const AUDIO_ONLY_KEY: &str = "audio-feedback-only";
+pub(super) const SAMPLE_RATE: u32 = 22_050;
+const FADE_MS: u32 = 8;
+const VOLUME: f32 = 0.35;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    Tick,
+    CountdownEnd,
+    Flip,
+    Match,
+    Mismatch,
+}
+
+impl Cue {
+    // Frequency in Hz and length in milliseconds of each note; a zero frequency is a pause.
+    fn notes(self) -> &'static [(f32, u32)] {
+        match self {
+            Self::Tick => &[(880.0, 45)],
+            Self::CountdownEnd => &[(1320.0, 220)],
+            Self::Flip => &[(520.0, 30)],
+            Self::Match => &[(660.0, 90), (990.0, 140)],
+            Self::Mismatch => &[(220.0, 110), (0.0, 40), (196.0, 160)],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AudioCues {
+    pub countdown: bool,
+    pub matches: bool,
+    pub mismatches: bool,
+    // Every cue plays, including flips, so the game can be followed by ear.
+    pub audio_only: bool,
+}
+
+impl AudioCues {
+    pub fn plays(self, cue: Cue) -> bool {
+        self.audio_only
+            || match cue {
+                Cue::Tick | Cue::CountdownEnd => self.countdown,
+                Cue::Match => self.matches,
+                Cue::Mismatch => self.mismatches,
+                Cue::Flip => false,
+            }
+    }
+}
+
+fn render_wav(notes: &[(f32, u32)]) -> Vec<u8> {
+    let mut samples: Vec<i16> = Vec::new();
+    let fade_len = (SAMPLE_RATE * FADE_MS / 1000) as usize;
+    for &(freq, ms) in notes {
+        let len = (SAMPLE_RATE * ms / 1000) as usize;
+        for n in 0..len {
+            let envelope = (n.min(len - n) as f32 / fade_len as f32).min(1.0);
+            let value = (TAU * freq * n as f32 / SAMPLE_RATE as f32).sin() * VOLUME * envelope;
+            samples.push((value * f32::from(i16::MAX)) as i16);
+        }
+    }
+    wav_from_samples(&samples)
+}
+
+// 16-bit mono PCM at SAMPLE_RATE.
+pub(super) fn wav_from_samples(samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+thread_local! {
+    // Streams stay alive until they finish playing.
+    static PLAYING: RefCell<Vec<gtk::MediaFile>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn play(cues: AudioCues, cue: Cue) {
+    if !cues.plays(cue) {
+        return;
+    }
+    let bytes = glib::Bytes::from_owned(render_wav(cue.notes()));
+    let media = gtk::MediaFile::for_input_stream(&gio::MemoryInputStream::from_bytes(&bytes));
+    media.connect_error_notify(|media| {
+        if let Some(err) = media.error() {
+            tracing::warn!("failed to play audio cue: {err}");
+        }
+        // Without a media backend the system bell is better than silence.
+        if let Some(display) = gdk::Display::default() {
+            display.beep();
+        }
+        forget(media);
+    });
+    media.connect_ended_notify(forget);
+    media.play();
+    PLAYING.with(|playing| playing.borrow_mut().push(media));
+}
+
+fn forget(media: &gtk::MediaFile) {
+    PLAYING.with(|playing| playing.borrow_mut().retain(|other| other != media));
+}
+
+// The flip that completes a pick locks the board; its match or mismatch cue
+// plays instead of the click, so each outcome sounds different.
+fn flip_cue(st: &AppState) -> Option<Cue> {
+    (!st.lock_input).then_some(Cue::Flip)
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        let cue = match event {
+            GameEvent::TileFlipped(_) => match flip_cue(&state.borrow()) {
+                Some(cue) => cue,
+                None => return,
+            },
+            GameEvent::MatchFound(_) => Cue::Match,
+            GameEvent::MismatchMade(_) => Cue::Mismatch,
+            GameEvent::PenaltyApplied(_)
+            | GameEvent::RoundFinished { .. }
+            | GameEvent::BoardChanged => return,
+        };
+        play(state.borrow().audio_cues, cue);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cues_follow_their_switches_unless_audio_only() {
+        let cues = AudioCues {
+            countdown: true,
+            ..AudioCues::default()
+        };
+        assert!(cues.plays(Cue::Tick));
+        assert!(cues.plays(Cue::CountdownEnd));
+        assert!(!cues.plays(Cue::Match));
+        assert!(!cues.plays(Cue::Flip));
+
+        let audio_only = AudioCues {
+            audio_only: true,
+            ..AudioCues::default()
+        };
+        assert!(audio_only.plays(Cue::Flip));
+        assert!(audio_only.plays(Cue::Mismatch));
+    }
+
+    #[test]
+    fn only_flips_that_leave_the_pick_open_click() {
+        let mut st = AppState::default();
+        assert_eq!(flip_cue(&st), Some(Cue::Flip));
+        st.lock_input = true;
+        assert_eq!(flip_cue(&st), None);
+    }
+
+    #[test]
+    fn rendered_cues_are_valid_mono_wav() {
+        let wav = render_wav(Cue::Match.notes());
+        let samples = (SAMPLE_RATE * 230 / 1000) as usize;
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav.len(), 44 + samples * 2);
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), (samples * 2) as u32);
+    }
+}
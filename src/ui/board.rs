@@ -1,10 +1,15 @@
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::graphene;
 use gtk4::prelude::*;
-use gtk4::pango;
+use libadwaita as adw;
+use adw::prelude::*;
+use super::board_canvas::{BoardCanvas, CardFlag};
+use super::events::{self, GameEvent};
+use super::fonts;
 use super::state::{AppState, TileStatus};
-use super::app::handle_tile_click;
 
 pub const CONTENT_MARGIN: i32 = 12;
 pub const TILE_GAP: i32 = 6;
@@ -18,192 +23,272 @@ const TILE_GAP_MIN: i32 = 2;
 const CONTAINER_PADDING_FACTOR: f64 = 0.20;
 const CONTAINER_PADDING_MIN: i32 = 6;
 const CONTAINER_PADDING_MAX: i32 = 24;
+// Tiles stop growing here on ultra-wide monitors and never shrink below a
+// comfortable tap target, even on 8-column boards on 360 px phones.
+const MAX_TILE_SIZE: i32 = 152;
+const MIN_TILE_SIZE: i32 = 36;
+const NARROW_WIDTH_SP: f64 = 400.0;
+pub const NARROW_CONTENT_MARGIN: i32 = 4;
+const COMPACT_BOARD_WIDTH: f64 = 500.0;
+const COORDINATE_LABEL_SIZE: i32 = 16;
 
-pub fn build_board_grid(state: &Rc<RefCell<AppState>>) -> gtk::Grid {
-    let grid = gtk::Grid::new();
-    grid.add_css_class("recall-board");
-    grid.set_row_spacing(TILE_GAP as u32);
-    grid.set_column_spacing(TILE_GAP as u32);
-    grid.set_halign(gtk::Align::Fill);
-    grid.set_valign(gtk::Align::Fill);
-    grid.set_hexpand(true);
-    grid.set_vexpand(true);
-
-    let css_provider = {
-        let st = state.borrow();
-        st.dynamic_css_provider.clone()
+// Widest the board may get; the shell stays square or keeps the grid ratio, so
+// this caps its height as well.
+pub fn board_max_size(cols: i32, rows: i32) -> i32 {
+    let cells = cols.max(rows).max(1);
+    cells * MAX_TILE_SIZE + (cells - 1) * TILE_GAP + 2 * CONTAINER_PADDING_MAX
+}
+
+// Smallest board that still gives every tile a tap-sized cell.
+pub fn board_min_size(cols: i32, rows: i32, show_coordinates: bool) -> (i32, i32) {
+    let (width, height) = canvas_min_size(cols, rows, show_coordinates);
+    (width + 2 * CONTAINER_PADDING_MIN, height + 2 * CONTAINER_PADDING_MIN)
+}
+
+pub fn canvas_min_size(cols: i32, rows: i32, show_coordinates: bool) -> (i32, i32) {
+    let labels = if show_coordinates { COORDINATE_LABEL_SIZE + TILE_GAP_MIN } else { 0 };
+    let side = |cells: i32| {
+        let cells = cells.max(1);
+        cells * MIN_TILE_SIZE + (cells - 1) * TILE_GAP_MIN + labels
     };
+    (side(cols), side(rows))
+}
 
-    let update_styles = {
-        let state = state.clone();
-        let css_provider = css_provider.clone();
-        move |grid: &gtk::Grid| {
-            let width = grid.allocated_width();
-            let height = grid.allocated_height();
-            if width > 0 && height > 0 {
-                let (grid_cols, grid_rows) = {
-                    let st = state.borrow();
-                    (st.grid_cols.max(1), st.grid_rows.max(1))
-                };
-                let grid_cells = grid_cols.max(grid_rows).max(1);
-                let approx_cell = width.min(height) / grid_cells;
-                let tile_gap =
-                    ((approx_cell as f64 * 0.10).round() as i32).clamp(TILE_GAP_MIN, TILE_GAP);
-                grid.set_row_spacing(tile_gap as u32);
-                grid.set_column_spacing(tile_gap as u32);
-
-                let cell_width = (width - (grid_cols - 1) * tile_gap) / grid_cols;
-                let cell_height = (height - (grid_rows - 1) * tile_gap) / grid_rows;
-                let min_dim = cell_width.min(cell_height);
-                
-                // Dynamic radii based on available cell size.
-                let card_radius = ((min_dim as f64 * CARD_RADIUS_FACTOR).round() as i32)
-                    .clamp(CARD_RADIUS_MIN, CARD_RADIUS_MAX);
-                let container_radius =
-                    ((min_dim as f64 * CONTAINER_RADIUS_FACTOR).round() as i32)
-                        .clamp(CONTAINER_RADIUS_MIN, CONTAINER_RADIUS_MAX);
-                let container_padding =
-                    ((min_dim as f64 * CONTAINER_PADDING_FACTOR).round() as i32)
-                        .clamp(CONTAINER_PADDING_MIN, CONTAINER_PADDING_MAX);
-
-                if let Some(provider) = &css_provider {
-                    provider.load_from_data(&format!(
-                        "window.app-window .recall-card {{ border-radius: {card_radius}px; }} \
-                         window.app-window .recall-card-container {{ border-radius: {container_radius}px; padding: {container_padding}px; }}",
-                        card_radius = card_radius,
-                        container_radius = container_radius,
-                        container_padding = container_padding
-                    ));
-                }
-            }
+// Window-wide phone layout. Views add their own setters before the window
+// takes it, so the board, HUD and victory card shrink together.
+pub fn narrow_breakpoint() -> adw::Breakpoint {
+    adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+        adw::BreakpointConditionLengthType::MaxWidth,
+        NARROW_WIDTH_SP,
+        adw::LengthUnit::Sp,
+    ))
+}
+
+pub fn add_margin_setters(breakpoint: &adw::Breakpoint, widget: &impl IsA<gtk::glib::Object>, margin: i32) {
+    breakpoint.add_setters(&[
+        (widget, "margin-top", margin),
+        (widget, "margin-bottom", margin),
+        (widget, "margin-start", margin),
+        (widget, "margin-end", margin),
+    ]);
+}
+
+// The card follows its own width rather than the window's, since a short
+// window squeezes the square board just as much as a narrow one.
+pub fn build_board_bin(board_card: &gtk::Box) -> adw::BreakpointBin {
+    let bin = adw::BreakpointBin::new();
+    bin.set_hexpand(true);
+    bin.set_vexpand(true);
+    let compact = adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+        adw::BreakpointConditionLengthType::MaxWidth,
+        COMPACT_BOARD_WIDTH,
+        adw::LengthUnit::Px,
+    ));
+    compact.connect_apply({
+        let card = board_card.clone();
+        move |_| card.add_css_class("compact")
+    });
+    compact.connect_unapply({
+        let card = board_card.clone();
+        move |_| card.remove_css_class("compact")
+    });
+    bin.add_breakpoint(compact);
+    bin
+}
+
+// Square cells centred in the canvas, after the coordinate labels if shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardGeometry {
+    cols: i32,
+    rows: i32,
+    pub cell: f32,
+    pub gap: f32,
+    origin_x: f32,
+    origin_y: f32,
+}
+
+impl BoardGeometry {
+    pub fn new(width: i32, height: i32, cols: i32, rows: i32, show_coordinates: bool) -> Self {
+        let cols = cols.max(1);
+        let rows = rows.max(1);
+        let approx_cell = width.min(height) / cols.max(rows);
+        let gap = ((approx_cell as f64 * 0.10).round() as i32).clamp(TILE_GAP_MIN, TILE_GAP) as f32;
+        let labels = if show_coordinates { COORDINATE_LABEL_SIZE as f32 + gap } else { 0.0 };
+        let free_width = width as f32 - labels - (cols - 1) as f32 * gap;
+        let free_height = height as f32 - labels - (rows - 1) as f32 * gap;
+        let cell = (free_width / cols as f32).min(free_height / rows as f32).floor().max(0.0);
+        let grid_width = cols as f32 * cell + (cols - 1) as f32 * gap;
+        let grid_height = rows as f32 * cell + (rows - 1) as f32 * gap;
+        Self {
+            cols,
+            rows,
+            cell,
+            gap,
+            origin_x: labels + ((width as f32 - labels - grid_width) / 2.0).floor().max(0.0),
+            origin_y: labels + ((height as f32 - labels - grid_height) / 2.0).floor().max(0.0),
         }
-    };
+    }
+
+    pub fn card_rect(&self, index: usize) -> graphene::Rect {
+        let col = index as i32 % self.cols;
+        let row = index as i32 / self.cols;
+        graphene::Rect::new(
+            self.origin_x + col as f32 * (self.cell + self.gap),
+            self.origin_y + row as f32 * (self.cell + self.gap),
+            self.cell,
+            self.cell,
+        )
+    }
+
+    // The label cells sit one gap before the first column and row.
+    pub fn column_label_rect(&self, col: i32) -> graphene::Rect {
+        let size = COORDINATE_LABEL_SIZE as f32;
+        graphene::Rect::new(
+            self.origin_x + col as f32 * (self.cell + self.gap),
+            self.origin_y - self.gap - size,
+            self.cell,
+            size,
+        )
+    }
+
+    pub fn row_label_rect(&self, row: i32) -> graphene::Rect {
+        let size = COORDINATE_LABEL_SIZE as f32;
+        graphene::Rect::new(
+            self.origin_x - self.gap - size,
+            self.origin_y + row as f32 * (self.cell + self.gap),
+            size,
+            self.cell,
+        )
+    }
 
-    let last_size = Rc::new(Cell::new((0, 0)));
-    let update_styles_tick = update_styles.clone();
-    let last_size_tick = last_size.clone();
-    grid.add_tick_callback(move |grid, _| {
-        let size = (grid.allocated_width(), grid.allocated_height());
-        if size.0 > 0 && size.1 > 0 && size != last_size_tick.get() {
-            last_size_tick.set(size);
-            update_styles_tick(grid);
+    // Gaps between cards do not count, so a tap between two never picks either.
+    pub fn index_at(&self, x: f64, y: f64) -> Option<usize> {
+        if self.cell <= 0.0 {
+            return None;
+        }
+        let step = f64::from(self.cell + self.gap);
+        let x = x - f64::from(self.origin_x);
+        let y = y - f64::from(self.origin_y);
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let col = (x / step) as i32;
+        let row = (y / step) as i32;
+        let inside = x - f64::from(col) * step < f64::from(self.cell)
+            && y - f64::from(row) * step < f64::from(self.cell);
+        (inside && col < self.cols && row < self.rows).then(|| (row * self.cols + col) as usize)
+    }
+
+    pub fn card_radius(&self) -> f32 {
+        ((self.cell as f64 * CARD_RADIUS_FACTOR).round() as i32).clamp(CARD_RADIUS_MIN, CARD_RADIUS_MAX) as f32
+    }
+}
+
+// Player flips reveal here; reshuffle and round flips call `reveal_card` themselves.
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        if let GameEvent::TileFlipped(index) = event
+            && let Some(board) = &state.borrow().board_canvas
+        {
+            reveal_card(board, *index);
         }
-        glib::ControlFlow::Continue
     });
+}
 
-    let mut buttons = Vec::new();
+pub fn reveal_card(board: &BoardCanvas, index: usize) {
+    for flag in [CardFlag::ReshuffleFlip, CardFlag::FastReshuffleFlip, CardFlag::RoundFlip] {
+        board.remove_flag(index, flag);
+    }
+    board.flip_reveal(index);
+}
 
-    let (grid_cols, grid_rows) = {
+pub fn build_board_canvas(state: &Rc<RefCell<AppState>>) -> BoardCanvas {
+    let (grid_cols, grid_rows, show_coordinates) = {
         let st = state.borrow();
-        (st.grid_cols, st.grid_rows)
+        (st.grid_cols, st.grid_rows, st.show_coordinates)
     };
+    let canvas = BoardCanvas::new(state, grid_cols, grid_rows, show_coordinates);
+    for (index, tile) in state.borrow().tiles.iter().enumerate() {
+        if tile.status == TileStatus::Matched {
+            canvas.add_flag(index, CardFlag::Dim);
+        }
+    }
+    canvas.set_halign(gtk::Align::Fill);
+    canvas.set_valign(gtk::Align::Fill);
+    canvas.set_hexpand(true);
+    canvas.set_vexpand(true);
 
-    for i in 0..(grid_rows * grid_cols) {
-        let index = i as usize;
-        let aspect_frame = gtk::AspectFrame::builder()
-            .ratio(1.0)
-            .obey_child(false)
-            .halign(gtk::Align::Fill)
-            .valign(gtk::Align::Fill)
-            .hexpand(true)
-            .vexpand(true)
-            .build();
-
-        let button = gtk::Button::builder()
-            .css_classes(vec!["recall-card"])
-            .build();
-        button.set_hexpand(true);
-        button.set_vexpand(true);
-        
-        let drawing_area = gtk::DrawingArea::builder()
-            .hexpand(true)
-            .vexpand(true)
-            .build();
-        drawing_area.add_css_class("recall-card-label");
-
-        let state_draw = state.clone();
-        drawing_area.set_draw_func(move |area, cr, width, height| {
-            let st = state_draw.borrow();
-            if index >= st.tiles.len() {
-                return;
-            }
-            let tile = &st.tiles[index];
-            let is_hidden = tile.status == TileStatus::Hidden;
-            let text = if !is_hidden { &tile.value } else { "?" };
-
-            let min_dim = width.min(height) as f64;
-            let font_size = if is_hidden {
-                min_dim * 0.34
-            } else {
-                min_dim * 0.40
-            };
-
-            cr.set_antialias(gtk::cairo::Antialias::Default);
-
-            let layout = pangocairo::functions::create_layout(cr);
-            let mut font_desc = pango::FontDescription::new();
-            if is_hidden {
-                font_desc.set_family("Cantarell, Noto Sans, sans");
-                font_desc.set_weight(pango::Weight::Bold);
-            } else {
-                font_desc.set_family("Noto Color Emoji, Apple Color Emoji, Segoe UI Emoji, sans");
-            }
-            font_desc.set_size((font_size * pango::SCALE as f64) as i32);
-            layout.set_font_description(Some(&font_desc));
-            layout.set_text(text);
-
-            let fg = area.style_context().color();
-            cr.set_source_rgba(
-                fg.red() as f64,
-                fg.green() as f64,
-                fg.blue() as f64,
-                fg.alpha() as f64,
+    // The container's rounding and padding follow the card size.
+    let update_styles = {
+        let state = state.clone();
+        move |canvas: &BoardCanvas| {
+            let geometry = BoardGeometry::new(
+                canvas.width(),
+                canvas.height(),
+                grid_cols,
+                grid_rows,
+                show_coordinates,
             );
+            let cell = geometry.cell as f64;
+            let container_radius = ((cell * CONTAINER_RADIUS_FACTOR).round() as i32)
+                .clamp(CONTAINER_RADIUS_MIN, CONTAINER_RADIUS_MAX);
+            let container_padding = ((cell * CONTAINER_PADDING_FACTOR).round() as i32)
+                .clamp(CONTAINER_PADDING_MIN, CONTAINER_PADDING_MAX);
 
-            let (text_width, text_height) = layout.pixel_size();
-            let text_x = (width - text_width) as f64 / 2.0;
-            let text_y = (height - text_height) as f64 / 2.0;
-            cr.move_to(text_x, text_y);
-
-            pangocairo::functions::show_layout(cr, &layout);
-        });
-
-        button.set_child(Some(&drawing_area));
-
-        if let Some(tile) = state.borrow().tiles.get(index) {
-            match tile.status {
-                TileStatus::Matched => {
-                    button.add_css_class("matched");
-                    button.add_css_class("matched-dim");
-                }
-                TileStatus::Flipped => button.add_css_class("active"),
-                TileStatus::Hidden => (),
-            }
+            let mut st = state.borrow_mut();
+            st.board_css = format!(
+                "window.app-window .recall-card-container {{ border-radius: {container_radius}px; padding: {container_padding}px; }}",
+                container_radius = container_radius,
+                container_padding = container_padding
+            );
+            fonts::apply_dynamic_css(&st);
         }
+    };
 
-        let state_clone = state.clone();
-        button.connect_clicked(move |_| {
-            handle_tile_click(&state_clone, i as usize);
-        });
-        let state_mouse_enter = state.clone();
-        let motion = gtk::EventControllerMotion::new();
-        motion.connect_enter(move |_, _, _| {
-            let st = state_mouse_enter.borrow();
-            for button in &st.grid_buttons {
-                button.remove_css_class("kbd-focus");
-            }
-        });
-        button.add_controller(motion);
-
-        aspect_frame.set_child(Some(&button));
-
-        let x = i % grid_cols;
-        let y = i / grid_cols;
-        grid.attach(&aspect_frame, x, y, 1, 1);
-        buttons.push(button);
+    let last_size = Cell::new((0, 0));
+    canvas.add_tick_callback(move |canvas, _| {
+        let size = (canvas.width(), canvas.height());
+        if size.0 > 0 && size.1 > 0 && size != last_size.get() {
+            last_size.set(size);
+            update_styles(canvas);
+        }
+        glib::ControlFlow::Continue
+    });
+
+    state.borrow_mut().board_canvas = Some(canvas.clone());
+    canvas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::custom::MAX_SIDE;
+
+    #[test]
+    fn widest_board_keeps_tap_targets_on_narrow_phones() {
+        let (min_width, _) = board_min_size(MAX_SIDE, MAX_SIDE, true);
+        assert!(min_width + 2 * NARROW_CONTENT_MARGIN <= 360);
+        assert!(board_max_size(3, 4) < board_max_size(6, 8));
     }
 
-    state.borrow_mut().grid_buttons = buttons;
+    #[test]
+    fn taps_land_on_cards_and_never_on_gaps_or_labels() {
+        let geometry = BoardGeometry::new(400, 300, 4, 3, true);
+        let first = geometry.card_rect(0);
+        assert!(first.x() >= COORDINATE_LABEL_SIZE as f32);
+        let center = |rect: graphene::Rect| {
+            (f64::from(rect.x() + rect.width() / 2.0), f64::from(rect.y() + rect.height() / 2.0))
+        };
+        for index in 0..12 {
+            let (x, y) = center(geometry.card_rect(index));
+            assert_eq!(geometry.index_at(x, y), Some(index));
+        }
 
-    grid
+        let gap_x = f64::from(first.x() + first.width() + geometry.gap / 2.0);
+        assert_eq!(geometry.index_at(gap_x, center(first).1), None);
+        assert_eq!(geometry.index_at(1.0, 1.0), None);
+        let last = geometry.card_rect(11);
+        assert_eq!(geometry.index_at(f64::from(last.x() + last.width() + 1.0), center(last).1), None);
+    }
 }
@@ -0,0 +1,1125 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use gtk4 as gtk;
+use gtk4::gdk;
+use gtk4::glib;
+use gtk4::graphene;
+use gtk4::gsk;
+use gtk4::pango;
+use gtk4::prelude::*;
+use gtk4::subclass::prelude::*;
+use libadwaita as adw;
+use adw::prelude::AnimationExt;
+
+use crate::i18n::tr_format;
+use super::app::handle_tile_click;
+use super::board::{self, BoardGeometry};
+use super::coordinates::{column_label, row_label};
+use super::faces;
+use super::fonts;
+use super::peek;
+use super::perf_hud::{self, PendingKind};
+use super::state::{AppState, Tile, TileStatus};
+use super::tags;
+
+pub const FLIP_PHASE_MS: u64 = 260;
+// A whole turn onto the card back, edge-on halfway through.
+pub const TURN_DOWN_MS: u64 = 2 * FLIP_PHASE_MS;
+const MATCHED_DIM_OPACITY: f64 = 0.5;
+const PEEK_OPACITY: f64 = 0.45;
+const TAG_BAND_FACTOR: f64 = 0.07;
+const FLIP_EDGE_DEGREES: f64 = 90.0;
+const FLIP_PERSPECTIVE_FACTOR: f32 = 3.0;
+const HOVER_SCALE: f32 = 1.05;
+const FACE_RING_FACTOR: f32 = 0.05;
+const FACE_RING_MIN: f32 = 4.0;
+const FACE_RING_MAX: f32 = 6.0;
+const HINT_RING_WIDTH: f32 = 3.0;
+const FOCUS_RING_WIDTH: f32 = 2.0;
+const FOCUS_RING_ALPHA: f32 = 0.42;
+const COORDINATE_ALPHA: f32 = 0.55;
+const EMOJI_FAMILY: &str = "Noto Color Emoji, Apple Color Emoji, Segoe UI Emoji, sans";
+const LAYOUT_SIZE_STEP: f64 = 2.0;
+const LAYOUT_CACHE_LIMIT: usize = 512;
+
+// Highlights and one-shot animations drawn over what the tile status shows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CardFlag {
+    Dim,
+    KeyboardFocus,
+    HintMatch,
+    HintExplore,
+    PatternLit,
+    PatternHit,
+    PatternMiss,
+    NBackCue,
+    Shake,
+    Bump,
+    ReshuffleFlip,
+    FastReshuffleFlip,
+    RoundFlip,
+}
+
+impl CardFlag {
+    // When several animations are set, the one listed last here plays.
+    const ANIMATIONS: [CardFlag; 5] = [
+        CardFlag::Shake,
+        CardFlag::ReshuffleFlip,
+        CardFlag::FastReshuffleFlip,
+        CardFlag::RoundFlip,
+        CardFlag::Bump,
+    ];
+
+    fn bit(self) -> u16 {
+        1 << self as u16
+    }
+
+    fn keyframes(self) -> Option<&'static Keyframes> {
+        match self {
+            CardFlag::Shake => Some(&SHAKE),
+            CardFlag::Bump => Some(&BUMP),
+            CardFlag::ReshuffleFlip => Some(&RESHUFFLE_FLIP),
+            CardFlag::FastReshuffleFlip => Some(&FAST_RESHUFFLE_FLIP),
+            CardFlag::RoundFlip => Some(&ROUND_FLIP),
+            _ => None,
+        }
+    }
+
+    fn priority(self) -> Option<usize> {
+        Self::ANIMATIONS.iter().position(|flag| *flag == self)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pose {
+    shift: f32,
+    scale_x: f32,
+    scale_y: f32,
+    opacity: f32,
+}
+
+const REST: Pose = Pose {
+    shift: 0.0,
+    scale_x: 1.0,
+    scale_y: 1.0,
+    opacity: 1.0,
+};
+
+const fn shifted(shift: f32) -> Pose {
+    Pose { shift, ..REST }
+}
+
+const fn grown(scale: f32) -> Pose {
+    Pose {
+        scale_x: scale,
+        scale_y: scale,
+        ..REST
+    }
+}
+
+const fn edge_on(scale_x: f32, opacity: f32) -> Pose {
+    Pose {
+        scale_x,
+        opacity,
+        ..REST
+    }
+}
+
+impl Pose {
+    fn lerp(self, to: Pose, t: f32) -> Pose {
+        let mix = |from: f32, to: f32| from + (to - from) * t;
+        Pose {
+            shift: mix(self.shift, to.shift),
+            scale_x: mix(self.scale_x, to.scale_x),
+            scale_y: mix(self.scale_y, to.scale_y),
+            opacity: mix(self.opacity, to.opacity),
+        }
+    }
+}
+
+// Stops at fractions of the duration, eased between each pair.
+struct Keyframes {
+    duration_ms: i64,
+    easing: (f64, f64, f64, f64),
+    stops: &'static [(f64, Pose)],
+}
+
+const SHAKE: Keyframes = Keyframes {
+    duration_ms: 560,
+    easing: (0.37, 0.0, 0.63, 1.0),
+    stops: &[
+        (0.0, shifted(0.0)),
+        (0.12, shifted(-7.0)),
+        (0.24, shifted(7.0)),
+        (0.36, shifted(-5.0)),
+        (0.48, shifted(5.0)),
+        (0.60, shifted(-3.0)),
+        (0.72, shifted(3.0)),
+        (0.84, shifted(-2.0)),
+        (1.0, shifted(0.0)),
+    ],
+};
+
+const BUMP: Keyframes = Keyframes {
+    duration_ms: 700,
+    easing: (0.22, 0.61, 0.36, 1.0),
+    stops: &[
+        (0.0, grown(1.0)),
+        (0.30, grown(1.10)),
+        (0.65, grown(1.05)),
+        (1.0, grown(1.0)),
+    ],
+};
+
+const TWICE_EDGE_ON: &[(f64, Pose)] = &[
+    (0.0, REST),
+    (0.25, edge_on(0.08, 0.35)),
+    (0.50, REST),
+    (0.75, edge_on(0.08, 0.35)),
+    (1.0, REST),
+];
+
+const RESHUFFLE_FLIP: Keyframes = Keyframes {
+    duration_ms: 760,
+    easing: (0.28, 0.11, 0.32, 1.0),
+    stops: TWICE_EDGE_ON,
+};
+
+const FAST_RESHUFFLE_FLIP: Keyframes = Keyframes {
+    duration_ms: 620,
+    easing: (0.28, 0.11, 0.32, 1.0),
+    stops: TWICE_EDGE_ON,
+};
+
+const ROUND_FLIP: Keyframes = Keyframes {
+    duration_ms: 620,
+    easing: (0.28, 0.11, 0.32, 1.0),
+    stops: &[(0.0, REST), (0.50, edge_on(0.08, 0.35)), (1.0, REST)],
+};
+
+impl Keyframes {
+    fn pose(&self, elapsed_ms: f64) -> Pose {
+        let progress = (elapsed_ms / self.duration_ms as f64).clamp(0.0, 1.0);
+        let (from, to) = self
+            .stops
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|(_, to)| progress <= to.0)
+            .unwrap_or((self.stops[self.stops.len() - 1], self.stops[self.stops.len() - 1]));
+        let span = (to.0 - from.0).max(f64::EPSILON);
+        let eased = cubic_bezier(self.easing, ((progress - from.0) / span).clamp(0.0, 1.0));
+        from.1.lerp(to.1, eased as f32)
+    }
+}
+
+// CSS-style timing curve through (0, 0), the two control points and (1, 1).
+fn cubic_bezier((x1, y1, x2, y2): (f64, f64, f64, f64), t: f64) -> f64 {
+    let sample = |a: f64, b: f64, s: f64| {
+        3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s.powi(2) * (1.0 - s) + s.powi(3)
+    };
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..24 {
+        let mid = (low + high) / 2.0;
+        if sample(x1, x2, mid) < t {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    sample(y1, y2, (low + high) / 2.0)
+}
+
+#[derive(Default)]
+struct Card {
+    flags: u16,
+    // Frame-clock start of each running animation, set on its first frame.
+    animations: Vec<(CardFlag, Option<i64>)>,
+    flip_degrees: f64,
+    flip_animation: Option<adw::TimedAnimation>,
+    // Set while turning face down: the face stays up until the card is edge-on.
+    turning_down: bool,
+}
+
+impl Card {
+    fn has(&self, flag: CardFlag) -> bool {
+        self.flags & flag.bit() != 0
+    }
+
+    // Past the edge a turn shows the back coming round, from the opposite side.
+    fn shown_degrees(&self) -> f64 {
+        if self.flip_degrees > FLIP_EDGE_DEGREES {
+            self.flip_degrees - 2.0 * FLIP_EDGE_DEGREES
+        } else {
+            self.flip_degrees
+        }
+    }
+
+    fn face_held_up(&self) -> bool {
+        self.turning_down && self.flip_degrees < FLIP_EDGE_DEGREES
+    }
+
+    fn pose(&self, now: i64) -> Option<Pose> {
+        let (flag, started) = self
+            .animations
+            .iter()
+            .max_by_key(|(flag, _)| flag.priority())?;
+        let elapsed_ms = started.map_or(0.0, |started| (now - started) as f64 / 1000.0);
+        flag.keyframes().map(|keyframes| keyframes.pose(elapsed_ms))
+    }
+
+    // Starts animations waiting for their first frame and drops finished ones; the flag
+    // itself stays set until the caller removes it. True while any is still playing.
+    fn advance(&mut self, now: i64) -> bool {
+        self.animations.retain_mut(|(flag, started)| {
+            let started = *started.get_or_insert(now);
+            let duration_us = flag.keyframes().map_or(0, |keyframes| keyframes.duration_ms * 1000);
+            now - started < duration_us
+        });
+        !self.animations.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct LayoutKey {
+    text: String,
+    family: &'static str,
+    bold: bool,
+    size_bucket: i32,
+}
+
+type ShapedLayout = (pango::Layout, (i32, i32));
+
+thread_local! {
+    // Shaped layouts with their measured pixel size, shared by every card on the board.
+    static LAYOUT_CACHE: RefCell<HashMap<LayoutKey, ShapedLayout>> =
+        RefCell::new(HashMap::new());
+}
+
+// Nearby sizes share a layout so cascades and resizes do not reshape every frame.
+fn size_bucket(font_size: f64) -> i32 {
+    ((font_size / LAYOUT_SIZE_STEP).round() as i32).max(1)
+}
+
+fn cached_layout(canvas: &BoardCanvas, key: LayoutKey) -> ShapedLayout {
+    LAYOUT_CACHE.with(|cache| {
+        if let Some(entry) = cache.borrow().get(&key) {
+            return entry.clone();
+        }
+        let mut font_desc = pango::FontDescription::new();
+        font_desc.set_family(key.family);
+        if key.bold {
+            font_desc.set_weight(pango::Weight::Bold);
+        }
+        let font_size = f64::from(key.size_bucket) * LAYOUT_SIZE_STEP;
+        font_desc.set_size((font_size * pango::SCALE as f64) as i32);
+
+        let layout = canvas.create_pango_layout(Some(&key.text));
+        layout.set_font_description(Some(&font_desc));
+        let pixel_size = layout.pixel_size();
+        let entry = (layout, pixel_size);
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= LAYOUT_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, entry.clone());
+        entry
+    })
+}
+
+fn card_description(index: usize, cols: i32, tile: &Tile) -> String {
+    let cols = cols.max(1) as usize;
+    let cell = format!(
+        "{}{}",
+        column_label((index % cols) as i32),
+        row_label((index / cols) as i32)
+    );
+    match tile.status {
+        TileStatus::Hidden => tr_format("{cell}, face down", &[("cell", &cell)]),
+        TileStatus::Flipped => {
+            tr_format("{cell}, {symbol}", &[("cell", &cell), ("symbol", &tile.value)])
+        }
+        TileStatus::Matched => {
+            tr_format("{cell}, {symbol}, matched", &[("cell", &cell), ("symbol", &tile.value)])
+        }
+    }
+}
+
+fn rgba((red, green, blue, alpha): (f32, f32, f32, f32)) -> gdk::RGBA {
+    gdk::RGBA::new(red, green, blue, alpha)
+}
+
+// Card colours are the CSS `color` of hidden swatch children, set in the theme
+// stylesheets from style.vars.css, so user.css can still restyle the board.
+const SWATCHES: [&str; 10] = [
+    "card-back",
+    "card-back-hover",
+    "card-face",
+    "card-ring",
+    "card-edge",
+    "accent",
+    "accent-bg",
+    "success",
+    "success-bg",
+    "error-bg",
+];
+
+struct Palette {
+    text: gdk::RGBA,
+    back: gdk::RGBA,
+    back_hover: gdk::RGBA,
+    face: gdk::RGBA,
+    ring: gdk::RGBA,
+    edge: gdk::RGBA,
+    shadow: gdk::RGBA,
+    accent: gdk::RGBA,
+    accent_bg: gdk::RGBA,
+    success: gdk::RGBA,
+    success_bg: gdk::RGBA,
+    error_bg: gdk::RGBA,
+}
+
+impl Palette {
+    fn resolve(canvas: &BoardCanvas) -> Self {
+        let swatches = canvas.imp().swatches.borrow();
+        let swatch = |name: &str| {
+            swatches
+                .iter()
+                .find(|swatch| swatch.has_css_class(name))
+                .map_or_else(|| canvas.color(), WidgetExt::color)
+        };
+        Self {
+            text: canvas.color(),
+            back: swatch("card-back"),
+            back_hover: swatch("card-back-hover"),
+            face: swatch("card-face"),
+            ring: swatch("card-ring"),
+            edge: swatch("card-edge"),
+            shadow: rgba((0.0, 0.0, 0.0, 0.10)),
+            accent: swatch("accent"),
+            accent_bg: swatch("accent-bg"),
+            success: swatch("success"),
+            success_bg: swatch("success-bg"),
+            error_bg: swatch("error-bg"),
+        }
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct BoardCanvas {
+        pub state: RefCell<Weak<RefCell<AppState>>>,
+        pub cols: Cell<i32>,
+        pub rows: Cell<i32>,
+        pub show_coordinates: Cell<bool>,
+        pub(super) cards: RefCell<Vec<Card>>,
+        pub hovered: Cell<Option<usize>>,
+        pub pressed: Cell<Option<usize>>,
+        pub ticking: Cell<bool>,
+        pub swatches: RefCell<Vec<gtk::Widget>>,
+        // Drawn again when a redraw lands while a handler still holds the state.
+        pub last_frame: RefCell<Option<gsk::RenderNode>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BoardCanvas {
+        const NAME: &'static str = "RecallBoardCanvas";
+        type Type = super::BoardCanvas;
+        type ParentType = gtk::Widget;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.set_accessible_role(gtk::AccessibleRole::Grid);
+        }
+    }
+
+    impl ObjectImpl for BoardCanvas {
+        fn dispose(&self) {
+            for swatch in self.swatches.take() {
+                swatch.unparent();
+            }
+        }
+    }
+
+    impl WidgetImpl for BoardCanvas {
+        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            let (width, height) = board::canvas_min_size(
+                self.cols.get(),
+                self.rows.get(),
+                self.show_coordinates.get(),
+            );
+            let size = if orientation == gtk::Orientation::Horizontal { width } else { height };
+            (size, size, -1, -1)
+        }
+
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let Some(state) = self.state.borrow().upgrade() else {
+                return;
+            };
+            let Ok(st) = state.try_borrow() else {
+                if let Some(node) = self.last_frame.borrow().as_ref() {
+                    snapshot.append_node(node);
+                }
+                return;
+            };
+            let frame = gtk::Snapshot::new();
+            self.obj().draw_board(&frame, &st);
+            if let Some(node) = frame.to_node() {
+                snapshot.append_node(&node);
+                self.last_frame.replace(Some(node));
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    // The whole board in one widget: cards are drawn in its snapshot and picked by position.
+    pub struct BoardCanvas(ObjectSubclass<imp::BoardCanvas>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl BoardCanvas {
+    pub fn new(state: &Rc<RefCell<AppState>>, cols: i32, rows: i32, show_coordinates: bool) -> Self {
+        let canvas: Self = glib::Object::new();
+        canvas.add_css_class("recall-board");
+        canvas.set_focusable(true);
+        let imp = canvas.imp();
+        imp.state.replace(Rc::downgrade(state));
+        imp.cols.set(cols.max(1));
+        imp.rows.set(rows.max(1));
+        imp.show_coordinates.set(show_coordinates);
+        imp.cards
+            .replace((0..(cols * rows).max(0)).map(|_| Card::default()).collect());
+        for name in SWATCHES {
+            let swatch = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            swatch.add_css_class("board-swatch");
+            swatch.add_css_class(name);
+            swatch.set_visible(false);
+            swatch.set_parent(&canvas);
+            imp.swatches.borrow_mut().push(swatch.upcast());
+        }
+        canvas.connect_input();
+        canvas
+    }
+
+    pub fn card_count(&self) -> usize {
+        self.imp().cards.borrow().len()
+    }
+
+    fn geometry(&self) -> BoardGeometry {
+        let imp = self.imp();
+        BoardGeometry::new(
+            self.width(),
+            self.height(),
+            imp.cols.get(),
+            imp.rows.get(),
+            imp.show_coordinates.get(),
+        )
+    }
+
+    fn index_at(&self, x: f64, y: f64) -> Option<usize> {
+        self.geometry()
+            .index_at(x, y)
+            .filter(|index| *index < self.card_count())
+    }
+
+    fn with_card<T>(&self, index: usize, update: impl FnOnce(&mut Card) -> T) -> Option<T> {
+        self.imp().cards.borrow_mut().get_mut(index).map(update)
+    }
+
+    pub fn has_flag(&self, index: usize, flag: CardFlag) -> bool {
+        self.imp()
+            .cards
+            .borrow()
+            .get(index)
+            .is_some_and(|card| card.has(flag))
+    }
+
+    // Setting an animation again restarts it.
+    pub fn add_flag(&self, index: usize, flag: CardFlag) {
+        let animated = flag.keyframes().is_some();
+        let found = self.with_card(index, |card| {
+            card.flags |= flag.bit();
+            if animated {
+                card.animations.retain(|(running, _)| *running != flag);
+                card.animations.push((flag, None));
+            }
+        });
+        if found.is_some() {
+            if animated {
+                self.ensure_ticking();
+            }
+            self.queue_draw();
+        }
+    }
+
+    pub fn remove_flag(&self, index: usize, flag: CardFlag) {
+        let changed = self.with_card(index, |card| {
+            let had = card.has(flag);
+            card.flags &= !flag.bit();
+            card.animations.retain(|(running, _)| *running != flag);
+            had
+        });
+        if changed == Some(true) {
+            self.queue_draw();
+        }
+    }
+
+    pub fn remove_flags_everywhere(&self, flags: &[CardFlag]) {
+        let mask = flags.iter().fold(0, |mask, flag| mask | flag.bit());
+        for card in self.imp().cards.borrow_mut().iter_mut() {
+            card.flags &= !mask;
+            card.animations.retain(|(running, _)| !flags.contains(running));
+        }
+        self.queue_draw();
+    }
+
+    pub fn focused_index(&self) -> Option<usize> {
+        self.imp()
+            .cards
+            .borrow()
+            .iter()
+            .position(|card| card.has(CardFlag::KeyboardFocus))
+    }
+
+    // Moves the keyboard ring to one card, or drops it with `None`.
+    pub fn focus_card(&self, index: Option<usize>) {
+        for (card_index, card) in self.imp().cards.borrow_mut().iter_mut().enumerate() {
+            if Some(card_index) == index {
+                card.flags |= CardFlag::KeyboardFocus.bit();
+            } else {
+                card.flags &= !CardFlag::KeyboardFocus.bit();
+            }
+        }
+        self.describe_card(index);
+        self.queue_draw();
+    }
+
+    // Cards have no widgets of their own, so screen readers hear the focused one here.
+    fn describe_card(&self, index: Option<usize>) {
+        let cols = self.imp().cols.get();
+        let description = index
+            .and_then(|index| {
+                let state = self.imp().state.borrow().upgrade()?;
+                let st = state.try_borrow().ok()?;
+                Some(card_description(index, cols, st.tiles.get(index)?))
+            })
+            .unwrap_or_default();
+        self.update_property(&[gtk::accessible::Property::Description(&description)]);
+    }
+
+    fn ensure_ticking(&self) {
+        if self.imp().ticking.replace(true) {
+            return;
+        }
+        self.add_tick_callback(|canvas, clock| {
+            let now = clock.frame_time();
+            let running = canvas
+                .imp()
+                .cards
+                .borrow_mut()
+                .iter_mut()
+                .fold(false, |running, card| card.advance(now) | running);
+            canvas.queue_draw();
+            if running {
+                glib::ControlFlow::Continue
+            } else {
+                canvas.imp().ticking.set(false);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    fn connect_input(&self) {
+        let click = gtk::GestureClick::new();
+        click.set_button(gdk::BUTTON_PRIMARY);
+        click.connect_pressed(|gesture, _, x, y| {
+            if let Some(canvas) = gesture.widget().and_downcast::<BoardCanvas>() {
+                canvas.imp().pressed.set(canvas.index_at(x, y));
+                canvas.queue_draw();
+            }
+        });
+        click.connect_released(|gesture, _, x, y| {
+            let Some(canvas) = gesture.widget().and_downcast::<BoardCanvas>() else {
+                return;
+            };
+            let pressed = canvas.imp().pressed.take();
+            canvas.queue_draw();
+            let Some(state) = canvas.imp().state.borrow().upgrade() else {
+                return;
+            };
+            if let Some(index) = pressed.filter(|index| canvas.index_at(x, y) == Some(*index)) {
+                handle_tile_click(&state, index);
+            }
+        });
+        click.connect_stopped(|gesture| {
+            if let Some(canvas) = gesture.widget().and_downcast::<BoardCanvas>()
+                && canvas.imp().pressed.take().is_some()
+            {
+                canvas.queue_draw();
+            }
+        });
+        self.add_controller(click);
+
+        // Claiming the press keeps the release from also flipping the card.
+        let long_press = gtk::GestureLongPress::new();
+        long_press.connect_pressed(|gesture, x, y| {
+            let Some(canvas) = gesture.widget().and_downcast::<BoardCanvas>() else {
+                return;
+            };
+            let Some(state) = canvas.imp().state.borrow().upgrade() else {
+                return;
+            };
+            if let Some(index) = canvas.index_at(x, y)
+                && tags::cycle(&mut state.borrow_mut(), index)
+            {
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+            }
+        });
+        self.add_controller(long_press);
+
+        let motion = gtk::EventControllerMotion::new();
+        motion.connect_motion(|controller, x, y| {
+            if let Some(canvas) = controller.widget().and_downcast::<BoardCanvas>() {
+                canvas.set_hovered(canvas.index_at(x, y));
+            }
+        });
+        motion.connect_leave(|controller| {
+            if let Some(canvas) = controller.widget().and_downcast::<BoardCanvas>() {
+                canvas.set_hovered(None);
+            }
+        });
+        self.add_controller(motion);
+    }
+
+    // Pointing at a card hands focus back from the keyboard.
+    fn set_hovered(&self, index: Option<usize>) {
+        if self.imp().hovered.replace(index) == index {
+            return;
+        }
+        if index.is_some() && self.focused_index().is_some() {
+            self.focus_card(None);
+        }
+        self.queue_draw();
+    }
+}
+
+impl BoardCanvas {
+    fn animate_flip(&self, index: usize, from: f64, to: f64, duration_ms: u64, easing: adw::Easing) {
+        if let Some(previous) = self.with_card(index, |card| card.flip_animation.take()).flatten() {
+            previous.pause();
+        }
+        let target = adw::CallbackAnimationTarget::new({
+            let canvas = self.downgrade();
+            move |degrees| {
+                if let Some(canvas) = canvas.upgrade() {
+                    canvas.with_card(index, |card| card.flip_degrees = degrees);
+                    canvas.queue_draw();
+                }
+            }
+        });
+        let animation = adw::TimedAnimation::new(self, from, to, duration_ms as u32, target);
+        animation.set_easing(easing);
+        let pending = RefCell::new(Some(perf_hud::Pending::new(PendingKind::Animation)));
+        animation.connect_done(move |_| {
+            pending.take();
+        });
+        animation.play();
+        self.with_card(index, |card| card.flip_animation = Some(animation));
+    }
+
+    // The tile may already be hidden: its face keeps showing until the card is edge-on,
+    // and the turn settles itself once it is done.
+    pub fn turn_face_down(&self, index: usize) {
+        let Some(from) = self.with_card(index, |card| {
+            let from = if card.turning_down { card.flip_degrees } else { card.flip_degrees.abs() };
+            card.turning_down = true;
+            from
+        }) else {
+            return;
+        };
+        let to = 2.0 * FLIP_EDGE_DEGREES;
+        self.animate_flip(index, from, to, TURN_DOWN_MS, adw::Easing::EaseInOutCubic);
+        let Some(animation) = self.with_card(index, |card| card.flip_animation.clone()).flatten() else {
+            return;
+        };
+        animation.connect_done({
+            let canvas = self.downgrade();
+            move |finished| {
+                let Some(canvas) = canvas.upgrade() else {
+                    return;
+                };
+                let current = canvas
+                    .with_card(index, |card| card.flip_animation.as_ref() == Some(finished))
+                    .unwrap_or(false);
+                if current {
+                    canvas.settle_flip(index);
+                }
+            }
+        });
+    }
+
+    // Turns the card to face the player from the opposite edge, continuing any turn in progress.
+    pub fn flip_reveal(&self, index: usize) {
+        let Some(degrees) = self.with_card(index, |card| {
+            card.turning_down = false;
+            card.shown_degrees().abs()
+        }) else {
+            return;
+        };
+        let from = if degrees > f64::EPSILON { -degrees } else { -FLIP_EDGE_DEGREES };
+        self.animate_flip(index, from, 0.0, FLIP_PHASE_MS, adw::Easing::EaseOutCubic);
+    }
+
+    pub fn settle_flip(&self, index: usize) {
+        if let Some(animation) = self.with_card(index, |card| card.flip_animation.take()).flatten() {
+            animation.pause();
+        }
+        self.with_card(index, |card| {
+            card.flip_degrees = 0.0;
+            card.turning_down = false;
+        });
+        self.queue_draw();
+    }
+
+    // Stops any flip on the card, both the turn and the reshuffle or round animations.
+    pub fn clear_flip(&self, index: usize) {
+        self.settle_flip(index);
+        for flag in [CardFlag::ReshuffleFlip, CardFlag::FastReshuffleFlip, CardFlag::RoundFlip] {
+            self.remove_flag(index, flag);
+        }
+    }
+
+    pub fn clear_flips(&self) {
+        for index in 0..self.card_count() {
+            self.clear_flip(index);
+        }
+    }
+
+    // Back to plain cards: no highlights, animations or flips left over.
+    pub fn reset_cards(&self) {
+        self.clear_flips();
+        for card in self.imp().cards.borrow_mut().iter_mut() {
+            card.flags = 0;
+            card.animations.clear();
+        }
+        self.queue_draw();
+    }
+}
+
+impl BoardCanvas {
+    fn draw_board(&self, snapshot: &gtk::Snapshot, st: &AppState) {
+        if self.width() <= 0 || self.height() <= 0 {
+            return;
+        }
+        let imp = self.imp();
+        let geometry = self.geometry();
+        let palette = Palette::resolve(self);
+        if imp.show_coordinates.get() {
+            self.draw_coordinates(snapshot, &geometry, &palette);
+        }
+
+        let now = self.frame_clock().map_or(0, |clock| clock.frame_time());
+        let no_hover = st
+            .board_container
+            .as_ref()
+            .is_some_and(|container| container.has_css_class("no-hover"));
+        let hovered = imp.hovered.get().filter(|_| !no_hover);
+        let pressed = imp.pressed.get();
+        for (index, card) in imp.cards.borrow().iter().enumerate() {
+            let rect = geometry.card_rect(index);
+            let tile = st.tiles.get(index);
+            let face_up =
+                card.face_held_up() || tile.is_some_and(|tile| tile.status != TileStatus::Hidden);
+            let look = CardLook {
+                face_up,
+                revealed: pressed == Some(index) || face_up,
+                dimmed: card.has(CardFlag::Dim),
+                hovered: hovered == Some(index),
+                lifts: hovered == Some(index)
+                    && tile.is_none_or(|tile| tile.status != TileStatus::Matched),
+            };
+            snapshot.save();
+            let half = rect.width() / 2.0;
+            snapshot.translate(&graphene::Point::new(rect.x() + half, rect.y() + half));
+            let pose = card.pose(now);
+            let lift = if pose.is_none() && look.lifts { HOVER_SCALE } else { 1.0 };
+            let pose = pose.unwrap_or(REST);
+            if pose.shift != 0.0 {
+                snapshot.translate(&graphene::Point::new(pose.shift, 0.0));
+            }
+            let degrees = card.shown_degrees();
+            if degrees.abs() > f64::EPSILON {
+                snapshot.perspective(rect.width() * FLIP_PERSPECTIVE_FACTOR);
+                snapshot.rotate_3d(degrees as f32, &graphene::Vec3::y_axis());
+            }
+            snapshot.scale(pose.scale_x * lift, pose.scale_y * lift);
+            snapshot.translate(&graphene::Point::new(-half, -half));
+            let faded = pose.opacity < 1.0;
+            if faded {
+                snapshot.push_opacity(f64::from(pose.opacity));
+            }
+            draw_card_body(snapshot, rect.width(), geometry.card_radius(), card, &look, &palette);
+            if let Some(tile) = tile {
+                self.draw_face(snapshot, st, tile, &look, rect.width(), &palette);
+            }
+            if faded {
+                snapshot.pop();
+            }
+            snapshot.restore();
+        }
+    }
+
+    fn draw_coordinates(&self, snapshot: &gtk::Snapshot, geometry: &BoardGeometry, palette: &Palette) {
+        let imp = self.imp();
+        let mut color = palette.text;
+        color.set_alpha(color.alpha() * COORDINATE_ALPHA);
+        for col in 0..imp.cols.get() {
+            self.draw_label(snapshot, &column_label(col), geometry.column_label_rect(col), &color);
+        }
+        for row in 0..imp.rows.get() {
+            self.draw_label(snapshot, &row_label(row), geometry.row_label_rect(row), &color);
+        }
+    }
+
+    fn draw_label(&self, snapshot: &gtk::Snapshot, text: &str, bounds: graphene::Rect, color: &gdk::RGBA) {
+        let layout = self.create_pango_layout(Some(text));
+        let attributes = pango::AttrList::new();
+        attributes.insert(pango::AttrInt::new_weight(pango::Weight::Bold));
+        attributes.insert(pango::AttrFloat::new_scale(pango::SCALE_SMALL));
+        layout.set_attributes(Some(&attributes));
+        let (width, height) = layout.pixel_size();
+        snapshot.save();
+        snapshot.translate(&graphene::Point::new(
+            bounds.x() + (bounds.width() - width as f32) / 2.0,
+            bounds.y() + (bounds.height() - height as f32) / 2.0,
+        ));
+        snapshot.append_layout(&layout, color);
+        snapshot.restore();
+    }
+
+    fn draw_face(
+        &self,
+        snapshot: &gtk::Snapshot,
+        st: &AppState,
+        tile: &Tile,
+        look: &CardLook,
+        size: f32,
+        palette: &Palette,
+    ) {
+        // Pattern rounds only use the card backs.
+        if st.pattern.is_some() {
+            return;
+        }
+        let peeked = !look.face_up && peek::shows(st, tile);
+        let is_hidden = !look.face_up && !peeked;
+        if is_hidden && st.nback.is_some() {
+            return;
+        }
+        let side = size.round() as i32;
+        if side <= 0 {
+            return;
+        }
+
+        let dimmed = look.dimmed || peeked;
+        if dimmed {
+            snapshot.push_opacity(if peeked { PEEK_OPACITY } else { MATCHED_DIM_OPACITY });
+        }
+
+        let min_dim = f64::from(size);
+        let drew_face = !is_hidden && st.artwork_faces && {
+            let bounds = graphene::Rect::new(0.0, 0.0, size, size);
+            let cr = snapshot.append_cairo(&bounds);
+            faces::draw_face(&cr, &tile.value, side, side, min_dim * 0.62)
+        };
+        if !drew_face {
+            self.draw_text(snapshot, st, &tile.value, is_hidden, size, palette);
+        }
+        if let Some(tag) = tile.tag.filter(|_| is_hidden) {
+            // A row of marks rather than one bar, so the count tells tags apart too.
+            let dot = (min_dim * TAG_BAND_FACTOR).max(2.0) as f32;
+            let marks = tag.marks() as f32;
+            let left = (size - (marks * 2.0 - 1.0) * dot) / 2.0;
+            for mark in 0..tag.marks() {
+                let x = left + mark as f32 * dot * 2.0;
+                let bounds = graphene::Rect::new(x, size - dot * 2.0, dot, dot);
+                snapshot.append_color(&tag.rgba(), &bounds);
+            }
+        }
+
+        if dimmed {
+            snapshot.pop();
+        }
+    }
+
+    fn draw_text(
+        &self,
+        snapshot: &gtk::Snapshot,
+        st: &AppState,
+        value: &str,
+        is_hidden: bool,
+        size: f32,
+        palette: &Palette,
+    ) {
+        let min_dim = f64::from(size);
+        let text = if is_hidden { "?" } else { value };
+        let font_size = if is_hidden {
+            fonts::tile_font_size(min_dim * 0.34, min_dim * 0.55, st.text_scale)
+        } else {
+            min_dim * 0.40
+        };
+
+        let key = LayoutKey {
+            text: text.to_string(),
+            family: if is_hidden { st.font_choice.family() } else { EMOJI_FAMILY },
+            bold: is_hidden,
+            size_bucket: size_bucket(font_size),
+        };
+        let (layout, (text_width, text_height)) = cached_layout(self, key);
+        let text_x = (size - text_width as f32) / 2.0;
+        let text_y = (size - text_height as f32) / 2.0;
+
+        snapshot.save();
+        snapshot.translate(&graphene::Point::new(text_x, text_y));
+        snapshot.append_layout(&layout, &palette.text);
+        snapshot.restore();
+    }
+}
+
+struct CardLook {
+    // Shown face up, whether by its tile or by a turn not yet edge-on.
+    face_up: bool,
+    // Face up, or held down under the pointer.
+    revealed: bool,
+    dimmed: bool,
+    hovered: bool,
+    lifts: bool,
+}
+
+fn draw_card_body(
+    snapshot: &gtk::Snapshot,
+    size: f32,
+    radius: f32,
+    card: &Card,
+    look: &CardLook,
+    palette: &Palette,
+) {
+    let bounds = graphene::Rect::new(0.0, 0.0, size, size);
+    let outline = gsk::RoundedRect::from_rect(bounds, radius);
+    snapshot.append_outset_shadow(&outline, &palette.shadow, 0.0, 2.0, 0.0, 2.0);
+
+    let highlight = if card.has(CardFlag::PatternHit) {
+        Some(palette.success_bg)
+    } else if card.has(CardFlag::PatternMiss) {
+        Some(palette.error_bg)
+    } else if card.has(CardFlag::PatternLit) || card.has(CardFlag::NBackCue) {
+        Some(palette.accent_bg)
+    } else {
+        None
+    };
+    let background = highlight.unwrap_or(if look.revealed {
+        palette.face
+    } else if look.hovered {
+        palette.back_hover
+    } else {
+        palette.back
+    });
+    snapshot.push_rounded_clip(&outline);
+    snapshot.append_color(&background, &bounds);
+    snapshot.pop();
+    snapshot.append_border(&outline, &[0.0, 0.0, 1.0, 0.0], &[palette.edge; 4]);
+
+    let hint = if card.has(CardFlag::HintMatch) {
+        Some(palette.success)
+    } else if card.has(CardFlag::HintExplore) {
+        Some(palette.accent)
+    } else {
+        None
+    };
+    let ring = hint.map(|color| (color, HINT_RING_WIDTH)).or_else(|| {
+        (look.revealed && highlight.is_none()).then(|| {
+            (palette.ring, (size * FACE_RING_FACTOR).round().clamp(FACE_RING_MIN, FACE_RING_MAX))
+        })
+    });
+    if let Some((color, width)) = ring {
+        snapshot.append_inset_shadow(&outline, &color, 0.0, 0.0, width, 0.0);
+    }
+
+    if card.has(CardFlag::KeyboardFocus) {
+        let inset = graphene::Rect::new(1.0, 1.0, size - 2.0, size - 2.0);
+        let focus = gsk::RoundedRect::from_rect(inset, (radius - 1.0).max(0.0));
+        let mut color = palette.text;
+        color.set_alpha(color.alpha() * FOCUS_RING_ALPHA);
+        snapshot.append_border(&focus, &[FOCUS_RING_WIDTH; 4], &[color; 4]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_font_sizes_share_a_bucket() {
+        assert_eq!(size_bucket(31.2), size_bucket(32.8));
+        assert_ne!(size_bucket(30.0), size_bucket(34.0));
+        assert_eq!(size_bucket(0.2), 1);
+    }
+
+    #[test]
+    fn focused_cards_are_described_by_cell_and_face() {
+        let mut tile = Tile {
+            value: "🍎".to_string(),
+            status: TileStatus::Hidden,
+            seen: false,
+            views: 0,
+            tag: None,
+        };
+        assert_eq!(card_description(5, 4, &tile), "B2, face down");
+        tile.status = TileStatus::Flipped;
+        assert_eq!(card_description(0, 4, &tile), "A1, 🍎");
+        tile.status = TileStatus::Matched;
+        assert_eq!(card_description(11, 4, &tile), "D3, 🍎, matched");
+    }
+
+    #[test]
+    fn card_animations_settle_back_at_rest() {
+        for flag in CardFlag::ANIMATIONS {
+            let keyframes = flag.keyframes().expect("every listed animation has keyframes");
+            assert_eq!(keyframes.pose(0.0), REST);
+            assert_eq!(keyframes.pose(keyframes.duration_ms as f64 + 50.0), REST);
+        }
+        let peak = BUMP.pose(0.30 * BUMP.duration_ms as f64);
+        assert!((peak.scale_x - 1.10).abs() < 1e-4);
+        assert!(cubic_bezier((0.37, 0.0, 0.63, 1.0), 0.25) < cubic_bezier((0.37, 0.0, 0.63, 1.0), 0.75));
+    }
+
+    #[test]
+    fn a_bump_outranks_a_shake_until_both_finish() {
+        let mut card = Card::default();
+        card.animations.push((CardFlag::Bump, Some(0)));
+        card.animations.push((CardFlag::Shake, Some(0)));
+        let at = |ms: i64| ms * 1000;
+        assert_eq!(card.pose(at(210)), Some(BUMP.pose(210.0)));
+        assert!(card.advance(at(600)));
+        assert_eq!(card.pose(at(600)), Some(BUMP.pose(600.0)));
+        assert!(!card.advance(at(800)));
+        assert_eq!(card.pose(at(800)), None);
+    }
+
+    #[test]
+    fn a_card_turning_down_shows_its_face_until_edge_on() {
+        let mut card = Card {
+            turning_down: true,
+            flip_degrees: 60.0,
+            ..Default::default()
+        };
+        assert!(card.face_held_up());
+        assert_eq!(card.shown_degrees(), 60.0);
+        card.flip_degrees = 135.0;
+        assert!(!card.face_held_up());
+        assert_eq!(card.shown_degrees(), -45.0);
+    }
+}
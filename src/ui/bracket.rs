@@ -0,0 +1,244 @@
+use crate::i18n::{tr, tr_format};
+
+use super::hotseat;
+
+pub const MIN_PLAYERS: usize = 3;
+pub const MAX_PLAYERS: usize = 8;
+
+// Standard bracket order, 1v8, 4v5, 2v7, 3v6 for eight: each seed meets the
+// one mirroring it, and the top two land in opposite halves.
+fn seed_order(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let count = order.len() * 2;
+        order = order
+            .into_iter()
+            .flat_map(|seed| [seed, count - 1 - seed])
+            .collect();
+    }
+    order
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BracketMatch {
+    pub first: usize,
+    // No opponent is a bye, which the first player wins outright.
+    pub second: Option<usize>,
+    pub winner: Option<usize>,
+}
+
+// Single elimination in entry order: the first entries are seeded highest,
+// take the byes when the field is not a power of two and only meet late.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bracket {
+    pub level: u8,
+    pub players: Vec<String>,
+    pub rounds: Vec<Vec<BracketMatch>>,
+}
+
+impl Bracket {
+    pub fn new(level: u8, names: Vec<String>) -> Self {
+        let players: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let name = hotseat::clean_name(name);
+                if name.is_empty() { hotseat::default_name(index) } else { name }
+            })
+            .collect();
+        let size = players.len().max(2).next_power_of_two();
+        let first_round = seed_order(size)
+            .chunks(2)
+            .map(|pair| {
+                let second = Some(pair[1]).filter(|seed| *seed < players.len());
+                BracketMatch {
+                    first: pair[0],
+                    second,
+                    winner: second.is_none().then_some(pair[0]),
+                }
+            })
+            .collect();
+        let mut bracket = Self {
+            level: level.clamp(1, 4),
+            players,
+            rounds: vec![first_round],
+        };
+        bracket.advance();
+        bracket
+    }
+
+    fn advance(&mut self) {
+        while let Some(last) = self.rounds.last() {
+            if last.len() < 2 || last.iter().any(|game| game.winner.is_none()) {
+                return;
+            }
+            let next = last
+                .chunks(2)
+                .map(|pair| {
+                    let first = pair[0].winner.unwrap_or_default();
+                    BracketMatch {
+                        first,
+                        second: pair.get(1).and_then(|game| game.winner),
+                        winner: None,
+                    }
+                })
+                .collect();
+            self.rounds.push(next);
+        }
+    }
+
+    pub fn current_match(&self) -> Option<BracketMatch> {
+        self.rounds.last()?.iter().find(|game| game.winner.is_none()).copied()
+    }
+
+    // The two players of the next match, first to play first.
+    pub fn current_pair(&self) -> Option<(usize, usize)> {
+        let game = self.current_match()?;
+        Some((game.first, game.second?))
+    }
+
+    pub fn record_winner(&mut self, winner: usize) -> bool {
+        let Some(game) = self
+            .rounds
+            .last_mut()
+            .and_then(|round| round.iter_mut().find(|game| game.winner.is_none()))
+        else {
+            return false;
+        };
+        if winner != game.first && Some(winner) != game.second {
+            return false;
+        }
+        game.winner = Some(winner);
+        self.advance();
+        true
+    }
+
+    pub fn champion(&self) -> Option<usize> {
+        let last = self.rounds.last()?;
+        if last.len() == 1 { last[0].winner } else { None }
+    }
+
+    pub fn name(&self, player: usize) -> &str {
+        self.players.get(player).map(String::as_str).unwrap_or_default()
+    }
+
+    fn round_title(&self, round: usize) -> String {
+        let total = self.players.len().max(2).next_power_of_two().trailing_zeros() as usize;
+        match total.saturating_sub(round) {
+            1 => tr("Final"),
+            2 => tr("Semifinal"),
+            3 => tr("Quarterfinal"),
+            _ => tr_format("Round {number}", &[("number", &(round + 1))]),
+        }
+    }
+
+    pub fn mode_label(&self) -> String {
+        format!(
+            "{} · {}",
+            tr("Bracket"),
+            self.round_title(self.rounds.len().saturating_sub(1))
+        )
+    }
+
+    // One line per played match, byes left out.
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (round, games) in self.rounds.iter().enumerate() {
+            for game in games {
+                let (Some(second), Some(winner)) = (game.second, game.winner) else {
+                    continue;
+                };
+                let loser = if winner == game.first { second } else { game.first };
+                lines.push(tr_format(
+                    "{round}: {winner} beat {loser}",
+                    &[
+                        ("round", &self.round_title(round)),
+                        ("winner", &self.name(winner)),
+                        ("loser", &self.name(loser)),
+                    ],
+                ));
+            }
+        }
+        lines
+    }
+
+    pub fn to_code(&self) -> String {
+        let winners = self
+            .rounds
+            .iter()
+            .flatten()
+            .filter(|game| game.second.is_some())
+            .filter_map(|game| game.winner)
+            .map(|winner| winner.to_string())
+            .collect::<Vec<String>>();
+        format!("{};{};{}", self.level, self.players.join(","), winners.join(","))
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let mut parts = code.trim().splitn(3, ';');
+        let level: u8 = parts.next()?.parse().ok()?;
+        let names: Vec<String> = parts.next()?.split(',').map(str::to_string).collect();
+        if !(1..=4).contains(&level) || !(MIN_PLAYERS..=MAX_PLAYERS).contains(&names.len()) {
+            return None;
+        }
+        let mut bracket = Self::new(level, names);
+        for winner in parts.next()?.split(',').filter(|winner| !winner.is_empty()) {
+            if !bracket.record_winner(winner.parse().ok()?) {
+                return None;
+            }
+        }
+        bracket.champion().is_none().then_some(bracket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(count: usize) -> Vec<String> {
+        (1..=count).map(|index| format!("P{index}")).collect()
+    }
+
+    #[test]
+    fn top_seeds_take_the_byes() {
+        let bracket = Bracket::new(1, names(5));
+        let first_round = &bracket.rounds[0];
+        assert_eq!(first_round.len(), 4);
+        assert_eq!(first_round.iter().filter(|game| game.second.is_none()).count(), 3);
+        assert_eq!(bracket.current_pair(), Some((3, 4)));
+    }
+
+    #[test]
+    fn top_seeds_meet_only_in_the_final() {
+        assert_eq!(seed_order(8), vec![0, 7, 3, 4, 1, 6, 2, 5]);
+        let mut bracket = Bracket::new(1, names(8));
+        while let Some((first, _)) = bracket.current_pair() {
+            bracket.record_winner(first);
+        }
+        assert_eq!((bracket.rounds[1][0].first, bracket.rounds[1][0].second), (0, Some(3)));
+        assert_eq!((bracket.rounds[2][0].first, bracket.rounds[2][0].second), (0, Some(1)));
+        assert_eq!(bracket.champion(), Some(0));
+    }
+
+    #[test]
+    fn winners_advance_to_a_champion() {
+        let mut bracket = Bracket::new(2, names(3));
+        assert_eq!(bracket.current_pair(), Some((1, 2)));
+        assert!(!bracket.record_winner(0));
+        assert!(bracket.record_winner(2));
+        assert_eq!(bracket.current_pair(), Some((0, 2)));
+        assert!(bracket.record_winner(2));
+        assert_eq!(bracket.champion(), Some(2));
+        assert_eq!(bracket.summary_lines().len(), 2);
+    }
+
+    #[test]
+    fn progress_code_roundtrip() {
+        let mut bracket = Bracket::new(3, names(6));
+        assert_eq!(Bracket::from_code(&bracket.to_code()), Some(bracket.clone()));
+        let (first, _) = bracket.current_pair().expect("expected a first match");
+        bracket.record_winner(first);
+        assert_eq!(Bracket::from_code(&bracket.to_code()), Some(bracket.clone()));
+        assert!(Bracket::from_code("3;P1,P2;").is_none());
+    }
+}
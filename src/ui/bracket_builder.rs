@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk4 as gtk;
+use libadwaita as adw;
+
+use crate::i18n::tr;
+
+use super::app::apply_bracket_start;
+use super::bracket::{Bracket, MAX_PLAYERS, MIN_PLAYERS};
+use super::classic::CLASSIC_LEVEL_OPTIONS;
+use super::hotseat;
+use super::hotseat_builder::combo_row;
+use super::mode_dialogs::{self, ModeChoice};
+use super::records::classic_level_name;
+use super::state::AppState;
+
+pub fn build_bracket_page(
+    state: &Rc<RefCell<AppState>>,
+    mode_dialog: &adw::Dialog,
+) -> adw::NavigationPage {
+    let level_labels: Vec<String> = CLASSIC_LEVEL_OPTIONS
+        .iter()
+        .map(|level| tr(classic_level_name(*level)))
+        .collect();
+    let level_row = combo_row(&tr("Difficulty"), &level_labels, 0);
+    let count_row = adw::SpinRow::with_range(MIN_PLAYERS as f64, MAX_PLAYERS as f64, 1.0);
+    count_row.set_title(&tr("Players"));
+    count_row.set_value(MIN_PLAYERS as f64);
+
+    let board_group = adw::PreferencesGroup::new();
+    board_group.set_description(Some(&tr(
+        "Players meet two at a time on the same deal. The better score moves on until one champion is left",
+    )));
+    board_group.add(&level_row);
+    board_group.add(&count_row);
+
+    // Entry order is the seeding; the first players get any byes.
+    let players_group = adw::PreferencesGroup::new();
+    players_group.set_title(&tr("Players"));
+    let names: Rc<Vec<adw::EntryRow>> = Rc::new(
+        (0..MAX_PLAYERS)
+            .map(|index| {
+                let row = adw::EntryRow::builder().title(hotseat::default_name(index)).build();
+                row.set_text(&hotseat::default_name(index));
+                row.set_visible(index < MIN_PLAYERS);
+                players_group.add(&row);
+                row
+            })
+            .collect(),
+    );
+    count_row.connect_value_notify({
+        let names = names.clone();
+        move |row| {
+            let count = row.value() as usize;
+            for (index, name) in names.iter().enumerate() {
+                name.set_visible(index < count);
+            }
+        }
+    });
+
+    let page = adw::PreferencesPage::new();
+    page.add(&board_group);
+    page.add(&players_group);
+
+    let start = gtk::Button::with_label(&tr("Start"));
+    start.add_css_class("suggested-action");
+    start.add_css_class("pill");
+    start.set_halign(gtk::Align::Center);
+    start.connect_clicked({
+        let state = state.clone();
+        let mode_dialog = mode_dialog.clone();
+        move |_| {
+            let level = CLASSIC_LEVEL_OPTIONS
+                .get(level_row.selected() as usize)
+                .copied()
+                .unwrap_or(1);
+            let count = count_row.value() as usize;
+            let bracket = Bracket::new(
+                level,
+                names.iter().take(count).map(|name| name.text().to_string()).collect(),
+            );
+            mode_dialogs::remember_choice(ModeChoice::Bracket, level);
+            apply_bracket_start(&state, bracket);
+            mode_dialog.close();
+        }
+    });
+    let start_group = adw::PreferencesGroup::new();
+    start_group.add(&start);
+    page.add(&start_group);
+
+    let header = adw::HeaderBar::new();
+    header.set_show_end_title_buttons(true);
+    header.set_show_back_button(true);
+    header.add_css_class("flat");
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&page));
+
+    adw::NavigationPage::builder()
+        .title(tr("Bracket"))
+        .child(&toolbar)
+        .build()
+}
@@ -0,0 +1,114 @@
+use crate::i18n::{ntr_format, tr};
+
+use super::state::AppState;
+
+pub const BREAK_REMINDER_KEY: &str = "break-reminder";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BreakReminder {
+    #[default]
+    Off,
+    Rounds(u32),
+    Minutes(u32),
+}
+
+impl BreakReminder {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Rounds(10),
+        Self::Rounds(25),
+        Self::Minutes(15),
+        Self::Minutes(30),
+    ];
+
+    pub fn code(self) -> String {
+        match self {
+            Self::Off => "off".to_string(),
+            Self::Rounds(rounds) => format!("rounds-{rounds}"),
+            Self::Minutes(minutes) => format!("minutes-{minutes}"),
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|reminder| reminder.code() == code)
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Off => tr("Off"),
+            Self::Rounds(rounds) => {
+                ntr_format("Every {count} round", "Every {count} rounds", rounds, &[])
+            }
+            Self::Minutes(minutes) => ntr_format(
+                "Every {count} minute",
+                "Every {count} minutes",
+                minutes,
+                &[],
+            ),
+        }
+    }
+
+    pub fn is_due(self, rounds_since_break: u32, secs_since_break: u32) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Rounds(rounds) => rounds_since_break >= rounds,
+            Self::Minutes(minutes) => secs_since_break >= minutes * 60,
+        }
+    }
+}
+
+// Called between rounds, once the next round number is already set.
+pub fn break_due(st: &AppState) -> bool {
+    let (round_mark, secs_mark) = st.break_mark;
+    st.break_reminder.is_due(
+        st.infinite_round.saturating_sub(round_mark),
+        st.seconds_elapsed.saturating_sub(secs_mark),
+    )
+}
+
+pub fn mark_break(st: &mut AppState) {
+    st.break_mark = (st.infinite_round, st.seconds_elapsed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reminder_codes_roundtrip_and_fall_back_to_off() {
+        for reminder in BreakReminder::ALL {
+            assert_eq!(BreakReminder::from_code(&reminder.code()), reminder);
+        }
+        assert_eq!(BreakReminder::from_code("rounds-7"), BreakReminder::Off);
+    }
+
+    #[test]
+    fn labels_show_the_configured_count() {
+        assert_eq!(BreakReminder::Rounds(25).label(), "Every 25 rounds");
+        assert_eq!(BreakReminder::Rounds(40).label(), "Every 40 rounds");
+        assert_eq!(BreakReminder::Minutes(1).label(), "Every 1 minute");
+    }
+
+    #[test]
+    fn break_is_due_after_rounds_or_minutes() {
+        let mut st = AppState {
+            break_reminder: BreakReminder::Rounds(10),
+            infinite_round: 10,
+            ..AppState::default()
+        };
+        mark_break(&mut st);
+        st.infinite_round = 19;
+        assert!(!break_due(&st));
+        st.infinite_round = 20;
+        assert!(break_due(&st));
+
+        st.break_reminder = BreakReminder::Minutes(15);
+        st.seconds_elapsed = 15 * 60 - 1;
+        assert!(!break_due(&st));
+        st.seconds_elapsed = 15 * 60;
+        assert!(break_due(&st));
+    }
+}
@@ -0,0 +1,258 @@
+use gtk4::glib;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::i18n::{tr, tr_format};
+
+use super::state::Difficulty;
+
+const WEEKLY_DIFFICULTY: Difficulty = Difficulty::Impossible;
+const URI_SCHEME: &str = "recall";
+const URI_PLAY_PATH: &str = "play";
+const SECONDS_PER_DAY: i64 = 86_400;
+const SECONDS_PER_HOUR: i64 = 3_600;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChallengeKind {
+    Weekly { week: String },
+    Shared { difficulty: Difficulty },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    pub kind: ChallengeKind,
+    pub seed: u64,
+}
+
+impl Challenge {
+    pub fn weekly(week: &str) -> Self {
+        Self {
+            kind: ChallengeKind::Weekly {
+                week: week.to_string(),
+            },
+            seed: seed_for_label(&format!("weekly:{week}")),
+        }
+    }
+
+    pub fn current_weekly() -> Option<Self> {
+        current_week_label().map(|week| Self::weekly(&week))
+    }
+
+    pub fn shared(difficulty: Difficulty, seed: u64) -> Option<Self> {
+        mode_to_code(difficulty)?;
+        Some(Self {
+            kind: ChallengeKind::Shared { difficulty },
+            seed,
+        })
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        match self.kind {
+            ChallengeKind::Weekly { .. } => WEEKLY_DIFFICULTY,
+            ChallengeKind::Shared { difficulty } => difficulty,
+        }
+    }
+
+    pub fn week(&self) -> Option<&str> {
+        match &self.kind {
+            ChallengeKind::Weekly { week } => Some(week),
+            ChallengeKind::Shared { .. } => None,
+        }
+    }
+
+    pub fn mode_label(&self) -> String {
+        match &self.kind {
+            ChallengeKind::Weekly { week } => {
+                format!("{} · {}", tr("Weekly"), week_display_name(week))
+            }
+            ChallengeKind::Shared { difficulty } => {
+                format!("{} · {}", tr("Shared Board"), tr(difficulty.name()))
+            }
+        }
+    }
+
+    pub fn to_code(&self) -> String {
+        match &self.kind {
+            ChallengeKind::Weekly { week } => format!("weekly:{week}"),
+            ChallengeKind::Shared { difficulty } => format!(
+                "shared:{}:{}",
+                mode_to_code(*difficulty).unwrap_or_default(),
+                self.seed
+            ),
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let (kind, value) = code.trim().split_once(':')?;
+        match kind {
+            "weekly" if parse_week_label(value).is_some() => Some(Self::weekly(value)),
+            "shared" => {
+                let (mode, seed) = value.split_once(':')?;
+                Self::shared(mode_from_code(mode)?, seed.parse().ok()?)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let rest = uri.trim().strip_prefix(URI_SCHEME)?.strip_prefix(':')?;
+        let rest = rest.trim_start_matches('/');
+        let (path, query) = rest.split_once('?')?;
+        if path.trim_end_matches('/') != URI_PLAY_PATH {
+            return None;
+        }
+
+        let mut seed = None;
+        let mut difficulty = None;
+        for param in query.split('&') {
+            match param.split_once('=') {
+                Some(("seed", value)) => seed = value.parse::<u64>().ok(),
+                Some(("mode", value)) => difficulty = mode_from_code(value),
+                _ => {}
+            }
+        }
+        Self::shared(difficulty?, seed?)
+    }
+}
+
+fn mode_to_code(difficulty: Difficulty) -> Option<&'static str> {
+    match difficulty {
+        Difficulty::Easy => Some("easy"),
+        Difficulty::Medium => Some("medium"),
+        Difficulty::Hard => Some("hard"),
+        Difficulty::Impossible => Some("expert"),
+        Difficulty::Trio | Difficulty::Infinite | Difficulty::InfiniteTrio => None,
+    }
+}
+
+fn mode_from_code(code: &str) -> Option<Difficulty> {
+    match code.to_ascii_lowercase().as_str() {
+        "easy" => Some(Difficulty::Easy),
+        "medium" | "normal" => Some(Difficulty::Medium),
+        "hard" => Some(Difficulty::Hard),
+        "expert" | "impossible" => Some(Difficulty::Impossible),
+        _ => None,
+    }
+}
+
+// ChaCha8 gives the same stream for a seed on every platform and rand release,
+// which `StdRng` does not promise; shared boards depend on that.
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+fn seed_for_label(label: &str) -> u64 {
+    // FNV-1a keeps the seed stable across builds and platforms.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in label.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+pub fn week_label(year: i32, week: i32) -> String {
+    format!("{year}-W{week:02}")
+}
+
+fn parse_week_label(label: &str) -> Option<(i32, i32)> {
+    let (year, week) = label.split_once("-W")?;
+    let year = year.parse::<i32>().ok()?;
+    let week = week.parse::<i32>().ok()?;
+    (1..=53).contains(&week).then_some((year, week))
+}
+
+pub fn week_display_name(label: &str) -> String {
+    match parse_week_label(label) {
+        Some((_, week)) => tr_format("Week {week}", &[("week", &week)]),
+        None => label.to_string(),
+    }
+}
+
+pub fn current_week_label() -> Option<String> {
+    let now = glib::DateTime::now_local().ok()?;
+    Some(week_label(now.week_numbering_year(), now.week_of_year()))
+}
+
+pub fn seconds_until_next_rotation() -> Option<i64> {
+    let now = glib::DateTime::now_local().ok()?;
+    let (year, month, day) = now.ymd();
+    let midnight = glib::DateTime::from_local(year, month, day, 0, 0, 0.0).ok()?;
+    let days_until_monday = 8 - now.day_of_week();
+    let next_rotation = midnight.add_days(days_until_monday).ok()?;
+    Some((next_rotation.to_unix() - now.to_unix()).max(0))
+}
+
+pub fn format_rotation_countdown(total_secs: i64) -> String {
+    let total_secs = total_secs.max(0);
+    let days = total_secs / SECONDS_PER_DAY;
+    let hours = (total_secs % SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+    let mins = (total_secs % SECONDS_PER_HOUR) / 60;
+    if days > 0 {
+        tr_format("{days}d {hours}h", &[("days", &days), ("hours", &hours)])
+    } else if hours > 0 {
+        tr_format("{hours}h {mins}m", &[("hours", &hours), ("mins", &mins)])
+    } else {
+        tr_format("{mins}m", &[("mins", &mins.max(1))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weekly_seed_is_stable_per_week() {
+        let first = Challenge::weekly("2026-W42");
+        let second = Challenge::weekly("2026-W42");
+        let next_week = Challenge::weekly("2026-W43");
+
+        assert_eq!(first.seed, second.seed);
+        assert_ne!(first.seed, next_week.seed);
+    }
+
+    #[test]
+    fn challenge_code_roundtrip() {
+        let challenge = Challenge::weekly("2026-W07");
+        let parsed = Challenge::from_code(&challenge.to_code()).expect("code should parse");
+
+        assert_eq!(parsed, challenge);
+        assert!(Challenge::from_code("weekly:2026-W99").is_none());
+        assert!(Challenge::from_code("monthly:2026-W07").is_none());
+    }
+
+    #[test]
+    fn shared_challenge_uri_parses_seed_and_mode() {
+        let challenge = Challenge::shared(Difficulty::Hard, 9_876_543_210).expect("classic mode");
+        let parsed = Challenge::from_uri("recall://play?seed=9876543210&mode=hard")
+            .expect("uri should parse");
+
+        assert_eq!(parsed, challenge);
+        assert_eq!(
+            Challenge::from_uri("recall://play?mode=expert&seed=42"),
+            Challenge::shared(Difficulty::Impossible, 42)
+        );
+        assert_eq!(Challenge::from_code(&challenge.to_code()), Some(challenge));
+    }
+
+    #[test]
+    fn malformed_challenge_uris_are_rejected() {
+        assert!(Challenge::from_uri("recall://play?seed=abc&mode=easy").is_none());
+        assert!(Challenge::from_uri("recall://play?seed=12&mode=trio").is_none());
+        assert!(Challenge::from_uri("recall://settings?seed=12&mode=easy").is_none());
+        assert!(Challenge::from_uri("https://play?seed=12&mode=easy").is_none());
+    }
+
+    #[test]
+    fn rotation_countdown_uses_coarsest_units() {
+        assert_eq!(
+            format_rotation_countdown(2 * SECONDS_PER_DAY + 5 * SECONDS_PER_HOUR),
+            "2d 5h"
+        );
+        assert_eq!(
+            format_rotation_countdown(3 * SECONDS_PER_HOUR + 12 * 60),
+            "3h 12m"
+        );
+        assert_eq!(format_rotation_countdown(20), "1m");
+    }
+}
@@ -1,6 +1,10 @@
+use crate::i18n::{ntr_format, tr, tr_format};
+
+use super::infinite;
 use super::state::{AppState, Difficulty};
 
 pub const MISMATCH_THRESHOLD: u8 = 3;
+pub const FIRST_MISMATCH_GRACE_KEY: &str = "first-mismatch-grace";
 pub const PREVIEW_SECONDS: f64 = 11.0;
 const MEDIUM_MISMATCH_THRESHOLD: u8 = 5;
 const HARD_MISMATCH_THRESHOLD: u8 = 2;
@@ -17,6 +21,76 @@ pub struct PunishmentPlan {
     pub reshuffle_hidden: bool,
     pub reveal_all_hidden: bool,
     pub source_difficulty: Difficulty,
+    pub mismatches: u8,
+    pub repeated_first_pick: bool,
+}
+
+impl PunishmentPlan {
+    // Short explanation for players who just watched the board move.
+    pub fn summary(&self, hidden_count: usize) -> String {
+        let cause = if self.repeated_first_pick {
+            tr("Same first card twice")
+        } else {
+            let mismatches = u32::from(self.mismatches);
+            ntr_format("{count} mismatch", "{count} mismatches", mismatches, &[])
+        };
+        let revealed = self.reveal_count.min(hidden_count) as u32;
+        let effect = match (self.reshuffle_hidden, self.reveal_all_hidden) {
+            (true, true) => tr("hidden cards reshuffled, all revealed"),
+            (false, true) => tr("hidden cards shown, all revealed"),
+            (true, false) if revealed > 0 => ntr_format(
+                "hidden cards reshuffled, {count} revealed",
+                "hidden cards reshuffled, {count} revealed",
+                revealed,
+                &[],
+            ),
+            (false, false) if revealed > 0 => ntr_format(
+                "hidden cards shown, {count} revealed",
+                "hidden cards shown, {count} revealed",
+                revealed,
+                &[],
+            ),
+            (true, false) => tr("hidden cards reshuffled"),
+            (false, false) => tr("hidden cards shown"),
+        };
+        tr_format("{cause} — {effect}", &[("cause", &cause), ("effect", &effect)])
+    }
+}
+
+// How close the board is to its next punishment, for the HUD pips.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pressure {
+    pub mismatches: u8,
+    pub threshold: u8,
+    // Punishments taken so far on boards whose reveals shrink with each one.
+    pub stage: Option<u8>,
+}
+
+// Infinite borrows the classic board of its round, Hot Seat the player's handicap.
+pub fn penalty_difficulty(st: &AppState) -> Difficulty {
+    if infinite::is_infinite(st.difficulty) {
+        infinite::classic_difficulty_for_round(st.infinite_pacing, st.infinite_round)
+    } else if let Some(run) = &st.hotseat {
+        run.penalty_difficulty()
+    } else {
+        st.difficulty
+    }
+}
+
+pub fn pressure(st: &AppState) -> Option<Pressure> {
+    match penalty_difficulty(st) {
+        Difficulty::Hard => Some(Pressure {
+            mismatches: st.impossible_mismatch_count,
+            threshold: HARD_MISMATCH_THRESHOLD,
+            stage: None,
+        }),
+        Difficulty::Impossible => Some(Pressure {
+            mismatches: st.impossible_mismatch_count,
+            threshold: MISMATCH_THRESHOLD,
+            stage: Some(st.impossible_punish_stage),
+        }),
+        _ => None,
+    }
 }
 
 pub fn is_expert(difficulty: Difficulty) -> bool {
@@ -30,11 +104,21 @@ pub fn mismatch_pause_ms(difficulty: Difficulty) -> u64 {
     }
 }
 
+// With the grace rule on, a game's opening mismatch never counts towards a penalty.
+// Like an assist it only reaches boards that can go unranked.
+// Expects `run_mismatches` to already include the mismatch being planned for.
+pub fn forgives_mismatch(st: &AppState) -> bool {
+    st.first_mismatch_grace && st.modifiers_apply() && st.run_mismatches == 1
+}
+
 pub fn register_mismatch_and_plan_reshuffle_for(
     st: &mut AppState,
     first_pick_index: usize,
     difficulty: Difficulty,
 ) -> Option<PunishmentPlan> {
+    if forgives_mismatch(st) {
+        return None;
+    }
     match difficulty {
         Difficulty::Easy => return None,
         Difficulty::Medium => {
@@ -49,6 +133,8 @@ pub fn register_mismatch_and_plan_reshuffle_for(
                 reshuffle_hidden: true,
                 reveal_all_hidden: false,
                 source_difficulty: difficulty,
+                mismatches: MEDIUM_MISMATCH_THRESHOLD,
+                repeated_first_pick: false,
             });
         }
         Difficulty::Hard => {
@@ -63,6 +149,8 @@ pub fn register_mismatch_and_plan_reshuffle_for(
                 reshuffle_hidden: true,
                 reveal_all_hidden: true,
                 source_difficulty: difficulty,
+                mismatches: HARD_MISMATCH_THRESHOLD,
+                repeated_first_pick: false,
             });
         }
         Difficulty::Impossible => {}
@@ -85,6 +173,7 @@ pub fn register_mismatch_and_plan_reshuffle_for(
         return None;
     }
 
+    let mismatches = st.impossible_mismatch_count;
     st.impossible_mismatch_count = 0;
     st.impossible_same_first_streak = 0;
     st.impossible_last_first_index = None;
@@ -102,6 +191,8 @@ pub fn register_mismatch_and_plan_reshuffle_for(
         reshuffle_hidden: true,
         reveal_all_hidden: false,
         source_difficulty: difficulty,
+        mismatches,
+        repeated_first_pick: repeated_first_hit && !threshold_hit,
     })
 }
 
@@ -110,3 +201,83 @@ pub fn reset_penalty_after_match_for(st: &mut AppState, difficulty: Difficulty)
         st.reset_impossible_pressure();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(reveal_count: usize, reveal_all_hidden: bool) -> PunishmentPlan {
+        PunishmentPlan {
+            reveal_count,
+            reveal_ms: 500,
+            reshuffle_hidden: true,
+            reveal_all_hidden,
+            source_difficulty: Difficulty::Impossible,
+            mismatches: 3,
+            repeated_first_pick: false,
+        }
+    }
+
+    #[test]
+    fn summary_reports_cause_and_cards_actually_revealed() {
+        assert_eq!(
+            plan(5, false).summary(12),
+            "3 mismatches — hidden cards reshuffled, 5 revealed"
+        );
+        assert_eq!(
+            plan(7, false).summary(4),
+            "3 mismatches — hidden cards reshuffled, 4 revealed"
+        );
+        assert!(plan(0, true).summary(8).ends_with("all revealed"));
+    }
+
+    #[test]
+    fn pressure_follows_the_round_in_infinite() {
+        let mut st = AppState {
+            difficulty: Difficulty::Hard,
+            impossible_mismatch_count: 1,
+            ..AppState::default()
+        };
+        assert_eq!(
+            pressure(&st),
+            Some(Pressure { mismatches: 1, threshold: 2, stage: None })
+        );
+        st.difficulty = Difficulty::Infinite;
+        st.infinite_round = 1;
+        assert_eq!(pressure(&st), None);
+    }
+
+    #[test]
+    fn grace_lets_hard_take_one_extra_mismatch_at_the_start() {
+        let plans = |grace: bool| {
+            let mut st = AppState {
+                first_mismatch_grace: grace,
+                ..AppState::default()
+            };
+            (0..3)
+                .map(|pick| {
+                    st.run_mismatches += 1;
+                    register_mismatch_and_plan_reshuffle_for(&mut st, pick, Difficulty::Hard).is_some()
+                })
+                .collect::<Vec<bool>>()
+        };
+        assert_eq!(plans(false), vec![false, true, false]);
+        assert_eq!(plans(true), vec![false, false, true]);
+    }
+
+    #[test]
+    fn grace_marks_the_run_and_stays_out_of_scored_modes() {
+        let mut st = AppState {
+            first_mismatch_grace: true,
+            run_mismatches: 1,
+            ..AppState::default()
+        };
+        st.note_run_modifiers();
+        assert!(forgives_mismatch(&st));
+        assert_eq!(st.run_modifiers(), "grace");
+        assert!(st.run_assisted());
+
+        st.speedrun = true;
+        assert!(!forgives_mismatch(&st));
+    }
+}
@@ -0,0 +1,47 @@
+use gtk4::gdk;
+
+pub const SHOW_COORDINATES_KEY: &str = "show-coordinates";
+
+pub fn column_label(col: i32) -> String {
+    char::from_u32('A' as u32 + col.clamp(0, 25) as u32)
+        .unwrap_or('?')
+        .to_string()
+}
+
+pub fn row_label(row: i32) -> String {
+    (row + 1).to_string()
+}
+
+pub fn column_from_key(key: gdk::Key) -> Option<i32> {
+    let ch = key.to_lower().to_unicode()?;
+    ch.is_ascii_lowercase().then(|| ch as i32 - 'a' as i32)
+}
+
+// Accepts both the number row and the numpad.
+pub fn row_from_key(key: gdk::Key) -> Option<i32> {
+    let digit = key.to_unicode()?.to_digit(10)?;
+    (digit > 0).then(|| digit as i32 - 1)
+}
+
+pub fn tile_index(col: i32, row: i32, cols: i32, len: usize) -> Option<usize> {
+    if col < 0 || row < 0 || col >= cols {
+        return None;
+    }
+    let index = (row * cols + col) as usize;
+    (index < len).then_some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coordinates_map_to_row_major_tiles() {
+        assert_eq!(tile_index(1, 2, 4, 24), Some(9));
+        assert_eq!(tile_index(0, 0, 4, 24), Some(0));
+        assert!(tile_index(4, 0, 4, 24).is_none());
+        assert!(tile_index(0, 6, 4, 24).is_none());
+        assert_eq!(column_label(1), "B");
+        assert_eq!(row_label(2), "3");
+    }
+}
@@ -0,0 +1,251 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+
+use super::defeat::FailCondition;
+use super::paths;
+use super::state::Difficulty;
+
+const PRESETS_FILE_NAME: &str = "custom_presets.v1";
+pub const MIN_SIDE: i32 = 2;
+pub const MAX_SIDE: i32 = 8;
+pub const MIN_PREVIEW_SECS: u32 = 1;
+pub const MAX_PREVIEW_SECS: u32 = 30;
+const MAX_NAME_CHARS: usize = 32;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PenaltyProfile {
+    #[default]
+    Off,
+    Gentle,
+    Strict,
+    Expert,
+}
+
+impl PenaltyProfile {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Gentle, Self::Strict, Self::Expert];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Gentle => "gentle",
+            Self::Strict => "strict",
+            Self::Expert => "expert",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|profile| profile.code() == code)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "No penalties",
+            Self::Gentle => "Gentle",
+            Self::Strict => "Strict",
+            Self::Expert => "Expert",
+        }
+    }
+
+    // The profile whose penalties a classic level already plays with.
+    pub fn for_level(level: u8) -> Self {
+        match level {
+            1 => Self::Off,
+            2 => Self::Gentle,
+            3 => Self::Strict,
+            _ => Self::Expert,
+        }
+    }
+
+    // Custom boards borrow the mismatch penalties of a classic difficulty.
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Self::Off => Difficulty::Easy,
+            Self::Gentle => Difficulty::Medium,
+            Self::Strict => Difficulty::Hard,
+            Self::Expert => Difficulty::Impossible,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomPreset {
+    pub name: String,
+    pub cols: i32,
+    pub rows: i32,
+    pub match_size: usize,
+    pub preview_secs: u32,
+    pub penalty: PenaltyProfile,
+    pub fail_condition: FailCondition,
+    pub lookalikes: bool,
+}
+
+impl Default for CustomPreset {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            cols: 4,
+            rows: 4,
+            match_size: 2,
+            preview_secs: 6,
+            penalty: PenaltyProfile::Off,
+            fail_condition: FailCondition::Off,
+            lookalikes: false,
+        }
+    }
+}
+
+impl CustomPreset {
+    pub fn is_valid(&self) -> bool {
+        let tile_count = (self.cols * self.rows) as usize;
+        !self.name.trim().is_empty()
+            && (MIN_SIDE..=MAX_SIDE).contains(&self.cols)
+            && (MIN_SIDE..=MAX_SIDE).contains(&self.rows)
+            && matches!(self.match_size, 2 | 3)
+            && tile_count.is_multiple_of(self.match_size)
+            && tile_count / self.match_size >= 2
+            && (MIN_PREVIEW_SECS..=MAX_PREVIEW_SECS).contains(&self.preview_secs)
+    }
+
+    // The classic level whose board is closest in size, for the rank thresholds.
+    pub fn rank_level(&self) -> u8 {
+        match self.cols * self.rows {
+            ..=12 => 1,
+            13..=24 => 2,
+            25..=42 => 3,
+            _ => 4,
+        }
+    }
+
+    pub fn mode_label(&self) -> String {
+        format!("{} · {}", tr("Custom"), self.name)
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}x{} · {} · {} {} · {}",
+            self.cols,
+            self.rows,
+            if self.match_size == 3 { tr("Trios") } else { tr("Pairs") },
+            self.preview_secs,
+            tr("s preview"),
+            tr(self.penalty.label())
+        );
+        if self.fail_condition != FailCondition::Off {
+            summary = format!("{} · {}", summary, tr(self.fail_condition.label()));
+        }
+        if self.lookalikes {
+            summary = format!("{} · {}", summary, tr("Lookalikes"));
+        }
+        summary
+    }
+
+    // Everything but the name, so records can tell two boards apart.
+    pub fn to_code(&self) -> String {
+        format!(
+            "{}x{}x{}:{}:{}:{}:{}",
+            self.cols,
+            self.rows,
+            self.match_size,
+            self.preview_secs,
+            self.penalty.code(),
+            self.fail_condition.code(),
+            u8::from(self.lookalikes)
+        )
+    }
+
+    pub fn from_code(code: &str, name: &str) -> Option<Self> {
+        let mut parts = code.trim().split(':');
+        let mut grid = parts.next()?.split('x');
+        let preset = Self {
+            name: clean_name(name),
+            cols: grid.next()?.parse().ok()?,
+            rows: grid.next()?.parse().ok()?,
+            match_size: grid.next()?.parse().ok()?,
+            preview_secs: parts.next()?.parse().ok()?,
+            penalty: PenaltyProfile::from_code(parts.next()?)?,
+            fail_condition: FailCondition::from_code(parts.next()?),
+            lookalikes: parts.next()? == "1",
+        };
+        preset.is_valid().then_some(preset)
+    }
+
+    pub fn to_line(&self) -> String {
+        format!("{}|{}", self.to_code(), self.name)
+    }
+
+    pub fn from_line(line: &str) -> Option<Self> {
+        let (code, name) = line.split_once('|')?;
+        Self::from_code(code, name)
+    }
+}
+
+pub fn clean_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| *ch != '|' && !ch.is_control())
+        .take(MAX_NAME_CHARS)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn presets_path() -> PathBuf {
+    paths::config_dir().join(PRESETS_FILE_NAME)
+}
+
+pub fn load_presets() -> Vec<CustomPreset> {
+    let Ok(raw) = fs::read_to_string(presets_path()) else {
+        return Vec::new();
+    };
+    raw.lines().filter_map(CustomPreset::from_line).collect()
+}
+
+pub fn save_presets(presets: &[CustomPreset]) -> io::Result<()> {
+    let raw: String = presets
+        .iter()
+        .map(|preset| format!("{}\n", preset.to_line()))
+        .collect();
+    paths::write_atomic(&presets_path(), &raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_lines_roundtrip() {
+        let preset = CustomPreset {
+            name: "Lunch break".to_string(),
+            cols: 5,
+            rows: 6,
+            match_size: 3,
+            preview_secs: 12,
+            penalty: PenaltyProfile::Strict,
+            fail_condition: FailCondition::Countdown,
+            lookalikes: true,
+        };
+
+        assert_eq!(preset.to_code(), "5x6x3:12:strict:countdown:1");
+        assert_eq!(CustomPreset::from_line(&preset.to_line()), Some(preset));
+    }
+
+    #[test]
+    fn invalid_boards_are_rejected() {
+        assert!(CustomPreset::from_code("3x3x2:6:off:off:0", "Odd").is_none());
+        assert!(CustomPreset::from_code("9x8x2:6:off:off:0", "Too wide").is_none());
+        assert!(CustomPreset::from_code("4x4x2:0:off:off:0", "No look").is_none());
+        assert!(CustomPreset::from_code("4x4x2:6:off:off:0", " | ").is_none());
+        assert!(CustomPreset::from_code("4x4x2:6:off:off:0", "Fine").is_some());
+    }
+
+    #[test]
+    fn rank_level_follows_board_size_not_penalties() {
+        let small = CustomPreset::from_code("3x4x2:6:expert:off:0", "Small").unwrap();
+        let large = CustomPreset::from_code("8x8x2:6:off:off:0", "Large").unwrap();
+        assert_eq!(small.rank_level(), 1);
+        assert_eq!(large.rank_level(), 4);
+        assert_eq!(CustomPreset::from_code("5x6x3:6:off:off:0", "Mid").unwrap().rank_level(), 3);
+    }
+}
@@ -0,0 +1,289 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk4 as gtk;
+use libadwaita as adw;
+
+use crate::i18n::tr;
+
+use super::app::apply_custom_start;
+use super::custom::{
+    self, CustomPreset, PenaltyProfile, MAX_PREVIEW_SECS, MAX_SIDE, MIN_PREVIEW_SECS, MIN_SIDE,
+};
+use super::defeat::FailCondition;
+use super::mode_dialogs::{self, ModeChoice};
+use super::state::AppState;
+
+const MATCH_SIZES: [usize; 2] = [2, 3];
+
+fn combo_row(title: &str, labels: &[String]) -> adw::ComboRow {
+    let refs: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+    let row = adw::ComboRow::builder().title(title).build();
+    row.set_model(Some(&gtk::StringList::new(&refs)));
+    row
+}
+
+pub fn build_custom_page(
+    state: &Rc<RefCell<AppState>>,
+    mode_dialog: &adw::Dialog,
+) -> adw::NavigationPage {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+
+    let preset_list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    preset_list.add_css_class("boxed-list");
+    let placeholder = gtk::Label::new(Some(&tr("No presets yet")));
+    placeholder.add_css_class("dim-label");
+    placeholder.set_margin_top(12);
+    placeholder.set_margin_bottom(12);
+    preset_list.set_placeholder(Some(&placeholder));
+    refresh_preset_list(&preset_list, state, mode_dialog);
+    content.append(&preset_list);
+
+    let new_row = adw::ActionRow::builder()
+        .title(tr("New Preset"))
+        .subtitle(tr("Pick the board, preview, penalties and modifiers"))
+        .activatable(true)
+        .build();
+    new_row.add_css_class("mode-native-row");
+    new_row.add_suffix(&gtk::Image::from_icon_name("list-add-symbolic"));
+    new_row.connect_activated({
+        let state = state.clone();
+        let mode_dialog = mode_dialog.clone();
+        let preset_list = preset_list.clone();
+        move |_| {
+            let state = state.clone();
+            let mode_dialog_saved = mode_dialog.clone();
+            let preset_list = preset_list.clone();
+            show_builder_dialog(&mode_dialog, move |preset| {
+                let mut presets = custom::load_presets();
+                presets.push(preset);
+                if let Err(err) = custom::save_presets(&presets) {
+                    tracing::warn!("failed to save custom presets: {err}");
+                }
+                refresh_preset_list(&preset_list, &state, &mode_dialog_saved);
+            });
+        }
+    });
+    let new_list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    new_list.add_css_class("boxed-list");
+    new_list.append(&new_row);
+    content.append(&new_list);
+
+    let clamp = adw::Clamp::builder().maximum_size(520).build();
+    clamp.set_margin_top(12);
+    clamp.set_margin_start(15);
+    clamp.set_margin_end(15);
+    clamp.set_child(Some(&content));
+
+    let scroller = gtk::ScrolledWindow::new();
+    scroller.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&clamp));
+
+    let header = adw::HeaderBar::new();
+    header.set_show_end_title_buttons(true);
+    header.set_show_back_button(true);
+    header.add_css_class("flat");
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&scroller));
+
+    adw::NavigationPage::builder()
+        .title(tr("Custom"))
+        .child(&toolbar)
+        .build()
+}
+
+fn refresh_preset_list(
+    list: &gtk::ListBox,
+    state: &Rc<RefCell<AppState>>,
+    mode_dialog: &adw::Dialog,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    for (index, preset) in custom::load_presets().into_iter().enumerate() {
+        let row = adw::ActionRow::builder()
+            .title(&preset.name)
+            .subtitle(preset.summary())
+            .activatable(true)
+            .build();
+        row.add_css_class("difficulty-native-row");
+
+        let delete = gtk::Button::from_icon_name("user-trash-symbolic");
+        delete.add_css_class("flat");
+        delete.set_valign(gtk::Align::Center);
+        delete.set_tooltip_text(Some(&tr("Delete preset")));
+        delete.connect_clicked({
+            let list = list.clone();
+            let state = state.clone();
+            let mode_dialog = mode_dialog.clone();
+            move |_| {
+                let mut presets = custom::load_presets();
+                if index < presets.len() {
+                    presets.remove(index);
+                }
+                if let Err(err) = custom::save_presets(&presets) {
+                    tracing::warn!("failed to save custom presets: {err}");
+                }
+                refresh_preset_list(&list, &state, &mode_dialog);
+            }
+        });
+        row.add_suffix(&delete);
+
+        row.connect_activated({
+            let state = state.clone();
+            let mode_dialog = mode_dialog.clone();
+            move |_| {
+                mode_dialogs::remember_choice(ModeChoice::Custom, 0);
+                apply_custom_start(&state, preset.clone());
+                mode_dialog.close();
+            }
+        });
+        list.append(&row);
+    }
+}
+
+fn show_builder_dialog(parent: &adw::Dialog, on_save: impl Fn(CustomPreset) + 'static) {
+    let defaults = CustomPreset::default();
+    let dialog = adw::Dialog::builder()
+        .title(tr("New Preset"))
+        .content_width(400)
+        .build();
+
+    let name_row = adw::EntryRow::builder().title(tr("Name")).build();
+    let cols_row = adw::SpinRow::with_range(f64::from(MIN_SIDE), f64::from(MAX_SIDE), 1.0);
+    cols_row.set_title(&tr("Columns"));
+    cols_row.set_value(f64::from(defaults.cols));
+    let rows_row = adw::SpinRow::with_range(f64::from(MIN_SIDE), f64::from(MAX_SIDE), 1.0);
+    rows_row.set_title(&tr("Rows"));
+    rows_row.set_value(f64::from(defaults.rows));
+    let match_row = combo_row(&tr("Match size"), &[tr("Pairs"), tr("Trios")]);
+    let preview_row = adw::SpinRow::with_range(
+        f64::from(MIN_PREVIEW_SECS),
+        f64::from(MAX_PREVIEW_SECS),
+        1.0,
+    );
+    preview_row.set_title(&tr("Preview seconds"));
+    preview_row.set_value(f64::from(defaults.preview_secs));
+
+    let board_group = adw::PreferencesGroup::new();
+    board_group.add(&name_row);
+    board_group.add(&cols_row);
+    board_group.add(&rows_row);
+    board_group.add(&match_row);
+    board_group.add(&preview_row);
+
+    let penalty_labels: Vec<String> = PenaltyProfile::ALL
+        .iter()
+        .map(|profile| tr(profile.label()))
+        .collect();
+    let penalty_row = combo_row(&tr("Penalties"), &penalty_labels);
+    let fail_labels: Vec<String> = FailCondition::ALL
+        .iter()
+        .map(|condition| tr(condition.label()))
+        .collect();
+    let fail_row = combo_row(&tr("Fail condition"), &fail_labels);
+    let lookalike_row = adw::SwitchRow::builder()
+        .title(tr("Lookalike symbols"))
+        .build();
+
+    let rules_group = adw::PreferencesGroup::new();
+    rules_group.set_title(&tr("Rules"));
+    rules_group.add(&penalty_row);
+    rules_group.add(&fail_row);
+    rules_group.add(&lookalike_row);
+
+    let page = adw::PreferencesPage::new();
+    page.add(&board_group);
+    page.add(&rules_group);
+
+    let read_preset = {
+        let name_row = name_row.clone();
+        let cols_row = cols_row.clone();
+        let rows_row = rows_row.clone();
+        let match_row = match_row.clone();
+        let preview_row = preview_row.clone();
+        let penalty_row = penalty_row.clone();
+        let fail_row = fail_row.clone();
+        let lookalike_row = lookalike_row.clone();
+        move || CustomPreset {
+            name: custom::clean_name(&name_row.text()),
+            cols: cols_row.value() as i32,
+            rows: rows_row.value() as i32,
+            match_size: MATCH_SIZES
+                .get(match_row.selected() as usize)
+                .copied()
+                .unwrap_or(2),
+            preview_secs: preview_row.value() as u32,
+            penalty: PenaltyProfile::ALL
+                .get(penalty_row.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            fail_condition: FailCondition::ALL
+                .get(fail_row.selected() as usize)
+                .copied()
+                .unwrap_or_default(),
+            lookalikes: lookalike_row.is_active(),
+        }
+    };
+
+    let save = gtk::Button::with_label(&tr("Save"));
+    save.add_css_class("suggested-action");
+    save.set_sensitive(false);
+    let cancel = gtk::Button::with_label(&tr("Cancel"));
+    let header = adw::HeaderBar::new();
+    header.set_show_start_title_buttons(false);
+    header.set_show_end_title_buttons(false);
+    header.pack_start(&cancel);
+    header.pack_end(&save);
+
+    // The grid must split evenly into groups, so only valid presets can be saved.
+    let refresh_save = Rc::new({
+        let save = save.clone();
+        let read_preset = read_preset.clone();
+        move || save.set_sensitive(read_preset().is_valid())
+    });
+    name_row.connect_changed({
+        let refresh_save = refresh_save.clone();
+        move |_| refresh_save()
+    });
+    for spin_row in [&cols_row, &rows_row] {
+        spin_row.connect_value_notify({
+            let refresh_save = refresh_save.clone();
+            move |_| refresh_save()
+        });
+    }
+    match_row.connect_selected_notify({
+        let refresh_save = refresh_save.clone();
+        move |_| refresh_save()
+    });
+
+    cancel.connect_clicked({
+        let dialog = dialog.clone();
+        move |_| {
+            dialog.close();
+        }
+    });
+    save.connect_clicked({
+        let dialog = dialog.clone();
+        move |_| {
+            let preset = read_preset();
+            if preset.is_valid() {
+                on_save(preset);
+                dialog.close();
+            }
+        }
+    });
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&page));
+    dialog.set_child(Some(&toolbar));
+    dialog.present(Some(parent));
+}
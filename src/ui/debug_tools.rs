@@ -1,17 +1,20 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use gtk4::glib;
+use gtk4 as gtk;
 use gtk4::gdk;
-use gtk4::prelude::*;
+use libadwaita as adw;
+use adw::prelude::*;
 use super::state::{AppState, Difficulty, TileStatus};
 use super::hud::update_subtitle;
 use super::scene::rebuild_board;
-use super::app::{apply_difficulty_change, apply_trio_level_change, show_game};
+use super::app::{apply_difficulty_change, apply_trio_level_change, restore_saved_run, show_game};
 use super::hud::stop_preview;
 use super::hud::stop_timer;
-use super::app::clear_flip_classes;
-use super::app::redraw_button_child;
+use super::board_canvas::CardFlag;
 use super::infinite;
+use super::perf_hud;
+use super::game_title::StatusSource;
+use super::session_save;
 
 enum NearWinResult {
     Applied(usize),
@@ -58,6 +61,13 @@ pub fn handle_debug_shortcut(
             | gdk::Key::_4
             | gdk::Key::KP_4
             | gdk::Key::F9
+            | gdk::Key::J
+            | gdk::Key::j
+            | gdk::Key::D
+            | gdk::Key::d
+            | gdk::Key::L
+            | gdk::Key::l
+            | gdk::Key::F12
     );
     if !is_debug_key {
         return false;
@@ -68,8 +78,8 @@ pub fn handle_debug_shortcut(
             match debug_prepare_near_win(state) {
                 NearWinResult::Applied(remaining) => {
                     let st = state.borrow();
-                    eprintln!(
-                        "[DEBUG][{}] Board prepared: one group left ({} cards).",
+                    tracing::debug!(
+                        "[{}] Board prepared: one group left ({} cards).",
                         st.difficulty.name(),
                         remaining
                     );
@@ -78,8 +88,8 @@ pub fn handle_debug_shortcut(
                 }
                 NearWinResult::NoTiles => {
                     let st = state.borrow();
-                    eprintln!(
-                        "[DEBUG][{}] Near-win skipped: board has no tiles yet.",
+                    tracing::debug!(
+                        "[{}] Near-win skipped: board has no tiles yet.",
                         st.difficulty.name()
                     );
                     show_debug_banner(state, "DEBUG | Near-win failed (no tiles)");
@@ -87,8 +97,8 @@ pub fn handle_debug_shortcut(
                 }
                 NearWinResult::NoGroupFound => {
                     let st = state.borrow();
-                    eprintln!(
-                        "[DEBUG][{}] Near-win failed: no group with match_size={} found.",
+                    tracing::debug!(
+                        "[{}] Near-win failed: no group with match_size={} found.",
                         st.difficulty.name(),
                         st.match_size
                     );
@@ -107,15 +117,15 @@ pub fn handle_debug_shortcut(
                     let mut st = state.borrow_mut();
                     let level_up = infinite::advance_round(&mut st);
                     if let Some(level_up) = level_up {
-                        eprintln!(
-                            "[DEBUG][Infinite] Forced next round -> round {} (level up: {} -> {})",
+                        tracing::debug!(
+                            "[Infinite] Forced next round -> round {} (level up: {} -> {})",
                             st.infinite_round,
                             infinite::level_name(level_up.from_level),
                             infinite::level_name(level_up.to_level)
                         );
                     } else {
-                        eprintln!(
-                            "[DEBUG][Infinite] Forced next round -> round {} (level {})",
+                        tracing::debug!(
+                            "[Infinite] Forced next round -> round {} (level {})",
                             st.infinite_round, st.infinite_level
                         );
                     }
@@ -129,11 +139,33 @@ pub fn handle_debug_shortcut(
                 st.active_session_started = false;
                 drop(st);
                 show_game(state);
-                eprintln!("[DEBUG][{}] Restarted current map.", mode_name);
+                tracing::debug!("[{}] Restarted current map.", mode_name);
                 show_debug_banner(state, "DEBUG | Map restarted");
                 true
             }
         }
+        gdk::Key::J | gdk::Key::j => {
+            let is_infinite_mode = {
+                let st = state.borrow();
+                infinite::is_infinite(st.difficulty)
+            };
+            if is_infinite_mode {
+                show_round_jump_dialog(state);
+            } else {
+                show_debug_banner(state, "DEBUG | Round jump is Infinite only");
+            }
+            true
+        }
+        gdk::Key::D | gdk::Key::d => debug_dump_board(state),
+        // Ctrl+P is the pause shortcut, so the perf HUD sits out of the way.
+        gdk::Key::F12 if mods.contains(gdk::ModifierType::SHIFT_MASK) => {
+            let perf_hud = state.borrow().perf_hud.clone();
+            if let Some(perf_hud) = perf_hud {
+                perf_hud::toggle(&perf_hud);
+            }
+            true
+        }
+        gdk::Key::L | gdk::Key::l => debug_load_board(state),
         gdk::Key::_1 | gdk::Key::KP_1 => {
             debug_force_level(state, 1)
         }
@@ -150,13 +182,103 @@ pub fn handle_debug_shortcut(
     }
 }
 
+fn show_round_jump_dialog(state: &Rc<RefCell<AppState>>) {
+    let (parent, round) = {
+        let st = state.borrow();
+        (st.view_stack.clone(), st.infinite_round)
+    };
+    let Some(parent) = parent else {
+        return;
+    };
+
+    let round_spin = gtk::SpinButton::with_range(1.0, 999.0, 1.0);
+    round_spin.set_value(f64::from(round));
+    let dialog = adw::AlertDialog::builder()
+        .heading("DEBUG | Jump to Infinite round")
+        .extra_child(&round_spin)
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("jump", "Jump");
+    dialog.set_close_response("cancel");
+    dialog.set_default_response(Some("jump"));
+    dialog.set_response_appearance("jump", adw::ResponseAppearance::Suggested);
+    dialog.connect_response(Some("jump"), {
+        let state = state.clone();
+        move |_, _| {
+            debug_jump_to_round(&state, round_spin.value_as_int().max(1) as u32);
+        }
+    });
+    dialog.present(Some(&parent));
+}
+
+fn debug_jump_to_round(state: &Rc<RefCell<AppState>>, round: u32) {
+    {
+        let mut st = state.borrow_mut();
+        if !infinite::is_infinite(st.difficulty) {
+            return;
+        }
+        infinite::jump_to_round(&mut st, round);
+        tracing::debug!(
+            "[Infinite] Jumped to round {} (level {})",
+            st.infinite_round,
+            infinite::level_name(st.infinite_level)
+        );
+    }
+    show_game(state);
+    show_debug_banner(state, &format!("DEBUG | Infinite round {round}"));
+}
+
+fn debug_dump_board(state: &Rc<RefCell<AppState>>) -> bool {
+    let result = {
+        let st = state.borrow();
+        if st.tiles.is_empty() {
+            drop(st);
+            show_debug_banner(state, "DEBUG | Nothing to dump");
+            return true;
+        }
+        session_save::dump_board(&st)
+    };
+    match result {
+        Ok(path) => {
+            tracing::info!("board dumped to {}", path.display());
+            show_debug_banner(state, "DEBUG | Board dumped");
+        }
+        Err(err) => {
+            tracing::warn!("failed to dump board: {err}");
+            show_debug_banner(state, "DEBUG | Board dump failed");
+        }
+    }
+    true
+}
+
+fn debug_load_board(state: &Rc<RefCell<AppState>>) -> bool {
+    let dir = session_save::board_dump_dir();
+    let Some(path) = session_save::latest_board_dump(&dir) else {
+        tracing::debug!("No board dump in {}", dir.display());
+        show_debug_banner(state, "DEBUG | No board dump to load");
+        return true;
+    };
+    let Some(dump) = session_save::load_board_dump(&path) else {
+        tracing::warn!("invalid board dump at {}", path.display());
+        show_debug_banner(state, "DEBUG | Board dump is invalid");
+        return true;
+    };
+    if restore_saved_run(state, dump) {
+        tracing::debug!("Board loaded from {}", path.display());
+        show_debug_banner(state, "DEBUG | Board loaded");
+    } else {
+        show_debug_banner(state, "DEBUG | Board dump does not fit its mode");
+    }
+    true
+}
+
 fn debug_force_level(state: &Rc<RefCell<AppState>>, level: u8) -> bool {
     let mut st = state.borrow_mut();
     if infinite::is_infinite(st.difficulty) {
         st.set_infinite_level(level.clamp(1, 4));
         let level_name = infinite::level_name(st.infinite_level).to_string();
-        eprintln!(
-            "[DEBUG][Infinite] Forced level -> {} (round {})",
+        tracing::debug!(
+            "[Infinite] Forced level -> {} (round {})",
             level_name,
             st.infinite_round
         );
@@ -171,8 +293,8 @@ fn debug_force_level(state: &Rc<RefCell<AppState>>, level: u8) -> bool {
         let trio_level = level.clamp(1, 4);
         drop(st);
         apply_trio_level_change(state, trio_level);
-        eprintln!(
-            "[DEBUG][Trio] Forced level -> {}",
+        tracing::debug!(
+            "[Trio] Forced level -> {}",
             trio_level
         );
         show_debug_banner(state, &format!("DEBUG | Trio level {}", trio_level));
@@ -187,7 +309,7 @@ fn debug_force_level(state: &Rc<RefCell<AppState>>, level: u8) -> bool {
     };
     drop(st);
     apply_difficulty_change(state, target);
-    eprintln!("[DEBUG][Classic] Forced difficulty -> {}", target.name());
+    tracing::debug!("[Classic] Forced difficulty -> {}", target.name());
     show_debug_banner(state, &format!("DEBUG | Classic {}", target.name()));
     true
 }
@@ -231,19 +353,15 @@ fn debug_prepare_near_win(state: &Rc<RefCell<AppState>>) -> NearWinResult {
             st.tiles[idx].status = TileStatus::Matched;
         }
 
-        if let Some(button) = st.grid_buttons.get(idx) {
-            clear_flip_classes(button);
-            button.remove_css_class("active");
-            button.remove_css_class("mismatch-shake");
-            button.remove_css_class("match-bump");
-            button.remove_css_class("matched-dim");
+        if let Some(board) = &st.board_canvas {
+            board.clear_flip(idx);
+            board.remove_flag(idx, CardFlag::Shake);
+            board.remove_flag(idx, CardFlag::Bump);
             if keep_hidden {
-                button.remove_css_class("matched");
+                board.remove_flag(idx, CardFlag::Dim);
             } else {
-                button.add_css_class("matched");
-                button.add_css_class("matched-dim");
+                board.add_flag(idx, CardFlag::Dim);
             }
-            redraw_button_child(button);
         }
     }
 
@@ -252,21 +370,7 @@ fn debug_prepare_near_win(state: &Rc<RefCell<AppState>>) -> NearWinResult {
 }
 
 fn show_debug_banner(state: &Rc<RefCell<AppState>>, message: &str) {
-    let message = message.to_string();
-    let game_id = {
-        let st = state.borrow();
-        if let Some(subtitle) = &st.title_game_subtitle {
-            subtitle.set_text(&message);
-        }
-        st.game_id
-    };
-    let state_weak = Rc::downgrade(state);
-    glib::timeout_add_local_once(std::time::Duration::from_millis(1200), move || {
-        if let Some(state) = state_weak.upgrade() {
-            let st = state.borrow();
-            if st.game_id == game_id {
-                update_subtitle(&st);
-            }
-        }
-    });
+    if let Some(title) = &state.borrow().game_title {
+        title.show_status(StatusSource::Debug, message);
+    }
 }
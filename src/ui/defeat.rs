@@ -0,0 +1,187 @@
+use crate::i18n::tr;
+
+use super::infinite;
+use super::state::{AppState, Difficulty, TileStatus};
+
+pub const FAIL_CONDITION_KEY: &str = "fail-condition";
+const COUNTDOWN_SECS_PER_TILE: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailCondition {
+    #[default]
+    Off,
+    MismatchCap,
+    Countdown,
+}
+
+impl FailCondition {
+    pub const ALL: [Self; 3] = [Self::Off, Self::MismatchCap, Self::Countdown];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::MismatchCap => "mismatches",
+            Self::Countdown => "countdown",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|condition| condition.code() == code)
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::MismatchCap => "Mismatch limit",
+            Self::Countdown => "Countdown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DefeatReason {
+    MismatchCap,
+    Countdown,
+    SuddenDeath,
+}
+
+impl DefeatReason {
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::MismatchCap => "mismatches",
+            Self::Countdown => "countdown",
+            Self::SuddenDeath => "sudden-death",
+        }
+    }
+
+    pub fn label(code: &str) -> &'static str {
+        match code {
+            "countdown" => "Time ran out",
+            "sudden-death" => "Sudden death",
+            _ => "Too many mismatches",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::MismatchCap => "Out of Chances",
+            Self::Countdown => "Time's Up",
+            Self::SuddenDeath => "Sudden Death",
+        }
+    }
+}
+
+// Custom presets carry their own fail condition.
+pub fn fail_condition(st: &AppState) -> FailCondition {
+    st.custom
+        .as_ref()
+        .map_or(st.fail_condition, |custom| custom.fail_condition)
+}
+
+// Series modes keep their own pacing; the fail condition covers single boards.
+// The weekly board is the same run for everyone, so a personal fail rule stays off.
+pub fn applies_to(st: &AppState) -> bool {
+    fail_condition(st) != FailCondition::Off
+        && !infinite::is_infinite(st.difficulty)
+        && st.challenge.as_ref().is_none_or(|challenge| challenge.week().is_none())
+        && !st.in_scored_series()
+        && st.hotseat.is_none()
+        && !st.speedrun
+        && !st.sudden_death
+        && st.kids.is_none()
+}
+
+pub fn mismatch_cap(tile_count: usize, match_size: usize) -> u32 {
+    (tile_count / match_size.max(1)) as u32
+}
+
+pub fn countdown_secs(tile_count: usize) -> u32 {
+    tile_count as u32 * COUNTDOWN_SECS_PER_TILE
+}
+
+pub fn mismatch_cap_reached(st: &AppState) -> bool {
+    applies_to(st)
+        && fail_condition(st) == FailCondition::MismatchCap
+        && st.run_mismatches >= mismatch_cap(st.tiles.len(), st.match_size)
+}
+
+pub fn mismatch_defeat_reason(st: &AppState) -> Option<DefeatReason> {
+    if st.sudden_death {
+        Some(DefeatReason::SuddenDeath)
+    } else if mismatch_cap_reached(st) {
+        Some(DefeatReason::MismatchCap)
+    } else {
+        None
+    }
+}
+
+pub fn countdown_remaining_secs(st: &AppState) -> Option<u32> {
+    (applies_to(st) && fail_condition(st) == FailCondition::Countdown)
+        .then(|| countdown_secs(st.tiles.len()).saturating_sub(st.seconds_elapsed))
+}
+
+pub fn pairs_cleared(st: &AppState) -> (u32, u32) {
+    let match_size = st.match_size.max(1);
+    let matched = st
+        .tiles
+        .iter()
+        .filter(|tile| tile.status == TileStatus::Matched)
+        .count();
+    ((matched / match_size) as u32, (st.tiles.len() / match_size) as u32)
+}
+
+pub fn mode_code(difficulty: Difficulty) -> &'static str {
+    if difficulty == Difficulty::Trio {
+        "trio"
+    } else {
+        "classic"
+    }
+}
+
+pub fn defeat_message(reason: DefeatReason, st: &AppState) -> String {
+    match reason {
+        DefeatReason::MismatchCap => format!(
+            "{} {}",
+            mismatch_cap(st.tiles.len(), st.match_size),
+            tr("mismatches used up")
+        ),
+        DefeatReason::Countdown => tr("The countdown reached zero"),
+        DefeatReason::SuddenDeath => tr("A single mismatch ends the run"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::challenge::Challenge;
+
+    #[test]
+    fn fail_condition_codes_roundtrip() {
+        for condition in FailCondition::ALL {
+            assert_eq!(FailCondition::from_code(condition.code()), condition);
+        }
+        assert_eq!(FailCondition::from_code("bogus"), FailCondition::Off);
+    }
+
+    #[test]
+    fn limits_scale_with_board_size() {
+        assert_eq!(mismatch_cap(12, 2), 6);
+        assert_eq!(mismatch_cap(24, 3), 8);
+        assert_eq!(countdown_secs(12), 60);
+        assert!(countdown_secs(48) > countdown_secs(24));
+    }
+
+    #[test]
+    fn weekly_board_ignores_the_fail_condition() {
+        let mut st = AppState {
+            fail_condition: FailCondition::MismatchCap,
+            ..Default::default()
+        };
+        assert!(applies_to(&st));
+        st.challenge = Some(Challenge::weekly("2026-W42"));
+        assert!(!applies_to(&st));
+    }
+}
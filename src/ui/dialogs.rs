@@ -5,6 +5,9 @@ use adw::prelude::*;
 
 use crate::i18n::tr;
 
+use super::resources;
+use super::shortcuts;
+
 fn xml_escape(value: &str) -> String {
     value
         .replace('&', "&amp;")
@@ -36,6 +39,11 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
     let slide_5_desc = xml_escape(&tr(
         "Stumble several times and hidden cards may reshuffle",
     ));
+    let howto_flow = resources::uri(resources::HOWTO_FLOW);
+    let howto_goal = resources::uri(resources::HOWTO_GOAL);
+    let howto_modes = resources::uri(resources::HOWTO_MODES);
+    let howto_difficulty = resources::uri(resources::HOWTO_DIFFICULTY);
+    let howto_restless = resources::uri(resources::HOWTO_RESTLESS);
     let prev_tip = xml_escape(&tr("Previous"));
     let next_tip = xml_escape(&tr("Next"));
 
@@ -90,7 +98,7 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
                                 <property name="ratio">1</property>
                                 <child>
                                   <object class="GtkPicture">
-                                    <property name="file">resource://io/github/basshift/Recall/howto/01-flow.svg</property>
+                                    <property name="file">{howto_flow}</property>
                                   </object>
                                 </child>
                               </object>
@@ -118,7 +126,7 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
                                 <property name="ratio">1</property>
                                 <child>
                                   <object class="GtkPicture">
-                                    <property name="file">resource://io/github/basshift/Recall/howto/02-goal.svg</property>
+                                    <property name="file">{howto_goal}</property>
                                   </object>
                                 </child>
                               </object>
@@ -146,7 +154,7 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
                                 <property name="ratio">1</property>
                                 <child>
                                   <object class="GtkPicture">
-                                    <property name="file">resource://io/github/basshift/Recall/howto/03-modes.svg</property>
+                                    <property name="file">{howto_modes}</property>
                                   </object>
                                 </child>
                               </object>
@@ -174,7 +182,7 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
                                 <property name="ratio">1</property>
                                 <child>
                                   <object class="GtkPicture">
-                                    <property name="file">resource://io/github/basshift/Recall/howto/04-difficulty.svg</property>
+                                    <property name="file">{howto_difficulty}</property>
                                   </object>
                                 </child>
                               </object>
@@ -202,7 +210,7 @@ pub fn show_instructions_dialog(app: &adw::Application) -> adw::Dialog {
                                 <property name="ratio">1</property>
                                 <child>
                                   <object class="GtkPicture">
-                                    <property name="file">resource://io/github/basshift/Recall/howto/05-restless.svg</property>
+                                    <property name="file">{howto_restless}</property>
                                   </object>
                                 </child>
                               </object>
@@ -373,13 +381,30 @@ pub fn create_keyboard_shortcuts_overlay() -> gtk::ShortcutsWindow {
     let general_title = xml_escape(&tr("General"));
     let move_cards = xml_escape(&tr("Move between cards"));
     let flip_card = xml_escape(&tr("Flip selected card"));
+    let tag_card = xml_escape(&tr("Tag selected card"));
+    let show_hint = xml_escape(&tr("Show a hint"));
     let game_action = xml_escape(&tr("Restart game"));
+    let pause_game = xml_escape(&tr("Pause game"));
     let back_to_menu = xml_escape(&tr("Back to menu"));
     let show_shortcuts = xml_escape(&tr("Show shortcuts"));
     let how_to_play = xml_escape(&tr("How to play"));
     let preferences = xml_escape(&tr("Preferences"));
     let back_main = xml_escape(&tr("Back to main menu"));
     let quit = xml_escape(&tr("Quit"));
+    let shortcut_accel = |id: &str| {
+        xml_escape(
+            &shortcuts::find(id)
+                .map(shortcuts::accelerator)
+                .unwrap_or_default(),
+        )
+    };
+    let flip_accel = shortcut_accel("flip");
+    let tag_accel = shortcut_accel("tag");
+    let hint_accel = shortcut_accel("hint");
+    let restart_accel = shortcut_accel("restart");
+    let pause_accel = shortcut_accel("pause");
+    let instructions_accel = shortcut_accel("instructions");
+    let home_accel = shortcut_accel("home");
 
     let xml = format!(
         r#"
@@ -404,13 +429,31 @@ pub fn create_keyboard_shortcuts_overlay() -> gtk::ShortcutsWindow {
             <child>
               <object class="GtkShortcutsShortcut">
                 <property name="title">{flip_card}</property>
-                <property name="accelerator">space Return</property>
+                <property name="accelerator">{flip_accel} Return</property>
+              </object>
+            </child>
+            <child>
+              <object class="GtkShortcutsShortcut">
+                <property name="title">{tag_card}</property>
+                <property name="accelerator">{tag_accel}</property>
+              </object>
+            </child>
+            <child>
+              <object class="GtkShortcutsShortcut">
+                <property name="title">{show_hint}</property>
+                <property name="accelerator">{hint_accel}</property>
               </object>
             </child>
             <child>
               <object class="GtkShortcutsShortcut">
                 <property name="title">{game_action}</property>
-                <property name="accelerator">&lt;Primary&gt;r</property>
+                <property name="accelerator">{restart_accel}</property>
+              </object>
+            </child>
+            <child>
+              <object class="GtkShortcutsShortcut">
+                <property name="title">{pause_game}</property>
+                <property name="accelerator">{pause_accel}</property>
               </object>
             </child>
             <child>
@@ -433,7 +476,7 @@ pub fn create_keyboard_shortcuts_overlay() -> gtk::ShortcutsWindow {
             <child>
               <object class="GtkShortcutsShortcut">
                 <property name="title">{how_to_play}</property>
-                <property name="accelerator">F1</property>
+                <property name="accelerator">{instructions_accel}</property>
               </object>
             </child>
             <child>
@@ -445,7 +488,7 @@ pub fn create_keyboard_shortcuts_overlay() -> gtk::ShortcutsWindow {
             <child>
               <object class="GtkShortcutsShortcut">
                 <property name="title">{back_main}</property>
-                <property name="accelerator">&lt;Primary&gt;m</property>
+                <property name="accelerator">{home_accel}</property>
               </object>
             </child>
             <child>
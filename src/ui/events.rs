@@ -0,0 +1,100 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use gtk4::glib;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    TileFlipped(usize),
+    MatchFound(Vec<usize>),
+    MismatchMade(Vec<usize>),
+    PenaltyApplied(String),
+    RoundFinished { round: u32, level_up: Option<u8> },
+    // Cards turned without a player move, or a whole board laid out.
+    BoardChanged,
+}
+
+type Subscriber = Rc<dyn Fn(&GameEvent)>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionId(u64);
+
+thread_local! {
+    static SUBSCRIBERS: RefCell<Vec<(SubscriptionId, Subscriber)>> =
+        const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    static QUEUE: RefCell<VecDeque<GameEvent>> = const { RefCell::new(VecDeque::new()) };
+    static FLUSH_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn subscribe(subscriber: impl Fn(&GameEvent) + 'static) -> SubscriptionId {
+    let id = SubscriptionId(NEXT_ID.replace(NEXT_ID.get() + 1));
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().push((id, Rc::new(subscriber))));
+    id
+}
+
+pub fn unsubscribe(id: SubscriptionId) {
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().retain(|(other, _)| *other != id));
+}
+
+// Queues the event for the next main loop iteration, so emitters can hold the
+// app state borrow while subscribers are free to borrow it again.
+pub fn emit(event: GameEvent) {
+    tracing::debug!("event {event:?}");
+    QUEUE.with(|queue| queue.borrow_mut().push_back(event));
+    if !FLUSH_SCHEDULED.replace(true) {
+        glib::idle_add_local_once(flush);
+    }
+}
+
+fn flush() {
+    FLUSH_SCHEDULED.set(false);
+    while let Some(event) = QUEUE.with(|queue| queue.borrow_mut().pop_front()) {
+        let subscribers = SUBSCRIBERS.with(|subscribers| subscribers.borrow().clone());
+        for (_, subscriber) in subscribers {
+            subscriber(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_events_reach_subscribers_in_order() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        subscribe({
+            let seen = seen.clone();
+            move |event| seen.borrow_mut().push(event.clone())
+        });
+        QUEUE.with(|queue| {
+            let mut queue = queue.borrow_mut();
+            queue.push_back(GameEvent::TileFlipped(3));
+            queue.push_back(GameEvent::MatchFound(vec![3, 7]));
+        });
+        flush();
+
+        assert_eq!(
+            *seen.borrow(),
+            vec![GameEvent::TileFlipped(3), GameEvent::MatchFound(vec![3, 7])]
+        );
+    }
+
+    #[test]
+    fn unsubscribed_handlers_stop_receiving_events() {
+        let seen = Rc::new(Cell::new(0));
+        let id = subscribe({
+            let seen = seen.clone();
+            move |_| seen.set(seen.get() + 1)
+        });
+        QUEUE.with(|queue| queue.borrow_mut().push_back(GameEvent::BoardChanged));
+        flush();
+        unsubscribe(id);
+        QUEUE.with(|queue| queue.borrow_mut().push_back(GameEvent::BoardChanged));
+        flush();
+
+        assert_eq!(seen.get(), 1);
+    }
+}
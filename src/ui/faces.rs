@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use gtk4::gdk::prelude::*;
+use gtk4::{cairo, gdk_pixbuf};
+
+use super::resources;
+
+pub const CARD_FACES_KEY: &str = "card-faces";
+const FACE_CACHE_LIMIT: usize = 256;
+
+// Symbols from the emoji pool that also ship as SVG artwork. Tiles keep the emoji
+// as their value, so saves, stats and the heatmap never see the artwork names.
+pub const FACE_ART: [(&str, &str); 24] = [
+    ("⭐", "star"),
+    ("☀️", "sun"),
+    ("💧", "drop"),
+    ("🔥", "fire"),
+    ("🌈", "rainbow"),
+    ("🍀", "clover"),
+    ("🌊", "wave"),
+    ("🪐", "planet"),
+    ("🍎", "apple"),
+    ("🍒", "cherries"),
+    ("🍋", "lemon"),
+    ("🍇", "grapes"),
+    ("🍉", "watermelon"),
+    ("🥕", "carrot"),
+    ("🍄", "mushroom"),
+    ("🌳", "tree"),
+    ("🌵", "cactus"),
+    ("🌷", "tulip"),
+    ("⚽", "ball"),
+    ("🎯", "target"),
+    ("🎲", "die"),
+    ("🔍", "magnifier"),
+    ("🚀", "rocket"),
+    ("🐟", "fish"),
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CardFaces {
+    #[default]
+    Emoji,
+    Artwork,
+}
+
+impl CardFaces {
+    pub const ALL: [CardFaces; 2] = [CardFaces::Emoji, CardFaces::Artwork];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            CardFaces::Emoji => "emoji",
+            CardFaces::Artwork => "artwork",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|faces| faces.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CardFaces::Emoji => "Emoji",
+            CardFaces::Artwork => "Built-in artwork",
+        }
+    }
+}
+
+thread_local! {
+    static FACE_CACHE: RefCell<HashMap<(String, i32), gdk_pixbuf::Pixbuf>> =
+        RefCell::new(HashMap::new());
+}
+
+pub fn symbols() -> Vec<&'static str> {
+    FACE_ART.iter().map(|(symbol, _)| *symbol).collect()
+}
+
+// Larger boards need more distinct symbols than the artwork has, so they stay on emoji.
+pub fn fits(group_count: usize) -> bool {
+    group_count <= FACE_ART.len()
+}
+
+// A board is drawn with artwork only when every card has some, never a mix.
+pub fn covers<'a>(values: impl IntoIterator<Item = &'a str>) -> bool {
+    values.into_iter().all(|value| resource_path(value).is_some())
+}
+
+pub fn resource_path(value: &str) -> Option<String> {
+    let (_, name) = FACE_ART.iter().find(|(symbol, _)| *symbol == value)?;
+    Some(format!("{}/{name}.svg", resources::FACES_DIR))
+}
+
+fn load_face(value: &str, size: i32) -> Option<gdk_pixbuf::Pixbuf> {
+    FACE_CACHE.with(|cache| {
+        let key = (value.to_string(), size);
+        if let Some(pixbuf) = cache.borrow().get(&key) {
+            return Some(pixbuf.clone());
+        }
+        let path = resource_path(value)?;
+        let pixbuf = match gdk_pixbuf::Pixbuf::from_resource_at_scale(&path, size, size, true) {
+            Ok(pixbuf) => pixbuf,
+            Err(err) => {
+                tracing::warn!("failed to load card face {path}: {err}");
+                return None;
+            }
+        };
+        let mut cache = cache.borrow_mut();
+        // Window resizes produce a new size per frame, start over rather than grow forever.
+        if cache.len() >= FACE_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, pixbuf.clone());
+        Some(pixbuf)
+    })
+}
+
+// Rasterizes the face at the exact pixel size so it stays sharp at every board size.
+pub fn draw_face(cr: &cairo::Context, value: &str, width: i32, height: i32, size: f64) -> bool {
+    let size = size.round().max(1.0) as i32;
+    let Some(pixbuf) = load_face(value, size) else {
+        return false;
+    };
+    let x = (width - pixbuf.width()) as f64 / 2.0;
+    let y = (height - pixbuf.height()) as f64 / 2.0;
+    cr.set_source_pixbuf(&pixbuf, x, y);
+    if let Err(err) = cr.paint() {
+        tracing::warn!("failed to paint card face: {err}");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faces_are_unique_and_map_to_bundled_svgs() {
+        let faces = symbols();
+        for (idx, face) in faces.iter().enumerate() {
+            assert!(!faces[idx + 1..].contains(face));
+            let path = resource_path(face).expect("artwork symbols have a file");
+            let file = path.replace(resources::FACES_DIR, concat!(env!("CARGO_MANIFEST_DIR"), "/data/faces"));
+            assert!(std::path::Path::new(&file).exists(), "missing {file}");
+        }
+        assert!(resource_path("🐶").is_none());
+        assert!(covers(["⭐", "🍎"]));
+        assert!(!covers(["⭐", "🐶"]));
+        assert!(fits(24) && !fits(25));
+        assert_eq!(CardFaces::from_code("artwork"), CardFaces::Artwork);
+    }
+}
@@ -0,0 +1,190 @@
+use gtk4::pango::prelude::*;
+
+use super::state::AppState;
+
+pub const FONT_FAMILY_KEY: &str = "font-family";
+pub const TEXT_SCALE_KEY: &str = "text-scale";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FontChoice {
+    #[default]
+    Default,
+    OpenDyslexic,
+    AtkinsonHyperlegible,
+    Lexend,
+}
+
+impl FontChoice {
+    pub const ALL: [FontChoice; 4] = [
+        FontChoice::Default,
+        FontChoice::OpenDyslexic,
+        FontChoice::AtkinsonHyperlegible,
+        FontChoice::Lexend,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            FontChoice::Default => "default",
+            FontChoice::OpenDyslexic => "opendyslexic",
+            FontChoice::AtkinsonHyperlegible => "atkinson-hyperlegible",
+            FontChoice::Lexend => "lexend",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|choice| choice.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FontChoice::Default => "Default",
+            FontChoice::OpenDyslexic => "OpenDyslexic",
+            FontChoice::AtkinsonHyperlegible => "Atkinson Hyperlegible",
+            FontChoice::Lexend => "Lexend",
+        }
+    }
+
+    // Pango family list, the fallbacks keep tiles readable if a font goes missing.
+    pub fn family(self) -> &'static str {
+        match self {
+            FontChoice::Default => "Cantarell, Noto Sans, sans",
+            FontChoice::OpenDyslexic => "OpenDyslexic, Cantarell, sans",
+            FontChoice::AtkinsonHyperlegible => "Atkinson Hyperlegible, Cantarell, sans",
+            FontChoice::Lexend => "Lexend, Cantarell, sans",
+        }
+    }
+
+    fn installed_name(self) -> Option<&'static str> {
+        match self {
+            FontChoice::Default => None,
+            _ => Some(self.label()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextScale {
+    #[default]
+    Normal,
+    Large,
+    Larger,
+    Largest,
+}
+
+impl TextScale {
+    pub const ALL: [TextScale; 4] = [
+        TextScale::Normal,
+        TextScale::Large,
+        TextScale::Larger,
+        TextScale::Largest,
+    ];
+
+    pub fn factor(self) -> f64 {
+        match self {
+            TextScale::Normal => 1.0,
+            TextScale::Large => 1.15,
+            TextScale::Larger => 1.3,
+            TextScale::Largest => 1.5,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            TextScale::Normal => "100",
+            TextScale::Large => "115",
+            TextScale::Larger => "130",
+            TextScale::Largest => "150",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|scale| scale.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> String {
+        format!("{}%", self.code())
+    }
+}
+
+// Choices whose font is actually installed, the default is always offered.
+pub fn available_choices() -> Vec<FontChoice> {
+    let installed = pangocairo::FontMap::default()
+        .list_families()
+        .iter()
+        .map(|family| family.name().to_lowercase())
+        .collect::<Vec<String>>();
+    FontChoice::ALL
+        .into_iter()
+        .filter(|choice| {
+            choice
+                .installed_name()
+                .is_none_or(|name| installed.contains(&name.to_lowercase()))
+        })
+        .collect()
+}
+
+pub fn tile_font_size(base_size: f64, max_size: f64, scale: TextScale) -> f64 {
+    (base_size * scale.factor()).min(max_size)
+}
+
+pub fn css(font: FontChoice, scale: TextScale) -> String {
+    let percent = (scale.factor() * 100.0).round() as u32;
+    // The default keeps whatever interface font the desktop uses.
+    if font == FontChoice::Default {
+        return format!("window.app-window {{ font-size: {percent}%; }}");
+    }
+    let family = font
+        .family()
+        .split(',')
+        .map(|name| match name.trim() {
+            "sans" => "sans-serif".to_string(),
+            name => format!("\"{name}\""),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("window.app-window {{ font-family: {family}; font-size: {percent}%; }}")
+}
+
+// The board writes its size-dependent rules into `board_css`, fonts are appended here.
+pub fn apply_dynamic_css(st: &AppState) {
+    if let Some(provider) = &st.dynamic_css_provider {
+        provider.load_from_string(&format!(
+            "{} {}",
+            st.board_css,
+            css(st.font_choice, st.text_scale)
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_roundtrip_and_fall_back_to_defaults() {
+        for choice in FontChoice::ALL {
+            assert_eq!(FontChoice::from_code(choice.code()), choice);
+        }
+        for scale in TextScale::ALL {
+            assert_eq!(TextScale::from_code(scale.code()), scale);
+        }
+        assert_eq!(FontChoice::from_code("comic"), FontChoice::Default);
+        assert_eq!(TextScale::from_code("999"), TextScale::Normal);
+    }
+
+    #[test]
+    fn css_quotes_families_and_scales_text() {
+        let css = css(FontChoice::OpenDyslexic, TextScale::Larger);
+
+        assert!(css.contains("font-family: \"OpenDyslexic\", \"Cantarell\", sans-serif;"));
+        assert!(css.contains("font-size: 130%;"));
+        assert!(!super::css(FontChoice::Default, TextScale::Normal).contains("font-family"));
+        assert_eq!(tile_font_size(20.0, 26.0, TextScale::Largest), 26.0);
+    }
+}
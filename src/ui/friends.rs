@@ -0,0 +1,307 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use gio::prelude::*;
+use rand::Rng;
+
+use super::net;
+use super::paths;
+use super::settings;
+use super::state::{ModeRecord, PlayerRecords};
+
+pub const SCORE_NAME_KEY: &str = "score-key-name";
+const SCORE_ID_KEY: &str = "score-key-id";
+const FRIENDS_FILE_NAME: &str = "friends.v1";
+const KEY_PREFIX: &str = "recall-key";
+const KEY_VERSION: &str = "2";
+// Keys shared before ids existed; they carry a name only.
+const LEGACY_KEY_VERSION: &str = "1";
+const MAX_NAME_CHARS: usize = 24;
+pub const LEVELS: usize = 4;
+
+// Best time per level for Classic and Trio, which is all a key shares.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScoreKey {
+    // Empty for legacy keys.
+    pub id: String,
+    pub name: String,
+    pub classic: [Option<u32>; LEVELS],
+    pub trio: [Option<u32>; LEVELS],
+}
+
+fn best_times(records: &[ModeRecord]) -> [Option<u32>; LEVELS] {
+    let mut best = [None; LEVELS];
+    for entry in records.iter().filter(|entry| entry.is_ranked()) {
+        let Some(slot) = best.get_mut(usize::from(entry.level.saturating_sub(1))) else {
+            continue;
+        };
+        *slot = Some(slot.map_or(entry.time_secs, |time: u32| time.min(entry.time_secs)));
+    }
+    best
+}
+
+fn times_to_code(times: &[Option<u32>; LEVELS]) -> String {
+    times
+        .iter()
+        .map(|time| time.map_or_else(|| "-".to_string(), |time| time.to_string()))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn times_from_code(code: &str) -> Option<[Option<u32>; LEVELS]> {
+    let mut times = [None; LEVELS];
+    let mut parts = code.split(',');
+    for slot in &mut times {
+        let part = parts.next()?;
+        *slot = if part == "-" { None } else { Some(part.parse().ok()?) };
+    }
+    parts.next().is_none().then_some(times)
+}
+
+pub fn clean_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| *ch != ':' && *ch != '\t' && !ch.is_control())
+        .take(MAX_NAME_CHARS)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+impl ScoreKey {
+    pub fn from_records(id: &str, name: &str, records: &PlayerRecords) -> Self {
+        Self {
+            id: id.to_string(),
+            name: clean_name(name),
+            classic: best_times(&records.classic),
+            trio: best_times(&records.trio),
+        }
+    }
+
+    pub fn to_code(&self) -> String {
+        format!(
+            "{KEY_PREFIX}:{KEY_VERSION}:{}:{}:{}:{}",
+            self.id,
+            self.name,
+            times_to_code(&self.classic),
+            times_to_code(&self.trio)
+        )
+    }
+
+    // Accepts the key anywhere in the text, so a pasted message or a shared file both work.
+    pub fn from_code(text: &str) -> Option<Self> {
+        let start = text.find(KEY_PREFIX)?;
+        let code = text[start..].split_whitespace().next()?;
+        let mut parts = code.split(':');
+        if parts.next()? != KEY_PREFIX {
+            return None;
+        }
+        let id = match parts.next()? {
+            KEY_VERSION => clean_id(parts.next()?)?,
+            LEGACY_KEY_VERSION => String::new(),
+            _ => return None,
+        };
+        let name = clean_name(parts.next()?);
+        if name.is_empty() {
+            return None;
+        }
+        let key = Self {
+            id,
+            name,
+            classic: times_from_code(parts.next()?)?,
+            trio: times_from_code(parts.next()?)?,
+        };
+        parts.next().is_none().then_some(key)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Friend {
+    pub key: ScoreKey,
+    // File path or https address the key was imported from, used to refresh it.
+    pub source: Option<String>,
+}
+
+impl Friend {
+    // The key's id tells friends apart; legacy keys fall back to where they came from.
+    fn is_same_as(&self, other: &Friend) -> bool {
+        if !self.key.id.is_empty() || !other.key.id.is_empty() {
+            return self.key.id == other.key.id;
+        }
+        self.source.is_some() && self.source == other.source
+    }
+
+    fn to_line(&self) -> String {
+        format!("{}\t{}", self.source.as_deref().unwrap_or_default(), self.key.to_code())
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let (source, code) = line.split_once('\t')?;
+        Some(Self {
+            key: ScoreKey::from_code(code)?,
+            source: (!source.is_empty()).then(|| source.to_string()),
+        })
+    }
+}
+
+fn clean_id(id: &str) -> Option<String> {
+    (!id.is_empty() && id.chars().all(|ch| ch.is_ascii_hexdigit())).then(|| id.to_string())
+}
+
+// Empty until the player picks a name; nothing is taken from the account.
+pub fn own_name() -> String {
+    clean_name(&settings::string(SCORE_NAME_KEY, ""))
+}
+
+// A random id, created on first use, so friends who share a name stay apart.
+pub fn own_id() -> String {
+    let stored = settings::string(SCORE_ID_KEY, "");
+    if let Some(id) = clean_id(&stored) {
+        return id;
+    }
+    let id: String = (0..16)
+        .map(|_| format!("{:x}", rand::rng().random_range(0..16u8)))
+        .collect();
+    settings::set_string(SCORE_ID_KEY, &id);
+    id
+}
+
+fn friends_path() -> PathBuf {
+    paths::config_dir().join(FRIENDS_FILE_NAME)
+}
+
+pub fn load_friends() -> Vec<Friend> {
+    let Ok(raw) = fs::read_to_string(friends_path()) else {
+        return Vec::new();
+    };
+    raw.lines().filter_map(Friend::from_line).collect()
+}
+
+pub fn save_friends(friends: &[Friend]) -> io::Result<()> {
+    let raw: String = friends
+        .iter()
+        .map(|friend| format!("{}\n", friend.to_line()))
+        .collect();
+    paths::write_atomic(&friends_path(), &raw)
+}
+
+// A friend who shares a new key replaces their old one, even under a new name.
+pub fn add_or_replace(friends: &mut Vec<Friend>, friend: Friend) {
+    if let Some(existing) = friends
+        .iter_mut()
+        .find(|existing| existing.is_same_as(&friend))
+    {
+        *existing = friend;
+    } else {
+        friends.push(friend);
+    }
+}
+
+fn file_path_for(input: &str) -> Option<PathBuf> {
+    if input.starts_with("file://") {
+        return gio::File::for_uri(input).path();
+    }
+    let path = PathBuf::from(input);
+    path.is_file().then_some(path)
+}
+
+pub async fn import(input: &str) -> Result<Friend, String> {
+    let input = input.trim();
+    let (text, source) = if input.starts_with("https://") || input.starts_with("http://") {
+        let body = net::fetch(input).await.map_err(|err| err.to_string())?;
+        (String::from_utf8_lossy(&body).to_string(), Some(input.to_string()))
+    } else if let Some(path) = file_path_for(input) {
+        let text = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+        (text, Some(path.display().to_string()))
+    } else {
+        (input.to_string(), None)
+    };
+    let key = ScoreKey::from_code(&text).ok_or_else(|| "no score key found".to_string())?;
+    Ok(Friend { key, source })
+}
+
+// Friends whose source cannot be reached keep their last known times.
+pub async fn refresh(friends: Vec<Friend>) -> Vec<Friend> {
+    let mut refreshed = Vec::with_capacity(friends.len());
+    for friend in friends {
+        let Some(source) = friend.source.clone() else {
+            refreshed.push(friend);
+            continue;
+        };
+        match import(&source).await {
+            Ok(updated) => refreshed.push(updated),
+            Err(err) => {
+                tracing::warn!("failed to refresh score key from {source}: {err}");
+                refreshed.push(friend);
+            }
+        }
+    }
+    refreshed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::Rank;
+
+    fn classic(level: u8, time_secs: u32) -> ModeRecord {
+        ModeRecord {
+            level,
+            time_secs,
+            precision_pct: 90,
+            rank: Rank::A,
+            recorded_at: None,
+            modifiers: String::new(),
+        }
+    }
+
+    #[test]
+    fn keys_keep_the_best_time_per_level() {
+        let records = PlayerRecords {
+            classic: vec![classic(1, 70), classic(1, 52), classic(3, 140)],
+            ..PlayerRecords::default()
+        };
+        let key = ScoreKey::from_records("a1b2", "Ada: L.", &records);
+
+        assert_eq!(key.name, "Ada L.");
+        assert_eq!(key.to_code(), "recall-key:2:a1b2:Ada L.:52,-,140,-:-,-,-,-");
+        let pasted = format!("Beat this! {}\n", key.to_code());
+        assert_eq!(ScoreKey::from_code(&pasted), Some(key));
+    }
+
+    #[test]
+    fn malformed_keys_are_rejected() {
+        assert!(ScoreKey::from_code("recall-key:3:a1:Ada:-,-,-,-:-,-,-,-").is_none());
+        assert!(ScoreKey::from_code("recall-key:2:a1::-,-,-,-:-,-,-,-").is_none());
+        assert!(ScoreKey::from_code("recall-key:2:not-hex:Ada:-,-,-,-:-,-,-,-").is_none());
+        assert!(ScoreKey::from_code("recall-key:2:a1:Ada:1,2,3:-,-,-,-").is_none());
+        assert!(ScoreKey::from_code("hello").is_none());
+        let legacy = ScoreKey::from_code("recall-key:1:Ada:60,-,-,-:-,-,-,-").unwrap();
+        assert_eq!((legacy.id.as_str(), legacy.name.as_str()), ("", "Ada"));
+    }
+
+    #[test]
+    fn friends_are_told_apart_by_id_not_name() {
+        let sam = ScoreKey::from_code("recall-key:2:a1:Sam:60,-,-,-:-,-,-,-").unwrap();
+        let friend = Friend {
+            key: sam.clone(),
+            source: Some("https://example.org/sam.txt".to_string()),
+        };
+        assert_eq!(Friend::from_line(&friend.to_line()), Some(friend.clone()));
+
+        let mut friends = vec![friend];
+        let other_sam = ScoreKey::from_code("recall-key:2:b2:Sam:70,-,-,-:-,-,-,-").unwrap();
+        add_or_replace(&mut friends, Friend { key: other_sam, source: None });
+        assert_eq!(friends.len(), 2);
+
+        let renamed = ScoreKey {
+            name: "Samantha".to_string(),
+            classic: [Some(55), None, None, None],
+            ..sam
+        };
+        add_or_replace(&mut friends, Friend { key: renamed, source: None });
+        assert_eq!(friends.len(), 2);
+        assert_eq!(friends[0].key.name, "Samantha");
+        assert_eq!(friends[0].key.classic[0], Some(55));
+    }
+}
@@ -0,0 +1,220 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+use super::infinite_flow::INFINITE_MILESTONE_HOLD_MS;
+use super::par_time::{self, Tension};
+use super::timers;
+
+// Who put a message in the status badge, so each source only ever clears its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StatusSource {
+    Paused,
+    NewBest,
+    LevelUp,
+    Milestone,
+    Debug,
+}
+
+impl StatusSource {
+    // A message stays hidden while one of higher priority is up, and shows again once it goes.
+    fn priority(self) -> u8 {
+        match self {
+            StatusSource::Paused => 4,
+            StatusSource::NewBest => 3,
+            StatusSource::LevelUp => 2,
+            StatusSource::Milestone => 1,
+            StatusSource::Debug => 0,
+        }
+    }
+
+    // Everything but the pause badge clears itself.
+    fn lifetime(self) -> Option<Duration> {
+        let ms = match self {
+            StatusSource::Paused => return None,
+            StatusSource::NewBest => 2600,
+            StatusSource::LevelUp => 2200,
+            StatusSource::Milestone => INFINITE_MILESTONE_HOLD_MS,
+            StatusSource::Debug => 1200,
+        };
+        Some(Duration::from_millis(ms))
+    }
+}
+
+// Active messages; the badge shows the most important, newest first among equals.
+#[derive(Debug, Default)]
+struct StatusStack {
+    entries: Vec<(u64, StatusSource, String)>,
+    next_id: u64,
+}
+
+impl StatusStack {
+    fn set(&mut self, source: StatusSource, text: &str) -> u64 {
+        self.clear(source);
+        self.next_id += 1;
+        self.entries.push((self.next_id, source, text.to_string()));
+        self.next_id
+    }
+
+    fn clear(&mut self, source: StatusSource) {
+        self.entries.retain(|(_, owner, _)| *owner != source);
+    }
+
+    // Drops one particular message, leaving a newer one from the same source alone.
+    fn expire(&mut self, id: u64) {
+        self.entries.retain(|(entry, _, _)| *entry != id);
+    }
+
+    fn shown(&self) -> Option<&str> {
+        self.entries
+            .iter()
+            .max_by_key(|(id, source, _)| (source.priority(), *id))
+            .map(|(_, _, text)| text.as_str())
+    }
+}
+
+// The header title while a board is up: the app name over a mode chip, the clock
+// and a status badge, each updated on its own.
+#[derive(Clone)]
+pub struct GameTitle {
+    root: gtk::Box,
+    mode: gtk::Label,
+    timer: gtk::Label,
+    note: gtk::Label,
+    status: gtk::Label,
+    statuses: Rc<RefCell<StatusStack>>,
+}
+
+impl GameTitle {
+    pub fn new() -> Self {
+        let root = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        root.set_valign(gtk::Align::Center);
+        root.set_halign(gtk::Align::Center);
+        root.set_hexpand(true);
+
+        let main = gtk::Label::builder()
+            .label("Recall")
+            .halign(gtk::Align::Center)
+            .css_classes(vec!["game-title-main"])
+            .build();
+
+        let slots = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        slots.set_halign(gtk::Align::Center);
+        let mode = gtk::Label::builder()
+            .css_classes(vec!["game-title-subtitle", "game-title-mode", "caption"])
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .build();
+        let timer = gtk::Label::builder()
+            .css_classes(vec!["game-title-subtitle", "game-title-timer", "caption"])
+            .build();
+        let note = gtk::Label::builder()
+            .css_classes(vec!["game-title-subtitle", "game-title-note", "caption", "dim-label"])
+            .visible(false)
+            .build();
+        let status = gtk::Label::builder()
+            .css_classes(vec!["game-title-status", "caption"])
+            .visible(false)
+            .build();
+        slots.append(&mode);
+        slots.append(&timer);
+        slots.append(&note);
+        slots.append(&status);
+
+        root.append(&main);
+        root.append(&slots);
+        Self {
+            root,
+            mode,
+            timer,
+            note,
+            status,
+            statuses: Rc::new(RefCell::new(StatusStack::default())),
+        }
+    }
+
+    pub fn widget(&self) -> &gtk::Box {
+        &self.root
+    }
+
+    pub fn set_mode(&self, text: &str) {
+        self.mode.set_text(text);
+    }
+
+    // `None` hides the clock, e.g. when the compact header shows its own.
+    pub fn set_timer(&self, text: Option<&str>, tension: Option<Tension>) {
+        self.timer.set_visible(text.is_some());
+        self.timer.set_text(text.unwrap_or_default());
+        par_time::apply_tension(&self.timer, tension);
+    }
+
+    // A quiet aside after the clock, such as the best Infinite round.
+    pub fn set_note(&self, text: Option<&str>) {
+        self.note.set_visible(text.is_some());
+        self.note.set_text(text.unwrap_or_default());
+    }
+
+    // Replaces the source's previous message; its expiry follows `StatusSource::lifetime`.
+    pub fn show_status(&self, source: StatusSource, text: &str) {
+        let id = self.statuses.borrow_mut().set(source, text);
+        self.refresh_status();
+        if let Some(lifetime) = source.lifetime() {
+            let title = self.clone();
+            timers::once(lifetime, move || {
+                title.statuses.borrow_mut().expire(id);
+                title.refresh_status();
+            });
+        }
+    }
+
+    pub fn clear_status(&self, source: StatusSource) {
+        self.statuses.borrow_mut().clear(source);
+        self.refresh_status();
+    }
+
+    fn refresh_status(&self) {
+        match self.statuses.borrow().shown() {
+            Some(text) => {
+                self.status.set_text(text);
+                self.status.set_visible(true);
+            }
+            None => self.status.set_visible(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clearing_a_status_reveals_the_one_underneath() {
+        let mut statuses = StatusStack::default();
+        statuses.set(StatusSource::Milestone, "Hard ×2!");
+        statuses.set(StatusSource::Debug, "Near win");
+        assert_eq!(statuses.shown(), Some("Hard ×2!"));
+
+        let stale = statuses.set(StatusSource::Milestone, "Hard ×3!");
+        statuses.set(StatusSource::Milestone, "Hard ×4!");
+        statuses.expire(stale);
+        assert_eq!(statuses.shown(), Some("Hard ×4!"));
+        statuses.clear(StatusSource::Milestone);
+        assert_eq!(statuses.shown(), Some("Near win"));
+        statuses.clear(StatusSource::Debug);
+        assert_eq!(statuses.shown(), None);
+    }
+
+    #[test]
+    fn pause_outranks_later_transient_messages() {
+        let mut statuses = StatusStack::default();
+        statuses.set(StatusSource::Paused, "Paused");
+        statuses.set(StatusSource::LevelUp, "Level up: Hard");
+        statuses.set(StatusSource::Debug, "Shuffled");
+        assert_eq!(statuses.shown(), Some("Paused"));
+        statuses.clear(StatusSource::Paused);
+        assert_eq!(statuses.shown(), Some("Level up: Hard"));
+        assert!(StatusSource::Paused.lifetime().is_none());
+    }
+}
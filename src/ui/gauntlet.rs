@@ -0,0 +1,100 @@
+use crate::i18n::tr;
+
+use super::classic::difficulty_from_level;
+use super::state::Difficulty;
+
+pub const GAUNTLET_LEVELS: [u8; 4] = [1, 2, 3, 4];
+pub const GAUNTLET_SPLASH_MS: u64 = 1800;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GauntletRun {
+    pub board_index: usize,
+}
+
+impl GauntletRun {
+    pub fn board_count(&self) -> usize {
+        GAUNTLET_LEVELS.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.board_index >= GAUNTLET_LEVELS.len()
+    }
+
+    pub fn is_last_board(&self) -> bool {
+        self.board_index + 1 == GAUNTLET_LEVELS.len()
+    }
+
+    // Time and precision keep accumulating once the first board is cleared.
+    pub fn carries_run_totals(&self) -> bool {
+        self.board_index > 0
+    }
+
+    pub fn current_level(&self) -> u8 {
+        GAUNTLET_LEVELS
+            .get(self.board_index)
+            .copied()
+            .unwrap_or(GAUNTLET_LEVELS[0])
+    }
+
+    pub fn current_difficulty(&self) -> Difficulty {
+        difficulty_from_level(self.current_level())
+    }
+
+    pub fn advance(&mut self) {
+        self.board_index = (self.board_index + 1).min(GAUNTLET_LEVELS.len());
+    }
+
+    pub fn mode_label(&self) -> String {
+        format!(
+            "{} · {} {}/{}",
+            tr("Gauntlet"),
+            tr("Board"),
+            (self.board_index + 1).min(self.board_count()),
+            self.board_count()
+        )
+    }
+
+    pub fn to_code(self) -> String {
+        self.board_index.to_string()
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let board_index = code.trim().parse::<usize>().ok()?;
+        (board_index < GAUNTLET_LEVELS.len()).then_some(Self { board_index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gauntlet_walks_every_classic_difficulty() {
+        let mut run = GauntletRun::default();
+        let mut seen = Vec::new();
+        while !run.is_complete() {
+            seen.push(run.current_difficulty());
+            run.advance();
+        }
+
+        assert_eq!(
+            seen,
+            vec![
+                Difficulty::Easy,
+                Difficulty::Medium,
+                Difficulty::Hard,
+                Difficulty::Impossible
+            ]
+        );
+        assert!(!GauntletRun::default().carries_run_totals());
+    }
+
+    #[test]
+    fn progress_code_rejects_finished_runs() {
+        let run = GauntletRun { board_index: 2 };
+
+        assert_eq!(GauntletRun::from_code(&run.to_code()), Some(run));
+        assert!(GauntletRun::from_code("4").is_none());
+        assert!(GauntletRun::from_code("next").is_none());
+    }
+}
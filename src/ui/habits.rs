@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use gtk4::glib;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+use super::defeat;
+use super::infinite;
+use super::paths;
+use super::state::AppState;
+
+pub const COLLECT_HABITS_KEY: &str = "collect-habits";
+const HABITS_FILE_NAME: &str = "habits.json";
+const KEEP_DAYS: i32 = 90;
+pub const RECENT_DAYS: i32 = 7;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuitPoint {
+    Early,
+    Middle,
+    Late,
+}
+
+impl QuitPoint {
+    pub const ALL: [Self; 3] = [Self::Early, Self::Middle, Self::Late];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Early => "early",
+            Self::Middle => "middle",
+            Self::Late => "late",
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Early => tr("First third of the board"),
+            Self::Middle => tr("Middle of the board"),
+            Self::Late => tr("Last third of the board"),
+        }
+    }
+
+    pub fn for_progress(cleared: u32, total: u32) -> Self {
+        if total == 0 || cleared * 3 < total {
+            Self::Early
+        } else if cleared * 3 < total * 2 {
+            Self::Middle
+        } else {
+            Self::Late
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct HabitLog {
+    #[serde(default)]
+    pub sessions: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub modes: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub quits: BTreeMap<String, u32>,
+}
+
+impl HabitLog {
+    fn bump(map: &mut BTreeMap<String, u32>, key: &str) {
+        let count = map.entry(key.to_string()).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    // Day keys are ISO dates, so older days sort first.
+    fn prune_days_before(&mut self, cutoff_day: &str) {
+        self.sessions.retain(|day, _| day.as_str() >= cutoff_day);
+    }
+
+    pub fn sessions_on(&self, day: &str) -> u32 {
+        self.sessions.get(day).copied().unwrap_or(0)
+    }
+
+    pub fn top_modes(&self, limit: usize) -> Vec<(String, u32)> {
+        let mut modes: Vec<(String, u32)> = self
+            .modes
+            .iter()
+            .map(|(mode, count)| (mode.clone(), *count))
+            .collect();
+        modes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        modes.truncate(limit);
+        modes
+    }
+
+    pub fn quits_at(&self, point: QuitPoint) -> u32 {
+        self.quits.get(point.code()).copied().unwrap_or(0)
+    }
+}
+
+// A stable code per mode, so the log survives a change of language.
+pub fn mode_key(st: &AppState) -> &'static str {
+    if st.challenge.is_some() {
+        "challenge"
+    } else if st.tournament().is_some() {
+        "tournament"
+    } else if st.bracket.is_some() {
+        "bracket"
+    } else if st.hotseat.is_some() {
+        "hotseat"
+    } else if st.speedrun {
+        "speedrun"
+    } else if st.pattern.is_some() {
+        "pattern"
+    } else if st.nback.is_some() {
+        "nback"
+    } else if st.sudden_death {
+        "sudden-death"
+    } else if st.kids.is_some() {
+        "kids"
+    } else if st.custom.is_some() {
+        "custom"
+    } else if st.gauntlet().is_some() {
+        "gauntlet"
+    } else if infinite::is_infinite(st.difficulty) {
+        "infinite"
+    } else {
+        defeat::mode_code(st.difficulty)
+    }
+}
+
+pub fn mode_key_label(key: &str) -> String {
+    match key {
+        "challenge" => tr("Challenge"),
+        "tournament" => tr("Tournament"),
+        "hotseat" => tr("Pass the Device"),
+        "bracket" => tr("Bracket"),
+        "speedrun" => tr("Speedrun"),
+        "pattern" => tr("Pattern"),
+        "nback" => tr("N-back"),
+        "sudden-death" => tr("Sudden Death"),
+        "kids" => tr("Kids"),
+        "custom" => tr("Custom"),
+        "gauntlet" => tr("Gauntlet"),
+        "infinite" => tr("Infinite"),
+        "trio" => tr("Trio"),
+        "classic" => tr("Classic"),
+        other => other.to_string(),
+    }
+}
+
+pub fn day_key(date: &glib::DateTime) -> Option<String> {
+    date.format("%Y-%m-%d").ok().map(|text| text.to_string())
+}
+
+fn habits_path() -> PathBuf {
+    paths::config_dir().join(HABITS_FILE_NAME)
+}
+
+pub fn load_habits() -> HabitLog {
+    let Ok(raw) = fs::read_to_string(habits_path()) else {
+        return HabitLog::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        tracing::warn!("failed to parse habits: {err}");
+        HabitLog::default()
+    })
+}
+
+fn save_habits(log: &HabitLog) -> io::Result<()> {
+    paths::write_atomic(&habits_path(), &serde_json::to_string(log)?)
+}
+
+// Turning collection off forgets what was collected.
+pub fn clear_habits() {
+    match fs::remove_file(habits_path()) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            tracing::warn!("failed to remove habits: {err}");
+        }
+        _ => {}
+    }
+}
+
+// Nothing is read or written unless the player turned collection on.
+fn update(st: &AppState, f: impl FnOnce(&mut HabitLog)) {
+    if !st.collect_habits {
+        return;
+    }
+    let mut log = load_habits();
+    f(&mut log);
+    if let Err(err) = save_habits(&log) {
+        tracing::warn!("failed to save habits: {err}");
+    }
+}
+
+pub fn note_session(st: &AppState) {
+    let Ok(now) = glib::DateTime::now_local() else {
+        return;
+    };
+    let (Some(today), Some(cutoff)) = (
+        day_key(&now),
+        now.add_days(-KEEP_DAYS).ok().as_ref().and_then(day_key),
+    ) else {
+        return;
+    };
+    update(st, |log| {
+        HabitLog::bump(&mut log.sessions, &today);
+        log.prune_days_before(&cutoff);
+    });
+}
+
+pub fn note_game_start(st: &AppState) {
+    let key = mode_key(st);
+    update(st, |log| HabitLog::bump(&mut log.modes, key));
+}
+
+// Only for a run given up for good; leaving with the run saved to continue later is no quit.
+pub fn note_quit(st: &AppState) {
+    if st.pattern.is_some() || st.nback.is_some() {
+        return;
+    }
+    let (cleared, total) = defeat::pairs_cleared(st);
+    let point = QuitPoint::for_progress(cleared, total);
+    update(st, |log| HabitLog::bump(&mut log.quits, point.code()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quit_points_split_the_board_in_thirds() {
+        assert_eq!(QuitPoint::for_progress(0, 12), QuitPoint::Early);
+        assert_eq!(QuitPoint::for_progress(4, 12), QuitPoint::Middle);
+        assert_eq!(QuitPoint::for_progress(8, 12), QuitPoint::Late);
+        assert_eq!(QuitPoint::for_progress(0, 0), QuitPoint::Early);
+    }
+
+    #[test]
+    fn old_days_are_pruned_and_modes_ranked() {
+        let mut log = HabitLog::default();
+        HabitLog::bump(&mut log.sessions, "2026-01-02");
+        HabitLog::bump(&mut log.sessions, "2026-03-10");
+        HabitLog::bump(&mut log.modes, "trio");
+        HabitLog::bump(&mut log.modes, "classic");
+        HabitLog::bump(&mut log.modes, "classic");
+        log.prune_days_before("2026-02-01");
+
+        assert_eq!(log.sessions_on("2026-01-02"), 0);
+        assert_eq!(log.sessions_on("2026-03-10"), 1);
+        assert_eq!(
+            log.top_modes(5),
+            [("classic".to_string(), 2), ("trio".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use super::events::{self, GameEvent};
+use super::paths;
+use super::state::AppState;
+
+const HEATMAP_FILE_NAME: &str = "mistake_heatmap.json";
+pub const HEAT_LEVELS: u32 = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BoardHeat {
+    pub cols: i32,
+    pub rows: i32,
+    pub counts: Vec<u32>,
+}
+
+impl BoardHeat {
+    fn new(cols: i32, rows: i32) -> Self {
+        Self {
+            cols,
+            rows,
+            counts: vec![0; (cols * rows).max(0) as usize],
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    pub fn count_at(&self, index: usize) -> u32 {
+        self.counts.get(index).copied().unwrap_or(0)
+    }
+
+    // Relative to the worst cell, so every board uses the full range.
+    pub fn heat_level(&self, index: usize) -> u32 {
+        let max = self.counts.iter().copied().max().unwrap_or(0);
+        let count = self.count_at(index);
+        if max == 0 || count == 0 {
+            return 0;
+        }
+        (count * HEAT_LEVELS).div_ceil(max)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MistakeHeatmap {
+    #[serde(default)]
+    boards: Vec<BoardHeat>,
+}
+
+impl MistakeHeatmap {
+    pub fn record_mismatch(&mut self, cols: i32, rows: i32, indices: &[usize]) {
+        let pos = match self
+            .boards
+            .iter()
+            .position(|board| board.cols == cols && board.rows == rows)
+        {
+            Some(pos) => pos,
+            None => {
+                self.boards.push(BoardHeat::new(cols, rows));
+                self.boards.len() - 1
+            }
+        };
+        let board = &mut self.boards[pos];
+        for &index in indices {
+            if let Some(count) = board.counts.get_mut(index) {
+                *count = count.saturating_add(1);
+            }
+        }
+    }
+
+    pub fn boards_by_mistakes(&self) -> Vec<&BoardHeat> {
+        let mut boards: Vec<&BoardHeat> =
+            self.boards.iter().filter(|board| board.total() > 0).collect();
+        boards.sort_by_key(|board| std::cmp::Reverse(board.total()));
+        boards
+    }
+}
+
+fn heatmap_path() -> PathBuf {
+    paths::config_dir().join(HEATMAP_FILE_NAME)
+}
+
+pub fn load_heatmap() -> MistakeHeatmap {
+    let Ok(raw) = fs::read_to_string(heatmap_path()) else {
+        return MistakeHeatmap::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        tracing::warn!("failed to parse mistake heatmap: {err}");
+        MistakeHeatmap::default()
+    })
+}
+
+fn save_heatmap(heatmap: &MistakeHeatmap) -> io::Result<()> {
+    paths::write_atomic(&heatmap_path(), &serde_json::to_string(heatmap)?)
+}
+
+// Part of resetting local records, like the symbol stats.
+pub fn clear_heatmap(st: &mut AppState) {
+    st.mistake_heatmap = MistakeHeatmap::default();
+    match fs::remove_file(heatmap_path()) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            tracing::warn!("failed to remove mistake heatmap: {err}");
+        }
+        _ => {}
+    }
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        if let GameEvent::MismatchMade(indices) = event {
+            log_mismatch(&mut state.borrow_mut(), indices);
+        }
+    });
+}
+
+fn log_mismatch(st: &mut AppState, indices: &[usize]) {
+    let (cols, rows) = (st.grid_cols, st.grid_rows);
+    st.mistake_heatmap.record_mismatch(cols, rows, indices);
+}
+
+// Written alongside the symbol stats rather than on every mismatch.
+pub fn save(st: &AppState) {
+    if let Err(err) = save_heatmap(&st.mistake_heatmap) {
+        tracing::warn!("failed to save mistake heatmap: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatches_are_counted_per_board_size() {
+        let mut heatmap = MistakeHeatmap::default();
+        heatmap.record_mismatch(4, 3, &[0, 5]);
+        heatmap.record_mismatch(4, 3, &[0, 11]);
+        heatmap.record_mismatch(6, 5, &[29, 40]);
+
+        let boards = heatmap.boards_by_mistakes();
+        assert_eq!(boards.len(), 2);
+        assert_eq!((boards[0].cols, boards[0].rows), (4, 3));
+        assert_eq!(boards[0].count_at(0), 2);
+        assert_eq!(boards[0].total(), 4);
+        // Indices past the board are ignored.
+        assert_eq!(boards[1].total(), 1);
+    }
+
+    #[test]
+    fn heat_levels_scale_to_the_worst_cell() {
+        let mut heatmap = MistakeHeatmap::default();
+        for _ in 0..4 {
+            heatmap.record_mismatch(2, 2, &[0]);
+        }
+        heatmap.record_mismatch(2, 2, &[1]);
+
+        let board = heatmap.boards_by_mistakes()[0];
+        assert_eq!(board.heat_level(0), HEAT_LEVELS);
+        assert_eq!(board.heat_level(1), 1);
+        assert_eq!(board.heat_level(2), 0);
+    }
+}
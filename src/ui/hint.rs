@@ -0,0 +1,149 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::board_canvas::CardFlag;
+use super::sequence;
+use super::stats_panel;
+use super::state::{AppState, Tile, TileStatus};
+
+pub const HINT_ASSIST_KEY: &str = "hint-assist";
+// Hints a board allows before the player is on their own again.
+pub const HINT_CHARGES: u32 = 3;
+const HINT_GLOW_MS: u64 = 1_200;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    // A full group the player has already turned over.
+    Match(Vec<usize>),
+    // Face-down cards never turned over, all in the least explored row.
+    Explore(Vec<usize>),
+}
+
+impl Hint {
+    fn indices(&self) -> &[usize] {
+        match self {
+            Hint::Match(indices) | Hint::Explore(indices) => indices,
+        }
+    }
+}
+
+// Only cards the player has seen count, so the hint never knows more than they could.
+pub fn suggest(tiles: &[Tile], flipped: &[usize], match_size: usize, cols: usize) -> Option<Hint> {
+    let known = |index: usize, tile: &Tile| {
+        tile.seen && tile.status == TileStatus::Hidden && !flipped.contains(&index)
+    };
+
+    if let Some(value) = flipped.first().and_then(|index| tiles.get(*index)).map(|tile| &tile.value) {
+        let partners: Vec<usize> = tiles
+            .iter()
+            .enumerate()
+            .filter(|(index, tile)| known(*index, tile) && tile.value == *value)
+            .map(|(index, _)| index)
+            .take(match_size.saturating_sub(flipped.len()))
+            .collect();
+        if !partners.is_empty() && partners.len() + flipped.len() >= match_size {
+            return Some(Hint::Match(partners));
+        }
+    } else {
+        let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, tile) in tiles.iter().enumerate() {
+            if known(index, tile) {
+                groups.entry(tile.value.as_str()).or_default().push(index);
+            }
+        }
+        if let Some(group) = groups
+            .into_values()
+            .filter(|group| group.len() >= match_size)
+            .min_by_key(|group| group[0])
+        {
+            return Some(Hint::Match(group.into_iter().take(match_size).collect()));
+        }
+    }
+
+    let cols = cols.max(1);
+    let mut rows: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, tile) in tiles.iter().enumerate() {
+        if !tile.seen && tile.status == TileStatus::Hidden && !flipped.contains(&index) {
+            rows.entry(index / cols).or_default().push(index);
+        }
+    }
+    rows.into_iter()
+        .max_by(|(row_a, a), (row_b, b)| a.len().cmp(&b.len()).then(row_b.cmp(row_a)))
+        .map(|(_, indices)| Hint::Explore(indices))
+}
+
+pub fn clear_glow(st: &AppState) {
+    if let Some(board) = &st.board_canvas {
+        board.remove_flags_everywhere(&[CardFlag::HintMatch, CardFlag::HintExplore]);
+    }
+}
+
+pub fn show(state: &Rc<RefCell<AppState>>) -> bool {
+    let game_id = {
+        let mut st = state.borrow_mut();
+        if !st.hint_assist
+            || !st.run_assisted()
+            || st.preview_active
+            || st.lock_input
+            || st.hint_charges == 0
+        {
+            return false;
+        }
+        let cols = usize::try_from(st.grid_cols).unwrap_or_default();
+        let Some(hint) = suggest(&st.tiles, &st.flipped_indices, st.match_size, cols) else {
+            return false;
+        };
+        let flag = match hint {
+            Hint::Match(_) => CardFlag::HintMatch,
+            Hint::Explore(_) => CardFlag::HintExplore,
+        };
+        clear_glow(&st);
+        if let Some(board) = &st.board_canvas {
+            for index in hint.indices() {
+                board.add_flag(*index, flag);
+            }
+        }
+        st.hint_charges -= 1;
+        stats_panel::refresh_live_stats(&st);
+        st.game_id
+    };
+
+    sequence::after(state, game_id, HINT_GLOW_MS, |state| clear_glow(&state.borrow()));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(value: &str, seen: bool) -> Tile {
+        Tile {
+            value: value.to_string(),
+            status: TileStatus::Hidden,
+            seen,
+            views: u32::from(seen),
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn hints_use_only_cards_already_seen() {
+        let mut tiles = vec![
+            tile("a", true),
+            tile("b", true),
+            tile("a", false),
+            tile("b", false),
+            tile("c", false),
+            tile("c", false),
+        ];
+        // Both "a" cards exist, but only one has been seen: suggest the emptiest row.
+        assert_eq!(suggest(&tiles, &[], 2, 3), Some(Hint::Explore(vec![3, 4, 5])));
+
+        tiles[2].seen = true;
+        assert_eq!(suggest(&tiles, &[], 2, 3), Some(Hint::Match(vec![0, 2])));
+
+        tiles[2].status = TileStatus::Flipped;
+        assert_eq!(suggest(&tiles, &[2], 2, 3), Some(Hint::Match(vec![0])));
+    }
+}
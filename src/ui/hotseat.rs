@@ -0,0 +1,275 @@
+use crate::i18n::tr;
+
+use super::classic::difficulty_from_level;
+use super::custom::PenaltyProfile;
+use super::state::Difficulty;
+use super::tournament::board_score;
+
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = 4;
+// Share of the level's usual preview a player gets, in percent.
+pub const PREVIEW_SHARES: [u16; 5] = [50, 75, 100, 150, 200];
+const MAX_NAME_CHARS: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Handicap {
+    pub preview_pct: u16,
+    pub penalty: PenaltyProfile,
+}
+
+impl Handicap {
+    // No handicap: the level's own preview and penalties.
+    pub fn for_level(level: u8) -> Self {
+        Self {
+            preview_pct: 100,
+            penalty: PenaltyProfile::for_level(level),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TurnResult {
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub score: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HotseatPlayer {
+    pub name: String,
+    pub handicap: Handicap,
+    pub result: Option<TurnResult>,
+}
+
+// Every player clears the same deal in turn, each with their own handicap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HotseatRun {
+    pub seed: u64,
+    pub level: u8,
+    pub players: Vec<HotseatPlayer>,
+}
+
+pub fn clean_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| !matches!(ch, ';' | ',') && !ch.is_control())
+        .take(MAX_NAME_CHARS)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+pub fn default_name(index: usize) -> String {
+    format!("{} {}", tr("Player"), index + 1)
+}
+
+impl HotseatRun {
+    pub fn new(level: u8, players: Vec<(String, Handicap)>) -> Self {
+        Self {
+            seed: rand::random(),
+            level: level.clamp(1, 4),
+            players: players
+                .into_iter()
+                .enumerate()
+                .map(|(index, (name, handicap))| {
+                    let name = clean_name(&name);
+                    HotseatPlayer {
+                        name: if name.is_empty() { default_name(index) } else { name },
+                        handicap,
+                        result: None,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        difficulty_from_level(self.level)
+    }
+
+    pub fn current_index(&self) -> Option<usize> {
+        self.players.iter().position(|player| player.result.is_none())
+    }
+
+    pub fn current_player(&self) -> Option<&HotseatPlayer> {
+        self.current_index().and_then(|index| self.players.get(index))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current_index().is_none()
+    }
+
+    pub fn preview_seconds(&self, base_secs: f64) -> f64 {
+        let pct = self
+            .current_player()
+            .map_or(100, |player| player.handicap.preview_pct);
+        base_secs * f64::from(pct) / 100.0
+    }
+
+    // Penalties follow the player's handicap, not the board's level.
+    pub fn penalty_difficulty(&self) -> Difficulty {
+        self.current_player()
+            .map_or(self.difficulty(), |player| player.handicap.penalty.difficulty())
+    }
+
+    pub fn record_turn(&mut self, time_secs: u32, precision_pct: u8) -> Option<TurnResult> {
+        let level = self.level;
+        let player = self.players.iter_mut().find(|player| player.result.is_none())?;
+        let result = TurnResult {
+            time_secs,
+            precision_pct,
+            score: board_score(level, time_secs, precision_pct),
+        };
+        player.result = Some(result);
+        Some(result)
+    }
+
+    // Finished players from best to worst score.
+    pub fn standings(&self) -> Vec<(&HotseatPlayer, TurnResult)> {
+        let mut standings: Vec<(&HotseatPlayer, TurnResult)> = self
+            .players
+            .iter()
+            .filter_map(|player| player.result.map(|result| (player, result)))
+            .collect();
+        standings.sort_by(|(_, a), (_, b)| b.score.cmp(&a.score).then(a.time_secs.cmp(&b.time_secs)));
+        standings
+    }
+
+    // Index of the best finished player; ties go to whoever played first.
+    pub fn leader(&self) -> Option<usize> {
+        self.players
+            .iter()
+            .enumerate()
+            .filter_map(|(index, player)| player.result.map(|result| (index, result)))
+            .min_by(|(_, a), (_, b)| b.score.cmp(&a.score).then(a.time_secs.cmp(&b.time_secs)))
+            .map(|(index, _)| index)
+    }
+
+    pub fn mode_label(&self) -> String {
+        match self.current_player() {
+            Some(player) => format!("{} · {}", tr("Pass the Device"), player.name),
+            None => tr("Pass the Device"),
+        }
+    }
+
+    pub fn to_code(&self) -> String {
+        let players = self
+            .players
+            .iter()
+            .map(|player| {
+                let result = player.result.map_or_else(
+                    || "-".to_string(),
+                    |result| format!("{}/{}", result.time_secs, result.precision_pct),
+                );
+                format!(
+                    "{},{},{},{}",
+                    player.handicap.preview_pct,
+                    player.handicap.penalty.code(),
+                    result,
+                    player.name
+                )
+            })
+            .collect::<Vec<String>>();
+        format!("{}:{};{}", self.seed, self.level, players.join(";"))
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let mut parts = code.trim().split(';');
+        let (seed, level) = parts.next()?.split_once(':')?;
+        let mut run = Self {
+            seed: seed.parse().ok()?,
+            level: level.parse().ok()?,
+            players: Vec::new(),
+        };
+        if !(1..=4).contains(&run.level) {
+            return None;
+        }
+        for entry in parts {
+            let mut fields = entry.splitn(4, ',');
+            let preview_pct = fields.next()?.parse().ok()?;
+            let penalty = PenaltyProfile::from_code(fields.next()?)?;
+            let result = match fields.next()? {
+                "-" => None,
+                result => {
+                    let (time_secs, precision_pct) = result.split_once('/')?;
+                    let time_secs = time_secs.parse().ok()?;
+                    let precision_pct: u8 = precision_pct.parse().ok()?;
+                    Some(TurnResult {
+                        time_secs,
+                        precision_pct: precision_pct.min(100),
+                        score: board_score(run.level, time_secs, precision_pct),
+                    })
+                }
+            };
+            run.players.push(HotseatPlayer {
+                name: clean_name(fields.next()?),
+                handicap: Handicap { preview_pct, penalty },
+                result,
+            });
+        }
+        ((MIN_PLAYERS..=MAX_PLAYERS).contains(&run.players.len()) && !run.is_complete()).then_some(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_players() -> HotseatRun {
+        HotseatRun::new(
+            2,
+            vec![
+                ("Ana".to_string(), Handicap::for_level(2)),
+                (
+                    "Leo".to_string(),
+                    Handicap {
+                        preview_pct: 200,
+                        penalty: PenaltyProfile::Off,
+                    },
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn turns_follow_each_players_handicap() {
+        let mut run = two_players();
+        assert_eq!(run.preview_seconds(7.0), 7.0);
+        assert_eq!(run.penalty_difficulty(), Difficulty::Medium);
+
+        run.record_turn(60, 90);
+        assert_eq!(run.current_player().map(|player| player.name.as_str()), Some("Leo"));
+        assert_eq!(run.preview_seconds(7.0), 14.0);
+        assert_eq!(run.penalty_difficulty(), Difficulty::Easy);
+
+        run.record_turn(40, 100);
+        assert!(run.is_complete());
+        let standings = run.standings();
+        assert_eq!(standings[0].0.name, "Leo");
+        assert_eq!(run.leader(), Some(1));
+    }
+
+    #[test]
+    fn no_handicap_plays_the_levels_own_penalties() {
+        for level in 1..=4 {
+            let player = ("Ana".to_string(), Handicap::for_level(level));
+            let run = HotseatRun::new(level, vec![player; 2]);
+            assert_eq!(run.penalty_difficulty(), run.difficulty());
+        }
+    }
+
+    #[test]
+    fn progress_code_roundtrip() {
+        let mut run = two_players();
+        assert_eq!(HotseatRun::from_code(&run.to_code()), Some(run.clone()));
+        run.record_turn(75, 88);
+        assert_eq!(HotseatRun::from_code(&run.to_code()), Some(run.clone()));
+        run.record_turn(75, 88);
+        assert!(HotseatRun::from_code(&run.to_code()).is_none());
+    }
+
+    #[test]
+    fn blank_names_fall_back_to_player_numbers() {
+        let run = HotseatRun::new(1, vec![(" ; ".to_string(), Handicap::for_level(1)); 2]);
+        assert_eq!(run.players[1].name, "Player 2");
+    }
+}
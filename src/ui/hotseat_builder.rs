@@ -0,0 +1,184 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk4 as gtk;
+use libadwaita as adw;
+
+use crate::i18n::{format_percent, tr};
+
+use super::app::apply_hotseat_start;
+use super::classic::CLASSIC_LEVEL_OPTIONS;
+use super::custom::PenaltyProfile;
+use super::hotseat::{self, Handicap, HotseatRun, MAX_PLAYERS, MIN_PLAYERS, PREVIEW_SHARES};
+use super::mode_dialogs::{self, ModeChoice};
+use super::records::classic_level_name;
+use super::state::AppState;
+
+struct PlayerRows {
+    group: adw::PreferencesGroup,
+    name: adw::EntryRow,
+    preview: adw::ComboRow,
+    penalty: adw::ComboRow,
+}
+
+impl PlayerRows {
+    fn handicap(&self, level: u8) -> (String, Handicap) {
+        let defaults = Handicap::for_level(level);
+        let handicap = Handicap {
+            preview_pct: PREVIEW_SHARES
+                .get(self.preview.selected() as usize)
+                .copied()
+                .unwrap_or(defaults.preview_pct),
+            penalty: PenaltyProfile::ALL
+                .get(self.penalty.selected() as usize)
+                .copied()
+                .unwrap_or(defaults.penalty),
+        };
+        (self.name.text().to_string(), handicap)
+    }
+}
+
+pub(super) fn combo_row(title: &str, labels: &[String], selected: usize) -> adw::ComboRow {
+    let refs: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+    let row = adw::ComboRow::builder().title(title).build();
+    row.set_model(Some(&gtk::StringList::new(&refs)));
+    row.set_selected(selected as u32);
+    row
+}
+
+fn select_penalty(row: &adw::ComboRow, level: u8) {
+    let penalty = Handicap::for_level(level).penalty;
+    let position = PenaltyProfile::ALL
+        .iter()
+        .position(|profile| *profile == penalty)
+        .unwrap_or_default();
+    row.set_selected(position as u32);
+}
+
+fn build_player_rows(index: usize) -> PlayerRows {
+    let defaults = Handicap::for_level(CLASSIC_LEVEL_OPTIONS[0]);
+    let name = adw::EntryRow::builder().title(tr("Name")).build();
+    name.set_text(&hotseat::default_name(index));
+    let preview_labels: Vec<String> = PREVIEW_SHARES.iter().map(format_percent).collect();
+    let preview = combo_row(
+        &tr("Preview time"),
+        &preview_labels,
+        PREVIEW_SHARES
+            .iter()
+            .position(|pct| *pct == defaults.preview_pct)
+            .unwrap_or_default(),
+    );
+    let penalty_labels: Vec<String> = PenaltyProfile::ALL
+        .iter()
+        .map(|profile| tr(profile.label()))
+        .collect();
+    let penalty = combo_row(&tr("Penalties"), &penalty_labels, 0);
+    select_penalty(&penalty, CLASSIC_LEVEL_OPTIONS[0]);
+
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&hotseat::default_name(index));
+    group.add(&name);
+    group.add(&preview);
+    group.add(&penalty);
+    group.set_visible(index < MIN_PLAYERS);
+    PlayerRows {
+        group,
+        name,
+        preview,
+        penalty,
+    }
+}
+
+pub fn build_hotseat_page(
+    state: &Rc<RefCell<AppState>>,
+    mode_dialog: &adw::Dialog,
+) -> adw::NavigationPage {
+    let level_labels: Vec<String> = CLASSIC_LEVEL_OPTIONS
+        .iter()
+        .map(|level| tr(classic_level_name(*level)))
+        .collect();
+    let level_row = combo_row(&tr("Difficulty"), &level_labels, 0);
+    let count_row = adw::SpinRow::with_range(MIN_PLAYERS as f64, MAX_PLAYERS as f64, 1.0);
+    count_row.set_title(&tr("Players"));
+    count_row.set_value(MIN_PLAYERS as f64);
+
+    let board_group = adw::PreferencesGroup::new();
+    board_group.set_description(Some(&tr(
+        "Everyone plays the same deal in turn. Adjust preview time and penalties so players of any age can compete",
+    )));
+    board_group.add(&level_row);
+    board_group.add(&count_row);
+
+    let page = adw::PreferencesPage::new();
+    page.add(&board_group);
+    let players: Rc<Vec<PlayerRows>> = Rc::new((0..MAX_PLAYERS).map(build_player_rows).collect());
+    for player in players.iter() {
+        page.add(&player.group);
+    }
+    // Each player starts from the level's own penalties; a handicap moves away from them.
+    level_row.connect_selected_notify({
+        let players = players.clone();
+        move |row| {
+            let level = CLASSIC_LEVEL_OPTIONS
+                .get(row.selected() as usize)
+                .copied()
+                .unwrap_or(1);
+            for player in players.iter() {
+                select_penalty(&player.penalty, level);
+            }
+        }
+    });
+    count_row.connect_value_notify({
+        let players = players.clone();
+        move |row| {
+            let count = row.value() as usize;
+            for (index, player) in players.iter().enumerate() {
+                player.group.set_visible(index < count);
+            }
+        }
+    });
+
+    let start = gtk::Button::with_label(&tr("Start"));
+    start.add_css_class("suggested-action");
+    start.add_css_class("pill");
+    start.set_halign(gtk::Align::Center);
+    start.connect_clicked({
+        let state = state.clone();
+        let mode_dialog = mode_dialog.clone();
+        move |_| {
+            let level = CLASSIC_LEVEL_OPTIONS
+                .get(level_row.selected() as usize)
+                .copied()
+                .unwrap_or(1);
+            let count = count_row.value() as usize;
+            let run = HotseatRun::new(
+                level,
+                players
+                    .iter()
+                    .take(count)
+                    .map(|player| player.handicap(level))
+                    .collect(),
+            );
+            mode_dialogs::remember_choice(ModeChoice::Hotseat, level);
+            apply_hotseat_start(&state, run);
+            mode_dialog.close();
+        }
+    });
+    let start_group = adw::PreferencesGroup::new();
+    start_group.add(&start);
+    page.add(&start_group);
+
+    let header = adw::HeaderBar::new();
+    header.set_show_end_title_buttons(true);
+    header.set_show_back_button(true);
+    header.add_css_class("flat");
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&page));
+
+    adw::NavigationPage::builder()
+        .title(tr("Pass the Device"))
+        .child(&toolbar)
+        .build()
+}
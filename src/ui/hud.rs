@@ -1,14 +1,40 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::time::Duration;
 
 use gio::Menu;
 use gtk4::glib;
 use gtk4::prelude::*;
 
-use crate::i18n::tr;
+use crate::i18n::{format_duration, tr, tr_format};
 
+use super::audio::{self, Cue};
+use super::classic_penalties::{self, Pressure};
+use super::defeat::{self, DefeatReason};
 use super::infinite;
-use super::state::{AppState, Difficulty};
+use super::overlay;
+use super::par_time;
+use super::play_limit::{self, LimitTick};
+use super::scene::show_defeat;
+use super::state::{AppState, Difficulty, Tile, TileStatus};
+use super::events::{self, GameEvent};
+use super::game_title::StatusSource;
+use super::similarity;
+use super::speedrun;
+use super::stats_panel;
+use super::timers;
+use super::toasts::{self, Notice};
+use super::training;
+use super::trio_penalties;
+use super::warmup;
+
+pub const PREVIEW_TICK_KEY: &str = "preview-tick";
+const COUNTDOWN_TICKS_MS: [u32; 3] = [3000, 2000, 1000];
+const PREVIEW_TICK_MS: u32 = 50;
+pub const MATCH_PROGRESS_KEY: &str = "show-match-progress";
+pub const LEFT_HANDED_KEY: &str = "left-handed";
+pub const PROGRESS_IN_TITLE_KEY: &str = "progress-in-title";
+const APP_TITLE: &str = "Recall";
 
 fn refresh_header_action_button(st: &AppState) {
     let Some(button) = &st.restart_button else {
@@ -41,6 +67,7 @@ fn refresh_header_menu_button(st: &AppState, include_game_action: bool) {
     }
     menu_model.append(Some(&tr("Score")), Some("app.score"));
     menu_model.append(Some(&tr("Preferences")), Some("app.preferences"));
+    menu_model.append(Some(&tr("Spectator Window")), Some("app.spectate"));
     menu_model.append(Some(&tr("Keyboard Shortcuts")), Some("win.show-help-overlay"));
     menu_model.append(Some(&tr("How to Play")), Some("app.instructions"));
     menu_model.append(Some(&tr("About Recall")), Some("app.about"));
@@ -54,6 +81,7 @@ pub(super) fn set_header_menu(state: &Rc<RefCell<AppState>>) {
         empty_title.set_text("");
         header.set_title_widget(Some(&empty_title));
     }
+    refresh_window_title(&st);
     if let Some(back) = &st.back_button {
         back.set_visible(false);
     }
@@ -68,10 +96,11 @@ pub(super) fn set_header_menu(state: &Rc<RefCell<AppState>>) {
 
 pub(super) fn set_header_game(state: &Rc<RefCell<AppState>>) {
     let st = state.borrow();
-    if let (Some(header), Some(title_box)) = (&st.header, &st.title_game) {
+    if let (Some(header), Some(title)) = (&st.header, &st.game_title) {
         update_subtitle(&st);
-        header.set_title_widget(Some(title_box));
+        header.set_title_widget(Some(title.widget()));
     }
+    refresh_window_title(&st);
     if let Some(back) = &st.back_button {
         back.set_visible(true);
     }
@@ -84,6 +113,7 @@ pub(super) fn set_header_victory(state: &Rc<RefCell<AppState>>) {
     if let (Some(header), Some(title)) = (&st.header, &st.title_victory) {
         header.set_title_widget(Some(title));
     }
+    refresh_window_title(&st);
     if let Some(back) = &st.back_button {
         back.set_visible(true);
     }
@@ -96,31 +126,125 @@ pub(super) fn set_header_victory(state: &Rc<RefCell<AppState>>) {
     refresh_header_menu_button(&st, false);
 }
 
-pub(super) fn update_subtitle(st: &AppState) {
-    refresh_header_action_button(st);
-    let mode_label = if st.difficulty == Difficulty::Trio {
+// Left-handed puts restart and the menu on the left and the primary result button last.
+pub(super) fn apply_handedness(st: &AppState) {
+    if let (Some(header), Some(back), Some(timer), Some(menu), Some(restart)) = (
+        &st.header,
+        &st.back_button,
+        &st.header_timer_label,
+        &st.menu_button,
+        &st.restart_button,
+    ) {
+        header.remove(back);
+        header.remove(timer);
+        header.remove(menu);
+        header.remove(restart);
+        if st.left_handed {
+            header.pack_start(menu);
+            header.pack_start(restart);
+            header.pack_end(back);
+            header.pack_end(timer);
+        } else {
+            header.pack_start(back);
+            header.pack_start(timer);
+            header.pack_end(menu);
+            header.pack_end(restart);
+        }
+    }
+    for (buttons, primary) in [
+        (&st.victory_buttons, &st.victory_again_button),
+        (&st.defeat_buttons, &st.defeat_retry_button),
+    ] {
+        let (Some(buttons), Some(primary)) = (buttons, primary) else {
+            continue;
+        };
+        let mut child = buttons.first_child();
+        while let Some(other) = child {
+            child = other.next_sibling();
+            if &other == primary.upcast_ref::<gtk4::Widget>() {
+                continue;
+            }
+            if st.left_handed {
+                buttons.reorder_child_after(&other, None::<&gtk4::Widget>);
+            } else {
+                buttons.reorder_child_after(&other, Some(primary));
+            }
+        }
+    }
+}
+
+pub(super) fn mode_label(st: &AppState) -> String {
+    let mode_label = if let Some(challenge) = &st.challenge {
+        challenge.mode_label()
+    } else if let Some(tournament) = st.tournament() {
+        format!("{} · {}", tournament.mode_label(), tr(st.difficulty.name()))
+    } else if let (Some(bracket), Some(run)) = (&st.bracket, &st.hotseat) {
+        match run.current_player() {
+            Some(player) => format!("{} · {}", bracket.mode_label(), player.name),
+            None => bracket.mode_label(),
+        }
+    } else if let Some(run) = &st.hotseat {
+        format!("{} · {}", run.mode_label(), tr(st.difficulty.name()))
+    } else if st.speedrun {
+        format!("{} · {}", tr("Speedrun"), tr(st.difficulty.name()))
+    } else if let Some(pattern) = &st.pattern {
+        pattern.mode_label()
+    } else if let Some(nback) = &st.nback {
+        nback.mode_label()
+    } else if st.sudden_death {
+        format!("{} · {}", tr("Sudden Death"), tr(st.difficulty.name()))
+    } else if let Some(board) = st.kids {
+        board.mode_label()
+    } else if let Some(custom) = &st.custom {
+        custom.mode_label()
+    } else if let Some(target) = st.warmup_target {
+        warmup::mode_label(target)
+    } else if let Some(gauntlet) = st.gauntlet() {
+        format!("{} · {}", gauntlet.mode_label(), tr(st.difficulty.name()))
+    } else if st.difficulty == Difficulty::Trio {
         format!("{} · {}", tr("Trio"), tr(infinite::level_name(st.trio_level)))
     } else if infinite::is_infinite(st.difficulty) {
         infinite::mode_label(st)
     } else {
         format!("{} · {}", tr("Classic"), tr(st.difficulty.name()))
     };
+    match similarity::mode_tag(st) {
+        Some(tag) => format!("{} · {}", mode_label, tr(tag)),
+        None => mode_label,
+    }
+}
+
+pub(super) fn update_subtitle(st: &AppState) {
+    refresh_header_action_button(st);
+    let mode_label = mode_label(st);
+    refresh_preview_bar(st);
+    refresh_match_progress(st);
+    refresh_penalty_pips(st);
+    training::refresh_progress(st);
+    stats_panel::refresh_live_stats(st);
+    let par_secs = par_time::par_secs(st).filter(|_| !st.preview_active);
+    let tension = par_secs.map(|par| par_time::tension(st.seconds_elapsed, par));
     let timer_text = if st.preview_active {
-        let remain = st.preview_remaining_ms as f64 / 1000.0;
-        format!("{:.1}s", remain)
+        tr("Memorize")
     } else {
-        let mins = st.seconds_elapsed / 60;
-        let secs = st.seconds_elapsed % 60;
-        format!("{:02}:{:02}", mins, secs)
+        let shown_secs = defeat::countdown_remaining_secs(st).unwrap_or(st.seconds_elapsed);
+        let clock = format_duration(shown_secs);
+        match par_secs {
+            Some(par) => format!("{} / {} {}", clock, tr("Par"), format_duration(par)),
+            None => clock,
+        }
     };
 
-    if let Some(subtitle) = &st.title_game_subtitle {
-        if st.compact_layout {
-            subtitle.set_text(&mode_label);
-        } else {
-            subtitle.set_text(&format!("{} · {}", mode_label, timer_text));
-        }
+    if let Some(title) = &st.game_title {
+        title.set_mode(&mode_label);
+        title.set_timer((!st.compact_layout).then_some(timer_text.as_str()), tension);
+        let best = st
+            .infinite_best_round
+            .filter(|_| infinite::is_infinite(st.difficulty))
+            .map(|round| format!("{} {}", tr("Best:"), round));
+        title.set_note(best.as_deref());
     }
+    refresh_window_title(st);
     if let Some(timer_label) = &st.header_timer_label {
         let show_mobile_timer = st.compact_layout
             && (st.preview_active
@@ -128,13 +252,237 @@ pub(super) fn update_subtitle(st: &AppState) {
                     && (st.timer_handle.is_some() || st.seconds_elapsed > 0)));
         timer_label.set_visible(show_mobile_timer);
         timer_label.set_text(&timer_text);
+        par_time::apply_tension(timer_label, tension);
+    }
+    overlay::refresh(st);
+}
+
+pub(super) fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        let st = state.borrow();
+        match event {
+            GameEvent::MatchFound(_) | GameEvent::RoundFinished { .. } => {
+                if let GameEvent::RoundFinished {
+                    level_up: Some(level),
+                    ..
+                } = event
+                    && let Some(title) = &st.game_title
+                {
+                    title.show_status(
+                        StatusSource::LevelUp,
+                        &tr_format(
+                            "Level up: {level}",
+                            &[("level", &tr(infinite::level_name(*level)))],
+                        ),
+                    );
+                }
+                refresh_match_progress(&st);
+                refresh_penalty_pips(&st);
+                refresh_window_title(&st);
+                speedrun::refresh_splits_panel(&st);
+                if matches!(event, GameEvent::RoundFinished { .. }) {
+                    stats_panel::refresh_stats_panel(&st);
+                } else {
+                    stats_panel::refresh_live_stats(&st);
+                }
+                overlay::refresh(&st);
+            }
+            GameEvent::MismatchMade(_) => {
+                refresh_penalty_pips(&st);
+                stats_panel::refresh_live_stats(&st);
+                overlay::refresh(&st);
+            }
+            GameEvent::PenaltyApplied(_) => refresh_penalty_pips(&st),
+            GameEvent::TileFlipped(_) | GameEvent::BoardChanged => {}
+        }
+    });
+}
+
+fn refresh_preview_bar(st: &AppState) {
+    let Some(bar) = &st.preview_bar else {
+        return;
+    };
+    bar.set_visible(st.preview_active);
+    if st.preview_active && st.preview_total_ms > 0 {
+        bar.set_fraction(f64::from(st.preview_remaining_ms) / f64::from(st.preview_total_ms));
+    }
+}
+
+pub fn match_progress(tiles: &[Tile], match_size: usize) -> (usize, usize) {
+    let match_size = match_size.max(1);
+    let matched = tiles
+        .iter()
+        .filter(|tile| tile.status == TileStatus::Matched)
+        .count();
+    (matched / match_size, tiles.len() / match_size)
+}
+
+pub(super) fn build_match_progress() -> (gtk4::Box, gtk4::Label, gtk4::LevelBar) {
+    let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+    row.add_css_class("match-progress");
+    row.set_halign(gtk4::Align::Center);
+    row.set_visible(false);
+
+    let bar = gtk4::LevelBar::new();
+    bar.set_size_request(120, -1);
+    bar.set_valign(gtk4::Align::Center);
+    let label = gtk4::Label::new(None);
+    label.add_css_class("numeric");
+    label.add_css_class("caption");
+
+    row.append(&bar);
+    row.append(&label);
+    (row, label, bar)
+}
+
+pub(super) fn refresh_match_progress(st: &AppState) {
+    let Some((row, label, bar)) = &st.match_progress else {
+        return;
+    };
+    let (found, total) = match_progress(&st.tiles, st.match_size);
+    let visible = st.show_match_progress && st.nback.is_none() && total > 0;
+    row.set_visible(visible);
+    if !visible {
+        return;
     }
+    bar.set_max_value(total as f64);
+    bar.set_value(found as f64);
+    label.set_text(&format!("{found}/{total}"));
+    row.set_tooltip_text(Some(&format!("{} {}/{}", tr("Matches found"), found, total)));
+}
+
+// "Recall — Classic · Hard 7/21", so progress shows in the taskbar and window switcher.
+fn window_title(mode_label: &str, (found, total): (usize, usize)) -> String {
+    if total == 0 {
+        return format!("{APP_TITLE} — {mode_label}");
+    }
+    format!("{APP_TITLE} — {mode_label} {found}/{total}")
+}
+
+// Only while the header shows the game title, so menus and results read plain "Recall".
+pub(super) fn refresh_window_title(st: &AppState) {
+    let Some(window) = &st.main_window else {
+        return;
+    };
+    let in_game = match (&st.header, &st.game_title) {
+        (Some(header), Some(title)) => {
+            header.title_widget().as_ref() == Some(title.widget().upcast_ref::<gtk4::Widget>())
+        }
+        _ => false,
+    };
+    let title = if st.progress_in_title && in_game {
+        let progress = if st.nback.is_some() {
+            (0, 0)
+        } else {
+            match_progress(&st.tiles, st.match_size)
+        };
+        window_title(&mode_label(st), progress)
+    } else {
+        APP_TITLE.to_string()
+    };
+    if window.title().as_deref() != Some(title.as_str()) {
+        window.set_title(Some(&title));
+    }
+}
+
+pub(super) fn build_penalty_pips() -> gtk4::Box {
+    let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+    row.add_css_class("penalty-pips");
+    row.set_valign(gtk4::Align::Center);
+    row.set_visible(false);
+    let stage = gtk4::Label::new(None);
+    stage.add_css_class("caption");
+    stage.add_css_class("numeric");
+    stage.set_visible(false);
+    row.append(&stage);
+    row
+}
+
+fn pressure(st: &AppState) -> Option<Pressure> {
+    if st.kids.is_some() || st.nback.is_some() || st.pattern.is_some() {
+        return None;
+    }
+    if trio_penalties::applies_to(st) {
+        trio_penalties::pressure(st)
+    } else {
+        classic_penalties::pressure(st)
+    }
+}
+
+// One pip per mismatch the board tolerates, filled as they are used up. Runs on every
+// tick, so the pips are only added or removed when the board's threshold changes.
+pub(super) fn refresh_penalty_pips(st: &AppState) {
+    let Some(row) = &st.penalty_pips else {
+        return;
+    };
+    let Some(pressure) = pressure(st) else {
+        row.set_visible(false);
+        return;
+    };
+    let Some(stage_label) = row.last_child().and_downcast::<gtk4::Label>() else {
+        return;
+    };
+    let mut pips = penalty_pip_dots(row);
+    while pips.len() > usize::from(pressure.threshold) {
+        if let Some(dot) = pips.pop() {
+            row.remove(&dot);
+        }
+    }
+    while pips.len() < usize::from(pressure.threshold) {
+        let dot = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+        dot.add_css_class("penalty-pip");
+        dot.set_valign(gtk4::Align::Center);
+        dot.insert_before(row, Some(&stage_label));
+        pips.push(dot.upcast());
+    }
+    for (pip, dot) in pips.iter().enumerate() {
+        if pip < usize::from(pressure.mismatches) {
+            dot.add_css_class("filled");
+        } else {
+            dot.remove_css_class("filled");
+        }
+    }
+    let mut tooltip = format!(
+        "{} {}/{}",
+        tr("Mismatches before a reshuffle"),
+        pressure.mismatches,
+        pressure.threshold
+    );
+    match pressure.stage.filter(|stage| *stage > 0) {
+        Some(stage) => {
+            let text = format!("×{stage}");
+            if stage_label.text() != text {
+                stage_label.set_text(&text);
+            }
+            stage_label.set_visible(true);
+            tooltip = format!("{} · {} {}", tooltip, tr("Punishments so far"), stage);
+        }
+        None => stage_label.set_visible(false),
+    }
+    if row.tooltip_text().as_deref() != Some(tooltip.as_str()) {
+        row.set_tooltip_text(Some(&tooltip));
+    }
+    row.set_visible(true);
+}
+
+fn penalty_pip_dots(row: &gtk4::Box) -> Vec<gtk4::Widget> {
+    let mut dots = Vec::new();
+    let mut child = row.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if widget.has_css_class("penalty-pip") {
+            dots.push(widget);
+        }
+    }
+    dots
 }
 
 pub(super) fn stop_timer(st: &mut AppState) {
     if let Some(handle) = st.timer_handle.take() {
         handle.remove();
     }
+    st.run_clock.pause(glib::monotonic_time());
 }
 
 pub(super) fn stop_preview(st: &mut AppState) {
@@ -143,6 +491,7 @@ pub(super) fn stop_preview(st: &mut AppState) {
     if let Some(handle) = st.preview_handle.take() {
         handle.remove();
     }
+    refresh_preview_bar(st);
 }
 
 pub(super) fn start_timer(state: &Rc<RefCell<AppState>>, reset_elapsed: bool) {
@@ -151,35 +500,147 @@ pub(super) fn start_timer(state: &Rc<RefCell<AppState>>, reset_elapsed: bool) {
     if reset_elapsed {
         st.seconds_elapsed = 0;
     }
+    st.run_clock.resume(glib::monotonic_time());
 
-    let state_clone = state.clone();
-    let handle = glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+    let state_weak = Rc::downgrade(state);
+    let handle = timers::every(std::time::Duration::from_secs(1), move || {
+        let Some(state_clone) = state_weak.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
         let mut st = state_clone.borrow_mut();
         st.seconds_elapsed += 1;
         update_subtitle(&st);
+        match play_limit::note_second(&mut st) {
+            LimitTick::Warn(minutes) => toasts::notify(&st, Notice::PlayTimeLeft(minutes)),
+            LimitTick::Reached => {
+                st.timer_handle = None;
+                drop(st);
+                play_limit::show_lockout(&state_clone);
+                return glib::ControlFlow::Break;
+            }
+            LimitTick::Nothing => {}
+        }
+        if defeat::countdown_remaining_secs(&st) == Some(0) {
+            // This source ends itself, so keep show_defeat from removing it again.
+            st.timer_handle = None;
+            drop(st);
+            show_defeat(&state_clone, DefeatReason::Countdown);
+            return glib::ControlFlow::Break;
+        }
         glib::ControlFlow::Continue
     });
     st.timer_handle = Some(handle);
 }
 
-pub(super) fn start_preview_phase(state: &Rc<RefCell<AppState>>, preview_seconds: f64, game_id: u64) {
+// Counts down on the window's frame clock, so the bar moves every frame and a
+// paused preview simply stops accumulating time. `on_elapsed` runs once it hits zero.
+pub(super) fn start_preview_phase(
+    state: &Rc<RefCell<AppState>>,
+    preview_seconds: f64,
+    game_id: u64,
+    on_elapsed: impl FnOnce(&Rc<RefCell<AppState>>) + 'static,
+) {
     {
         let mut st = state.borrow_mut();
         stop_preview(&mut st);
         st.preview_active = true;
+        st.preview_paused = false;
         st.preview_remaining_ms = (preview_seconds.max(0.1) * 1000.0) as u32;
+        st.preview_total_ms = st.preview_remaining_ms;
         update_subtitle(&st);
     }
 
-    let state_tick = state.clone();
-    let tick = glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-        let mut st = state_tick.borrow_mut();
+    let state_weak = Rc::downgrade(state);
+    let on_elapsed = Cell::new(Some(on_elapsed));
+    let tick = timers::every(Duration::from_millis(PREVIEW_TICK_MS.into()), move || {
+        let Some(state) = state_weak.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
+        let mut st = state.borrow_mut();
         if st.game_id != game_id || !st.preview_active {
             return glib::ControlFlow::Break;
         }
-        st.preview_remaining_ms = st.preview_remaining_ms.saturating_sub(100);
+        if st.preview_paused {
+            return glib::ControlFlow::Continue;
+        }
+
+        let before = st.preview_remaining_ms;
+        st.preview_remaining_ms = st.preview_remaining_ms.saturating_sub(PREVIEW_TICK_MS);
+        if before > 0 && st.preview_remaining_ms == 0 {
+            audio::play(st.audio_cues, Cue::CountdownEnd);
+        } else if COUNTDOWN_TICKS_MS
+            .iter()
+            .any(|mark| before > *mark && st.preview_remaining_ms <= *mark)
+        {
+            audio::play(st.audio_cues, Cue::Tick);
+        }
         update_subtitle(&st);
-        glib::ControlFlow::Continue
+        if st.preview_remaining_ms > 0 {
+            return glib::ControlFlow::Continue;
+        }
+        // This source ends itself, so keep stop_preview from removing it again.
+        st.preview_handle = None;
+        drop(st);
+        if let Some(on_elapsed) = on_elapsed.take() {
+            on_elapsed(&state);
+        }
+        glib::ControlFlow::Break
     });
     state.borrow_mut().preview_handle = Some(tick);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::timers::fake::FakeClock;
+
+    fn tile(status: TileStatus) -> Tile {
+        Tile {
+            value: String::new(),
+            status,
+            seen: false,
+            views: 0,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn match_progress_counts_groups_not_tiles() {
+        let mut tiles = vec![tile(TileStatus::Hidden); 12];
+        for tile in tiles.iter_mut().take(6) {
+            tile.status = TileStatus::Matched;
+        }
+
+        assert_eq!(match_progress(&tiles, 2), (3, 6));
+        assert_eq!(match_progress(&tiles, 3), (2, 4));
+        assert_eq!(match_progress(&[], 2), (0, 0));
+    }
+
+    #[test]
+    fn preview_counts_down_on_the_timer_service_and_freezes_while_paused() {
+        let clock = FakeClock::install();
+        let state = Rc::new(RefCell::new(AppState::default()));
+        let elapsed = Rc::new(Cell::new(false));
+        start_preview_phase(&state, 0.5, 0, {
+            let elapsed = elapsed.clone();
+            move |_| elapsed.set(true)
+        });
+
+        clock.advance(200);
+        assert_eq!(state.borrow().preview_remaining_ms, 300);
+        state.borrow_mut().preview_paused = true;
+        clock.advance(1_000);
+        assert_eq!(state.borrow().preview_remaining_ms, 300);
+        state.borrow_mut().preview_paused = false;
+        clock.advance(300);
+        assert!(elapsed.get());
+        assert!(state.borrow().preview_handle.is_none());
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn window_title_shows_progress_only_with_a_board() {
+        assert_eq!(window_title("Classic · Hard", (7, 21)), "Recall — Classic · Hard 7/21");
+        assert_eq!(window_title("N-Back", (0, 0)), "Recall — N-Back");
+    }
+}
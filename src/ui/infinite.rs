@@ -1,10 +1,52 @@
-use super::state::{AppState, Difficulty};
+use super::state::{AppState, Difficulty, PlayerRecords};
 use crate::i18n::tr;
 
 pub const START_LEVEL: u8 = 1;
-const EASY_END_ROUND: u32 = 3;
-const MEDIUM_END_ROUND: u32 = 6;
-const HARD_END_ROUND: u32 = 10;
+pub const PACING_KEY: &str = "infinite-pacing";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pacing {
+    Casual,
+    #[default]
+    Standard,
+    Rush,
+}
+
+impl Pacing {
+    pub const ALL: [Self; 3] = [Self::Casual, Self::Standard, Self::Rush];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Casual => "casual",
+            Self::Standard => "standard",
+            Self::Rush => "rush",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|pacing| pacing.code() == code)
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Casual => "Casual",
+            Self::Standard => "Standard",
+            Self::Rush => "Rush",
+        }
+    }
+
+    // Last round of the Easy, Medium and Hard tiers.
+    fn tier_ends(self) -> (u32, u32, u32) {
+        match self {
+            Self::Casual => (5, 10, 16),
+            Self::Standard => (3, 6, 10),
+            Self::Rush => (2, 4, 6),
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct LevelUpEvent {
@@ -13,32 +55,57 @@ pub struct LevelUpEvent {
 }
 
 pub fn mode_label(st: &AppState) -> String {
-    if classic_difficulty_for_round(st.infinite_round) == Difficulty::Impossible {
+    let pacing = st.infinite_pacing;
+    let name = tr(st.difficulty.name());
+    if classic_difficulty_for_round(pacing, st.infinite_round) == Difficulty::Impossible {
         format!(
             "{} {} {}",
-            tr("Infinite"),
+            name,
             tr("Expert Survival"),
-            expert_survival_rounds(st.infinite_round)
+            expert_survival_rounds(pacing, st.infinite_round)
         )
     } else if st.infinite_level >= 3 {
         format!(
             "{} {} {}",
-            tr("Infinite"),
+            name,
             tr("Hard Survival"),
-            hard_survival_rounds(st.infinite_round)
+            hard_survival_rounds(pacing, st.infinite_round)
         )
     } else {
-        format!("{} {} {}", tr("Infinite"), tr("Round"), st.infinite_round)
+        format!("{} {} {}", name, tr("Round"), st.infinite_round)
     }
 }
 
 pub fn is_infinite(difficulty: Difficulty) -> bool {
-    difficulty == Difficulty::Infinite
+    matches!(difficulty, Difficulty::Infinite | Difficulty::InfiniteTrio)
 }
 
 pub fn prepare_start(st: &mut AppState) {
+    st.infinite_pacing = st.preferred_pacing;
     st.apply_infinite_level_without_reset(START_LEVEL);
     st.reset_infinite_round();
+    st.break_mark = (st.infinite_round, 0);
+    note_best_round(st);
+}
+
+// Only runs with the same pacing and board shape are comparable.
+pub fn best_round(records: &PlayerRecords, pacing: Pacing, trio: bool) -> Option<u32> {
+    records
+        .infinite
+        .iter()
+        .filter(|record| record.pacing == pacing && record.trio == trio)
+        .map(|record| record.round)
+        .max()
+}
+
+pub fn note_best_round(st: &mut AppState) {
+    st.infinite_best_round = best_round(&st.records, st.infinite_pacing, st.infinite_trio());
+}
+
+// True exactly once per run, on the first round past the previous best.
+pub fn just_passed_best(st: &AppState) -> bool {
+    st.infinite_best_round
+        .is_some_and(|best| st.infinite_round == best.saturating_add(1))
 }
 
 pub fn level_name(level: u8) -> &'static str {
@@ -50,55 +117,60 @@ pub fn level_name(level: u8) -> &'static str {
     }
 }
 
-pub fn level_for_round(round: u32) -> u8 {
-    if round <= EASY_END_ROUND {
-        1
-    } else if round <= MEDIUM_END_ROUND {
-        2
-    } else if round <= HARD_END_ROUND {
-        3
-    } else {
-        4
+pub fn level_for_round(pacing: Pacing, round: u32) -> u8 {
+    match classic_difficulty_for_round(pacing, round) {
+        Difficulty::Easy => 1,
+        Difficulty::Medium => 2,
+        Difficulty::Hard => 3,
+        _ => 4,
     }
 }
 
 pub fn projected_level_for_next_round(st: &AppState) -> u8 {
-    level_for_round(st.infinite_round.saturating_add(1))
+    level_for_round(st.infinite_pacing, st.infinite_round.saturating_add(1))
 }
 
-pub fn hard_survival_rounds(round: u32) -> u32 {
-    round.saturating_sub(MEDIUM_END_ROUND)
+pub fn hard_survival_rounds(pacing: Pacing, round: u32) -> u32 {
+    round.saturating_sub(pacing.tier_ends().1)
 }
 
-pub fn expert_survival_rounds(round: u32) -> u32 {
-    round.saturating_sub(HARD_END_ROUND)
+pub fn expert_survival_rounds(pacing: Pacing, round: u32) -> u32 {
+    round.saturating_sub(pacing.tier_ends().2)
 }
 
-pub fn classic_difficulty_for_round(round: u32) -> Difficulty {
-    if round <= EASY_END_ROUND {
+pub fn classic_difficulty_for_round(pacing: Pacing, round: u32) -> Difficulty {
+    let (easy_end, medium_end, hard_end) = pacing.tier_ends();
+    if round <= easy_end {
         Difficulty::Easy
-    } else if round <= MEDIUM_END_ROUND {
+    } else if round <= medium_end {
         Difficulty::Medium
-    } else if round <= HARD_END_ROUND {
+    } else if round <= hard_end {
         Difficulty::Hard
     } else {
         Difficulty::Impossible
     }
 }
 
+// Lands on `round` as if the earlier ones had been played; used by debug tools.
+pub fn jump_to_round(st: &mut AppState, round: u32) {
+    st.infinite_round = round.max(1);
+    st.reset_impossible_pressure();
+    st.apply_infinite_level_without_reset(level_for_round(st.infinite_pacing, st.infinite_round));
+}
+
 pub fn advance_round(st: &mut AppState) -> Option<LevelUpEvent> {
     if !is_infinite(st.difficulty) {
         return None;
     }
 
-    let previous_classic_difficulty = classic_difficulty_for_round(st.infinite_round);
+    let previous_classic_difficulty = classic_difficulty_for_round(st.infinite_pacing, st.infinite_round);
     let previous_level = st.infinite_level;
     st.advance_infinite_round();
-    let next_classic_difficulty = classic_difficulty_for_round(st.infinite_round);
+    let next_classic_difficulty = classic_difficulty_for_round(st.infinite_pacing, st.infinite_round);
     if next_classic_difficulty != previous_classic_difficulty {
         st.reset_impossible_pressure();
     }
-    let target_level = level_for_round(st.infinite_round);
+    let target_level = level_for_round(st.infinite_pacing, st.infinite_round);
     if target_level != previous_level {
         st.apply_infinite_level_without_reset(target_level);
         return Some(LevelUpEvent {
@@ -109,3 +181,59 @@ pub fn advance_round(st: &mut AppState) -> Option<LevelUpEvent> {
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::InfiniteRecord;
+
+    #[test]
+    fn jumping_to_a_round_applies_its_level() {
+        let mut st = AppState {
+            difficulty: Difficulty::Infinite,
+            ..AppState::default()
+        };
+        prepare_start(&mut st);
+
+        jump_to_round(&mut st, 8);
+        assert_eq!(st.infinite_round, 8);
+        assert_eq!(st.infinite_level, level_for_round(Pacing::Standard, 8));
+
+        jump_to_round(&mut st, 0);
+        assert_eq!(st.infinite_round, 1);
+        assert_eq!(st.infinite_level, 1);
+    }
+
+    #[test]
+    fn pacing_profiles_shift_the_tiers() {
+        assert_eq!(classic_difficulty_for_round(Pacing::Standard, 7), Difficulty::Hard);
+        assert_eq!(classic_difficulty_for_round(Pacing::Rush, 7), Difficulty::Impossible);
+        assert_eq!(classic_difficulty_for_round(Pacing::Casual, 7), Difficulty::Medium);
+        assert_eq!(expert_survival_rounds(Pacing::Rush, 11), 5);
+        assert_eq!(Pacing::from_code("unknown"), Pacing::Standard);
+    }
+
+    #[test]
+    fn best_round_only_compares_like_runs() {
+        let record = |round, pacing, trio| InfiniteRecord {
+            round,
+            pacing,
+            trio,
+            ..InfiniteRecord::default()
+        };
+        let mut st = AppState::default();
+        st.records.infinite = vec![
+            record(16, Pacing::Standard, false),
+            record(30, Pacing::Rush, false),
+            record(22, Pacing::Standard, true),
+            record(9, Pacing::Standard, false),
+        ];
+        st.difficulty = Difficulty::Infinite;
+        prepare_start(&mut st);
+        assert_eq!(st.infinite_best_round, Some(16));
+        assert!(!just_passed_best(&st));
+        st.infinite_round = 17;
+        assert!(just_passed_best(&st));
+        assert_eq!(best_round(&st.records, Pacing::Casual, false), None);
+    }
+}
@@ -1,132 +1,103 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use adw::prelude::*;
 use gtk4 as gtk;
 use gtk4::glib;
-use gtk4::prelude::*;
-use super::state::{AppState, TileStatus, Difficulty};
-use super::app::{clear_flip_classes, play_flip_show, redraw_button_child, show_game_with_reveal_delay};
-use super::infinite;
-use crate::i18n::tr;
+use libadwaita as adw;
+use super::state::{AppState, Difficulty};
+use super::app::{play_flip_hide, show_game_with_reveal_delay};
+use super::breaks;
+use super::game_title::{GameTitle, StatusSource};
+use super::board_canvas::{CardFlag, TURN_DOWN_MS};
+use super::hud::stop_timer;
+use super::scene::show_menu;
+use super::infinite::{self, Pacing};
+use super::events::{self, GameEvent};
+use super::records;
+use super::sequence::{self, Sequence};
+use crate::i18n::{format_duration, format_percent, tr, tr_format};
 
-const FLIP_PHASE_MS: u64 = 260;
 const INFINITE_ROUND_TRANSITION_MS: u64 = 620;
 const INFINITE_LEVEL_SWAP_OUT_MS: u64 = 520;
 const INFINITE_POST_TRANSITION_WAIT_MS: u64 = 0;
-const INFINITE_MILESTONE_HOLD_MS: u64 = 0;
+pub(super) const INFINITE_MILESTONE_HOLD_MS: u64 = 1800;
+const INFINITE_ROUND_SUMMARY_HOLD_MS: u64 = 1100;
+
+// Shown between rounds: the run so far, with a milestone headline every few survived rounds.
+pub struct RoundBanner {
+    root: gtk::Box,
+    title: gtk::Label,
+    hint: gtk::Label,
+    summary: gtk::Label,
+}
+
+impl RoundBanner {
+    pub fn hide(&self) {
+        self.root.set_visible(false);
+    }
+}
 
 pub fn schedule_infinite_round_transition(state: &Rc<RefCell<AppState>>, game_id: u64) {
-    {
+    let level_up_transition = {
         let mut st = state.borrow_mut();
         if st.game_id != game_id {
             return;
         }
         st.lock_input = true;
         st.flipped_indices.clear();
-    }
+        infinite::projected_level_for_next_round(&st) != st.infinite_level
+    };
+    let settle_ms = if level_up_transition {
+        INFINITE_LEVEL_SWAP_OUT_MS
+    } else {
+        INFINITE_ROUND_TRANSITION_MS
+    };
 
-    let state_hide_start = state.clone();
-    glib::timeout_add_local(
-        std::time::Duration::from_millis(0),
-        move || {
-            let st = state_hide_start.borrow();
-            if st.game_id != game_id {
-                return glib::ControlFlow::Break;
+    // Kept as a handle so a click or key press can skip the rest of the transition.
+    let handle = Sequence::new(state, game_id)
+        .at(0, |state| {
+            let mut st = state.borrow_mut();
+            if let Some(board) = &st.board_canvas {
+                board.remove_flags_everywhere(&[CardFlag::Bump, CardFlag::Shake, CardFlag::Dim]);
             }
-            for button in &st.grid_buttons {
-                clear_flip_classes(button);
-                button.remove_css_class("reshuffle-flip");
-                button.remove_css_class("match-bump");
-                button.remove_css_class("mismatch-shake");
-                button.remove_css_class("matched");
-                button.remove_css_class("matched-dim");
-                button.remove_css_class("active");
-                button.add_css_class("flip-hide");
-                redraw_button_child(button);
+            for i in 0..st.tiles.len() {
+                play_flip_hide(&mut st, i);
             }
-            drop(st);
-
-            let state_hide_mid = state_hide_start.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(FLIP_PHASE_MS),
-                move || {
-                    let mut st = state_hide_mid.borrow_mut();
-                    if st.game_id != game_id {
-                        return glib::ControlFlow::Break;
-                    }
-                    for i in 0..st.grid_buttons.len() {
-                        if let Some(tile) = st.tiles.get_mut(i) {
-                            tile.status = TileStatus::Hidden;
-                        }
-                        st.grid_buttons[i].remove_css_class("matched");
-                        st.grid_buttons[i].remove_css_class("matched-dim");
-                        st.grid_buttons[i].remove_css_class("active");
-                        play_flip_show(&mut st, i);
-                    }
-                    glib::ControlFlow::Break
-                },
-            );
-
-            let state_hide_finish = state_hide_start.clone();
-            glib::timeout_add_local(
-                std::time::Duration::from_millis(FLIP_PHASE_MS * 2),
-                move || {
-                    let st = state_hide_finish.borrow();
-                    if st.game_id != game_id {
-                        return glib::ControlFlow::Break;
-                    }
-                    for button in &st.grid_buttons {
-                        clear_flip_classes(button);
-                        button.remove_css_class("infinite-round-flip");
-                        redraw_button_child(button);
-                    }
-                    let next_level = infinite::projected_level_for_next_round(&st);
-                    let level_up_transition = next_level != st.infinite_level;
-                    if level_up_transition
-                        && let Some(subtitle) = &st.title_game_subtitle
-                    {
-                        set_level_up_subtitle(subtitle, next_level);
-                    }
-                    if level_up_transition
-                        && let Some(container) = &st.board_container
-                    {
-                        container.remove_css_class("infinite-level-swap-in");
-                        container.remove_css_class("infinite-level-swap-out");
-                        container.add_css_class("infinite-level-swap-out");
-                    }
-                    if !level_up_transition {
-                        for button in &st.grid_buttons {
-                            button.add_css_class("infinite-round-flip");
-                            redraw_button_child(button);
-                        }
-                    }
-                    drop(st);
-
-                    let state_apply = state_hide_finish.clone();
-                    if level_up_transition {
-                        glib::timeout_add_local(
-                            std::time::Duration::from_millis(INFINITE_LEVEL_SWAP_OUT_MS),
-                            move || {
-                                finalize_infinite_transition(&state_apply, game_id, true);
-                                glib::ControlFlow::Break
-                            },
-                        );
-                    } else {
-                        glib::timeout_add_local(
-                            std::time::Duration::from_millis(INFINITE_ROUND_TRANSITION_MS),
-                            move || {
-                                finalize_infinite_transition(&state_apply, game_id, false);
-                                glib::ControlFlow::Break
-                            },
-                        );
-                    }
-
-                    glib::ControlFlow::Break
-                },
-            );
-
+            glib::ControlFlow::Continue
+        })
+        .then_after(TURN_DOWN_MS, move |state| {
+            let st = state.borrow();
+            if let Some(board) = &st.board_canvas {
+                board.clear_flips();
+            }
+            let next_level = infinite::projected_level_for_next_round(&st);
+            events::emit(GameEvent::RoundFinished {
+                round: st.infinite_round,
+                level_up: level_up_transition.then_some(next_level),
+            });
+            if level_up_transition
+                && let Some(container) = &st.board_container
+            {
+                container.remove_css_class("infinite-level-swap-in");
+                container.remove_css_class("infinite-level-swap-out");
+                container.add_css_class("infinite-level-swap-out");
+            }
+            if !level_up_transition
+                && let Some(board) = &st.board_canvas
+            {
+                for i in 0..board.card_count() {
+                    board.add_flag(i, CardFlag::RoundFlip);
+                }
+            }
+            glib::ControlFlow::Continue
+        })
+        .then_after(settle_ms, move |state| {
+            state.borrow_mut().round_transition = None;
+            finalize_infinite_transition(state, game_id, level_up_transition);
             glib::ControlFlow::Break
-        },
-    );
+        })
+        .start();
+    state.borrow_mut().round_transition = Some(handle);
 }
 
 pub fn finalize_infinite_transition(
@@ -138,19 +109,24 @@ pub fn finalize_infinite_transition(
     if st.game_id != game_id {
         return;
     }
-    for button in &st.grid_buttons {
-        button.remove_css_class("reshuffle-flip");
-        clear_flip_classes(button);
-        redraw_button_child(button);
+    if let Some(board) = &st.board_canvas {
+        board.clear_flips();
     }
 
+    let rounds_cleared = st.infinite_round;
     let _ = infinite::advance_round(&mut st);
-    let milestone = infinite_milestone_value(st.infinite_round);
+    let milestone = infinite_milestone_value(st.infinite_pacing, st.infinite_round);
     if let Some((milestone_difficulty, milestone_value)) = milestone
-        && let Some(subtitle) = &st.title_game_subtitle
+        && let Some(title) = &st.game_title
     {
-        set_infinite_milestone_subtitle(subtitle, milestone_difficulty, milestone_value);
+        flash_milestone_status(title, milestone_difficulty, milestone_value);
     }
+    if infinite::just_passed_best(&st)
+        && let Some(title) = &st.game_title
+    {
+        title.show_status(StatusSource::NewBest, &tr("New best round!"));
+    }
+    show_round_banner(&st, rounds_cleared, milestone);
     drop(st);
 
     let launch_next_round = move |state_ref: &Rc<RefCell<AppState>>, with_swap_in: bool| {
@@ -168,8 +144,8 @@ pub fn finalize_infinite_transition(
         }
         show_game_with_reveal_delay(state_ref, Some(INFINITE_POST_TRANSITION_WAIT_MS));
         if with_swap_in {
-            let state_swap = state_ref.clone();
-            glib::timeout_add_local(std::time::Duration::from_millis(0), move || {
+            let game_id = state_ref.borrow().game_id;
+            sequence::after(state_ref, game_id, 0, |state_swap| {
                 let st = state_swap.borrow();
                 let in_game = st
                     .view_stack
@@ -177,36 +153,108 @@ pub fn finalize_infinite_transition(
                     .and_then(|stack| stack.visible_child_name())
                     .as_deref()
                     == Some("game");
-                if st.game_id != game_id || !in_game {
-                    return glib::ControlFlow::Break;
+                if !in_game {
+                    return;
                 }
                 if let Some(container) = &st.board_container {
                     container.remove_css_class("infinite-level-swap-in");
                     container.add_css_class("infinite-level-swap-in");
                 }
-                glib::ControlFlow::Break
             });
         }
     };
 
-    if milestone.is_some() {
-        let state_next = state.clone();
-        glib::timeout_add_local(
-            std::time::Duration::from_millis(INFINITE_MILESTONE_HOLD_MS),
-            move || {
-                launch_next_round(&state_next, apply_level_swap_in);
-                glib::ControlFlow::Break
-            },
-        );
+    let continue_run = move |state_ref: &Rc<RefCell<AppState>>, with_swap_in: bool| {
+        if breaks::break_due(&state_ref.borrow()) {
+            offer_break(state_ref, move |state| launch_next_round(state, with_swap_in));
+        } else {
+            launch_next_round(state_ref, with_swap_in);
+        }
+    };
+
+    let hold_ms = if milestone.is_some() {
+        INFINITE_MILESTONE_HOLD_MS
     } else {
-        launch_next_round(state, apply_level_swap_in);
+        INFINITE_ROUND_SUMMARY_HOLD_MS
+    };
+    let handle = Sequence::new(state, game_id)
+        .at(hold_ms, move |state| {
+            {
+                let mut st = state.borrow_mut();
+                st.round_interstitial = None;
+                if let Some(banner) = &st.round_banner {
+                    banner.hide();
+                }
+            }
+            continue_run(state, apply_level_swap_in);
+            glib::ControlFlow::Break
+        })
+        .start();
+    state.borrow_mut().round_interstitial = Some(handle);
+}
+
+// Taking the break still deals the next board first, so the saved run resumes on it.
+fn offer_break(state: &Rc<RefCell<AppState>>, resume: impl Fn(&Rc<RefCell<AppState>>) + 'static) {
+    let (parent, round, seconds_elapsed) = {
+        let mut st = state.borrow_mut();
+        breaks::mark_break(&mut st);
+        stop_timer(&mut st);
+        (st.view_stack.clone(), st.infinite_round, st.seconds_elapsed)
+    };
+    let Some(parent) = parent else {
+        resume(state);
+        return;
+    };
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(tr("Take a Break?"))
+        .body(format!(
+            "{} {} · {}\n{}",
+            tr("Round"),
+            round,
+            format_duration(seconds_elapsed),
+            tr("Your run is saved, so you can pick it up later from the menu.")
+        ))
+        .build();
+    dialog.add_response("break", &tr("Take a Break"));
+    dialog.add_response("continue", &tr("Keep Playing"));
+    dialog.set_close_response("continue");
+    dialog.set_default_response(Some("continue"));
+    dialog.set_response_appearance("continue", adw::ResponseAppearance::Suggested);
+    dialog.connect_response(None, {
+        let state = state.clone();
+        move |_, response| {
+            resume(&state);
+            if response == "break" {
+                show_menu(&state);
+            }
+        }
+    });
+    dialog.present(Some(&parent));
+}
+
+// "Run ended at round 12, Hard Survival 6, 18:32"
+pub fn quit_summary(pacing: Pacing, round: u32, seconds_elapsed: u32) -> String {
+    let mut parts = vec![tr_format("Run ended at round {round}", &[("round", &round)])];
+    match infinite::classic_difficulty_for_round(pacing, round) {
+        Difficulty::Hard => parts.push(tr_format(
+            "Hard Survival {rounds}",
+            &[("rounds", &infinite::hard_survival_rounds(pacing, round))],
+        )),
+        Difficulty::Impossible => parts.push(tr_format(
+            "Expert Survival {rounds}",
+            &[("rounds", &infinite::expert_survival_rounds(pacing, round))],
+        )),
+        _ => {}
     }
+    parts.push(format_duration(seconds_elapsed));
+    parts.join(", ")
 }
 
-pub fn infinite_milestone_value(round: u32) -> Option<(Difficulty, u32)> {
-    match infinite::classic_difficulty_for_round(round) {
+pub fn infinite_milestone_value(pacing: Pacing, round: u32) -> Option<(Difficulty, u32)> {
+    match infinite::classic_difficulty_for_round(pacing, round) {
         Difficulty::Hard => {
-            let hard_survival = infinite::hard_survival_rounds(round);
+            let hard_survival = infinite::hard_survival_rounds(pacing, round);
             if hard_survival > 0 && hard_survival.is_multiple_of(5) {
                 Some((Difficulty::Hard, hard_survival))
             } else {
@@ -214,7 +262,7 @@ pub fn infinite_milestone_value(round: u32) -> Option<(Difficulty, u32)> {
             }
         }
         Difficulty::Impossible => {
-            let expert_survival = infinite::expert_survival_rounds(round);
+            let expert_survival = infinite::expert_survival_rounds(pacing, round);
             if expert_survival > 0 && expert_survival.is_multiple_of(5) {
                 Some((Difficulty::Impossible, expert_survival))
             } else {
@@ -225,19 +273,116 @@ pub fn infinite_milestone_value(round: u32) -> Option<(Difficulty, u32)> {
     }
 }
 
-pub fn set_infinite_milestone_subtitle(subtitle: &gtk::Label, difficulty: Difficulty, value: u32) {
-    let label = if difficulty == Difficulty::Impossible {
+pub fn build_round_banner(state: &Rc<RefCell<AppState>>) -> gtk::Box {
+    let banner = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    banner.add_css_class("infinite-milestone");
+    banner.set_halign(gtk::Align::Fill);
+    banner.set_valign(gtk::Align::Fill);
+    banner.set_visible(false);
+
+    let title = gtk::Label::new(None);
+    title.add_css_class("infinite-milestone-title");
+    title.set_vexpand(true);
+    title.set_valign(gtk::Align::End);
+    let hint = gtk::Label::new(None);
+    hint.add_css_class("infinite-milestone-hint");
+    let summary = gtk::Label::new(None);
+    summary.add_css_class("infinite-round-summary");
+    summary.set_vexpand(true);
+    summary.set_valign(gtk::Align::Start);
+    banner.append(&title);
+    banner.append(&hint);
+    banner.append(&summary);
+
+    let click = gtk::GestureClick::new();
+    click.connect_released({
+        let state = state.clone();
+        move |_, _, _, _| {
+            let interstitial = state.borrow_mut().round_interstitial.take();
+            if let Some(interstitial) = interstitial {
+                interstitial.skip();
+            }
+        }
+    });
+    banner.add_controller(click);
+
+    state.borrow_mut().round_banner = Some(RoundBanner {
+        root: banner.clone(),
+        title,
+        hint,
+        summary,
+    });
+    banner
+}
+
+fn round_summary(seconds_elapsed: u32, matches: u32, mismatches: u32) -> String {
+    let precision = format_percent(records::precision_for(matches, mismatches));
+    format!(
+        "{} · {}",
+        format_duration(seconds_elapsed),
+        tr_format("Precision {precision}", &[("precision", &precision)])
+    )
+}
+
+fn show_round_banner(st: &AppState, rounds_cleared: u32, milestone: Option<(Difficulty, u32)>) {
+    let Some(banner) = &st.round_banner else {
+        return;
+    };
+    let round = format!("{} {}", tr("Round"), rounds_cleared);
+    match milestone {
+        Some((difficulty, value)) => {
+            banner
+                .title
+                .set_text(&format!("{} ×{}", milestone_label(difficulty).to_uppercase(), value));
+            banner.hint.set_text(&format!("{} · {}", tr("Keep going!"), round));
+        }
+        None => {
+            banner.title.set_text(&round);
+            banner.hint.set_text(&tr("Cleared!"));
+        }
+    }
+    banner
+        .summary
+        .set_text(&round_summary(st.seconds_elapsed, st.run_matches, st.run_mismatches));
+    banner.root.set_visible(true);
+}
+
+fn milestone_label(difficulty: Difficulty) -> String {
+    if difficulty == Difficulty::Impossible {
         tr("Expert")
     } else {
         tr("Hard")
-    };
-    let escaped_label = glib::markup_escape_text(&label);
-    subtitle.set_markup(&format!("<b>{} x{}!</b>", escaped_label, value));
+    }
+}
+
+pub fn flash_milestone_status(title: &GameTitle, difficulty: Difficulty, value: u32) {
+    title.show_status(
+        StatusSource::Milestone,
+        &format!("{} ×{}!", milestone_label(difficulty), value),
+    );
 }
 
-pub fn set_level_up_subtitle(subtitle: &gtk::Label, level: u8) {
-    let level_name = tr(infinite::level_name(level));
-    let escaped_heading = glib::markup_escape_text(&tr("Level up"));
-    let escaped_level_name = glib::markup_escape_text(&level_name);
-    subtitle.set_markup(&format!("<b>{}: {}!</b>", escaped_heading, escaped_level_name));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_summary_shows_time_and_precision() {
+        assert_eq!(round_summary(95, 8, 2), "01:35 · Precision 80%");
+    }
+
+    #[test]
+    fn quit_summary_names_the_survival_segment() {
+        let pacing = Pacing::default();
+        assert_eq!(quit_summary(pacing, 1, 95), "Run ended at round 1, 01:35");
+
+        let hard_round = (1..200)
+            .find(|round| infinite::classic_difficulty_for_round(pacing, *round) == Difficulty::Hard)
+            .expect("Infinite reaches Hard");
+        let survival = infinite::hard_survival_rounds(pacing, hard_round);
+        assert_eq!(
+            quit_summary(pacing, hard_round, 1112),
+            format!("Run ended at round {hard_round}, Hard Survival {survival}, 18:32")
+        );
+    }
 }
@@ -0,0 +1,74 @@
+use crate::i18n::tr;
+
+pub const KIDS_PREVIEW_SECONDS: f64 = 12.0;
+pub const KIDS_SPARK_DURATION_MS: u32 = 6000;
+
+// Friendly, easy to tell apart at a glance.
+pub const KIDS_SYMBOLS: [&str; 12] = [
+    "🐶", "🐱", "🐸", "🐵", "🦄", "🐝", "🦋", "🐢", "🍓", "🌈", "🎈", "⭐",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KidsBoard {
+    Small,
+    Large,
+}
+
+impl KidsBoard {
+    pub const ALL: [KidsBoard; 2] = [KidsBoard::Small, KidsBoard::Large];
+
+    pub fn grid(self) -> (i32, i32) {
+        match self {
+            KidsBoard::Small => (2, 3),
+            KidsBoard::Large => (3, 4),
+        }
+    }
+
+    pub fn grid_label(self) -> String {
+        let (cols, rows) = self.grid();
+        format!("{cols}x{rows}")
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KidsBoard::Small => "Little Board",
+            KidsBoard::Large => "Big Board",
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            KidsBoard::Small => "small",
+            KidsBoard::Large => "large",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|board| board.code() == code.trim())
+    }
+
+    pub fn mode_label(self) -> String {
+        format!("{} · {}", tr("Kids"), tr(self.label()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kids_boards_fit_the_cheerful_symbol_set() {
+        for board in KidsBoard::ALL {
+            let (cols, rows) = board.grid();
+            let pairs = (cols * rows) as usize / 2;
+            assert_eq!((cols * rows) % 2, 0);
+            assert!(pairs <= KIDS_SYMBOLS.len());
+            assert_eq!(KidsBoard::from_code(board.code()), Some(board));
+        }
+        for (idx, symbol) in KIDS_SYMBOLS.iter().enumerate() {
+            assert!(!KIDS_SYMBOLS[idx + 1..].contains(symbol));
+        }
+    }
+}
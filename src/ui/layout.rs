@@ -0,0 +1,277 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use super::infinite;
+use super::state::{AppState, Difficulty};
+
+const REPAIR_ATTEMPTS: usize = 400;
+pub const RESHUFFLE_STRATEGY_KEY: &str = "reshuffle-strategy";
+
+// How penalty reshuffles rearrange the face-down cards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReshuffleStrategy {
+    #[default]
+    Random,
+    // Keeps the board's adjacency allowance, so Hard and Expert never hand out a free pair.
+    Spread,
+    // No card that was already turned over lands back where it was seen.
+    MoveSeen,
+}
+
+impl ReshuffleStrategy {
+    pub const ALL: [ReshuffleStrategy; 3] = [
+        ReshuffleStrategy::Random,
+        ReshuffleStrategy::Spread,
+        ReshuffleStrategy::MoveSeen,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ReshuffleStrategy::Random => "random",
+            ReshuffleStrategy::Spread => "spread",
+            ReshuffleStrategy::MoveSeen => "move-seen",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReshuffleStrategy::Random => "Random",
+            ReshuffleStrategy::Spread => "Keep pairs apart",
+            ReshuffleStrategy::MoveSeen => "Move seen cards",
+        }
+    }
+}
+
+// A board cell during a reshuffle; fixed cells (`key: None`) never match anything.
+#[derive(Clone, Copy, Debug)]
+struct Cell<'a> {
+    key: Option<&'a str>,
+    origin: usize,
+}
+
+impl PartialEq for Cell<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.is_some() && self.key == other.key
+    }
+}
+
+// For each board position, the index of the card that moves there. Only cells with a
+// key take part; `seen` marks cards the player has already turned over.
+pub fn plan_reshuffle(
+    keys: &[Option<&str>],
+    seen: &[bool],
+    cols: usize,
+    strategy: ReshuffleStrategy,
+    allowed_adjacent: usize,
+    rng: &mut impl Rng,
+) -> Vec<usize> {
+    let movable = (0..keys.len())
+        .filter(|idx| keys[*idx].is_some())
+        .collect::<Vec<usize>>();
+    let mut order = (0..keys.len()).collect::<Vec<usize>>();
+    let mut shuffled = movable.clone();
+    shuffled.shuffle(rng);
+    for (slot, origin) in movable.iter().zip(shuffled) {
+        order[*slot] = origin;
+    }
+    match strategy {
+        ReshuffleStrategy::Random => {}
+        ReshuffleStrategy::Spread => {
+            let mut cells = order
+                .iter()
+                .map(|origin| Cell {
+                    key: keys[*origin],
+                    origin: *origin,
+                })
+                .collect::<Vec<Cell>>();
+            repair_adjacent_within(&mut cells, cols, allowed_adjacent, &movable, rng);
+            order = cells.into_iter().map(|cell| cell.origin).collect();
+        }
+        ReshuffleStrategy::MoveSeen => displace_seen(&mut order, &movable, seen, rng),
+    }
+    order
+}
+
+fn displace_seen(order: &mut [usize], movable: &[usize], seen: &[bool], rng: &mut impl Rng) {
+    for &slot in movable {
+        if order[slot] != slot || !seen[slot] {
+            continue;
+        }
+        let start = rng.random_range(0..movable.len());
+        // Swap with a cell whose card would not end up back at its own origin either.
+        let partner = movable
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(movable.len())
+            .copied()
+            .find(|&other| other != slot && order[other] != slot && order[slot] != other);
+        if let Some(other) = partner {
+            order.swap(slot, other);
+        }
+    }
+}
+
+// How many orthogonally adjacent matching tiles a fresh board may keep.
+pub fn allowed_adjacent_pairs(st: &AppState) -> usize {
+    if st.kids.is_some() {
+        return usize::MAX;
+    }
+    let difficulty = if infinite::is_infinite(st.difficulty) {
+        infinite::classic_difficulty_for_round(st.infinite_pacing, st.infinite_round)
+    } else {
+        st.difficulty
+    };
+    match difficulty {
+        Difficulty::Easy => 2,
+        Difficulty::Medium => 1,
+        _ => 0,
+    }
+}
+
+pub fn adjacent_pairs<T: PartialEq>(values: &[T], cols: usize) -> usize {
+    if cols == 0 {
+        return 0;
+    }
+    (0..values.len())
+        .map(|idx| {
+            let right = (idx % cols + 1 < cols && values.get(idx + 1) == Some(&values[idx])) as usize;
+            let below = (values.get(idx + cols) == Some(&values[idx])) as usize;
+            right + below
+        })
+        .sum()
+}
+
+fn touches_match<T: PartialEq>(values: &[T], cols: usize, idx: usize) -> bool {
+    let col = idx % cols;
+    let same = |other: Option<usize>| other.is_some_and(|other| values.get(other) == Some(&values[idx]));
+    same((col > 0).then(|| idx - 1))
+        || same((col + 1 < cols).then_some(idx + 1))
+        || same(idx.checked_sub(cols))
+        || same(Some(idx + cols))
+}
+
+// Swaps tiles that sit next to their match until the layout is within the allowance.
+// Gives up quietly on boards too cramped to satisfy it.
+pub fn repair_adjacent<T: PartialEq>(
+    values: &mut [T],
+    cols: usize,
+    allowed: usize,
+    rng: &mut impl Rng,
+) {
+    let all = (0..values.len()).collect::<Vec<usize>>();
+    repair_adjacent_within(values, cols, allowed, &all, rng);
+}
+
+// Same, but only ever moves the cells listed in `movable`.
+fn repair_adjacent_within<T: PartialEq>(
+    values: &mut [T],
+    cols: usize,
+    allowed: usize,
+    movable: &[usize],
+    rng: &mut impl Rng,
+) {
+    if cols == 0 || movable.len() < 2 {
+        return;
+    }
+    let mut current = adjacent_pairs(values, cols);
+    for _ in 0..REPAIR_ATTEMPTS {
+        if current <= allowed {
+            return;
+        }
+        let offenders = movable
+            .iter()
+            .copied()
+            .filter(|&idx| touches_match(values, cols, idx))
+            .collect::<Vec<usize>>();
+        let Some(&from) = offenders.get(rng.random_range(0..offenders.len().max(1))) else {
+            return;
+        };
+        let to = movable[rng.random_range(0..movable.len())];
+        values.swap(from, to);
+        let next = adjacent_pairs(values, cols);
+        if next < current {
+            current = next;
+        } else {
+            values.swap(from, to);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn counts_only_orthogonal_neighbours_within_a_row() {
+        // 3 columns: a a b / c c b
+        let values = ["a", "a", "b", "c", "c", "b"];
+        assert_eq!(adjacent_pairs(&values, 3), 3);
+        // Row wrap-around does not count as adjacency.
+        assert_eq!(adjacent_pairs(&["a", "b", "c", "c", "a", "b"], 3), 0);
+        assert_eq!(adjacent_pairs(&["a", "b", "a", "c", "b", "c"], 3), 1);
+    }
+
+    #[test]
+    fn repair_removes_adjacent_pairs_on_roomy_boards() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut values = (0..24).flat_map(|value| [value, value]).collect::<Vec<u32>>();
+        assert!(adjacent_pairs(&values, 6) > 0);
+
+        repair_adjacent(&mut values, 6, 0, &mut rng);
+
+        assert_eq!(adjacent_pairs(&values, 6), 0);
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..24).flat_map(|value| [value, value]).collect::<Vec<u32>>());
+    }
+
+    fn board() -> (Vec<Option<&'static str>>, Vec<bool>) {
+        // 6 columns; the first row is already matched and must stay put.
+        let symbols = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"];
+        let keys = (0..30)
+            .map(|idx| (idx >= 6).then(|| symbols[(idx - 6) / 2]))
+            .collect::<Vec<Option<&str>>>();
+        let seen = (0..30).map(|idx| idx % 3 == 0).collect();
+        (keys, seen)
+    }
+
+    #[test]
+    fn reshuffles_leave_fixed_cells_alone_and_honour_the_strategy() {
+        let (keys, seen) = board();
+        for strategy in ReshuffleStrategy::ALL {
+            for seed in 0..20 {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let order = plan_reshuffle(&keys, &seen, 6, strategy, 0, &mut rng);
+                assert_eq!(order[..6], [0, 1, 2, 3, 4, 5]);
+                let mut sorted = order.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, (0..30).collect::<Vec<usize>>());
+
+                let placed = order.iter().map(|origin| keys[*origin]).collect::<Vec<_>>();
+                let cells = placed
+                    .iter()
+                    .enumerate()
+                    .map(|(origin, key)| Cell { key: *key, origin })
+                    .collect::<Vec<Cell>>();
+                match strategy {
+                    ReshuffleStrategy::Spread => assert_eq!(adjacent_pairs(&cells, 6), 0),
+                    ReshuffleStrategy::MoveSeen => {
+                        assert!((6..30).all(|idx| !seen[idx] || order[idx] != idx));
+                    }
+                    ReshuffleStrategy::Random => {}
+                }
+            }
+        }
+        assert_eq!(ReshuffleStrategy::from_code("move-seen"), ReshuffleStrategy::MoveSeen);
+    }
+}
@@ -0,0 +1,223 @@
+use adw::prelude::*;
+use gtk4 as gtk;
+use gtk4::glib;
+use libadwaita as adw;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{format_duration, format_percent, ntr_format, tr, tr_format};
+
+use super::net::{self, Endpoint};
+use super::settings;
+
+pub const LEADERBOARD_ENABLED_KEY: &str = "leaderboard-enabled";
+pub const LEADERBOARD_URL_KEY: &str = "leaderboard-url";
+const PLAYER_ID_KEY: &str = "leaderboard-player";
+const STANDINGS_SHOWN: usize = 5;
+
+#[derive(Serialize)]
+struct Submission<'a> {
+    player: &'a str,
+    board: &'a str,
+    time_secs: u32,
+    precision_pct: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct Standing {
+    pub player: String,
+    pub time_secs: u32,
+    pub precision_pct: u8,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Standings {
+    #[serde(default)]
+    pub entries: Vec<Standing>,
+}
+
+impl Standings {
+    // Share of submitted runs that were no faster than this time.
+    pub fn percentile_for(&self, time_secs: u32) -> Option<u8> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let not_faster = self
+            .entries
+            .iter()
+            .filter(|entry| entry.time_secs >= time_secs)
+            .count();
+        Some(((not_faster as f64 / self.entries.len() as f64) * 100.0).round() as u8)
+    }
+
+    fn fastest(&self, limit: usize) -> Vec<Standing> {
+        let mut entries = self.entries.clone();
+        entries.sort_by(|a, b| {
+            a.time_secs
+                .cmp(&b.time_secs)
+                .then_with(|| b.precision_pct.cmp(&a.precision_pct))
+        });
+        entries.truncate(limit);
+        entries
+    }
+}
+
+fn weekly_board(week: &str) -> String {
+    format!("weekly-{week}")
+}
+
+fn endpoint() -> Option<Endpoint> {
+    if !settings::boolean(LEADERBOARD_ENABLED_KEY, false) {
+        return None;
+    }
+    let url = settings::string(LEADERBOARD_URL_KEY, "");
+    if url.trim().is_empty() {
+        return None;
+    }
+    Endpoint::parse(&url)
+        .inspect_err(|err| tracing::warn!("leaderboard disabled: {err}"))
+        .ok()
+}
+
+// An anonymous id, created on first use; nothing else identifies the player.
+fn player_id() -> String {
+    let stored = settings::string(PLAYER_ID_KEY, "");
+    if !stored.is_empty() {
+        return stored;
+    }
+    let id: String = (0..16)
+        .map(|_| format!("{:x}", rand::rng().random_range(0..16u8)))
+        .collect();
+    settings::set_string(PLAYER_ID_KEY, &id);
+    id
+}
+
+pub fn submit_weekly(week: &str, time_secs: u32, precision_pct: u8) {
+    let Some(endpoint) = endpoint() else {
+        return;
+    };
+    let player = player_id();
+    let board = weekly_board(week);
+    let body = match serde_json::to_string(&Submission {
+        player: &player,
+        board: &board,
+        time_secs,
+        precision_pct,
+    }) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::warn!("failed to encode leaderboard result: {err}");
+            return;
+        }
+    };
+    glib::spawn_future_local(async move {
+        if let Err(err) = net::send(&endpoint, "POST", "results", Some(body)).await {
+            tracing::warn!("failed to submit leaderboard result: {err}");
+        }
+    });
+}
+
+async fn fetch_standings(endpoint: &Endpoint, board: &str) -> Result<Standings, String> {
+    let body = net::send(endpoint, "GET", &format!("boards/{board}"), None)
+        .await
+        .map_err(|err| err.to_string())?;
+    serde_json::from_slice(&body).map_err(|err| err.to_string())
+}
+
+fn standing_row(title: &str, subtitle: &str, value: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::builder().title(title).subtitle(subtitle).build();
+    row.set_activatable(false);
+    let label = gtk::Label::new(Some(value));
+    label.add_css_class("score-row-time");
+    label.add_css_class("numeric");
+    row.add_suffix(&label);
+    row
+}
+
+// None when the player has not opted in, so the score dialog stays offline.
+pub fn standings_group(week: &str, best_time: Option<u32>) -> Option<adw::PreferencesGroup> {
+    let endpoint = endpoint()?;
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&tr("Global standings"));
+    group.set_description(Some(&tr("Loading…")));
+
+    let board = weekly_board(week);
+    let own_id = player_id();
+    glib::spawn_future_local({
+        let group = group.clone();
+        async move {
+            let standings = match fetch_standings(&endpoint, &board).await {
+                Ok(standings) => standings,
+                Err(err) => {
+                    tracing::warn!("failed to load leaderboard: {err}");
+                    group.set_description(Some(&tr("Could not reach the leaderboard server")));
+                    return;
+                }
+            };
+            let submitted = u32::try_from(standings.entries.len()).unwrap_or(u32::MAX);
+            group.set_description(Some(&ntr_format(
+                "{count} run submitted",
+                "{count} runs submitted",
+                submitted,
+                &[],
+            )));
+            if let Some(pct) = best_time.and_then(|time| standings.percentile_for(time)) {
+                group.add(&standing_row(
+                    &tr("Your best"),
+                    &tr_format("Percentile: {percent}", &[("percent", &format_percent(pct))]),
+                    &format_duration(best_time.unwrap_or_default()),
+                ));
+            }
+            for (place, entry) in standings.fastest(STANDINGS_SHOWN).iter().enumerate() {
+                let name = if entry.player == own_id {
+                    tr("You")
+                } else {
+                    let short_id = entry.player.chars().take(6).collect::<String>();
+                    tr_format("Player {id}", &[("id", &short_id)])
+                };
+                group.add(&standing_row(
+                    &tr_format("{place}. {name}", &[("place", &(place + 1)), ("name", &name)]),
+                    &tr_format(
+                        "Precision {percent}",
+                        &[("percent", &format_percent(entry.precision_pct))],
+                    ),
+                    &format_duration(entry.time_secs),
+                ));
+            }
+        }
+    });
+    Some(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(time_secs: u32, precision_pct: u8) -> Standing {
+        Standing {
+            player: "abc".to_string(),
+            time_secs,
+            precision_pct,
+        }
+    }
+
+    #[test]
+    fn standings_parse_and_rank_by_time() {
+        let standings: Standings = serde_json::from_str(
+            r#"{"entries":[{"player":"a","time_secs":90,"precision_pct":80},
+                {"player":"b","time_secs":60,"precision_pct":95}]}"#,
+        )
+        .unwrap();
+        assert_eq!(standings.fastest(1)[0].player, "b");
+    }
+
+    #[test]
+    fn percentile_counts_runs_no_faster_than_yours() {
+        let standings = Standings {
+            entries: vec![standing(50, 90), standing(70, 90), standing(90, 90), standing(120, 90)],
+        };
+        assert_eq!(standings.percentile_for(70), Some(75));
+        assert_eq!(standings.percentile_for(30), Some(100));
+        assert_eq!(Standings::default().percentile_for(70), None);
+    }
+}
@@ -1,15 +1,78 @@
 pub mod app;
+mod audio;
 mod board;
+mod board_canvas;
+mod bracket;
+mod bracket_builder;
+mod breaks;
+mod challenge;
 mod classic;
+mod coordinates;
+mod custom;
+mod custom_builder;
 mod debug_tools;
+mod defeat;
 mod dialogs;
+mod events;
+mod faces;
+mod fonts;
+mod friends;
+mod game_title;
+mod gauntlet;
+mod habits;
+mod heatmap;
+mod hint;
+mod hotseat;
+mod hotseat_builder;
 mod hud;
 mod infinite;
 mod infinite_flow;
+mod kids;
+mod layout;
+mod leaderboard;
 mod classic_penalties;
 mod mode_dialogs;
+mod music;
+mod nback;
+mod nback_flow;
+mod net;
+mod next_goal;
+mod overlay;
+mod par_time;
+mod particles;
+mod paths;
+mod pattern;
+mod pattern_flow;
+mod peek;
+mod penalty_sim;
+mod play_limit;
+mod rank_art;
+mod perf_hud;
 mod records;
+mod records_filter;
+mod records_trash;
+mod resources;
 mod scene;
+mod sequence;
+mod session_recap;
 mod session_save;
+mod settings;
+mod shortcuts;
+mod similarity;
+mod spectator;
+mod speech;
+mod speedrun;
 mod state;
+mod stats_panel;
+mod symbol_stats;
+mod tags;
+mod theme;
+mod timers;
+mod toasts;
+mod tournament;
+mod transitions;
+mod training;
 mod trio_penalties;
+mod user_css;
+mod warmup;
+mod weekly_summary;
@@ -5,11 +5,216 @@ use adw::prelude::*;
 use gtk4 as gtk;
 use libadwaita as adw;
 
-use crate::i18n::tr;
-
-use super::app::{apply_difficulty_change, apply_trio_level_change};
+use crate::i18n::{ntr_format, tr, tr_format};
+
+use super::app::{
+    apply_challenge, apply_difficulty_change, apply_gauntlet_start, apply_kids_start, apply_nback_start,
+    apply_pattern_start, apply_speedrun_start, apply_sudden_death_start, apply_tournament_start, apply_training_start, apply_trio_level_change, apply_warmup_start,
+};
+use super::bracket_builder::build_bracket_page;
+use super::challenge::{self, Challenge};
+use super::custom_builder::build_custom_page;
+use super::hotseat_builder::build_hotseat_page;
 use super::classic::{difficulty_from_level, CLASSIC_LEVEL_OPTIONS};
+use super::kids::KidsBoard;
+use super::nback::{self, NBACK_LEVELS};
+use super::settings;
 use super::state::{AppState, Difficulty};
+use super::warmup;
+
+pub const LAST_MODE_KEY: &str = "last-mode";
+pub const LAST_LEVEL_KEY: &str = "last-level";
+pub const TRIO_LEVEL_KEY: &str = "trio-level";
+
+// The rows of the mode dialog, so the last one played can be offered first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeChoice {
+    Classic,
+    Trio,
+    Kids,
+    Infinite,
+    InfiniteTrio,
+    Tournament,
+    Hotseat,
+    Bracket,
+    Gauntlet,
+    Training,
+    Speedrun,
+    SuddenDeath,
+    Pattern,
+    NBack,
+    Custom,
+    Weekly,
+}
+
+impl ModeChoice {
+    pub const ALL: [ModeChoice; 16] = [
+        ModeChoice::Classic,
+        ModeChoice::Trio,
+        ModeChoice::Kids,
+        ModeChoice::Infinite,
+        ModeChoice::InfiniteTrio,
+        ModeChoice::Tournament,
+        ModeChoice::Hotseat,
+        ModeChoice::Bracket,
+        ModeChoice::Gauntlet,
+        ModeChoice::Training,
+        ModeChoice::Speedrun,
+        ModeChoice::SuddenDeath,
+        ModeChoice::Pattern,
+        ModeChoice::NBack,
+        ModeChoice::Custom,
+        ModeChoice::Weekly,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ModeChoice::Classic => "classic",
+            ModeChoice::Trio => "trio",
+            ModeChoice::Kids => "kids",
+            ModeChoice::Infinite => "infinite",
+            ModeChoice::InfiniteTrio => "infinite-trio",
+            ModeChoice::Tournament => "tournament",
+            ModeChoice::Hotseat => "hotseat",
+            ModeChoice::Bracket => "bracket",
+            ModeChoice::Gauntlet => "gauntlet",
+            ModeChoice::Training => "training",
+            ModeChoice::Speedrun => "speedrun",
+            ModeChoice::SuddenDeath => "sudden-death",
+            ModeChoice::Pattern => "pattern",
+            ModeChoice::NBack => "nback",
+            ModeChoice::Custom => "custom",
+            ModeChoice::Weekly => "weekly",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.code() == code)
+    }
+}
+
+pub fn remember_choice(mode: ModeChoice, level: u8) {
+    settings::set_string(LAST_MODE_KEY, mode.code());
+    settings::set_integer(LAST_LEVEL_KEY, i32::from(level));
+}
+
+fn last_choice() -> Option<(ModeChoice, u8)> {
+    let mode = ModeChoice::from_code(&settings::string(LAST_MODE_KEY, ""))?;
+    let level = u8::try_from(settings::integer(LAST_LEVEL_KEY, 0)).unwrap_or_default();
+    Some((mode, level))
+}
+
+// Accepts a mode code with an optional level, e.g. "classic:3" or "pattern".
+pub fn parse_mode_request(request: &str) -> Option<(ModeChoice, Option<u8>)> {
+    let (code, level) = match request.trim().split_once(':') {
+        Some((code, level)) => (code, Some(level.trim().parse().ok()?)),
+        None => (request.trim(), None),
+    };
+    Some((ModeChoice::from_code(&code.to_lowercase())?, level))
+}
+
+// Starts a mode the same way its dialog row does; without a level the last one
+// picked for that mode is used.
+pub fn start_choice(state: &Rc<RefCell<AppState>>, mode: ModeChoice, level: Option<u8>) {
+    let level = level
+        .or_else(|| last_choice().filter(|(last, _)| *last == mode).map(|(_, level)| level))
+        .unwrap_or(if mode == ModeChoice::Kids { 0 } else { 1 });
+    match mode {
+        ModeChoice::Classic => {
+            let difficulty = difficulty_from_level(level);
+            if warmup::offered_before(difficulty) && state.borrow().expert_warmup {
+                apply_warmup_start(state, difficulty);
+            } else {
+                apply_difficulty_change(state, difficulty);
+            }
+        }
+        ModeChoice::Trio => {
+            let is_current_trio = state.borrow().difficulty == Difficulty::Trio;
+            apply_trio_level_change(state, level);
+            if !is_current_trio {
+                apply_difficulty_change(state, Difficulty::Trio);
+            }
+        }
+        ModeChoice::Kids => {
+            let board = KidsBoard::ALL
+                .get(usize::from(level))
+                .copied()
+                .unwrap_or(KidsBoard::Small);
+            apply_kids_start(state, board);
+        }
+        ModeChoice::Infinite => apply_difficulty_change(state, Difficulty::Infinite),
+        ModeChoice::InfiniteTrio => apply_difficulty_change(state, Difficulty::InfiniteTrio),
+        ModeChoice::Tournament => apply_tournament_start(state),
+        ModeChoice::Gauntlet => apply_gauntlet_start(state),
+        ModeChoice::Training => apply_training_start(state, difficulty_from_level(level)),
+        ModeChoice::Speedrun => apply_speedrun_start(state, difficulty_from_level(level)),
+        ModeChoice::SuddenDeath => apply_sudden_death_start(state, difficulty_from_level(level)),
+        ModeChoice::Pattern => apply_pattern_start(state),
+        ModeChoice::NBack => apply_nback_start(state, level),
+        ModeChoice::Custom => {
+            tracing::warn!("custom boards are started from a saved preset");
+            return;
+        }
+        ModeChoice::Hotseat => {
+            tracing::warn!("pass-the-device games need their players set up first");
+            return;
+        }
+        ModeChoice::Bracket => {
+            tracing::warn!("brackets need their players entered first");
+            return;
+        }
+        ModeChoice::Weekly => {
+            let Some(weekly_challenge) = Challenge::current_weekly() else {
+                return;
+            };
+            apply_challenge(state, weekly_challenge);
+        }
+    }
+    remember_choice(mode, level);
+}
+
+fn mark_last_played(row: &adw::ActionRow) {
+    let label = gtk::Label::new(Some(&tr("Last played")));
+    label.add_css_class("dim-label");
+    label.add_css_class("caption");
+    row.add_suffix(&label);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum DifficultyPageKind {
+    Classic,
+    Trio,
+    Speedrun,
+    SuddenDeath,
+    NBack,
+    Training,
+}
+
+impl DifficultyPageKind {
+    fn mode(self) -> ModeChoice {
+        match self {
+            DifficultyPageKind::Classic => ModeChoice::Classic,
+            DifficultyPageKind::Trio => ModeChoice::Trio,
+            DifficultyPageKind::Speedrun => ModeChoice::Speedrun,
+            DifficultyPageKind::SuddenDeath => ModeChoice::SuddenDeath,
+            DifficultyPageKind::NBack => ModeChoice::NBack,
+            DifficultyPageKind::Training => ModeChoice::Training,
+        }
+    }
+}
+
+struct DifficultyPages {
+    classic: adw::NavigationPage,
+    trio: adw::NavigationPage,
+    speedrun: adw::NavigationPage,
+    sudden_death: adw::NavigationPage,
+    nback: adw::NavigationPage,
+    training: adw::NavigationPage,
+    kids: adw::NavigationPage,
+    custom: adw::NavigationPage,
+    hotseat: adw::NavigationPage,
+    bracket: adw::NavigationPage,
+}
 
 fn difficulty_title(level: u8) -> String {
     match level {
@@ -99,12 +304,12 @@ fn build_difficulty_row(
 
 fn build_mode_content(
     navigation_view: &adw::NavigationView,
-    classic_difficulty_page: &adw::NavigationPage,
-    trio_difficulty_page: &adw::NavigationPage,
+    pages: &DifficultyPages,
     state: &Rc<RefCell<AppState>>,
     dialog: &adw::Dialog,
-) -> adw::Clamp {
+) -> (adw::Clamp, Option<adw::ActionRow>) {
     let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    let last = last_choice().map(|(mode, _)| mode);
 
     let classic_row = build_mode_row(
         &tr("Classic"),
@@ -112,7 +317,7 @@ fn build_mode_content(
         true,
         {
             let navigation_view = navigation_view.clone();
-            let target_page = classic_difficulty_page.clone();
+            let target_page = pages.classic.clone();
             move || navigation_view.push(&target_page)
         },
     );
@@ -125,13 +330,26 @@ fn build_mode_content(
         true,
         {
             let navigation_view = navigation_view.clone();
-            let target_page = trio_difficulty_page.clone();
+            let target_page = pages.trio.clone();
             move || navigation_view.push(&target_page)
         },
     );
     let trio_list = build_single_row_list(&trio_row);
     content.append(&trio_list);
 
+    let kids_row = build_mode_row(
+        &tr("Kids"),
+        &tr("Tiny boards, a long look first and no penalties"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.kids.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let kids_list = build_single_row_list(&kids_row);
+    content.append(&kids_list);
+
     let infinite_row = build_mode_row(
         &tr("Infinite"),
         &tr("Classic core rules with endless progression"),
@@ -140,6 +358,7 @@ fn build_mode_content(
             let state = state.clone();
             let dialog = dialog.clone();
             move || {
+                remember_choice(ModeChoice::Infinite, 0);
                 apply_difficulty_change(&state, Difficulty::Infinite);
                 dialog.close();
             }
@@ -148,51 +367,353 @@ fn build_mode_content(
     let infinite_list = build_single_row_list(&infinite_row);
     content.append(&infinite_list);
 
+    let infinite_trio_row = build_mode_row(
+        &tr("Infinite Trio"),
+        &tr("Trio groups with endless progression"),
+        false,
+        {
+            let state = state.clone();
+            let dialog = dialog.clone();
+            move || {
+                remember_choice(ModeChoice::InfiniteTrio, 0);
+                apply_difficulty_change(&state, Difficulty::InfiniteTrio);
+                dialog.close();
+            }
+        },
+    );
+    let infinite_trio_list = build_single_row_list(&infinite_trio_row);
+    content.append(&infinite_trio_list);
+
+    let tournament_row = build_mode_row(
+        &tr("Tournament"),
+        &tr("Five boards of rising difficulty, one composite score"),
+        false,
+        {
+            let state = state.clone();
+            let dialog = dialog.clone();
+            move || {
+                remember_choice(ModeChoice::Tournament, 0);
+                apply_tournament_start(&state);
+                dialog.close();
+            }
+        },
+    );
+    let tournament_list = build_single_row_list(&tournament_row);
+    content.append(&tournament_list);
+
+    let hotseat_row = build_mode_row(
+        &tr("Pass the Device"),
+        &tr("Take turns on the same board, each with their own handicap"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.hotseat.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let hotseat_list = build_single_row_list(&hotseat_row);
+    content.append(&hotseat_list);
+
+    let bracket_row = build_mode_row(
+        &tr("Bracket"),
+        &tr("Knock-out matches on shared boards until one champion is left"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.bracket.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let bracket_list = build_single_row_list(&bracket_row);
+    content.append(&bracket_list);
+
+    let gauntlet_row = build_mode_row(
+        &tr("Gauntlet"),
+        &tr("Easy to Expert back to back on a single clock"),
+        false,
+        {
+            let state = state.clone();
+            let dialog = dialog.clone();
+            move || {
+                remember_choice(ModeChoice::Gauntlet, 0);
+                apply_gauntlet_start(&state);
+                dialog.close();
+            }
+        },
+    );
+    let gauntlet_list = build_single_row_list(&gauntlet_row);
+    content.append(&gauntlet_list);
+
+    let training_row = build_mode_row(
+        &tr("Training Session"),
+        &tr("Three games with five-minute rests and a report at the end"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.training.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let training_list = build_single_row_list(&training_row);
+    content.append(&training_list);
+
+    let speedrun_row = build_mode_row(
+        &tr("Speedrun"),
+        &tr("Race your personal best with live splits"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.speedrun.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let speedrun_list = build_single_row_list(&speedrun_row);
+    content.append(&speedrun_list);
+
+    let sudden_death_row = build_mode_row(
+        &tr("Sudden Death"),
+        &tr("One mismatch ends the run, count the pairs you clear"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.sudden_death.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let sudden_death_list = build_single_row_list(&sudden_death_row);
+    content.append(&sudden_death_list);
+
+    let pattern_row = build_mode_row(
+        &tr("Pattern"),
+        &tr("Watch cells light up, then tap the same ones from memory"),
+        false,
+        {
+            let state = state.clone();
+            let dialog = dialog.clone();
+            move || {
+                remember_choice(ModeChoice::Pattern, 0);
+                apply_pattern_start(&state);
+                dialog.close();
+            }
+        },
+    );
+    let pattern_list = build_single_row_list(&pattern_row);
+    content.append(&pattern_list);
+
+    let nback_row = build_mode_row(
+        &tr("N-back"),
+        &tr("Spot when a position or symbol repeats from N steps back"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.nback.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let nback_list = build_single_row_list(&nback_row);
+    content.append(&nback_list);
+
+    let custom_row = build_mode_row(
+        &tr("Custom"),
+        &tr("Your own board size, preview, penalties and modifiers"),
+        true,
+        {
+            let navigation_view = navigation_view.clone();
+            let target_page = pages.custom.clone();
+            move || navigation_view.push(&target_page)
+        },
+    );
+    let custom_list = build_single_row_list(&custom_row);
+    content.append(&custom_list);
+
+    let mut last_row = [
+        (ModeChoice::Classic, &classic_row),
+        (ModeChoice::Trio, &trio_row),
+        (ModeChoice::Kids, &kids_row),
+        (ModeChoice::Infinite, &infinite_row),
+        (ModeChoice::InfiniteTrio, &infinite_trio_row),
+        (ModeChoice::Tournament, &tournament_row),
+        (ModeChoice::Hotseat, &hotseat_row),
+        (ModeChoice::Bracket, &bracket_row),
+        (ModeChoice::Gauntlet, &gauntlet_row),
+        (ModeChoice::Training, &training_row),
+        (ModeChoice::Speedrun, &speedrun_row),
+        (ModeChoice::SuddenDeath, &sudden_death_row),
+        (ModeChoice::Pattern, &pattern_row),
+        (ModeChoice::NBack, &nback_row),
+        (ModeChoice::Custom, &custom_row),
+    ]
+    .into_iter()
+    .find(|(mode, _)| last == Some(*mode))
+    .map(|(_, row)| row.clone());
+
+    if let Some(weekly_challenge) = Challenge::current_weekly() {
+        let countdown = challenge::seconds_until_next_rotation()
+            .map(challenge::format_rotation_countdown)
+            .unwrap_or_default();
+        let weekly_row = build_mode_row(
+            &tr("Weekly Challenge"),
+            &tr_format(
+                "Same Expert board for everyone this week · New board in {countdown}",
+                &[("countdown", &countdown)],
+            ),
+            false,
+            {
+                let state = state.clone();
+                let dialog = dialog.clone();
+                move || {
+                    remember_choice(ModeChoice::Weekly, 0);
+                    apply_challenge(&state, weekly_challenge.clone());
+                    dialog.close();
+                }
+            },
+        );
+        let weekly_list = build_single_row_list(&weekly_row);
+        content.append(&weekly_list);
+        if last == Some(ModeChoice::Weekly) {
+            last_row = Some(weekly_row);
+        }
+    }
+    if let Some(row) = &last_row {
+        mark_last_played(row);
+    }
+
     let clamp = adw::Clamp::builder().maximum_size(520).build();
     clamp.set_margin_top(12);
     clamp.set_margin_bottom(0);
     clamp.set_margin_start(15);
     clamp.set_margin_end(15);
     clamp.set_child(Some(&content));
-    clamp
+    (clamp, last_row)
 }
 
 fn build_difficulty_content(
     state: &Rc<RefCell<AppState>>,
     dialog: &adw::Dialog,
     options: &[u8],
-    is_trio: bool,
-) -> adw::Clamp {
+    kind: DifficultyPageKind,
+) -> (adw::Clamp, Option<adw::ActionRow>) {
     let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let is_trio = kind == DifficultyPageKind::Trio;
+    let last_level = last_choice()
+        .filter(|(mode, _)| *mode == kind.mode())
+        .map(|(_, level)| level);
+    let mut last_row = None;
 
     for &level in options {
-        let row = build_difficulty_row(level, is_trio, {
+        let on_select = {
             let state = state.clone();
             let dialog = dialog.clone();
             move || {
-                if is_trio {
-                    let is_current_trio = state.borrow().difficulty == Difficulty::Trio;
-                    apply_trio_level_change(&state, level);
-                    if !is_current_trio {
-                        apply_difficulty_change(&state, Difficulty::Trio);
-                    }
-                } else {
-                    apply_difficulty_change(&state, difficulty_from_level(level));
-                }
+                start_choice(&state, kind.mode(), Some(level));
                 dialog.close();
             }
-        });
+        };
+        let row = if kind == DifficultyPageKind::NBack {
+            build_mode_row(
+                &nback::level_name(level),
+                &ntr_format(
+                    "Compare with the cue {count} step back",
+                    "Compare with the cue {count} steps back",
+                    u32::from(level),
+                    &[],
+                ),
+                false,
+                on_select,
+            )
+        } else {
+            build_difficulty_row(level, is_trio, on_select)
+        };
+        if last_level == Some(level) {
+            mark_last_played(&row);
+            last_row = Some(row.clone());
+        }
         let list = build_single_row_list(&row);
         content.append(&list);
     }
 
+    if kind == DifficultyPageKind::Classic {
+        let warmup_row = adw::SwitchRow::builder()
+            .title(tr("Warm up before Expert"))
+            .subtitle(tr("A quick 3x4 board first, never recorded"))
+            .build();
+        settings::bind_boolean(warmup::WARMUP_KEY, false, &warmup_row, "active");
+        warmup_row.connect_active_notify({
+            let state = state.clone();
+            move |row| {
+                state.borrow_mut().expert_warmup = row.is_active();
+            }
+        });
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        list.add_css_class("boxed-list");
+        list.set_margin_top(6);
+        list.append(&warmup_row);
+        content.append(&list);
+    }
+
     let clamp = adw::Clamp::builder().maximum_size(520).build();
     clamp.set_margin_top(12);
     clamp.set_margin_bottom(0);
     clamp.set_margin_start(15);
     clamp.set_margin_end(15);
     clamp.set_child(Some(&content));
-    clamp
+    (clamp, last_row)
+}
+
+fn build_kids_page(state: &Rc<RefCell<AppState>>, dialog: &adw::Dialog) -> adw::NavigationPage {
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let last_board = last_choice()
+        .filter(|(mode, _)| *mode == ModeChoice::Kids)
+        .map(|(_, level)| usize::from(level));
+    let mut last_row = None;
+    for (index, board) in KidsBoard::ALL.into_iter().enumerate() {
+        let row = adw::ActionRow::builder()
+            .title(tr(board.label()))
+            .activatable(true)
+            .build();
+        row.add_css_class("difficulty-native-row");
+
+        let grid_size = gtk::Label::new(Some(&board.grid_label()));
+        grid_size.add_css_class("dim-label");
+        grid_size.add_css_class("caption");
+        row.add_suffix(&grid_size);
+        if last_board == Some(index) {
+            mark_last_played(&row);
+            last_row = Some(row.clone());
+        }
+
+        let state = state.clone();
+        let dialog = dialog.clone();
+        row.connect_activated(move |_| {
+            remember_choice(ModeChoice::Kids, index as u8);
+            apply_kids_start(&state, board);
+            dialog.close();
+        });
+        content.append(&build_single_row_list(&row));
+    }
+
+    let clamp = adw::Clamp::builder().maximum_size(520).build();
+    clamp.set_margin_top(12);
+    clamp.set_margin_start(15);
+    clamp.set_margin_end(15);
+    clamp.set_child(Some(&content));
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&build_page_header(true));
+    toolbar.set_content(Some(&clamp));
+
+    let page = adw::NavigationPage::builder()
+        .title(tr("Kids Board"))
+        .child(&toolbar)
+        .build();
+    if let Some(row) = last_row {
+        page.connect_shown(move |_| {
+            row.grab_focus();
+        });
+    }
+    page
 }
 
 fn build_difficulty_page(
@@ -200,19 +721,25 @@ fn build_difficulty_page(
     dialog: &adw::Dialog,
     title_text: &str,
     options: &[u8],
-    is_trio: bool,
+    kind: DifficultyPageKind,
 ) -> adw::NavigationPage {
     let header = build_page_header(true);
-    let content = build_difficulty_content(state, dialog, options, is_trio);
+    let (content, last_row) = build_difficulty_content(state, dialog, options, kind);
 
     let toolbar = adw::ToolbarView::new();
     toolbar.add_top_bar(&header);
     toolbar.set_content(Some(&content));
 
-    adw::NavigationPage::builder()
+    let page = adw::NavigationPage::builder()
         .title(title_text)
         .child(&toolbar)
-        .build()
+        .build();
+    if let Some(row) = last_row {
+        page.connect_shown(move |_| {
+            row.grab_focus();
+        });
+    }
+    page
 }
 
 pub fn show_mode_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
@@ -221,8 +748,8 @@ pub fn show_mode_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
     dialog.set_can_close(true);
 
     let compact_layout = if let Some(window) = parent_window.as_ref() {
-        let width = window.allocated_width().max(1);
-        let height = window.allocated_height().max(1);
+        let width = window.width().max(1);
+        let height = window.height().max(1);
         (width < 760 && height < 620) || width < 520
     } else {
         state.borrow().compact_layout
@@ -232,14 +759,14 @@ pub fn show_mode_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
     let (content_width, content_height) = parent_window
         .as_ref()
         .map(|window| {
-            let width = window.width().max(window.allocated_width()).max(1);
+            let width = window.width().max(window.width()).max(1);
             if compact_layout {
-                ((width - 20).clamp(300, 380), 308)
+                ((width - 20).clamp(300, 380), 612)
             } else {
-                ((width - 32).clamp(320, 420), 316)
+                ((width - 32).clamp(320, 420), 620)
             }
         })
-        .unwrap_or((420, 316));
+        .unwrap_or((420, 620));
     dialog.set_content_width(content_width);
     dialog.set_content_height(content_height);
 
@@ -247,33 +774,70 @@ pub fn show_mode_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
     navigation_view.set_hexpand(true);
     navigation_view.set_vexpand(true);
 
-    let classic_difficulty_page = build_difficulty_page(
-        state,
-        &dialog,
-        &tr("Classic Difficulty"),
-        &CLASSIC_LEVEL_OPTIONS,
-        false,
-    );
-    let trio_difficulty_page = build_difficulty_page(
-        state,
-        &dialog,
-        &tr("Trio Difficulty"),
-        &[1, 2, 3, 4],
-        true,
-    );
+    let pages = DifficultyPages {
+        classic: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("Classic Difficulty"),
+            &CLASSIC_LEVEL_OPTIONS,
+            DifficultyPageKind::Classic,
+        ),
+        trio: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("Trio Difficulty"),
+            &[1, 2, 3, 4],
+            DifficultyPageKind::Trio,
+        ),
+        speedrun: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("Speedrun Difficulty"),
+            &CLASSIC_LEVEL_OPTIONS,
+            DifficultyPageKind::Speedrun,
+        ),
+        sudden_death: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("Sudden Death Difficulty"),
+            &CLASSIC_LEVEL_OPTIONS,
+            DifficultyPageKind::SuddenDeath,
+        ),
+        nback: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("N-back Level"),
+            &NBACK_LEVELS,
+            DifficultyPageKind::NBack,
+        ),
+        training: build_difficulty_page(
+            state,
+            &dialog,
+            &tr("Training Difficulty"),
+            &CLASSIC_LEVEL_OPTIONS,
+            DifficultyPageKind::Training,
+        ),
+        kids: build_kids_page(state, &dialog),
+        custom: build_custom_page(state, &dialog),
+        hotseat: build_hotseat_page(state, &dialog),
+        bracket: build_bracket_page(state, &dialog),
+    };
 
     let mode_header = build_page_header(false);
-    let mode_content = build_mode_content(
+    let (mode_content, last_mode_row) = build_mode_content(
         &navigation_view,
-        &classic_difficulty_page,
-        &trio_difficulty_page,
+        &pages,
         state,
         &dialog,
     );
+    let mode_scroller = gtk::ScrolledWindow::new();
+    mode_scroller.set_hscrollbar_policy(gtk::PolicyType::Never);
+    mode_scroller.set_vexpand(true);
+    mode_scroller.set_child(Some(&mode_content));
 
     let mode_toolbar = adw::ToolbarView::new();
     mode_toolbar.add_top_bar(&mode_header);
-    mode_toolbar.set_content(Some(&mode_content));
+    mode_toolbar.set_content(Some(&mode_scroller));
 
     let mode_page = adw::NavigationPage::builder()
         .title(tr("Choose Mode"))
@@ -281,9 +845,41 @@ pub fn show_mode_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
         .build();
 
     navigation_view.add(&mode_page);
-    navigation_view.add(&classic_difficulty_page);
-    navigation_view.add(&trio_difficulty_page);
+    navigation_view.add(&pages.classic);
+    navigation_view.add(&pages.trio);
+    navigation_view.add(&pages.speedrun);
+    navigation_view.add(&pages.sudden_death);
+    navigation_view.add(&pages.nback);
+    navigation_view.add(&pages.training);
+    navigation_view.add(&pages.kids);
+    navigation_view.add(&pages.custom);
+    navigation_view.add(&pages.hotseat);
+    navigation_view.add(&pages.bracket);
 
     dialog.set_child(Some(&navigation_view));
     dialog.present(parent_window.as_ref());
+    if let Some(row) = last_mode_row {
+        row.grab_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModeChoice, parse_mode_request};
+
+    #[test]
+    fn mode_choices_roundtrip_through_their_codes() {
+        for mode in ModeChoice::ALL {
+            assert_eq!(ModeChoice::from_code(mode.code()), Some(mode));
+        }
+        assert_eq!(ModeChoice::from_code(""), None);
+    }
+
+    #[test]
+    fn mode_requests_take_an_optional_level() {
+        assert_eq!(parse_mode_request("classic:3"), Some((ModeChoice::Classic, Some(3))));
+        assert_eq!(parse_mode_request(" Pattern "), Some((ModeChoice::Pattern, None)));
+        assert_eq!(parse_mode_request("trio:hard"), None);
+        assert_eq!(parse_mode_request("chess"), None);
+    }
 }
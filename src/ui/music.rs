@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use super::audio::{SAMPLE_RATE, wav_from_samples};
+use super::events::{self, GameEvent};
+use super::infinite;
+use super::state::AppState;
+
+pub const DYNAMIC_MUSIC_KEY: &str = "dynamic-music";
+const MUSIC_VOLUME: f32 = 0.14;
+const BASE_TEMPO_BPM: u32 = 84;
+const TEMPO_PER_LEVEL_BPM: u32 = 10;
+const TEMPO_PER_TIER_BPM: u32 = 6;
+const MATCHES_PER_LEVEL: u32 = 3;
+const MAX_LAYERS: u8 = 4;
+// Two bars of A minor then F major, as root frequencies.
+const PROGRESSION: [f32; 2] = [220.0, 174.61];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Intensity {
+    pub tempo_bpm: u32,
+    // Bass, then an off-beat pulse, an arpeggio and a high shimmer.
+    pub layers: u8,
+}
+
+// Every few matches in a row add a layer and speed things up; a mismatch drops back to the bass.
+pub fn intensity(match_streak: u32, infinite_tier: u8) -> Intensity {
+    let level = (match_streak / MATCHES_PER_LEVEL).min(u32::from(MAX_LAYERS) - 1);
+    let tier_bonus = u32::from(infinite_tier.saturating_sub(1));
+    Intensity {
+        tempo_bpm: BASE_TEMPO_BPM + level * TEMPO_PER_LEVEL_BPM + tier_bonus * TEMPO_PER_TIER_BPM,
+        layers: 1 + level as u8,
+    }
+}
+
+fn current_intensity(st: &AppState) -> Intensity {
+    let tier = if infinite::is_infinite(st.difficulty) {
+        infinite::level_for_round(st.infinite_pacing, st.infinite_round)
+    } else {
+        0
+    };
+    intensity(st.match_streak, tier)
+}
+
+fn tone(freq: f32, n: usize, len: usize) -> f32 {
+    // Short attack and a linear decay keep the notes plucky rather than droning.
+    let attack = (n as f32 / 200.0).min(1.0);
+    let decay = 1.0 - n as f32 / len as f32;
+    (TAU * freq * n as f32 / SAMPLE_RATE as f32).sin() * attack * decay
+}
+
+// One seamless loop of the progression, as 16-bit mono samples.
+fn render_loop(intensity: Intensity) -> Vec<i16> {
+    let eighth_len = (SAMPLE_RATE * 30 / intensity.tempo_bpm) as usize;
+    let mut mix = vec![0.0f32; eighth_len * 8 * PROGRESSION.len()];
+    for (bar, root) in PROGRESSION.into_iter().enumerate() {
+        let chord = [root, root * 1.2, root * 1.5];
+        for eighth in 0..8 {
+            let start = (bar * 8 + eighth) * eighth_len;
+            for n in 0..eighth_len {
+                let mut value = 0.0;
+                if eighth % 2 == 0 {
+                    value += tone(root / 2.0, n, eighth_len) * 0.9;
+                }
+                if intensity.layers >= 2 && eighth % 2 == 1 {
+                    value += tone(chord[2], n, eighth_len) * 0.35;
+                }
+                if intensity.layers >= 3 {
+                    value += tone(chord[eighth % 3] * 2.0, n, eighth_len) * 0.3;
+                }
+                if intensity.layers >= 4 {
+                    let swell = ((start + n) as f32 / mix.len() as f32 * TAU).sin().abs();
+                    value += (TAU * chord[1] * 4.0 * n as f32 / SAMPLE_RATE as f32).sin() * 0.12 * swell;
+                }
+                mix[start + n] += value;
+            }
+        }
+    }
+    let scale = MUSIC_VOLUME / f32::from(intensity.layers).sqrt();
+    mix.into_iter()
+        .map(|value| (value.clamp(-2.0, 2.0) * scale * f32::from(i16::MAX)) as i16)
+        .collect()
+}
+
+thread_local! {
+    static PLAYING: RefCell<Option<(Intensity, gtk::MediaFile)>> = const { RefCell::new(None) };
+}
+
+fn stop() {
+    if let Some((_, media)) = PLAYING.with(|playing| playing.borrow_mut().take()) {
+        media.set_playing(false);
+    }
+}
+
+fn play(intensity: Intensity) {
+    let unchanged = PLAYING.with(|playing| {
+        playing
+            .borrow()
+            .as_ref()
+            .is_some_and(|(current, _)| *current == intensity)
+    });
+    if unchanged {
+        return;
+    }
+    stop();
+    let bytes = glib::Bytes::from_owned(wav_from_samples(&render_loop(intensity)));
+    let media = gtk::MediaFile::for_input_stream(&gio::MemoryInputStream::from_bytes(&bytes));
+    media.set_loop(true);
+    media.connect_error_notify(|media| {
+        if let Some(err) = media.error() {
+            tracing::warn!("failed to play music: {err}");
+        }
+    });
+    media.play();
+    PLAYING.with(|playing| *playing.borrow_mut() = Some((intensity, media)));
+}
+
+fn sync(st: &AppState, view_stack: &gtk::Stack) {
+    let in_game = view_stack.visible_child_name().as_deref() == Some("game");
+    if st.dynamic_music && in_game && st.kids.is_none() {
+        play(current_intensity(st));
+    } else {
+        stop();
+    }
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>, view_stack: &gtk::Stack) {
+    events::subscribe({
+        let state = state.clone();
+        let view_stack = view_stack.downgrade();
+        move |event| {
+            let Some(view_stack) = view_stack.upgrade() else {
+                return;
+            };
+            match event {
+                GameEvent::TileFlipped(_)
+                | GameEvent::MatchFound(_)
+                | GameEvent::MismatchMade(_)
+                | GameEvent::RoundFinished { .. } => sync(&state.borrow(), &view_stack),
+                GameEvent::PenaltyApplied(_) | GameEvent::BoardChanged => {}
+            }
+        }
+    });
+    let state = state.clone();
+    view_stack.connect_visible_child_name_notify(move |view_stack| {
+        if let Ok(st) = state.try_borrow() {
+            sync(&st, view_stack);
+        } else {
+            stop();
+        }
+    });
+}
+
+pub fn set_enabled(state: &Rc<RefCell<AppState>>, enabled: bool) {
+    let mut st = state.borrow_mut();
+    st.dynamic_music = enabled;
+    if !enabled {
+        stop();
+    } else if let Some(view_stack) = &st.view_stack {
+        sync(&st, view_stack);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaks_and_tiers_raise_the_intensity() {
+        assert_eq!(intensity(0, 0), Intensity { tempo_bpm: 84, layers: 1 });
+        assert_eq!(intensity(4, 0), Intensity { tempo_bpm: 94, layers: 2 });
+        assert_eq!(intensity(40, 0).layers, MAX_LAYERS);
+        assert_eq!(intensity(0, 4).tempo_bpm, 102);
+    }
+
+    #[test]
+    fn loops_cover_the_progression_at_the_tempo() {
+        let slow = render_loop(intensity(0, 0));
+        let fast = render_loop(intensity(9, 0));
+        assert_eq!(slow.len(), (SAMPLE_RATE * 30 / 84) as usize * 16);
+        assert!(fast.len() < slow.len());
+        assert!(slow.iter().any(|sample| *sample != 0));
+    }
+}
@@ -0,0 +1,198 @@
+use rand::Rng;
+
+use crate::i18n::tr_format;
+
+pub const NBACK_LEVELS: [u8; 4] = [1, 2, 3, 4];
+pub const NBACK_SCORED_TRIALS: usize = 20;
+pub const NBACK_TRIAL_MS: u64 = 2500;
+pub const NBACK_SHOW_MS: u64 = 1000;
+const NBACK_MATCH_CHANCE: f64 = 0.3;
+const NBACK_SYMBOLS: [&str; 8] = ["🍎", "🐟", "⭐", "🌙", "🔔", "🍀", "🎈", "🚗"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NBackStimulus {
+    pub cell: usize,
+    pub symbol: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    pub hits: u32,
+    pub misses: u32,
+    pub false_alarms: u32,
+    pub correct_rejections: u32,
+}
+
+impl ChannelStats {
+    fn record(&mut self, is_match: bool, responded: bool) -> bool {
+        match (is_match, responded) {
+            (true, true) => self.hits += 1,
+            (true, false) => self.misses += 1,
+            (false, true) => self.false_alarms += 1,
+            (false, false) => self.correct_rejections += 1,
+        }
+        is_match == responded
+    }
+
+    pub fn accuracy_pct(&self) -> u8 {
+        let correct = self.hits + self.correct_rejections;
+        let total = correct + self.misses + self.false_alarms;
+        if total == 0 {
+            return 100;
+        }
+        ((correct as f64 / total as f64) * 100.0).round() as u8
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TrialFeedback {
+    pub position_correct: bool,
+    pub symbol_correct: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NBackRun {
+    pub n: u8,
+    pub stimuli: Vec<NBackStimulus>,
+    pub trial: usize,
+    pub position_pressed: bool,
+    pub symbol_pressed: bool,
+    pub position: ChannelStats,
+    pub symbol: ChannelStats,
+}
+
+impl NBackRun {
+    pub fn new(n: u8, cell_count: usize, rng: &mut impl Rng) -> Self {
+        let n = n.clamp(NBACK_LEVELS[0], NBACK_LEVELS[NBACK_LEVELS.len() - 1]);
+        let total = NBACK_SCORED_TRIALS + usize::from(n);
+        let mut stimuli: Vec<NBackStimulus> = Vec::with_capacity(total);
+        for index in 0..total {
+            let back = index
+                .checked_sub(usize::from(n))
+                .map(|back_index| stimuli[back_index]);
+            let cell = match back {
+                Some(back) if rng.random_bool(NBACK_MATCH_CHANCE) => back.cell,
+                _ => rng.random_range(0..cell_count.max(1)),
+            };
+            let symbol = match back {
+                Some(back) if rng.random_bool(NBACK_MATCH_CHANCE) => back.symbol,
+                _ => NBACK_SYMBOLS[rng.random_range(0..NBACK_SYMBOLS.len())],
+            };
+            stimuli.push(NBackStimulus { cell, symbol });
+        }
+        Self {
+            n,
+            stimuli,
+            ..Self::default()
+        }
+    }
+
+    pub fn current(&self) -> Option<NBackStimulus> {
+        self.stimuli.get(self.trial).copied()
+    }
+
+    // The first N trials have nothing to compare against.
+    pub fn is_scored_trial(&self) -> bool {
+        self.trial >= usize::from(self.n)
+    }
+
+    pub fn position_matches(&self) -> bool {
+        self.back_stimulus()
+            .zip(self.current())
+            .is_some_and(|(back, current)| back.cell == current.cell)
+    }
+
+    pub fn symbol_matches(&self) -> bool {
+        self.back_stimulus()
+            .zip(self.current())
+            .is_some_and(|(back, current)| back.symbol == current.symbol)
+    }
+
+    fn back_stimulus(&self) -> Option<NBackStimulus> {
+        self.trial
+            .checked_sub(usize::from(self.n))
+            .and_then(|index| self.stimuli.get(index).copied())
+    }
+
+    pub fn finish_trial(&mut self) -> Option<TrialFeedback> {
+        let feedback = self.is_scored_trial().then(|| {
+            let position_matches = self.position_matches();
+            let symbol_matches = self.symbol_matches();
+            TrialFeedback {
+                position_correct: self.position.record(position_matches, self.position_pressed),
+                symbol_correct: self.symbol.record(symbol_matches, self.symbol_pressed),
+            }
+        });
+        self.trial += 1;
+        self.position_pressed = false;
+        self.symbol_pressed = false;
+        feedback
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.trial >= self.stimuli.len()
+    }
+
+    pub fn mode_label(&self) -> String {
+        tr_format(
+            "N-back · {level} · Trial {trial}/{total}",
+            &[
+                ("level", &level_name(self.n)),
+                ("trial", &(self.trial + 1).min(self.stimuli.len())),
+                ("total", &self.stimuli.len()),
+            ],
+        )
+    }
+}
+
+pub fn level_name(n: u8) -> String {
+    tr_format("{n}-back", &[("n", &n)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn sessions_include_warmup_trials_and_some_matches() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let run = NBackRun::new(2, 12, &mut rng);
+
+        assert_eq!(run.stimuli.len(), NBACK_SCORED_TRIALS + 2);
+        assert!(run.stimuli.iter().all(|stimulus| stimulus.cell < 12));
+        let position_matches = (2..run.stimuli.len())
+            .filter(|&index| run.stimuli[index].cell == run.stimuli[index - 2].cell)
+            .count();
+        assert!(position_matches > 0);
+    }
+
+    #[test]
+    fn responses_are_scored_against_n_back() {
+        let stimulus = |cell, symbol| NBackStimulus { cell, symbol };
+        let mut run = NBackRun {
+            n: 1,
+            stimuli: vec![stimulus(0, "🍎"), stimulus(0, "🐟"), stimulus(3, "🐟")],
+            ..NBackRun::default()
+        };
+
+        assert_eq!(run.finish_trial(), None);
+        run.position_pressed = true;
+        assert_eq!(
+            run.finish_trial(),
+            Some(TrialFeedback {
+                position_correct: true,
+                symbol_correct: true
+            })
+        );
+        run.position_pressed = true;
+        let feedback = run.finish_trial().expect("scored trial");
+        assert!(!feedback.position_correct);
+        assert!(!feedback.symbol_correct);
+        assert!(run.is_complete());
+        assert_eq!(run.position.accuracy_pct(), 50);
+        assert_eq!(run.symbol.misses, 1);
+        assert_eq!(run.mode_label(), "N-back · 1-back · Trial 3/3");
+    }
+}
@@ -0,0 +1,244 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::gdk;
+use gtk4::prelude::*;
+
+use crate::i18n::tr;
+
+use super::app::{is_game_view_active, stop_victory_sparks};
+use super::board_canvas::{BoardCanvas, CardFlag};
+use super::events::{self, GameEvent};
+use super::habits;
+use super::hud::{set_header_game, start_timer, stop_preview, stop_timer, update_subtitle};
+use super::nback::{NBACK_SHOW_MS, NBACK_TRIAL_MS, NBackRun};
+use super::records::register_nback_result;
+use super::scene::{rebuild_board, show_victory};
+use super::sequence;
+use super::state::{AppState, TileStatus};
+
+const NBACK_FIRST_TRIAL_DELAY_MS: u64 = 800;
+const NBACK_FEEDBACK_MS: u64 = 400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NBackChannel {
+    Position,
+    Symbol,
+}
+
+pub fn build_nback_controls(state: &Rc<RefCell<AppState>>) -> gtk::Box {
+    let controls = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    controls.set_halign(gtk::Align::Center);
+    controls.add_css_class("nback-controls");
+    controls.set_visible(false);
+
+    for (channel, title, key) in [
+        (NBackChannel::Position, tr("Position match"), "A"),
+        (NBackChannel::Symbol, tr("Symbol match"), "L"),
+    ] {
+        let button = gtk::Button::with_label(&format!("{title} ({key})"));
+        button.add_css_class("pill");
+        button.set_focus_on_click(false);
+        let state = state.clone();
+        button.connect_clicked(move |_| register_response(&state, channel));
+        controls.append(&button);
+    }
+    controls
+}
+
+fn channel_button(st: &AppState, channel: NBackChannel) -> Option<gtk::Widget> {
+    let controls = st.nback_controls.as_ref()?;
+    match channel {
+        NBackChannel::Position => controls.first_child(),
+        NBackChannel::Symbol => controls.last_child(),
+    }
+}
+
+fn reset_channel_buttons(st: &AppState) {
+    for channel in [NBackChannel::Position, NBackChannel::Symbol] {
+        if let Some(button) = channel_button(st, channel) {
+            button.remove_css_class("suggested-action");
+            button.remove_css_class("destructive-action");
+        }
+    }
+}
+
+pub fn refresh_nback_controls(st: &AppState) {
+    if let Some(controls) = &st.nback_controls {
+        controls.set_visible(st.nback.is_some());
+    }
+}
+
+pub fn handle_nback_key(state: &Rc<RefCell<AppState>>, key: gdk::Key) -> bool {
+    {
+        let st = state.borrow();
+        if st.nback.is_none() || !is_game_view_active(&st) {
+            return false;
+        }
+    }
+    let channel = match key.to_lower() {
+        gdk::Key::a => NBackChannel::Position,
+        gdk::Key::l => NBackChannel::Symbol,
+        _ => return false,
+    };
+    register_response(state, channel);
+    true
+}
+
+fn register_response(state: &Rc<RefCell<AppState>>, channel: NBackChannel) {
+    let mut st = state.borrow_mut();
+    let Some(run) = st.nback.as_mut() else {
+        return;
+    };
+    if run.is_complete() || !run.is_scored_trial() {
+        return;
+    }
+    let pressed = match channel {
+        NBackChannel::Position => &mut run.position_pressed,
+        NBackChannel::Symbol => &mut run.symbol_pressed,
+    };
+    if *pressed {
+        return;
+    }
+    *pressed = true;
+    if let Some(button) = channel_button(&st, channel) {
+        button.add_css_class("suggested-action");
+    }
+}
+
+pub fn start_nback_game(state: &Rc<RefCell<AppState>>) {
+    let (needs_rebuild, game_id) = {
+        let mut st = state.borrow_mut();
+        let n = st.nback.as_ref().map_or(2, |run| run.n);
+        st.reset_game();
+        stop_timer(&mut st);
+        stop_preview(&mut st);
+        stop_victory_sparks(&mut st);
+        st.seconds_elapsed = 0;
+        st.lock_input = true;
+        let cell_count = st.tiles.len();
+        st.nback = Some(NBackRun::new(n, cell_count, &mut rand::rng()));
+        habits::note_game_start(&st);
+        (st.board_canvas.as_ref().map_or(0, BoardCanvas::card_count) != st.tiles.len(), st.game_id)
+    };
+
+    if needs_rebuild {
+        rebuild_board(state);
+    }
+
+    {
+        let st = state.borrow();
+        if let Some(container) = &st.board_container {
+            container.add_css_class("no-hover");
+            container.remove_css_class("victory-pending");
+            container.remove_css_class("mode-infinite");
+        }
+        if let Some(board) = &st.board_canvas {
+            board.reset_cards();
+        }
+        reset_channel_buttons(&st);
+        refresh_nback_controls(&st);
+    }
+
+    set_header_game(state);
+    {
+        let st = state.borrow();
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
+            stack.set_visible_child_name("game");
+        }
+    }
+    start_timer(state, true);
+
+    sequence::after(state, game_id, NBACK_FIRST_TRIAL_DELAY_MS, move |state| {
+        show_trial(state, game_id);
+    });
+}
+
+fn set_cue(st: &mut AppState, cell: usize, symbol: Option<&str>) {
+    if let Some(tile) = st.tiles.get_mut(cell) {
+        match symbol {
+            Some(symbol) => {
+                tile.value = symbol.to_string();
+                tile.status = TileStatus::Flipped;
+            }
+            None => tile.status = TileStatus::Hidden,
+        }
+    }
+    if let Some(board) = &st.board_canvas {
+        if symbol.is_some() {
+            board.add_flag(cell, CardFlag::NBackCue);
+        } else {
+            board.remove_flag(cell, CardFlag::NBackCue);
+        }
+    }
+    events::emit(GameEvent::BoardChanged);
+}
+
+fn show_trial(state: &Rc<RefCell<AppState>>, game_id: u64) {
+    let cue = {
+        let mut st = state.borrow_mut();
+        if st.game_id != game_id {
+            return;
+        }
+        let Some(stimulus) = st.nback.as_ref().and_then(NBackRun::current) else {
+            return;
+        };
+        reset_channel_buttons(&st);
+        set_cue(&mut st, stimulus.cell, Some(stimulus.symbol));
+        update_subtitle(&st);
+        stimulus.cell
+    };
+
+    sequence::after(state, game_id, NBACK_SHOW_MS, move |state| {
+        set_cue(&mut state.borrow_mut(), cue, None);
+    });
+    sequence::after(state, game_id, NBACK_TRIAL_MS, move |state| end_trial(state, game_id));
+}
+
+fn end_trial(state: &Rc<RefCell<AppState>>, game_id: u64) {
+    let finished = {
+        let mut st = state.borrow_mut();
+        if st.game_id != game_id {
+            return;
+        }
+        let Some(run) = st.nback.as_mut() else {
+            return;
+        };
+        let feedback = run.finish_trial();
+        let finished = run.is_complete();
+        if let Some(feedback) = feedback {
+            for (channel, correct) in [
+                (NBackChannel::Position, feedback.position_correct),
+                (NBackChannel::Symbol, feedback.symbol_correct),
+            ] {
+                if !correct && let Some(button) = channel_button(&st, channel) {
+                    button.remove_css_class("suggested-action");
+                    button.add_css_class("destructive-action");
+                }
+            }
+        }
+        finished
+    };
+
+    sequence::after(state, game_id, NBACK_FEEDBACK_MS, move |state| {
+        if finished {
+            finish_nback_run(state);
+        } else {
+            show_trial(state, game_id);
+        }
+    });
+}
+
+fn finish_nback_run(state: &Rc<RefCell<AppState>>) {
+    {
+        let mut st = state.borrow_mut();
+        stop_timer(&mut st);
+        st.invalidate_callbacks();
+        st.lock_input = false;
+        register_nback_result(&mut st);
+        reset_channel_buttons(&st);
+    }
+    show_victory(state);
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use gio::prelude::*;
+use gtk4::glib;
+
+const REQUEST_TIMEOUT_SECS: u32 = 10;
+const HTTPS_PORT: u16 = 443;
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+const READ_CHUNK_BYTES: usize = 16 * 1024;
+const USER_AGENT: &str = concat!("Recall/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum NetError {
+    InvalidUrl,
+    InsecureUrl,
+    Io(glib::Error),
+    BadResponse,
+    Status(u16),
+    TooLarge,
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl => write!(f, "invalid server address"),
+            Self::InsecureUrl => write!(f, "server address must use https"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::BadResponse => write!(f, "malformed response"),
+            Self::Status(code) => write!(f, "server answered {code}"),
+            Self::TooLarge => write!(f, "response too large"),
+        }
+    }
+}
+
+// Only https is accepted; results never travel in the clear.
+fn parse_https(url: &str) -> Result<glib::Uri, NetError> {
+    let uri =
+        glib::Uri::parse(url.trim(), glib::UriFlags::NONE).map_err(|_| NetError::InvalidUrl)?;
+    if !uri.scheme().eq_ignore_ascii_case("https") {
+        return Err(NetError::InsecureUrl);
+    }
+    if uri.host().is_none_or(|host| host.is_empty()) {
+        return Err(NetError::InvalidUrl);
+    }
+    Ok(uri)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    base: String,
+}
+
+impl Endpoint {
+    pub fn parse(url: &str) -> Result<Self, NetError> {
+        let uri = parse_https(url)?;
+        let base = glib::Uri::join(
+            glib::UriFlags::NONE,
+            Some("https"),
+            None,
+            uri.host().as_deref(),
+            uri.port(),
+            uri.path().trim_end_matches('/'),
+            None,
+            None,
+        );
+        Ok(Self {
+            base: base.to_string(),
+        })
+    }
+
+    fn url_for(&self, path: &str) -> String {
+        if path.is_empty() {
+            return self.base.clone();
+        }
+        format!("{}/{}", self.base, path.trim_start_matches('/'))
+    }
+}
+
+// HTTP/1.0 keeps the exchange simple: one request per connection, no chunked
+// bodies, and the server hangs up once the answer is sent.
+fn request_head(method: &str, uri: &glib::Uri, body: Option<&str>) -> String {
+    let host = uri.host().unwrap_or_default();
+    let host = if host.contains(':') { format!("[{host}]") } else { host.to_string() };
+    let authority = if uri.port() > 0 { format!("{host}:{}", uri.port()) } else { host };
+    let mut target = uri.path().to_string();
+    if target.is_empty() {
+        target.push('/');
+    }
+    if let Some(query) = uri.query() {
+        target.push('?');
+        target.push_str(&query);
+    }
+    let mut head = format!(
+        "{method} {target} HTTP/1.0\r\n\
+         Host: {authority}\r\n\
+         User-Agent: {USER_AGENT}\r\n\
+         Accept: application/json\r\n"
+    );
+    if let Some(body) = body {
+        head.push_str(&format!(
+            "Content-Type: application/json\r\nContent-Length: {}\r\n",
+            body.len()
+        ));
+    }
+    head.push_str("\r\n");
+    head
+}
+
+fn parse_response(raw: &[u8]) -> Result<Vec<u8>, NetError> {
+    let head_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .filter(|&end| end <= MAX_HEADER_BYTES)
+        .ok_or(NetError::BadResponse)?;
+    let head = std::str::from_utf8(&raw[..head_end]).map_err(|_| NetError::BadResponse)?;
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .filter(|line| line.starts_with("HTTP/"))
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or(NetError::BadResponse)?;
+    if !(200..300).contains(&status) {
+        return Err(NetError::Status(status));
+    }
+    let length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.trim().parse::<usize>().map_err(|_| NetError::BadResponse))
+        .transpose()?;
+    let body = &raw[head_end + 4..];
+    if length.unwrap_or(body.len()) > MAX_RESPONSE_BYTES {
+        return Err(NetError::TooLarge);
+    }
+    match length {
+        Some(length) => body.get(..length).map(<[u8]>::to_vec).ok_or(NetError::BadResponse),
+        None => Ok(body.to_vec()),
+    }
+}
+
+// Gives up as soon as the answer outgrows the cap rather than buffering all of it.
+fn read_capped(input: &gio::InputStream) -> Result<Vec<u8>, NetError> {
+    let mut raw = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK_BYTES];
+    loop {
+        match input.read(&mut chunk[..], gio::Cancellable::NONE) {
+            Ok(0) => return Ok(raw),
+            Ok(read) => raw.extend_from_slice(&chunk[..read]),
+            // Plenty of servers hang up without a TLS close notice once they are done.
+            Err(err) if err.matches(gio::TlsError::Eof) => return Ok(raw),
+            Err(err) => return Err(NetError::Io(err)),
+        }
+        if raw.len() > MAX_HEADER_BYTES + MAX_RESPONSE_BYTES {
+            return Err(NetError::TooLarge);
+        }
+    }
+}
+
+// Blocks until the whole answer is in, so it only ever runs on a worker thread.
+fn send_blocking(method: &str, url: &str, body: Option<&str>) -> Result<Vec<u8>, NetError> {
+    parse_https(url)?;
+    // Kept encoded, so the path and query go out exactly as given.
+    let uri = glib::Uri::parse(url.trim(), glib::UriFlags::ENCODED)
+        .map_err(|_| NetError::InvalidUrl)?;
+    let host = uri.host().ok_or(NetError::InvalidUrl)?;
+    let port = u16::try_from(uri.port()).ok().filter(|&port| port > 0).unwrap_or(HTTPS_PORT);
+
+    let client = gio::SocketClient::new();
+    client.set_tls(true);
+    client.set_timeout(REQUEST_TIMEOUT_SECS);
+    let address = gio::NetworkAddress::new(&host, port);
+    let connection = SocketClientExt::connect(&client, &address, gio::Cancellable::NONE)
+        .map_err(NetError::Io)?;
+
+    let mut request = request_head(method, &uri, body).into_bytes();
+    if let Some(body) = body {
+        request.extend_from_slice(body.as_bytes());
+    }
+    let (_, write_error) = connection
+        .output_stream()
+        .write_all(&request, gio::Cancellable::NONE)
+        .map_err(NetError::Io)?;
+    if let Some(err) = write_error {
+        return Err(NetError::Io(err));
+    }
+    parse_response(&read_capped(&connection.input_stream())?)
+}
+
+async fn request(method: &str, url: String, body: Option<String>) -> Result<Vec<u8>, NetError> {
+    let method = method.to_string();
+    gio::spawn_blocking(move || send_blocking(&method, &url, body.as_deref()))
+        .await
+        .unwrap_or(Err(NetError::BadResponse))
+}
+
+pub async fn send(
+    endpoint: &Endpoint,
+    method: &str,
+    path: &str,
+    body: Option<String>,
+) -> Result<Vec<u8>, NetError> {
+    request(method, endpoint.url_for(path), body).await
+}
+
+// Fetches a whole https address as-is, query included, e.g. a shared file.
+pub async fn fetch(url: &str) -> Result<Vec<u8>, NetError> {
+    parse_https(url)?;
+    request("GET", url.trim().to_string(), None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_must_use_https() {
+        let endpoint = Endpoint::parse("https://scores.example.org:8443/recall/").unwrap();
+        assert_eq!(
+            endpoint.url_for("boards/weekly"),
+            "https://scores.example.org:8443/recall/boards/weekly"
+        );
+        assert_eq!(
+            endpoint.url_for(""),
+            "https://scores.example.org:8443/recall"
+        );
+        assert_eq!(
+            Endpoint::parse("https://scores.example.org")
+                .unwrap()
+                .url_for("/results"),
+            "https://scores.example.org/results"
+        );
+        assert!(matches!(
+            Endpoint::parse("http://scores.example.org"),
+            Err(NetError::InsecureUrl)
+        ));
+        assert!(matches!(
+            Endpoint::parse("not a url"),
+            Err(NetError::InvalidUrl)
+        ));
+    }
+
+    #[test]
+    fn responses_are_checked_for_status_and_size() {
+        assert_eq!(
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}trailing").unwrap(),
+            b"{}"
+        );
+        assert_eq!(parse_response(b"HTTP/1.0 201 Created\r\n\r\n[]").unwrap(), b"[]");
+        assert!(matches!(
+            parse_response(b"HTTP/1.1 404 Not Found\r\n\r\n"),
+            Err(NetError::Status(404))
+        ));
+        assert!(matches!(parse_response(b"{}"), Err(NetError::BadResponse)));
+        let declared =
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", MAX_RESPONSE_BYTES + 1);
+        assert!(matches!(parse_response(declared.as_bytes()), Err(NetError::TooLarge)));
+        let mut oversized = b"HTTP/1.1 200 OK\r\n\r\n".to_vec();
+        oversized.resize(oversized.len() + MAX_RESPONSE_BYTES + 1, b' ');
+        assert!(matches!(parse_response(&oversized), Err(NetError::TooLarge)));
+    }
+
+    #[test]
+    fn requests_name_the_host_and_keep_the_query() {
+        let uri = glib::Uri::parse(
+            "https://scores.example.org:8443/share?board=a%20b",
+            glib::UriFlags::ENCODED,
+        )
+        .unwrap();
+        let head = request_head("POST", &uri, Some("{}"));
+        assert!(head.starts_with("POST /share?board=a%20b HTTP/1.0\r\n"));
+        assert!(head.contains("\r\nHost: scores.example.org:8443\r\n"));
+        assert!(head.ends_with("Content-Length: 2\r\n\r\n"));
+    }
+}
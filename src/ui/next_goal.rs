@@ -0,0 +1,93 @@
+use crate::i18n::ntr_format;
+
+use super::state::Rank;
+
+// Roughly what a mismatch costs in seconds, so time and precision goals can be compared.
+const MISMATCH_COST_SECS: u32 = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Goal {
+    BestTime { mode_label: String, secs_away: u32 },
+    Par { secs_away: u32 },
+    Rank { rank: Rank, mismatches_away: u32 },
+}
+
+impl Goal {
+    fn cost_secs(&self) -> u32 {
+        match self {
+            Self::BestTime { secs_away, .. } | Self::Par { secs_away } => *secs_away,
+            Self::Rank {
+                mismatches_away, ..
+            } => mismatches_away.saturating_mul(MISMATCH_COST_SECS),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::BestTime {
+                mode_label,
+                secs_away,
+            } => ntr_format(
+                "{count} second away from your best {mode} time",
+                "{count} seconds away from your best {mode} time",
+                *secs_away,
+                &[("mode", mode_label)],
+            ),
+            Self::Par { secs_away } => ntr_format(
+                "{count} second over par",
+                "{count} seconds over par",
+                *secs_away,
+                &[],
+            ),
+            Self::Rank {
+                rank,
+                mismatches_away,
+            } => ntr_format(
+                "{count} mismatch from rank {rank}",
+                "{count} mismatches from rank {rank}",
+                *mismatches_away,
+                &[("rank", &rank.as_str())],
+            ),
+        }
+    }
+}
+
+// Goals that were already reached come in with a zero distance and are skipped.
+pub fn nearest(goals: impl IntoIterator<Item = Goal>) -> Option<Goal> {
+    goals
+        .into_iter()
+        .filter(|goal| goal.cost_secs() > 0)
+        .min_by_key(Goal::cost_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_goal_weighs_mismatches_against_seconds() {
+        let best = Goal::BestTime {
+            mode_label: "Hard".to_string(),
+            secs_away: 6,
+        };
+        let rank = Goal::Rank {
+            rank: Rank::S,
+            mismatches_away: 1,
+        };
+        assert_eq!(nearest([best.clone(), rank.clone()]), Some(rank));
+        let close = Goal::BestTime {
+            mode_label: "Hard".to_string(),
+            secs_away: 4,
+        };
+        assert_eq!(
+            nearest([close.clone(), Goal::Par { secs_away: 0 }]),
+            Some(close.clone())
+        );
+        assert_eq!(nearest([Goal::Par { secs_away: 0 }]), None);
+        assert_eq!(close.describe(), "4 seconds away from your best Hard time");
+        assert_eq!(
+            Goal::Rank { rank: Rank::S, mismatches_away: 1 }.describe(),
+            "1 mismatch from rank S"
+        );
+    }
+}
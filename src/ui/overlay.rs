@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::io;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::i18n::{format_duration, tr};
+
+use super::hud;
+use super::paths;
+use super::state::AppState;
+
+pub const STREAM_OVERLAY_KEY: &str = "stream-overlay";
+const OVERLAY_DIR_NAME: &str = "overlay";
+const STATUS_JSON_FILE_NAME: &str = "status.json";
+const STATUS_TEXT_FILE_NAME: &str = "status.txt";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct OverlayStatus {
+    running: bool,
+    mode: String,
+    time: String,
+    seconds: u32,
+    matches: usize,
+    total: usize,
+    streak: u32,
+}
+
+thread_local! {
+    static LAST_STATUS: RefCell<Option<OverlayStatus>> = const { RefCell::new(None) };
+}
+
+// Streaming tools read these files, so they live with the per-run state.
+pub fn overlay_dir() -> PathBuf {
+    paths::data_dir().join(OVERLAY_DIR_NAME)
+}
+
+fn status_for(st: &AppState) -> OverlayStatus {
+    let (matches, total) = hud::match_progress(&st.tiles, st.match_size);
+    OverlayStatus {
+        running: st.timer_handle.is_some() || st.preview_active,
+        mode: hud::mode_label(st),
+        time: format_duration(st.seconds_elapsed),
+        seconds: st.seconds_elapsed,
+        matches,
+        total,
+        streak: st.match_streak,
+    }
+}
+
+fn status_text(status: &OverlayStatus) -> String {
+    if !status.running {
+        return format!("{}\n", tr("In the menu"));
+    }
+    format!(
+        "{}\n{}\n{} {}/{}\n{} {}\n",
+        status.mode,
+        status.time,
+        tr("Matches"),
+        status.matches,
+        status.total,
+        tr("Streak"),
+        status.streak
+    )
+}
+
+// Streaming tools poll these files, so they must never see one half written.
+fn write_status(status: &OverlayStatus) -> io::Result<()> {
+    let dir = overlay_dir();
+    paths::write_atomic(&dir.join(STATUS_JSON_FILE_NAME), &serde_json::to_string(status)?)?;
+    paths::write_atomic(&dir.join(STATUS_TEXT_FILE_NAME), &status_text(status))
+}
+
+// Called on every clock tick and board event; the files are only rewritten when something changed.
+pub fn refresh(st: &AppState) {
+    if !st.stream_overlay {
+        return;
+    }
+    let status = status_for(st);
+    let changed = LAST_STATUS.with(|last| {
+        let mut last = last.borrow_mut();
+        if last.as_ref() == Some(&status) {
+            return false;
+        }
+        *last = Some(status.clone());
+        true
+    });
+    if changed && let Err(err) = write_status(&status) {
+        tracing::warn!("failed to write stream overlay: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_text_lists_mode_time_matches_and_streak() {
+        let status = OverlayStatus {
+            running: true,
+            mode: "Classic · Hard".to_string(),
+            time: format_duration(75),
+            seconds: 75,
+            matches: 4,
+            total: 12,
+            streak: 2,
+        };
+        assert_eq!(
+            status_text(&status),
+            "Classic · Hard\n01:15\nMatches 4/12\nStreak 2\n"
+        );
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"running":true,"mode":"Classic · Hard","time":"01:15","seconds":75,"matches":4,"total":12,"streak":2}"#
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+use super::defeat;
+use super::state::{AppState, Difficulty};
+
+// (tiles, match size, par seconds) for the boards where pacing is part of the challenge.
+const BOARD_PARS: [(usize, usize, u32); 3] = [(48, 2, 260), (42, 3, 210), (48, 3, 250)];
+const NEAR_PAR_PCT: u32 = 75;
+const TENSION_CLASSES: [&str; 3] = ["par-ahead", "par-near", "par-over"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tension {
+    Ahead,
+    Near,
+    Over,
+}
+
+impl Tension {
+    fn css_class(self) -> &'static str {
+        match self {
+            Self::Ahead => TENSION_CLASSES[0],
+            Self::Near => TENSION_CLASSES[1],
+            Self::Over => TENSION_CLASSES[2],
+        }
+    }
+}
+
+pub fn board_par_secs(tile_count: usize, match_size: usize) -> Option<u32> {
+    BOARD_PARS
+        .iter()
+        .find(|(tiles, size, _)| *tiles == tile_count && *size == match_size)
+        .map(|(_, _, par)| *par)
+}
+
+// Expert and the two largest Trio boards, played as a plain single board.
+pub fn par_secs(st: &AppState) -> Option<u32> {
+    let applies = match st.difficulty {
+        Difficulty::Impossible => true,
+        Difficulty::Trio => st.trio_level >= 3,
+        _ => false,
+    };
+    if !applies
+        || st.kids.is_some()
+        || st.custom.is_some()
+        || st.pattern.is_some()
+        || st.nback.is_some()
+        || defeat::countdown_remaining_secs(st).is_some()
+    {
+        return None;
+    }
+    board_par_secs((st.grid_cols * st.grid_rows) as usize, st.match_size)
+}
+
+pub fn tension(elapsed_secs: u32, par_secs: u32) -> Tension {
+    if elapsed_secs > par_secs {
+        Tension::Over
+    } else if elapsed_secs * 100 >= par_secs * NEAR_PAR_PCT {
+        Tension::Near
+    } else {
+        Tension::Ahead
+    }
+}
+
+pub fn apply_tension(widget: &impl IsA<gtk::Widget>, tension: Option<Tension>) {
+    for class in TENSION_CLASSES {
+        widget.remove_css_class(class);
+    }
+    if let Some(tension) = tension {
+        widget.add_css_class(tension.css_class());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tension_rises_as_the_clock_passes_par() {
+        assert_eq!(tension(100, 260), Tension::Ahead);
+        assert_eq!(tension(195, 260), Tension::Near);
+        assert_eq!(tension(260, 260), Tension::Near);
+        assert_eq!(tension(261, 260), Tension::Over);
+    }
+
+    #[test]
+    fn only_large_boards_have_a_par() {
+        assert_eq!(board_par_secs(48, 2), Some(260));
+        assert_eq!(board_par_secs(48, 3), Some(250));
+        assert!(board_par_secs(42, 2).is_none());
+    }
+}
@@ -0,0 +1,292 @@
+use std::cell::{Cell, RefCell};
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::state::Rank;
+
+const FESTIVE_COLORS: [(f64, f64, f64); 6] = [
+    (0.208, 0.518, 0.894),
+    (0.200, 0.820, 0.478),
+    (0.965, 0.827, 0.176),
+    (1.000, 0.471, 0.000),
+    (0.878, 0.106, 0.141),
+    (0.569, 0.255, 0.675),
+];
+const GOLD_COLORS: [(f64, f64, f64); 3] =
+    [(0.965, 0.827, 0.176), (0.898, 0.647, 0.039), (1.000, 1.000, 1.000)];
+const COOL_COLORS: [(f64, f64, f64); 3] =
+    [(0.208, 0.518, 0.894), (0.384, 0.627, 0.918), (0.600, 0.757, 0.945)];
+const GRAVITY_PX_S2: f64 = 420.0;
+// Longer frame gaps (a hidden window, a stalled main loop) are treated as one frame.
+const MAX_STEP_MS: f64 = 50.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Emission {
+    pub per_second: f64,
+    pub duration_ms: f64,
+    // Half-angle of the launch cone, in degrees around straight down.
+    pub spread_deg: f64,
+    pub speed_px_s: (f64, f64),
+    pub lifetime_ms: (f64, f64),
+    pub size_px: (f64, f64),
+    pub colors: &'static [(f64, f64, f64)],
+    // Fountains launch upwards from the bottom edge instead of raining from the top.
+    pub fountain: bool,
+}
+
+pub fn emission_for(rank: Rank, kids: bool) -> Option<Emission> {
+    let festive = Emission {
+        per_second: 32.0,
+        duration_ms: 3500.0,
+        spread_deg: 28.0,
+        speed_px_s: (60.0, 160.0),
+        lifetime_ms: (1100.0, 1800.0),
+        size_px: (5.0, 9.0),
+        colors: &FESTIVE_COLORS,
+        fountain: false,
+    };
+    if kids {
+        return Some(Emission {
+            per_second: 48.0,
+            duration_ms: f64::from(super::kids::KIDS_SPARK_DURATION_MS),
+            size_px: (7.0, 12.0),
+            ..festive
+        });
+    }
+    match rank {
+        Rank::S => Some(Emission {
+            per_second: 44.0,
+            spread_deg: 40.0,
+            colors: &GOLD_COLORS,
+            ..festive
+        }),
+        Rank::A => Some(festive),
+        Rank::B => Some(Emission {
+            per_second: 12.0,
+            duration_ms: 2000.0,
+            spread_deg: 16.0,
+            colors: &COOL_COLORS,
+            ..festive
+        }),
+        Rank::C => None,
+    }
+}
+
+// Personal bests get gold fountains from both lower corners of the card.
+pub fn record_emission() -> Emission {
+    Emission {
+        per_second: 40.0,
+        duration_ms: 4000.0,
+        spread_deg: 14.0,
+        speed_px_s: (360.0, 470.0),
+        lifetime_ms: (1300.0, 2000.0),
+        size_px: (5.0, 9.0),
+        colors: &GOLD_COLORS,
+        fountain: true,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shape {
+    Square,
+    Diamond,
+    Circle,
+}
+
+#[derive(Clone, Debug)]
+struct Particle {
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    angle: f64,
+    spin: f64,
+    age_ms: f64,
+    lifetime_ms: f64,
+    size: f64,
+    color: (f64, f64, f64),
+    shape: Shape,
+}
+
+pub struct ParticleSystem {
+    emission: Emission,
+    particles: Vec<Particle>,
+    elapsed_ms: f64,
+    spawn_debt: f64,
+    rng: StdRng,
+}
+
+impl ParticleSystem {
+    pub fn new(emission: Emission, seed: u64) -> Self {
+        Self {
+            emission,
+            particles: Vec::new(),
+            elapsed_ms: 0.0,
+            spawn_debt: 0.0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed_ms >= self.emission.duration_ms && self.particles.is_empty()
+    }
+
+    pub fn step(&mut self, dt_ms: f64, width: f64, height: f64) {
+        let dt_ms = dt_ms.clamp(0.0, MAX_STEP_MS);
+        let dt = dt_ms / 1000.0;
+        for particle in &mut self.particles {
+            particle.vy += GRAVITY_PX_S2 * dt;
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+            particle.angle += particle.spin * dt;
+            particle.age_ms += dt_ms;
+        }
+        self.particles.retain(|particle| particle.age_ms < particle.lifetime_ms);
+
+        if self.elapsed_ms < self.emission.duration_ms {
+            self.spawn_debt += self.emission.per_second * dt;
+            while self.spawn_debt >= 1.0 {
+                self.spawn_debt -= 1.0;
+                let particle = self.spawn(width, height);
+                self.particles.push(particle);
+            }
+        }
+        self.elapsed_ms += dt_ms;
+    }
+
+    fn spawn(&mut self, width: f64, height: f64) -> Particle {
+        let emission = self.emission;
+        let rng = &mut self.rng;
+        let spread = emission.spread_deg.to_radians();
+        let (x, y, heading) = if emission.fountain {
+            // Alternate corners, each aimed a little inwards.
+            let from_left = rng.random_bool(0.5);
+            let lean = if from_left { 0.3 } else { -0.3 };
+            let x = if from_left { width * 0.15 } else { width * 0.85 };
+            (x, height + 4.0, -PI / 2.0 + lean + rng.random_range(-spread..=spread))
+        } else {
+            let x = rng.random_range(8.0..=(width - 8.0).max(9.0));
+            (x, rng.random_range(-24.0..=-5.0), PI / 2.0 + rng.random_range(-spread..=spread))
+        };
+        let speed = rng.random_range(emission.speed_px_s.0..=emission.speed_px_s.1);
+        Particle {
+            x,
+            y,
+            vx: speed * heading.cos(),
+            vy: speed * heading.sin(),
+            angle: rng.random_range(0.0..PI),
+            spin: rng.random_range(-9.0..=9.0),
+            age_ms: 0.0,
+            lifetime_ms: rng.random_range(emission.lifetime_ms.0..=emission.lifetime_ms.1),
+            size: rng.random_range(emission.size_px.0..=emission.size_px.1),
+            color: emission.colors[rng.random_range(0..emission.colors.len())],
+            shape: match rng.random_range(0..3) {
+                0 => Shape::Square,
+                1 => Shape::Diamond,
+                _ => Shape::Circle,
+            },
+        }
+    }
+
+    fn draw(&self, cr: &gtk::cairo::Context) {
+        for particle in &self.particles {
+            // Fade in quickly, then out over the last third of the particle's life.
+            let life = particle.age_ms / particle.lifetime_ms;
+            let alpha = (life / 0.12).min(1.0).min((1.0 - life) / 0.33).clamp(0.0, 1.0);
+            let (r, g, b) = particle.color;
+            let half = particle.size / 2.0;
+            cr.save().ok();
+            cr.translate(particle.x, particle.y);
+            cr.rotate(particle.angle);
+            cr.set_source_rgba(r, g, b, alpha);
+            match particle.shape {
+                Shape::Square => cr.rectangle(-half, -half, particle.size, particle.size),
+                Shape::Diamond => {
+                    cr.move_to(0.0, -half);
+                    cr.line_to(half, 0.0);
+                    cr.line_to(0.0, half);
+                    cr.line_to(-half, 0.0);
+                    cr.close_path();
+                }
+                Shape::Circle => cr.arc(0.0, 0.0, half, 0.0, 2.0 * PI),
+            }
+            cr.fill().ok();
+            cr.restore().ok();
+        }
+    }
+}
+
+// Runs on the area's frame clock until the emission is spent or `keep_running` says stop.
+pub fn start(
+    area: &gtk::DrawingArea,
+    emission: Emission,
+    keep_running: impl Fn() -> bool + 'static,
+) -> gtk::TickCallbackId {
+    let system = Rc::new(RefCell::new(ParticleSystem::new(emission, rand::random())));
+    area.set_draw_func({
+        let system = system.clone();
+        move |_, cr, _, _| system.borrow().draw(cr)
+    });
+    let last_frame_us = Cell::new(None::<i64>);
+    area.add_tick_callback(move |area, clock| {
+        let now_us = clock.frame_time();
+        let dt_ms = last_frame_us
+            .replace(Some(now_us))
+            .map_or(0.0, |last| (now_us - last) as f64 / 1000.0);
+        let mut system = system.borrow_mut();
+        system.step(dt_ms, f64::from(area.width()), f64::from(area.height()));
+        area.queue_draw();
+        if system.is_finished() || !keep_running() {
+            return gtk::glib::ControlFlow::Break;
+        }
+        gtk::glib::ControlFlow::Continue
+    })
+}
+
+pub fn clear(area: &gtk::DrawingArea) {
+    area.set_draw_func(|_, _, _, _| {});
+    area.queue_draw();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emission_stops_and_particles_settle() {
+        let emission = emission_for(Rank::S, false).expect("S ranks celebrate");
+        let mut system = ParticleSystem::new(emission, 7);
+        let mut peak = 0;
+        for _ in 0..(emission.duration_ms / 16.0) as usize {
+            system.step(16.0, 280.0, 430.0);
+            peak = peak.max(system.particles.len());
+        }
+        assert!(peak > 20);
+        assert!(!system.is_finished());
+        for _ in 0..200 {
+            system.step(16.0, 280.0, 430.0);
+        }
+        assert!(system.is_finished());
+    }
+
+    #[test]
+    fn record_fountains_rise_from_the_bottom() {
+        let mut system = ParticleSystem::new(record_emission(), 3);
+        system.step(50.0, 280.0, 430.0);
+        assert!(!system.particles.is_empty());
+        assert!(system.particles.iter().all(|particle| particle.vy < 0.0 && particle.y > 400.0));
+    }
+
+    #[test]
+    fn higher_ranks_get_livelier_bursts() {
+        let rate = |rank| emission_for(rank, false).map_or(0.0, |emission| emission.per_second);
+        assert!(rate(Rank::S) > rate(Rank::A));
+        assert!(rate(Rank::A) > rate(Rank::B));
+        assert_eq!(rate(Rank::C), 0.0);
+    }
+}
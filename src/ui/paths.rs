@@ -0,0 +1,110 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use gtk4::glib;
+
+const APP_DIR_NAME: &str = "recall";
+pub const DATA_DIR_ENV: &str = "RECALL_DATA_DIR";
+
+static DIR_OVERRIDE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// Must run before the first path lookup; `--data-dir` wins over the environment.
+pub fn set_dir_override(dir: Option<PathBuf>) {
+    if DIR_OVERRIDE.set(dir.or_else(env_dir_override)).is_err() {
+        tracing::warn!("data directory was chosen too late and is ignored");
+    }
+}
+
+fn env_dir_override() -> Option<PathBuf> {
+    std::env::var_os(DATA_DIR_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+// Portable installs and test profiles keep everything in one chosen directory.
+pub fn dir_override() -> Option<&'static Path> {
+    DIR_OVERRIDE.get_or_init(env_dir_override).as_deref()
+}
+
+// Preferences and records, which people may want to back up or sync.
+pub fn config_dir() -> PathBuf {
+    match dir_override() {
+        Some(dir) => dir.to_path_buf(),
+        None => glib::user_config_dir().join(APP_DIR_NAME),
+    }
+}
+
+// Per-run state the app can rebuild or drop, such as the saved last run.
+pub fn data_dir() -> PathBuf {
+    match dir_override() {
+        Some(dir) => dir.to_path_buf(),
+        None => glib::user_data_dir().join(APP_DIR_NAME),
+    }
+}
+
+// Moves a file written by an older version to its new place, once.
+pub fn migrate_file(old_path: &Path, new_path: &Path) -> io::Result<()> {
+    if new_path.exists() || !old_path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = new_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // rename fails across filesystems, e.g. a separate /home/.local mount.
+    if fs::rename(old_path, new_path).is_err() {
+        fs::copy(old_path, new_path)?;
+        fs::remove_file(old_path)?;
+    }
+    Ok(())
+}
+
+// Readers never see a half-written file: the data lands next to it and is renamed over it.
+pub fn write_atomic(path: &Path, data: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    fs::write(&tmp_path, data)?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migration_moves_old_file_and_keeps_newer_one() {
+        let root = std::env::temp_dir().join(format!("recall-paths-{}", std::process::id()));
+        let old_path = root.join("config").join("last_run.v1");
+        let new_path = root.join("data").join("last_run.v1");
+        fs::create_dir_all(old_path.parent().unwrap()).unwrap();
+        fs::write(&old_path, "old").unwrap();
+
+        migrate_file(&old_path, &new_path).unwrap();
+        assert!(!old_path.exists());
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "old");
+
+        fs::write(&old_path, "stale").unwrap();
+        migrate_file(&old_path, &new_path).unwrap();
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "old");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn atomic_writes_replace_the_file_without_leaving_a_temp_file() {
+        let root = std::env::temp_dir().join(format!("recall-atomic-{}", std::process::id()));
+        let path = root.join("nested").join("presets.v1");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
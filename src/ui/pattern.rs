@@ -0,0 +1,131 @@
+use rand::Rng;
+
+use crate::i18n::tr;
+
+pub const PATTERN_ROUNDS: u32 = 8;
+const PATTERN_START_LEN: usize = 3;
+const PATTERN_MAX_LEN: usize = 10;
+const PATTERN_FLASH_BASE_MS: u64 = 900;
+const PATTERN_FLASH_PER_CELL_MS: u64 = 150;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatternRun {
+    pub round: u32,
+    pub pattern: Vec<usize>,
+    pub picks: Vec<usize>,
+    pub hits: u32,
+    pub misses: u32,
+    pub perfect_rounds: u32,
+    pub longest_pattern: usize,
+}
+
+impl PatternRun {
+    // Patterns grow by one cell after every flawless round.
+    pub fn pattern_len(&self) -> usize {
+        (PATTERN_START_LEN + self.perfect_rounds as usize).min(PATTERN_MAX_LEN)
+    }
+
+    pub fn start_round(&mut self, tile_count: usize, rng: &mut impl Rng) {
+        let len = self.pattern_len().min(tile_count);
+        self.round += 1;
+        self.pattern = rand::seq::index::sample(rng, tile_count, len).into_vec();
+        self.picks.clear();
+    }
+
+    // Returns whether the pick was part of the pattern, or None if already picked.
+    pub fn register_pick(&mut self, index: usize) -> Option<bool> {
+        if self.picks.contains(&index) || self.round_complete() {
+            return None;
+        }
+        self.picks.push(index);
+        let hit = self.pattern.contains(&index);
+        if hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        if self.round_complete() {
+            let round_hits = self
+                .picks
+                .iter()
+                .filter(|pick| self.pattern.contains(pick))
+                .count();
+            if round_hits == self.pattern.len() {
+                self.perfect_rounds += 1;
+                self.longest_pattern = self.longest_pattern.max(self.pattern.len());
+            }
+        }
+        Some(hit)
+    }
+
+    pub fn round_complete(&self) -> bool {
+        !self.pattern.is_empty() && self.picks.len() >= self.pattern.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.round >= PATTERN_ROUNDS && self.round_complete()
+    }
+
+    pub fn accuracy_pct(&self) -> u8 {
+        let attempts = self.hits + self.misses;
+        if attempts == 0 {
+            return 100;
+        }
+        ((self.hits as f64 / attempts as f64) * 100.0).round() as u8
+    }
+
+    pub fn flash_ms(&self) -> u64 {
+        PATTERN_FLASH_BASE_MS + PATTERN_FLASH_PER_CELL_MS * self.pattern.len() as u64
+    }
+
+    pub fn mode_label(&self) -> String {
+        format!(
+            "{} · {} {}/{}",
+            tr("Pattern"),
+            tr("Round"),
+            self.round.clamp(1, PATTERN_ROUNDS),
+            PATTERN_ROUNDS
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn flawless_rounds_grow_the_pattern() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut run = PatternRun::default();
+        run.start_round(24, &mut rng);
+        assert_eq!(run.pattern.len(), 3);
+
+        for index in run.pattern.clone() {
+            assert_eq!(run.register_pick(index), Some(true));
+        }
+        assert!(run.round_complete());
+        assert_eq!(run.perfect_rounds, 1);
+
+        run.start_round(24, &mut rng);
+        assert_eq!(run.pattern.len(), 4);
+    }
+
+    #[test]
+    fn misses_count_against_accuracy() {
+        let mut run = PatternRun {
+            round: 1,
+            pattern: vec![0, 1, 2],
+            ..PatternRun::default()
+        };
+
+        assert_eq!(run.register_pick(0), Some(true));
+        assert_eq!(run.register_pick(0), None);
+        assert_eq!(run.register_pick(5), Some(false));
+        assert_eq!(run.register_pick(2), Some(true));
+        assert!(run.round_complete());
+        assert_eq!(run.perfect_rounds, 0);
+        assert_eq!(run.accuracy_pct(), 67);
+    }
+}
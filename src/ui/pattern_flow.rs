@@ -0,0 +1,168 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+use super::app::stop_victory_sparks;
+use super::board_canvas::{BoardCanvas, CardFlag};
+use super::habits;
+use super::hud::{set_header_game, start_timer, stop_preview, stop_timer, update_subtitle};
+use super::pattern::PatternRun;
+use super::records::register_pattern_result;
+use super::scene::{rebuild_board, show_victory};
+use super::sequence;
+use super::state::AppState;
+
+const PATTERN_FIRST_ROUND_DELAY_MS: u64 = 600;
+const PATTERN_ROUND_PAUSE_MS: u64 = 900;
+
+const PATTERN_FLAGS: [CardFlag; 3] = [CardFlag::PatternLit, CardFlag::PatternHit, CardFlag::PatternMiss];
+
+fn clear_pattern_flags(st: &AppState) {
+    if let Some(board) = &st.board_canvas {
+        board.remove_flags_everywhere(&PATTERN_FLAGS);
+    }
+}
+
+pub fn start_pattern_game(state: &Rc<RefCell<AppState>>) {
+    let (needs_rebuild, game_id) = {
+        let mut st = state.borrow_mut();
+        st.reset_game();
+        stop_timer(&mut st);
+        stop_preview(&mut st);
+        stop_victory_sparks(&mut st);
+        st.seconds_elapsed = 0;
+        st.lock_input = true;
+        st.pattern = Some(PatternRun::default());
+        habits::note_game_start(&st);
+        (st.board_canvas.as_ref().map_or(0, BoardCanvas::card_count) != st.tiles.len(), st.game_id)
+    };
+
+    if needs_rebuild {
+        rebuild_board(state);
+    }
+
+    {
+        let st = state.borrow();
+        if let Some(container) = &st.board_container {
+            container.add_css_class("no-hover");
+            container.remove_css_class("victory-pending");
+            container.remove_css_class("mode-infinite");
+        }
+        if let Some(board) = &st.board_canvas {
+            board.reset_cards();
+        }
+    }
+
+    set_header_game(state);
+    {
+        let st = state.borrow();
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
+            stack.set_visible_child_name("game");
+        }
+    }
+    start_timer(state, true);
+
+    sequence::after(state, game_id, PATTERN_FIRST_ROUND_DELAY_MS, move |state| {
+        schedule_pattern_round(state, game_id);
+    });
+}
+
+fn schedule_pattern_round(state: &Rc<RefCell<AppState>>, game_id: u64) {
+    let flash_ms = {
+        let mut st = state.borrow_mut();
+        if st.game_id != game_id {
+            return;
+        }
+        let tile_count = st.tiles.len();
+        let Some(run) = st.pattern.as_mut() else {
+            return;
+        };
+        run.start_round(tile_count, &mut rand::rng());
+        let pattern = run.pattern.clone();
+        let flash_ms = run.flash_ms();
+        st.lock_input = true;
+        clear_pattern_flags(&st);
+        if let Some(board) = &st.board_canvas {
+            for index in pattern {
+                board.add_flag(index, CardFlag::PatternLit);
+            }
+        }
+        update_subtitle(&st);
+        flash_ms
+    };
+
+    sequence::after(state, game_id, flash_ms, |state| {
+        let mut st = state.borrow_mut();
+        clear_pattern_flags(&st);
+        st.lock_input = false;
+    });
+}
+
+pub fn handle_pattern_pick(state: &Rc<RefCell<AppState>>, index: usize) {
+    let (game_id, finished) = {
+        let mut st = state.borrow_mut();
+        if st.lock_input {
+            return;
+        }
+        let Some(run) = st.pattern.as_mut() else {
+            return;
+        };
+        let Some(hit) = run.register_pick(index) else {
+            return;
+        };
+        let round_complete = run.round_complete();
+        let finished = run.is_finished();
+        let missed_cells = run
+            .pattern
+            .iter()
+            .copied()
+            .filter(|cell| !run.picks.contains(cell))
+            .collect::<Vec<usize>>();
+        if let Some(board) = &st.board_canvas {
+            board.add_flag(index, if hit { CardFlag::PatternHit } else { CardFlag::PatternMiss });
+            if !hit {
+                board.add_flag(index, CardFlag::Shake);
+            }
+        }
+        if !round_complete {
+            return;
+        }
+        st.lock_input = true;
+        // Show the cells the player forgot before moving on.
+        if let Some(board) = &st.board_canvas {
+            for cell in missed_cells {
+                board.add_flag(cell, CardFlag::PatternLit);
+            }
+        }
+        (st.game_id, finished)
+    };
+
+    sequence::after(state, game_id, PATTERN_ROUND_PAUSE_MS, move |state| {
+        {
+            let st = state.borrow();
+            clear_pattern_flags(&st);
+            if let Some(board) = &st.board_canvas {
+                board.remove_flags_everywhere(&[CardFlag::Shake]);
+            }
+        }
+        if finished {
+            finish_pattern_run(state);
+        } else {
+            schedule_pattern_round(state, game_id);
+        }
+    });
+}
+
+fn finish_pattern_run(state: &Rc<RefCell<AppState>>) {
+    {
+        let mut st = state.borrow_mut();
+        stop_timer(&mut st);
+        st.invalidate_callbacks();
+        st.lock_input = false;
+        register_pattern_result(&mut st);
+    }
+    show_victory(state);
+}
@@ -0,0 +1,55 @@
+use gtk4::gdk;
+use gtk4::prelude::*;
+
+use super::state::{AppState, Tile, TileStatus};
+
+pub const PEEK_ASSIST_KEY: &str = "peek-assist";
+
+pub fn is_peek_key(key: gdk::Key) -> bool {
+    matches!(key, gdk::Key::Shift_L | gdk::Key::Shift_R)
+}
+
+pub fn shows(st: &AppState, tile: &Tile) -> bool {
+    st.peeking && tile.seen && tile.status == TileStatus::Hidden
+}
+
+pub fn set_peeking(st: &mut AppState, peeking: bool) {
+    let allowed = peeking && st.peek_assist && st.run_assisted() && !st.preview_active;
+    if st.peeking == allowed {
+        return;
+    }
+    st.peeking = allowed;
+    if let Some(board) = &st.board_canvas
+        && st.tiles.iter().any(|tile| tile.seen && tile.status == TileStatus::Hidden)
+    {
+        board.queue_draw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(status: TileStatus, seen: bool) -> Tile {
+        Tile {
+            value: "🐶".to_string(),
+            status,
+            seen,
+            views: u32::from(seen),
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn only_seen_hidden_tiles_show_while_peeking() {
+        let mut st = AppState {
+            peeking: true,
+            ..AppState::default()
+        };
+        assert!(shows(&st, &tile(TileStatus::Hidden, true)));
+        assert!(!shows(&st, &tile(TileStatus::Hidden, false)));
+        assert!(!shows(&st, &tile(TileStatus::Matched, true)));
+        st.peeking = false;
+        assert!(!shows(&st, &tile(TileStatus::Hidden, true)));
+    }
+}
@@ -0,0 +1,378 @@
+use rand::rngs::StdRng;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+
+use super::app::reshuffle_flip_ms;
+use super::board_canvas::{FLIP_PHASE_MS, TURN_DOWN_MS};
+use super::classic_penalties::{self, PunishmentPlan};
+use super::state::{AppState, Difficulty, TileStatus};
+use super::trio_penalties;
+
+const MAX_GAMES: u32 = 100_000;
+// Gives up on a game that a model cannot finish, so a harsh profile still reports.
+const MAX_TURNS: u32 = 2_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MemoryModel {
+    pub name: &'static str,
+    // Chance that a card turned over is remembered at all.
+    pub recall: f64,
+    // Chance that each remembered card is lost again after a turn.
+    pub forget: f64,
+    // Share of the preview that sticks before the first pick.
+    pub preview_recall: f64,
+    pub think_ms: u64,
+}
+
+pub const MODELS: [MemoryModel; 3] = [
+    MemoryModel {
+        name: "sharp",
+        recall: 0.95,
+        forget: 0.01,
+        preview_recall: 0.4,
+        think_ms: 600,
+    },
+    MemoryModel {
+        name: "steady",
+        recall: 0.8,
+        forget: 0.03,
+        preview_recall: 0.2,
+        think_ms: 800,
+    },
+    MemoryModel {
+        name: "forgetful",
+        recall: 0.6,
+        forget: 0.08,
+        preview_recall: 0.1,
+        think_ms: 1000,
+    },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    Classic(Difficulty),
+    Trio(u8),
+}
+
+impl Profile {
+    pub const ALL: [Self; 8] = [
+        Self::Classic(Difficulty::Easy),
+        Self::Classic(Difficulty::Medium),
+        Self::Classic(Difficulty::Hard),
+        Self::Classic(Difficulty::Impossible),
+        Self::Trio(1),
+        Self::Trio(2),
+        Self::Trio(3),
+        Self::Trio(4),
+    ];
+
+    fn name(self) -> String {
+        match self {
+            Self::Classic(Difficulty::Easy) => "Classic Easy".to_string(),
+            Self::Classic(Difficulty::Medium) => "Classic Medium".to_string(),
+            Self::Classic(Difficulty::Hard) => "Classic Hard".to_string(),
+            Self::Classic(_) => "Classic Expert".to_string(),
+            Self::Trio(level) => format!("Trio {level}"),
+        }
+    }
+
+    fn board(self, rng: &mut StdRng) -> AppState {
+        let (difficulty, trio_level, (cols, rows, match_size)) = match self {
+            Self::Classic(difficulty) => (
+                difficulty,
+                1,
+                difficulty.fixed_config().unwrap_or((4, 4, 2)),
+            ),
+            Self::Trio(level) => (Difficulty::Trio, level, AppState::trio_config(level)),
+        };
+        let mut st = AppState {
+            difficulty,
+            trio_level,
+            grid_cols: cols,
+            grid_rows: rows,
+            match_size,
+            ..AppState::default()
+        };
+        st.reset_game_with_seed(rng.random());
+        st
+    }
+}
+
+// `GAMES` runs every built-in model; `GAMES:RECALL` runs one custom model instead.
+pub fn parse_spec(spec: &str) -> Option<(u32, Vec<MemoryModel>)> {
+    let mut parts = spec.trim().splitn(2, ':');
+    let games: u32 = parts.next()?.parse().ok()?;
+    if !(1..=MAX_GAMES).contains(&games) {
+        return None;
+    }
+    let models = match parts.next() {
+        Some(recall) => {
+            let recall: f64 = recall.parse().ok()?;
+            if !(0.05..=1.0).contains(&recall) {
+                return None;
+            }
+            vec![MemoryModel {
+                name: "custom",
+                recall,
+                forget: (1.0 - recall) / 10.0,
+                preview_recall: recall / 3.0,
+                think_ms: 800,
+            }]
+        }
+        None => MODELS.to_vec(),
+    };
+    Some((games, models))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GameOutcome {
+    pub time_ms: u64,
+    pub mismatches: u32,
+    pub penalties: u32,
+    pub finished: bool,
+}
+
+struct Player<'a> {
+    model: &'a MemoryModel,
+    // What the player believes sits at each index; None once forgotten or reshuffled.
+    memory: Vec<Option<String>>,
+}
+
+impl Player<'_> {
+    fn see(&mut self, st: &AppState, idx: usize, rng: &mut StdRng) {
+        if rng.random_bool(self.model.recall) {
+            self.memory[idx] = Some(st.tiles[idx].value.clone());
+        }
+    }
+
+    fn forget(&mut self, rng: &mut StdRng) {
+        for slot in &mut self.memory {
+            if slot.is_some() && rng.random_bool(self.model.forget) {
+                *slot = None;
+            }
+        }
+    }
+
+    // A remembered complete group first, then something new, then a known card.
+    fn pick(&self, st: &AppState, picks: &[usize], rng: &mut StdRng) -> Option<usize> {
+        let open: Vec<usize> = (0..st.tiles.len())
+            .filter(|idx| st.tiles[*idx].status == TileStatus::Hidden && !picks.contains(idx))
+            .collect();
+        let remembered = |value: &str| -> Vec<usize> {
+            open.iter()
+                .copied()
+                .filter(|idx| self.memory[*idx].as_deref() == Some(value))
+                .collect()
+        };
+        let target = match picks.first() {
+            Some(first) => Some(st.tiles[*first].value.clone()),
+            None => open
+                .iter()
+                .filter_map(|idx| self.memory[*idx].clone())
+                .find(|value| remembered(value).len() >= st.match_size),
+        };
+        if let Some(target) = target
+            && let Some(idx) = remembered(&target).first()
+        {
+            return Some(*idx);
+        }
+        let unknown: Vec<usize> = open
+            .iter()
+            .copied()
+            .filter(|idx| self.memory[*idx].is_none())
+            .collect();
+        unknown.choose(rng).or_else(|| open.choose(rng)).copied()
+    }
+}
+
+fn plan_penalty(st: &mut AppState, profile: Profile, first_pick: usize) -> Option<PunishmentPlan> {
+    match profile {
+        Profile::Classic(difficulty) => {
+            classic_penalties::register_mismatch_and_plan_reshuffle_for(st, first_pick, difficulty)
+        }
+        Profile::Trio(_) => trio_penalties::register_mismatch_and_plan_reshuffle(st, first_pick),
+    }
+}
+
+fn reset_penalty(st: &mut AppState, profile: Profile) {
+    match profile {
+        Profile::Classic(difficulty) => classic_penalties::reset_penalty_after_match_for(st, difficulty),
+        Profile::Trio(_) => trio_penalties::reset_penalty_after_match(st),
+    }
+}
+
+// Mirrors the punishment sequence in app.rs, drawing its timings from there.
+fn apply_penalty(
+    st: &mut AppState,
+    player: &mut Player,
+    plan: PunishmentPlan,
+    rng: &mut StdRng,
+) -> u64 {
+    let hidden: Vec<usize> = (0..st.tiles.len())
+        .filter(|idx| st.tiles[*idx].status == TileStatus::Hidden)
+        .collect();
+    let reshuffle_ms = reshuffle_flip_ms(&plan, hidden.len(), st.tiles.len());
+    if plan.reshuffle_hidden {
+        st.reshuffle_hidden_tiles();
+        for &idx in &hidden {
+            player.memory[idx] = None;
+        }
+    }
+    let mut revealed = hidden;
+    revealed.shuffle(rng);
+    if !plan.reveal_all_hidden {
+        revealed.truncate(plan.reveal_count);
+    }
+    for idx in revealed {
+        player.see(st, idx, rng);
+    }
+    reshuffle_ms + plan.reveal_ms + TURN_DOWN_MS
+}
+
+fn mismatch_pause_ms(st: &AppState, profile: Profile) -> u64 {
+    match profile {
+        Profile::Classic(difficulty) => classic_penalties::mismatch_pause_ms(difficulty),
+        Profile::Trio(_) => trio_penalties::mismatch_pause_ms(trio_penalties::penalty_level(st)),
+    }
+}
+
+pub fn play_game(profile: Profile, model: &MemoryModel, rng: &mut StdRng) -> GameOutcome {
+    let mut st = profile.board(rng);
+    let mut player = Player {
+        model,
+        memory: vec![None; st.tiles.len()],
+    };
+    for idx in 0..st.tiles.len() {
+        if rng.random_bool(model.preview_recall) {
+            player.memory[idx] = Some(st.tiles[idx].value.clone());
+        }
+    }
+    let mut outcome = GameOutcome::default();
+    for _ in 0..MAX_TURNS {
+        if st.tiles.iter().all(|tile| tile.status == TileStatus::Matched) {
+            outcome.finished = true;
+            break;
+        }
+        let mut picks: Vec<usize> = Vec::with_capacity(st.match_size);
+        let mut mismatch = false;
+        while picks.len() < st.match_size {
+            let Some(idx) = player.pick(&st, &picks, rng) else {
+                return outcome;
+            };
+            outcome.time_ms += model.think_ms;
+            player.see(&st, idx, rng);
+            picks.push(idx);
+            if st.tiles[idx].value != st.tiles[picks[0]].value {
+                mismatch = true;
+                break;
+            }
+        }
+        outcome.time_ms += FLIP_PHASE_MS;
+        if mismatch {
+            outcome.mismatches += 1;
+            outcome.time_ms += mismatch_pause_ms(&st, profile) + TURN_DOWN_MS;
+            if let Some(plan) = plan_penalty(&mut st, profile, picks[0]) {
+                outcome.penalties += 1;
+                outcome.time_ms += apply_penalty(&mut st, &mut player, plan, rng);
+            }
+        } else {
+            for &idx in &picks {
+                st.tiles[idx].status = TileStatus::Matched;
+                player.memory[idx] = None;
+            }
+            reset_penalty(&mut st, profile);
+        }
+        player.forget(rng);
+    }
+    outcome
+}
+
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[(sorted.len() - 1) * pct / 100]
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+pub fn report(games: u32, models: &[MemoryModel], seed: u64) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut lines = vec![
+        format!("Penalty simulation, {games} games per row, seed {seed}"),
+        format!(
+            "{:<15} {:<10} {:>8} {:>8} {:>8} {:>11} {:>10} {:>9}",
+            "profile", "model", "p10", "p50", "p90", "mismatches", "penalties", "finished"
+        ),
+    ];
+    for profile in Profile::ALL {
+        for model in models {
+            let outcomes: Vec<GameOutcome> = (0..games)
+                .map(|_| play_game(profile, model, &mut rng))
+                .collect();
+            let mut times: Vec<u64> = outcomes
+                .iter()
+                .filter(|outcome| outcome.finished)
+                .map(|outcome| outcome.time_ms)
+                .collect();
+            times.sort_unstable();
+            let mean = |value: fn(&GameOutcome) -> u32| {
+                outcomes.iter().map(|outcome| f64::from(value(outcome))).sum::<f64>() / f64::from(games)
+            };
+            lines.push(format!(
+                "{:<15} {:<10} {:>8} {:>8} {:>8} {:>11.1} {:>10.1} {:>8.0}%",
+                profile.name(),
+                model.name,
+                seconds(percentile(&times, 10)),
+                seconds(percentile(&times, 50)),
+                seconds(percentile(&times, 90)),
+                mean(|outcome| outcome.mismatches),
+                mean(|outcome| outcome.penalties),
+                times.len() as f64 * 100.0 / f64::from(games)
+            ));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_accepts_games_and_an_optional_recall() {
+        assert_eq!(parse_spec("500").map(|(games, models)| (games, models.len())), Some((500, 3)));
+        let (_, models) = parse_spec("20:0.7").expect("a recall should be accepted");
+        assert_eq!(models[0].recall, 0.7);
+        assert!(parse_spec("0").is_none());
+        assert!(parse_spec("20:3").is_none());
+    }
+
+    #[test]
+    fn a_perfect_memory_never_mismatches() {
+        let model = MemoryModel {
+            name: "perfect",
+            recall: 1.0,
+            forget: 0.0,
+            preview_recall: 1.0,
+            think_ms: 500,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for profile in Profile::ALL {
+            let outcome = play_game(profile, &model, &mut rng);
+            assert!(outcome.finished);
+            assert_eq!((outcome.mismatches, outcome.penalties), (0, 0));
+        }
+    }
+
+    #[test]
+    fn a_seed_replays_the_same_games() {
+        let play = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            Profile::ALL.map(|profile| play_game(profile, &MODELS[2], &mut rng))
+        };
+        assert_eq!(play(11), play(11));
+    }
+}
@@ -0,0 +1,156 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+const FRAME_SAMPLES: usize = 120;
+const REFRESH_EVERY_FRAMES: u32 = 15;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PendingKind {
+    Sequence,
+    Animation,
+}
+
+thread_local! {
+    static PENDING_SEQUENCES: Cell<u32> = const { Cell::new(0) };
+    static PENDING_ANIMATIONS: Cell<u32> = const { Cell::new(0) };
+    static LAST_SAVE: Cell<Option<Duration>> = const { Cell::new(None) };
+    static TICK: RefCell<Option<gtk::TickCallbackId>> = const { RefCell::new(None) };
+}
+
+fn pending_counter(kind: PendingKind, f: impl FnOnce(&Cell<u32>)) {
+    match kind {
+        PendingKind::Sequence => PENDING_SEQUENCES.with(f),
+        PendingKind::Animation => PENDING_ANIMATIONS.with(f),
+    }
+}
+
+// Counts as pending for as long as it is alive.
+pub struct Pending(PendingKind);
+
+impl Pending {
+    pub fn new(kind: PendingKind) -> Self {
+        pending_counter(kind, |count| count.set(count.get() + 1));
+        Self(kind)
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        pending_counter(self.0, |count| count.set(count.get().saturating_sub(1)));
+    }
+}
+
+pub fn record_save(duration: Duration) {
+    LAST_SAVE.with(|last| last.set(Some(duration)));
+}
+
+#[derive(Default)]
+pub struct FrameStats {
+    intervals_us: VecDeque<i64>,
+    last_frame_us: Option<i64>,
+}
+
+impl FrameStats {
+    pub fn push_frame(&mut self, frame_time_us: i64) {
+        if let Some(last) = self.last_frame_us.replace(frame_time_us) {
+            if self.intervals_us.len() == FRAME_SAMPLES {
+                self.intervals_us.pop_front();
+            }
+            self.intervals_us.push_back(frame_time_us - last);
+        }
+    }
+
+    pub fn average_ms(&self) -> f64 {
+        if self.intervals_us.is_empty() {
+            return 0.0;
+        }
+        let total: i64 = self.intervals_us.iter().sum();
+        total as f64 / self.intervals_us.len() as f64 / 1000.0
+    }
+
+    pub fn worst_ms(&self) -> f64 {
+        self.intervals_us.iter().copied().max().unwrap_or(0) as f64 / 1000.0
+    }
+}
+
+fn hud_text(frames: &FrameStats) -> String {
+    let last_save = LAST_SAVE
+        .with(Cell::get)
+        .map(|duration| format!("{:.1} ms", duration.as_secs_f64() * 1000.0))
+        .unwrap_or_else(|| "-".to_string());
+    format!(
+        "frame {:.1} ms · worst {:.1} ms\nsequences {} · animations {}\nlast save {}",
+        frames.average_ms(),
+        frames.worst_ms(),
+        PENDING_SEQUENCES.with(Cell::get),
+        PENDING_ANIMATIONS.with(Cell::get),
+        last_save
+    )
+}
+
+pub fn build_perf_hud() -> gtk::Label {
+    let label = gtk::Label::new(None);
+    label.add_css_class("perf-hud");
+    label.add_css_class("monospace");
+    label.set_halign(gtk::Align::End);
+    label.set_valign(gtk::Align::Start);
+    label.set_can_target(false);
+    label.set_visible(false);
+    label
+}
+
+// Measuring keeps the frame clock running, so it only ticks while shown.
+pub fn toggle(label: &gtk::Label) -> bool {
+    let visible = !label.is_visible();
+    label.set_visible(visible);
+    TICK.with(|tick| {
+        if let Some(previous) = tick.borrow_mut().take() {
+            previous.remove();
+        }
+        if !visible {
+            return;
+        }
+        let frames = RefCell::new(FrameStats::default());
+        let frames_since_refresh = Cell::new(0);
+        let id = label.add_tick_callback(move |label, clock| {
+            frames.borrow_mut().push_frame(clock.frame_time());
+            frames_since_refresh.set(frames_since_refresh.get() + 1);
+            if frames_since_refresh.get() >= REFRESH_EVERY_FRAMES {
+                frames_since_refresh.set(0);
+                label.set_text(&hud_text(&frames.borrow()));
+            }
+            gtk::glib::ControlFlow::Continue
+        });
+        tick.replace(Some(id));
+    });
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_stats_track_average_and_worst_interval() {
+        let mut frames = FrameStats::default();
+        for frame_time_us in [0, 16_000, 32_000, 82_000] {
+            frames.push_frame(frame_time_us);
+        }
+
+        assert_eq!(frames.worst_ms(), 50.0);
+        assert!((frames.average_ms() - 82.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pending_guards_count_while_alive() {
+        let before = PENDING_ANIMATIONS.with(Cell::get);
+        let guard = Pending::new(PendingKind::Animation);
+        assert_eq!(PENDING_ANIMATIONS.with(Cell::get), before + 1);
+        drop(guard);
+        assert_eq!(PENDING_ANIMATIONS.with(Cell::get), before);
+    }
+}
@@ -0,0 +1,271 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::glib;
+use libadwaita as adw;
+use adw::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{ntr_format, tr, tr_format};
+
+use super::habits;
+use super::paths;
+use super::scene::show_menu;
+use super::state::AppState;
+
+pub const KIDS_DAILY_LIMIT_KEY: &str = "kids-daily-limit";
+const PLAY_TIME_FILE_NAME: &str = "kids_play_time.json";
+const SAVE_EVERY_SECS: u32 = 10;
+const WARN_AT_SECS: [u32; 2] = [5 * 60, 60];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DailyLimit {
+    #[default]
+    Off,
+    Minutes(u32),
+}
+
+impl DailyLimit {
+    pub const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Minutes(15),
+        Self::Minutes(30),
+        Self::Minutes(45),
+        Self::Minutes(60),
+    ];
+
+    pub fn code(self) -> String {
+        match self {
+            Self::Off => "off".to_string(),
+            Self::Minutes(minutes) => format!("minutes-{minutes}"),
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|limit| limit.code() == code)
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Self::Off => tr("No limit"),
+            Self::Minutes(minutes) if minutes % 60 == 0 => ntr_format(
+                "{count} hour a day",
+                "{count} hours a day",
+                minutes / 60,
+                &[("count", &(minutes / 60))],
+            ),
+            Self::Minutes(minutes) => ntr_format(
+                "{count} minute a day",
+                "{count} minutes a day",
+                minutes,
+                &[("count", &minutes)],
+            ),
+        }
+    }
+
+    // Raising or lifting the limit needs a grown-up; lowering it does not.
+    pub fn is_looser_than(self, other: Self) -> bool {
+        match (self.secs(), other.secs()) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(new), Some(old)) => new > old,
+        }
+    }
+
+    fn secs(self) -> Option<u32> {
+        match self {
+            Self::Off => None,
+            Self::Minutes(minutes) => Some(minutes * 60),
+        }
+    }
+}
+
+// Kids mode play time for one day; a new day starts from zero.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayTime {
+    pub day: String,
+    pub secs: u32,
+}
+
+impl PlayTime {
+    fn roll_to(&mut self, today: &str) {
+        if self.day != today {
+            self.day = today.to_string();
+            self.secs = 0;
+        }
+    }
+
+    fn remaining(&self, limit: DailyLimit) -> Option<u32> {
+        limit.secs().map(|secs| secs.saturating_sub(self.secs))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitTick {
+    Nothing,
+    Warn(u32),
+    Reached,
+}
+
+fn play_time_path() -> PathBuf {
+    paths::config_dir().join(PLAY_TIME_FILE_NAME)
+}
+
+pub fn load_play_time() -> PlayTime {
+    let Ok(raw) = fs::read_to_string(play_time_path()) else {
+        return PlayTime::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        tracing::warn!("failed to parse kids play time: {err}");
+        PlayTime::default()
+    })
+}
+
+fn save_play_time(play: &PlayTime) -> io::Result<()> {
+    paths::write_atomic(&play_time_path(), &serde_json::to_string(play)?)
+}
+
+fn today() -> Option<String> {
+    glib::DateTime::now_local().ok().as_ref().and_then(habits::day_key)
+}
+
+pub fn is_locked(st: &mut AppState) -> bool {
+    let Some(today) = today() else {
+        return false;
+    };
+    st.kids_play.roll_to(&today);
+    st.kids_play.remaining(st.kids_daily_limit) == Some(0)
+}
+
+fn tick(play: &mut PlayTime, limit: DailyLimit, today: &str) -> LimitTick {
+    play.roll_to(today);
+    play.secs = play.secs.saturating_add(1);
+    match play.remaining(limit) {
+        None => LimitTick::Nothing,
+        Some(0) => LimitTick::Reached,
+        Some(left) if WARN_AT_SECS.contains(&left) => LimitTick::Warn(left / 60),
+        Some(_) => LimitTick::Nothing,
+    }
+}
+
+// Called once per second of the game clock; only Kids games count.
+pub fn note_second(st: &mut AppState) -> LimitTick {
+    if st.kids.is_none() || st.kids_daily_limit == DailyLimit::Off {
+        return LimitTick::Nothing;
+    }
+    let Some(today) = today() else {
+        return LimitTick::Nothing;
+    };
+    let result = tick(&mut st.kids_play, st.kids_daily_limit, &today);
+    if (result != LimitTick::Nothing || st.kids_play.secs.is_multiple_of(SAVE_EVERY_SECS))
+        && let Err(err) = save_play_time(&st.kids_play)
+    {
+        tracing::warn!("failed to save kids play time: {err}");
+    }
+    result
+}
+
+// Ends the game and keeps Kids mode closed until tomorrow; a grown-up can change the limit in Preferences.
+pub fn show_lockout(state: &Rc<RefCell<AppState>>) {
+    show_menu(state);
+    let dialog = adw::AlertDialog::builder()
+        .heading(tr("Play time is up"))
+        .body(tr("Today's Kids play time is used up. See you tomorrow!"))
+        .build();
+    dialog.add_response("ok", &tr("OK"));
+    dialog.set_default_response(Some("ok"));
+    dialog.set_close_response("ok");
+    let parent = state.borrow().view_stack.clone();
+    dialog.present(parent.as_ref());
+}
+
+fn is_answer(text: &str, a: u32, b: u32) -> bool {
+    text.trim().parse::<u32>().ok() == Some(a * b)
+}
+
+// A sum young players can't do yet stands in for a parental PIN.
+pub fn ask_grown_up(parent: &impl IsA<gtk::Widget>, on_passed: impl Fn() + 'static) {
+    let (a, b) = {
+        let mut rng = rand::rng();
+        (rng.random_range(12..20u32), rng.random_range(6..10u32))
+    };
+    let entry = gtk::Entry::builder()
+        .input_purpose(gtk::InputPurpose::Digits)
+        .activates_default(true)
+        .build();
+    let dialog = adw::AlertDialog::builder()
+        .heading(tr("Grown-ups only"))
+        .body(tr_format(
+            "To change the play time, type the answer to {a} × {b}.",
+            &[("a", &a), ("b", &b)],
+        ))
+        .extra_child(&entry)
+        .build();
+    dialog.add_response("cancel", &tr("Cancel"));
+    dialog.add_response("change", &tr("Change"));
+    dialog.set_default_response(Some("change"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(None, move |_, response| {
+        if response == "change" && is_answer(&entry.text(), a, b) {
+            on_passed();
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_codes_roundtrip() {
+        for limit in DailyLimit::ALL {
+            assert_eq!(DailyLimit::from_code(&limit.code()), limit);
+        }
+        assert_eq!(DailyLimit::from_code("minutes-7"), DailyLimit::Off);
+    }
+
+    #[test]
+    fn only_loosening_the_limit_needs_a_grown_up() {
+        let half_hour = DailyLimit::Minutes(30);
+        assert!(DailyLimit::Minutes(45).is_looser_than(half_hour));
+        assert!(DailyLimit::Off.is_looser_than(half_hour));
+        assert!(!DailyLimit::Minutes(15).is_looser_than(half_hour));
+        assert!(!half_hour.is_looser_than(DailyLimit::Off));
+        assert!(is_answer(" 126 ", 14, 9));
+        assert!(!is_answer("125", 14, 9));
+    }
+
+    #[test]
+    fn labels_show_the_configured_time() {
+        assert_eq!(DailyLimit::Minutes(45).label(), "45 minutes a day");
+        assert_eq!(DailyLimit::Minutes(60).label(), "1 hour a day");
+        assert_eq!(DailyLimit::Minutes(120).label(), "2 hours a day");
+    }
+
+    #[test]
+    fn ticks_warn_then_lock_and_reset_the_next_day() {
+        let limit = DailyLimit::Minutes(15);
+        let mut play = PlayTime {
+            day: "2026-05-01".to_string(),
+            secs: 10 * 60 - 1,
+        };
+        assert_eq!(tick(&mut play, limit, "2026-05-01"), LimitTick::Warn(5));
+        assert_eq!(tick(&mut play, limit, "2026-05-01"), LimitTick::Nothing);
+
+        play.secs = 15 * 60 - 1;
+        assert_eq!(tick(&mut play, limit, "2026-05-01"), LimitTick::Reached);
+        assert_eq!(play.remaining(limit), Some(0));
+
+        assert_eq!(tick(&mut play, limit, "2026-05-02"), LimitTick::Nothing);
+        assert_eq!(play.secs, 1);
+    }
+}
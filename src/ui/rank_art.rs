@@ -0,0 +1,133 @@
+use super::resources::{self, VICTORY_DIR};
+use super::state::{AppState, PlayerRecords, Rank};
+
+pub const RANK_ART_KEY: &str = "rank-art";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArtPack {
+    #[default]
+    Classic,
+    Ink,
+    Aurora,
+}
+
+impl ArtPack {
+    pub const ALL: [ArtPack; 3] = [ArtPack::Classic, ArtPack::Ink, ArtPack::Aurora];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ArtPack::Classic => "classic",
+            ArtPack::Ink => "ink",
+            ArtPack::Aurora => "aurora",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|pack| pack.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ArtPack::Classic => "Classic",
+            ArtPack::Ink => "Ink",
+            ArtPack::Aurora => "Aurora",
+        }
+    }
+
+    pub fn requirement(self) -> Option<&'static str> {
+        match self {
+            ArtPack::Classic => None,
+            ArtPack::Ink => Some("Earn an S rank on any Classic board"),
+            ArtPack::Aurora => Some("Earn an S rank on Classic Expert"),
+        }
+    }
+
+    // Only unassisted Classic runs count towards unlocks.
+    pub fn is_unlocked(self, records: &PlayerRecords) -> bool {
+        let flawless = |min_level: u8| {
+            records.classic.iter().any(|entry| {
+                entry.level >= min_level && entry.rank == Rank::S && entry.is_ranked()
+            })
+        };
+        match self {
+            ArtPack::Classic => true,
+            ArtPack::Ink => flawless(1),
+            ArtPack::Aurora => flawless(4),
+        }
+    }
+
+    pub fn resource_path(self, rank: Rank) -> String {
+        let file = match rank {
+            Rank::S => "rank-s.svg",
+            Rank::A => "rank-a.svg",
+            Rank::B => "rank-b.svg",
+            Rank::C => "rank-c.svg",
+        };
+        match self {
+            ArtPack::Classic => format!("{VICTORY_DIR}/{file}"),
+            pack => format!("{VICTORY_DIR}/{}/{file}", pack.code()),
+        }
+    }
+
+    fn is_installed(self) -> bool {
+        Rank::ALL
+            .into_iter()
+            .all(|rank| resources::exists(&self.resource_path(rank)))
+    }
+}
+
+pub fn unlocked_packs(records: &PlayerRecords) -> Vec<ArtPack> {
+    ArtPack::ALL
+        .into_iter()
+        .filter(|pack| pack.is_unlocked(records))
+        .collect()
+}
+
+pub fn all_resource_paths() -> impl Iterator<Item = String> {
+    ArtPack::ALL
+        .into_iter()
+        .flat_map(|pack| Rank::ALL.map(|rank| pack.resource_path(rank)))
+}
+
+// A pack that is locked again (records cleared) or missing from the bundle falls back to Classic.
+pub fn rank_resource_path(st: &AppState, rank: Rank) -> String {
+    let pack = Some(st.rank_art)
+        .filter(|pack| pack.is_unlocked(&st.records) && pack.is_installed())
+        .unwrap_or_default();
+    pack.resource_path(rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::ModeRecord;
+
+    fn classic(level: u8, rank: Rank, modifiers: &str) -> ModeRecord {
+        ModeRecord {
+            level,
+            rank,
+            modifiers: modifiers.to_string(),
+            ..ModeRecord::default()
+        }
+    }
+
+    #[test]
+    fn packs_unlock_from_unassisted_classic_ranks() {
+        let mut records = PlayerRecords::default();
+        assert_eq!(unlocked_packs(&records), vec![ArtPack::Classic]);
+
+        records.classic.push(classic(4, Rank::S, "peek"));
+        records.classic.push(classic(2, Rank::S, ""));
+        assert_eq!(unlocked_packs(&records), vec![ArtPack::Classic, ArtPack::Ink]);
+
+        records.classic.push(classic(4, Rank::S, ""));
+        assert_eq!(unlocked_packs(&records).len(), ArtPack::ALL.len());
+        assert_eq!(
+            ArtPack::Aurora.resource_path(Rank::B),
+            "/io/github/basshift/Recall/victory/aurora/rank-b.svg"
+        );
+    }
+}
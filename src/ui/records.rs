@@ -1,7 +1,9 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
 use std::io;
 use std::rc::Rc;
-use std::{fs, path::PathBuf};
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use gtk4 as gtk;
 use gtk4::glib;
@@ -10,23 +12,56 @@ use libadwaita as adw;
 use adw::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::i18n::tr;
-
-use super::infinite;
-use super::state::{AppState, Difficulty, InfiniteRecord, ModeRecord, PlayerRecords, Rank};
-
+use crate::i18n::{
+    format_decimal, format_duration, format_percent, ntr_format, tr, tr_format, tr_noop,
+};
+
+use super::challenge;
+use super::custom::CustomPreset;
+use super::defeat::{self, DefeatReason};
+use super::friends::{self, Friend, ScoreKey};
+use super::habits::{self, HabitLog, QuitPoint};
+use super::heatmap::{self, MistakeHeatmap};
+use super::infinite::{self, Pacing};
+use super::layout::ReshuffleStrategy;
+use super::leaderboard;
+use super::nback;
+use super::next_goal::{self, Goal};
+use super::par_time;
+use super::paths;
+use super::records_filter;
+use super::records_trash::{self, TrashedRecords};
+use super::resources;
+use super::settings;
+use super::state::{
+    AppState, CustomRecord, Difficulty, GauntletRecord, IncompleteRecord, InfiniteRecord, ModeRecord,
+    NBackRecord, PatternRecord, PlayerRecords, Rank, SpeedrunRecord, SuddenDeathRecord,
+    TournamentRecord, WeeklyRecord,
+};
+use super::speedrun;
+use super::symbol_stats::{self, SymbolStats};
+use super::tournament;
+use super::warmup;
+use super::weekly_summary::{self, WeekSummary};
+
+const SYMBOL_ROWS_SHOWN: usize = 8;
+const HEATMAP_BOARDS_SHOWN: usize = 4;
+const HEATMAP_CELL_SIZE: i32 = 22;
 const RECORDS_FILE_NAME: &str = "records.json";
 const LEGACY_RECORDS_FILE_NAME: &str = "records.v1";
 const MODE_HISTORY_LIMIT: usize = 200;
 const INFINITE_HISTORY_LIMIT: usize = 200;
-
-fn format_mm_ss(total_secs: u32) -> String {
-    let mins = total_secs / 60;
-    let secs = total_secs % 60;
-    format!("{:02}:{:02}", mins, secs)
-}
-
-fn classic_level_name(level: u8) -> &'static str {
+const WEEKLY_HISTORY_LIMIT: usize = 200;
+const TOURNAMENT_HISTORY_LIMIT: usize = 200;
+const GAUNTLET_HISTORY_LIMIT: usize = 200;
+const SPEEDRUN_HISTORY_LIMIT: usize = 200;
+const INCOMPLETE_HISTORY_LIMIT: usize = 200;
+const SUDDEN_DEATH_HISTORY_LIMIT: usize = 200;
+const PATTERN_HISTORY_LIMIT: usize = 200;
+const NBACK_HISTORY_LIMIT: usize = 200;
+const CUSTOM_HISTORY_LIMIT: usize = 200;
+
+pub(super) fn classic_level_name(level: u8) -> &'static str {
     match level.clamp(1, 4) {
         1 => "Easy",
         2 => "Medium",
@@ -35,37 +70,85 @@ fn classic_level_name(level: u8) -> &'static str {
     }
 }
 
+// Minimum precision for each rank, per classic level from Easy to Expert.
+const RANK_TABLE: [(Rank, [u8; 4]); 3] = [
+    (Rank::S, [100, 100, 100, 100]),
+    (Rank::A, [85, 90, 88, 85]),
+    (Rank::B, [70, 80, 75, 70]),
+];
+
+fn rank_threshold(rank: Rank, level: u8) -> Option<u8> {
+    RANK_TABLE
+        .iter()
+        .find(|(entry, _)| *entry == rank)
+        .map(|(_, thresholds)| thresholds[usize::from(level.clamp(1, 4) - 1)])
+}
+
 fn rank_for_precision(level: u8, precision_pct: u8) -> Rank {
-    if precision_pct >= 100 {
-        return Rank::S;
-    }
-    let a_threshold = match level.clamp(1, 4) {
-        1 => 85,
-        2 => 90,
-        3 => 88,
-        _ => 85,
-    };
-    let b_threshold = match level.clamp(1, 4) {
-        1 => 70,
-        2 => 80,
-        3 => 75,
-        _ => 70,
-    };
-    if precision_pct >= a_threshold {
-        Rank::A
-    } else if precision_pct >= b_threshold {
-        Rank::B
+    RANK_TABLE
+        .iter()
+        .map(|(rank, _)| *rank)
+        .find(|rank| rank_threshold(*rank, level).is_some_and(|min| precision_pct >= min))
+        .unwrap_or(Rank::C)
+}
+
+pub(super) fn precision_for(matches: u32, mismatches: u32) -> u8 {
+    let attempts = matches.saturating_add(mismatches);
+    if attempts == 0 {
+        100
     } else {
-        Rank::C
+        ((matches as f64 / attempts as f64) * 100.0).round() as u8
+    }
+}
+
+// The next rank up and the most mismatches it would have allowed on this board.
+fn next_rank_requirement(level: u8, matches: u32, precision_pct: u8) -> Option<(Rank, u8, u32)> {
+    let current = rank_for_precision(level, precision_pct);
+    let (next, min_pct) = RANK_TABLE
+        .iter()
+        .rev()
+        .filter(|(rank, _)| *rank > current)
+        .find_map(|(rank, _)| Some((*rank, rank_threshold(*rank, level)?)))?;
+    let mut allowed = 0;
+    while precision_for(matches, allowed + 1) >= min_pct {
+        allowed += 1;
     }
+    Some((next, min_pct, allowed))
+}
+
+fn rank_requirement_text(level: u8, matches: u32, mismatches: u32, precision_pct: u8) -> String {
+    let level_name = tr(classic_level_name(level));
+    let Some((next, min_pct, allowed)) = next_rank_requirement(level, matches, precision_pct) else {
+        return format!(
+            "{}\n{}",
+            tr_format("Top rank on {level}", &[("level", &level_name)]),
+            tr("A flawless board, with no mismatches at all.")
+        );
+    };
+    format!(
+        "{}\n{}\n{}",
+        tr_format(
+            "Rank {rank} on {level}: {precision} precision",
+            &[
+                ("rank", &next.as_str()),
+                ("level", &level_name),
+                ("precision", &format_percent(min_pct)),
+            ],
+        ),
+        tr_format(
+            "Mismatches allowed: {allowed} (you made {made})",
+            &[("allowed", &allowed), ("made", &mismatches)],
+        ),
+        tr("Time does not change the rank.")
+    )
 }
 
 fn records_path() -> Option<PathBuf> {
-    Some(glib::user_config_dir().join("recall").join(RECORDS_FILE_NAME))
+    Some(paths::config_dir().join(RECORDS_FILE_NAME))
 }
 
 fn legacy_records_path() -> Option<PathBuf> {
-    Some(glib::user_config_dir().join("recall").join(LEGACY_RECORDS_FILE_NAME))
+    Some(paths::config_dir().join(LEGACY_RECORDS_FILE_NAME))
 }
 
 fn parse_mode_record(raw: &str) -> Option<ModeRecord> {
@@ -75,7 +158,8 @@ fn parse_mode_record(raw: &str) -> Option<ModeRecord> {
         rank: Rank::from_str(parts.next()?)?,
         time_secs: parts.next()?.parse().ok()?,
         precision_pct: parts.next()?.parse().ok()?,
-        date_label: parts.next()?.to_string(),
+        recorded_at: parse_date_label(parts.next()?),
+        modifiers: String::new(),
     })
 }
 
@@ -86,7 +170,10 @@ fn parse_infinite_record(raw: &str) -> Option<InfiniteRecord> {
         segment_level: parts.next()?.parse().ok()?,
         segment_survival: parts.next()?.parse().ok()?,
         time_secs: parts.next()?.parse().ok()?,
-        date_label: parts.next()?.to_string(),
+        recorded_at: parse_date_label(parts.next()?),
+        pacing: Pacing::Standard,
+        trio: false,
+        modifiers: String::new(),
     })
 }
 
@@ -97,7 +184,8 @@ fn parse_legacy_mode_best(raw: &str) -> Option<ModeRecord> {
         rank: Rank::from_str(parts.next()?)?,
         time_secs: parts.next()?.parse().ok()?,
         precision_pct: parts.next()?.parse().ok()?,
-        date_label: String::new(),
+        recorded_at: None,
+        modifiers: String::new(),
     })
 }
 
@@ -108,18 +196,39 @@ fn parse_legacy_infinite_best(raw: &str) -> Option<InfiniteRecord> {
         segment_level: parts.next()?.parse().ok()?,
         segment_survival: parts.next()?.parse().ok()?,
         time_secs: parts.next()?.parse().ok()?,
-        date_label: String::new(),
+        recorded_at: None,
+        pacing: Pacing::Standard,
+        trio: false,
+        modifiers: String::new(),
     })
 }
 
 #[derive(Default, Deserialize, Serialize)]
-struct RecordsFile {
+pub(super) struct RecordsFile {
     #[serde(default)]
     classic: Vec<ModeRecordWire>,
     #[serde(default, alias = "tri")]
     trio: Vec<ModeRecordWire>,
     #[serde(default)]
     infinite: Vec<InfiniteRecordWire>,
+    #[serde(default)]
+    weekly: Vec<WeeklyRecordWire>,
+    #[serde(default)]
+    tournament: Vec<TournamentRecordWire>,
+    #[serde(default)]
+    gauntlet: Vec<GauntletRecordWire>,
+    #[serde(default)]
+    speedrun: Vec<SpeedrunRecordWire>,
+    #[serde(default)]
+    incomplete: Vec<IncompleteRecordWire>,
+    #[serde(default)]
+    sudden_death: Vec<SuddenDeathRecordWire>,
+    #[serde(default)]
+    pattern: Vec<PatternRecordWire>,
+    #[serde(default)]
+    nback: Vec<NBackRecordWire>,
+    #[serde(default)]
+    custom: Vec<CustomRecordWire>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -128,7 +237,12 @@ struct ModeRecordWire {
     time_secs: u32,
     precision_pct: u8,
     rank: Rank,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
     date_label: String,
+    #[serde(default)]
+    modifiers: String,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -137,6 +251,126 @@ struct InfiniteRecordWire {
     segment_level: u8,
     segment_survival: u32,
     time_secs: u32,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+    #[serde(default)]
+    pacing: String,
+    #[serde(default)]
+    trio: bool,
+    #[serde(default)]
+    modifiers: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct WeeklyRecordWire {
+    week: String,
+    time_secs: u32,
+    precision_pct: u8,
+    rank: Rank,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct TournamentRecordWire {
+    total_score: u32,
+    total_secs: u32,
+    precision_pct: u8,
+    #[serde(default)]
+    board_scores: Vec<u32>,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct GauntletRecordWire {
+    time_secs: u32,
+    precision_pct: u8,
+    rank: Rank,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SpeedrunRecordWire {
+    level: u8,
+    time_ms: u64,
+    #[serde(default)]
+    splits_ms: Vec<u64>,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct IncompleteRecordWire {
+    mode: String,
+    level: u8,
+    reason: String,
+    pairs_cleared: u32,
+    pairs_total: u32,
+    time_secs: u32,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SuddenDeathRecordWire {
+    level: u8,
+    pairs_cleared: u32,
+    pairs_total: u32,
+    time_secs: u32,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct CustomRecordWire {
+    name: String,
+    code: String,
+    time_secs: u32,
+    precision_pct: u8,
+    rank: Rank,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct NBackRecordWire {
+    n: u8,
+    position_pct: u8,
+    symbol_pct: u8,
+    trials: u32,
+    time_secs: u32,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
+    date_label: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct PatternRecordWire {
+    accuracy_pct: u8,
+    longest_pattern: u32,
+    time_secs: u32,
+    #[serde(default)]
+    recorded_at: Option<i64>,
+    #[serde(default)]
     date_label: String,
 }
 
@@ -147,7 +381,10 @@ impl From<ModeRecordWire> for ModeRecord {
             time_secs: value.time_secs,
             precision_pct: value.precision_pct,
             rank: value.rank,
-            date_label: value.date_label,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+            modifiers: value.modifiers,
         }
     }
 }
@@ -159,7 +396,9 @@ impl From<&ModeRecord> for ModeRecordWire {
             time_secs: value.time_secs,
             precision_pct: value.precision_pct,
             rank: value.rank,
-            date_label: value.date_label.clone(),
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+            modifiers: value.modifiers.clone(),
         }
     }
 }
@@ -171,7 +410,12 @@ impl From<InfiniteRecordWire> for InfiniteRecord {
             segment_level: value.segment_level,
             segment_survival: value.segment_survival,
             time_secs: value.time_secs,
-            date_label: value.date_label,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+            pacing: Pacing::from_code(&value.pacing),
+            trio: value.trio,
+            modifiers: value.modifiers,
         }
     }
 }
@@ -183,7 +427,256 @@ impl From<&InfiniteRecord> for InfiniteRecordWire {
             segment_level: value.segment_level,
             segment_survival: value.segment_survival,
             time_secs: value.time_secs,
-            date_label: value.date_label.clone(),
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+            pacing: value.pacing.code().to_string(),
+            trio: value.trio,
+            modifiers: value.modifiers.clone(),
+        }
+    }
+}
+
+impl From<WeeklyRecordWire> for WeeklyRecord {
+    fn from(value: WeeklyRecordWire) -> Self {
+        Self {
+            week: value.week,
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&WeeklyRecord> for WeeklyRecordWire {
+    fn from(value: &WeeklyRecord) -> Self {
+        Self {
+            week: value.week.clone(),
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<TournamentRecordWire> for TournamentRecord {
+    fn from(value: TournamentRecordWire) -> Self {
+        Self {
+            total_score: value.total_score,
+            total_secs: value.total_secs,
+            precision_pct: value.precision_pct,
+            board_scores: value.board_scores,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&TournamentRecord> for TournamentRecordWire {
+    fn from(value: &TournamentRecord) -> Self {
+        Self {
+            total_score: value.total_score,
+            total_secs: value.total_secs,
+            precision_pct: value.precision_pct,
+            board_scores: value.board_scores.clone(),
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<GauntletRecordWire> for GauntletRecord {
+    fn from(value: GauntletRecordWire) -> Self {
+        Self {
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&GauntletRecord> for GauntletRecordWire {
+    fn from(value: &GauntletRecord) -> Self {
+        Self {
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<SpeedrunRecordWire> for SpeedrunRecord {
+    fn from(value: SpeedrunRecordWire) -> Self {
+        Self {
+            level: value.level,
+            time_ms: value.time_ms,
+            splits_ms: value.splits_ms,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&SpeedrunRecord> for SpeedrunRecordWire {
+    fn from(value: &SpeedrunRecord) -> Self {
+        Self {
+            level: value.level,
+            time_ms: value.time_ms,
+            splits_ms: value.splits_ms.clone(),
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<IncompleteRecordWire> for IncompleteRecord {
+    fn from(value: IncompleteRecordWire) -> Self {
+        Self {
+            mode: value.mode,
+            level: value.level,
+            reason: value.reason,
+            pairs_cleared: value.pairs_cleared,
+            pairs_total: value.pairs_total,
+            time_secs: value.time_secs,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&IncompleteRecord> for IncompleteRecordWire {
+    fn from(value: &IncompleteRecord) -> Self {
+        Self {
+            mode: value.mode.clone(),
+            level: value.level,
+            reason: value.reason.clone(),
+            pairs_cleared: value.pairs_cleared,
+            pairs_total: value.pairs_total,
+            time_secs: value.time_secs,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<SuddenDeathRecordWire> for SuddenDeathRecord {
+    fn from(value: SuddenDeathRecordWire) -> Self {
+        Self {
+            level: value.level,
+            pairs_cleared: value.pairs_cleared,
+            pairs_total: value.pairs_total,
+            time_secs: value.time_secs,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&SuddenDeathRecord> for SuddenDeathRecordWire {
+    fn from(value: &SuddenDeathRecord) -> Self {
+        Self {
+            level: value.level,
+            pairs_cleared: value.pairs_cleared,
+            pairs_total: value.pairs_total,
+            time_secs: value.time_secs,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<CustomRecordWire> for CustomRecord {
+    fn from(value: CustomRecordWire) -> Self {
+        Self {
+            name: value.name,
+            code: value.code,
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&CustomRecord> for CustomRecordWire {
+    fn from(value: &CustomRecord) -> Self {
+        Self {
+            name: value.name.clone(),
+            code: value.code.clone(),
+            time_secs: value.time_secs,
+            precision_pct: value.precision_pct,
+            rank: value.rank,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<PatternRecordWire> for PatternRecord {
+    fn from(value: PatternRecordWire) -> Self {
+        Self {
+            accuracy_pct: value.accuracy_pct,
+            longest_pattern: value.longest_pattern,
+            time_secs: value.time_secs,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&PatternRecord> for PatternRecordWire {
+    fn from(value: &PatternRecord) -> Self {
+        Self {
+            accuracy_pct: value.accuracy_pct,
+            longest_pattern: value.longest_pattern,
+            time_secs: value.time_secs,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
+        }
+    }
+}
+
+impl From<NBackRecordWire> for NBackRecord {
+    fn from(value: NBackRecordWire) -> Self {
+        Self {
+            n: value.n,
+            position_pct: value.position_pct,
+            symbol_pct: value.symbol_pct,
+            trials: value.trials,
+            time_secs: value.time_secs,
+            recorded_at: value
+                .recorded_at
+                .or_else(|| parse_date_label(&value.date_label)),
+        }
+    }
+}
+
+impl From<&NBackRecord> for NBackRecordWire {
+    fn from(value: &NBackRecord) -> Self {
+        Self {
+            n: value.n,
+            position_pct: value.position_pct,
+            symbol_pct: value.symbol_pct,
+            trials: value.trials,
+            time_secs: value.time_secs,
+            recorded_at: value.recorded_at,
+            date_label: legacy_date_label(value.recorded_at),
         }
     }
 }
@@ -198,6 +691,27 @@ impl From<RecordsFile> for PlayerRecords {
                 .into_iter()
                 .map(InfiniteRecord::from)
                 .collect(),
+            weekly: value.weekly.into_iter().map(WeeklyRecord::from).collect(),
+            tournament: value
+                .tournament
+                .into_iter()
+                .map(TournamentRecord::from)
+                .collect(),
+            gauntlet: value.gauntlet.into_iter().map(GauntletRecord::from).collect(),
+            speedrun: value.speedrun.into_iter().map(SpeedrunRecord::from).collect(),
+            incomplete: value
+                .incomplete
+                .into_iter()
+                .map(IncompleteRecord::from)
+                .collect(),
+            sudden_death: value
+                .sudden_death
+                .into_iter()
+                .map(SuddenDeathRecord::from)
+                .collect(),
+            pattern: value.pattern.into_iter().map(PatternRecord::from).collect(),
+            nback: value.nback.into_iter().map(NBackRecord::from).collect(),
+            custom: value.custom.into_iter().map(CustomRecord::from).collect(),
         }
     }
 }
@@ -212,6 +726,27 @@ impl From<&PlayerRecords> for RecordsFile {
                 .iter()
                 .map(InfiniteRecordWire::from)
                 .collect(),
+            weekly: value.weekly.iter().map(WeeklyRecordWire::from).collect(),
+            tournament: value
+                .tournament
+                .iter()
+                .map(TournamentRecordWire::from)
+                .collect(),
+            gauntlet: value.gauntlet.iter().map(GauntletRecordWire::from).collect(),
+            speedrun: value.speedrun.iter().map(SpeedrunRecordWire::from).collect(),
+            incomplete: value
+                .incomplete
+                .iter()
+                .map(IncompleteRecordWire::from)
+                .collect(),
+            sudden_death: value
+                .sudden_death
+                .iter()
+                .map(SuddenDeathRecordWire::from)
+                .collect(),
+            pattern: value.pattern.iter().map(PatternRecordWire::from).collect(),
+            nback: value.nback.iter().map(NBackRecordWire::from).collect(),
+            custom: value.custom.iter().map(CustomRecordWire::from).collect(),
         }
     }
 }
@@ -234,27 +769,87 @@ fn rank_suffix_label(text: &str) -> gtk::Label {
     label
 }
 
-fn now_date_label() -> String {
-    if let Ok(dt) = glib::DateTime::now_local()
-        && let Ok(text) = dt.format("%Y-%m-%d %H:%M")
-    {
-        return text.to_string();
+fn modifier_name(code: &str) -> String {
+    match code {
+        "peek" => tr("Hold to peek"),
+        "tags" => tr("Color tags"),
+        "hint" => tr("Hints"),
+        "grace" => tr("First mismatch grace"),
+        "spread" | "move-seen" => tr(ReshuffleStrategy::from_code(code).label()),
+        _ => tr("Assists"),
     }
-    tr("Unknown date")
 }
 
-fn load_legacy_records(raw: &str) -> PlayerRecords {
-    let mut records = PlayerRecords::default();
-    for line in raw.lines() {
-        if let Some(rest) = line.strip_prefix("classic_entry=") {
-            if let Some(entry) = parse_mode_record(rest) {
-                records.classic.push(entry);
-            }
-        } else if let Some(rest) = line.strip_prefix("trio_entry=") {
-            if let Some(entry) = parse_mode_record(rest) {
-                records.trio.push(entry);
-            }
-        } else if let Some(rest) = line.strip_prefix("tri_entry=") {
+// Marks runs that had assists on, so they never pass for pure ones.
+fn modifiers_suffix_label(modifiers: &str) -> Option<gtk::Label> {
+    if modifiers.is_empty() {
+        return None;
+    }
+    let label = gtk::Label::new(Some(&tr("Assisted")));
+    label.add_css_class("score-row-modifier");
+    label.add_css_class("caption");
+    label.add_css_class("dim-label");
+    label.set_halign(gtk::Align::End);
+    label.set_valign(gtk::Align::Center);
+    let names = modifiers
+        .split(',')
+        .map(modifier_name)
+        .collect::<Vec<String>>();
+    label.set_tooltip_text(Some(&names.join(", ")));
+    Some(label)
+}
+
+fn now_timestamp() -> Option<i64> {
+    glib::DateTime::now_local().ok().map(|dt| dt.to_unix())
+}
+
+// Records used to store only a "YYYY-MM-DD HH:MM" local-time label.
+pub(super) fn parse_date_label(label: &str) -> Option<i64> {
+    let (date, time) = label.trim().split_once(' ')?;
+    let mut date = date.split('-');
+    let (hour, minute) = time.split_once(':')?;
+    let dt = glib::DateTime::from_local(
+        date.next()?.parse().ok()?,
+        date.next()?.parse().ok()?,
+        date.next()?.parse().ok()?,
+        hour.parse().ok()?,
+        minute.parse().ok()?,
+        0.0,
+    )
+    .ok()?;
+    Some(dt.to_unix())
+}
+
+// Still written next to the timestamp so older builds can read the file.
+fn legacy_date_label(recorded_at: Option<i64>) -> String {
+    recorded_at
+        .and_then(|ts| glib::DateTime::from_unix_local(ts).ok())
+        .and_then(|dt| dt.format("%Y-%m-%d %H:%M").ok())
+        .map(|text| text.to_string())
+        .unwrap_or_default()
+}
+
+// Both halves follow the locale, including a 12-hour clock where that is usual.
+pub(super) fn format_record_date(recorded_at: Option<i64>) -> String {
+    recorded_at
+        .and_then(|ts| glib::DateTime::from_unix_local(ts).ok())
+        .and_then(|dt| dt.format("%x %X").ok())
+        .map(|text| text.to_string())
+        .unwrap_or_else(|| tr("Unknown date"))
+}
+
+fn load_legacy_records(raw: &str) -> PlayerRecords {
+    let mut records = PlayerRecords::default();
+    for line in raw.lines() {
+        if let Some(rest) = line.strip_prefix("classic_entry=") {
+            if let Some(entry) = parse_mode_record(rest) {
+                records.classic.push(entry);
+            }
+        } else if let Some(rest) = line.strip_prefix("trio_entry=") {
+            if let Some(entry) = parse_mode_record(rest) {
+                records.trio.push(entry);
+            }
+        } else if let Some(rest) = line.strip_prefix("tri_entry=") {
             if let Some(entry) = parse_mode_record(rest) {
                 records.trio.push(entry);
             }
@@ -300,13 +895,13 @@ pub fn load_records() -> PlayerRecords {
         {
             let records = load_legacy_records(&legacy_raw);
             if let Err(err) = migrate_legacy_records(&records) {
-                eprintln!("warning: failed to migrate legacy records: {err}");
+                tracing::warn!("failed to migrate legacy records: {err}");
             }
             return records;
         }
 
-        eprintln!(
-            "warning: failed to parse records file; keeping current file untouched: {}",
+        tracing::warn!(
+            "failed to parse records file; keeping current file untouched: {}",
             path.display()
         );
         return PlayerRecords::default();
@@ -315,7 +910,7 @@ pub fn load_records() -> PlayerRecords {
     {
         let records = load_legacy_records(&raw);
         if let Err(err) = migrate_legacy_records(&records) {
-            eprintln!("warning: failed to migrate legacy records: {err}");
+            tracing::warn!("failed to migrate legacy records: {err}");
         }
         return records;
     }
@@ -348,6 +943,26 @@ fn migrate_legacy_records(records: &PlayerRecords) -> io::Result<()> {
     Ok(())
 }
 
+// How two runs on the same board compare in the best-runs lists: rank, then precision, then time.
+fn run_key(rank: Rank, precision_pct: u8, time_secs: u32) -> (Rank, u8, Reverse<u32>) {
+    (rank, precision_pct, Reverse(time_secs))
+}
+
+// The run `sort_mode_records` puts first for the level.
+fn personal_best(records: &[ModeRecord], level: u8) -> Option<&ModeRecord> {
+    records
+        .iter()
+        .filter(|entry| entry.level == level && entry.is_ranked())
+        .max_by_key(|entry| run_key(entry.rank, entry.precision_pct, entry.time_secs))
+}
+
+fn beats_personal_best(records: &[ModeRecord], candidate: &ModeRecord) -> bool {
+    personal_best(records, candidate.level).is_some_and(|best| {
+        run_key(candidate.rank, candidate.precision_pct, candidate.time_secs)
+            > run_key(best.rank, best.precision_pct, best.time_secs)
+    })
+}
+
 fn sort_mode_records(entries: &mut [ModeRecord]) {
     entries.sort_by(|a, b| {
         b.level
@@ -358,25 +973,177 @@ fn sort_mode_records(entries: &mut [ModeRecord]) {
     });
 }
 
-fn top_infinite_records(records: &[InfiniteRecord], limit: usize) -> Vec<InfiniteRecord> {
-    let mut entries = records.to_vec();
+// Compact best-run lines for the current mode, used by the wide-window stats panel.
+pub(super) fn top_record_lines(st: &AppState, limit: usize) -> Vec<String> {
+    if infinite::is_infinite(st.difficulty) {
+        let entries =
+            top_infinite_records(&st.records.infinite, st.infinite_pacing, st.infinite_trio(), limit);
+        return entries
+            .iter()
+            .map(|entry| {
+                tr_format(
+                    "Round {round} · {time}",
+                    &[("round", &entry.round), ("time", &format_duration(entry.time_secs))],
+                )
+            })
+            .collect();
+    }
+    let (records, level) = if st.difficulty == Difficulty::Trio {
+        (&st.records.trio, st.trio_level)
+    } else if st.kids.is_none()
+        && st.challenge.is_none()
+        && !st.in_scored_series()
+        && st.hotseat.is_none()
+    {
+        (&st.records.classic, speedrun::classic_level(st))
+    } else {
+        return Vec::new();
+    };
+    let mut entries = records
+        .iter()
+        .filter(|entry| entry.level == level && entry.is_ranked())
+        .cloned()
+        .collect::<Vec<ModeRecord>>();
+    sort_mode_records(&mut entries);
+    entries
+        .iter()
+        .take(limit)
+        .map(|entry| {
+            format!(
+                "{} · {} · {}",
+                format_duration(entry.time_secs),
+                format_percent(entry.precision_pct),
+                entry.rank.as_str()
+            )
+        })
+        .collect()
+}
+
+// Rounds only compare within one pacing, and Trio boards are a separate ladder.
+fn top_infinite_records(
+    records: &[InfiniteRecord],
+    pacing: Pacing,
+    trio: bool,
+    limit: usize,
+) -> Vec<InfiniteRecord> {
+    let mut entries = records
+        .iter()
+        .filter(|entry| entry.is_ranked() && entry.pacing == pacing && entry.trio == trio)
+        .cloned()
+        .collect::<Vec<InfiniteRecord>>();
     entries.sort_by(|a, b| b.round.cmp(&a.round).then_with(|| a.time_secs.cmp(&b.time_secs)));
     entries.truncate(limit);
     entries
 }
 
-fn recent_mode_records(records: &[ModeRecord], limit: usize) -> Vec<ModeRecord> {
-    records.iter().rev().take(limit).cloned().collect()
+fn top_weekly_records(records: &[WeeklyRecord], week: &str, limit: usize) -> Vec<WeeklyRecord> {
+    let mut entries = records
+        .iter()
+        .filter(|entry| entry.week == week)
+        .cloned()
+        .collect::<Vec<WeeklyRecord>>();
+    entries.sort_by(|a, b| {
+        b.rank
+            .cmp(&a.rank)
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+            .then_with(|| b.precision_pct.cmp(&a.precision_pct))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_tournament_records(records: &[TournamentRecord], limit: usize) -> Vec<TournamentRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.total_score
+            .cmp(&a.total_score)
+            .then_with(|| a.total_secs.cmp(&b.total_secs))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_gauntlet_records(records: &[GauntletRecord], limit: usize) -> Vec<GauntletRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.rank
+            .cmp(&a.rank)
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+            .then_with(|| b.precision_pct.cmp(&a.precision_pct))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_sudden_death_records(records: &[SuddenDeathRecord], limit: usize) -> Vec<SuddenDeathRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.pairs_cleared
+            .cmp(&a.pairs_cleared)
+            .then_with(|| b.level.cmp(&a.level))
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_custom_records(records: &[CustomRecord], limit: usize) -> Vec<CustomRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.rank
+            .cmp(&a.rank)
+            .then_with(|| b.precision_pct.cmp(&a.precision_pct))
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_pattern_records(records: &[PatternRecord], limit: usize) -> Vec<PatternRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.accuracy_pct
+            .cmp(&a.accuracy_pct)
+            .then_with(|| b.longest_pattern.cmp(&a.longest_pattern))
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn top_nback_records(records: &[NBackRecord], limit: usize) -> Vec<NBackRecord> {
+    let mut entries = records.to_vec();
+    entries.sort_by(|a, b| {
+        b.n.cmp(&a.n)
+            .then_with(|| nback_combined_pct(b).cmp(&nback_combined_pct(a)))
+            .then_with(|| a.time_secs.cmp(&b.time_secs))
+    });
+    entries.truncate(limit);
+    entries
+}
+
+fn nback_combined_pct(record: &NBackRecord) -> u16 {
+    u16::from(record.position_pct) + u16::from(record.symbol_pct)
+}
+
+fn recent_mode_records(records: &[ModeRecord], level: u8, limit: usize) -> Vec<ModeRecord> {
+    records
+        .iter()
+        .rev()
+        .filter(|entry| entry.level == level)
+        .take(limit)
+        .cloned()
+        .collect()
 }
 
 fn recent_infinite_records(records: &[InfiniteRecord], limit: usize) -> Vec<InfiniteRecord> {
     records.iter().rev().take(limit).cloned().collect()
 }
 
-fn build_empty_records_status() -> adw::StatusPage {
+fn build_empty_records_status(description: &str) -> adw::StatusPage {
     adw::StatusPage::builder()
-        .title(tr("No scores yet"))
-        .description(tr("Finish a run to populate this section"))
+        .title(tr("No games yet"))
+        .description(description)
         .icon_name("view-list-symbolic")
         .build()
 }
@@ -388,10 +1155,13 @@ fn build_mode_group(title: &str, entries: &[ModeRecord]) -> adw::PreferencesGrou
     for entry in entries {
         let row = adw::ActionRow::builder()
             .title(tr(classic_level_name(entry.level)))
-            .subtitle(format!("{} {}%", tr("Precision"), entry.precision_pct))
+            .subtitle(format!("{} {}", tr("Precision"), format_percent(entry.precision_pct)))
             .build();
         row.set_activatable(false);
-        row.add_suffix(&time_suffix_label(&format_mm_ss(entry.time_secs)));
+        if let Some(badge) = modifiers_suffix_label(&entry.modifiers) {
+            row.add_suffix(&badge);
+        }
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
         row.add_suffix(&rank_suffix_label(entry.rank.as_str()));
         group.add(&row);
     }
@@ -404,17 +1174,208 @@ fn build_infinite_group(title: &str, entries: &[InfiniteRecord]) -> adw::Prefere
     group.set_title(title);
 
     for entry in entries {
-        let milestone = format!(
+        let mut milestone = format!(
             "{} x{}",
             tr(infinite::level_name(entry.segment_level)),
             entry.segment_survival
         );
+        if entry.pacing != Pacing::Standard {
+            milestone = format!("{} · {}", milestone, tr(entry.pacing.label()));
+        }
+        if entry.trio {
+            milestone = format!("{} · {}", milestone, tr("Trio"));
+        }
         let row = adw::ActionRow::builder()
             .title(format!("{} {}", tr("Round"), entry.round))
             .subtitle(format!("{} {}", tr("Milestone"), milestone))
             .build();
         row.set_activatable(false);
-        row.add_suffix(&time_suffix_label(&format_mm_ss(entry.time_secs)));
+        if let Some(badge) = modifiers_suffix_label(&entry.modifiers) {
+            row.add_suffix(&badge);
+        }
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_weekly_group(title: &str, entries: &[WeeklyRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(challenge::week_display_name(&entry.week))
+            .subtitle(format!("{} {}", tr("Precision"), format_percent(entry.precision_pct)))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        row.add_suffix(&rank_suffix_label(entry.rank.as_str()));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_tournament_group(title: &str, entries: &[TournamentRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let breakdown = entry
+            .board_scores
+            .iter()
+            .map(|score| tournament::format_score(*score))
+            .collect::<Vec<String>>()
+            .join(" · ");
+        let row = adw::ActionRow::builder()
+            .title(format!("{} {}", tournament::format_score(entry.total_score), tr("pts")))
+            .subtitle(format!("{} {} · {}", tr("Precision"), format_percent(entry.precision_pct), breakdown))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.total_secs)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_gauntlet_group(title: &str, entries: &[GauntletRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(format_record_date(entry.recorded_at))
+            .subtitle(format!("{} {}", tr("Precision"), format_percent(entry.precision_pct)))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        row.add_suffix(&rank_suffix_label(entry.rank.as_str()));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_speedrun_group(title: &str, entries: &[SpeedrunRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(tr(classic_level_name(entry.level)))
+            .subtitle(format_record_date(entry.recorded_at))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&speedrun::format_split(entry.time_ms)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_sudden_death_group(title: &str, entries: &[SuddenDeathRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(tr(classic_level_name(entry.level)))
+            .subtitle(format!(
+                "{} {}/{}",
+                tr("Pairs"),
+                entry.pairs_cleared,
+                entry.pairs_total
+            ))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_custom_group(title: &str, entries: &[CustomRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let summary = CustomPreset::from_code(&entry.code, &entry.name)
+            .map(|preset| preset.summary())
+            .unwrap_or_else(|| entry.code.clone());
+        let row = adw::ActionRow::builder()
+            .title(&entry.name)
+            .subtitle(format!("{} · {}", summary, format_percent(entry.precision_pct)))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        row.add_suffix(&rank_suffix_label(entry.rank.as_str()));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_pattern_group(title: &str, entries: &[PatternRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(format!("{} {}", tr("Accuracy"), format_percent(entry.accuracy_pct)))
+            .subtitle(format!("{} {}", tr("Longest pattern"), entry.longest_pattern))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_nback_group(title: &str, entries: &[NBackRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(nback::level_name(entry.n))
+            .subtitle(tr_format(
+                "Position {position} · Symbol {symbol}",
+                &[
+                    ("position", &format_percent(entry.position_pct)),
+                    ("symbol", &format_percent(entry.symbol_pct)),
+                ],
+            ))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
+        group.add(&row);
+    }
+
+    group
+}
+
+fn build_incomplete_group(title: &str, entries: &[IncompleteRecord]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::new();
+    group.set_title(title);
+
+    for entry in entries {
+        let row = adw::ActionRow::builder()
+            .title(tr(classic_level_name(entry.level)))
+            .subtitle(format!(
+                "{} · {} {}/{}",
+                tr(DefeatReason::label(&entry.reason)),
+                tr("Pairs"),
+                entry.pairs_cleared,
+                entry.pairs_total
+            ))
+            .build();
+        row.set_activatable(false);
+        row.add_suffix(&time_suffix_label(&format_duration(entry.time_secs)));
         group.add(&row);
     }
 
@@ -437,76 +1398,1337 @@ fn wrap_records_page(content: &impl IsA<gtk::Widget>) -> gtk::ScrolledWindow {
     clamp.set_tightening_threshold(360);
     clamp.set_child(Some(content));
 
-    let scroller = gtk::ScrolledWindow::new();
-    scroller.set_hscrollbar_policy(gtk::PolicyType::Never);
-    scroller.set_min_content_height(280);
-    scroller.set_vexpand(true);
-    scroller.set_child(Some(&clamp));
-    scroller
+    let scroller = gtk::ScrolledWindow::new();
+    scroller.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scroller.set_min_content_height(280);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&clamp));
+    scroller
+}
+
+const PRECISION_LEVELS: [u8; 4] = [1, 2, 3, 4];
+
+fn mode_records_for_level(records: &[ModeRecord], level: u8) -> Vec<ModeRecord> {
+    records
+        .iter()
+        .filter(|entry| entry.level == level && entry.is_ranked())
+        .cloned()
+        .collect()
+}
+
+// One page per level, so the top runs compare like with like.
+fn build_precision_tab(
+    records: &[ModeRecord],
+    incomplete: &[IncompleteRecord],
+    mode: &str,
+) -> gtk::Box {
+    let tab = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    let level_switcher = gtk::StackSwitcher::new();
+    level_switcher.set_halign(gtk::Align::Center);
+    level_switcher.add_css_class("score-level-switcher");
+    let level_stack = gtk::Stack::new();
+    level_stack.set_vexpand(true);
+    level_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+    level_switcher.set_stack(Some(&level_stack));
+
+    for level in PRECISION_LEVELS {
+        let level_incomplete = incomplete
+            .iter()
+            .filter(|entry| entry.level == level)
+            .cloned()
+            .collect::<Vec<IncompleteRecord>>();
+        let page = build_precision_level_page(records, level, &level_incomplete, mode);
+        level_stack.add_titled(
+            &page,
+            Some(&format!("level-{level}")),
+            &tr(classic_level_name(level)),
+        );
+    }
+    if let Some(latest) = records.last() {
+        level_stack.set_visible_child_name(&format!("level-{}", latest.level.clamp(1, 4)));
+    }
+
+    tab.append(&level_switcher);
+    tab.append(&level_stack);
+    tab
+}
+
+// Best runs leave assisted ones out; recent runs show them with their badge.
+fn build_precision_level_page(
+    records: &[ModeRecord],
+    level: u8,
+    incomplete: &[IncompleteRecord],
+    mode: &str,
+) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = {
+        let mut rows = mode_records_for_level(records, level);
+        sort_mode_records(&mut rows);
+        rows.truncate(3);
+        rows
+    };
+    let recent_entries = recent_mode_records(records, level, 10);
+    let incomplete_entries = incomplete
+        .iter()
+        .rev()
+        .filter(|entry| entry.mode == mode)
+        .take(10)
+        .cloned()
+        .collect::<Vec<IncompleteRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() && incomplete_entries.is_empty() {
+        page.append(&build_empty_records_status(&if mode == "trio" {
+            tr("Play Trio to set your first record")
+        } else {
+            tr("Play Classic to set your first record")
+        }));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_mode_group(
+                &tr("Best runs"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_mode_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+        if !incomplete_entries.is_empty() {
+            page.append(&build_incomplete_group(
+                &tr("Incomplete runs"),
+                &incomplete_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_infinite_tab(records: &[InfiniteRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_groups = [false, true]
+        .into_iter()
+        .flat_map(|trio| Pacing::ALL.into_iter().map(move |pacing| (pacing, trio)))
+        .map(|(pacing, trio)| (pacing, trio, top_infinite_records(records, pacing, trio, 3)))
+        .filter(|(_, _, entries)| !entries.is_empty())
+        .collect::<Vec<_>>();
+    let recent_entries = recent_infinite_records(records, 10);
+
+    if top_groups.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play Infinite to set your first record")));
+    } else {
+        for (pacing, trio, top_entries) in &top_groups {
+            let pacing_name = tr(pacing.label());
+            let title = match (pacing, trio) {
+                (Pacing::Standard, false) => tr("Best runs"),
+                (_, false) => tr_format("Best runs · {pacing}", &[("pacing", &pacing_name)]),
+                (_, true) => tr_format("Best Trio runs · {pacing}", &[("pacing", &pacing_name)]),
+            };
+            page.append(&build_infinite_group(&title, top_entries));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_infinite_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_weekly_tab(records: &[WeeklyRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let current_week = challenge::current_week_label().unwrap_or_default();
+    let top_entries = top_weekly_records(records, &current_week, 3);
+    let recent_entries = records.iter().rev().take(10).cloned().collect::<Vec<WeeklyRecord>>();
+
+    let countdown = challenge::seconds_until_next_rotation()
+        .map(challenge::format_rotation_countdown)
+        .unwrap_or_default();
+    let rotation_label =
+        gtk::Label::new(Some(&tr_format("New board in {countdown}", &[("countdown", &countdown)])));
+    rotation_label.add_css_class("dim-label");
+    rotation_label.add_css_class("caption");
+    rotation_label.set_halign(gtk::Align::Center);
+    page.append(&rotation_label);
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr(
+            "Play the Weekly Challenge to set your first record",
+        )));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_weekly_group(
+                &tr("This week"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_weekly_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+    let best_time = top_entries.first().map(|entry| entry.time_secs);
+    if let Some(group) = leaderboard::standings_group(&current_week, best_time) {
+        page.append(&group);
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_tournament_tab(records: &[TournamentRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_tournament_records(records, 3);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<TournamentRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play a Tournament to set your first record")));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_tournament_group(
+                &tr("Best series"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_tournament_group(
+                &tr("Recent series"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_gauntlet_tab(records: &[GauntletRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_gauntlet_records(records, 3);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<GauntletRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play the Gauntlet to set your first record")));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_gauntlet_group(
+                &tr("Best runs"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_gauntlet_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_speedrun_tab(records: &[SpeedrunRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let best_entries = (1..=4)
+        .filter_map(|level| speedrun::personal_best(records, level).cloned())
+        .collect::<Vec<SpeedrunRecord>>();
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<SpeedrunRecord>>();
+
+    if best_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play Speedrun to set your first record")));
+    } else {
+        if !best_entries.is_empty() {
+            page.append(&build_speedrun_group(
+                &tr("Personal bests"),
+                &best_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_speedrun_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_sudden_death_tab(records: &[SuddenDeathRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_sudden_death_records(records, 5);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<SuddenDeathRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play Sudden Death to set your first record")));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_sudden_death_group(
+                &tr("Best runs"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_sudden_death_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_custom_tab(records: &[CustomRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_custom_records(records, 5);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<CustomRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr(
+            "Play a Custom preset to set your first record",
+        )));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_custom_group(&tr("Best runs"), &top_entries));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_custom_group(&tr("Recent runs"), &recent_entries));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_symbols_tab(stats: &SymbolStats) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let confusions = stats.top_confusions(SYMBOL_ROWS_SHOWN);
+    let hardest = stats.hardest_symbols(SYMBOL_ROWS_SHOWN);
+
+    if confusions.is_empty() && hardest.is_empty() {
+        page.append(
+            &adw::StatusPage::builder()
+                .title(tr("No mix-ups yet"))
+                .description(tr("Symbols you confuse will show up here"))
+                .icon_name("view-list-symbolic")
+                .build(),
+        );
+        return wrap_records_page(&page);
+    }
+
+    if !confusions.is_empty() {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&tr("Most confused"));
+        for confusion in &confusions {
+            let row = adw::ActionRow::builder()
+                .title(format!("{}  {}", confusion.symbols[0], confusion.symbols[1]))
+                .build();
+            row.set_activatable(false);
+            row.add_suffix(&time_suffix_label(&format!("{}×", confusion.count)));
+            group.add(&row);
+        }
+        page.append(&group);
+    }
+    if !hardest.is_empty() {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&tr("Hardest symbols"));
+        for (symbol, tally) in &hardest {
+            let row = adw::ActionRow::builder()
+                .title(symbol)
+                .subtitle(format!(
+                    "{} {} · {} {}",
+                    tally.mismatches,
+                    tr("mismatches"),
+                    tally.attempts,
+                    tr("attempts")
+                ))
+                .build();
+            row.set_activatable(false);
+            row.add_suffix(&time_suffix_label(&format_percent(tally.mismatch_pct())));
+            group.add(&row);
+        }
+        page.append(&group);
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_heatmap_tab(heatmap: &MistakeHeatmap) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let boards = heatmap.boards_by_mistakes();
+    if boards.is_empty() {
+        page.append(
+            &adw::StatusPage::builder()
+                .title(tr("No mismatches yet"))
+                .description(tr("Spots where you slip up will light up here"))
+                .icon_name("view-grid-symbolic")
+                .build(),
+        );
+        return wrap_records_page(&page);
+    }
+
+    for board in boards.into_iter().take(HEATMAP_BOARDS_SHOWN) {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&format!("{}×{}", board.cols, board.rows));
+        group.set_description(Some(&ntr_format(
+            "{count} mismatched flip",
+            "{count} mismatched flips",
+            board.total(),
+            &[],
+        )));
+
+        let grid = gtk::Grid::new();
+        grid.set_row_spacing(4);
+        grid.set_column_spacing(4);
+        grid.set_halign(gtk::Align::Center);
+        for index in 0..board.counts.len() {
+            let cell = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            cell.set_size_request(HEATMAP_CELL_SIZE, HEATMAP_CELL_SIZE);
+            cell.add_css_class("heat-cell");
+            cell.add_css_class(&format!("heat-{}", board.heat_level(index)));
+            cell.set_tooltip_text(Some(&board.count_at(index).to_string()));
+            let col = index as i32 % board.cols;
+            let row = index as i32 / board.cols;
+            grid.attach(&cell, col, row, 1, 1);
+        }
+        group.add(&grid);
+        page.append(&group);
+    }
+
+    wrap_records_page(&page)
+}
+
+fn week_row(title: &str, this_week: String, last_week: String) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(format!("{}: {}", tr("Last week"), last_week))
+        .build();
+    row.set_activatable(false);
+    row.add_suffix(&time_suffix_label(&this_week));
+    row
+}
+
+fn build_this_week_tab(records: &PlayerRecords) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let empty_status = || {
+        adw::StatusPage::builder()
+            .title(tr("No games this week"))
+            .description(tr("Finish a run to compare it with last week"))
+            .icon_name("view-list-symbolic")
+            .build()
+    };
+    let Some((this_week, last_week)) = weekly_summary::current_and_previous_week() else {
+        page.append(&empty_status());
+        return wrap_records_page(&page);
+    };
+    let current = weekly_summary::summarize(records, &this_week);
+    let previous = weekly_summary::summarize(records, &last_week);
+    if current.runs == 0 && previous.runs == 0 {
+        page.append(&empty_status());
+        return wrap_records_page(&page);
+    }
+
+    let precision = |summary: &WeekSummary| {
+        summary
+            .precision_pct()
+            .map(format_percent)
+            .unwrap_or_else(|| "–".to_string())
+    };
+    let best_rank = |summary: &WeekSummary| {
+        summary
+            .best_rank
+            .map(|rank| rank.as_str().to_string())
+            .unwrap_or_else(|| "–".to_string())
+    };
+
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&tr("This Week"));
+    group.add(&week_row(
+        &tr("Play time"),
+        format_duration(current.play_secs),
+        format_duration(previous.play_secs),
+    ));
+    group.add(&week_row(
+        &tr("Runs"),
+        current.runs.to_string(),
+        previous.runs.to_string(),
+    ));
+    group.add(&week_row(
+        &tr("Precision"),
+        precision(&current),
+        precision(&previous),
+    ));
+    group.add(&week_row(
+        &tr("Best rank"),
+        best_rank(&current),
+        best_rank(&previous),
+    ));
+    if current.best_infinite_round > 0 || previous.best_infinite_round > 0 {
+        group.add(&week_row(
+            &tr("Best Infinite round"),
+            current.best_infinite_round.to_string(),
+            previous.best_infinite_round.to_string(),
+        ));
+    }
+    page.append(&group);
+
+    wrap_records_page(&page)
+}
+
+fn build_trash_tab(
+    state: &Rc<RefCell<AppState>>,
+    app: &adw::Application,
+    dialog: &adw::Dialog,
+) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let now = now_timestamp().unwrap_or_default();
+    let trash: Vec<TrashedRecords> = records_trash::load_trash(now);
+    if trash.is_empty() {
+        page.append(
+            &adw::StatusPage::builder()
+                .title(tr("Trash is empty"))
+                .description(tr("Reset records stay here for 30 days"))
+                .icon_name("user-trash-symbolic")
+                .build(),
+        );
+        return wrap_records_page(&page);
+    }
+
+    let group = adw::PreferencesGroup::new();
+    group.set_title(&tr("Trash"));
+    for entry in trash.iter().rev() {
+        let row = adw::ActionRow::builder()
+            .title(format_record_date(Some(entry.trashed_at)))
+            .subtitle(format!(
+                "{} {} · {} {}",
+                entry.records.entry_count(),
+                tr("records"),
+                entry.days_left(now),
+                tr("days left")
+            ))
+            .build();
+        row.set_activatable(false);
+        let restore = gtk::Button::with_label(&tr("Restore"));
+        restore.set_valign(gtk::Align::Center);
+        restore.connect_clicked({
+            let state = state.clone();
+            let app = app.clone();
+            let dialog = dialog.clone();
+            let trashed_at = entry.trashed_at;
+            move |_| {
+                if restore_trashed_records(&state, trashed_at) {
+                    // Every tab reads the records when built, so reopen with the restored ones.
+                    dialog.close();
+                    show_memory_dialog(&state, &app);
+                }
+            }
+        });
+        row.add_suffix(&restore);
+        group.add(&row);
+    }
+    page.append(&group);
+
+    wrap_records_page(&page)
+}
+
+const HABIT_MODES_SHOWN: usize = 5;
+
+fn count_row(title: &str, count: u32) -> adw::ActionRow {
+    let row = adw::ActionRow::builder().title(title).build();
+    row.set_activatable(false);
+    row.add_suffix(&time_suffix_label(&count.to_string()));
+    row
+}
+
+fn build_habits_tab(collecting: bool) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let log = habits::load_habits();
+    if !collecting && log == HabitLog::default() {
+        page.append(
+            &adw::StatusPage::builder()
+                .title(tr("Habit tracking is off"))
+                .description(tr("Turn on Track play habits in Preferences, nothing leaves this device"))
+                .icon_name("preferences-system-symbolic")
+                .build(),
+        );
+        return wrap_records_page(&page);
+    }
+
+    let sessions_group = adw::PreferencesGroup::new();
+    sessions_group.set_title(&tr("Sessions per day"));
+    if let Ok(now) = glib::DateTime::now_local() {
+        for days_ago in 0..habits::RECENT_DAYS {
+            let Some(day) = now.add_days(-days_ago).ok() else {
+                continue;
+            };
+            let (Some(key), Ok(title)) = (habits::day_key(&day), day.format("%A %x")) else {
+                continue;
+            };
+            sessions_group.add(&count_row(&title, log.sessions_on(&key)));
+        }
+    }
+    page.append(&sessions_group);
+
+    let top_modes = log.top_modes(HABIT_MODES_SHOWN);
+    if !top_modes.is_empty() {
+        let modes_group = adw::PreferencesGroup::new();
+        modes_group.set_title(&tr("Favourite modes"));
+        for (mode, count) in &top_modes {
+            modes_group.add(&count_row(&habits::mode_key_label(mode), *count));
+        }
+        page.append(&modes_group);
+    }
+
+    let quits_group = adw::PreferencesGroup::new();
+    quits_group.set_title(&tr("Where you stop"));
+    quits_group.set_description(Some(&tr("Boards left for the menu before they were cleared")));
+    for point in QuitPoint::ALL {
+        quits_group.add(&count_row(&point.label(), log.quits_at(point)));
+    }
+    page.append(&quits_group);
+
+    wrap_records_page(&page)
+}
+
+fn friend_time(time: Option<u32>) -> String {
+    time.map_or_else(|| "—".to_string(), format_duration)
+}
+
+fn fill_friend_comparison(
+    container: &gtk::Box,
+    own: &ScoreKey,
+    friends: &Rc<RefCell<Vec<Friend>>>,
+) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+    let list = friends.borrow().clone();
+    if list.is_empty() {
+        return;
+    }
+
+    for (mode_name, own_times, pick) in [
+        ("Classic", own.classic, (|key: &ScoreKey| key.classic) as fn(&ScoreKey) -> _),
+        ("Trio", own.trio, |key: &ScoreKey| key.trio),
+    ] {
+        let group = adw::PreferencesGroup::new();
+        group.set_title(&tr(mode_name));
+        for (level, own_time) in own_times.iter().enumerate() {
+            let others = list
+                .iter()
+                .map(|friend| format!("{} {}", friend.key.name, friend_time(pick(&friend.key)[level])))
+                .collect::<Vec<String>>()
+                .join(" · ");
+            let row = adw::ActionRow::builder()
+                .title(tr(classic_level_name(level as u8 + 1)))
+                .subtitle(others)
+                .build();
+            row.set_activatable(false);
+            row.add_suffix(&time_suffix_label(&friend_time(*own_time)));
+            group.add(&row);
+        }
+        container.append(&group);
+    }
+
+    let people_group = adw::PreferencesGroup::new();
+    people_group.set_title(&tr("Friends"));
+    for friend in &list {
+        let row = adw::ActionRow::builder()
+            .title(friend.key.name.as_str())
+            .subtitle(friend.source.clone().unwrap_or_else(|| tr("Pasted key")))
+            .build();
+        row.set_activatable(false);
+        let remove = gtk::Button::from_icon_name("user-trash-symbolic");
+        remove.set_tooltip_text(Some(&tr("Remove")));
+        remove.set_valign(gtk::Align::Center);
+        remove.add_css_class("flat");
+        remove.connect_clicked({
+            let container = container.clone();
+            let own = own.clone();
+            let friends = friends.clone();
+            let removed = friend.clone();
+            move |_| {
+                friends.borrow_mut().retain(|friend| *friend != removed);
+                if let Err(err) = friends::save_friends(&friends.borrow()) {
+                    tracing::warn!("failed to save friends: {err}");
+                }
+                fill_friend_comparison(&container, &own, &friends);
+            }
+        });
+        row.add_suffix(&remove);
+        people_group.add(&row);
+    }
+    container.append(&people_group);
+}
+
+fn build_friends_tab(records: &PlayerRecords) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let own = Rc::new(RefCell::new(ScoreKey::from_records(
+        &friends::own_id(),
+        &friends::own_name(),
+        records,
+    )));
+    let friends = Rc::new(RefCell::new(friends::load_friends()));
+    let comparison = gtk::Box::new(gtk::Orientation::Vertical, 18);
+
+    let key_group = adw::PreferencesGroup::new();
+    key_group.set_title(&tr("Your score key"));
+    key_group.set_description(Some(&tr("Shares only your best Classic and Trio times per level")));
+    let name_row = adw::EntryRow::builder()
+        .title(tr("Your name"))
+        .text(own.borrow().name.as_str())
+        .show_apply_button(true)
+        .build();
+    key_group.add(&name_row);
+    let copy_row = adw::ActionRow::builder()
+        .title(tr("Copy my key"))
+        .subtitle(tr("Send it to friends or save it in a shared file"))
+        .build();
+    copy_row.set_activatable(false);
+    let copy = gtk::Button::with_label(&tr("Copy"));
+    copy.set_valign(gtk::Align::Center);
+    let update_copy = {
+        let copy = copy.clone();
+        move |name: &str| {
+            copy.set_sensitive(!name.is_empty());
+            let hint = name.is_empty().then(|| tr("Add your name first"));
+            copy.set_tooltip_text(hint.as_deref());
+        }
+    };
+    update_copy(&own.borrow().name);
+    name_row.connect_apply({
+        let own = own.clone();
+        move |row| {
+            let name = friends::clean_name(&row.text());
+            settings::set_string(friends::SCORE_NAME_KEY, &name);
+            own.borrow_mut().name = friends::own_name();
+            row.set_text(&own.borrow().name);
+            update_copy(&own.borrow().name);
+        }
+    });
+    copy.connect_clicked({
+        let own = own.clone();
+        move |button| {
+            button.clipboard().set_text(&own.borrow().to_code());
+        }
+    });
+    copy_row.add_suffix(&copy);
+    key_group.add(&copy_row);
+    page.append(&key_group);
+
+    let add_group = adw::PreferencesGroup::new();
+    add_group.set_title(&tr("Add a friend"));
+    add_group.set_description(Some(&tr("Paste a score key, a file path or an https address")));
+    let refresh = gtk::Button::from_icon_name("view-refresh-symbolic");
+    refresh.set_tooltip_text(Some(&tr("Refresh")));
+    refresh.add_css_class("flat");
+    refresh.set_valign(gtk::Align::Center);
+    refresh.connect_clicked({
+        let comparison = comparison.clone();
+        let own = own.clone();
+        let friends = friends.clone();
+        move |button| {
+            button.set_sensitive(false);
+            let button = button.clone();
+            let comparison = comparison.clone();
+            let own = own.clone();
+            let friends = friends.clone();
+            glib::spawn_future_local(async move {
+                let current = friends.borrow().clone();
+                let refreshed = friends::refresh(current).await;
+                if let Err(err) = friends::save_friends(&refreshed) {
+                    tracing::warn!("failed to save friends: {err}");
+                }
+                *friends.borrow_mut() = refreshed;
+                fill_friend_comparison(&comparison, &own.borrow(), &friends);
+                button.set_sensitive(true);
+            });
+        }
+    });
+    add_group.set_header_suffix(Some(&refresh));
+    let add_row = adw::EntryRow::builder()
+        .title(tr("Score key or address"))
+        .show_apply_button(true)
+        .build();
+    add_row.connect_apply({
+        let add_group = add_group.clone();
+        let comparison = comparison.clone();
+        let own = own.clone();
+        let friends = friends.clone();
+        move |row| {
+            let input = row.text().to_string();
+            if input.trim().is_empty() {
+                return;
+            }
+            row.set_sensitive(false);
+            let row = row.clone();
+            let add_group = add_group.clone();
+            let comparison = comparison.clone();
+            let own = own.clone();
+            let friends = friends.clone();
+            glib::spawn_future_local(async move {
+                match friends::import(&input).await {
+                    Ok(friend) => {
+                        friends::add_or_replace(&mut friends.borrow_mut(), friend);
+                        if let Err(err) = friends::save_friends(&friends.borrow()) {
+                            tracing::warn!("failed to save friends: {err}");
+                        }
+                        row.set_text("");
+                        add_group.set_description(Some(&tr(
+                            "Paste a score key, a file path or an https address",
+                        )));
+                        fill_friend_comparison(&comparison, &own.borrow(), &friends);
+                    }
+                    Err(err) => {
+                        tracing::warn!("failed to import score key: {err}");
+                        add_group.set_description(Some(&tr("Could not read a score key from that")));
+                    }
+                }
+                row.set_sensitive(true);
+            });
+        }
+    });
+    add_group.add(&add_row);
+    page.append(&add_group);
+
+    fill_friend_comparison(&comparison, &own.borrow(), &friends);
+    page.append(&comparison);
+
+    wrap_records_page(&page)
+}
+
+fn build_pattern_tab(records: &[PatternRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_pattern_records(records, 5);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<PatternRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play Pattern to set your first record")));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_pattern_group(
+                &tr("Best runs"),
+                &top_entries,
+            ));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_pattern_group(
+                &tr("Recent runs"),
+                &recent_entries,
+            ));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn build_nback_tab(records: &[NBackRecord]) -> gtk::ScrolledWindow {
+    let page = build_records_page_shell();
+    let top_entries = top_nback_records(records, 5);
+    let recent_entries = records
+        .iter()
+        .rev()
+        .take(10)
+        .cloned()
+        .collect::<Vec<NBackRecord>>();
+
+    if top_entries.is_empty() && recent_entries.is_empty() {
+        page.append(&build_empty_records_status(&tr("Play N-back to set your first record")));
+    } else {
+        if !top_entries.is_empty() {
+            page.append(&build_nback_group(&tr("Best sessions"), &top_entries));
+        }
+        if !recent_entries.is_empty() {
+            page.append(&build_nback_group(&tr("Recent sessions"), &recent_entries));
+        }
+    }
+
+    wrap_records_page(&page)
+}
+
+fn rank_for_accuracy(accuracy_pct: u8) -> Rank {
+    match accuracy_pct {
+        100.. => Rank::S,
+        90..=99 => Rank::A,
+        75..=89 => Rank::B,
+        _ => Rank::C,
+    }
+}
+
+pub fn register_pattern_result(st: &mut AppState) {
+    let Some(run) = st.pattern.clone() else {
+        return;
+    };
+    let accuracy_pct = run.accuracy_pct();
+    st.records.pattern.push(PatternRecord {
+        accuracy_pct,
+        longest_pattern: run.longest_pattern as u32,
+        time_secs: st.seconds_elapsed,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.pattern.len().saturating_sub(PATTERN_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.pattern.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+
+    let rank = rank_for_accuracy(accuracy_pct);
+    st.victory_title_text = match rank {
+        Rank::S => tr("Photographic Memory!"),
+        Rank::A => tr("Sharp Mind!"),
+        Rank::B => tr("Keep the Momentum!"),
+        Rank::C => tr("Growing Strong!"),
+    };
+    st.victory_message_text = tr_format("{mode} completed", &[("mode", &tr("Pattern"))]);
+    st.victory_stats_text = format!(
+        "{}: {}\n{}: {}\n{}: {}",
+        tr("Accuracy"),
+        format_percent(accuracy_pct),
+        tr("Longest pattern"),
+        run.longest_pattern,
+        tr("Time"),
+        format_duration(st.seconds_elapsed)
+    );
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
+}
+
+pub fn register_nback_result(st: &mut AppState) {
+    let Some(run) = st.nback.clone() else {
+        return;
+    };
+    let position_pct = run.position.accuracy_pct();
+    let symbol_pct = run.symbol.accuracy_pct();
+    st.records.nback.push(NBackRecord {
+        n: run.n,
+        position_pct,
+        symbol_pct,
+        trials: run.stimuli.len() as u32,
+        time_secs: st.seconds_elapsed,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.nback.len().saturating_sub(NBACK_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.nback.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+
+    let rank = rank_for_accuracy(position_pct.min(symbol_pct));
+    st.victory_title_text = match rank {
+        Rank::S => tr("Photographic Memory!"),
+        Rank::A => tr("Sharp Mind!"),
+        Rank::B => tr("Keep the Momentum!"),
+        Rank::C => tr("Growing Strong!"),
+    };
+    st.victory_message_text = tr_format("{mode} completed", &[("mode", &nback::level_name(run.n))]);
+    st.victory_stats_text = format!(
+        "{}\n{}\n{}: {}",
+        tr_format(
+            "Position: {accuracy} (hits: {hits}, false alarms: {false_alarms})",
+            &[
+                ("accuracy", &format_percent(position_pct)),
+                ("hits", &run.position.hits),
+                ("false_alarms", &run.position.false_alarms),
+            ],
+        ),
+        tr_format(
+            "Symbol: {accuracy} (hits: {hits}, false alarms: {false_alarms})",
+            &[
+                ("accuracy", &format_percent(symbol_pct)),
+                ("hits", &run.symbol.hits),
+                ("false_alarms", &run.symbol.false_alarms),
+            ],
+        ),
+        tr("Time"),
+        format_duration(st.seconds_elapsed)
+    );
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
+}
+
+pub fn register_sudden_death_result(st: &mut AppState) {
+    let (pairs_cleared, pairs_total) = defeat::pairs_cleared(st);
+    st.records.sudden_death.push(SuddenDeathRecord {
+        level: speedrun::classic_level(st),
+        pairs_cleared,
+        pairs_total,
+        time_secs: st.seconds_elapsed,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st
+        .records
+        .sudden_death
+        .len()
+        .saturating_sub(SUDDEN_DEATH_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.sudden_death.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+}
+
+fn register_speedrun_result(st: &mut AppState, level: u8, precision_pct: u8) {
+    let time_ms = st
+        .match_times_ms
+        .last()
+        .copied()
+        .unwrap_or_else(|| st.run_clock.elapsed_ms(glib::monotonic_time()));
+    let previous_best_ms =
+        speedrun::personal_best(&st.records.speedrun, level).map(|record| record.time_ms);
+    let rank = rank_for_precision(level, precision_pct);
+
+    st.records.speedrun.push(SpeedrunRecord {
+        level,
+        time_ms,
+        splits_ms: st.match_times_ms.clone(),
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.speedrun.len().saturating_sub(SPEEDRUN_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.speedrun.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+
+    let is_new_best = previous_best_ms.is_none_or(|best_ms| time_ms < best_ms);
+    st.victory_new_record = previous_best_ms.is_some() && is_new_best;
+    st.victory_title_text = if is_new_best {
+        tr("New Personal Best!")
+    } else {
+        tr("Run Complete")
+    };
+    st.victory_message_text = tr_format(
+        "Speedrun {level} completed",
+        &[("level", &tr(classic_level_name(level)))],
+    );
+    let best_line = match previous_best_ms {
+        Some(best_ms) => format!(
+            "{}: {} ({})",
+            tr("Personal best"),
+            speedrun::format_split(best_ms),
+            speedrun::format_delta(speedrun::split_delta_ms(time_ms, best_ms))
+        ),
+        None => tr("First run on this board"),
+    };
+    st.victory_stats_text = format!(
+        "{}: {}\n{}\n{}: {}",
+        tr("Time"),
+        speedrun::format_split(time_ms),
+        best_line,
+        tr("Precision"),
+        format_percent(precision_pct)
+    );
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
+}
+
+fn register_custom_result(st: &mut AppState, precision_pct: u8) {
+    let Some(preset) = st.custom.clone() else {
+        return;
+    };
+    let rank = rank_for_precision(preset.rank_level(), precision_pct);
+    st.records.custom.push(CustomRecord {
+        name: preset.name.clone(),
+        code: preset.to_code(),
+        time_secs: st.seconds_elapsed,
+        precision_pct,
+        rank,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.custom.len().saturating_sub(CUSTOM_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.custom.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+
+    st.victory_title_text = tr("Board Cleared!");
+    st.victory_message_text = tr_format("{mode} completed", &[("mode", &preset.mode_label())]);
+    st.victory_stats_text = format!(
+        "{}: {}\n{}: {}\n{}: {}",
+        tr("Time"),
+        format_duration(st.seconds_elapsed),
+        tr("Precision"),
+        format_percent(precision_pct),
+        tr("Harmony"),
+        rank.as_str()
+    );
+    append_views_per_match(st);
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
+}
+
+fn register_gauntlet_board(st: &mut AppState, precision_pct: u8) {
+    let Some(gauntlet) = st.gauntlet_mut() else {
+        return;
+    };
+    let finished = gauntlet.is_last_board();
+    gauntlet.advance();
+    if !finished {
+        return;
+    }
+
+    let rank = rank_for_precision(4, precision_pct);
+    st.records.gauntlet.push(GauntletRecord {
+        time_secs: st.seconds_elapsed,
+        precision_pct,
+        rank,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.gauntlet.len().saturating_sub(GAUNTLET_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.gauntlet.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+
+    st.victory_title_text = tr("Gauntlet Cleared!");
+    st.victory_message_text = tr("Easy to Expert in a single run");
+    st.victory_stats_text = format!(
+        "{}: {}\n{}: {}\n{}: {}",
+        tr("Time"),
+        format_duration(st.seconds_elapsed),
+        tr("Precision"),
+        format_percent(precision_pct),
+        tr("Harmony"),
+        rank.as_str()
+    );
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
 }
 
-fn build_precision_tab(records: &[ModeRecord]) -> gtk::ScrolledWindow {
-    let page = build_records_page_shell();
-    let top_entries = {
-        let mut rows = records.to_vec();
-        sort_mode_records(&mut rows);
-        rows.truncate(3);
-        rows
+// Turns share one device and are never filed under anyone's records.
+fn register_hotseat_turn(st: &mut AppState, level: u8, precision_pct: u8) {
+    let Some(run) = st.hotseat.as_mut() else {
+        return;
+    };
+    let Some(name) = run.current_player().map(|player| player.name.clone()) else {
+        return;
+    };
+    let Some(result) = run.record_turn(st.seconds_elapsed, precision_pct) else {
+        return;
     };
-    let recent_entries = recent_mode_records(records, 10);
 
-    if top_entries.is_empty() && recent_entries.is_empty() {
-        page.append(&build_empty_records_status());
-    } else {
-        if !top_entries.is_empty() {
-            page.append(&build_mode_group(
-                &tr("Best runs"),
-                &top_entries,
-            ));
-        }
-        if !recent_entries.is_empty() {
-            page.append(&build_mode_group(
-                &tr("Recent runs"),
-                &recent_entries,
-            ));
-        }
+    if let Some(next) = run.current_player() {
+        st.victory_title_text = tr_format("{name} finished", &[("name", &name)]);
+        st.victory_message_text =
+            tr_format("Pass the device to {name}", &[("name", &next.name)]);
+        st.victory_stats_text = format!(
+            "{}: {}\n{}: {}\n{}: {}",
+            tr("Time"),
+            format_duration(result.time_secs),
+            tr("Precision"),
+            format_percent(precision_pct),
+            tr("Score"),
+            tournament::format_score(result.score)
+        );
+        st.victory_rank = rank_for_precision(level, precision_pct);
+        st.victory_art_resource = None;
+        return;
     }
 
-    wrap_records_page(&page)
+    let standings = run.standings();
+    let Some((winner, best)) = standings.first().map(|(player, result)| (player.name.clone(), *result)) else {
+        return;
+    };
+    st.victory_stats_text = standings
+        .iter()
+        .enumerate()
+        .map(|(idx, (player, result))| {
+            format!(
+                "{}. {} · {} · {} · {}",
+                idx + 1,
+                player.name,
+                format_duration(result.time_secs),
+                format_percent(result.precision_pct),
+                tournament::format_score(result.score)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    st.victory_title_text = tr_format("{name} wins!", &[("name", &winner)]);
+    st.victory_message_text = tr_format(
+        "Pass the Device {level} completed",
+        &[("level", &tr(classic_level_name(level)))],
+    );
+    st.victory_rank = rank_for_precision(level, best.precision_pct);
+    st.victory_art_resource = None;
+
+    let leader = run.leader();
+    if st.bracket.is_some() {
+        register_bracket_match(st, leader);
+    }
 }
 
-fn build_infinite_tab(records: &[InfiniteRecord]) -> gtk::ScrolledWindow {
-    let page = build_records_page_shell();
-    let top_entries = top_infinite_records(records, 3);
-    let recent_entries = recent_infinite_records(records, 10);
+// A head-to-head match is a two-player turn run; its leader moves on.
+fn register_bracket_match(st: &mut AppState, leader: Option<usize>) {
+    let Some(bracket) = st.bracket.as_mut() else {
+        return;
+    };
+    let Some((first, second)) = bracket.current_pair() else {
+        return;
+    };
+    let winner = if leader == Some(1) { second } else { first };
+    if !bracket.record_winner(winner) {
+        return;
+    }
+    if let Some(champion) = bracket.champion() {
+        st.victory_title_text =
+            tr_format("{name} is the champion!", &[("name", &bracket.name(champion))]);
+        st.victory_message_text = tr("Bracket completed");
+        st.victory_stats_text = bracket.summary_lines().join("\n");
+        st.victory_art_resource = Some(resources::FINISH_FLAG_ART.to_string());
+        return;
+    }
+    st.victory_title_text = tr_format("{name} advances", &[("name", &bracket.name(winner))]);
+    if let Some((next_first, next_second)) = bracket.current_pair() {
+        st.victory_message_text = tr_format(
+            "Next match: {first} vs {second}",
+            &[
+                ("first", &bracket.name(next_first)),
+                ("second", &bracket.name(next_second)),
+            ],
+        );
+    }
+}
 
-    if top_entries.is_empty() && recent_entries.is_empty() {
-        page.append(&build_empty_records_status());
-    } else {
-        if !top_entries.is_empty() {
-            page.append(&build_infinite_group(
-                &tr("Best runs"),
-                &top_entries,
-            ));
-        }
-        if !recent_entries.is_empty() {
-            page.append(&build_infinite_group(
-                &tr("Recent runs"),
-                &recent_entries,
-            ));
-        }
+fn register_tournament_board(st: &mut AppState, level: u8, precision_pct: u8) {
+    let time_secs = st.seconds_elapsed;
+    let Some(run) = st.tournament_mut() else {
+        return;
+    };
+    let result = run.record_board(level, time_secs, precision_pct);
+    let board_number = run.board_index();
+    let board_count = run.board_count();
+    let total_score = run.total_score();
+
+    if !run.is_complete() {
+        let next_level = tournament::TOURNAMENT_LEVELS[run.board_index()];
+        let rank = rank_for_precision(level, precision_pct);
+        st.victory_title_text = tr_format(
+            "Board {number}/{count} cleared",
+            &[("number", &board_number), ("count", &board_count)],
+        );
+        st.victory_message_text =
+            tr_format("Next board: {level}", &[("level", &tr(classic_level_name(next_level)))]);
+        st.victory_stats_text = format!(
+            "{}: {}\n{}: {}\n{}: {}\n{}: {}",
+            tr("Time"),
+            format_duration(result.time_secs),
+            tr("Precision"),
+            format_percent(precision_pct),
+            tr("Board score"),
+            tournament::format_score(result.score),
+            tr("Series total"),
+            tournament::format_score(total_score)
+        );
+        st.victory_rank = rank;
+        st.victory_art_resource = None;
+        return;
     }
 
-    wrap_records_page(&page)
+    let series_precision = run.precision_pct();
+    let record = TournamentRecord {
+        total_score,
+        total_secs: run.total_secs(),
+        precision_pct: series_precision,
+        board_scores: run.results.iter().map(|result| result.score).collect(),
+        recorded_at: now_timestamp(),
+    };
+    let breakdown = run
+        .results
+        .iter()
+        .enumerate()
+        .map(|(idx, result)| {
+            format!(
+                "{}. {} · {} · {} · {}",
+                idx + 1,
+                tr(classic_level_name(result.level)),
+                format_duration(result.time_secs),
+                format_percent(result.precision_pct),
+                tournament::format_score(result.score)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let rank = rank_for_precision(4, series_precision);
+
+    st.victory_title_text = tr("Tournament Complete!");
+    st.victory_message_text = format!(
+        "{} {} {}",
+        tr("Composite score"),
+        tournament::format_score(total_score),
+        tr("pts")
+    );
+    st.victory_stats_text = format!(
+        "{}\n{}: {} · {}: {}",
+        breakdown,
+        tr("Time"),
+        format_duration(record.total_secs),
+        tr("Precision"),
+        format_percent(series_precision)
+    );
+    st.victory_rank = rank;
+    st.victory_art_resource = None;
+
+    st.records.tournament.push(record);
+    let overflow = st
+        .records
+        .tournament
+        .len()
+        .saturating_sub(TOURNAMENT_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.tournament.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
 }
 
 pub fn register_non_infinite_result(st: &mut AppState) {
-    let attempts = st.run_matches.saturating_add(st.run_mismatches);
-    let precision_pct = if attempts == 0 {
-        100
-    } else {
-        ((st.run_matches as f64 / attempts as f64) * 100.0).round() as u8
-    };
+    let precision_pct = precision_for(st.run_matches, st.run_mismatches);
     let level = if st.difficulty == Difficulty::Trio {
         st.trio_level
     } else {
@@ -518,15 +2740,118 @@ pub fn register_non_infinite_result(st: &mut AppState) {
             _ => 1,
         }
     };
+    // Warm-ups are never recorded; the splash hands over to the real board.
+    if warmup::is_warming_up(st) {
+        return;
+    }
+    if st.tournament().is_some() {
+        register_tournament_board(st, level, precision_pct);
+        return;
+    }
+    if st.hotseat.is_some() {
+        register_hotseat_turn(st, level, precision_pct);
+        return;
+    }
+    if st.gauntlet().is_some() {
+        register_gauntlet_board(st, precision_pct);
+        return;
+    }
+    if st.speedrun {
+        register_speedrun_result(st, level, precision_pct);
+        return;
+    }
+    if st.run_assisted() && st.kids.is_none() {
+        register_assisted_result(st, level, precision_pct);
+        return;
+    }
+    if st.custom.is_some() {
+        register_custom_result(st, precision_pct);
+        return;
+    }
+    if let Some(board) = st.kids {
+        // Kids runs are never ranked or stored, every finish is a celebration.
+        st.victory_title_text = tr("Great Job!");
+        st.victory_message_text = tr_format("{mode} completed", &[("mode", &board.mode_label())]);
+        st.victory_stats_text = format!(
+            "{}: {}\n{}: {}\n{}",
+            tr("Pairs found"),
+            st.run_matches,
+            tr("Time"),
+            format_duration(st.seconds_elapsed),
+            tr("Just for fun, kids boards are not ranked or saved")
+        );
+        st.victory_rank = Rank::S;
+        st.victory_art_resource = None;
+        return;
+    }
+    if st.sudden_death {
+        register_sudden_death_result(st);
+        let (_, pairs_total) = defeat::pairs_cleared(st);
+        st.victory_title_text = tr("Untouchable!");
+        st.victory_message_text = tr_format(
+            "Sudden Death {level} completed",
+            &[("level", &tr(classic_level_name(level)))],
+        );
+        st.victory_stats_text = format!(
+            "{}: {}/{}\n{}: {}",
+            tr("Pairs"),
+            pairs_total,
+            pairs_total,
+            tr("Time"),
+            format_duration(st.seconds_elapsed)
+        );
+        st.victory_rank = Rank::S;
+        st.victory_art_resource = None;
+        return;
+    }
     let rank = rank_for_precision(level, precision_pct);
+    let next_goal = nearest_goal(st, level, precision_pct);
+    let weekly_week = st
+        .challenge
+        .as_ref()
+        .and_then(|challenge| challenge.week())
+        .map(str::to_string);
     let best_candidate = ModeRecord {
         level,
         time_secs: st.seconds_elapsed,
         precision_pct,
         rank,
-        date_label: now_date_label(),
+        recorded_at: now_timestamp(),
+        modifiers: String::new(),
+    };
+    let run = run_key(rank, precision_pct, best_candidate.time_secs);
+    let weekly_best = weekly_week.as_ref().and_then(|week| {
+        st.records
+            .weekly
+            .iter()
+            .filter(|entry| &entry.week == week)
+            .map(|entry| run_key(entry.rank, entry.precision_pct, entry.time_secs))
+            .max()
+    });
+    st.victory_new_record = if weekly_week.is_some() {
+        weekly_best.is_some_and(|best| run > best)
+    } else if st.difficulty == Difficulty::Trio {
+        beats_personal_best(&st.records.trio, &best_candidate)
+    } else {
+        beats_personal_best(&st.records.classic, &best_candidate)
     };
-    if st.difficulty == Difficulty::Trio {
+    if let Some(week) = &weekly_week {
+        // Replays only reach the leaderboard when they beat the week's best so far.
+        if weekly_best.is_none_or(|best| run > best) {
+            leaderboard::submit_weekly(week, best_candidate.time_secs, precision_pct);
+        }
+        st.records.weekly.push(WeeklyRecord {
+            week: week.clone(),
+            time_secs: best_candidate.time_secs,
+            precision_pct,
+            rank,
+            recorded_at: best_candidate.recorded_at,
+        });
+        let overflow = st.records.weekly.len().saturating_sub(WEEKLY_HISTORY_LIMIT);
+        if overflow > 0 {
+            st.records.weekly.drain(0..overflow);
+        }
+    } else if st.difficulty == Difficulty::Trio {
         st.records.trio.push(best_candidate);
         let overflow = st.records.trio.len().saturating_sub(MODE_HISTORY_LIMIT);
         if overflow > 0 {
@@ -540,7 +2865,7 @@ pub fn register_non_infinite_result(st: &mut AppState) {
         }
     }
     if let Err(err) = save_records(&st.records) {
-        eprintln!("warning: failed to save records: {err}");
+        tracing::warn!("failed to save records: {err}");
     }
 
     st.victory_title_text = match rank {
@@ -549,27 +2874,164 @@ pub fn register_non_infinite_result(st: &mut AppState) {
         Rank::B => tr("Keep the Momentum!"),
         Rank::C => tr("Growing Strong!"),
     };
-    st.victory_message_text = if st.difficulty == Difficulty::Trio {
-        format!("{} {} {}", tr("Trio"), tr(classic_level_name(level)), tr("completed"))
+    st.victory_message_text = if let Some(week) = &weekly_week {
+        tr_format(
+            "Weekly Challenge {week} completed",
+            &[("week", &challenge::week_display_name(week))],
+        )
     } else {
-        format!("{} {} {}", tr("Classic"), tr(classic_level_name(level)), tr("completed"))
+        tr_format("{mode} completed", &[("mode", &precision_mode_label(st, level))])
     };
     st.victory_stats_text = format!(
-        "{}: {}\n{}: {}%\n{}: {}",
+        "{}: {}\n{}: {}\n{}: {}",
         tr("Time"),
-        format_mm_ss(st.seconds_elapsed),
+        format_duration(st.seconds_elapsed),
         tr("Precision"),
-        precision_pct,
+        format_percent(precision_pct),
         tr("Harmony"),
         rank.as_str()
     );
+    append_views_per_match(st);
     st.victory_rank = rank;
+    st.victory_rank_hint = Some(rank_requirement_text(
+        level,
+        st.run_matches,
+        st.run_mismatches,
+        precision_pct,
+    ));
+    st.victory_goal_text = next_goal.map(|goal| goal.describe());
+    st.victory_art_resource = None;
+}
+
+// Checked before this run is filed, so the best time is the one it was chasing.
+fn nearest_goal(st: &AppState, level: u8, precision_pct: u8) -> Option<Goal> {
+    let mut goals = Vec::new();
+    let is_weekly = st.challenge.as_ref().is_some_and(|challenge| challenge.week().is_some());
+    if !is_weekly {
+        let (records, mode_label) = if st.difficulty == Difficulty::Trio {
+            (&st.records.trio, format!("{} {}", tr("Trio"), tr(classic_level_name(level))))
+        } else {
+            (&st.records.classic, tr(classic_level_name(level)))
+        };
+        let rank = rank_for_precision(level, precision_pct);
+        if let Some(best) = personal_best(records, level)
+            && run_key(rank, precision_pct, st.seconds_elapsed)
+                < run_key(best.rank, best.precision_pct, best.time_secs)
+        {
+            goals.push(Goal::BestTime {
+                mode_label,
+                secs_away: st.seconds_elapsed.saturating_sub(best.time_secs),
+            });
+        }
+    }
+    if let Some(par_secs) = par_time::par_secs(st) {
+        goals.push(Goal::Par {
+            secs_away: st.seconds_elapsed.saturating_sub(par_secs),
+        });
+    }
+    if let Some((rank, _, allowed)) = next_rank_requirement(level, st.run_matches, precision_pct) {
+        goals.push(Goal::Rank {
+            rank,
+            mismatches_away: st.run_mismatches.saturating_sub(allowed),
+        });
+    }
+    next_goal::nearest(goals)
+}
+
+// How often cards were turned over before their group was found.
+fn append_views_per_match(st: &mut AppState) {
+    if let Some(views) = st.views_per_match() {
+        st.victory_stats_text
+            .push_str(&format!("\n{}: {}", tr("Views per match"), format_decimal(views)));
+    }
+}
+
+// Assisted classic and Trio runs are kept with their modifiers but never
+// compete for best runs; custom boards are still not stored.
+fn precision_mode_label(st: &AppState, level: u8) -> String {
+    let level_name = tr(classic_level_name(level));
+    if let Some(preset) = &st.custom {
+        preset.mode_label()
+    } else if st.difficulty == Difficulty::Trio {
+        tr_format("Trio {level}", &[("level", &level_name)])
+    } else {
+        tr_format("Classic {level}", &[("level", &level_name)])
+    }
+}
+
+fn register_assisted_result(st: &mut AppState, level: u8, precision_pct: u8) {
+    if st.custom.is_none() {
+        let entry = ModeRecord {
+            level,
+            time_secs: st.seconds_elapsed,
+            precision_pct,
+            rank: rank_for_precision(level, precision_pct),
+            recorded_at: now_timestamp(),
+            modifiers: st.run_modifiers(),
+        };
+        let history = if st.difficulty == Difficulty::Trio {
+            &mut st.records.trio
+        } else {
+            &mut st.records.classic
+        };
+        history.push(entry);
+        let overflow = history.len().saturating_sub(MODE_HISTORY_LIMIT);
+        if overflow > 0 {
+            history.drain(0..overflow);
+        }
+        if let Err(err) = save_records(&st.records) {
+            tracing::warn!("failed to save records: {err}");
+        }
+    }
+    st.victory_title_text = tr("Board Cleared!");
+    st.victory_message_text =
+        tr_format("{mode} completed", &[("mode", &precision_mode_label(st, level))]);
+    st.victory_stats_text = format!(
+        "{}: {}\n{}: {}\n{}",
+        tr("Time"),
+        format_duration(st.seconds_elapsed),
+        tr("Precision"),
+        format_percent(precision_pct),
+        tr("Assists on, not ranked")
+    );
+    append_views_per_match(st);
+    st.victory_rank = Rank::C;
     st.victory_art_resource = None;
 }
 
+pub fn register_incomplete_run(st: &mut AppState, reason: DefeatReason) {
+    // Custom boards have no classic or Trio level to file an incomplete run under.
+    if warmup::is_warming_up(st) || st.custom.is_some() || st.run_assisted() {
+        return;
+    }
+    let (pairs_cleared, pairs_total) = defeat::pairs_cleared(st);
+    let level = if st.difficulty == Difficulty::Trio {
+        st.trio_level
+    } else {
+        speedrun::classic_level(st)
+    };
+    st.records.incomplete.push(IncompleteRecord {
+        mode: defeat::mode_code(st.difficulty).to_string(),
+        level,
+        reason: reason.code().to_string(),
+        pairs_cleared,
+        pairs_total,
+        time_secs: st.seconds_elapsed,
+        recorded_at: now_timestamp(),
+    });
+    let overflow = st.records.incomplete.len().saturating_sub(INCOMPLETE_HISTORY_LIMIT);
+    if overflow > 0 {
+        st.records.incomplete.drain(0..overflow);
+    }
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+}
+
 pub fn register_infinite_run_result(st: &mut AppState) {
     let round = st.infinite_round;
-    let segment = infinite::classic_difficulty_for_round(round);
+    let pacing = st.infinite_pacing;
+    let segment = infinite::classic_difficulty_for_round(pacing, round);
     let segment_level = match segment {
         Difficulty::Easy => 1,
         Difficulty::Medium => 2,
@@ -578,9 +3040,9 @@ pub fn register_infinite_run_result(st: &mut AppState) {
         _ => 1,
     };
     let segment_survival = if segment == Difficulty::Impossible {
-        infinite::expert_survival_rounds(round)
+        infinite::expert_survival_rounds(pacing, round)
     } else if segment == Difficulty::Hard {
-        infinite::hard_survival_rounds(round)
+        infinite::hard_survival_rounds(pacing, round)
     } else {
         round
     };
@@ -589,7 +3051,10 @@ pub fn register_infinite_run_result(st: &mut AppState) {
         segment_level,
         segment_survival,
         time_secs: st.seconds_elapsed,
-        date_label: now_date_label(),
+        recorded_at: now_timestamp(),
+        pacing,
+        trio: st.infinite_trio(),
+        modifiers: st.run_modifiers(),
     };
     st.records.infinite.push(candidate);
     let overflow = st.records.infinite.len().saturating_sub(INFINITE_HISTORY_LIMIT);
@@ -597,18 +3062,164 @@ pub fn register_infinite_run_result(st: &mut AppState) {
         st.records.infinite.drain(0..overflow);
     }
     if let Err(err) = save_records(&st.records) {
-        eprintln!("warning: failed to save records: {err}");
+        tracing::warn!("failed to save records: {err}");
+    }
+}
+
+// Cleared records go to the trash first; returns the trash entry so it can be undone.
+// Restored or imported entries may overflow a list; its newest entries are kept.
+fn trim_history(records: &mut PlayerRecords) {
+    fn keep_newest<T>(entries: &mut Vec<T>, limit: usize) {
+        let overflow = entries.len().saturating_sub(limit);
+        entries.drain(0..overflow);
     }
+    keep_newest(&mut records.classic, MODE_HISTORY_LIMIT);
+    keep_newest(&mut records.trio, MODE_HISTORY_LIMIT);
+    keep_newest(&mut records.infinite, INFINITE_HISTORY_LIMIT);
+    keep_newest(&mut records.weekly, WEEKLY_HISTORY_LIMIT);
+    keep_newest(&mut records.tournament, TOURNAMENT_HISTORY_LIMIT);
+    keep_newest(&mut records.gauntlet, GAUNTLET_HISTORY_LIMIT);
+    keep_newest(&mut records.speedrun, SPEEDRUN_HISTORY_LIMIT);
+    keep_newest(&mut records.incomplete, INCOMPLETE_HISTORY_LIMIT);
+    keep_newest(&mut records.sudden_death, SUDDEN_DEATH_HISTORY_LIMIT);
+    keep_newest(&mut records.pattern, PATTERN_HISTORY_LIMIT);
+    keep_newest(&mut records.nback, NBACK_HISTORY_LIMIT);
+    keep_newest(&mut records.custom, CUSTOM_HISTORY_LIMIT);
 }
 
-pub fn reset_local_records(state: &Rc<RefCell<AppState>>) {
+// Nothing is cleared unless the records made it into the trash; returns when they were trashed.
+pub fn reset_local_records(state: &Rc<RefCell<AppState>>) -> io::Result<Option<i64>> {
     let mut st = state.borrow_mut();
+    let trashed_at = now_timestamp().filter(|_| st.records.entry_count() > 0);
+    if let Some(trashed_at) = trashed_at {
+        records_trash::move_to_trash(st.records.clone(), trashed_at)?;
+    }
     st.records = PlayerRecords::default();
     if let Err(err) = save_records(&st.records) {
-        eprintln!("warning: failed to reset local records: {err}");
+        tracing::warn!("failed to reset local records: {err}");
+    }
+    symbol_stats::clear_symbol_stats(&mut st);
+    heatmap::clear_heatmap(&mut st);
+    Ok(trashed_at)
+}
+
+// Accepts the current JSON file or the legacy text format from older builds.
+fn read_records_file(path: &Path) -> io::Result<PlayerRecords> {
+    let raw = fs::read_to_string(path)?;
+    if let Some(records) = load_json_records(&raw) {
+        return Ok(records);
+    }
+    let legacy = load_legacy_records(&raw);
+    if legacy.entry_count() > 0 {
+        return Ok(legacy);
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "not a Recall records file"))
+}
+
+// Counts only the imported entries that survive trimming.
+fn merge_within_limits(records: &mut PlayerRecords, incoming: PlayerRecords) -> usize {
+    fn kept<T: PartialEq>(before: &[T], after: &[T]) -> usize {
+        after.iter().filter(|entry| !before.contains(entry)).count()
+    }
+    let before = records.clone();
+    records.merge(incoming);
+    trim_history(records);
+    kept(&before.classic, &records.classic)
+        + kept(&before.trio, &records.trio)
+        + kept(&before.infinite, &records.infinite)
+        + kept(&before.weekly, &records.weekly)
+        + kept(&before.tournament, &records.tournament)
+        + kept(&before.gauntlet, &records.gauntlet)
+        + kept(&before.speedrun, &records.speedrun)
+        + kept(&before.incomplete, &records.incomplete)
+        + kept(&before.sudden_death, &records.sudden_death)
+        + kept(&before.pattern, &records.pattern)
+        + kept(&before.nback, &records.nback)
+        + kept(&before.custom, &records.custom)
+}
+
+// Unlike a reset, merging keeps every local entry; returns how many entries were new.
+pub fn merge_records_from_file(state: &Rc<RefCell<AppState>>, path: &Path) -> io::Result<usize> {
+    let incoming = read_records_file(path)?;
+    let mut st = state.borrow_mut();
+    let added = merge_within_limits(&mut st.records, incoming);
+    if added > 0 {
+        save_records(&st.records)?;
+    }
+    Ok(added)
+}
+
+pub fn restore_trashed_records(state: &Rc<RefCell<AppState>>, trashed_at: i64) -> bool {
+    let Some(now) = now_timestamp() else {
+        return false;
+    };
+    let Some(restored) = records_trash::take_from_trash(trashed_at, now) else {
+        return false;
+    };
+    let mut st = state.borrow_mut();
+    st.records.prepend(restored);
+    trim_history(&mut st.records);
+    if let Err(err) = save_records(&st.records) {
+        tracing::warn!("failed to save records: {err}");
+    }
+    true
+}
+
+type BuildHistoryTab = fn(&PlayerRecords) -> gtk::Widget;
+type HistoryTab = (&'static str, &'static str, BuildHistoryTab);
+
+struct HistorySlot {
+    name: &'static str,
+    slot: gtk::Box,
+    build: BuildHistoryTab,
+    stale: Cell<bool>,
+}
+
+impl HistorySlot {
+    fn rebuild(&self, records: &PlayerRecords) {
+        while let Some(child) = self.slot.first_child() {
+            self.slot.remove(&child);
+        }
+        self.slot.append(&(self.build)(records));
     }
 }
 
+const HISTORY_TABS: [HistoryTab; 11] = [
+    ("score-classic", tr_noop("Classic"), |records| {
+        build_precision_tab(&records.classic, &records.incomplete, "classic").upcast()
+    }),
+    ("score-trio", tr_noop("Trio"), |records| {
+        build_precision_tab(&records.trio, &records.incomplete, "trio").upcast()
+    }),
+    ("score-infinite", tr_noop("Infinite"), |records| {
+        build_infinite_tab(&records.infinite).upcast()
+    }),
+    ("score-weekly", tr_noop("Weekly"), |records| {
+        build_weekly_tab(&records.weekly).upcast()
+    }),
+    ("score-tournament", tr_noop("Tournament"), |records| {
+        build_tournament_tab(&records.tournament).upcast()
+    }),
+    ("score-gauntlet", tr_noop("Gauntlet"), |records| {
+        build_gauntlet_tab(&records.gauntlet).upcast()
+    }),
+    ("score-speedrun", tr_noop("Speedrun"), |records| {
+        build_speedrun_tab(&records.speedrun).upcast()
+    }),
+    ("score-sudden-death", tr_noop("Sudden Death"), |records| {
+        build_sudden_death_tab(&records.sudden_death).upcast()
+    }),
+    ("score-pattern", tr_noop("Pattern"), |records| {
+        build_pattern_tab(&records.pattern).upcast()
+    }),
+    ("score-nback", tr_noop("N-back"), |records| {
+        build_nback_tab(&records.nback).upcast()
+    }),
+    ("score-custom", tr_noop("Custom"), |records| {
+        build_custom_tab(&records.custom).upcast()
+    }),
+];
+
 pub fn show_memory_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application) -> adw::Dialog {
     let parent_window = app.active_window();
     let dialog = adw::Dialog::new();
@@ -633,14 +3244,9 @@ pub fn show_memory_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application)
     content.set_halign(gtk::Align::Fill);
     content.set_vexpand(true);
 
-    let (classic_records, trio_records, infinite_records) = {
-        let st = state.borrow();
-        (
-            st.records.classic.clone(),
-            st.records.trio.clone(),
-            st.records.infinite.clone(),
-        )
-    };
+    let records = state.borrow().records.clone();
+    let symbol_stats = state.borrow().symbol_stats.clone();
+    let mistake_heatmap = state.borrow().mistake_heatmap.clone();
 
     let mode_switcher = gtk::StackSwitcher::new();
     mode_switcher.set_halign(gtk::Align::Center);
@@ -653,13 +3259,78 @@ pub fn show_memory_dialog(state: &Rc<RefCell<AppState>>, app: &adw::Application)
     mode_stack.set_transition_duration(180);
     mode_switcher.set_stack(Some(&mode_stack));
 
-    let classic_tab = build_precision_tab(&classic_records);
-    mode_stack.add_titled(&classic_tab, Some("score-classic"), &tr("Classic"));
-    let trio_tab = build_precision_tab(&trio_records);
-    mode_stack.add_titled(&trio_tab, Some("score-trio"), &tr("Trio"));
-    let infinite_tab = build_infinite_tab(&infinite_records);
-    mode_stack.add_titled(&infinite_tab, Some("score-infinite"), &tr("Infinite"));
-
+    // History tabs sit in slots so a filter change can rebuild them in place;
+    // hidden tabs wait until they are shown.
+    let mut history_slots = Vec::with_capacity(HISTORY_TABS.len());
+    for (name, title, build) in HISTORY_TABS {
+        let slot = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        slot.set_vexpand(true);
+        slot.append(&build(&records));
+        mode_stack.add_titled(&slot, Some(name), &tr(title));
+        history_slots.push(HistorySlot {
+            name,
+            slot,
+            build,
+            stale: Cell::new(false),
+        });
+    }
+    let history_slots = Rc::new(history_slots);
+    let filtered = Rc::new(RefCell::new(records.clone()));
+    let refresh_shown_tab = {
+        let history_slots = history_slots.clone();
+        let filtered = filtered.clone();
+        move |stack: &gtk::Stack| {
+            let shown = stack.visible_child_name();
+            if let Some(slot) = history_slots
+                .iter()
+                .find(|slot| shown.as_deref() == Some(slot.name))
+                && slot.stale.replace(false)
+            {
+                slot.rebuild(&filtered.borrow());
+            }
+        }
+    };
+    let filter_bar = {
+        let records = records.clone();
+        let history_slots = history_slots.clone();
+        let refresh_shown_tab = refresh_shown_tab.clone();
+        let mode_stack = mode_stack.downgrade();
+        records_filter::build_filter_bar(move |filter| {
+            *filtered.borrow_mut() = filter.apply(&records);
+            for slot in history_slots.iter() {
+                slot.stale.set(true);
+            }
+            if let Some(mode_stack) = mode_stack.upgrade() {
+                refresh_shown_tab(&mode_stack);
+            }
+        })
+    };
+    {
+        let filter_bar = filter_bar.clone();
+        mode_stack.connect_visible_child_name_notify(move |stack| {
+            let shown = stack.visible_child_name();
+            filter_bar.set_visible(
+                history_slots
+                    .iter()
+                    .any(|slot| shown.as_deref() == Some(slot.name)),
+            );
+            refresh_shown_tab(stack);
+        });
+    }
+    let this_week_tab = build_this_week_tab(&records);
+    mode_stack.add_titled(&this_week_tab, Some("score-this-week"), &tr("This Week"));
+    let symbols_tab = build_symbols_tab(&symbol_stats);
+    mode_stack.add_titled(&symbols_tab, Some("score-symbols"), &tr("Symbols"));
+    let heatmap_tab = build_heatmap_tab(&mistake_heatmap);
+    mode_stack.add_titled(&heatmap_tab, Some("score-heatmap"), &tr("Heatmap"));
+    let habits_tab = build_habits_tab(state.borrow().collect_habits);
+    mode_stack.add_titled(&habits_tab, Some("score-habits"), &tr("Habits"));
+    let friends_tab = build_friends_tab(&records);
+    mode_stack.add_titled(&friends_tab, Some("score-friends"), &tr("Friends"));
+    let trash_tab = build_trash_tab(state, app, &dialog);
+    mode_stack.add_titled(&trash_tab, Some("score-trash"), &tr("Trash"));
+
+    content.append(&filter_bar);
     content.append(&mode_switcher);
     content.append(&mode_stack);
 
@@ -682,7 +3353,8 @@ mod tests {
             time_secs,
             precision_pct,
             rank,
-            date_label: date.to_string(),
+            recorded_at: parse_date_label(date),
+            modifiers: String::new(),
         }
     }
 
@@ -692,7 +3364,10 @@ mod tests {
             segment_level,
             segment_survival,
             time_secs,
-            date_label: date.to_string(),
+            recorded_at: parse_date_label(date),
+            pacing: Pacing::Standard,
+            trio: false,
+            modifiers: String::new(),
         }
     }
 
@@ -700,8 +3375,75 @@ mod tests {
     fn json_roundtrip_preserves_records_content() {
         let records = PlayerRecords {
             classic: vec![mode_record(2, 70, 92, Rank::A, "2026-03-01 10:00")],
-            trio: vec![mode_record(4, 130, 87, Rank::B, "2026-03-01 10:05")],
+            trio: vec![ModeRecord {
+                modifiers: "peek,hint".to_string(),
+                ..mode_record(4, 130, 87, Rank::B, "2026-03-01 10:05")
+            }],
             infinite: vec![infinite_record(11, 4, 1, 220, "2026-03-01 10:10")],
+            weekly: vec![WeeklyRecord {
+                week: "2026-W09".to_string(),
+                time_secs: 180,
+                precision_pct: 81,
+                rank: Rank::B,
+                recorded_at: parse_date_label("2026-03-01 10:15"),
+            }],
+            tournament: vec![TournamentRecord {
+                total_score: 12_480,
+                total_secs: 640,
+                precision_pct: 84,
+                board_scores: vec![1_060, 1_900, 2_680, 3_400, 3_440],
+                recorded_at: parse_date_label("2026-03-01 10:30"),
+            }],
+            gauntlet: vec![GauntletRecord {
+                time_secs: 410,
+                precision_pct: 79,
+                rank: Rank::B,
+                recorded_at: parse_date_label("2026-03-01 11:00"),
+            }],
+            speedrun: vec![SpeedrunRecord {
+                level: 3,
+                time_ms: 95_420,
+                splits_ms: vec![3_100, 7_820, 95_420],
+                recorded_at: parse_date_label("2026-03-01 11:30"),
+            }],
+            incomplete: vec![IncompleteRecord {
+                mode: "trio".to_string(),
+                level: 2,
+                reason: "countdown".to_string(),
+                pairs_cleared: 5,
+                pairs_total: 10,
+                time_secs: 150,
+                recorded_at: parse_date_label("2026-03-01 12:00"),
+            }],
+            sudden_death: vec![SuddenDeathRecord {
+                level: 4,
+                pairs_cleared: 17,
+                pairs_total: 24,
+                time_secs: 140,
+                recorded_at: parse_date_label("2026-03-01 12:30"),
+            }],
+            pattern: vec![PatternRecord {
+                accuracy_pct: 94,
+                longest_pattern: 7,
+                time_secs: 88,
+                recorded_at: parse_date_label("2026-03-01 13:00"),
+            }],
+            nback: vec![NBackRecord {
+                n: 3,
+                position_pct: 86,
+                symbol_pct: 78,
+                trials: 23,
+                time_secs: 58,
+                recorded_at: parse_date_label("2026-03-01 13:30"),
+            }],
+            custom: vec![CustomRecord {
+                name: "Lunch break".to_string(),
+                code: "5x6x3:12:strict:off:1".to_string(),
+                time_secs: 75,
+                precision_pct: 81,
+                rank: Rank::B,
+                recorded_at: parse_date_label("2026-03-01 14:00"),
+            }],
         };
 
         let raw = serialize_json_records(&records);
@@ -716,7 +3458,8 @@ mod tests {
         assert_eq!(classic.time_secs, 70);
         assert_eq!(classic.precision_pct, 92);
         assert!(classic.rank == Rank::A);
-        assert_eq!(classic.date_label, "2026-03-01 10:00");
+        assert!(classic.recorded_at.is_some());
+        assert_eq!(classic.recorded_at, parse_date_label("2026-03-01 10:00"));
 
         let trio = &parsed.trio[0];
         assert_eq!(trio.level, 4);
@@ -729,6 +3472,44 @@ mod tests {
         assert_eq!(infinite.segment_level, 4);
         assert_eq!(infinite.segment_survival, 1);
         assert_eq!(infinite.time_secs, 220);
+
+        let weekly = &parsed.weekly[0];
+        assert_eq!(weekly.week, "2026-W09");
+        assert_eq!(weekly.time_secs, 180);
+        assert!(weekly.rank == Rank::B);
+
+        let tournament = &parsed.tournament[0];
+        assert_eq!(tournament.total_score, 12_480);
+        assert_eq!(tournament.precision_pct, 84);
+        assert_eq!(tournament.board_scores.len(), 5);
+
+        let gauntlet = &parsed.gauntlet[0];
+        assert_eq!(gauntlet.time_secs, 410);
+        assert!(gauntlet.rank == Rank::B);
+
+        let speedrun = &parsed.speedrun[0];
+        assert_eq!(speedrun.time_ms, 95_420);
+        assert_eq!(speedrun.splits_ms, vec![3_100, 7_820, 95_420]);
+
+        let incomplete = &parsed.incomplete[0];
+        assert_eq!(incomplete.reason, "countdown");
+        assert_eq!(incomplete.pairs_cleared, 5);
+
+        let sudden_death = &parsed.sudden_death[0];
+        assert_eq!(sudden_death.pairs_cleared, 17);
+        assert_eq!(sudden_death.pairs_total, 24);
+
+        let pattern = &parsed.pattern[0];
+        assert_eq!(pattern.accuracy_pct, 94);
+        assert_eq!(pattern.longest_pattern, 7);
+
+        let nback = &parsed.nback[0];
+        assert_eq!(nback.n, 3);
+        assert_eq!(nback.symbol_pct, 78);
+
+        let custom = &parsed.custom[0];
+        assert_eq!(custom.code, "5x6x3:12:strict:off:1");
+        assert_eq!(custom.rank, Rank::B);
     }
 
     #[test]
@@ -751,6 +3532,165 @@ mod tests {
         assert_eq!(parsed.classic.len(), 1);
         assert_eq!(parsed.classic[0].level, 2);
         assert!(parsed.classic[0].rank == Rank::A);
+        assert_eq!(
+            format_record_date(parsed.classic[0].recorded_at),
+            format_record_date(parse_date_label("2026-03-01 10:00"))
+        );
+    }
+
+    #[test]
+    fn classic_records_are_bucketed_by_level() {
+        let records = vec![
+            mode_record(1, 40, 100, Rank::S, "2026-03-01 10:00"),
+            mode_record(4, 300, 71, Rank::C, "2026-03-01 10:10"),
+            mode_record(1, 35, 92, Rank::A, "2026-03-01 10:20"),
+        ];
+
+        let easy = mode_records_for_level(&records, 1);
+        assert_eq!(easy.len(), 2);
+        assert!(easy.iter().all(|entry| entry.level == 1));
+        assert_eq!(mode_records_for_level(&records, 4).len(), 1);
+        assert!(mode_records_for_level(&records, 2).is_empty());
+    }
+
+    #[test]
+    fn next_rank_counts_the_mismatches_it_allowed() {
+        assert_eq!(rank_for_precision(2, 73), Rank::C);
+        assert_eq!(next_rank_requirement(2, 8, 73), Some((Rank::B, 80, 2)));
+        assert_eq!(next_rank_requirement(2, 8, 90), Some((Rank::S, 100, 0)));
+        assert_eq!(next_rank_requirement(2, 8, 100), None);
+    }
+
+    #[test]
+    fn rank_requirement_reads_as_whole_sentences() {
+        assert_eq!(
+            rank_requirement_text(2, 8, 3, 73),
+            "Rank B on Medium: 80% precision\nMismatches allowed: 2 (you made 3)\n\
+             Time does not change the rank."
+        );
+    }
+
+    #[test]
+    fn personal_best_needs_a_better_run_on_the_same_level() {
+        let records = vec![
+            mode_record(2, 90, 92, Rank::A, "2026-03-01 10:00"),
+            mode_record(3, 60, 100, Rank::S, "2026-03-01 10:10"),
+        ];
+
+        assert!(beats_personal_best(&records, &mode_record(2, 80, 92, Rank::A, "2026-03-02 10:00")));
+        assert!(!beats_personal_best(&records, &mode_record(2, 70, 85, Rank::B, "2026-03-02 10:00")));
+        assert!(!beats_personal_best(&records, &mode_record(1, 20, 100, Rank::S, "2026-03-02 10:00")));
+    }
+
+    #[test]
+    fn restoring_over_new_runs_keeps_the_newest_within_the_limit() {
+        let run = |level: u8| mode_record(level, 60, 90, Rank::A, "2026-03-01 10:00");
+        let mut records = PlayerRecords {
+            classic: vec![run(4); 3],
+            ..PlayerRecords::default()
+        };
+        records.prepend(PlayerRecords {
+            classic: vec![run(1); MODE_HISTORY_LIMIT],
+            ..PlayerRecords::default()
+        });
+        trim_history(&mut records);
+
+        assert_eq!(records.classic.len(), MODE_HISTORY_LIMIT);
+        assert!(records.classic[MODE_HISTORY_LIMIT - 3..].iter().all(|entry| entry.level == 4));
+    }
+
+    #[test]
+    fn merging_keeps_the_newest_within_the_limit() {
+        let mut records = PlayerRecords {
+            classic: vec![mode_record(4, 60, 90, Rank::A, "2026-03-02 10:00")],
+            ..PlayerRecords::default()
+        };
+        let incoming = PlayerRecords {
+            classic: (0..MODE_HISTORY_LIMIT as u32)
+                .map(|secs| mode_record(1, secs, 90, Rank::A, "2026-03-01 10:00"))
+                .collect(),
+            ..PlayerRecords::default()
+        };
+
+        // The oldest import is trimmed straight away, so it is not counted.
+        assert_eq!(merge_within_limits(&mut records, incoming), MODE_HISTORY_LIMIT - 1);
+        assert_eq!(records.classic.len(), MODE_HISTORY_LIMIT);
+        assert_eq!(records.classic.last().map(|entry| entry.level), Some(4));
+    }
+
+    #[test]
+    fn imports_older_than_a_full_history_add_nothing() {
+        let mut records = PlayerRecords {
+            classic: (0..MODE_HISTORY_LIMIT as u32)
+                .map(|secs| mode_record(4, secs, 90, Rank::A, "2026-03-02 10:00"))
+                .collect(),
+            ..PlayerRecords::default()
+        };
+        let incoming = PlayerRecords {
+            classic: vec![mode_record(1, 60, 90, Rank::A, "2026-03-01 10:00")],
+            ..PlayerRecords::default()
+        };
+
+        assert_eq!(merge_within_limits(&mut records, incoming), 0);
+        assert!(records.classic.iter().all(|entry| entry.level == 4));
+    }
+
+    #[test]
+    fn assisted_runs_stay_out_of_best_runs() {
+        let assisted = InfiniteRecord {
+            modifiers: "assist".to_string(),
+            ..infinite_record(30, 4, 2, 500, "2026-03-01 10:00")
+        };
+        let records = vec![assisted, infinite_record(12, 4, 1, 240, "2026-03-01 10:10")];
+
+        let top = top_infinite_records(&records, Pacing::Standard, false, 3);
+
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].round, 12);
+    }
+
+    #[test]
+    fn infinite_best_runs_keep_pacings_and_trio_apart() {
+        let rush = InfiniteRecord {
+            pacing: Pacing::Rush,
+            ..infinite_record(20, 4, 3, 400, "2026-03-01 10:00")
+        };
+        let trio = InfiniteRecord {
+            trio: true,
+            ..infinite_record(15, 3, 2, 300, "2026-03-01 10:05")
+        };
+        let records = vec![rush, trio, infinite_record(9, 3, 1, 200, "2026-03-01 10:10")];
+
+        let standard = top_infinite_records(&records, Pacing::Standard, false, 3);
+        assert_eq!(standard.iter().map(|entry| entry.round).collect::<Vec<_>>(), vec![9]);
+        let rush = top_infinite_records(&records, Pacing::Rush, false, 3);
+        assert_eq!(rush.iter().map(|entry| entry.round).collect::<Vec<_>>(), vec![20]);
+        let trio = top_infinite_records(&records, Pacing::Standard, true, 3);
+        assert_eq!(trio.iter().map(|entry| entry.round).collect::<Vec<_>>(), vec![15]);
+    }
+
+    #[test]
+    fn weekly_top_only_includes_requested_week() {
+        let weekly = |week: &str, time_secs: u32, rank: Rank| WeeklyRecord {
+            week: week.to_string(),
+            time_secs,
+            precision_pct: 90,
+            rank,
+            recorded_at: None,
+        };
+        let records = vec![
+            weekly("2026-W08", 60, Rank::S),
+            weekly("2026-W09", 200, Rank::A),
+            weekly("2026-W09", 150, Rank::A),
+            weekly("2026-W09", 100, Rank::B),
+        ];
+
+        let top = top_weekly_records(&records, "2026-W09", 3);
+
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].time_secs, 150);
+        assert_eq!(top[1].time_secs, 200);
+        assert_eq!(top[2].time_secs, 100);
     }
 
     #[test]
@@ -0,0 +1,292 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::prelude::*;
+
+use crate::i18n::{tr, tr_format};
+
+use super::challenge;
+use super::nback;
+use super::records::classic_level_name;
+use super::state::{PlayerRecords, Rank};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+// Narrows the score history to a local-date range and to runs whose rank or
+// level name matches every typed word.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HistoryFilter {
+    pub from: Option<i64>,
+    pub until: Option<i64>,
+    pub text: String,
+}
+
+struct RecordTerms {
+    rank: Option<Rank>,
+    labels: Vec<String>,
+}
+
+impl RecordTerms {
+    fn new(rank: Option<Rank>, labels: Vec<String>) -> Self {
+        Self { rank, labels }
+    }
+
+    fn level(rank: Option<Rank>, level: u8) -> Self {
+        Self::new(rank, vec![tr(classic_level_name(level))])
+    }
+
+    // A single letter only matches a rank, so "a" does not also find "Easy".
+    fn matches_word(&self, word: &str) -> bool {
+        let rank_hit = self
+            .rank
+            .is_some_and(|rank| rank.as_str().eq_ignore_ascii_case(word));
+        rank_hit
+            || (word.chars().count() > 1
+                && self
+                    .labels
+                    .iter()
+                    .flat_map(|label| label.split_whitespace())
+                    .any(|label| label.to_lowercase().starts_with(word)))
+    }
+}
+
+impl HistoryFilter {
+    pub fn is_active(&self) -> bool {
+        self.from.is_some() || self.until.is_some() || !self.text.trim().is_empty()
+    }
+
+    fn in_range(&self, recorded_at: Option<i64>) -> bool {
+        if self.from.is_none() && self.until.is_none() {
+            return true;
+        }
+        recorded_at.is_some_and(|ts| {
+            self.from.is_none_or(|from| ts >= from) && self.until.is_none_or(|until| ts < until)
+        })
+    }
+
+    fn keeps(&self, recorded_at: Option<i64>, terms: RecordTerms) -> bool {
+        self.in_range(recorded_at)
+            && self
+                .text
+                .to_lowercase()
+                .split_whitespace()
+                .all(|word| terms.matches_word(word))
+    }
+
+    pub fn apply(&self, records: &PlayerRecords) -> PlayerRecords {
+        if !self.is_active() {
+            return records.clone();
+        }
+        let mut filtered = records.clone();
+        filtered
+            .classic
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(Some(entry.rank), entry.level)));
+        filtered
+            .trio
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(Some(entry.rank), entry.level)));
+        filtered
+            .infinite
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(None, entry.segment_level)));
+        filtered.weekly.retain(|entry| {
+            self.keeps(
+                entry.recorded_at,
+                RecordTerms::new(Some(entry.rank), vec![challenge::week_display_name(&entry.week)]),
+            )
+        });
+        filtered
+            .tournament
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::new(None, Vec::new())));
+        filtered
+            .gauntlet
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::new(Some(entry.rank), Vec::new())));
+        filtered
+            .speedrun
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(None, entry.level)));
+        filtered
+            .sudden_death
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(None, entry.level)));
+        filtered
+            .pattern
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::new(None, Vec::new())));
+        filtered
+            .nback
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::new(None, vec![nback::level_name(entry.n)])));
+        filtered
+            .incomplete
+            .retain(|entry| self.keeps(entry.recorded_at, RecordTerms::level(None, entry.level)));
+        filtered.custom.retain(|entry| {
+            self.keeps(entry.recorded_at, RecordTerms::new(Some(entry.rank), vec![entry.name.clone()]))
+        });
+        filtered
+    }
+}
+
+fn day_start(date: &glib::DateTime) -> Option<i64> {
+    glib::DateTime::from_local(date.year(), date.month(), date.day_of_month(), 0, 0, 0.0)
+        .ok()
+        .map(|start| start.to_unix())
+}
+
+#[derive(Clone, Copy)]
+enum DateBound {
+    From,
+    To,
+}
+
+fn date_button_label(bound: DateBound, date: Option<&glib::DateTime>) -> String {
+    let date = match date.and_then(|date| date.format("%x").ok()) {
+        Some(text) => text.to_string(),
+        None => tr("any date"),
+    };
+    match bound {
+        DateBound::From => tr_format("From {date}", &[("date", &date)]),
+        DateBound::To => tr_format("To {date}", &[("date", &date)]),
+    }
+}
+
+// A button whose popover holds a calendar and a way back to "any date".
+fn build_date_button(
+    bound: DateBound,
+    on_pick: impl Fn(Option<glib::DateTime>) + 'static,
+) -> gtk::MenuButton {
+    let button = gtk::MenuButton::new();
+    button.set_label(&date_button_label(bound, None));
+    let calendar = gtk::Calendar::new();
+    let clear = gtk::Button::with_label(&tr("Any Date"));
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    content.append(&calendar);
+    content.append(&clear);
+    let popover = gtk::Popover::new();
+    popover.set_child(Some(&content));
+    button.set_popover(Some(&popover));
+
+    let on_pick = Rc::new(on_pick);
+    {
+        let button = button.clone();
+        let popover = popover.clone();
+        let on_pick = on_pick.clone();
+        calendar.connect_day_selected(move |calendar| {
+            let date = calendar.date();
+            button.set_label(&date_button_label(bound, Some(&date)));
+            popover.popdown();
+            on_pick(Some(date));
+        });
+    }
+    {
+        let button = button.clone();
+        clear.connect_clicked(move |_| {
+            button.set_label(&date_button_label(bound, None));
+            popover.popdown();
+            on_pick(None);
+        });
+    }
+    button
+}
+
+pub fn build_filter_bar(on_change: impl Fn(&HistoryFilter) + 'static) -> gtk::Box {
+    let filter = Rc::new(RefCell::new(HistoryFilter::default()));
+    let on_change = Rc::new(on_change);
+    let bar = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    bar.add_css_class("score-filter-bar");
+
+    let search = gtk::SearchEntry::new();
+    search.set_placeholder_text(Some(&tr("Filter by rank or level")));
+    search.set_hexpand(true);
+    {
+        let filter = filter.clone();
+        let on_change = on_change.clone();
+        search.connect_search_changed(move |search| {
+            filter.borrow_mut().text = search.text().to_string();
+            on_change(&filter.borrow());
+        });
+    }
+
+    let from_button = {
+        let filter = filter.clone();
+        let on_change = on_change.clone();
+        build_date_button(DateBound::From, move |date| {
+            filter.borrow_mut().from = date.as_ref().and_then(day_start);
+            on_change(&filter.borrow());
+        })
+    };
+    let until_button = build_date_button(DateBound::To, move |date| {
+        filter.borrow_mut().until = date
+            .as_ref()
+            .and_then(day_start)
+            .map(|start| start + SECONDS_PER_DAY);
+        on_change(&filter.borrow());
+    });
+
+    bar.append(&search);
+    bar.append(&from_button);
+    bar.append(&until_button);
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::ModeRecord;
+
+    fn classic(level: u8, rank: Rank, recorded_at: i64) -> ModeRecord {
+        ModeRecord {
+            level,
+            time_secs: 60,
+            precision_pct: 90,
+            rank,
+            recorded_at: Some(recorded_at),
+            modifiers: String::new(),
+        }
+    }
+
+    #[test]
+    fn filters_by_date_range_and_words() {
+        let records = PlayerRecords {
+            classic: vec![
+                classic(1, Rank::A, 1_000),
+                classic(3, Rank::S, 2_000),
+                classic(3, Rank::A, 3_000),
+            ],
+            ..PlayerRecords::default()
+        };
+
+        let ranged = HistoryFilter {
+            from: Some(1_500),
+            until: Some(3_000),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(ranged.apply(&records).classic.len(), 1);
+
+        let by_rank = HistoryFilter {
+            text: "a".to_string(),
+            ..HistoryFilter::default()
+        };
+        assert_eq!(by_rank.apply(&records).classic.len(), 2);
+
+        let by_level_and_rank = HistoryFilter {
+            text: "hard a".to_string(),
+            ..HistoryFilter::default()
+        };
+        let kept = by_level_and_rank.apply(&records).classic;
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].recorded_at, Some(3_000));
+    }
+
+    #[test]
+    fn undated_runs_are_hidden_only_by_a_date_range() {
+        let mut undated = classic(2, Rank::B, 0);
+        undated.recorded_at = None;
+        let records = PlayerRecords {
+            classic: vec![undated],
+            ..PlayerRecords::default()
+        };
+        assert_eq!(HistoryFilter::default().apply(&records).classic.len(), 1);
+        let ranged = HistoryFilter {
+            from: Some(0),
+            ..HistoryFilter::default()
+        };
+        assert!(ranged.apply(&records).classic.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::paths;
+use super::records::RecordsFile;
+use super::state::PlayerRecords;
+
+const TRASH_FILE_NAME: &str = "records_trash.json";
+pub const TRASH_KEEP_DAYS: i64 = 30;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Debug)]
+pub struct TrashedRecords {
+    pub trashed_at: i64,
+    pub records: PlayerRecords,
+}
+
+impl TrashedRecords {
+    pub fn days_left(&self, now: i64) -> i64 {
+        let elapsed_days = (now - self.trashed_at).max(0) / SECONDS_PER_DAY;
+        (TRASH_KEEP_DAYS - elapsed_days).max(0)
+    }
+
+    fn is_expired(&self, now: i64) -> bool {
+        now - self.trashed_at >= TRASH_KEEP_DAYS * SECONDS_PER_DAY
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct TrashEntryWire {
+    trashed_at: i64,
+    records: RecordsFile,
+}
+
+fn trash_path() -> PathBuf {
+    paths::config_dir().join(TRASH_FILE_NAME)
+}
+
+fn parse_trash(raw: &str, now: i64) -> Vec<TrashedRecords> {
+    let entries: Vec<TrashEntryWire> = match serde_json::from_str(raw) {
+        Ok(entries) => entries,
+        Err(err) => {
+            tracing::warn!("failed to parse records trash: {err}");
+            return Vec::new();
+        }
+    };
+    entries
+        .into_iter()
+        .map(|entry| TrashedRecords {
+            trashed_at: entry.trashed_at,
+            records: entry.records.into(),
+        })
+        .filter(|entry| !entry.is_expired(now))
+        .collect()
+}
+
+fn serialize_trash(trash: &[TrashedRecords]) -> serde_json::Result<String> {
+    let entries: Vec<TrashEntryWire> = trash
+        .iter()
+        .map(|entry| TrashEntryWire {
+            trashed_at: entry.trashed_at,
+            records: RecordsFile::from(&entry.records),
+        })
+        .collect();
+    serde_json::to_string(&entries)
+}
+
+// Expired entries are dropped here, so the trash empties itself as it is read.
+pub fn load_trash(now: i64) -> Vec<TrashedRecords> {
+    let Ok(raw) = fs::read_to_string(trash_path()) else {
+        return Vec::new();
+    };
+    parse_trash(&raw, now)
+}
+
+fn save_trash(trash: &[TrashedRecords]) -> io::Result<()> {
+    let path = trash_path();
+    if trash.is_empty() {
+        return match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    paths::write_atomic(&path, &serialize_trash(trash)?)
+}
+
+pub fn move_to_trash(records: PlayerRecords, now: i64) -> io::Result<()> {
+    let mut trash = load_trash(now);
+    trash.push(TrashedRecords {
+        trashed_at: now,
+        records,
+    });
+    save_trash(&trash)
+}
+
+pub fn take_from_trash(trashed_at: i64, now: i64) -> Option<PlayerRecords> {
+    let mut trash = load_trash(now);
+    let pos = trash.iter().position(|entry| entry.trashed_at == trashed_at)?;
+    let entry = trash.remove(pos);
+    if let Err(err) = save_trash(&trash) {
+        tracing::warn!("failed to save records trash: {err}");
+    }
+    Some(entry.records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::{ModeRecord, Rank};
+
+    fn trashed(trashed_at: i64) -> TrashedRecords {
+        TrashedRecords {
+            trashed_at,
+            records: PlayerRecords {
+                classic: vec![ModeRecord {
+                    level: 2,
+                    time_secs: 70,
+                    precision_pct: 92,
+                    rank: Rank::A,
+                    recorded_at: Some(trashed_at - 60),
+                    modifiers: String::new(),
+                }],
+                ..PlayerRecords::default()
+            },
+        }
+    }
+
+    #[test]
+    fn trash_roundtrips_and_drops_expired_entries() {
+        let now = 1_800_000_000;
+        let fresh = trashed(now - 2 * SECONDS_PER_DAY);
+        let stale = trashed(now - TRASH_KEEP_DAYS * SECONDS_PER_DAY);
+        let raw = serialize_trash(&[stale, fresh]).unwrap();
+
+        let trash = parse_trash(&raw, now);
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].days_left(now), TRASH_KEEP_DAYS - 2);
+        assert_eq!(trash[0].records.entry_count(), 1);
+        assert_eq!(trash[0].records.classic[0].recorded_at, Some(now - 2 * SECONDS_PER_DAY - 60));
+    }
+
+    #[test]
+    fn restored_records_go_back_in_front() {
+        let mut current = PlayerRecords {
+            classic: vec![ModeRecord {
+                level: 4,
+                ..ModeRecord::default()
+            }],
+            ..PlayerRecords::default()
+        };
+        current.prepend(trashed(1_000).records);
+
+        let levels: Vec<u8> = current.classic.iter().map(|entry| entry.level).collect();
+        assert_eq!(levels, [2, 4]);
+    }
+}
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+use gtk4 as gtk;
+use gtk4::gio;
+
+use super::debug_tools;
+use super::faces::{self, FACE_ART};
+use super::rank_art;
+use super::user_css;
+
+// Every path the app loads from the bundled gresource is spelled out here, under one prefix.
+macro_rules! bundled {
+    ($path:literal) => {
+        concat!("/io/github/basshift/Recall/", $path)
+    };
+}
+
+pub const ICONS: &str = bundled!("icons");
+pub const ICONS_HICOLOR: &str = bundled!("icons/hicolor");
+pub const STYLESHEETS: [&str; 5] = [
+    bundled!("style.vars.css"),
+    bundled!("style.css"),
+    bundled!("style.light.css"),
+    bundled!("style.dark.css"),
+    bundled!("style.mobile.css"),
+];
+pub const VICTORY_DIR: &str = bundled!("victory");
+pub const FINISH_FLAG_ART: &str = bundled!("victory/finish-flag.svg");
+pub const FACES_DIR: &str = bundled!("faces");
+pub const HOWTO_FLOW: &str = bundled!("howto/01-flow.svg");
+pub const HOWTO_GOAL: &str = bundled!("howto/02-goal.svg");
+pub const HOWTO_MODES: &str = bundled!("howto/03-modes.svg");
+pub const HOWTO_DIFFICULTY: &str = bundled!("howto/04-difficulty.svg");
+pub const HOWTO_RESTLESS: &str = bundled!("howto/05-restless.svg");
+
+// Where the bundled files live in the source tree, for live-reloading while theming.
+const SOURCE_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+
+const STATIC_FILES: [&str; 6] = [
+    FINISH_FLAG_ART,
+    HOWTO_FLOW,
+    HOWTO_GOAL,
+    HOWTO_MODES,
+    HOWTO_DIFFICULTY,
+    HOWTO_RESTLESS,
+];
+
+pub fn register() {
+    static RESOURCES_INIT: Once = Once::new();
+    RESOURCES_INIT.call_once(|| {
+        gio::resources_register_include!("recall.gresource")
+            .expect("failed to register embedded resources");
+        if cfg!(debug_assertions) {
+            let missing = missing_files();
+            assert!(missing.is_empty(), "missing bundled resources: {missing:?}");
+        }
+    });
+}
+
+thread_local! {
+    static LIVE_STYLESHEETS: RefCell<Vec<gio::FileMonitor>> = const { RefCell::new(Vec::new()) };
+}
+
+// With RECALL_DEBUG set, stylesheets are read from data/ and reloaded whenever they are saved.
+pub fn load_stylesheet(provider: &gtk::CssProvider, resource_path: &str) {
+    if debug_tools::debug_mode_enabled()
+        && let Some(path) = source_file(resource_path)
+    {
+        provider.load_from_path(&path);
+        let monitor = user_css::watch(&path, provider, {
+            let path = path.clone();
+            move |provider| {
+                tracing::info!("reloading {}", path.display());
+                provider.load_from_path(&path);
+            }
+        });
+        if let Some(monitor) = monitor {
+            LIVE_STYLESHEETS.with(|monitors| monitors.borrow_mut().push(monitor));
+        }
+        return;
+    }
+    provider.load_from_resource(resource_path);
+}
+
+fn source_file(resource_path: &str) -> Option<PathBuf> {
+    let relative = resource_path.strip_prefix(bundled!(""))?;
+    let path = Path::new(SOURCE_DATA_DIR).join(relative);
+    path.is_file().then_some(path)
+}
+
+pub fn uri(path: &str) -> String {
+    format!("resource://{path}")
+}
+
+pub fn exists(path: &str) -> bool {
+    gio::resources_get_info(path, gio::ResourceLookupFlags::NONE).is_ok()
+}
+
+fn missing_files() -> Vec<String> {
+    STYLESHEETS
+        .iter()
+        .chain(STATIC_FILES.iter())
+        .map(|path| path.to_string())
+        .chain(rank_art::all_resource_paths())
+        .chain(FACE_ART.iter().filter_map(|(symbol, _)| faces::resource_path(symbol)))
+        .filter(|path| !exists(path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_referenced_resource_is_bundled() {
+        register();
+        assert!(missing_files().is_empty());
+        assert!(!exists(bundled!("victory/rank-x.svg")));
+        assert!(STYLESHEETS.iter().all(|path| source_file(path).is_some()));
+    }
+}
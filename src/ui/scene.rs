@@ -1,28 +1,31 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use gtk4 as gtk;
+use gtk4::glib;
 use gtk4::prelude::*;
 
-use super::board::build_board_grid;
-use super::hud::{set_header_menu, set_header_victory, stop_preview, stop_timer};
-use super::session_save;
-use super::state::{AppState, Rank};
+use crate::i18n::{format_duration, format_percent, tr, tr_format};
+
 use super::app::{
-    refresh_board_shell_ratio,
-    refresh_continue_button_state,
-    start_victory_sparks,
-    stop_victory_sparks,
+    advance_gauntlet_board, cancel_all_transients, finish_warmup, refresh_board_shell_ratio,
+    refresh_continue_button_state, save_play_stats, start_victory_sparks, stop_victory_sparks,
 };
-
-fn rank_resource_path(rank: Rank) -> &'static str {
-    match rank {
-        Rank::S => "/io/github/basshift/Recall/victory/rank-s.svg",
-        Rank::A => "/io/github/basshift/Recall/victory/rank-a.svg",
-        Rank::B => "/io/github/basshift/Recall/victory/rank-b.svg",
-        Rank::C => "/io/github/basshift/Recall/victory/rank-c.svg",
-    }
-}
+use super::board::build_board_canvas;
+use super::defeat::{self, DefeatReason};
+use super::events::{self, GameEvent};
+use super::gauntlet::GAUNTLET_SPLASH_MS;
+use super::hud::{set_header_menu, set_header_victory, stop_preview, stop_timer};
+use super::overlay;
+use super::rank_art;
+use super::records::{register_incomplete_run, register_sudden_death_result};
+use super::sequence::Sequence;
+use super::session_recap;
+use super::session_save;
+use super::state::AppState;
+use super::timers;
+use super::toasts::{self, Notice};
+use super::training::{self, TRAINING_REST_SECS};
 
 pub(super) fn rebuild_board(state: &Rc<RefCell<AppState>>) {
     let (board_container, grid_cols, grid_rows) = {
@@ -36,7 +39,7 @@ pub(super) fn rebuild_board(state: &Rc<RefCell<AppState>>) {
     while let Some(child) = board_container.first_child() {
         board_container.remove(&child);
     }
-    let grid = build_board_grid(state);
+    let canvas = build_board_canvas(state);
     let grid_ratio = if grid_rows > 0 {
         grid_cols as f32 / grid_rows as f32
     } else {
@@ -47,14 +50,17 @@ pub(super) fn rebuild_board(state: &Rc<RefCell<AppState>>) {
     grid_frame.set_valign(gtk::Align::Fill);
     grid_frame.set_hexpand(true);
     grid_frame.set_vexpand(true);
-    grid_frame.set_child(Some(&grid));
+    grid_frame.set_child(Some(&canvas));
     board_container.append(&grid_frame);
     refresh_board_shell_ratio(state);
+    events::emit(GameEvent::BoardChanged);
 }
 
 pub(super) fn show_victory(state: &Rc<RefCell<AppState>>) {
-    let is_s_rank = {
-        let st = state.borrow();
+    let celebration = {
+        let mut st = state.borrow_mut();
+        st.game_span.in_scope(|| tracing::info!(rank = ?st.victory_rank, "won"));
+        st.game_span = tracing::Span::none();
         if let Some(label) = &st.victory_title_label {
             label.set_text(&st.victory_title_text);
         }
@@ -64,47 +70,299 @@ pub(super) fn show_victory(state: &Rc<RefCell<AppState>>) {
         if let Some(label) = &st.victory_stats_label {
             label.set_text(&st.victory_stats_text);
         }
+        let new_record = std::mem::take(&mut st.victory_new_record) && !st.run_assisted();
+        if let Some(banner) = &st.victory_record_banner {
+            banner.set_visible(new_record);
+        }
+        let goal_text = st.victory_goal_text.take();
+        if let Some(label) = &st.victory_goal_label {
+            label.set_text(goal_text.as_deref().unwrap_or_default());
+            label.set_visible(goal_text.is_some());
+        }
+        if let Some(button) = &st.victory_again_button {
+            let series_in_progress = st
+                .tournament()
+                .is_some_and(|tournament| !tournament.is_complete());
+            let training_continues = st.training().is_some_and(|session| !session.is_complete());
+            let turns_left = st.hotseat.as_ref().is_some_and(|run| !run.is_complete());
+            let matches_left = st
+                .bracket
+                .as_ref()
+                .is_some_and(|bracket| bracket.champion().is_none());
+            button.set_label(&if training_continues {
+                tr("Rest")
+            } else if turns_left {
+                tr("Next Player")
+            } else if matches_left {
+                tr("Next Match")
+            } else if series_in_progress {
+                tr("Next Board")
+            } else {
+                tr("Play Again")
+            });
+        }
         let rank = st.victory_rank;
         if let Some(image) = &st.victory_rank_art {
             if let Some(custom_resource) = &st.victory_art_resource {
                 image.set_resource(Some(custom_resource));
             } else {
-                image.set_resource(Some(rank_resource_path(rank)));
+                image.set_resource(Some(&rank_art::rank_resource_path(&st, rank)));
             }
-            image.set_visible(true);
+            // Assisted and kids runs have no rank to show.
+            image.set_visible(!st.run_assisted() && st.kids.is_none());
         }
-        rank == Rank::S
+        let rank_hint = st.victory_rank_hint.take();
+        if let Some(label) = &st.victory_rank_info_label {
+            label.set_text(rank_hint.as_deref().unwrap_or_default());
+        }
+        if let Some(button) = &st.victory_rank_info {
+            button.set_visible(rank_hint.is_some());
+        }
+        (!st.run_assisted()).then_some(new_record)
     };
     set_header_victory(state);
-    if is_s_rank {
-        start_victory_sparks(state);
+    if let Some(new_record) = celebration {
+        start_victory_sparks(state, new_record);
     } else {
         let mut st = state.borrow_mut();
         stop_victory_sparks(&mut st);
     }
-    let st = state.borrow();
-    if let Some(stack) = &st.view_stack {
-        stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
-        stack.set_visible_child_name("victory");
+    {
+        let st = state.borrow();
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
+            stack.set_visible_child_name("victory");
+        }
     }
+    training::present_report_if_done(state);
 }
 
-pub(super) fn show_menu(state: &Rc<RefCell<AppState>>) {
+pub(super) fn show_defeat(state: &Rc<RefCell<AppState>>, reason: DefeatReason) {
     {
         let mut st = state.borrow_mut();
-        if st.active_session_started
-            && let Err(err) = session_save::save_current_run(&st)
-        {
-            eprintln!("warning: failed to save current run: {err}");
-        }
-        // Invalidate pending async callbacks from in-game transitions.
+        st.game_span.in_scope(|| tracing::info!(?reason, "lost"));
+        st.game_span = tracing::Span::none();
+        stop_timer(&mut st);
+        stop_preview(&mut st);
         st.invalidate_callbacks();
         st.lock_input = false;
         st.flipped_indices.clear();
+        if reason == DefeatReason::SuddenDeath {
+            register_sudden_death_result(&mut st);
+        } else {
+            register_incomplete_run(&mut st, reason);
+        }
+        session_recap::note_board(&mut st, None);
+        save_play_stats(&st);
+        training::note_game(&mut st, false);
+        if let Some(button) = &st.defeat_retry_button {
+            let training_continues = st.training().is_some_and(|session| !session.is_complete());
+            button.set_label(&if training_continues {
+                tr("Rest")
+            } else {
+                tr("Try Again")
+            });
+        }
+        st.active_session_started = false;
+        session_save::clear_saved_run();
+        refresh_continue_button_state(&st);
+
+        let (pairs_cleared, pairs_total) = defeat::pairs_cleared(&st);
+        if let Some(label) = &st.defeat_title_label {
+            label.set_text(&tr(reason.title()));
+        }
+        if let Some(label) = &st.defeat_message_label {
+            label.set_text(&defeat::defeat_message(reason, &st));
+        }
+        if let Some(label) = &st.defeat_stats_label {
+            label.set_text(&format!(
+                "{}: {}/{}\n{}: {}\n{}: {}",
+                tr("Pairs"),
+                pairs_cleared,
+                pairs_total,
+                tr("Mismatches"),
+                st.run_mismatches,
+                tr("Time"),
+                format_duration(st.seconds_elapsed)
+            ));
+        }
+    }
+    set_header_victory(state);
+    {
+        let st = state.borrow();
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::SlideLeft);
+            stack.set_visible_child_name("defeat");
+        }
+    }
+    training::present_report_if_done(state);
+}
+
+pub(super) fn show_gauntlet_splash(state: &Rc<RefCell<AppState>>) {
+    let Some(gauntlet) = state.borrow().gauntlet() else {
+        return;
+    };
+    show_between_boards_splash(
+        state,
+        &tr_format(
+            "Board {index}/{count}",
+            &[
+                ("index", &(gauntlet.board_index + 1)),
+                ("count", &gauntlet.board_count()),
+            ],
+        ),
+        &tr(gauntlet.current_difficulty().name()),
+        advance_gauntlet_board,
+    );
+}
+
+pub(super) fn show_warmup_splash(state: &Rc<RefCell<AppState>>) {
+    let Some(target) = state.borrow().warmup_target else {
+        return;
+    };
+    show_between_boards_splash(
+        state,
+        &tr("Warm-up done"),
+        &tr_format("Up next: {mode}", &[("mode", &tr(target.name()))]),
+        finish_warmup,
+    );
+}
+
+fn splash_is_showing(st: &AppState, game_id: u64) -> bool {
+    st.game_id == game_id
+        && st
+            .view_stack
+            .as_ref()
+            .and_then(|stack| stack.visible_child_name())
+            .as_deref()
+            == Some("splash")
+}
+
+// The rest counts down on the splash screen; the player can skip it at any time.
+pub(super) fn show_training_rest(
+    state: &Rc<RefCell<AppState>>,
+    advance: fn(&Rc<RefCell<AppState>>),
+) {
+    let game_id = {
+        let st = state.borrow();
+        let Some(session) = st.training() else {
+            return;
+        };
+        if let Some(label) = &st.splash_title_label {
+            label.set_text(&tr("Rest"));
+        }
+        if let Some(label) = &st.splash_subtitle_label {
+            label.set_text(&tr_format(
+                "Up next: {mode}",
+                &[("mode", &session.progress_label())],
+            ));
+        }
+        if let Some(label) = &st.splash_stats_label {
+            label.set_text(&format_duration(TRAINING_REST_SECS));
+        }
+        if let Some(button) = &st.splash_skip_button {
+            button.set_visible(true);
+        }
+        training::refresh_progress(&st);
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+            stack.set_visible_child_name("splash");
+        }
+        st.game_id
+    };
+
+    let remaining = Cell::new(TRAINING_REST_SECS);
+    let state = Rc::downgrade(state);
+    timers::every(std::time::Duration::from_secs(1), move || {
+        let Some(state) = state.upgrade() else {
+            return glib::ControlFlow::Break;
+        };
+        let st = state.borrow();
+        if !splash_is_showing(&st, game_id) || st.training().is_none() {
+            return glib::ControlFlow::Break;
+        }
+        remaining.set(remaining.get().saturating_sub(1));
+        if let Some(label) = &st.splash_stats_label {
+            label.set_text(&format_duration(remaining.get()));
+        }
+        if remaining.get() > 0 {
+            return glib::ControlFlow::Continue;
+        }
+        drop(st);
+        advance(&state);
+        glib::ControlFlow::Break
+    });
+}
+
+fn show_between_boards_splash(
+    state: &Rc<RefCell<AppState>>,
+    title: &str,
+    subtitle: &str,
+    advance: fn(&Rc<RefCell<AppState>>),
+) {
+    let game_id = {
+        let st = state.borrow();
+        if let Some(button) = &st.splash_skip_button {
+            button.set_visible(false);
+        }
+        if let Some(label) = &st.splash_title_label {
+            label.set_text(title);
+        }
+        if let Some(label) = &st.splash_subtitle_label {
+            label.set_text(subtitle);
+        }
+        if let Some(label) = &st.splash_stats_label {
+            let attempts = st.run_matches.saturating_add(st.run_mismatches);
+            let precision_pct = if attempts == 0 {
+                100
+            } else {
+                ((st.run_matches as f64 / attempts as f64) * 100.0).round() as u32
+            };
+            label.set_text(&tr_format(
+                "Time {time} · Precision {precision}",
+                &[
+                    ("time", &format_duration(st.seconds_elapsed)),
+                    ("precision", &format_percent(precision_pct)),
+                ],
+            ));
+        }
+        if let Some(stack) = &st.view_stack {
+            stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+            stack.set_visible_child_name("splash");
+        }
+        st.game_id
+    };
+
+    Sequence::new(state, game_id)
+        .only_while(move |st| splash_is_showing(st, game_id))
+        .at(GAUNTLET_SPLASH_MS, move |state| {
+            advance(state);
+            glib::ControlFlow::Break
+        })
+        .start();
+}
+
+pub(super) fn show_menu(state: &Rc<RefCell<AppState>>) {
+    {
+        let mut st = state.borrow_mut();
+        if st.active_session_started {
+            save_play_stats(&st);
+            match session_save::save_current_run(&st) {
+                Ok(()) => toasts::notify(&st, Notice::RunSaved),
+                Err(err) => tracing::warn!("failed to save current run: {err}"),
+            }
+        }
+        st.game_span = tracing::Span::none();
+        // Invalidate pending async callbacks from in-game transitions.
         stop_timer(&mut st);
-        stop_preview(&mut st);
-        stop_victory_sparks(&mut st);
+        cancel_all_transients(&mut st);
         refresh_continue_button_state(&st);
+        // Leaving for the menu abandons a training session.
+        if st.training().is_some() {
+            st.series = None;
+        }
+        session_recap::refresh_recap_card(&st);
+        overlay::refresh(&st);
     }
     set_header_menu(state);
     let st = state.borrow();
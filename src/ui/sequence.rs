@@ -0,0 +1,299 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use gtk4::glib;
+
+use super::perf_hud::{self, PendingKind};
+use super::state::AppState;
+use super::timers::{self, Timer};
+
+type Step = Box<dyn FnOnce(&Rc<RefCell<AppState>>) -> glib::ControlFlow>;
+type Guard = Box<dyn Fn(&AppState) -> bool>;
+
+struct Cue {
+    offset_ms: u64,
+    step: Option<Step>,
+    source: Option<Timer>,
+}
+
+// Timed steps for one game. Every step is dropped once the game changes, a guard
+// fails or a step returns Break, so callers no longer re-check game_id by hand.
+pub struct Sequence {
+    state: Rc<RefCell<AppState>>,
+    game_id: u64,
+    guard: Option<Guard>,
+    cues: Vec<Cue>,
+    cursor_ms: u64,
+}
+
+struct Runner {
+    state: Weak<RefCell<AppState>>,
+    game_id: u64,
+    guard: Option<Guard>,
+    cues: RefCell<Vec<Cue>>,
+    finished: Cell<bool>,
+    pending: RefCell<Option<perf_hud::Pending>>,
+}
+
+#[derive(Clone)]
+pub struct SequenceHandle(Rc<Runner>);
+
+impl Sequence {
+    pub fn new(state: &Rc<RefCell<AppState>>, game_id: u64) -> Self {
+        Self {
+            state: state.clone(),
+            game_id,
+            guard: None,
+            cues: Vec::new(),
+            cursor_ms: 0,
+        }
+    }
+
+    pub fn only_while(mut self, guard: impl Fn(&AppState) -> bool + 'static) -> Self {
+        self.guard = Some(Box::new(guard));
+        self
+    }
+
+    // Runs `step` at an absolute offset from start(), for steps that overlap.
+    pub fn at(
+        mut self,
+        offset_ms: u64,
+        step: impl FnOnce(&Rc<RefCell<AppState>>) -> glib::ControlFlow + 'static,
+    ) -> Self {
+        self.cursor_ms = offset_ms;
+        self.cues.push(Cue {
+            offset_ms,
+            step: Some(Box::new(step)),
+            source: None,
+        });
+        self
+    }
+
+    // Runs `step` `delay_ms` after the previously added one.
+    pub fn then_after(
+        self,
+        delay_ms: u64,
+        step: impl FnOnce(&Rc<RefCell<AppState>>) -> glib::ControlFlow + 'static,
+    ) -> Self {
+        let offset_ms = self.cursor_ms + delay_ms;
+        self.at(offset_ms, step)
+    }
+
+    pub fn start(self) -> SequenceHandle {
+        let runner = Rc::new(Runner {
+            state: Rc::downgrade(&self.state),
+            game_id: self.game_id,
+            guard: self.guard,
+            cues: RefCell::new(self.cues),
+            finished: Cell::new(false),
+            pending: RefCell::new(Some(perf_hud::Pending::new(PendingKind::Sequence))),
+        });
+        let offsets: Vec<u64> = runner.cues.borrow().iter().map(|cue| cue.offset_ms).collect();
+        // Pending cues own the runner, so fire-and-forget sequences still play out;
+        // cancelling removes the sources and with them the last references.
+        for (idx, offset_ms) in offsets.into_iter().enumerate() {
+            let cue_runner = runner.clone();
+            let source = timers::once(std::time::Duration::from_millis(offset_ms), move || {
+                cue_runner.cues.borrow_mut()[idx].source = None;
+                cue_runner.fire(idx);
+            });
+            runner.cues.borrow_mut()[idx].source = Some(source);
+        }
+        SequenceHandle(runner)
+    }
+
+    #[cfg(test)]
+    fn offsets(&self) -> Vec<u64> {
+        self.cues.iter().map(|cue| cue.offset_ms).collect()
+    }
+}
+
+impl Runner {
+    fn live_state(&self) -> Option<Rc<RefCell<AppState>>> {
+        if self.finished.get() {
+            return None;
+        }
+        let state = self.state.upgrade()?;
+        let valid = {
+            let st = state.borrow();
+            st.game_id == self.game_id && self.guard.as_ref().is_none_or(|guard| guard(&st))
+        };
+        valid.then_some(state)
+    }
+
+    fn fire(&self, idx: usize) {
+        let step = self.cues.borrow_mut()[idx].step.take();
+        let Some(step) = step else {
+            return;
+        };
+        let Some(state) = self.live_state() else {
+            self.cancel();
+            return;
+        };
+        if step(&state) == glib::ControlFlow::Break {
+            self.cancel();
+        } else if self.cues.borrow().iter().all(|cue| cue.step.is_none()) {
+            self.pending.take();
+        }
+    }
+
+    fn cancel(&self) {
+        self.finished.set(true);
+        self.pending.take();
+        for cue in self.cues.borrow_mut().iter_mut() {
+            cue.step = None;
+            if let Some(source) = cue.source.take() {
+                source.remove();
+            }
+        }
+    }
+}
+
+impl SequenceHandle {
+    pub fn cancel(&self) {
+        self.0.cancel();
+    }
+
+    // Runs every remaining step right away, in timeline order.
+    pub fn skip(&self) {
+        let mut order: Vec<(u64, usize)> = self
+            .0
+            .cues
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(idx, cue)| {
+                let source = cue.source.take()?;
+                source.remove();
+                Some((cue.offset_ms, idx))
+            })
+            .collect();
+        order.sort();
+        for (_, idx) in order {
+            self.0.fire(idx);
+        }
+    }
+}
+
+// A single delayed step for one game. Like a Sequence it is dropped once the
+// game changes, and it holds the state weakly so it never outlives the window.
+pub fn after(
+    state: &Rc<RefCell<AppState>>,
+    game_id: u64,
+    delay_ms: u64,
+    step: impl FnOnce(&Rc<RefCell<AppState>>) + 'static,
+) -> Timer {
+    let state = Rc::downgrade(state);
+    let pending = perf_hud::Pending::new(PendingKind::Sequence);
+    timers::once(std::time::Duration::from_millis(delay_ms), move || {
+        let _pending = pending;
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        if state.borrow().game_id != game_id {
+            return;
+        }
+        step(&state);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::timers::fake::FakeClock;
+
+    #[test]
+    fn then_after_chains_from_the_previous_cue() {
+        let state = Rc::new(RefCell::new(AppState::default()));
+        let sequence = Sequence::new(&state, 0)
+            .at(100, |_| glib::ControlFlow::Continue)
+            .then_after(50, |_| glib::ControlFlow::Continue)
+            .then_after(25, |_| glib::ControlFlow::Continue)
+            .at(20, |_| glib::ControlFlow::Continue)
+            .then_after(10, |_| glib::ControlFlow::Continue);
+
+        assert_eq!(sequence.offsets(), vec![100, 150, 175, 20, 30]);
+    }
+
+    #[test]
+    fn a_dropped_handle_still_plays_to_the_last_step() {
+        let clock = FakeClock::install();
+        let state = Rc::new(RefCell::new(AppState::default()));
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        let step = |fired: &Rc<RefCell<Vec<u32>>>, label: u32| {
+            let fired = fired.clone();
+            move |_: &Rc<RefCell<AppState>>| {
+                fired.borrow_mut().push(label);
+                glib::ControlFlow::Continue
+            }
+        };
+        drop(
+            Sequence::new(&state, 0)
+                .at(100, step(&fired, 1))
+                .then_after(200, step(&fired, 2))
+                .then_after(300, step(&fired, 3))
+                .start(),
+        );
+
+        clock.advance(299);
+        assert_eq!(*fired.borrow(), vec![1]);
+        clock.advance(1);
+        assert_eq!(*fired.borrow(), vec![1, 2]);
+        clock.advance(300);
+        assert_eq!(*fired.borrow(), vec![1, 2, 3]);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn steps_stop_once_the_game_changes() {
+        let clock = FakeClock::install();
+        let state = Rc::new(RefCell::new(AppState::default()));
+        let fired = Rc::new(Cell::new(0));
+        let step = |fired: &Rc<Cell<u32>>| {
+            let fired = fired.clone();
+            move |_: &Rc<RefCell<AppState>>| {
+                fired.set(fired.get() + 1);
+                glib::ControlFlow::Continue
+            }
+        };
+        Sequence::new(&state, 0)
+            .at(100, step(&fired))
+            .then_after(100, step(&fired))
+            .start();
+
+        clock.advance(150);
+        assert_eq!(fired.get(), 1);
+        state.borrow_mut().game_id = 1;
+        clock.advance(500);
+        assert_eq!(fired.get(), 1);
+        assert_eq!(clock.pending(), 0);
+    }
+
+    #[test]
+    fn after_runs_once_for_the_same_game_and_window() {
+        let clock = FakeClock::install();
+        let state = Rc::new(RefCell::new(AppState::default()));
+        let fired = Rc::new(Cell::new(0));
+        let step = |fired: &Rc<Cell<u32>>| {
+            let fired = fired.clone();
+            move |_: &Rc<RefCell<AppState>>| fired.set(fired.get() + 1)
+        };
+
+        after(&state, 0, 100, step(&fired));
+        clock.advance(99);
+        assert_eq!(fired.get(), 0);
+        clock.advance(1);
+        assert_eq!(fired.get(), 1);
+
+        after(&state, 0, 100, step(&fired));
+        state.borrow_mut().game_id = 1;
+        clock.advance(100);
+        assert_eq!(fired.get(), 1);
+
+        after(&state, 1, 100, step(&fired));
+        drop(state);
+        clock.advance(100);
+        assert_eq!(fired.get(), 1);
+        assert_eq!(clock.pending(), 0);
+    }
+}
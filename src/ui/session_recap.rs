@@ -0,0 +1,157 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+use crate::i18n::{format_duration, format_percent, ntr_format, tr};
+
+use super::state::{AppState, Rank};
+use super::warmup;
+
+// A couple of boards is just a game; the recap waits until it reads like a session.
+pub const RECAP_MIN_BOARDS: u32 = 3;
+const RANKS_SHOWN: [Rank; 4] = [Rank::S, Rank::A, Rank::B, Rank::C];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SessionTally {
+    pub boards: u32,
+    pub total_secs: u32,
+    precision_sum: u32,
+    ranks: [u32; 4],
+}
+
+impl SessionTally {
+    pub fn record_board(&mut self, secs: u32, precision_pct: u8, rank: Option<Rank>) {
+        self.boards = self.boards.saturating_add(1);
+        self.total_secs = self.total_secs.saturating_add(secs);
+        self.precision_sum = self.precision_sum.saturating_add(u32::from(precision_pct));
+        if let Some(rank) = rank {
+            self.ranks[rank as usize] = self.ranks[rank as usize].saturating_add(1);
+        }
+    }
+
+    pub fn average_precision_pct(&self) -> u8 {
+        if self.boards == 0 {
+            return 0;
+        }
+        (self.precision_sum as f64 / self.boards as f64).round() as u8
+    }
+
+    pub fn rank_count(&self, rank: Rank) -> u32 {
+        self.ranks[rank as usize]
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.boards >= RECAP_MIN_BOARDS
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = ntr_format(
+            "{count} board · {time} · {precision} precision",
+            "{count} boards · {time} · {precision} precision",
+            self.boards,
+            &[
+                ("time", &format_duration(self.total_secs)),
+                ("precision", &format_percent(self.average_precision_pct())),
+            ],
+        );
+        let ranks: Vec<String> = RANKS_SHOWN
+            .iter()
+            .filter(|rank| self.rank_count(**rank) > 0)
+            .map(|rank| format!("{} ×{}", rank.as_str(), self.rank_count(*rank)))
+            .collect();
+        if !ranks.is_empty() {
+            summary = format!("{}\n{}", summary, ranks.join("  "));
+        }
+        summary
+    }
+}
+
+// Called once per finished board; warm-ups stay out of the tally like they stay out of records.
+pub fn note_board(st: &mut AppState, rank: Option<Rank>) {
+    if warmup::is_warming_up(st) {
+        return;
+    }
+    let attempts = st.run_matches.saturating_add(st.run_mismatches);
+    let precision_pct = if attempts == 0 {
+        100
+    } else {
+        ((st.run_matches as f64 / attempts as f64) * 100.0).round() as u8
+    };
+    let secs = st.seconds_elapsed;
+    st.session_tally.record_board(secs, precision_pct, rank);
+}
+
+pub fn build_recap_card(state: &Rc<RefCell<AppState>>) -> gtk::Box {
+    let card = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    card.add_css_class("card");
+    card.add_css_class("session-recap");
+    card.set_halign(gtk::Align::Center);
+    card.set_visible(false);
+
+    let text = gtk::Box::new(gtk::Orientation::Vertical, 4);
+    text.set_hexpand(true);
+    let title = gtk::Label::new(Some(&tr("This Session")));
+    title.add_css_class("heading");
+    title.set_halign(gtk::Align::Start);
+    let summary = gtk::Label::new(None);
+    summary.add_css_class("numeric");
+    summary.set_halign(gtk::Align::Start);
+    summary.set_xalign(0.0);
+    text.append(&title);
+    text.append(&summary);
+
+    let dismiss = gtk::Button::from_icon_name("window-close-symbolic");
+    dismiss.add_css_class("flat");
+    dismiss.add_css_class("circular");
+    dismiss.set_valign(gtk::Align::Start);
+    dismiss.set_tooltip_text(Some(&tr("Dismiss")));
+    dismiss.connect_clicked({
+        let state = state.clone();
+        move |_| {
+            let mut st = state.borrow_mut();
+            // Dismissing starts a fresh session, so the next recap covers new boards only.
+            st.session_tally = SessionTally::default();
+            refresh_recap_card(&st);
+        }
+    });
+
+    card.append(&text);
+    card.append(&dismiss);
+    state.borrow_mut().session_recap_label = Some(summary);
+    card
+}
+
+pub fn refresh_recap_card(st: &AppState) {
+    let Some(card) = &st.session_recap_card else {
+        return;
+    };
+    let due = st.session_tally.is_due();
+    if due && let Some(label) = &st.session_recap_label {
+        label.set_text(&st.session_tally.summary());
+    }
+    card.set_visible(due);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tally_averages_precision_and_counts_ranks() {
+        let mut tally = SessionTally::default();
+        tally.record_board(60, 100, Some(Rank::S));
+        tally.record_board(90, 81, Some(Rank::B));
+        assert!(!tally.is_due());
+        tally.record_board(30, 70, None);
+
+        assert!(tally.is_due());
+        assert_eq!(tally.total_secs, 180);
+        assert_eq!(tally.average_precision_pct(), 84);
+        assert_eq!(tally.rank_count(Rank::S), 1);
+        assert_eq!(tally.rank_count(Rank::A), 0);
+        assert_eq!(tally.rank_count(Rank::B), 1);
+        assert_eq!(tally.summary(), "3 boards · 03:00 · 84% precision\nS ×1  B ×1");
+    }
+}
@@ -1,10 +1,25 @@
 use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
+use gtk4::glib;
+
+use super::challenge::Challenge;
 use super::state::{AppState, Difficulty, Tile, TileStatus};
+use super::gauntlet::GauntletRun;
+use super::bracket::Bracket;
+use super::hotseat::HotseatRun;
+use super::infinite::Pacing;
+use super::custom::CustomPreset;
+use super::kids::KidsBoard;
+use super::paths;
+use super::perf_hud;
+use super::tags::TileTag;
+use super::tournament::TournamentRun;
 
 const SAVE_FILE_NAME: &str = "last_run.v1";
+const BOARD_DUMP_DIR_NAME: &str = "board-dumps";
 const SAVE_VERSION: u8 = 1;
 
 #[derive(Clone)]
@@ -13,6 +28,18 @@ pub struct SavedRun {
     pub trio_level: u8,
     pub infinite_level: u8,
     pub infinite_round: u32,
+    pub infinite_pacing: Pacing,
+    pub challenge: Option<Challenge>,
+    pub tournament: Option<TournamentRun>,
+    pub hotseat: Option<HotseatRun>,
+    pub bracket: Option<Bracket>,
+    pub gauntlet: Option<GauntletRun>,
+    pub speedrun: bool,
+    pub sudden_death: bool,
+    pub modifiers: Vec<String>,
+    pub kids: Option<KidsBoard>,
+    pub custom: Option<CustomPreset>,
+    pub match_times_ms: Vec<u64>,
     pub seconds_elapsed: u32,
     pub run_mismatches: u32,
     pub run_matches: u32,
@@ -21,11 +48,21 @@ pub struct SavedRun {
     pub impossible_last_first_index: Option<usize>,
     pub impossible_same_first_streak: u8,
     pub flipped_indices: Vec<usize>,
+    // Missing from saves written before boards kept their seed.
+    pub seed: Option<u64>,
     pub tiles: Vec<Tile>,
 }
 
 fn save_path() -> Option<PathBuf> {
-    Some(glib::user_config_dir().join("recall").join(SAVE_FILE_NAME))
+    Some(paths::data_dir().join(SAVE_FILE_NAME))
+}
+
+// Older versions kept the saved run with the preferences; runs once at startup.
+pub fn migrate_saved_run() {
+    let path = paths::data_dir().join(SAVE_FILE_NAME);
+    if let Err(err) = paths::migrate_file(&paths::config_dir().join(SAVE_FILE_NAME), &path) {
+        tracing::warn!("failed to move saved run to the data directory: {err}");
+    }
 }
 
 fn difficulty_to_code(difficulty: Difficulty) -> &'static str {
@@ -36,6 +73,7 @@ fn difficulty_to_code(difficulty: Difficulty) -> &'static str {
         Difficulty::Impossible => "impossible",
         Difficulty::Trio => "trio",
         Difficulty::Infinite => "infinite",
+        Difficulty::InfiniteTrio => "infinite_trio",
     }
 }
 
@@ -47,6 +85,7 @@ fn difficulty_from_code(code: &str) -> Option<Difficulty> {
         "impossible" => Some(Difficulty::Impossible),
         "trio" | "tri" => Some(Difficulty::Trio),
         "infinite" | "recall" => Some(Difficulty::Infinite),
+        "infinite_trio" => Some(Difficulty::InfiniteTrio),
         _ => None,
     }
 }
@@ -110,29 +149,48 @@ fn split_escaped_pair(raw: &str) -> Option<(String, String)> {
 }
 
 fn encode_tile(tile: &Tile) -> String {
+    // A lowercase code marks a hidden tile the player has already turned over,
+    // a number counts how often it was turned over and a last letter is its tag.
     let status = match tile.status {
+        TileStatus::Hidden if tile.seen => 'h',
         TileStatus::Hidden => 'H',
         TileStatus::Flipped => 'F',
         TileStatus::Matched => 'M',
     };
-    format!("{}|{}", status, escape_value(&tile.value))
+    let mut code = status.to_string();
+    if tile.views > 0 {
+        code.push_str(&tile.views.to_string());
+    }
+    if let Some(tag) = tile.tag {
+        code.push(tag.code());
+    }
+    format!("{}|{}", code, escape_value(&tile.value))
 }
 
 fn parse_tile(raw: &str) -> Option<Tile> {
     let (status_code, value_code) = split_escaped_pair(raw)?;
     let mut status_chars = status_code.chars();
-    let status = match status_chars.next()? {
-        'H' => TileStatus::Hidden,
-        'F' => TileStatus::Flipped,
-        'M' => TileStatus::Matched,
+    let (status, seen) = match status_chars.next()? {
+        'H' => (TileStatus::Hidden, false),
+        'h' => (TileStatus::Hidden, true),
+        'F' => (TileStatus::Flipped, true),
+        'M' => (TileStatus::Matched, true),
         _ => return None,
     };
-    if status_chars.next().is_some() {
-        return None;
-    }
+    let rest = status_chars.as_str();
+    let tag = rest.chars().last().and_then(TileTag::from_code);
+    let views_code = if tag.is_some() { &rest[..rest.len() - 1] } else { rest };
+    let views = if views_code.is_empty() {
+        0
+    } else {
+        views_code.parse().ok()?
+    };
     Some(Tile {
         status,
         value: unescape_value(&value_code),
+        seen,
+        views,
+        tag,
     })
 }
 
@@ -144,6 +202,46 @@ fn serialize_saved_run(run: &SavedRun) -> String {
     out.push_str(&format!("trio_level={}\n", run.trio_level));
     out.push_str(&format!("infinite_level={}\n", run.infinite_level));
     out.push_str(&format!("infinite_round={}\n", run.infinite_round));
+    out.push_str(&format!("infinite_pacing={}\n", run.infinite_pacing.code()));
+    if let Some(challenge) = &run.challenge {
+        out.push_str(&format!("challenge={}\n", challenge.to_code()));
+    }
+    if let Some(tournament) = &run.tournament {
+        out.push_str(&format!("tournament={}\n", tournament.to_code()));
+    }
+    if let Some(hotseat) = &run.hotseat {
+        out.push_str(&format!("hotseat={}\n", hotseat.to_code()));
+    }
+    if let Some(bracket) = &run.bracket {
+        out.push_str(&format!("bracket={}\n", bracket.to_code()));
+    }
+    if let Some(gauntlet) = run.gauntlet {
+        out.push_str(&format!("gauntlet={}\n", gauntlet.to_code()));
+    }
+    if run.speedrun {
+        out.push_str("speedrun=1\n");
+    }
+    if run.sudden_death {
+        out.push_str("sudden_death=1\n");
+    }
+    if !run.modifiers.is_empty() {
+        out.push_str(&format!("modifiers={}\n", run.modifiers.join(",")));
+    }
+    if let Some(board) = run.kids {
+        out.push_str(&format!("kids={}\n", board.code()));
+    }
+    if let Some(custom) = &run.custom {
+        out.push_str(&format!("custom={}\n", custom.to_line()));
+    }
+    if !run.match_times_ms.is_empty() {
+        let match_times_text = run
+            .match_times_ms
+            .iter()
+            .map(|ms| ms.to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        out.push_str(&format!("match_times_ms={}\n", match_times_text));
+    }
     out.push_str(&format!("seconds_elapsed={}\n", run.seconds_elapsed));
     out.push_str(&format!("run_mismatches={}\n", run.run_mismatches));
     out.push_str(&format!("run_matches={}\n", run.run_matches));
@@ -172,6 +270,9 @@ fn serialize_saved_run(run: &SavedRun) -> String {
         .collect::<Vec<String>>()
         .join(",");
     out.push_str(&format!("flipped_indices={}\n", flipped_text));
+    if let Some(seed) = run.seed {
+        out.push_str(&format!("seed={}\n", seed));
+    }
     for tile in &run.tiles {
         out.push_str("tile=");
         out.push_str(&encode_tile(tile));
@@ -187,6 +288,19 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
     let mut trio_level = 3u8;
     let mut infinite_level = 2u8;
     let mut infinite_round = 1u32;
+    let mut infinite_pacing = Pacing::Standard;
+    let mut infinite_trio = false;
+    let mut challenge = None;
+    let mut tournament = None;
+    let mut hotseat = None;
+    let mut bracket = None;
+    let mut gauntlet = None;
+    let mut speedrun = false;
+    let mut sudden_death = false;
+    let mut modifiers = Vec::new();
+    let mut kids = None;
+    let mut custom = None;
+    let mut match_times_ms = Vec::new();
     let mut seconds_elapsed = 0u32;
     let mut run_mismatches = 0u32;
     let mut run_matches = 0u32;
@@ -195,6 +309,7 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
     let mut impossible_last_first_index = None;
     let mut impossible_same_first_streak = 0u8;
     let mut flipped_indices = Vec::new();
+    let mut seed = None;
     let mut tiles = Vec::new();
 
     for line in raw.lines() {
@@ -230,6 +345,70 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
             infinite_round = rest.parse::<u32>().ok()?.max(1);
             continue;
         }
+        if let Some(rest) = line.strip_prefix("infinite_pacing=") {
+            infinite_pacing = Pacing::from_code(rest.trim());
+            continue;
+        }
+        // Saves from before Infinite Trio was its own mode flagged an Infinite run.
+        if let Some(rest) = line.strip_prefix("infinite_trio=") {
+            infinite_trio = rest.trim() == "1";
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("challenge=") {
+            challenge = Some(Challenge::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("tournament=") {
+            tournament = Some(TournamentRun::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("hotseat=") {
+            hotseat = Some(HotseatRun::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("bracket=") {
+            bracket = Some(Bracket::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("gauntlet=") {
+            gauntlet = Some(GauntletRun::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("speedrun=") {
+            speedrun = rest.trim() == "1";
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("sudden_death=") {
+            sudden_death = rest.trim() == "1";
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("modifiers=") {
+            modifiers = rest
+                .split(',')
+                .map(str::trim)
+                .filter(|code| !code.is_empty())
+                .map(str::to_string)
+                .collect();
+            continue;
+        }
+        // Saves from before modifiers were listed only knew a run was assisted.
+        if line.trim() == "assisted=1" {
+            modifiers = vec!["assist".to_string()];
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("kids=") {
+            kids = Some(KidsBoard::from_code(rest)?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("match_times_ms=") {
+            match_times_ms = rest
+                .split(',')
+                .map(str::trim)
+                .filter(|part| !part.is_empty())
+                .map(|part| part.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()?;
+            continue;
+        }
         if let Some(rest) = line.strip_prefix("seconds_elapsed=") {
             seconds_elapsed = rest.parse::<u32>().ok()?;
             continue;
@@ -276,6 +455,14 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
             }
             continue;
         }
+        if let Some(rest) = line.strip_prefix("seed=") {
+            seed = Some(rest.trim().parse::<u64>().ok()?);
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("custom=") {
+            custom = Some(CustomPreset::from_line(rest)?);
+            continue;
+        }
         if let Some(rest) = line.strip_prefix("tile=") {
             tiles.push(parse_tile(rest)?);
         }
@@ -285,11 +472,27 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
         return None;
     }
 
+    let mut difficulty = difficulty?;
+    if infinite_trio && difficulty == Difficulty::Infinite {
+        difficulty = Difficulty::InfiniteTrio;
+    }
     let run = SavedRun {
-        difficulty: difficulty?,
+        difficulty,
         trio_level,
         infinite_level,
         infinite_round,
+        infinite_pacing,
+        challenge,
+        tournament,
+        hotseat,
+        bracket,
+        gauntlet,
+        speedrun,
+        sudden_death,
+        modifiers,
+        kids,
+        custom,
+        match_times_ms,
         seconds_elapsed,
         run_mismatches,
         run_matches,
@@ -298,20 +501,33 @@ fn parse_saved_run(raw: &str) -> Option<SavedRun> {
         impossible_last_first_index,
         impossible_same_first_streak,
         flipped_indices,
+        seed,
         tiles,
     };
 
     validate_saved_run(run)
 }
 
+fn trio_grid(level: u8) -> (i32, i32, usize) {
+    match level.clamp(1, 4) {
+        1 => (4, 6, 3),
+        2 => (5, 6, 3),
+        3 => (6, 7, 3),
+        _ => (6, 8, 3),
+    }
+}
+
 fn expected_saved_run_tile_count(run: &SavedRun) -> usize {
+    if let Some(custom) = &run.custom {
+        return (custom.cols * custom.rows) as usize;
+    }
+    if let Some(board) = run.kids {
+        let (cols, rows) = board.grid();
+        return (cols * rows) as usize;
+    }
     let (cols, rows, _) = match run.difficulty {
-        Difficulty::Trio => match run.trio_level.clamp(1, 4) {
-            1 => (4, 6, 3),
-            2 => (5, 6, 3),
-            3 => (6, 7, 3),
-            _ => (6, 8, 3),
-        },
+        Difficulty::Trio => trio_grid(run.trio_level),
+        Difficulty::InfiniteTrio => trio_grid(run.infinite_level),
         Difficulty::Infinite => match run.infinite_level.clamp(1, 4) {
             1 => (3, 4, 2),
             2 => (4, 6, 2),
@@ -331,6 +547,37 @@ fn validate_saved_run(run: SavedRun) -> Option<SavedRun> {
     if run.tiles.len() != expected_tiles || expected_tiles == 0 {
         return None;
     }
+    if run
+        .challenge
+        .as_ref()
+        .is_some_and(|challenge| challenge.difficulty() != run.difficulty)
+    {
+        return None;
+    }
+    if run
+        .tournament
+        .as_ref()
+        .is_some_and(|tournament| tournament.current_difficulty() != run.difficulty)
+    {
+        return None;
+    }
+    if run
+        .hotseat
+        .as_ref()
+        .is_some_and(|hotseat| hotseat.difficulty() != run.difficulty)
+    {
+        return None;
+    }
+    // Bracket matches are always played as a turn run.
+    if run.bracket.is_some() && run.hotseat.is_none() {
+        return None;
+    }
+    if run
+        .gauntlet
+        .is_some_and(|gauntlet| gauntlet.current_difficulty() != run.difficulty)
+    {
+        return None;
+    }
     if run
         .impossible_last_first_index
         .is_some_and(|index| index >= run.tiles.len())
@@ -344,15 +591,6 @@ fn validate_saved_run(run: SavedRun) -> Option<SavedRun> {
     Some(run)
 }
 
-fn write_atomic(path: &PathBuf, data: &str) -> io::Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-    let tmp_path = path.with_extension("tmp");
-    fs::write(&tmp_path, data)?;
-    fs::rename(&tmp_path, path)
-}
-
 pub fn load_saved_run() -> Option<SavedRun> {
     let path = save_path()?;
     let raw = fs::read_to_string(path).ok()?;
@@ -366,29 +604,81 @@ pub fn clear_saved_run() {
 }
 
 pub fn save_current_run(st: &AppState) -> io::Result<()> {
-    if !st.active_session_started || st.tiles.is_empty() {
+    if !st.active_session_started || st.tiles.is_empty() || st.warmup_target.is_some() {
         return Ok(());
     }
 
     // Never persist transient visual states (Flipped). If a run is saved mid-animation,
     // resume from a stable board where only matched tiles stay revealed.
-    let normalized_tiles = st
-        .tiles
-        .iter()
-        .cloned()
-        .map(|mut tile| {
-            if tile.status == TileStatus::Flipped {
-                tile.status = TileStatus::Hidden;
-            }
-            tile
-        })
-        .collect::<Vec<Tile>>();
+    let mut run = saved_run_from_state(st);
+    for tile in &mut run.tiles {
+        if tile.status == TileStatus::Flipped {
+            tile.status = TileStatus::Hidden;
+        }
+    }
+    run.flipped_indices.clear();
 
-    let run = SavedRun {
+    if let Some(path) = save_path() {
+        let started = Instant::now();
+        paths::write_atomic(&path, &serialize_saved_run(&run))?;
+        perf_hud::record_save(started.elapsed());
+    }
+    Ok(())
+}
+
+pub fn board_dump_dir() -> PathBuf {
+    paths::data_dir().join(BOARD_DUMP_DIR_NAME)
+}
+
+// Unlike the resumable save, a dump keeps face-up cards, the open turn and the
+// deal's seed so bug reports can be replayed exactly. Each dump gets its own file.
+pub fn dump_board(st: &AppState) -> io::Result<PathBuf> {
+    let stamp = glib::DateTime::now_local()
+        .and_then(|now| now.format("%Y%m%d-%H%M%S"))
+        .map(|stamp| stamp.to_string())
+        .unwrap_or_else(|_| "undated".to_string());
+    let name = format!("board-{stamp}-game{}.v1", st.game_id);
+    write_board_dump(&board_dump_dir(), &name, &saved_run_from_state(st))
+}
+
+fn write_board_dump(dir: &Path, name: &str, run: &SavedRun) -> io::Result<PathBuf> {
+    let path = dir.join(name);
+    paths::write_atomic(&path, &serialize_saved_run(run))?;
+    Ok(path)
+}
+
+// File names start with the time of the dump, so the newest sorts last.
+pub fn latest_board_dump(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "v1"))
+        .max()
+}
+
+pub fn load_board_dump(path: &Path) -> Option<SavedRun> {
+    let raw = fs::read_to_string(path).ok()?;
+    parse_saved_run(&raw)
+}
+
+fn saved_run_from_state(st: &AppState) -> SavedRun {
+    SavedRun {
         difficulty: st.difficulty,
         trio_level: st.trio_level,
         infinite_level: st.infinite_level,
         infinite_round: st.infinite_round,
+        infinite_pacing: st.infinite_pacing,
+        challenge: st.challenge.clone(),
+        tournament: st.tournament().cloned(),
+        hotseat: st.hotseat.clone(),
+        bracket: st.bracket.clone(),
+        gauntlet: st.gauntlet(),
+        speedrun: st.speedrun,
+        sudden_death: st.sudden_death,
+        modifiers: st.run_modifier_codes.clone(),
+        kids: st.kids,
+        custom: st.custom.clone(),
+        match_times_ms: st.match_times_ms.clone(),
         seconds_elapsed: st.seconds_elapsed,
         run_mismatches: st.run_mismatches,
         run_matches: st.run_matches,
@@ -396,14 +686,10 @@ pub fn save_current_run(st: &AppState) -> io::Result<()> {
         impossible_punish_stage: st.impossible_punish_stage,
         impossible_last_first_index: st.impossible_last_first_index,
         impossible_same_first_streak: st.impossible_same_first_streak,
-        flipped_indices: Vec::new(),
-        tiles: normalized_tiles,
-    };
-
-    if let Some(path) = save_path() {
-        write_atomic(&path, &serialize_saved_run(&run))?;
+        flipped_indices: st.flipped_indices.clone(),
+        seed: Some(st.board_seed),
+        tiles: st.tiles.clone(),
     }
-    Ok(())
 }
 
 #[cfg(test)]
@@ -422,6 +708,18 @@ mod tests {
             trio_level: 4,
             infinite_level: 3,
             infinite_round: 1,
+            infinite_pacing: Pacing::Rush,
+            challenge: None,
+            tournament: None,
+            hotseat: None,
+            bracket: None,
+            gauntlet: None,
+            speedrun: true,
+            sudden_death: true,
+            modifiers: vec!["peek".to_string(), "hint".to_string()],
+            kids: None,
+            custom: None,
+            match_times_ms: vec![1_250, 4_980],
             seconds_elapsed: 97,
             run_mismatches: 8,
             run_matches: 14,
@@ -430,54 +728,91 @@ mod tests {
             impossible_last_first_index: Some(5),
             impossible_same_first_streak: 1,
             flipped_indices: vec![1, 4, 7],
+            seed: Some(0x5eed),
             tiles: vec![
                 Tile {
                     status: TileStatus::Hidden,
                     value: "plain".to_string(),
+                    seen: false,
+                    views: 0,
+                    tag: Some(TileTag::Blue),
                 },
                 Tile {
                     status: TileStatus::Flipped,
                     value: "pipe|slash\\newline\nok".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Matched,
                     value: "ascii-token".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Hidden,
                     value: "tile-3".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: Some(TileTag::Red),
                 },
                 Tile {
                     status: TileStatus::Flipped,
                     value: "tile-4".to_string(),
+                    seen: true,
+                    views: 3,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Matched,
                     value: "tile-5".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Hidden,
                     value: "tile-6".to_string(),
+                    seen: false,
+                    views: 0,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Flipped,
                     value: "tile-7".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Matched,
                     value: "tile-8".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Hidden,
                     value: "tile-9".to_string(),
+                    seen: false,
+                    views: 0,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Flipped,
                     value: "tile-10".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
                 Tile {
                     status: TileStatus::Matched,
                     value: "tile-11".to_string(),
+                    seen: true,
+                    views: 1,
+                    tag: None,
                 },
             ],
         }
@@ -534,6 +869,31 @@ flipped_indices=
         assert_eq!(parsed.infinite_level, 4);
     }
 
+    #[test]
+    fn legacy_infinite_trio_flag_resumes_as_infinite_trio() {
+        let raw = format!("\
+version=1
+started=1
+difficulty=infinite
+infinite_level=1
+infinite_round=4
+infinite_trio=1
+seconds_elapsed=40
+run_mismatches=0
+run_matches=3
+impossible_mismatch_count=0
+impossible_punish_stage=0
+impossible_last_first_index=-
+impossible_same_first_streak=0
+flipped_indices=
+{}",
+            repeated_tile_lines(24)
+        );
+        let parsed = parse_saved_run(&raw).expect("expected legacy Infinite Trio run to parse");
+        assert!(parsed.difficulty == Difficulty::InfiniteTrio);
+        assert_eq!(serialize_saved_run(&parsed).lines().nth(2), Some("difficulty=infinite_trio"));
+    }
+
     #[test]
     fn saved_run_roundtrip_preserves_payload() {
         let source = sample_saved_run();
@@ -544,6 +904,18 @@ flipped_indices=
         assert_eq!(parsed.trio_level, source.trio_level);
         assert_eq!(parsed.infinite_level, source.infinite_level);
         assert_eq!(parsed.infinite_round, source.infinite_round);
+        assert_eq!(parsed.infinite_pacing, source.infinite_pacing);
+        assert_eq!(parsed.challenge, source.challenge);
+        assert_eq!(parsed.tournament, source.tournament);
+        assert_eq!(parsed.hotseat, source.hotseat);
+        assert_eq!(parsed.bracket, source.bracket);
+        assert_eq!(parsed.gauntlet, source.gauntlet);
+        assert_eq!(parsed.speedrun, source.speedrun);
+        assert_eq!(parsed.sudden_death, source.sudden_death);
+        assert_eq!(parsed.modifiers, source.modifiers);
+        assert_eq!(parsed.kids, source.kids);
+        assert_eq!(parsed.custom, source.custom);
+        assert_eq!(parsed.match_times_ms, source.match_times_ms);
         assert_eq!(parsed.seconds_elapsed, source.seconds_elapsed);
         assert_eq!(parsed.run_mismatches, source.run_mismatches);
         assert_eq!(parsed.run_matches, source.run_matches);
@@ -552,14 +924,90 @@ flipped_indices=
         assert_eq!(parsed.impossible_last_first_index, source.impossible_last_first_index);
         assert_eq!(parsed.impossible_same_first_streak, source.impossible_same_first_streak);
         assert_eq!(parsed.flipped_indices, source.flipped_indices);
+        assert_eq!(parsed.seed, source.seed);
         assert_eq!(parsed.tiles.len(), source.tiles.len());
 
         for (left, right) in parsed.tiles.iter().zip(source.tiles.iter()) {
             assert!(left.status == right.status);
             assert_eq!(left.value, right.value);
+            assert_eq!(left.seen, right.seen);
+            assert_eq!(left.views, right.views);
+            assert_eq!(left.tag, right.tag);
         }
     }
 
+    #[test]
+    fn saved_run_roundtrip_preserves_weekly_challenge() {
+        let mut source = sample_saved_run();
+        source.difficulty = Difficulty::Impossible;
+        source.challenge = Some(Challenge::weekly("2026-W42"));
+        source.tiles = (0..48)
+            .map(|idx| Tile {
+                status: TileStatus::Hidden,
+                value: format!("tile-{idx}"),
+                seen: false,
+                views: 0,
+                tag: None,
+            })
+            .collect();
+        source.impossible_last_first_index = None;
+        source.flipped_indices.clear();
+
+        let parsed = parse_saved_run(&serialize_saved_run(&source))
+            .expect("expected weekly run to parse");
+
+        assert_eq!(parsed.challenge, source.challenge);
+    }
+
+    #[test]
+    fn saved_run_roundtrip_resumes_a_kids_board() {
+        let mut source = sample_saved_run();
+        source.speedrun = false;
+        source.sudden_death = false;
+        source.modifiers.clear();
+        source.kids = Some(KidsBoard::Small);
+        source.tiles.truncate(6);
+        source.flipped_indices = vec![1, 4];
+        source.impossible_last_first_index = None;
+
+        let parsed = parse_saved_run(&serialize_saved_run(&source))
+            .expect("expected a Little Board run to parse");
+        assert_eq!(parsed.kids, Some(KidsBoard::Small));
+        assert_eq!(parsed.tiles.len(), 6);
+
+        source.kids = Some(KidsBoard::Large);
+        assert!(parse_saved_run(&serialize_saved_run(&source)).is_none());
+    }
+
+    #[test]
+    fn saved_run_custom_board_sets_the_tile_count() {
+        let mut source = sample_saved_run();
+        source.custom = CustomPreset::from_line("3x4x3:8:gentle:off:0|Triples");
+
+        let parsed = parse_saved_run(&serialize_saved_run(&source))
+            .expect("expected custom run to parse");
+        assert_eq!(parsed.custom, source.custom);
+
+        source.custom = CustomPreset::from_line("4x4x2:8:gentle:off:0|Bigger");
+        assert!(parse_saved_run(&serialize_saved_run(&source)).is_none());
+    }
+
+    #[test]
+    fn saved_run_tournament_must_match_current_board() {
+        let mut source = sample_saved_run();
+        source.impossible_last_first_index = None;
+        source.tournament = Some(TournamentRun::default());
+
+        let parsed = parse_saved_run(&serialize_saved_run(&source))
+            .expect("expected first tournament board to parse");
+        assert_eq!(parsed.tournament, source.tournament);
+
+        let mut ahead = TournamentRun::default();
+        ahead.record_board(1, 20, 100);
+        source.tournament = Some(ahead);
+        assert!(parse_saved_run(&serialize_saved_run(&source)).is_none());
+    }
+
     #[test]
     fn parse_saved_run_rejects_tile_count_mismatch() {
         let raw = "\
@@ -614,4 +1062,27 @@ tile=H|l
 ";
         assert!(parse_saved_run(raw).is_none());
     }
+
+    #[test]
+    fn the_newest_board_dump_loads_back_with_its_seed_and_open_turn() {
+        let dir = std::env::temp_dir().join(format!("recall-dumps-{}", std::process::id()));
+        let mut older = sample_saved_run();
+        older.seed = Some(1);
+        let source = sample_saved_run();
+        write_board_dump(&dir, "board-20261016-090000-game3.v1", &older).unwrap();
+        let written = write_board_dump(&dir, "board-20261017-120000-game1.v1", &source).unwrap();
+
+        let latest = latest_board_dump(&dir).expect("expected a dump in the directory");
+        assert_eq!(latest, written);
+        let loaded = load_board_dump(&latest).expect("expected the dump to load");
+        assert_eq!(loaded.seed, source.seed);
+        assert_eq!(loaded.flipped_indices, source.flipped_indices);
+        assert!(loaded
+            .tiles
+            .iter()
+            .zip(source.tiles.iter())
+            .all(|(left, right)| left.status == right.status && left.value == right.value));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,212 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use gio::prelude::*;
+use gtk4::glib;
+
+use super::paths;
+
+pub const SCHEMA_ID: &str = "io.github.basshift.Recall";
+const FALLBACK_FILE_NAME: &str = "settings.ini";
+const FALLBACK_GROUP: &str = "preferences";
+const MIGRATED_SUFFIX: &str = "migrated";
+
+enum Backend {
+    GSettings(gio::Settings),
+    // Used when the schema is not installed, e.g. when running straight from cargo.
+    KeyFile(glib::KeyFile),
+}
+
+thread_local! {
+    static BACKEND: RefCell<Option<Backend>> = const { RefCell::new(None) };
+}
+
+fn fallback_path() -> PathBuf {
+    paths::config_dir().join(FALLBACK_FILE_NAME)
+}
+
+fn open_backend() -> Backend {
+    // GSettings is per user, so a custom data directory keeps its own key file.
+    let schema_installed = paths::dir_override().is_none()
+        && gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(SCHEMA_ID, true))
+        .is_some();
+    if schema_installed {
+        let settings = gio::Settings::new(SCHEMA_ID);
+        migrate_key_file(&settings);
+        return Backend::GSettings(settings);
+    }
+
+    let key_file = glib::KeyFile::new();
+    let path = fallback_path();
+    if path.exists()
+        && let Err(err) = key_file.load_from_file(&path, glib::KeyFileFlags::KEEP_COMMENTS)
+    {
+        tracing::warn!("failed to load settings: {err}");
+    }
+    Backend::KeyFile(key_file)
+}
+
+// Preferences saved while the schema was missing move into GSettings once; the
+// key file is then renamed so it is never read again.
+fn migrate_key_file(settings: &gio::Settings) {
+    let path = fallback_path();
+    if !path.exists() {
+        return;
+    }
+    let key_file = glib::KeyFile::new();
+    if let Err(err) = key_file.load_from_file(&path, glib::KeyFileFlags::NONE) {
+        tracing::warn!("failed to load settings for migration: {err}");
+        return;
+    }
+    let Some(schema) = settings.settings_schema() else {
+        return;
+    };
+    let keys = key_file.keys(FALLBACK_GROUP).map(|keys| keys.to_vec()).unwrap_or_default();
+    let mut migrated = 0;
+    for key in keys {
+        let key = key.as_str();
+        if !schema.has_key(key) {
+            continue;
+        }
+        let stored = match schema.key(key).value_type().as_str() {
+            "b" => key_file
+                .boolean(FALLBACK_GROUP, key)
+                .map_err(|err| err.to_string())
+                .and_then(|value| settings.set_boolean(key, value).map_err(|err| err.to_string())),
+            "i" => key_file
+                .integer(FALLBACK_GROUP, key)
+                .map_err(|err| err.to_string())
+                .and_then(|value| settings.set_int(key, value).map_err(|err| err.to_string())),
+            _ => key_file
+                .string(FALLBACK_GROUP, key)
+                .map_err(|err| err.to_string())
+                .and_then(|value| settings.set_string(key, &value).map_err(|err| err.to_string())),
+        };
+        match stored {
+            Ok(()) => migrated += 1,
+            Err(err) => tracing::warn!("failed to migrate setting {key}: {err}"),
+        }
+    }
+    let done_path = path.with_extension(format!("ini.{MIGRATED_SUFFIX}"));
+    if let Err(err) = std::fs::rename(&path, &done_path) {
+        tracing::warn!("failed to retire migrated settings file: {err}");
+    }
+    tracing::info!("migrated {migrated} settings into GSettings");
+}
+
+fn with_backend<R>(f: impl FnOnce(&Backend) -> R) -> R {
+    BACKEND.with(|backend| {
+        let mut backend = backend.borrow_mut();
+        f(backend.get_or_insert_with(open_backend))
+    })
+}
+
+fn save_key_file(key_file: &glib::KeyFile) {
+    let path = fallback_path();
+    if let Some(parent) = path.parent()
+        && let Err(err) = std::fs::create_dir_all(parent)
+    {
+        tracing::warn!("failed to create settings directory: {err}");
+        return;
+    }
+    if let Err(err) = key_file.save_to_file(&path) {
+        tracing::warn!("failed to save settings: {err}");
+    }
+}
+
+pub fn string(key: &str, default: &str) -> String {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => settings.string(key).to_string(),
+        Backend::KeyFile(key_file) => key_file
+            .string(FALLBACK_GROUP, key)
+            .map(|value| value.to_string())
+            .unwrap_or_else(|_| default.to_string()),
+    })
+}
+
+pub fn set_string(key: &str, value: &str) {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => {
+            if let Err(err) = settings.set_string(key, value) {
+                tracing::warn!("failed to store setting {key}: {err}");
+            }
+        }
+        Backend::KeyFile(key_file) => {
+            key_file.set_string(FALLBACK_GROUP, key, value);
+            save_key_file(key_file);
+        }
+    });
+}
+
+pub fn boolean(key: &str, default: bool) -> bool {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => settings.boolean(key),
+        Backend::KeyFile(key_file) => key_file.boolean(FALLBACK_GROUP, key).unwrap_or(default),
+    })
+}
+
+pub fn set_boolean(key: &str, value: bool) {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => {
+            if let Err(err) = settings.set_boolean(key, value) {
+                tracing::warn!("failed to store setting {key}: {err}");
+            }
+        }
+        Backend::KeyFile(key_file) => {
+            key_file.set_boolean(FALLBACK_GROUP, key, value);
+            save_key_file(key_file);
+        }
+    });
+}
+
+pub fn integer(key: &str, default: i32) -> i32 {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => settings.int(key),
+        Backend::KeyFile(key_file) => key_file.integer(FALLBACK_GROUP, key).unwrap_or(default),
+    })
+}
+
+pub fn set_integer(key: &str, value: i32) {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => {
+            if let Err(err) = settings.set_int(key, value) {
+                tracing::warn!("failed to store setting {key}: {err}");
+            }
+        }
+        Backend::KeyFile(key_file) => {
+            key_file.set_integer(FALLBACK_GROUP, key, value);
+            save_key_file(key_file);
+        }
+    });
+}
+
+// Keeps a boolean property and its key in sync both ways. Without the schema
+// the property starts from the key file and writes back on change.
+pub fn bind_boolean(key: &'static str, default: bool, object: &impl IsA<glib::Object>, property: &'static str) {
+    let bound = with_backend(|backend| match backend {
+        Backend::GSettings(settings) => {
+            settings.bind(key, object, property).build();
+            true
+        }
+        Backend::KeyFile(_) => false,
+    });
+    if bound {
+        return;
+    }
+    object.set_property(property, boolean(key, default));
+    object.connect_notify_local(Some(property), move |object, _| {
+        set_boolean(key, object.property::<bool>(property));
+    });
+}
+
+pub fn reset(key: &str) {
+    with_backend(|backend| match backend {
+        Backend::GSettings(settings) => settings.reset(key),
+        Backend::KeyFile(key_file) => {
+            if key_file.remove_key(FALLBACK_GROUP, key).is_ok() {
+                save_key_file(key_file);
+            }
+        }
+    });
+}
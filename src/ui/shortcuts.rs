@@ -0,0 +1,205 @@
+use gtk4 as gtk;
+use gtk4::gdk;
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use super::settings;
+
+pub struct Shortcut {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub action: Option<&'static str>,
+    pub default_accel: &'static str,
+}
+
+pub const SHORTCUTS: [Shortcut; 7] = [
+    Shortcut {
+        id: "restart",
+        title: "Restart game",
+        action: Some("app.game-action"),
+        default_accel: "<Primary>r",
+    },
+    Shortcut {
+        id: "home",
+        title: "Back to main menu",
+        action: Some("app.back-menu"),
+        default_accel: "<Primary>m",
+    },
+    Shortcut {
+        id: "pause",
+        title: "Pause game",
+        action: Some("app.pause"),
+        default_accel: "<Primary>p",
+    },
+    Shortcut {
+        id: "instructions",
+        title: "How to play",
+        action: Some("app.instructions"),
+        default_accel: "F1",
+    },
+    Shortcut {
+        id: "flip",
+        title: "Flip selected card",
+        action: None,
+        default_accel: "space",
+    },
+    Shortcut {
+        id: "tag",
+        title: "Tag selected card",
+        action: None,
+        default_accel: "<Primary>t",
+    },
+    Shortcut {
+        id: "hint",
+        title: "Show a hint",
+        action: None,
+        default_accel: "<Primary>h",
+    },
+];
+
+// App accelerators that cannot be remapped: (action, title, accelerator).
+pub const FIXED_ACCELS: [(&str, &str, &str); 3] = [
+    ("win.show-help-overlay", "Show shortcuts", "<Primary>slash"),
+    ("app.preferences", "Preferences", "<Primary>comma"),
+    ("app.quit", "Quit", "<Primary>q"),
+];
+
+fn settings_key(shortcut: &Shortcut) -> String {
+    format!("shortcut-{}", shortcut.id)
+}
+
+pub fn find(id: &str) -> Option<&'static Shortcut> {
+    SHORTCUTS.iter().find(|shortcut| shortcut.id == id)
+}
+
+pub fn accelerator(shortcut: &Shortcut) -> String {
+    let value = settings::string(&settings_key(shortcut), shortcut.default_accel);
+    if gtk::accelerator_parse(value.as_str()).is_some() {
+        value
+    } else {
+        shortcut.default_accel.to_string()
+    }
+}
+
+pub fn set_accelerator(shortcut: &Shortcut, accel: &str) {
+    settings::set_string(&settings_key(shortcut), accel);
+}
+
+pub fn reset_all() {
+    for shortcut in &SHORTCUTS {
+        settings::reset(&settings_key(shortcut));
+    }
+}
+
+pub fn apply_app_accels(app: &adw::Application) {
+    for (action, _, accel) in FIXED_ACCELS {
+        app.set_accels_for_action(action, &[accel]);
+    }
+    for shortcut in &SHORTCUTS {
+        if let Some(action) = shortcut.action {
+            app.set_accels_for_action(action, &[accelerator(shortcut).as_str()]);
+        }
+    }
+}
+
+// The title of whatever else already answers to `accel`, remappable or not.
+pub fn conflict(shortcut: &Shortcut, accel: &str) -> Option<&'static str> {
+    let same = |other: &str| {
+        gtk::accelerator_parse(other).is_some_and(|parsed| gtk::accelerator_parse(accel) == Some(parsed))
+    };
+    SHORTCUTS
+        .iter()
+        .filter(|other| other.id != shortcut.id)
+        .find(|other| same(&accelerator(other)))
+        .map(|other| other.title)
+        .or_else(|| {
+            FIXED_ACCELS
+                .iter()
+                .find(|(_, _, fixed)| same(fixed))
+                .map(|(_, title, _)| *title)
+        })
+}
+
+pub fn label(accel: &str) -> String {
+    match gtk::accelerator_parse(accel) {
+        Some((key, mods)) => gtk::accelerator_get_label(key, mods).to_string(),
+        None => accel.to_string(),
+    }
+}
+
+pub fn key_matches(accel: &str, key: gdk::Key, mods: gdk::ModifierType) -> bool {
+    let Some((accel_key, accel_mods)) = gtk::accelerator_parse(accel) else {
+        return false;
+    };
+    let mods = mods & gtk::accelerator_get_default_mod_mask();
+    accel_key.to_lower() == key.to_lower() && accel_mods == mods
+}
+
+fn is_modifier_key(key: gdk::Key) -> bool {
+    matches!(
+        key,
+        gdk::Key::Shift_L
+            | gdk::Key::Shift_R
+            | gdk::Key::Control_L
+            | gdk::Key::Control_R
+            | gdk::Key::Alt_L
+            | gdk::Key::Alt_R
+            | gdk::Key::Super_L
+            | gdk::Key::Super_R
+            | gdk::Key::Meta_L
+            | gdk::Key::Meta_R
+            | gdk::Key::ISO_Level3_Shift
+            | gdk::Key::Caps_Lock
+    )
+}
+
+pub fn accelerator_from_event(key: gdk::Key, mods: gdk::ModifierType) -> Option<String> {
+    if is_modifier_key(key) {
+        return None;
+    }
+    let mods = mods & gtk::accelerator_get_default_mod_mask();
+    Some(gtk::accelerator_name(key.to_lower(), mods).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shortcut_ids_and_defaults_are_unique() {
+        for (idx, shortcut) in SHORTCUTS.iter().enumerate() {
+            assert!(SHORTCUTS[idx + 1..].iter().all(|other| other.id != shortcut.id));
+            assert!(
+                SHORTCUTS[idx + 1..]
+                    .iter()
+                    .all(|other| other.default_accel != shortcut.default_accel)
+            );
+        }
+        assert_eq!(find("flip").map(|shortcut| shortcut.default_accel), Some("space"));
+    }
+
+    #[test]
+    fn every_shortcut_has_a_schema_key_with_its_default() {
+        let schema = include_str!("../../data/io.github.basshift.Recall.gschema.xml");
+        for shortcut in &SHORTCUTS {
+            let key = format!("<key name=\"{}\" type=\"s\">", settings_key(shortcut));
+            let default = shortcut.default_accel.replace('<', "&lt;").replace('>', "&gt;");
+            let entry = schema
+                .split_once(key.as_str())
+                .map(|(_, rest)| rest.split("</key>").next().unwrap_or_default())
+                .unwrap_or_else(|| panic!("{key} is missing from the schema"));
+            assert!(entry.contains(&format!("<default>'{default}'</default>")), "{key}");
+        }
+    }
+
+    #[test]
+    fn defaults_stay_clear_of_fixed_accelerators() {
+        for shortcut in &SHORTCUTS {
+            assert!(
+                FIXED_ACCELS
+                    .iter()
+                    .all(|(_, _, fixed)| *fixed != shortcut.default_accel)
+            );
+        }
+    }
+}
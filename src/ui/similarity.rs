@@ -0,0 +1,231 @@
+use super::infinite;
+use super::state::{AppState, Difficulty};
+
+pub const CRUEL_SYMBOLS_KEY: &str = "cruel-symbols";
+pub const SCALED_SIMILARITY_KEY: &str = "scaled-similarity";
+const EXPERT_LOOKALIKE_SHARE: f64 = 0.5;
+const INFINITE_LOOKALIKE_BASE: f64 = 0.3;
+const INFINITE_LOOKALIKE_PER_ROUND: f64 = 0.05;
+const INFINITE_LOOKALIKE_MAX: f64 = 0.7;
+
+// Symbols that are hard to tell apart at small tile sizes.
+pub const CONFUSABLE_CLUSTERS: &[&[&str]] = &[
+    &["🐱", "🐯", "🦁"],
+    &["🐶", "🦊"],
+    &["🐭", "🐹", "🐰"],
+    &["🐻", "🐼", "🐨"],
+    &["🐮", "🐷"],
+    &["🐔", "🐦", "🐤", "🐣", "🦆", "🪿", "🦢"],
+    &["🐠", "🐟", "🐡"],
+    &["🐬", "🐳", "🦈", "🦭"],
+    &["🦐", "🦞", "🦀"],
+    &["🐙", "🦑"],
+    &["🐊", "🦎", "🐢"],
+    &["🐪", "🐫", "🦙"],
+    &["🐐", "🐏"],
+    &["🐴", "🦄", "🦓"],
+    &["🐘", "🦛", "🦏", "🦬"],
+    &["🐝", "🪲", "🐞"],
+    &["🦚", "🦜", "🦩"],
+    &["🍏", "🍎", "🍐"],
+    &["🍊", "🍑", "🍋", "🥭"],
+    &["🍓", "🍒", "🫐", "🍇"],
+    &["🥦", "🥬", "🥒", "🫑"],
+    &["🍅", "🌶️"],
+    &["🥔", "🍠", "🥜"],
+    &["🍞", "🥐", "🥨"],
+    &["🥞", "🧇", "🍳", "🥚"],
+    &["🌮", "🌯"],
+    &["⚽", "🏐", "🏀", "🏈", "🏉", "⚾", "🥎", "🎾"],
+    &["🏓", "🏸"],
+    &["🏒", "🏑", "🥍", "🏏"],
+    &["🖌️", "🖍️", "✏️", "🖊️"],
+    &["🧵", "🧶"],
+    &["🎮", "🕹️"],
+    &["🎺", "🎷"],
+    &["📷", "📸"],
+    &["💻", "🖥️", "⌨️", "🖨️"],
+    &["🔍", "🔬", "🔭"],
+    &["🔧", "🔨", "🪛", "🔩", "⚙️", "🧰"],
+    &["📌", "📎"],
+    &["📚", "📓"],
+    &["🌞", "🌝", "☀️", "⭐"],
+    &["🌎", "🧭", "🗺️"],
+    &["🌊", "💧"],
+    &["⛰️", "🗻", "🌋"],
+    &["🏝️", "🏜️", "🏞️"],
+    &["🌳", "🌴", "🌵"],
+    &["🌱", "🍀", "🌿", "🌾"],
+    &["🌷", "🌹", "🌺", "🌸", "🪻", "🪷", "🌻"],
+    &["🚗", "🚕", "🚓", "🚑", "🚒", "🏎️"],
+    &["🚌", "🚎"],
+    &["🚲", "🛵", "🚴"],
+];
+
+pub fn cluster_of(symbol: &str) -> Option<usize> {
+    CONFUSABLE_CLUSTERS
+        .iter()
+        .position(|cluster| cluster.contains(&symbol))
+}
+
+// Share of a board drawn from lookalike clusters: cruel mode is all lookalikes,
+// otherwise Expert boards and the Expert stretch of Infinite mix some in.
+pub fn lookalike_share(st: &AppState) -> f64 {
+    // Shared boards have to come out the same for everyone, whatever their settings.
+    if st.shared_seed().is_some() {
+        return scaled_share(st);
+    }
+    if st.kids.is_some() {
+        return 0.0;
+    }
+    if st.cruel_symbols {
+        return 1.0;
+    }
+    if let Some(custom) = &st.custom {
+        return if custom.lookalikes { 1.0 } else { 0.0 };
+    }
+    if !st.scaled_similarity {
+        return 0.0;
+    }
+    scaled_share(st)
+}
+
+fn scaled_share(st: &AppState) -> f64 {
+    match st.difficulty {
+        Difficulty::Impossible => EXPERT_LOOKALIKE_SHARE,
+        Difficulty::Infinite | Difficulty::InfiniteTrio
+            if infinite::classic_difficulty_for_round(st.infinite_pacing, st.infinite_round)
+                == Difficulty::Impossible =>
+        {
+            let rounds = infinite::expert_survival_rounds(st.infinite_pacing, st.infinite_round) as f64;
+            (INFINITE_LOOKALIKE_BASE + rounds * INFINITE_LOOKALIKE_PER_ROUND)
+                .min(INFINITE_LOOKALIKE_MAX)
+        }
+        _ => 0.0,
+    }
+}
+
+pub fn mode_tag(st: &AppState) -> Option<&'static str> {
+    let share = lookalike_share(st);
+    if share >= 1.0 {
+        Some("Cruel")
+    } else if share > 0.0 {
+        Some("Lookalikes")
+    } else {
+        None
+    }
+}
+
+fn lookalike_symbols(pool: &[&'static str], count: usize) -> Vec<&'static str> {
+    let mut selected = Vec::with_capacity(count);
+    let mut used_clusters = Vec::new();
+    for symbol in pool {
+        let Some(cluster) = cluster_of(symbol) else {
+            continue;
+        };
+        if used_clusters.contains(&cluster) {
+            continue;
+        }
+        used_clusters.push(cluster);
+        for member in pool.iter().filter(|member| cluster_of(member) == Some(cluster)) {
+            if selected.len() < count {
+                selected.push(*member);
+            }
+        }
+    }
+    selected
+}
+
+// Takes `count` symbols from an already shuffled pool. The lookalike share is filled
+// with whole clusters first, the rest uses at most one symbol per cluster.
+pub fn select_symbols(
+    pool: &[&'static str],
+    count: usize,
+    lookalike_share: f64,
+) -> Vec<&'static str> {
+    let lookalike_count = (count as f64 * lookalike_share.clamp(0.0, 1.0)).round() as usize;
+    let mut selected = lookalike_symbols(pool, lookalike_count);
+    let mut used_clusters = selected
+        .iter()
+        .filter_map(|symbol| cluster_of(symbol))
+        .collect::<Vec<usize>>();
+    let mut leftovers = Vec::new();
+
+    for symbol in pool {
+        if selected.len() == count {
+            return selected;
+        }
+        if selected.contains(symbol) {
+            continue;
+        }
+        match cluster_of(symbol) {
+            Some(cluster) if used_clusters.contains(&cluster) => leftovers.push(*symbol),
+            Some(cluster) => {
+                used_clusters.push(cluster);
+                selected.push(*symbol);
+            }
+            None => selected.push(*symbol),
+        }
+    }
+
+    // Fall back to whatever is left when the pool is too small to honour the rule.
+    for symbol in leftovers {
+        if selected.len() == count {
+            break;
+        }
+        selected.push(symbol);
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clusters_are_disjoint() {
+        let mut seen = Vec::new();
+        for cluster in CONFUSABLE_CLUSTERS {
+            assert!(cluster.len() >= 2);
+            for symbol in *cluster {
+                assert!(!seen.contains(symbol), "{symbol} is listed twice");
+                seen.push(*symbol);
+            }
+        }
+    }
+
+    #[test]
+    fn default_selection_keeps_one_symbol_per_cluster() {
+        let pool = ["🐱", "🐯", "🍊", "🍑", "🎲", "🦁", "🃏"];
+        assert_eq!(select_symbols(&pool, 4, 0.0), vec!["🐱", "🍊", "🎲", "🃏"]);
+        assert_eq!(select_symbols(&pool, 6, 0.0).len(), 6);
+    }
+
+    #[test]
+    fn cruel_selection_groups_lookalikes() {
+        let pool = ["🎲", "🐱", "🍊", "🐯", "🍑", "🦁"];
+        assert_eq!(select_symbols(&pool, 4, 1.0), vec!["🐱", "🐯", "🦁", "🍊"]);
+    }
+
+    #[test]
+    fn partial_share_mixes_a_cluster_with_distinct_symbols() {
+        let pool = ["🐱", "🎲", "🍊", "🐯", "🍑", "🃏"];
+        assert_eq!(select_symbols(&pool, 4, 0.5), vec!["🐱", "🐯", "🎲", "🍊"]);
+    }
+
+    #[test]
+    fn expert_boards_scale_similarity_unless_disabled() {
+        let mut st = AppState {
+            difficulty: Difficulty::Impossible,
+            scaled_similarity: true,
+            ..Default::default()
+        };
+        assert_eq!(mode_tag(&st), Some("Lookalikes"));
+
+        st.scaled_similarity = false;
+        assert_eq!(lookalike_share(&st), 0.0);
+        st.difficulty = Difficulty::Easy;
+        st.cruel_symbols = true;
+        assert_eq!(mode_tag(&st), Some("Cruel"));
+    }
+}
@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::glib;
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use crate::i18n::tr;
+
+use super::events;
+use super::faces;
+use super::state::{AppState, Tile, TileStatus};
+
+const CELL_SIZE: i32 = 72;
+const FACE_PIXEL_SIZE: i32 = 44;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CellView {
+    Hidden,
+    Flipped(String),
+    Matched(String),
+}
+
+// Only what the player has turned over is shown, so the audience learns nothing the player does not.
+pub fn cell_views(tiles: &[Tile], conceal_all: bool) -> Vec<CellView> {
+    tiles
+        .iter()
+        .map(|tile| match tile.status {
+            _ if conceal_all => CellView::Hidden,
+            TileStatus::Hidden => CellView::Hidden,
+            TileStatus::Flipped => CellView::Flipped(tile.value.clone()),
+            TileStatus::Matched => CellView::Matched(tile.value.clone()),
+        })
+        .collect()
+}
+
+fn current_views(st: &AppState) -> (i32, bool, Vec<CellView>) {
+    // Previews and Pattern rounds show every card at once, which would give the board away.
+    let conceal_all = st.preview_active || st.pattern.is_some();
+    (st.grid_cols.max(1), st.artwork_faces, cell_views(&st.tiles, conceal_all))
+}
+
+fn fill_cell(cell: &gtk::Box, view: &CellView, artwork: bool) {
+    while let Some(child) = cell.first_child() {
+        cell.remove(&child);
+    }
+    cell.remove_css_class("spectator-flipped");
+    cell.remove_css_class("spectator-matched");
+    let value = match view {
+        CellView::Hidden => return,
+        CellView::Flipped(value) => {
+            cell.add_css_class("spectator-flipped");
+            value
+        }
+        CellView::Matched(value) => {
+            cell.add_css_class("spectator-matched");
+            value
+        }
+    };
+    if let Some(path) = artwork.then(|| faces::resource_path(value)).flatten() {
+        let image = gtk::Image::from_resource(&path);
+        image.set_pixel_size(FACE_PIXEL_SIZE);
+        image.set_vexpand(true);
+        cell.append(&image);
+    } else {
+        let label = gtk::Label::new(Some(value));
+        label.add_css_class("spectator-symbol");
+        label.set_vexpand(true);
+        cell.append(&label);
+    }
+}
+
+struct Mirror {
+    grid: gtk::Grid,
+    cells: Vec<gtk::Box>,
+    shown: Vec<CellView>,
+    cols: i32,
+    artwork: bool,
+}
+
+impl Mirror {
+    fn rebuild(&mut self, cols: i32, artwork: bool, views: &[CellView]) {
+        while let Some(child) = self.grid.first_child() {
+            self.grid.remove(&child);
+        }
+        self.cells.clear();
+        for (index, view) in views.iter().enumerate() {
+            let cell = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            cell.add_css_class("spectator-card");
+            cell.set_size_request(CELL_SIZE, CELL_SIZE);
+            fill_cell(&cell, view, artwork);
+            let index = index as i32;
+            self.grid.attach(&cell, index % cols, index / cols, 1, 1);
+            self.cells.push(cell);
+        }
+        self.cols = cols;
+        self.artwork = artwork;
+        self.shown = views.to_vec();
+    }
+
+    fn sync(&mut self, cols: i32, artwork: bool, views: Vec<CellView>) {
+        if cols != self.cols || artwork != self.artwork || views.len() != self.shown.len() {
+            self.rebuild(cols, artwork, &views);
+            return;
+        }
+        for ((cell, shown), view) in self.cells.iter().zip(&mut self.shown).zip(views) {
+            if *shown != view {
+                fill_cell(cell, &view, artwork);
+                *shown = view;
+            }
+        }
+    }
+}
+
+pub fn open_spectator(state: &Rc<RefCell<AppState>>, app: &adw::Application) {
+    if let Some(window) = &state.borrow().spectator_window {
+        window.present();
+        return;
+    }
+
+    let grid = gtk::Grid::new();
+    grid.set_row_spacing(8);
+    grid.set_column_spacing(8);
+    grid.set_row_homogeneous(true);
+    grid.set_column_homogeneous(true);
+    grid.set_halign(gtk::Align::Center);
+    grid.set_valign(gtk::Align::Center);
+    grid.add_css_class("spectator-board");
+
+    let header = adw::HeaderBar::new();
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&grid));
+
+    let window = adw::Window::builder()
+        .application(app)
+        .title(tr("Recall Spectator"))
+        .default_width(640)
+        .default_height(560)
+        .content(&toolbar)
+        .build();
+    // Goes with the game window, so it never keeps the app running on its own.
+    window.set_transient_for(state.borrow().main_window.as_ref());
+    window.set_destroy_with_parent(true);
+
+    let mirror = Rc::new(RefCell::new(Mirror {
+        grid,
+        cells: Vec::new(),
+        shown: Vec::new(),
+        cols: 0,
+        artwork: false,
+    }));
+    {
+        let (cols, artwork, views) = current_views(&state.borrow());
+        mirror.borrow_mut().rebuild(cols, artwork, &views);
+    }
+
+    // Board events include cards turned back after a mismatch and new boards.
+    let subscription = events::subscribe({
+        let state = Rc::downgrade(state);
+        let mirror = Rc::downgrade(&mirror);
+        move |_| {
+            let (Some(state), Some(mirror)) = (state.upgrade(), mirror.upgrade()) else {
+                return;
+            };
+            if let Ok(st) = state.try_borrow() {
+                let (cols, artwork, views) = current_views(&st);
+                mirror.borrow_mut().sync(cols, artwork, views);
+            }
+        }
+    });
+    let mirror = RefCell::new(Some(mirror));
+    window.connect_close_request({
+        let state = state.clone();
+        move |_| {
+            mirror.take();
+            events::unsubscribe(subscription);
+            state.borrow_mut().spectator_window = None;
+            glib::Propagation::Proceed
+        }
+    });
+
+    state.borrow_mut().spectator_window = Some(window.clone());
+    window.present();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(value: &str, status: TileStatus) -> Tile {
+        Tile {
+            value: value.to_string(),
+            status,
+            seen: false,
+            views: 0,
+            tag: None,
+        }
+    }
+
+    #[test]
+    fn hidden_cards_never_reach_the_spectator() {
+        let tiles = [
+            tile("🍎", TileStatus::Hidden),
+            tile("🍐", TileStatus::Flipped),
+            tile("🍋", TileStatus::Matched),
+        ];
+        assert_eq!(
+            cell_views(&tiles, false),
+            [
+                CellView::Hidden,
+                CellView::Flipped("🍐".to_string()),
+                CellView::Matched("🍋".to_string()),
+            ]
+        );
+        assert!(cell_views(&tiles, true).iter().all(|view| *view == CellView::Hidden));
+    }
+}
@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4::prelude::*;
+
+use crate::i18n::{ntr_format, tr, tr_format};
+
+use super::events::{self, GameEvent};
+use super::hud;
+use super::infinite;
+use super::state::AppState;
+
+pub const SPOKEN_ANNOUNCEMENTS_KEY: &str = "spoken-announcements";
+pub fn announcement(st: &AppState, event: &GameEvent) -> Option<String> {
+    match event {
+        GameEvent::MatchFound(_) => {
+            let (found, total) = hud::match_progress(&st.tiles, st.match_size);
+            let left = total.saturating_sub(found);
+            Some(if left == 0 {
+                tr("Match! Board cleared")
+            } else {
+                let left = u32::try_from(left).unwrap_or(u32::MAX);
+                if st.match_size == 3 {
+                    ntr_format(
+                        "Match! {count} trio remaining",
+                        "Match! {count} trios remaining",
+                        left,
+                        &[],
+                    )
+                } else {
+                    ntr_format(
+                        "Match! {count} pair remaining",
+                        "Match! {count} pairs remaining",
+                        left,
+                        &[],
+                    )
+                }
+            })
+        }
+        GameEvent::MismatchMade(_) => Some(tr("No match")),
+        GameEvent::PenaltyApplied(summary) => Some(summary.clone()),
+        GameEvent::RoundFinished {
+            level_up: Some(level),
+            ..
+        } => Some(tr_format(
+            "Level up: {level}",
+            &[("level", &tr(infinite::level_name(*level)))],
+        )),
+        GameEvent::TileFlipped(_) | GameEvent::RoundFinished { .. } | GameEvent::BoardChanged => {
+            None
+        }
+    }
+}
+
+// The screen reader speaks it, in the player's voice and language settings.
+pub fn speak(st: &AppState, text: &str) {
+    if let Some(window) = &st.main_window {
+        window.announce(text, gtk4::AccessibleAnnouncementPriority::Medium);
+    }
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        let st = state.borrow();
+        if !st.spoken_announcements {
+            return;
+        }
+        if let Some(text) = announcement(&st, event) {
+            speak(&st, &text);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::{Tile, TileStatus};
+
+    fn board(matched: usize, hidden: usize) -> Vec<Tile> {
+        let tile = |status| Tile {
+            value: String::new(),
+            status,
+            seen: false,
+            views: 0,
+            tag: None,
+        };
+        let mut tiles = vec![tile(TileStatus::Matched); matched];
+        tiles.extend(vec![tile(TileStatus::Hidden); hidden]);
+        tiles
+    }
+
+    #[test]
+    fn matches_announce_what_is_left() {
+        let mut st = AppState {
+            tiles: board(4, 8),
+            match_size: 2,
+            ..AppState::default()
+        };
+        let event = GameEvent::MatchFound(vec![0, 1]);
+        assert_eq!(
+            announcement(&st, &event).as_deref(),
+            Some("Match! 4 pairs remaining")
+        );
+
+        st.tiles = board(10, 2);
+        assert_eq!(
+            announcement(&st, &event).as_deref(),
+            Some("Match! 1 pair remaining")
+        );
+
+        st.tiles = board(12, 0);
+        assert_eq!(
+            announcement(&st, &event).as_deref(),
+            Some("Match! Board cleared")
+        );
+        assert_eq!(announcement(&st, &GameEvent::TileFlipped(3)), None);
+    }
+}
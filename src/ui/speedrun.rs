@@ -0,0 +1,153 @@
+use gtk4 as gtk;
+use gtk4::prelude::*;
+
+use crate::i18n::{format_duration, format_fraction, tr};
+
+use super::state::{AppState, Difficulty, SpeedrunRecord};
+
+pub fn personal_best(records: &[SpeedrunRecord], level: u8) -> Option<&SpeedrunRecord> {
+    records
+        .iter()
+        .filter(|record| record.level == level)
+        .min_by_key(|record| record.time_ms)
+}
+
+pub fn split_delta_ms(current_ms: u64, best_ms: u64) -> i64 {
+    current_ms as i64 - best_ms as i64
+}
+
+pub fn format_split(ms: u64) -> String {
+    let total_secs = ms / 1000;
+    let hundredths = (ms % 1000) / 10;
+    format_fraction(format_duration(total_secs as u32), &format!("{hundredths:02}"))
+}
+
+pub fn format_delta(delta_ms: i64) -> String {
+    let sign = if delta_ms < 0 { '-' } else { '+' };
+    let abs_ms = delta_ms.unsigned_abs();
+    format_fraction(format!("{sign}{}", abs_ms / 1000), &format!("{:02}", (abs_ms % 1000) / 10))
+}
+
+pub fn build_splits_panel() -> (gtk::Box, gtk::ListBox) {
+    let panel = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    panel.add_css_class("speedrun-splits");
+    panel.set_size_request(190, -1);
+    panel.set_vexpand(true);
+    panel.set_visible(false);
+
+    let title = gtk::Label::new(Some(&tr("Splits")));
+    title.add_css_class("heading");
+    title.set_halign(gtk::Align::Start);
+
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    list.add_css_class("boxed-list");
+
+    let scroller = gtk::ScrolledWindow::new();
+    scroller.set_hscrollbar_policy(gtk::PolicyType::Never);
+    scroller.set_vexpand(true);
+    scroller.set_child(Some(&list));
+
+    panel.append(&title);
+    panel.append(&scroller);
+    (panel, list)
+}
+
+fn split_row(index: usize, current_ms: Option<u64>, best_ms: Option<u64>) -> gtk::Box {
+    let row = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    row.set_margin_top(4);
+    row.set_margin_bottom(4);
+    row.set_margin_start(10);
+    row.set_margin_end(10);
+
+    let name = gtk::Label::new(Some(&format!("{} {}", tr("Pair"), index + 1)));
+    name.set_hexpand(true);
+    name.set_halign(gtk::Align::Start);
+    row.append(&name);
+
+    if let (Some(current_ms), Some(best_ms)) = (current_ms, best_ms) {
+        let delta_ms = split_delta_ms(current_ms, best_ms);
+        let delta = gtk::Label::new(Some(&format_delta(delta_ms)));
+        delta.add_css_class("numeric");
+        delta.add_css_class("caption");
+        delta.add_css_class(if delta_ms <= 0 { "success" } else { "error" });
+        row.append(&delta);
+    }
+
+    let time = gtk::Label::new(current_ms.or(best_ms).map(format_split).as_deref());
+    time.add_css_class("numeric");
+    if current_ms.is_none() {
+        time.add_css_class("dim-label");
+    }
+    row.append(&time);
+    row
+}
+
+pub fn refresh_splits_panel(st: &AppState) {
+    let visible = st.speedrun && !st.compact_layout;
+    if let Some(panel) = &st.splits_panel {
+        panel.set_visible(visible);
+    }
+    let Some(list) = &st.splits_list else {
+        return;
+    };
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    if !visible {
+        return;
+    }
+
+    let level = classic_level(st);
+    let best_splits = personal_best(&st.records.speedrun, level)
+        .map(|record| record.splits_ms.as_slice())
+        .unwrap_or_default();
+    let pair_count = st.tiles.len() / st.match_size.max(1);
+    for index in 0..pair_count {
+        let row = split_row(
+            index,
+            st.match_times_ms.get(index).copied(),
+            best_splits.get(index).copied(),
+        );
+        list.append(&row);
+    }
+}
+
+pub fn classic_level(st: &AppState) -> u8 {
+    match st.difficulty {
+        Difficulty::Medium => 2,
+        Difficulty::Hard => 3,
+        Difficulty::Impossible => 4,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(level: u8, time_ms: u64) -> SpeedrunRecord {
+        SpeedrunRecord {
+            level,
+            time_ms,
+            splits_ms: vec![time_ms],
+            recorded_at: None,
+        }
+    }
+
+    #[test]
+    fn personal_best_is_fastest_run_for_level() {
+        let records = vec![record(2, 42_000), record(2, 38_500), record(3, 10_000)];
+
+        assert_eq!(personal_best(&records, 2).map(|r| r.time_ms), Some(38_500));
+        assert!(personal_best(&records, 4).is_none());
+    }
+
+    #[test]
+    fn split_formatting_uses_hundredths() {
+        assert_eq!(format_split(83_456), "01:23.45");
+        assert_eq!(format_delta(split_delta_ms(9_000, 10_250)), "-1.25");
+        assert_eq!(format_delta(split_delta_ms(10_500, 10_000)), "+0.50");
+    }
+}
@@ -1,7 +1,41 @@
 use gtk4 as gtk;
 use libadwaita as adw;
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 
+use super::audio::AudioCues;
+use super::bracket::Bracket;
+use super::board_canvas::BoardCanvas;
+use super::breaks::BreakReminder;
+use super::challenge::{self, Challenge};
+use super::custom::CustomPreset;
+use super::defeat::FailCondition;
+use super::game_title::GameTitle;
+use super::gauntlet::GauntletRun;
+use super::heatmap::MistakeHeatmap;
+use super::hint::HINT_CHARGES;
+use super::hotseat::HotseatRun;
+use super::infinite::{self, Pacing};
+use super::infinite_flow::RoundBanner;
+use super::faces::{self, CardFaces};
+use super::fonts::{FontChoice, TextScale};
+use super::kids::{KIDS_SYMBOLS, KidsBoard};
+use super::rank_art::ArtPack;
+use super::transitions::WavePattern;
+use super::layout::{ReshuffleStrategy, allowed_adjacent_pairs, plan_reshuffle, repair_adjacent};
+use super::nback::NBackRun;
+use super::sequence::SequenceHandle;
+use super::session_recap::SessionTally;
+use super::similarity::{lookalike_share, select_symbols};
+use super::stats_panel::StatsPanel;
+use super::symbol_stats::SymbolStats;
+use super::tags::TileTag;
+use super::timers::Timer;
+use super::pattern::PatternRun;
+use super::play_limit::{DailyLimit, PlayTime};
+use super::tournament::TournamentRun;
+use super::training::TrainingSession;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum TileStatus {
     Hidden,
@@ -13,9 +47,14 @@ pub enum TileStatus {
 pub struct Tile {
     pub value: String,
     pub status: TileStatus,
+    // Turned over by the player at least once since it was dealt or reshuffled.
+    pub seen: bool,
+    // Times the player turned this card over, kept when it is reshuffled.
+    pub views: u32,
+    pub tag: Option<TileTag>,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub enum Difficulty {
     #[default]
     Easy,
@@ -24,6 +63,8 @@ pub enum Difficulty {
     Impossible,
     Trio,
     Infinite,
+    // Infinite's endless progression on match-3 boards.
+    InfiniteTrio,
 }
 
 impl Difficulty {
@@ -33,7 +74,7 @@ impl Difficulty {
             Difficulty::Medium => Some((4, 6, 2)),
             Difficulty::Hard => Some((6, 7, 2)),
             Difficulty::Impossible => Some((6, 8, 2)),
-            Difficulty::Trio | Difficulty::Infinite => None,
+            Difficulty::Trio | Difficulty::Infinite | Difficulty::InfiniteTrio => None,
         }
     }
 
@@ -45,6 +86,7 @@ impl Difficulty {
             Difficulty::Impossible => "Expert",
             Difficulty::Trio => "Trio",
             Difficulty::Infinite => "Infinite",
+            Difficulty::InfiniteTrio => "Infinite Trio",
         }
     }
 }
@@ -78,6 +120,8 @@ pub enum Rank {
 }
 
 impl Rank {
+    pub const ALL: [Rank; 4] = [Rank::S, Rank::A, Rank::B, Rank::C];
+
     pub fn as_str(self) -> &'static str {
         match self {
             Rank::S => "S",
@@ -98,22 +142,122 @@ impl Rank {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct ModeRecord {
     pub level: u8,
     pub time_secs: u32,
     pub precision_pct: u8,
     pub rank: Rank,
-    pub date_label: String,
+    pub recorded_at: Option<i64>,
+    // Comma-separated assist codes; empty for a pure run.
+    pub modifiers: String,
 }
 
-#[derive(Clone, Debug, Default)]
+impl ModeRecord {
+    // Runs with assists or house rules are kept, but never compete for bests.
+    pub fn is_ranked(&self) -> bool {
+        self.modifiers.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct InfiniteRecord {
     pub round: u32,
     pub segment_level: u8,
     pub segment_survival: u32,
     pub time_secs: u32,
-    pub date_label: String,
+    pub recorded_at: Option<i64>,
+    pub pacing: Pacing,
+    pub trio: bool,
+    pub modifiers: String,
+}
+
+impl InfiniteRecord {
+    pub fn is_ranked(&self) -> bool {
+        self.modifiers.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WeeklyRecord {
+    pub week: String,
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub rank: Rank,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TournamentRecord {
+    pub total_score: u32,
+    pub total_secs: u32,
+    pub precision_pct: u8,
+    pub board_scores: Vec<u32>,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GauntletRecord {
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub rank: Rank,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpeedrunRecord {
+    pub level: u8,
+    pub time_ms: u64,
+    pub splits_ms: Vec<u64>,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SuddenDeathRecord {
+    pub level: u8,
+    pub pairs_cleared: u32,
+    pub pairs_total: u32,
+    pub time_secs: u32,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PatternRecord {
+    pub accuracy_pct: u8,
+    pub longest_pattern: u32,
+    pub time_secs: u32,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NBackRecord {
+    pub n: u8,
+    pub position_pct: u8,
+    pub symbol_pct: u8,
+    pub trials: u32,
+    pub time_secs: u32,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IncompleteRecord {
+    pub mode: String,
+    pub level: u8,
+    pub reason: String,
+    pub pairs_cleared: u32,
+    pub pairs_total: u32,
+    pub time_secs: u32,
+    pub recorded_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CustomRecord {
+    pub name: String,
+    pub code: String,
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub rank: Rank,
+    pub recorded_at: Option<i64>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -121,6 +265,127 @@ pub struct PlayerRecords {
     pub classic: Vec<ModeRecord>,
     pub trio: Vec<ModeRecord>,
     pub infinite: Vec<InfiniteRecord>,
+    pub weekly: Vec<WeeklyRecord>,
+    pub tournament: Vec<TournamentRecord>,
+    pub gauntlet: Vec<GauntletRecord>,
+    pub speedrun: Vec<SpeedrunRecord>,
+    pub incomplete: Vec<IncompleteRecord>,
+    pub sudden_death: Vec<SuddenDeathRecord>,
+    pub pattern: Vec<PatternRecord>,
+    pub nback: Vec<NBackRecord>,
+    pub custom: Vec<CustomRecord>,
+}
+
+impl PlayerRecords {
+    pub fn entry_count(&self) -> usize {
+        self.classic.len()
+            + self.trio.len()
+            + self.infinite.len()
+            + self.weekly.len()
+            + self.tournament.len()
+            + self.gauntlet.len()
+            + self.speedrun.len()
+            + self.incomplete.len()
+            + self.sudden_death.len()
+            + self.pattern.len()
+            + self.nback.len()
+            + self.custom.len()
+    }
+
+    // Puts older entries back in front, so history keeps its order.
+    pub fn prepend(&mut self, older: PlayerRecords) {
+        fn put_before<T>(current: &mut Vec<T>, mut older: Vec<T>) {
+            older.append(current);
+            *current = older;
+        }
+        put_before(&mut self.classic, older.classic);
+        put_before(&mut self.trio, older.trio);
+        put_before(&mut self.infinite, older.infinite);
+        put_before(&mut self.weekly, older.weekly);
+        put_before(&mut self.tournament, older.tournament);
+        put_before(&mut self.gauntlet, older.gauntlet);
+        put_before(&mut self.speedrun, older.speedrun);
+        put_before(&mut self.incomplete, older.incomplete);
+        put_before(&mut self.sudden_death, older.sudden_death);
+        put_before(&mut self.pattern, older.pattern);
+        put_before(&mut self.nback, older.nback);
+        put_before(&mut self.custom, older.custom);
+    }
+
+    // Adds the entries this set lacks and keeps each list in recording order.
+    // Returns how many entries were new.
+    pub fn merge(&mut self, other: PlayerRecords) -> usize {
+        fn union<T: PartialEq>(
+            current: &mut Vec<T>,
+            incoming: Vec<T>,
+            recorded_at: fn(&T) -> Option<i64>,
+        ) -> usize {
+            let before = current.len();
+            for entry in incoming {
+                if !current.contains(&entry) {
+                    current.push(entry);
+                }
+            }
+            current.sort_by_key(recorded_at);
+            current.len() - before
+        }
+        union(&mut self.classic, other.classic, |entry| entry.recorded_at)
+            + union(&mut self.trio, other.trio, |entry| entry.recorded_at)
+            + union(&mut self.infinite, other.infinite, |entry| entry.recorded_at)
+            + union(&mut self.weekly, other.weekly, |entry| entry.recorded_at)
+            + union(&mut self.tournament, other.tournament, |entry| entry.recorded_at)
+            + union(&mut self.gauntlet, other.gauntlet, |entry| entry.recorded_at)
+            + union(&mut self.speedrun, other.speedrun, |entry| entry.recorded_at)
+            + union(&mut self.incomplete, other.incomplete, |entry| entry.recorded_at)
+            + union(&mut self.sudden_death, other.sudden_death, |entry| entry.recorded_at)
+            + union(&mut self.pattern, other.pattern, |entry| entry.recorded_at)
+            + union(&mut self.nback, other.nback, |entry| entry.recorded_at)
+            + union(&mut self.custom, other.custom, |entry| entry.recorded_at)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunClock {
+    accumulated_us: i64,
+    resumed_at_us: Option<i64>,
+}
+
+impl RunClock {
+    pub fn starting_at_ms(elapsed_ms: u64) -> Self {
+        Self {
+            accumulated_us: elapsed_ms as i64 * 1000,
+            resumed_at_us: None,
+        }
+    }
+
+    pub fn resume(&mut self, now_us: i64) {
+        if self.resumed_at_us.is_none() {
+            self.resumed_at_us = Some(now_us);
+        }
+    }
+
+    pub fn pause(&mut self, now_us: i64) {
+        if let Some(resumed_at) = self.resumed_at_us.take() {
+            self.accumulated_us += (now_us - resumed_at).max(0);
+        }
+    }
+
+    pub fn elapsed_ms(&self, now_us: i64) -> u64 {
+        let running_us = self
+            .resumed_at_us
+            .map(|resumed_at| (now_us - resumed_at).max(0))
+            .unwrap_or(0);
+        ((self.accumulated_us + running_us) / 1000) as u64
+    }
+}
+
+// Multi-board runs that carry progress from one board to the next; only one
+// can be under way at a time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Series {
+    Tournament(TournamentRun),
+    Gauntlet(GauntletRun),
+    Training(TrainingSession),
 }
 
 pub struct AppState {
@@ -130,46 +395,146 @@ pub struct AppState {
     pub menu_button: Option<gtk::MenuButton>,
     pub restart_button: Option<gtk::Button>,
     pub continue_button: Option<gtk::Button>,
+    pub session_recap_card: Option<gtk::Box>,
+    pub session_recap_label: Option<gtk::Label>,
     pub title_menu: Option<gtk::Label>,
-    pub title_game: Option<gtk::Widget>,
-    pub title_game_subtitle: Option<gtk::Label>,
+    pub game_title: Option<GameTitle>,
     pub header_timer_label: Option<gtk::Label>,
     pub title_victory: Option<gtk::Widget>,
     pub victory_title_label: Option<gtk::Label>,
     pub victory_message_label: Option<gtk::Label>,
     pub victory_stats_label: Option<gtk::Label>,
+    pub victory_goal_label: Option<gtk::Label>,
+    pub victory_record_banner: Option<gtk::Label>,
+    pub victory_again_button: Option<gtk::Button>,
+    pub victory_buttons: Option<gtk::Box>,
     pub victory_rank_art: Option<gtk::Image>,
+    pub victory_rank_info: Option<gtk::MenuButton>,
+    pub victory_rank_info_label: Option<gtk::Label>,
     pub victory_art_resource: Option<String>,
-    pub victory_spark_layer: Option<gtk::Fixed>,
+    pub victory_spark_layer: Option<gtk::DrawingArea>,
+    pub splash_title_label: Option<gtk::Label>,
+    pub splash_subtitle_label: Option<gtk::Label>,
+    pub splash_stats_label: Option<gtk::Label>,
+    pub splash_skip_button: Option<gtk::Button>,
+    pub defeat_title_label: Option<gtk::Label>,
+    pub defeat_message_label: Option<gtk::Label>,
+    pub defeat_stats_label: Option<gtk::Label>,
+    pub defeat_retry_button: Option<gtk::Button>,
+    pub defeat_buttons: Option<gtk::Box>,
+    pub splits_panel: Option<gtk::Box>,
+    pub splits_list: Option<gtk::ListBox>,
+    pub nback_controls: Option<gtk::Box>,
+    pub toast_overlay: Option<adw::ToastOverlay>,
+    pub spectator_window: Option<adw::Window>,
+    pub main_window: Option<adw::ApplicationWindow>,
+    pub victory_cascade: Option<SequenceHandle>,
+    pub round_banner: Option<RoundBanner>,
+    pub round_interstitial: Option<SequenceHandle>,
+    pub round_transition: Option<SequenceHandle>,
     pub board_container: Option<gtk::Box>,
     pub board_shell: Option<gtk::AspectFrame>,
+    pub board_clamp: Option<adw::Clamp>,
+    pub board_bin: Option<adw::BreakpointBin>,
+    pub narrow_breakpoint: Option<adw::Breakpoint>,
     pub dynamic_css_provider: Option<gtk::CssProvider>,
+    pub board_css: String,
+    pub font_choice: FontChoice,
+    pub text_scale: TextScale,
+    pub card_faces: CardFaces,
+    // Whether the board on screen is drawn with the bundled artwork.
+    pub artwork_faces: bool,
+    pub rank_art: ArtPack,
+    pub board_transition: WavePattern,
+    pub reshuffle_strategy: ReshuffleStrategy,
+    pub cruel_symbols: bool,
+    pub scaled_similarity: bool,
+    pub audio_cues: AudioCues,
+    pub spoken_announcements: bool,
+    pub dynamic_music: bool,
+    pub expert_warmup: bool,
+    pub collect_habits: bool,
+    pub kids_daily_limit: DailyLimit,
+    pub kids_play: PlayTime,
+    pub stream_overlay: bool,
+    pub show_match_progress: bool,
+    pub progress_in_title: bool,
+    pub left_handed: bool,
+    pub wide_layout: bool,
+    pub stats_panel: Option<StatsPanel>,
+    pub match_streak: u32,
+    pub best_match_streak: u32,
+    pub match_progress: Option<(gtk::Box, gtk::Label, gtk::LevelBar)>,
+    pub penalty_pips: Option<gtk::Box>,
     pub compact_layout: bool,
+    pub show_coordinates: bool,
+    pub peek_assist: bool,
+    pub first_mismatch_grace: bool,
+    pub peeking: bool,
+    pub tile_tags: bool,
+    pub hint_assist: bool,
+    pub hint_charges: u32,
 
     // Game state
     pub tiles: Vec<Tile>,
     pub flipped_indices: Vec<usize>,
-    pub grid_buttons: Vec<gtk::Button>,
+    pub board_canvas: Option<BoardCanvas>,
     pub lock_input: bool,
-    pub flip_anim_phase: bool,
+    pub pending_coordinate_col: Option<i32>,
     pub game_id: u64,
+    pub game_span: tracing::Span,
     pub grid_cols: i32,
     pub grid_rows: i32,
     pub match_size: usize,
+    // Every deal draws from a seed, so a dumped board can be dealt again.
+    pub board_seed: u64,
+    // Carries on from the deal's seed, so a shared deal's reshuffles are shared too.
+    pub board_rng: ChaCha8Rng,
     pub difficulty: Difficulty,
     pub trio_level: u8,
     pub infinite_level: u8,
     pub infinite_round: u32,
+    pub infinite_pacing: Pacing,
+    pub infinite_best_round: Option<u32>,
+    pub preferred_pacing: Pacing,
+    pub break_reminder: BreakReminder,
+    // Round and elapsed seconds when the current stretch without a break began.
+    pub break_mark: (u32, u32),
+    pub challenge: Option<Challenge>,
+    pub series: Option<Series>,
+    pub hotseat: Option<HotseatRun>,
+    // Set while the hotseat run is one head-to-head match of a bracket.
+    pub bracket: Option<Bracket>,
+    pub training_bar: Option<gtk::ProgressBar>,
+    pub speedrun: bool,
+    pub sudden_death: bool,
+    pub pattern: Option<PatternRun>,
+    pub nback: Option<NBackRun>,
+    pub kids: Option<KidsBoard>,
+    pub custom: Option<CustomPreset>,
+    // The board a warm-up hands over to once it is cleared.
+    pub warmup_target: Option<Difficulty>,
+    // Assists and house rules that were on at any point of this run; any of
+    // them keeps it unranked.
+    pub run_modifier_codes: Vec<String>,
+    pub fail_condition: FailCondition,
+    pub run_clock: RunClock,
+    pub match_times_ms: Vec<u64>,
     pub impossible_mismatch_count: u8,
     pub impossible_punish_stage: u8,
     pub impossible_last_first_index: Option<usize>,
     pub impossible_same_first_streak: u8,
     pub preview_active: bool,
     pub preview_remaining_ms: u32,
-    pub preview_handle: Option<glib::SourceId>,
+    pub preview_total_ms: u32,
+    pub preview_bar: Option<gtk::ProgressBar>,
+    pub perf_hud: Option<gtk::Label>,
+    pub preview_handle: Option<Timer>,
+    // Set while an overlay covers the preview, which freezes its countdown.
+    pub preview_paused: bool,
     pub seconds_elapsed: u32,
-    pub timer_handle: Option<glib::SourceId>,
-    pub spark_timer_handle: Option<glib::SourceId>,
+    pub timer_handle: Option<Timer>,
+    pub spark_tick: Option<gtk::TickCallbackId>,
     pub run_mismatches: u32,
     pub run_matches: u32,
     pub active_session_started: bool,
@@ -178,7 +543,15 @@ pub struct AppState {
     pub victory_message_text: String,
     pub victory_stats_text: String,
     pub victory_rank: Rank,
+    // What the next rank would have needed; only ranked classic and Trio runs set it.
+    pub victory_rank_hint: Option<String>,
+    pub victory_goal_text: Option<String>,
+    // Set when the run beat a stored personal best, not on the first run of a board.
+    pub victory_new_record: bool,
     pub records: PlayerRecords,
+    pub symbol_stats: SymbolStats,
+    pub mistake_heatmap: MistakeHeatmap,
+    pub session_tally: SessionTally,
 }
 
 impl Default for AppState {
@@ -190,44 +563,135 @@ impl Default for AppState {
             menu_button: None,
             restart_button: None,
             continue_button: None,
+            session_recap_card: None,
+            session_recap_label: None,
             title_menu: None,
-            title_game: None,
-            title_game_subtitle: None,
+            game_title: None,
             header_timer_label: None,
             title_victory: None,
             victory_title_label: None,
             victory_message_label: None,
             victory_stats_label: None,
+            victory_goal_label: None,
+            victory_record_banner: None,
+            victory_again_button: None,
+            victory_buttons: None,
             victory_rank_art: None,
+            victory_rank_info: None,
+            victory_rank_info_label: None,
             victory_art_resource: None,
             victory_spark_layer: None,
+            splash_title_label: None,
+            splash_subtitle_label: None,
+            splash_stats_label: None,
+            splash_skip_button: None,
+            defeat_title_label: None,
+            defeat_message_label: None,
+            defeat_stats_label: None,
+            defeat_retry_button: None,
+            defeat_buttons: None,
+            splits_panel: None,
+            splits_list: None,
+            nback_controls: None,
+            toast_overlay: None,
+            spectator_window: None,
+            main_window: None,
+            victory_cascade: None,
+            round_banner: None,
+            round_interstitial: None,
+            round_transition: None,
             board_container: None,
             board_shell: None,
+            board_clamp: None,
+            board_bin: None,
+            narrow_breakpoint: None,
             dynamic_css_provider: None,
+            board_css: String::new(),
+            font_choice: FontChoice::Default,
+            text_scale: TextScale::Normal,
+            card_faces: CardFaces::Emoji,
+            artwork_faces: false,
+            rank_art: ArtPack::Classic,
+            board_transition: WavePattern::Corners,
+            reshuffle_strategy: ReshuffleStrategy::Random,
+            cruel_symbols: false,
+            scaled_similarity: true,
+            audio_cues: AudioCues::default(),
+            spoken_announcements: false,
+            dynamic_music: false,
+            expert_warmup: false,
+            collect_habits: false,
+            kids_daily_limit: DailyLimit::Off,
+            kids_play: PlayTime::default(),
+            stream_overlay: false,
+            show_match_progress: true,
+            progress_in_title: false,
+            left_handed: false,
+            wide_layout: false,
+            stats_panel: None,
+            match_streak: 0,
+            best_match_streak: 0,
+            match_progress: None,
+            penalty_pips: None,
             compact_layout: false,
+            show_coordinates: false,
+            peek_assist: false,
+            first_mismatch_grace: false,
+            peeking: false,
+            tile_tags: false,
+            hint_assist: false,
+            hint_charges: HINT_CHARGES,
             tiles: Vec::new(),
             flipped_indices: Vec::new(),
-            grid_buttons: Vec::new(),
+            board_canvas: None,
             lock_input: false,
-            flip_anim_phase: false,
+            pending_coordinate_col: None,
             game_id: 0,
+            game_span: tracing::Span::none(),
             grid_cols: 0,
             grid_rows: 0,
             match_size: 2,
+            board_seed: 0,
+            board_rng: challenge::seeded_rng(0),
             difficulty: Difficulty::Easy,
             trio_level: 3,
             infinite_level: 2,
             infinite_round: 1,
+            infinite_pacing: Pacing::Standard,
+            infinite_best_round: None,
+            preferred_pacing: Pacing::Standard,
+            break_reminder: BreakReminder::Off,
+            break_mark: (1, 0),
+            challenge: None,
+            series: None,
+            hotseat: None,
+            bracket: None,
+            training_bar: None,
+            speedrun: false,
+            sudden_death: false,
+            pattern: None,
+            nback: None,
+            kids: None,
+            custom: None,
+            warmup_target: None,
+            run_modifier_codes: Vec::new(),
+            fail_condition: FailCondition::Off,
+            run_clock: RunClock::default(),
+            match_times_ms: Vec::new(),
             impossible_mismatch_count: 0,
             impossible_punish_stage: 0,
             impossible_last_first_index: None,
             impossible_same_first_streak: 0,
             preview_active: false,
             preview_remaining_ms: 0,
+            preview_total_ms: 0,
+            preview_bar: None,
+            perf_hud: None,
             preview_handle: None,
+            preview_paused: false,
             seconds_elapsed: 0,
             timer_handle: None,
-            spark_timer_handle: None,
+            spark_tick: None,
             run_mismatches: 0,
             run_matches: 0,
             active_session_started: false,
@@ -236,12 +700,66 @@ impl Default for AppState {
             victory_message_text: String::new(),
             victory_stats_text: String::new(),
             victory_rank: Rank::C,
+            victory_rank_hint: None,
+            victory_goal_text: None,
+            victory_new_record: false,
             records: PlayerRecords::default(),
+            symbol_stats: SymbolStats::default(),
+            mistake_heatmap: MistakeHeatmap::default(),
+            session_tally: SessionTally::default(),
         }
     }
 }
 
 impl AppState {
+    pub fn tournament(&self) -> Option<&TournamentRun> {
+        match &self.series {
+            Some(Series::Tournament(run)) => Some(run),
+            _ => None,
+        }
+    }
+
+    pub fn tournament_mut(&mut self) -> Option<&mut TournamentRun> {
+        match &mut self.series {
+            Some(Series::Tournament(run)) => Some(run),
+            _ => None,
+        }
+    }
+
+    pub fn gauntlet(&self) -> Option<GauntletRun> {
+        match self.series {
+            Some(Series::Gauntlet(run)) => Some(run),
+            _ => None,
+        }
+    }
+
+    pub fn gauntlet_mut(&mut self) -> Option<&mut GauntletRun> {
+        match &mut self.series {
+            Some(Series::Gauntlet(run)) => Some(run),
+            _ => None,
+        }
+    }
+
+    pub fn training(&self) -> Option<&TrainingSession> {
+        match &self.series {
+            Some(Series::Training(session)) => Some(session),
+            _ => None,
+        }
+    }
+
+    pub fn training_mut(&mut self) -> Option<&mut TrainingSession> {
+        match &mut self.series {
+            Some(Series::Training(session)) => Some(session),
+            _ => None,
+        }
+    }
+
+    // Tournament and Gauntlet boards are scored as part of their run; training
+    // games are ordinary boards with rests in between.
+    pub fn in_scored_series(&self) -> bool {
+        matches!(self.series, Some(Series::Tournament(_) | Series::Gauntlet(_)))
+    }
+
     fn apply_grid_config(&mut self, cols: i32, rows: i32, match_size: usize) {
         self.grid_cols = cols;
         self.grid_rows = rows;
@@ -249,16 +767,30 @@ impl AppState {
     }
 
     fn config_for_current_difficulty(&self, difficulty: Difficulty) -> (i32, i32, usize) {
+        if let Some(kids) = self.kids {
+            let (cols, rows) = kids.grid();
+            return (cols, rows, 2);
+        }
+        if let Some(custom) = &self.custom {
+            return (custom.cols, custom.rows, custom.match_size);
+        }
         match difficulty {
             Difficulty::Trio => Self::trio_config(self.trio_level),
-            Difficulty::Infinite => Self::infinite_config(self.infinite_level),
+            Difficulty::Infinite => Self::infinite_config(self.infinite_level, false),
+            Difficulty::InfiniteTrio => Self::infinite_config(self.infinite_level, true),
             _ => difficulty
                 .fixed_config()
                 .expect("fixed config required for classic difficulties"),
         }
     }
 
-    fn trio_config(level: u8) -> (i32, i32, usize) {
+    pub fn needs_new_grid(&self, difficulty: Difficulty) -> bool {
+        self.difficulty != difficulty
+            || self.config_for_current_difficulty(difficulty)
+                != (self.grid_cols, self.grid_rows, self.match_size)
+    }
+
+    pub(super) fn trio_config(level: u8) -> (i32, i32, usize) {
         match level.clamp(1, 4) {
             1 => (4, 6, 3),
             2 => (5, 6, 3),
@@ -267,7 +799,11 @@ impl AppState {
         }
     }
 
-    fn infinite_config(level: u8) -> (i32, i32, usize) {
+    // Endless Trio climbs the Trio boards; the pairs variant has its own ladder.
+    fn infinite_config(level: u8, trio: bool) -> (i32, i32, usize) {
+        if trio {
+            return Self::trio_config(level);
+        }
         match level.clamp(1, 4) {
             1 => (3, 4, 2),
             2 => (4, 6, 2),
@@ -285,7 +821,7 @@ impl AppState {
     pub fn set_difficulty(&mut self, difficulty: Difficulty) {
         self.difficulty = difficulty;
         self.reset_impossible_pressure();
-        if difficulty == Difficulty::Infinite {
+        if infinite::is_infinite(difficulty) {
             self.infinite_round = 1;
         }
         let (cols, rows, match_size) = self.config_for_current_difficulty(difficulty);
@@ -304,8 +840,8 @@ impl AppState {
 
     pub fn set_infinite_level(&mut self, level: u8) {
         self.infinite_level = level.clamp(1, 4);
-        if self.difficulty == Difficulty::Infinite {
-            let (cols, rows, match_size) = Self::infinite_config(self.infinite_level);
+        if infinite::is_infinite(self.difficulty) {
+            let (cols, rows, match_size) = Self::infinite_config(self.infinite_level, self.infinite_trio());
             self.apply_grid_config(cols, rows, match_size);
             self.reset_game();
         }
@@ -313,10 +849,14 @@ impl AppState {
 
     pub fn apply_infinite_level_without_reset(&mut self, level: u8) {
         self.infinite_level = level.clamp(1, 4);
-        let (cols, rows, match_size) = Self::infinite_config(self.infinite_level);
+        let (cols, rows, match_size) = Self::infinite_config(self.infinite_level, self.infinite_trio());
         self.apply_grid_config(cols, rows, match_size);
     }
 
+    pub fn infinite_trio(&self) -> bool {
+        self.difficulty == Difficulty::InfiniteTrio
+    }
+
     pub fn reset_infinite_round(&mut self) {
         self.infinite_round = 1;
     }
@@ -327,6 +867,18 @@ impl AppState {
 
     pub fn invalidate_callbacks(&mut self) {
         self.game_id = self.game_id.wrapping_add(1);
+        if let Some(cascade) = self.victory_cascade.take() {
+            cascade.cancel();
+        }
+        if let Some(interstitial) = self.round_interstitial.take() {
+            interstitial.cancel();
+        }
+        if let Some(transition) = self.round_transition.take() {
+            transition.cancel();
+        }
+        if let Some(banner) = &self.round_banner {
+            banner.hide();
+        }
     }
 
     pub fn reset_impossible_pressure(&mut self) {
@@ -337,48 +889,199 @@ impl AppState {
     }
 
     pub fn reshuffle_hidden_tiles(&mut self) {
-        use rand::Rng;
+        let keys = self
+            .tiles
+            .iter()
+            .map(|tile| (tile.status == TileStatus::Hidden).then_some(tile.value.as_str()))
+            .collect::<Vec<Option<&str>>>();
+        if keys.iter().flatten().count() < 2 {
+            return;
+        }
+        let seen = self.tiles.iter().map(|tile| tile.seen).collect::<Vec<bool>>();
+        let cols = self.grid_cols.max(1) as usize;
+        let allowed_adjacent = allowed_adjacent_pairs(self);
+        let strategy = self.reshuffle_strategy_in_play();
+        let order = plan_reshuffle(
+            &keys,
+            &seen,
+            cols,
+            strategy,
+            allowed_adjacent,
+            &mut self.board_rng,
+        );
 
-        let mut hidden_indices = Vec::new();
-        for (idx, tile) in self.tiles.iter().enumerate() {
-            if tile.status == TileStatus::Hidden {
-                hidden_indices.push(idx);
+        let dealt = self.tiles.clone();
+        for (idx, origin) in order.into_iter().enumerate() {
+            if dealt[idx].status != TileStatus::Hidden {
+                continue;
             }
+            self.tiles[idx] = dealt[origin].clone();
+            self.tiles[idx].seen = false;
+            self.tiles[idx].tag = None;
         }
+    }
 
-        if hidden_indices.len() < 2 {
-            return;
+    // Seeded and scored runs must deal out the same reshuffles for everyone.
+    pub fn reshuffle_strategy_in_play(&self) -> ReshuffleStrategy {
+        if self.modifiers_apply() {
+            self.reshuffle_strategy
+        } else {
+            ReshuffleStrategy::Random
         }
+    }
 
-        let mut rng = rand::rng();
-        for shuffle_end in (1..hidden_indices.len()).rev() {
-            let swap_pos = rng.random_range(0..=shuffle_end);
-            if swap_pos == shuffle_end {
-                continue;
+    pub fn reseed_board_rng(&mut self) {
+        let seed = self.shared_seed().unwrap_or_else(rand::random);
+        self.seed_board_rng(seed);
+    }
+
+    pub fn seed_board_rng(&mut self, seed: u64) {
+        self.board_seed = seed;
+        self.board_rng = challenge::seeded_rng(seed);
+    }
+
+    pub fn with_board_rng<T>(&mut self, draw: impl FnOnce(&mut dyn rand::RngCore) -> T) -> T {
+        draw(&mut self.board_rng)
+    }
+
+    // Shared deals: a challenge, or every turn of a pass-the-device game.
+    pub fn shared_seed(&self) -> Option<u64> {
+        self.challenge
+            .as_ref()
+            .map(|challenge| challenge.seed)
+            .or_else(|| self.hotseat.as_ref().map(|run| run.seed))
+    }
+
+    fn deal_symbols<R: rand::Rng>(&self, group_count: usize, rng: &mut R) -> Vec<&'static str> {
+        use rand::seq::SliceRandom;
+
+        let mut values = Vec::with_capacity(group_count * self.match_size);
+        let mut symbol_pool = if self.shared_seed().is_some() {
+            SYMBOL_POOL.to_vec()
+        } else if self.kids.is_some() {
+            KIDS_SYMBOLS.to_vec()
+        } else if self.card_faces == CardFaces::Artwork && faces::fits(group_count) {
+            faces::symbols()
+        } else {
+            if self.card_faces == CardFaces::Artwork {
+                tracing::info!(
+                    "{group_count} symbols needed, more than the artwork has; dealing emoji"
+                );
+            }
+            SYMBOL_POOL.to_vec()
+        };
+        symbol_pool.shuffle(rng);
+        for symbol in select_symbols(&symbol_pool, group_count, lookalike_share(self)) {
+            for _ in 0..self.match_size {
+                values.push(symbol);
             }
+        }
 
-            let left_index = hidden_indices[shuffle_end];
-            let right_index = hidden_indices[swap_pos];
-            let (first, second) = if left_index < right_index {
-                let (left, right) = self.tiles.split_at_mut(right_index);
-                (&mut left[left_index].value, &mut right[0].value)
-            } else {
-                let (left, right) = self.tiles.split_at_mut(left_index);
-                (&mut right[0].value, &mut left[right_index].value)
-            };
-            std::mem::swap(first, second);
+        values.shuffle(rng);
+        repair_adjacent(
+            &mut values,
+            self.grid_cols.max(1) as usize,
+            allowed_adjacent_pairs(self),
+            rng,
+        );
+        values
+    }
+
+    pub fn record_match_time(&mut self, now_us: i64) {
+        let elapsed_ms = self.run_clock.elapsed_ms(now_us);
+        self.match_times_ms.push(elapsed_ms);
+    }
+
+    // Series, challenges and timed modes keep their own scoring, so assists
+    // only reach boards that can simply go unranked.
+    pub fn modifiers_apply(&self) -> bool {
+        self.challenge.is_none()
+            && !self.in_scored_series()
+            && self.hotseat.is_none()
+            && !self.speedrun
+            && !self.sudden_death
+            && self.pattern.is_none()
+            && self.nback.is_none()
+    }
+
+    fn active_modifiers(&self) -> Vec<&'static str> {
+        if !self.modifiers_apply() {
+            return Vec::new();
+        }
+        [
+            (self.peek_assist, "peek"),
+            (self.tile_tags, "tags"),
+            (self.hint_assist, "hint"),
+            (self.first_mismatch_grace, "grace"),
+            (
+                self.reshuffle_strategy != ReshuffleStrategy::Random,
+                self.reshuffle_strategy.code(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(on, code)| on.then_some(code))
+        .collect()
+    }
+
+    // Called when a run starts and whenever an assist is switched on, so the
+    // record lists everything that was used, not just what is on at the end.
+    pub fn note_run_modifiers(&mut self) {
+        for code in self.active_modifiers() {
+            if !self.run_modifier_codes.iter().any(|used| used == code) {
+                self.run_modifier_codes.push(code.to_string());
+            }
+        }
+    }
+
+    pub fn run_assisted(&self) -> bool {
+        !self.run_modifier_codes.is_empty()
+    }
+
+    pub fn run_modifiers(&self) -> String {
+        self.run_modifier_codes.join(",")
+    }
+
+    // A perfect memory turns each card of a group over exactly once.
+    pub fn views_per_match(&self) -> Option<f64> {
+        let matched: Vec<&Tile> = self
+            .tiles
+            .iter()
+            .filter(|tile| tile.status == TileStatus::Matched)
+            .collect();
+        let groups = matched.len() / self.match_size.max(1);
+        if groups == 0 {
+            return None;
         }
+        let views: u32 = matched.iter().map(|tile| tile.views).sum();
+        Some(f64::from(views) / groups as f64)
     }
 
     pub fn reset_game(&mut self) {
+        let seed = self.shared_seed().unwrap_or_else(rand::random);
+        self.reset_game_with_seed(seed);
+    }
+
+    pub fn reset_game_with_seed(&mut self, seed: u64) {
         self.invalidate_callbacks();
         self.tiles.clear();
         self.flipped_indices.clear();
+        self.pending_coordinate_col = None;
         self.lock_input = false;
+        self.peeking = false;
+        self.hint_charges = HINT_CHARGES;
         self.reset_impossible_pressure();
-        if self.difficulty != Difficulty::Infinite || self.infinite_round <= 1 {
+        let carries_run_totals = self
+            .gauntlet()
+            .is_some_and(|gauntlet| gauntlet.carries_run_totals());
+        if !carries_run_totals
+            && (!infinite::is_infinite(self.difficulty) || self.infinite_round <= 1)
+        {
             self.run_mismatches = 0;
             self.run_matches = 0;
+            self.match_streak = 0;
+            self.best_match_streak = 0;
+            self.run_clock = RunClock::default();
+            self.match_times_ms.clear();
         }
 
         let total_tiles = (self.grid_cols * self.grid_rows) as usize;
@@ -394,32 +1097,38 @@ impl AppState {
             "grid config requires more unique symbols than available"
         );
 
-        use rand::seq::SliceRandom;
-        let mut rng = rand::rng();
-        let mut values = Vec::with_capacity(total_tiles);
-
-        let mut symbol_pool = SYMBOL_POOL.to_vec();
-        symbol_pool.shuffle(&mut rng);
-        for symbol in symbol_pool.iter().take(group_count) {
-            for _ in 0..self.match_size {
-                values.push(symbol);
-            }
-        }
-
-        values.shuffle(&mut rng);
+        self.seed_board_rng(seed);
+        let mut rng = self.board_rng.clone();
+        let values = self.deal_symbols(group_count, &mut rng);
+        self.board_rng = rng;
 
         for value in values {
             self.tiles.push(Tile {
                 status: TileStatus::Hidden,
                 value: value.to_string(),
+                seen: false,
+                views: 0,
+                tag: None,
             });
         }
+        self.refresh_board_faces();
+    }
+
+    pub fn refresh_board_faces(&mut self) {
+        self.artwork_faces = self.card_faces == CardFaces::Artwork
+            && faces::covers(self.tiles.iter().map(|tile| tile.value.as_str()));
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AppState, Difficulty};
+    use super::{
+        AppState, Difficulty, KIDS_SYMBOLS, KidsBoard, ModeRecord, PlayerRecords, Rank, RunClock, Series,
+        Tile, TileStatus,
+    };
+    use crate::ui::challenge::Challenge;
+    use crate::ui::gauntlet::GauntletRun;
+    use crate::ui::layout::ReshuffleStrategy;
 
     #[test]
     fn classic_difficulties_divide_evenly_by_match_size() {
@@ -447,8 +1156,11 @@ mod tests {
     #[test]
     fn infinite_levels_divide_evenly_by_match_size() {
         for level in 1..=4 {
-            let (cols, rows, match_size) = AppState::infinite_config(level);
-            assert_eq!(((cols * rows) as usize) % match_size, 0);
+            for trio in [false, true] {
+                let (cols, rows, match_size) = AppState::infinite_config(level, trio);
+                assert_eq!(((cols * rows) as usize) % match_size, 0);
+                assert_eq!(match_size, if trio { 3 } else { 2 });
+            }
         }
     }
 
@@ -474,9 +1186,223 @@ mod tests {
         }
 
         for level in 1..=4 {
-            let (cols, rows, match_size) = AppState::infinite_config(level);
+            let (cols, rows, match_size) = AppState::infinite_config(level, false);
             let group_count = (cols * rows) as usize / match_size;
             assert!(group_count <= super::SYMBOL_POOL.len());
         }
     }
+
+    #[test]
+    fn artwork_symbols_come_from_the_pool() {
+        for symbol in crate::ui::faces::symbols() {
+            assert!(super::SYMBOL_POOL.contains(&symbol), "{symbol} is not in the pool");
+        }
+    }
+
+    #[test]
+    fn shared_deals_ignore_personal_symbol_settings() {
+        let deal = |configure: fn(&mut AppState)| {
+            let mut st = AppState {
+                challenge: Some(Challenge::weekly("2026-W42")),
+                ..Default::default()
+            };
+            configure(&mut st);
+            st.set_difficulty(Difficulty::Impossible);
+            st.tiles.into_iter().map(|tile| tile.value).collect::<Vec<String>>()
+        };
+        let plain = deal(|_| {});
+        assert_eq!(deal(|st| st.cruel_symbols = true), plain);
+        assert_eq!(deal(|st| st.scaled_similarity = false), plain);
+        assert_eq!(deal(|st| st.card_faces = crate::ui::faces::CardFaces::Artwork), plain);
+    }
+
+    #[test]
+    fn shared_boards_reshuffle_alike() {
+        let reshuffled = |reshuffle_strategy| {
+            let mut st = AppState {
+                challenge: Some(Challenge::weekly("2026-W42")),
+                reshuffle_strategy,
+                ..Default::default()
+            };
+            st.set_difficulty(Difficulty::Impossible);
+            st.reshuffle_hidden_tiles();
+            st.reshuffle_hidden_tiles();
+            st.tiles.into_iter().map(|tile| tile.value).collect::<Vec<String>>()
+        };
+        assert_eq!(reshuffled(ReshuffleStrategy::Random), reshuffled(ReshuffleStrategy::Random));
+        assert_eq!(reshuffled(ReshuffleStrategy::Spread), reshuffled(ReshuffleStrategy::Random));
+    }
+
+    #[test]
+    fn a_personal_reshuffle_strategy_marks_the_run() {
+        let mut st = AppState {
+            reshuffle_strategy: ReshuffleStrategy::MoveSeen,
+            ..Default::default()
+        };
+        st.note_run_modifiers();
+        assert_eq!(st.reshuffle_strategy_in_play(), ReshuffleStrategy::MoveSeen);
+        assert_eq!(st.run_modifiers(), "move-seen");
+
+        st.challenge = Some(Challenge::weekly("2026-W42"));
+        assert_eq!(st.reshuffle_strategy_in_play(), ReshuffleStrategy::Random);
+    }
+
+    #[test]
+    fn artwork_boards_keep_emoji_values() {
+        let mut st = AppState {
+            card_faces: crate::ui::faces::CardFaces::Artwork,
+            ..Default::default()
+        };
+        st.set_difficulty(Difficulty::Impossible);
+        assert!(st.artwork_faces);
+        assert!(st.tiles.iter().all(|tile| super::SYMBOL_POOL.contains(&tile.value.as_str())));
+
+        st.card_faces = crate::ui::faces::CardFaces::Emoji;
+        st.reset_game();
+        assert!(!st.artwork_faces);
+    }
+
+    #[test]
+    fn confusable_clusters_only_list_pool_symbols() {
+        for cluster in crate::ui::similarity::CONFUSABLE_CLUSTERS {
+            for symbol in *cluster {
+                assert!(super::SYMBOL_POOL.contains(symbol), "{symbol} is not in the pool");
+            }
+        }
+    }
+
+    #[test]
+    fn kids_preset_deals_cheerful_symbols_on_a_small_grid() {
+        let mut st = AppState {
+            kids: Some(KidsBoard::Small),
+            ..Default::default()
+        };
+        st.set_difficulty(Difficulty::Easy);
+
+        assert_eq!(st.tiles.len(), 6);
+        assert!(
+            st.tiles
+                .iter()
+                .all(|tile| KIDS_SYMBOLS.contains(&tile.value.as_str()))
+        );
+    }
+
+    #[test]
+    fn seeded_challenge_deals_the_same_board() {
+        let deal = || {
+            let mut st = AppState {
+                challenge: Some(Challenge::weekly("2026-W42")),
+                ..Default::default()
+            };
+            st.set_difficulty(Difficulty::Impossible);
+            st.tiles.iter().map(|tile| tile.value.clone()).collect::<Vec<String>>()
+        };
+
+        assert_eq!(deal(), deal());
+    }
+
+    #[test]
+    fn gauntlet_carries_precision_after_first_board() {
+        let mut st = AppState {
+            series: Some(Series::Gauntlet(GauntletRun { board_index: 1 })),
+            run_matches: 6,
+            run_mismatches: 2,
+            ..Default::default()
+        };
+        st.set_difficulty(Difficulty::Medium);
+        assert_eq!((st.run_matches, st.run_mismatches), (6, 2));
+
+        st.series = Some(Series::Gauntlet(GauntletRun::default()));
+        st.set_difficulty(Difficulty::Easy);
+        assert_eq!((st.run_matches, st.run_mismatches), (0, 0));
+    }
+
+    #[test]
+    fn assists_stay_out_of_scored_series() {
+        let mut st = AppState {
+            tile_tags: true,
+            speedrun: true,
+            ..Default::default()
+        };
+        st.note_run_modifiers();
+        assert!(!st.run_assisted());
+        st.speedrun = false;
+        st.note_run_modifiers();
+        assert_eq!(st.run_modifiers(), "tags");
+    }
+
+    #[test]
+    fn assists_switched_off_mid_run_stay_on_the_record() {
+        let mut st = AppState {
+            difficulty: Difficulty::Infinite,
+            hint_assist: true,
+            ..Default::default()
+        };
+        st.note_run_modifiers();
+        st.hint_assist = false;
+        st.peek_assist = true;
+        st.note_run_modifiers();
+        st.note_run_modifiers();
+        assert_eq!(st.run_modifiers(), "hint,peek");
+    }
+
+    #[test]
+    fn views_per_match_counts_matched_groups_only() {
+        let tile = |status, views| Tile {
+            value: String::new(),
+            status,
+            seen: views > 0,
+            views,
+            tag: None,
+        };
+        let mut st = AppState {
+            match_size: 2,
+            tiles: vec![tile(TileStatus::Hidden, 4), tile(TileStatus::Hidden, 0)],
+            ..AppState::default()
+        };
+        assert_eq!(st.views_per_match(), None);
+        st.tiles.extend([
+            tile(TileStatus::Matched, 1),
+            tile(TileStatus::Matched, 2),
+            tile(TileStatus::Matched, 1),
+            tile(TileStatus::Matched, 1),
+        ]);
+        assert_eq!(st.views_per_match(), Some(2.5));
+    }
+
+    #[test]
+    fn run_clock_excludes_paused_time() {
+        let mut clock = RunClock::default();
+        clock.resume(1_000_000);
+        clock.pause(3_500_000);
+        assert_eq!(clock.elapsed_ms(9_000_000), 2_500);
+
+        clock.resume(10_000_000);
+        assert_eq!(clock.elapsed_ms(10_250_000), 2_750);
+        assert_eq!(RunClock::starting_at_ms(4_000).elapsed_ms(0), 4_000);
+    }
+
+    #[test]
+    fn merging_records_skips_identical_entries() {
+        let run = |time_secs, recorded_at| ModeRecord {
+            level: 2,
+            time_secs,
+            precision_pct: 88,
+            rank: Rank::A,
+            recorded_at: Some(recorded_at),
+            modifiers: String::new(),
+        };
+        let mut here = PlayerRecords {
+            classic: vec![run(70, 100), run(65, 300)],
+            ..PlayerRecords::default()
+        };
+        let there = PlayerRecords {
+            classic: vec![run(70, 100), run(80, 200), run(80, 200)],
+            ..PlayerRecords::default()
+        };
+
+        assert_eq!(here.merge(there), 1);
+        let times: Vec<u32> = here.classic.iter().map(|entry| entry.time_secs).collect();
+        assert_eq!(times, vec![70, 80, 65]);
+    }
 }
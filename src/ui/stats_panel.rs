@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gtk4 as gtk;
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use crate::i18n::{format_duration, format_percent, tr, tr_format};
+
+use super::hint;
+use super::records::top_record_lines;
+use super::state::AppState;
+
+const WIDE_LAYOUT_WIDTH: i32 = 1100;
+const TOP_RECORDS_SHOWN: usize = 3;
+
+pub struct StatsPanel {
+    pub root: gtk::Box,
+    time: gtk::Label,
+    matches: gtk::Label,
+    mismatches: gtk::Label,
+    precision: gtk::Label,
+    streak: gtk::Label,
+    hints_row: gtk::ListBoxRow,
+    hints: gtk::Label,
+    records: gtk::ListBox,
+}
+
+fn stat_row(list: &gtk::ListBox, title: &str) -> (gtk::ListBoxRow, gtk::Label) {
+    let row = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+    row.set_margin_top(6);
+    row.set_margin_bottom(6);
+    row.set_margin_start(10);
+    row.set_margin_end(10);
+
+    let name = gtk::Label::new(Some(title));
+    name.set_hexpand(true);
+    name.set_halign(gtk::Align::Start);
+    let value = gtk::Label::new(None);
+    value.add_css_class("numeric");
+    row.append(&name);
+    row.append(&value);
+    let list_row = gtk::ListBoxRow::builder().child(&row).activatable(false).build();
+    list.append(&list_row);
+    (list_row, value)
+}
+
+fn boxed_list() -> gtk::ListBox {
+    let list = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    list.add_css_class("boxed-list");
+    list
+}
+
+fn heading(text: &str) -> gtk::Label {
+    let label = gtk::Label::new(Some(text));
+    label.add_css_class("heading");
+    label.set_halign(gtk::Align::Start);
+    label
+}
+
+pub fn build_stats_panel() -> StatsPanel {
+    let root = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    root.add_css_class("stats-panel");
+    root.set_size_request(220, -1);
+    root.set_vexpand(true);
+    root.set_visible(false);
+
+    let live = boxed_list();
+    let (_, time) = stat_row(&live, &tr("Time"));
+    let (_, matches) = stat_row(&live, &tr("Matches"));
+    let (_, mismatches) = stat_row(&live, &tr("Mismatches"));
+    let (_, precision) = stat_row(&live, &tr("Precision"));
+    let (_, streak) = stat_row(&live, &tr("Streak"));
+    let (hints_row, hints) = stat_row(&live, &tr("Hints left"));
+    let records = boxed_list();
+
+    root.append(&heading(&tr("This run")));
+    root.append(&live);
+    let records_heading = heading(&tr("Best runs"));
+    records_heading.set_margin_top(12);
+    root.append(&records_heading);
+    root.append(&records);
+
+    StatsPanel {
+        root,
+        time,
+        matches,
+        mismatches,
+        precision,
+        streak,
+        hints_row,
+        hints,
+        records,
+    }
+}
+
+// Shows the side panel from `WIDE_LAYOUT_WIDTH` up. It never overlaps the narrow
+// breakpoint, so the window can hold both.
+pub fn wide_breakpoint(state: &Rc<RefCell<AppState>>) -> adw::Breakpoint {
+    let breakpoint = adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+        adw::BreakpointConditionLengthType::MinWidth,
+        f64::from(WIDE_LAYOUT_WIDTH),
+        adw::LengthUnit::Px,
+    ));
+    breakpoint.connect_apply({
+        let state = state.clone();
+        move |_| set_wide_layout(&state, true)
+    });
+    breakpoint.connect_unapply({
+        let state = state.clone();
+        move |_| set_wide_layout(&state, false)
+    });
+    breakpoint
+}
+
+fn set_wide_layout(state: &Rc<RefCell<AppState>>, wide: bool) {
+    state.borrow_mut().wide_layout = wide;
+    refresh_stats_panel(&state.borrow());
+}
+
+fn panel_visible(st: &AppState) -> bool {
+    st.wide_layout && !st.speedrun && st.nback.is_none() && st.pattern.is_none()
+}
+
+pub fn refresh_live_stats(st: &AppState) {
+    let Some(panel) = &st.stats_panel else {
+        return;
+    };
+    if !panel_visible(st) {
+        return;
+    }
+    let attempts = st.run_matches.saturating_add(st.run_mismatches);
+    let precision = (st.run_matches * 100).checked_div(attempts).unwrap_or(100);
+    panel.time.set_text(&format_duration(st.seconds_elapsed));
+    panel.matches.set_text(&st.run_matches.to_string());
+    panel.mismatches.set_text(&st.run_mismatches.to_string());
+    panel.precision.set_text(&format_percent(precision));
+    panel.streak.set_text(&format!(
+        "{} · {} {}",
+        st.match_streak,
+        tr("best"),
+        st.best_match_streak
+    ));
+    let hints_shown = st.hint_assist && st.run_assisted();
+    panel.hints_row.set_visible(hints_shown);
+    if hints_shown {
+        panel.hints.set_text(&tr_format(
+            "{left} of {total}",
+            &[("left", &st.hint_charges), ("total", &hint::HINT_CHARGES)],
+        ));
+    }
+}
+
+pub fn refresh_stats_panel(st: &AppState) {
+    let Some(panel) = &st.stats_panel else {
+        return;
+    };
+    let visible = panel_visible(st);
+    panel.root.set_visible(visible);
+    if !visible {
+        return;
+    }
+    refresh_live_stats(st);
+    refresh_records(st);
+}
+
+// Records only move when a board ends, so this stays off the per-match path.
+pub fn refresh_records(st: &AppState) {
+    let Some(panel) = &st.stats_panel else {
+        return;
+    };
+    if !panel_visible(st) {
+        return;
+    }
+    while let Some(child) = panel.records.first_child() {
+        panel.records.remove(&child);
+    }
+    let mut lines = top_record_lines(st, TOP_RECORDS_SHOWN);
+    if lines.is_empty() {
+        lines.push(tr("No records yet"));
+    }
+    for line in lines {
+        let label = gtk::Label::new(Some(&line));
+        label.set_halign(gtk::Align::Start);
+        label.set_margin_top(6);
+        label.set_margin_bottom(6);
+        label.set_margin_start(10);
+        label.set_margin_end(10);
+        panel.records.append(&label);
+    }
+}
@@ -0,0 +1,222 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+
+use super::events::{self, GameEvent};
+use super::paths;
+use super::state::AppState;
+
+const SYMBOL_STATS_FILE_NAME: &str = "symbol_stats.json";
+// Rates over a handful of flips are noise, so rarely seen symbols stay unranked.
+const MIN_RANKED_ATTEMPTS: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SymbolTally {
+    pub attempts: u32,
+    pub mismatches: u32,
+}
+
+impl SymbolTally {
+    pub fn mismatch_pct(self) -> u8 {
+        if self.attempts == 0 {
+            return 0;
+        }
+        ((self.mismatches as f64 / self.attempts as f64) * 100.0).round() as u8
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Confusion {
+    pub symbols: [String; 2],
+    pub count: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SymbolStats {
+    #[serde(default)]
+    symbols: BTreeMap<String, SymbolTally>,
+    #[serde(default)]
+    confusions: Vec<Confusion>,
+}
+
+impl SymbolStats {
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    // One finished attempt: a full group that matched, or the flips that broke it.
+    pub fn record_attempt(&mut self, values: &[&str], matched: bool) {
+        let mut distinct: Vec<&str> = values.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+
+        for value in &distinct {
+            let tally = self.symbols.entry((*value).to_string()).or_default();
+            tally.attempts = tally.attempts.saturating_add(1);
+            if !matched {
+                tally.mismatches = tally.mismatches.saturating_add(1);
+            }
+        }
+        if matched {
+            return;
+        }
+        for (pos, first) in distinct.iter().enumerate() {
+            for second in &distinct[pos + 1..] {
+                self.add_confusion(first, second);
+            }
+        }
+    }
+
+    fn add_confusion(&mut self, first: &str, second: &str) {
+        if let Some(confusion) = self
+            .confusions
+            .iter_mut()
+            .find(|confusion| confusion.symbols[0] == first && confusion.symbols[1] == second)
+        {
+            confusion.count = confusion.count.saturating_add(1);
+        } else {
+            self.confusions.push(Confusion {
+                symbols: [first.to_string(), second.to_string()],
+                count: 1,
+            });
+        }
+    }
+
+    pub fn top_confusions(&self, limit: usize) -> Vec<Confusion> {
+        let mut confusions = self.confusions.clone();
+        confusions.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.symbols.cmp(&b.symbols)));
+        confusions.truncate(limit);
+        confusions
+    }
+
+    pub fn hardest_symbols(&self, limit: usize) -> Vec<(String, SymbolTally)> {
+        let mut ranked: Vec<(String, SymbolTally)> = self
+            .symbols
+            .iter()
+            .filter(|(_, tally)| tally.attempts >= MIN_RANKED_ATTEMPTS && tally.mismatches > 0)
+            .map(|(symbol, tally)| (symbol.clone(), *tally))
+            .collect();
+        ranked.sort_by(|(_, a), (_, b)| {
+            b.mismatch_pct()
+                .cmp(&a.mismatch_pct())
+                .then_with(|| b.mismatches.cmp(&a.mismatches))
+        });
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+fn stats_path() -> PathBuf {
+    paths::config_dir().join(SYMBOL_STATS_FILE_NAME)
+}
+
+pub fn load_symbol_stats() -> SymbolStats {
+    let Ok(raw) = fs::read_to_string(stats_path()) else {
+        return SymbolStats::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        tracing::warn!("failed to parse symbol stats: {err}");
+        SymbolStats::default()
+    })
+}
+
+fn save_symbol_stats(stats: &SymbolStats) -> io::Result<()> {
+    paths::write_atomic(&stats_path(), &serde_json::to_string(stats)?)
+}
+
+// Part of resetting local records; the stats are built from the same games.
+pub fn clear_symbol_stats(st: &mut AppState) {
+    st.symbol_stats = SymbolStats::default();
+    match fs::remove_file(stats_path()) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            tracing::warn!("failed to remove symbol stats: {err}");
+        }
+        _ => {}
+    }
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        let (indices, matched) = match event {
+            GameEvent::MatchFound(indices) => (indices, true),
+            GameEvent::MismatchMade(indices) => (indices, false),
+            _ => return,
+        };
+        let mut st = state.borrow_mut();
+        log_attempt(&mut st, indices, matched);
+        // The winning match arrives after the finished game saved its stats.
+        if !st.active_session_started {
+            save(&st);
+        }
+    });
+}
+
+fn log_attempt(st: &mut AppState, indices: &[usize], matched: bool) {
+    let values: Vec<String> = indices
+        .iter()
+        .filter_map(|&index| st.tiles.get(index).map(|tile| tile.value.clone()))
+        .collect();
+    let values: Vec<&str> = values.iter().map(String::as_str).collect();
+    st.symbol_stats.record_attempt(&values, matched);
+}
+
+// Attempts pile up in memory during a game and are written once it ends or the player leaves.
+pub fn save(st: &AppState) {
+    if let Err(err) = save_symbol_stats(&st.symbol_stats) {
+        tracing::warn!("failed to save symbol stats: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatches_rank_the_pairs_confused_most() {
+        let mut stats = SymbolStats::default();
+        for _ in 0..3 {
+            stats.record_attempt(&["🐨", "🐼"], false);
+        }
+        stats.record_attempt(&["🐼", "🦊"], false);
+        stats.record_attempt(&["🐼", "🐼"], true);
+
+        let top = stats.top_confusions(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].symbols, ["🐨".to_string(), "🐼".to_string()]);
+        assert_eq!(top[0].count, 3);
+
+        let panda = stats.symbols["🐼"];
+        assert_eq!(panda, SymbolTally { attempts: 5, mismatches: 4 });
+        assert_eq!(panda.mismatch_pct(), 80);
+    }
+
+    #[test]
+    fn rarely_seen_symbols_are_not_ranked() {
+        let mut stats = SymbolStats::default();
+        stats.record_attempt(&["🐙", "🦑"], false);
+        for _ in 0..5 {
+            stats.record_attempt(&["🐝", "🐞"], false);
+        }
+
+        let hardest: Vec<String> = stats
+            .hardest_symbols(10)
+            .into_iter()
+            .map(|(symbol, _)| symbol)
+            .collect();
+        assert_eq!(hardest, ["🐝", "🐞"]);
+    }
+
+    #[test]
+    fn stats_roundtrip_through_json() {
+        let mut stats = SymbolStats::default();
+        stats.record_attempt(&["A", "B", "C"], false);
+        let raw = serde_json::to_string(&stats).unwrap();
+        assert_eq!(serde_json::from_str::<SymbolStats>(&raw).unwrap(), stats);
+    }
+}
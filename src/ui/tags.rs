@@ -0,0 +1,118 @@
+use gtk4::gdk;
+use gtk4::prelude::*;
+
+use super::state::{AppState, TileStatus};
+
+pub const TILE_TAGS_KEY: &str = "tile-tags";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileTag {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl TileTag {
+    const ORDER: [TileTag; 5] = [
+        TileTag::Red,
+        TileTag::Yellow,
+        TileTag::Green,
+        TileTag::Blue,
+        TileTag::Purple,
+    ];
+
+    // A letter for the saved run, kept apart from the tile status codes by position.
+    pub fn code(self) -> char {
+        match self {
+            TileTag::Red => 'r',
+            TileTag::Yellow => 'y',
+            TileTag::Green => 'g',
+            TileTag::Blue => 'b',
+            TileTag::Purple => 'p',
+        }
+    }
+
+    pub fn from_code(code: char) -> Option<Self> {
+        Self::ORDER.into_iter().find(|tag| tag.code() == code)
+    }
+
+    // Each color also gets its own number of marks, so tags read without color.
+    pub fn marks(self) -> usize {
+        Self::ORDER
+            .iter()
+            .position(|tag| *tag == self)
+            .map_or(1, |idx| idx + 1)
+    }
+
+    // Colors from the GNOME palette, bright enough to read on either card back.
+    pub fn rgba(self) -> gdk::RGBA {
+        let (red, green, blue): (u8, u8, u8) = match self {
+            TileTag::Red => (0xe0, 0x1b, 0x24),
+            TileTag::Yellow => (0xf6, 0xd3, 0x2d),
+            TileTag::Green => (0x33, 0xd1, 0x7a),
+            TileTag::Blue => (0x35, 0x84, 0xe4),
+            TileTag::Purple => (0x91, 0x41, 0xac),
+        };
+        gdk::RGBA::new(
+            f32::from(red) / 255.0,
+            f32::from(green) / 255.0,
+            f32::from(blue) / 255.0,
+            1.0,
+        )
+    }
+}
+
+// Steps through every color and back to no tag.
+pub fn next(tag: Option<TileTag>) -> Option<TileTag> {
+    match tag {
+        None => Some(TileTag::ORDER[0]),
+        Some(tag) => TileTag::ORDER
+            .iter()
+            .position(|candidate| *candidate == tag)
+            .and_then(|idx| TileTag::ORDER.get(idx + 1))
+            .copied(),
+    }
+}
+
+pub fn cycle(st: &mut AppState, index: usize) -> bool {
+    if !st.tile_tags || !st.run_assisted() {
+        return false;
+    }
+    let Some(tile) = st.tiles.get_mut(index) else {
+        return false;
+    };
+    if tile.status != TileStatus::Hidden {
+        return false;
+    }
+    tile.tag = next(tile.tag);
+    if let Some(board) = &st.board_canvas {
+        board.queue_draw();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_cycle_through_every_color_and_clear() {
+        let mut tag = None;
+        for expected in TileTag::ORDER {
+            tag = next(tag);
+            assert_eq!(tag, Some(expected));
+        }
+        assert_eq!(next(tag), None);
+    }
+
+    #[test]
+    fn every_tag_has_its_own_code_and_mark_count() {
+        for (idx, tag) in TileTag::ORDER.into_iter().enumerate() {
+            assert_eq!(TileTag::from_code(tag.code()), Some(tag));
+            assert_eq!(tag.marks(), idx + 1);
+        }
+        assert_eq!(TileTag::from_code('h'), None);
+    }
+}
@@ -0,0 +1,65 @@
+use libadwaita as adw;
+
+use crate::i18n::tr_noop;
+
+pub const COLOR_SCHEME_KEY: &str = "color-scheme";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThemeChoice {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::System, ThemeChoice::Light, ThemeChoice::Dark];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            ThemeChoice::System => "system",
+            ThemeChoice::Light => "light",
+            ThemeChoice::Dark => "dark",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|choice| choice.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeChoice::System => tr_noop("System"),
+            ThemeChoice::Light => tr_noop("Light"),
+            ThemeChoice::Dark => tr_noop("Dark"),
+        }
+    }
+
+    pub fn color_scheme(self) -> adw::ColorScheme {
+        match self {
+            ThemeChoice::System => adw::ColorScheme::Default,
+            ThemeChoice::Light => adw::ColorScheme::ForceLight,
+            ThemeChoice::Dark => adw::ColorScheme::ForceDark,
+        }
+    }
+
+    pub fn apply(self) {
+        adw::StyleManager::default().set_color_scheme(self.color_scheme());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_codes_follow_the_system() {
+        for choice in ThemeChoice::ALL {
+            assert_eq!(ThemeChoice::from_code(choice.code()), choice);
+        }
+        assert_eq!(ThemeChoice::from_code("sepia"), ThemeChoice::System);
+    }
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use gtk4::glib;
+
+// Every timeout the game schedules goes through here, so tests can swap the
+// main loop for a virtual clock and step a whole flow through deterministically.
+pub struct Timer(Source);
+
+enum Source {
+    Glib(glib::SourceId),
+    #[cfg(test)]
+    Virtual(u64),
+}
+
+impl Timer {
+    pub fn remove(self) {
+        match self.0 {
+            Source::Glib(source) => source.remove(),
+            #[cfg(test)]
+            Source::Virtual(id) => fake::remove(id),
+        }
+    }
+}
+
+pub fn once(delay: Duration, callback: impl FnOnce() + 'static) -> Timer {
+    #[cfg(test)]
+    let callback = match fake::try_once(delay, callback) {
+        Ok(timer) => return timer,
+        Err(callback) => callback,
+    };
+    Timer(Source::Glib(glib::timeout_add_local_once(delay, callback)))
+}
+
+pub fn every(interval: Duration, callback: impl FnMut() -> glib::ControlFlow + 'static) -> Timer {
+    #[cfg(test)]
+    let callback = match fake::try_every(interval, callback) {
+        Ok(timer) => return timer,
+        Err(callback) => callback,
+    };
+    Timer(Source::Glib(glib::timeout_add_local(interval, callback)))
+}
+
+#[cfg(test)]
+pub mod fake {
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    use gtk4::glib;
+
+    use super::{Source, Timer};
+
+    enum Callback {
+        Once(Box<dyn FnOnce()>),
+        Every(Box<dyn FnMut() -> glib::ControlFlow>),
+    }
+
+    struct Entry {
+        id: u64,
+        due_ms: u64,
+        interval_ms: u64,
+        callback: Callback,
+    }
+
+    #[derive(Default)]
+    struct Clock {
+        now_ms: u64,
+        next_id: u64,
+        entries: Vec<Entry>,
+        // The entry whose callback is running, and whether it removed itself.
+        running: Option<(u64, bool)>,
+    }
+
+    thread_local! {
+        static CLOCK: RefCell<Option<Clock>> = const { RefCell::new(None) };
+    }
+
+    // Installed for the current thread until dropped; timers only fire from advance().
+    pub struct FakeClock(());
+
+    impl FakeClock {
+        pub fn install() -> Self {
+            CLOCK.with(|clock| *clock.borrow_mut() = Some(Clock::default()));
+            Self(())
+        }
+
+        pub fn now_ms(&self) -> u64 {
+            CLOCK.with(|clock| clock.borrow().as_ref().map_or(0, |clock| clock.now_ms))
+        }
+
+        pub fn pending(&self) -> usize {
+            CLOCK.with(|clock| clock.borrow().as_ref().map_or(0, |clock| clock.entries.len()))
+        }
+
+        // Fires everything due within `ms`, including timers those callbacks add.
+        pub fn advance(&self, ms: u64) {
+            let target_ms = self.now_ms() + ms;
+            while let Some(entry) = next_due(target_ms) {
+                let Entry {
+                    id,
+                    due_ms,
+                    interval_ms,
+                    callback,
+                } = entry;
+                match callback {
+                    Callback::Once(callback) => {
+                        callback();
+                        finish_running();
+                    }
+                    Callback::Every(mut callback) => {
+                        let flow = callback();
+                        let removed = finish_running();
+                        if flow == glib::ControlFlow::Continue && !removed {
+                            with_clock(|clock| {
+                                clock.entries.push(Entry {
+                                    id,
+                                    due_ms: due_ms + interval_ms.max(1),
+                                    interval_ms,
+                                    callback: Callback::Every(callback),
+                                });
+                            });
+                        }
+                    }
+                }
+            }
+            with_clock(|clock| clock.now_ms = clock.now_ms.max(target_ms));
+        }
+    }
+
+    impl Drop for FakeClock {
+        fn drop(&mut self) {
+            let clock = CLOCK.with(|clock| clock.borrow_mut().take());
+            drop(clock);
+        }
+    }
+
+    fn with_clock<R>(f: impl FnOnce(&mut Clock) -> R) -> Option<R> {
+        CLOCK.with(|clock| clock.borrow_mut().as_mut().map(f))
+    }
+
+    fn next_due(target_ms: u64) -> Option<Entry> {
+        with_clock(|clock| {
+            let position = clock
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| entry.due_ms <= target_ms)
+                .min_by_key(|(_, entry)| (entry.due_ms, entry.id))
+                .map(|(position, _)| position)?;
+            let entry = clock.entries.remove(position);
+            clock.now_ms = clock.now_ms.max(entry.due_ms);
+            clock.running = Some((entry.id, false));
+            Some(entry)
+        })
+        .flatten()
+    }
+
+    fn finish_running() -> bool {
+        with_clock(|clock| clock.running.take().is_some_and(|(_, removed)| removed)).unwrap_or(true)
+    }
+
+    fn installed() -> bool {
+        CLOCK.with(|clock| clock.borrow().is_some())
+    }
+
+    fn schedule(delay_ms: u64, interval_ms: u64, callback: Callback) -> Timer {
+        let id = with_clock(|clock| {
+            let id = clock.next_id;
+            clock.next_id += 1;
+            clock.entries.push(Entry {
+                id,
+                due_ms: clock.now_ms + delay_ms,
+                interval_ms,
+                callback,
+            });
+            id
+        });
+        Timer(Source::Virtual(id.unwrap_or(u64::MAX)))
+    }
+
+    pub(super) fn try_once<F: FnOnce() + 'static>(delay: Duration, callback: F) -> Result<Timer, F> {
+        if !installed() {
+            return Err(callback);
+        }
+        Ok(schedule(delay.as_millis() as u64, 0, Callback::Once(Box::new(callback))))
+    }
+
+    pub(super) fn try_every<F: FnMut() -> glib::ControlFlow + 'static>(
+        interval: Duration,
+        callback: F,
+    ) -> Result<Timer, F> {
+        if !installed() {
+            return Err(callback);
+        }
+        let interval_ms = interval.as_millis() as u64;
+        Ok(schedule(interval_ms, interval_ms, Callback::Every(Box::new(callback))))
+    }
+
+    pub(super) fn remove(id: u64) {
+        with_clock(|clock| {
+            clock.entries.retain(|entry| entry.id != id);
+            if let Some((running, removed)) = &mut clock.running
+                && *running == id
+            {
+                *removed = true;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::fake::FakeClock;
+    use super::*;
+
+    #[test]
+    fn fake_clock_fires_due_timers_in_order() {
+        let clock = FakeClock::install();
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        for (label, delay_ms) in [("late", 300), ("early", 100), ("never", 500)] {
+            let fired = fired.clone();
+            let timer = once(Duration::from_millis(delay_ms), move || fired.borrow_mut().push(label));
+            if label == "never" {
+                timer.remove();
+            }
+        }
+        let ticks = Rc::new(RefCell::new(0));
+        every(Duration::from_millis(150), {
+            let ticks = ticks.clone();
+            move || {
+                *ticks.borrow_mut() += 1;
+                if *ticks.borrow() < 2 { glib::ControlFlow::Continue } else { glib::ControlFlow::Break }
+            }
+        });
+
+        clock.advance(99);
+        assert!(fired.borrow().is_empty());
+        clock.advance(1_000);
+        assert_eq!(*fired.borrow(), vec!["early", "late"]);
+        assert_eq!(*ticks.borrow(), 2);
+        assert_eq!(clock.pending(), 0);
+        assert_eq!(clock.now_ms(), 1_099);
+    }
+}
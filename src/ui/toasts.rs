@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use libadwaita as adw;
+
+use crate::i18n::{ntr_format, tr, tr_format};
+
+use super::events::{self, GameEvent};
+use super::infinite;
+use super::state::AppState;
+
+const SHORT_TOAST_SECS: u32 = 2;
+const LONG_TOAST_SECS: u32 = 4;
+
+thread_local! {
+    // Toasts not yet dismissed, oldest first; the first one is on screen.
+    static OUTSTANDING: RefCell<Vec<adw::Toast>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Notice {
+    Penalty(String),
+    Milestone(String),
+    RunSaved,
+    PlayTimeLeft(u32),
+}
+
+impl Notice {
+    pub fn message(&self) -> String {
+        match self {
+            Notice::Penalty(text) | Notice::Milestone(text) => text.clone(),
+            Notice::RunSaved => tr("Run saved, continue it from the menu"),
+            Notice::PlayTimeLeft(minutes) => ntr_format(
+                "{count} minute of play time left today",
+                "{count} minutes of play time left today",
+                *minutes,
+                &[("count", minutes)],
+            ),
+        }
+    }
+
+    fn timeout_secs(&self) -> u32 {
+        match self {
+            Notice::Penalty(_) => SHORT_TOAST_SECS,
+            Notice::RunSaved | Notice::Milestone(_) | Notice::PlayTimeLeft(_) => LONG_TOAST_SECS,
+        }
+    }
+
+    // Only one penalty toast makes sense at a time, a new one replaces the old.
+    fn replaces_previous(&self) -> bool {
+        matches!(self, Notice::Penalty(_))
+    }
+}
+
+pub fn notify(st: &AppState, event: Notice) {
+    let Some(overlay) = &st.toast_overlay else {
+        return;
+    };
+    let toast = adw::Toast::new(&event.message());
+    toast.set_use_markup(false);
+    toast.set_timeout(event.timeout_secs());
+    if event.replaces_previous() {
+        toast.set_priority(adw::ToastPriority::High);
+        // A high-priority toast would only push the current one back into the queue.
+        let shown = OUTSTANDING.with_borrow(|outstanding| outstanding.first().cloned());
+        if let Some(shown) = shown {
+            shown.dismiss();
+        }
+    }
+    toast.connect_dismissed(|dismissed| {
+        OUTSTANDING.with_borrow_mut(|outstanding| outstanding.retain(|toast| toast != dismissed));
+    });
+    OUTSTANDING.with_borrow_mut(|outstanding| outstanding.push(toast.clone()));
+    overlay.add_toast(toast);
+}
+
+pub fn subscribe_to_game_events(state: &Rc<RefCell<AppState>>) {
+    let state = state.clone();
+    events::subscribe(move |event| {
+        let notice = match event {
+            GameEvent::PenaltyApplied(summary) => Notice::Penalty(summary.clone()),
+            GameEvent::RoundFinished {
+                level_up: Some(level),
+                ..
+            } => Notice::Milestone(tr_format(
+                "Level up: {level}!",
+                &[("level", &tr(infinite::level_name(*level)))],
+            )),
+            _ => return,
+        };
+        notify(&state.borrow(), notice);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penalties_are_brief_and_replace_each_other() {
+        let penalty = Notice::Penalty("3 mismatches".into());
+        assert!(penalty.replaces_previous());
+        assert!(!Notice::RunSaved.replaces_previous());
+        assert!(penalty.timeout_secs() < Notice::RunSaved.timeout_secs());
+        assert_eq!(Notice::Milestone("Level up".into()).message(), "Level up");
+    }
+}
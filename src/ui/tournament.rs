@@ -0,0 +1,181 @@
+use crate::i18n::{format_integer, tr};
+
+use super::classic::difficulty_from_level;
+use super::state::Difficulty;
+
+// The series climbs one board per classic level, then replays Expert as a
+// deciding final so the last two boards weigh the most.
+pub const TOURNAMENT_LEVELS: [u8; 5] = [1, 2, 3, 4, 4];
+const PAR_SECS: [u32; 4] = [30, 80, 160, 260];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TournamentBoardResult {
+    pub level: u8,
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub score: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TournamentRun {
+    pub results: Vec<TournamentBoardResult>,
+}
+
+impl TournamentRun {
+    pub fn board_index(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn board_count(&self) -> usize {
+        TOURNAMENT_LEVELS.len()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.results.len() >= TOURNAMENT_LEVELS.len()
+    }
+
+    pub fn current_difficulty(&self) -> Difficulty {
+        let level = TOURNAMENT_LEVELS
+            .get(self.board_index())
+            .copied()
+            .unwrap_or(TOURNAMENT_LEVELS[0]);
+        difficulty_from_level(level)
+    }
+
+    pub fn record_board(
+        &mut self,
+        level: u8,
+        time_secs: u32,
+        precision_pct: u8,
+    ) -> TournamentBoardResult {
+        let result = TournamentBoardResult {
+            level,
+            time_secs,
+            precision_pct,
+            score: board_score(level, time_secs, precision_pct),
+        };
+        self.results.push(result);
+        result
+    }
+
+    pub fn total_score(&self) -> u32 {
+        self.results.iter().map(|result| result.score).sum()
+    }
+
+    pub fn total_secs(&self) -> u32 {
+        self.results.iter().map(|result| result.time_secs).sum()
+    }
+
+    pub fn precision_pct(&self) -> u8 {
+        if self.results.is_empty() {
+            return 100;
+        }
+        let sum: u32 = self
+            .results
+            .iter()
+            .map(|result| u32::from(result.precision_pct))
+            .sum();
+        (sum as f64 / self.results.len() as f64).round() as u8
+    }
+
+    pub fn mode_label(&self) -> String {
+        format!(
+            "{} · {} {}/{}",
+            tr("Tournament"),
+            tr("Board"),
+            (self.board_index() + 1).min(self.board_count()),
+            self.board_count()
+        )
+    }
+
+    pub fn to_code(&self) -> String {
+        self.results
+            .iter()
+            .map(|result| {
+                format!(
+                    "{},{},{}",
+                    result.level, result.time_secs, result.precision_pct
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(";")
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let mut run = Self::default();
+        for entry in code.trim().split(';').filter(|entry| !entry.is_empty()) {
+            let mut parts = entry.split(',');
+            let level = parts.next()?.parse::<u8>().ok()?;
+            let time_secs = parts.next()?.parse::<u32>().ok()?;
+            let precision_pct = parts.next()?.parse::<u8>().ok()?;
+            if parts.next().is_some()
+                || precision_pct > 100
+                || TOURNAMENT_LEVELS.get(run.board_index()) != Some(&level)
+            {
+                return None;
+            }
+            run.record_board(level, time_secs, precision_pct);
+        }
+        (!run.is_complete()).then_some(run)
+    }
+}
+
+pub fn board_score(level: u8, time_secs: u32, precision_pct: u8) -> u32 {
+    let level = level.clamp(1, 4);
+    let weight = u32::from(level);
+    let par = PAR_SECS[usize::from(level - 1)];
+    let precision_points = weight * u32::from(precision_pct.min(100)) * 10;
+    let time_bonus = par.saturating_sub(time_secs) * weight * 2;
+    precision_points + time_bonus
+}
+
+pub fn format_score(score: u32) -> String {
+    format_integer(u64::from(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_advances_through_rising_difficulties() {
+        let mut run = TournamentRun::default();
+        assert_eq!(run.current_difficulty(), Difficulty::Easy);
+
+        run.record_board(1, 20, 100);
+        run.record_board(2, 60, 90);
+        assert_eq!(run.current_difficulty(), Difficulty::Hard);
+        assert!(!run.is_complete());
+
+        run.record_board(3, 120, 80);
+        run.record_board(4, 200, 75);
+        run.record_board(4, 300, 70);
+        assert!(run.is_complete());
+        assert_eq!(run.total_secs(), 700);
+    }
+
+    #[test]
+    fn faster_and_more_precise_boards_score_higher() {
+        assert!(board_score(2, 40, 100) > board_score(2, 70, 100));
+        assert!(board_score(2, 40, 100) > board_score(2, 40, 90));
+        assert!(board_score(4, 400, 70) > board_score(1, 400, 70));
+    }
+
+    #[test]
+    fn progress_code_roundtrip() {
+        let mut run = TournamentRun::default();
+        run.record_board(1, 18, 100);
+        run.record_board(2, 75, 88);
+
+        assert_eq!(TournamentRun::from_code(&run.to_code()), Some(run));
+        assert_eq!(TournamentRun::from_code(""), Some(TournamentRun::default()));
+        assert!(TournamentRun::from_code("2,75,88").is_none());
+    }
+
+    #[test]
+    fn scores_use_thousands_separators() {
+        assert_eq!(format_score(0), "0");
+        assert_eq!(format_score(980), "980");
+        assert_eq!(format_score(12_345), "12,345");
+    }
+}
@@ -0,0 +1,208 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use adw::prelude::*;
+use gtk4 as gtk;
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use crate::i18n::{format_duration, format_percent, tr, tr_format};
+
+use super::state::{AppState, Series};
+
+pub const TRAINING_GAMES: usize = 3;
+pub const TRAINING_REST_SECS: u32 = 5 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrainingGame {
+    pub time_secs: u32,
+    pub precision_pct: u8,
+    pub won: bool,
+}
+
+// A set of games of one difficulty with rests in between, for deliberate practice.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TrainingSession {
+    pub games: Vec<TrainingGame>,
+}
+
+impl TrainingSession {
+    pub fn is_complete(&self) -> bool {
+        self.games.len() >= TRAINING_GAMES
+    }
+
+    // True between games, when the next one should wait for a rest.
+    pub fn is_resting_due(&self) -> bool {
+        !self.games.is_empty() && !self.is_complete()
+    }
+
+    pub fn current_game(&self) -> usize {
+        (self.games.len() + 1).min(TRAINING_GAMES)
+    }
+
+    pub fn progress_label(&self) -> String {
+        tr_format(
+            "Training · Game {index}/{count}",
+            &[("index", &self.current_game()), ("count", &TRAINING_GAMES)],
+        )
+    }
+
+    pub fn report(&self) -> String {
+        let won = self.games.iter().filter(|game| game.won).count();
+        let total_secs: u32 = self.games.iter().map(|game| game.time_secs).sum();
+        let precision_pct = if self.games.is_empty() {
+            0
+        } else {
+            let sum: u32 = self
+                .games
+                .iter()
+                .map(|game| u32::from(game.precision_pct))
+                .sum();
+            (sum as f64 / self.games.len() as f64).round() as u8
+        };
+        let mut lines = vec![
+            tr_format(
+                "Boards cleared: {won}/{count}",
+                &[("won", &won), ("count", &self.games.len())],
+            ),
+            tr_format(
+                "Time played: {time}",
+                &[("time", &format_duration(total_secs))],
+            ),
+            tr_format(
+                "Average precision: {precision}",
+                &[("precision", &format_percent(precision_pct))],
+            ),
+        ];
+        if let Some(best) = self
+            .games
+            .iter()
+            .filter(|game| game.won)
+            .map(|game| game.time_secs)
+            .min()
+        {
+            lines.push(tr_format(
+                "Best time: {time}",
+                &[("time", &format_duration(best))],
+            ));
+        }
+        for (idx, game) in self.games.iter().enumerate() {
+            let args: [(&str, &dyn fmt::Display); 3] = [
+                ("index", &(idx + 1)),
+                ("time", &format_duration(game.time_secs)),
+                ("precision", &format_percent(game.precision_pct)),
+            ];
+            lines.push(if game.won {
+                tr_format("Game {index}: {time} · {precision}", &args)
+            } else {
+                tr_format("Game {index}: {time} · {precision} · not cleared", &args)
+            });
+        }
+        lines.join("\n")
+    }
+}
+
+// Called once per finished board, cleared or not.
+pub fn note_game(st: &mut AppState, won: bool) {
+    let attempts = st.run_matches.saturating_add(st.run_mismatches);
+    let precision_pct = if attempts == 0 {
+        100
+    } else {
+        ((st.run_matches as f64 / attempts as f64) * 100.0).round() as u8
+    };
+    let time_secs = st.seconds_elapsed;
+    if let Some(session) = st.training_mut()
+        && !session.is_complete()
+    {
+        session.games.push(TrainingGame {
+            time_secs,
+            precision_pct,
+            won,
+        });
+    }
+}
+
+pub fn build_progress_bar() -> gtk::ProgressBar {
+    let bar = gtk::ProgressBar::new();
+    bar.add_css_class("training-progress");
+    bar.set_show_text(true);
+    bar.set_halign(gtk::Align::Center);
+    bar.set_size_request(220, -1);
+    bar.set_visible(false);
+    bar
+}
+
+pub fn refresh_progress(st: &AppState) {
+    let Some(bar) = &st.training_bar else {
+        return;
+    };
+    let Some(session) = st.training() else {
+        bar.set_visible(false);
+        return;
+    };
+    bar.set_visible(true);
+    bar.set_fraction(session.games.len() as f64 / TRAINING_GAMES as f64);
+    bar.set_text(Some(&session.progress_label()));
+}
+
+// Shows the end-of-session report once the last game is in and ends the session.
+pub fn present_report_if_done(state: &Rc<RefCell<AppState>>) {
+    let (report, parent) = {
+        let mut st = state.borrow_mut();
+        if !st.training().is_some_and(TrainingSession::is_complete) {
+            return;
+        }
+        let Some(Series::Training(session)) = st.series.take() else {
+            return;
+        };
+        (session.report(), st.view_stack.clone())
+    };
+    let dialog = adw::AlertDialog::builder()
+        .heading(tr("Training complete"))
+        .body(report)
+        .build();
+    dialog.add_response("ok", &tr("OK"));
+    dialog.set_default_response(Some("ok"));
+    dialog.set_close_response("ok");
+    dialog.present(parent.as_ref());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(time_secs: u32, precision_pct: u8, won: bool) -> TrainingGame {
+        TrainingGame {
+            time_secs,
+            precision_pct,
+            won,
+        }
+    }
+
+    #[test]
+    fn rests_come_only_between_games() {
+        let mut session = TrainingSession::default();
+        assert!(!session.is_resting_due());
+        assert_eq!(session.current_game(), 1);
+        session.games.push(game(60, 90, true));
+        assert!(session.is_resting_due());
+        assert_eq!(session.current_game(), 2);
+        session.games.push(game(70, 80, false));
+        session.games.push(game(50, 100, true));
+        assert!(session.is_complete());
+        assert!(!session.is_resting_due());
+    }
+
+    #[test]
+    fn report_sums_time_and_keeps_the_best_cleared_board() {
+        let session = TrainingSession {
+            games: vec![game(60, 90, true), game(40, 70, false), game(55, 80, true)],
+        };
+        let report = session.report();
+        assert!(report.contains("2/3"));
+        assert!(report.contains("02:35"));
+        assert!(report.contains("80%"));
+        assert!(report.contains("00:55"));
+    }
+}
@@ -0,0 +1,154 @@
+pub const BOARD_TRANSITION_KEY: &str = "board-transition";
+
+// How cards are grouped into waves when a board is dealt in or cleared away.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WavePattern {
+    #[default]
+    Corners,
+    Spiral,
+    Checkerboard,
+    RowSweep,
+}
+
+impl WavePattern {
+    pub const ALL: [WavePattern; 4] = [
+        WavePattern::Corners,
+        WavePattern::Spiral,
+        WavePattern::Checkerboard,
+        WavePattern::RowSweep,
+    ];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            WavePattern::Corners => "corners",
+            WavePattern::Spiral => "spiral",
+            WavePattern::Checkerboard => "checkerboard",
+            WavePattern::RowSweep => "row-sweep",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|pattern| pattern.code() == code.trim())
+            .unwrap_or_default()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WavePattern::Corners => "Classic",
+            WavePattern::Spiral => "Spiral cascade",
+            WavePattern::Checkerboard => "Checkerboard",
+            WavePattern::RowSweep => "Row sweep",
+        }
+    }
+
+    // The classic deal flips every card at once; the others deal in along their waves.
+    pub fn staggers_intro(self) -> bool {
+        self != WavePattern::Corners
+    }
+
+    // Larger boards pair waves up (`paired`) so the cascade does not drag on.
+    pub fn waves(self, cols: usize, rows: usize, total_cards: usize, paired: bool) -> Vec<Vec<usize>> {
+        let cols = cols.max(1);
+        if cols * rows < total_cards {
+            return corner_waves(total_cards, paired);
+        }
+        let waves = match self {
+            WavePattern::Corners => return corner_waves(total_cards, paired),
+            WavePattern::Spiral => {
+                let order = spiral_order(cols, rows);
+                order.chunks(if paired { 2 } else { 1 }).map(<[usize]>::to_vec).collect()
+            }
+            WavePattern::Checkerboard => [0, 1]
+                .into_iter()
+                .flat_map(|parity| {
+                    (0..rows).map(move |row| {
+                        (0..cols)
+                            .filter(|col| (row + col) % 2 == parity)
+                            .map(|col| row * cols + col)
+                            .collect::<Vec<usize>>()
+                    })
+                })
+                .collect(),
+            WavePattern::RowSweep => (0..rows)
+                .map(|row| (row * cols..(row + 1) * cols).collect())
+                .collect::<Vec<Vec<usize>>>(),
+        };
+        // Uneven boards leave the tail of the grid empty.
+        waves
+            .into_iter()
+            .map(|wave| wave.into_iter().filter(|idx| *idx < total_cards).collect::<Vec<usize>>())
+            .filter(|wave| !wave.is_empty())
+            .collect()
+    }
+}
+
+fn corner_waves(total_cards: usize, dual_corner_wave: bool) -> Vec<Vec<usize>> {
+    if total_cards == 0 {
+        return Vec::new();
+    }
+    if !dual_corner_wave {
+        return (0..total_cards).map(|idx| vec![idx]).collect();
+    }
+
+    let mut waves = Vec::new();
+    let mut left = 0usize;
+    let mut right = total_cards - 1;
+    while left < right {
+        waves.push(vec![left, right]);
+        left += 1;
+        right = right.saturating_sub(1);
+    }
+    if left == right {
+        waves.push(vec![left]);
+    }
+    waves
+}
+
+// Clockwise from the top-left corner, working inwards.
+fn spiral_order(cols: usize, rows: usize) -> Vec<usize> {
+    let mut order = Vec::with_capacity(cols * rows);
+    let (mut top, mut left) = (0, 0);
+    let (mut bottom, mut right) = (rows, cols);
+    while top < bottom && left < right {
+        order.extend((left..right).map(|col| top * cols + col));
+        order.extend((top + 1..bottom).map(|row| row * cols + right - 1));
+        if bottom - top > 1 {
+            order.extend((left..right - 1).rev().map(|col| (bottom - 1) * cols + col));
+        }
+        if right - left > 1 {
+            order.extend((top + 1..bottom - 1).rev().map(|row| row * cols + left));
+        }
+        top += 1;
+        left += 1;
+        bottom -= 1;
+        right -= 1;
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_pattern_covers_each_card_once() {
+        for pattern in WavePattern::ALL {
+            for (cols, rows, total) in [(4, 3, 12), (5, 4, 20), (4, 4, 15), (1, 3, 3)] {
+                for paired in [false, true] {
+                    let mut seen: Vec<usize> = pattern.waves(cols, rows, total, paired).concat();
+                    seen.sort_unstable();
+                    assert_eq!(seen, (0..total).collect::<Vec<usize>>(), "{pattern:?} {cols}x{rows}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spiral_walks_the_rim_before_the_centre() {
+        assert_eq!(spiral_order(3, 3), vec![0, 1, 2, 5, 8, 7, 6, 3, 4]);
+        assert_eq!(WavePattern::RowSweep.waves(3, 2, 6, false), vec![vec![0, 1, 2], vec![3, 4, 5]]);
+        assert_eq!(corner_waves(5, true), vec![vec![0, 4], vec![1, 3], vec![2]]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::classic_penalties::PunishmentPlan;
+use super::classic_penalties::{self, Pressure, PunishmentPlan};
 use super::state::{AppState, Difficulty, TileStatus};
 
 const TRIO_NORMAL_MISMATCH_THRESHOLD: u8 = 5;
@@ -19,11 +19,43 @@ pub fn mismatch_pause_ms(level: u8) -> u64 {
     }
 }
 
+// Endless Trio borrows the Trio penalties for its current Infinite level.
+pub fn penalty_level(st: &AppState) -> u8 {
+    if st.infinite_trio() {
+        st.infinite_level
+    } else {
+        st.trio_level
+    }
+}
+
+pub fn applies_to(st: &AppState) -> bool {
+    st.difficulty == Difficulty::Trio || st.infinite_trio()
+}
+
+pub fn pressure(st: &AppState) -> Option<Pressure> {
+    match penalty_level(st).clamp(1, 4) {
+        1 | 2 => None,
+        3 => Some(Pressure {
+            mismatches: st.impossible_mismatch_count,
+            threshold: TRIO_HARD_MISMATCH_THRESHOLD,
+            stage: None,
+        }),
+        _ => Some(Pressure {
+            mismatches: st.impossible_mismatch_count,
+            threshold: TRIO_EXPERT_MISMATCH_THRESHOLD,
+            stage: Some(st.impossible_punish_stage),
+        }),
+    }
+}
+
 pub fn register_mismatch_and_plan_reshuffle(
     st: &mut AppState,
     first_pick_index: usize,
 ) -> Option<PunishmentPlan> {
-    match st.trio_level.clamp(1, 4) {
+    if classic_penalties::forgives_mismatch(st) {
+        return None;
+    }
+    match penalty_level(st).clamp(1, 4) {
         1 => return None,
         2 => {
             st.impossible_mismatch_count = st.impossible_mismatch_count.saturating_add(1);
@@ -37,6 +69,8 @@ pub fn register_mismatch_and_plan_reshuffle(
                 reshuffle_hidden: true,
                 reveal_all_hidden: false,
                 source_difficulty: Difficulty::Trio,
+                mismatches: TRIO_NORMAL_MISMATCH_THRESHOLD,
+                repeated_first_pick: false,
             });
         }
         3 => {
@@ -51,6 +85,8 @@ pub fn register_mismatch_and_plan_reshuffle(
                 reshuffle_hidden: true,
                 reveal_all_hidden: true,
                 source_difficulty: Difficulty::Trio,
+                mismatches: TRIO_HARD_MISMATCH_THRESHOLD,
+                repeated_first_pick: false,
             });
         }
         _ => {}
@@ -72,6 +108,7 @@ pub fn register_mismatch_and_plan_reshuffle(
         return None;
     }
 
+    let mismatches = st.impossible_mismatch_count;
     st.impossible_mismatch_count = 0;
     st.impossible_same_first_streak = 0;
     st.impossible_last_first_index = None;
@@ -95,11 +132,13 @@ pub fn register_mismatch_and_plan_reshuffle(
         reshuffle_hidden: true,
         reveal_all_hidden: false,
         source_difficulty: Difficulty::Trio,
+        mismatches,
+        repeated_first_pick: repeated_first_hit && !threshold_hit,
     })
 }
 
 pub fn reset_penalty_after_match(st: &mut AppState) {
-    if st.trio_level < 2 {
+    if penalty_level(st) < 2 {
         return;
     }
     st.reset_impossible_pressure();
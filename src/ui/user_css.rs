@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use gio::prelude::*;
+use gtk4 as gtk;
+
+use super::paths;
+
+const USER_CSS_FILE_NAME: &str = "user.css";
+
+thread_local! {
+    // The monitor stops reporting once dropped, so it lives as long as the provider.
+    static USER_CSS: RefCell<Option<(gtk::CssProvider, gio::FileMonitor)>> =
+        const { RefCell::new(None) };
+}
+
+pub fn user_css_path() -> PathBuf {
+    paths::config_dir().join(USER_CSS_FILE_NAME)
+}
+
+fn reload(provider: &gtk::CssProvider) {
+    let path = user_css_path();
+    if path.exists() {
+        provider.load_from_path(&path);
+    } else {
+        provider.load_from_string("");
+    }
+}
+
+// Loaded above the bundled styles so cards and the victory screen can be restyled.
+pub fn install(display: &gtk::gdk::Display) {
+    let provider = gtk::CssProvider::new();
+    provider.connect_parsing_error(|_, section, err| {
+        tracing::warn!("user.css {}: {err}", section.to_str());
+    });
+    reload(&provider);
+    gtk::style_context_add_provider_for_display(
+        display,
+        &provider,
+        gtk::STYLE_PROVIDER_PRIORITY_USER,
+    );
+
+    let Some(monitor) = watch(&user_css_path(), &provider, reload) else {
+        return;
+    };
+    USER_CSS.with(|slot| *slot.borrow_mut() = Some((provider, monitor)));
+}
+
+// Reruns `reload` whenever the file at `path` is saved, replaced or removed.
+pub fn watch(
+    path: &Path,
+    provider: &gtk::CssProvider,
+    reload: impl Fn(&gtk::CssProvider) + 'static,
+) -> Option<gio::FileMonitor> {
+    let file = gio::File::for_path(path);
+    let monitor = match file.monitor_file(gio::FileMonitorFlags::NONE, gio::Cancellable::NONE) {
+        Ok(monitor) => monitor,
+        Err(err) => {
+            tracing::warn!("failed to watch {}: {err}", path.display());
+            return None;
+        }
+    };
+    monitor.connect_changed({
+        let provider = provider.clone();
+        move |_, _, _, event| {
+            if matches!(
+                event,
+                gio::FileMonitorEvent::ChangesDoneHint
+                    | gio::FileMonitorEvent::Created
+                    | gio::FileMonitorEvent::Deleted
+                    | gio::FileMonitorEvent::Renamed
+                    | gio::FileMonitorEvent::MovedIn
+                    | gio::FileMonitorEvent::MovedOut
+            ) {
+                reload(&provider);
+            }
+        }
+    });
+    Some(monitor)
+}
@@ -0,0 +1,31 @@
+use crate::i18n::tr;
+
+use super::state::{AppState, Difficulty};
+
+pub const WARMUP_KEY: &str = "expert-warmup";
+// The small Easy board: enough to prime memory without tiring it.
+pub const WARMUP_DIFFICULTY: Difficulty = Difficulty::Easy;
+
+pub fn offered_before(difficulty: Difficulty) -> bool {
+    difficulty == Difficulty::Impossible
+}
+
+pub fn is_warming_up(st: &AppState) -> bool {
+    st.warmup_target.is_some()
+}
+
+pub fn mode_label(target: Difficulty) -> String {
+    format!("{} · {}", tr("Warm-up"), tr(target.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warm_up_is_a_three_by_four_board_before_expert_only() {
+        assert_eq!(WARMUP_DIFFICULTY.fixed_config(), Some((3, 4, 2)));
+        assert!(offered_before(Difficulty::Impossible));
+        assert!(!offered_before(Difficulty::Hard));
+    }
+}
@@ -0,0 +1,166 @@
+use gtk4::glib;
+
+use super::challenge;
+use super::state::{PlayerRecords, Rank};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeekSummary {
+    pub runs: u32,
+    pub play_secs: u32,
+    precision_sum: u32,
+    precision_runs: u32,
+    pub best_rank: Option<Rank>,
+    pub best_infinite_round: u32,
+}
+
+impl WeekSummary {
+    fn add(&mut self, secs: u32, precision_pct: Option<u8>, rank: Option<Rank>) {
+        self.runs = self.runs.saturating_add(1);
+        self.play_secs = self.play_secs.saturating_add(secs);
+        if let Some(precision_pct) = precision_pct {
+            self.precision_sum = self.precision_sum.saturating_add(u32::from(precision_pct));
+            self.precision_runs += 1;
+        }
+        if rank.is_some() {
+            self.best_rank = self.best_rank.max(rank);
+        }
+    }
+
+    pub fn precision_pct(&self) -> Option<u8> {
+        (self.precision_runs > 0)
+            .then(|| (self.precision_sum as f64 / self.precision_runs as f64).round() as u8)
+    }
+}
+
+pub fn week_of(recorded_at: Option<i64>) -> Option<String> {
+    let date = glib::DateTime::from_unix_local(recorded_at?).ok()?;
+    Some(challenge::week_label(date.week_numbering_year(), date.week_of_year()))
+}
+
+pub fn current_and_previous_week() -> Option<(String, String)> {
+    let now = glib::DateTime::now_local().ok()?;
+    let last_week = now.add_weeks(-1).ok()?;
+    Some((
+        challenge::week_label(now.week_numbering_year(), now.week_of_year()),
+        challenge::week_label(last_week.week_numbering_year(), last_week.week_of_year()),
+    ))
+}
+
+pub fn summarize(records: &PlayerRecords, week: &str) -> WeekSummary {
+    let mut summary = WeekSummary::default();
+    let in_week = |recorded_at: Option<i64>| week_of(recorded_at).as_deref() == Some(week);
+
+    let ranked = records
+        .classic
+        .iter()
+        .chain(&records.trio)
+        .filter(|entry| entry.is_ranked())
+        .map(|entry| (entry.recorded_at, entry.time_secs, entry.precision_pct, entry.rank))
+        .chain(records.weekly.iter().map(|entry| {
+            (entry.recorded_at, entry.time_secs, entry.precision_pct, entry.rank)
+        }))
+        .chain(records.gauntlet.iter().map(|entry| {
+            (entry.recorded_at, entry.time_secs, entry.precision_pct, entry.rank)
+        }))
+        .chain(records.custom.iter().map(|entry| {
+            (entry.recorded_at, entry.time_secs, entry.precision_pct, entry.rank)
+        }));
+    for (recorded_at, secs, precision_pct, rank) in ranked {
+        if in_week(recorded_at) {
+            summary.add(secs, Some(precision_pct), Some(rank));
+        }
+    }
+
+    for entry in &records.tournament {
+        if in_week(entry.recorded_at) {
+            summary.add(entry.total_secs, Some(entry.precision_pct), None);
+        }
+    }
+    for entry in &records.infinite {
+        if in_week(entry.recorded_at) {
+            summary.add(entry.time_secs, None, None);
+            summary.best_infinite_round = summary.best_infinite_round.max(entry.round);
+        }
+    }
+    let unranked = records
+        .speedrun
+        .iter()
+        .map(|entry| (entry.recorded_at, (entry.time_ms / 1000) as u32))
+        .chain(records.sudden_death.iter().map(|entry| (entry.recorded_at, entry.time_secs)))
+        .chain(records.pattern.iter().map(|entry| (entry.recorded_at, entry.time_secs)))
+        .chain(records.nback.iter().map(|entry| (entry.recorded_at, entry.time_secs)))
+        .chain(records.incomplete.iter().map(|entry| (entry.recorded_at, entry.time_secs)))
+        .chain(
+            records
+                .classic
+                .iter()
+                .chain(&records.trio)
+                .filter(|entry| !entry.is_ranked())
+                .map(|entry| (entry.recorded_at, entry.time_secs)),
+        );
+    for (recorded_at, secs) in unranked {
+        if in_week(recorded_at) {
+            summary.add(secs, None, None);
+        }
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::records::parse_date_label;
+    use crate::ui::state::{IncompleteRecord, InfiniteRecord, ModeRecord};
+
+    fn classic(date: &str, time_secs: u32, precision_pct: u8, rank: Rank) -> ModeRecord {
+        ModeRecord {
+            level: 2,
+            time_secs,
+            precision_pct,
+            rank,
+            recorded_at: parse_date_label(date),
+            modifiers: String::new(),
+        }
+    }
+
+    #[test]
+    fn dates_map_to_iso_weeks() {
+        let week = |label: &str| week_of(parse_date_label(label));
+        assert_eq!(week("2026-03-01 10:00").as_deref(), Some("2026-W09"));
+        assert_eq!(week("2027-01-01 08:30").as_deref(), Some("2026-W53"));
+        assert_eq!(week("Unknown date"), None);
+    }
+
+    #[test]
+    fn summary_only_counts_runs_from_that_week() {
+        let records = PlayerRecords {
+            classic: vec![
+                classic("2026-03-02 09:00", 60, 90, Rank::A),
+                classic("2026-03-03 09:00", 120, 70, Rank::C),
+                classic("2026-02-20 09:00", 30, 100, Rank::S),
+            ],
+            infinite: vec![InfiniteRecord {
+                round: 14,
+                time_secs: 300,
+                recorded_at: parse_date_label("2026-03-04 20:00"),
+                ..InfiniteRecord::default()
+            }],
+            incomplete: vec![IncompleteRecord {
+                time_secs: 45,
+                recorded_at: parse_date_label("2026-03-05 20:00"),
+                ..IncompleteRecord::default()
+            }],
+            ..PlayerRecords::default()
+        };
+
+        let week = summarize(&records, "2026-W10");
+        assert_eq!(week.runs, 4);
+        assert_eq!(week.play_secs, 525);
+        assert_eq!(week.precision_pct(), Some(80));
+        assert_eq!(week.best_rank, Some(Rank::A));
+        assert_eq!(week.best_infinite_round, 14);
+
+        assert_eq!(summarize(&records, "2026-W01"), WeekSummary::default());
+    }
+}